@@ -85,6 +85,103 @@ pub const LIB_VERSIONS: [LibVersion; {libs_count}] = ["#,
 }};"#
     )
     .expect("Failed to write LIB_VERSION_MAP const");
+
+    // === asset_manifest.rs codegen: ===
+    write_asset_manifest(Path::new(&out_dir).join("fe"));
+
+    // === internal gRPC service codegen: ===
+    // `PROTOC` wins when set; otherwise the vendored binary, so a plain build
+    // needs no protoc install.
+    let mut proto_config = tonic_prost_build::Config::new();
+    if env::var_os("PROTOC").is_none() {
+        proto_config.protoc_executable(
+            protoc_bin_vendored::protoc_bin_path().expect("No vendored protoc for this platform"),
+        );
+    }
+    tonic_prost_build::configure()
+        .build_client(false)
+        .compile_with_config(proto_config, &["proto/internal.proto"], &["proto"])
+        .expect("Failed to compile proto/internal.proto");
+}
+
+/// Writes `$OUT_DIR/asset_manifest.rs`: every file embedded from `fe/` except
+/// entry points (`index.html`) and precompressed `.gz`/`.zst` siblings, paired
+/// with a fingerprinted name that carries a hash of its contents
+/// (`assets/app.js` -> `assets/app.0123456789abcdef.js`).
+fn write_asset_manifest(fe_dir: std::path::PathBuf) {
+    let mut files = Vec::new();
+    collect_files(&fe_dir, &fe_dir, &mut files);
+
+    let mut by_logical: Vec<(String, String)> = Vec::new();
+    for (relative, full) in files {
+        let file_name = relative.rsplit('/').next().unwrap_or(&relative);
+        if file_name == "index.html" || relative.ends_with(".gz") || relative.ends_with(".zst") {
+            continue;
+        }
+        let Ok(bytes) = std::fs::read(&full) else {
+            continue;
+        };
+        let hash = format!("{:016x}", fnv1a64(&bytes));
+        let fingerprinted = match file_name.rfind('.') {
+            Some(dot) if dot > 0 => {
+                let split = relative.len() - file_name.len() + dot;
+                format!("{}.{hash}{}", &relative[..split], &relative[split..])
+            }
+            _ => format!("{relative}.{hash}"),
+        };
+        by_logical.push((relative, fingerprinted));
+    }
+    by_logical.sort();
+    let mut by_fingerprint: Vec<(String, String)> = by_logical
+        .iter()
+        .map(|(logical, fingerprinted)| (fingerprinted.clone(), logical.clone()))
+        .collect();
+    by_fingerprint.sort();
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not found");
+    let dest_path = Path::new(&out_dir).join("asset_manifest.rs");
+    let mut f = File::create(&dest_path).expect("Unable to create asset_manifest.rs");
+    writeln!(f, "// autogenerated by build.rs - do not edit!").expect("Failed to write manifest");
+    for (name, entries) in [
+        ("BY_LOGICAL", &by_logical),
+        ("BY_FINGERPRINT", &by_fingerprint),
+    ] {
+        writeln!(f, "pub static {name}: &[(&str, &str)] = &[").expect("Failed to write manifest");
+        for (key, value) in entries {
+            writeln!(f, "    ({key:?}, {value:?}),").expect("Failed to write manifest entry");
+        }
+        writeln!(f, "];").expect("Failed to write manifest");
+    }
+}
+
+/// Paths under `root`, relative and `/`-separated. A missing `fe/` yields an
+/// empty manifest.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<(String, std::path::PathBuf)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(root, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative, path));
+        }
+    }
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
 }
 
 fn rustc_version() -> String {
@@ -0,0 +1,10 @@
+-- Reverse the photo album schema.
+DROP INDEX IF EXISTS idx_photographs_album_position;
+
+ALTER TABLE photographs
+    DROP CONSTRAINT IF EXISTS fk_photographs_album,
+    DROP COLUMN IF EXISTS photograph_album_position,
+    DROP COLUMN IF EXISTS album_id;
+
+DROP INDEX IF EXISTS idx_albums_sort_order;
+DROP TABLE IF EXISTS albums;
@@ -0,0 +1,26 @@
+------------------------------------------------------------
+-- Photo albums: ordered collections of photographs.
+------------------------------------------------------------
+
+CREATE TABLE albums (
+    album_id uuid PRIMARY KEY DEFAULT uuidv7(),
+    user_id uuid NOT NULL,
+    album_title text NOT NULL,
+    album_description text NOT NULL DEFAULT '',
+    album_cover_photograph_id uuid,
+    album_sort_order integer NOT NULL DEFAULT 0,
+    album_created_at timestamptz NOT NULL DEFAULT now(),
+    album_updated_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT fk_albums_user FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_albums_cover_photograph FOREIGN KEY (album_cover_photograph_id) REFERENCES photographs (photograph_id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_albums_sort_order ON albums USING btree (album_sort_order, album_created_at DESC);
+
+-- A photograph belongs to at most one album; deleting the album keeps the photographs.
+ALTER TABLE photographs
+    ADD COLUMN album_id uuid,
+    ADD COLUMN photograph_album_position integer NOT NULL DEFAULT 0,
+    ADD CONSTRAINT fk_photographs_album FOREIGN KEY (album_id) REFERENCES albums (album_id) ON DELETE SET NULL;
+
+CREATE INDEX idx_photographs_album_position ON photographs USING btree (album_id, photograph_album_position);
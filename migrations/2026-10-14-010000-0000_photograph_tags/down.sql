@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_photograph_tags_tag_id;
+DROP TABLE IF EXISTS public.photograph_tags;
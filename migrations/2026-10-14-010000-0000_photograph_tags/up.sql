@@ -0,0 +1,11 @@
+-- Photograph-to-tag relations (many-to-many), sharing the blog `tags` table so
+-- both post and photograph tags draw from one vocabulary.
+CREATE TABLE public.photograph_tags (
+    photograph_id uuid NOT NULL,
+    tag_id smallint NOT NULL,
+    CONSTRAINT photograph_tags_pkey PRIMARY KEY (photograph_id, tag_id),
+    CONSTRAINT fk_photograph_tags_photograph FOREIGN KEY (photograph_id) REFERENCES public.photographs(photograph_id) ON DELETE CASCADE,
+    CONSTRAINT fk_photograph_tags_tag FOREIGN KEY (tag_id) REFERENCES public.tags(tag_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_photograph_tags_tag_id ON public.photograph_tags USING btree (tag_id);
@@ -0,0 +1,8 @@
+ALTER TABLE photographs
+    DROP COLUMN IF EXISTS photograph_iso,
+    DROP COLUMN IF EXISTS photograph_exposure_time_seconds,
+    DROP COLUMN IF EXISTS photograph_aperture_f_number,
+    DROP COLUMN IF EXISTS photograph_focal_length_mm,
+    DROP COLUMN IF EXISTS photograph_lens_model,
+    DROP COLUMN IF EXISTS photograph_camera_model,
+    DROP COLUMN IF EXISTS photograph_camera_make;
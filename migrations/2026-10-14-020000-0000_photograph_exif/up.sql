@@ -0,0 +1,10 @@
+-- Structured EXIF capture for photo detail pages. All nullable: many images
+-- (screenshots, edited exports) carry partial or no EXIF.
+ALTER TABLE photographs
+    ADD COLUMN photograph_camera_make text,
+    ADD COLUMN photograph_camera_model text,
+    ADD COLUMN photograph_lens_model text,
+    ADD COLUMN photograph_focal_length_mm double precision,
+    ADD COLUMN photograph_aperture_f_number double precision,
+    ADD COLUMN photograph_exposure_time_seconds double precision,
+    ADD COLUMN photograph_iso integer;
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS idx_photographs_lat_lon;
@@ -0,0 +1,2 @@
+-- Viewport queries (`/api/photographs/in-bounds`) range-scan on latitude first.
+CREATE INDEX idx_photographs_lat_lon ON photographs USING btree (photograph_lat, photograph_lon);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_photographs_processing_unfinished;
+
+ALTER TABLE public.photographs
+    DROP COLUMN IF EXISTS photograph_processing_error,
+    DROP COLUMN IF EXISTS photograph_processing_status;
+
+DROP TYPE IF EXISTS public.photograph_processing_status;
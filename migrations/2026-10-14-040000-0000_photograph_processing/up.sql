@@ -0,0 +1,18 @@
+-- Asynchronous processing: a photograph row is inserted as soon as the upload
+-- is accepted and flipped to 'ready' once its AVIF + thumbnail are on S3.
+-- Existing rows were processed synchronously, so they default to 'ready'.
+DO $$
+BEGIN
+    CREATE TYPE public.photograph_processing_status AS ENUM ('pending', 'processing', 'ready', 'failed');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END $$;
+
+ALTER TABLE public.photographs
+    ADD COLUMN photograph_processing_status public.photograph_processing_status NOT NULL DEFAULT 'ready',
+    ADD COLUMN photograph_processing_error text;
+
+-- Startup recovery scans for unfinished rows; keep that scan off the full table.
+CREATE INDEX idx_photographs_processing_unfinished
+    ON public.photographs (photograph_created_at)
+    WHERE photograph_processing_status IN ('pending', 'processing');
@@ -0,0 +1,2 @@
+ALTER TABLE public.photographs
+    DROP COLUMN IF EXISTS photograph_renditions;
@@ -0,0 +1,5 @@
+-- Responsive renditions: {"small": {"url", "width", "height"}, "medium": ..., "large": ...}.
+-- Rows uploaded before renditions keep '{}' and are served via
+-- photograph_thumbnail_link alone.
+ALTER TABLE public.photographs
+    ADD COLUMN photograph_renditions jsonb NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,3 @@
+ALTER TABLE public.photographs
+    DROP COLUMN IF EXISTS photograph_raw_link,
+    DROP COLUMN IF EXISTS photograph_raw_format;
@@ -0,0 +1,7 @@
+-- Camera RAW uploads keep their untouched original in S3 next to the AVIF
+-- derivatives. The format is recorded at accept time so queued processing can
+-- develop the staged original again after a restart.
+ALTER TABLE public.photographs
+    ADD COLUMN photograph_raw_format text
+        CONSTRAINT photographs_raw_format_check CHECK (photograph_raw_format IN ('cr2', 'nef', 'arw')),
+    ADD COLUMN photograph_raw_link text;
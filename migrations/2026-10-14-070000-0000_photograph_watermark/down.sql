@@ -0,0 +1,3 @@
+ALTER TABLE public.photographs
+    DROP COLUMN IF EXISTS photograph_private_original_key,
+    DROP COLUMN IF EXISTS photograph_watermarked;
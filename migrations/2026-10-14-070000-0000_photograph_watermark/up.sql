@@ -0,0 +1,6 @@
+-- Watermarked photographs publish stamped derivatives only; the unwatermarked
+-- original is kept under a private S3 prefix and referenced by key (never a
+-- public link).
+ALTER TABLE public.photographs
+    ADD COLUMN photograph_watermarked boolean NOT NULL DEFAULT false,
+    ADD COLUMN photograph_private_original_key text;
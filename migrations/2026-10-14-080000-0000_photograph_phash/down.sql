@@ -0,0 +1,3 @@
+ALTER TABLE public.photographs
+    DROP COLUMN IF EXISTS photograph_duplicate_of,
+    DROP COLUMN IF EXISTS photograph_phash;
@@ -0,0 +1,7 @@
+-- 64-bit DCT perceptual hash computed during processing (u64 bit pattern
+-- stored as bigint) and, under the `warn` duplicate policy, the closest
+-- existing near-duplicate at ingest time.
+ALTER TABLE public.photographs
+    ADD COLUMN photograph_phash bigint,
+    ADD COLUMN photograph_duplicate_of uuid
+        REFERENCES public.photographs (photograph_id) ON DELETE SET NULL;
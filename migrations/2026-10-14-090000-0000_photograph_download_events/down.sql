@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_photograph_download_events_photograph_id;
+DROP TABLE IF EXISTS public.photograph_download_events;
@@ -0,0 +1,16 @@
+-- Audit trail of full-resolution original downloads. No FK to photographs so
+-- the trail survives photograph deletion.
+CREATE TABLE public.photograph_download_events (
+    photograph_download_event_id uuid NOT NULL,
+    photograph_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    photograph_download_object_key text NOT NULL,
+    photograph_download_method varchar(16) NOT NULL,
+    photograph_download_ip inet,
+    photograph_downloaded_at timestamptz DEFAULT now() NOT NULL,
+    CONSTRAINT photograph_download_events_pkey PRIMARY KEY (photograph_download_event_id),
+    CONSTRAINT fk_photograph_download_events_user FOREIGN KEY (user_id) REFERENCES public.users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_photograph_download_events_photograph_id
+    ON public.photograph_download_events USING btree (photograph_id, photograph_downloaded_at DESC);
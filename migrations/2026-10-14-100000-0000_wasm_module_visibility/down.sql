@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_wasm_module_allowed_users_user_id;
+DROP TABLE IF EXISTS public.wasm_module_allowed_users;
+ALTER TABLE public.wasm_module DROP COLUMN IF EXISTS wasm_module_visibility;
+DROP TYPE IF EXISTS public.wasm_module_visibility;
@@ -0,0 +1,24 @@
+-- Per-module visibility: 'public' modules are listed and served to everyone,
+-- 'unlisted' ones are served by link but not listed, 'superuser_only' ones
+-- are hidden. Users in wasm_module_allowed_users can see any of them.
+DO $$
+BEGIN
+    CREATE TYPE public.wasm_module_visibility AS ENUM ('public', 'unlisted', 'superuser_only');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END $$;
+
+ALTER TABLE public.wasm_module
+    ADD COLUMN wasm_module_visibility public.wasm_module_visibility NOT NULL DEFAULT 'public';
+
+CREATE TABLE public.wasm_module_allowed_users (
+    wasm_module_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    wasm_module_allowed_user_created_at timestamptz DEFAULT now() NOT NULL,
+    CONSTRAINT wasm_module_allowed_users_pkey PRIMARY KEY (wasm_module_id, user_id),
+    CONSTRAINT fk_wasm_module_allowed_users_module FOREIGN KEY (wasm_module_id) REFERENCES public.wasm_module(wasm_module_id) ON DELETE CASCADE,
+    CONSTRAINT fk_wasm_module_allowed_users_user FOREIGN KEY (user_id) REFERENCES public.users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_wasm_module_allowed_users_user_id
+    ON public.wasm_module_allowed_users USING btree (user_id);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS public.wasm_module_loads;
@@ -0,0 +1,13 @@
+-- Daily WASM bundle load counts per country. serve_wasm buffers loads in
+-- memory and a minute job upserts them here, so a popular demo costs one row
+-- per day and country instead of one per load.
+CREATE TABLE public.wasm_module_loads (
+    wasm_module_id uuid NOT NULL,
+    wasm_module_load_date date NOT NULL,
+    -- ISO 3166-1 alpha-2 from geo-IP; 'ZZ' when unknown.
+    wasm_module_load_country_code varchar(2) NOT NULL,
+    wasm_module_load_count bigint DEFAULT 0 NOT NULL,
+    wasm_module_last_loaded_at timestamptz DEFAULT now() NOT NULL,
+    CONSTRAINT wasm_module_loads_pkey PRIMARY KEY (wasm_module_id, wasm_module_load_date, wasm_module_load_country_code),
+    CONSTRAINT fk_wasm_module_loads_module FOREIGN KEY (wasm_module_id) REFERENCES public.wasm_module(wasm_module_id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Fails while any bundle lives only in object storage; move those back first.
+ALTER TABLE public.wasm_module DROP CONSTRAINT IF EXISTS wasm_module_bundle_location_check;
+ALTER TABLE public.wasm_module
+    DROP COLUMN IF EXISTS wasm_module_bundle_size_bytes,
+    DROP COLUMN IF EXISTS wasm_module_bundle_sha256,
+    DROP COLUMN IF EXISTS wasm_module_bundle_key,
+    ALTER COLUMN wasm_module_bundle_gz SET NOT NULL;
@@ -0,0 +1,18 @@
+-- Bundles may live in object storage instead of the bytea column: such rows
+-- keep only wasm_module_bundle_key. Every row records the SHA-256 (hex) and
+-- size of its gzipped bundle wherever the bytes live.
+ALTER TABLE public.wasm_module
+    ALTER COLUMN wasm_module_bundle_gz DROP NOT NULL,
+    ADD COLUMN wasm_module_bundle_key varchar NULL,
+    ADD COLUMN wasm_module_bundle_sha256 varchar(64) NULL,
+    ADD COLUMN wasm_module_bundle_size_bytes int8 NULL;
+
+UPDATE public.wasm_module
+SET wasm_module_bundle_sha256 = encode(sha256(wasm_module_bundle_gz), 'hex'),
+    wasm_module_bundle_size_bytes = octet_length(wasm_module_bundle_gz);
+
+ALTER TABLE public.wasm_module
+    ALTER COLUMN wasm_module_bundle_sha256 SET NOT NULL,
+    ALTER COLUMN wasm_module_bundle_size_bytes SET NOT NULL,
+    ADD CONSTRAINT wasm_module_bundle_location_check
+        CHECK (wasm_module_bundle_gz IS NOT NULL OR wasm_module_bundle_key IS NOT NULL);
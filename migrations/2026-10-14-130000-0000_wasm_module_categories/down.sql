@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_wasm_module_tags;
+DROP INDEX IF EXISTS idx_wasm_module_category;
+ALTER TABLE public.wasm_module
+    DROP COLUMN IF EXISTS wasm_module_tags,
+    DROP COLUMN IF EXISTS wasm_module_category;
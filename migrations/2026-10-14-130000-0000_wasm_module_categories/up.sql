@@ -0,0 +1,11 @@
+-- One optional category and a free-form tag list per module, both stored
+-- lowercased. Titles and descriptions are searched through the in-memory
+-- Tantivy index, not SQL.
+ALTER TABLE public.wasm_module
+    ADD COLUMN wasm_module_category varchar(64) NULL,
+    ADD COLUMN wasm_module_tags text[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_wasm_module_category
+    ON public.wasm_module USING btree (wasm_module_category);
+CREATE INDEX idx_wasm_module_tags
+    ON public.wasm_module USING gin (wasm_module_tags);
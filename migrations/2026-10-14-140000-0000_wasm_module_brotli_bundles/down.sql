@@ -0,0 +1,3 @@
+ALTER TABLE public.wasm_module
+    DROP COLUMN IF EXISTS wasm_module_bundle_br_key,
+    DROP COLUMN IF EXISTS wasm_module_bundle_br;
@@ -0,0 +1,6 @@
+-- Optional Brotli copy of each bundle, produced at upload time next to the
+-- gzip one and stored the same way (bytea column or object key). Rows
+-- uploaded before this keep serving gzip only.
+ALTER TABLE public.wasm_module
+    ADD COLUMN wasm_module_bundle_br bytea NULL,
+    ADD COLUMN wasm_module_bundle_br_key varchar NULL;
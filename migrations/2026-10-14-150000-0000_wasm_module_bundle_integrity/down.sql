@@ -0,0 +1,2 @@
+ALTER TABLE public.wasm_module
+    DROP COLUMN IF EXISTS wasm_module_bundle_integrity;
@@ -0,0 +1,5 @@
+-- Subresource Integrity value ("sha384-<base64>") of the decoded bundle, so
+-- embedding pages can pin it. NULL for bundles uploaded before it was
+-- computed; replacing the bundle fills it in.
+ALTER TABLE public.wasm_module
+    ADD COLUMN wasm_module_bundle_integrity varchar(96) NULL;
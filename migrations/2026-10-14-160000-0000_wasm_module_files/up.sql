@@ -0,0 +1,13 @@
+-- Files of modules uploaded as a zip (wasm + JS glue + assets), served under
+-- /api/wasm-modules/{id}/files/{path}. Bytes live in object storage under
+-- content-addressed keys; the row maps the archive path to its object.
+CREATE TABLE public.wasm_module_files (
+    wasm_module_id uuid NOT NULL,
+    wasm_module_file_path varchar(512) NOT NULL,
+    wasm_module_file_key varchar NOT NULL,
+    wasm_module_file_content_type varchar(255) NOT NULL,
+    wasm_module_file_size_bytes int8 NOT NULL,
+    wasm_module_file_sha256 varchar(64) NOT NULL,
+    CONSTRAINT wasm_module_files_pkey PRIMARY KEY (wasm_module_id, wasm_module_file_path),
+    CONSTRAINT fk_wasm_module_files_module FOREIGN KEY (wasm_module_id) REFERENCES public.wasm_module(wasm_module_id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+-- Last successful run of each scheduled job, so a restart can tell whether a
+-- daily/weekly run was missed while the process was down.
+CREATE TABLE public.job_runs (
+    job_name varchar(64) NOT NULL,
+    job_last_success_at timestamptz NOT NULL,
+    job_updated_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT job_runs_pkey PRIMARY KEY (job_name)
+);
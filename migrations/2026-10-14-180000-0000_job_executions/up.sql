@@ -0,0 +1,16 @@
+-- One row per scheduled-task execution, for /api/admin/jobs. Successful runs
+-- of every-second jobs are not recorded; PRUNE_JOB_HISTORY drops old rows.
+CREATE TABLE public.job_executions (
+    job_execution_id int8 GENERATED ALWAYS AS IDENTITY NOT NULL,
+    job_name varchar(64) NOT NULL,
+    job_scheduled_for timestamptz NOT NULL,
+    job_started_at timestamptz NOT NULL,
+    job_duration_ms int8 NOT NULL,
+    job_outcome varchar(16) NOT NULL,
+    job_error text NULL,
+    CONSTRAINT job_executions_pkey PRIMARY KEY (job_execution_id),
+    CONSTRAINT job_executions_outcome_check CHECK (job_outcome IN ('success', 'failure', 'panicked'))
+);
+
+CREATE INDEX idx_job_executions_name_started ON public.job_executions (job_name, job_started_at DESC);
+CREATE INDEX idx_job_executions_started ON public.job_executions (job_started_at);
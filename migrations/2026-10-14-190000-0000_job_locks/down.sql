@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS public.idx_job_executions_name_scheduled;
+
+DELETE FROM public.job_executions WHERE job_outcome = 'skipped';
+ALTER TABLE public.job_executions DROP CONSTRAINT job_executions_outcome_check;
+ALTER TABLE public.job_executions ADD CONSTRAINT job_executions_outcome_check
+    CHECK (job_outcome IN ('success', 'failure', 'panicked'));
+
+ALTER TABLE public.job_executions DROP COLUMN IF EXISTS job_lock_wait_ms;
@@ -0,0 +1,9 @@
+-- Cluster-exclusive jobs take a Postgres advisory lock per tick. A tick that
+-- another instance holds or already ran is recorded as 'skipped'.
+ALTER TABLE public.job_executions ADD COLUMN job_lock_wait_ms int8 NULL;
+
+ALTER TABLE public.job_executions DROP CONSTRAINT job_executions_outcome_check;
+ALTER TABLE public.job_executions ADD CONSTRAINT job_executions_outcome_check
+    CHECK (job_outcome IN ('success', 'failure', 'panicked', 'skipped'));
+
+CREATE INDEX idx_job_executions_name_scheduled ON public.job_executions (job_name, job_scheduled_for);
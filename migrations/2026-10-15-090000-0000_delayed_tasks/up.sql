@@ -0,0 +1,22 @@
+-- One-shot follow-up work enqueued by handlers (`schedule_once_at` /
+-- `schedule_after`), kept here so it survives restarts. Instances claim due
+-- rows with FOR UPDATE SKIP LOCKED; PRUNE_JOB_HISTORY drops finished rows.
+CREATE TABLE public.delayed_tasks (
+    delayed_task_id uuid NOT NULL,
+    delayed_task_kind varchar(64) NOT NULL,
+    delayed_task_payload jsonb NOT NULL,
+    delayed_task_run_at timestamptz NOT NULL,
+    delayed_task_status varchar(16) NOT NULL DEFAULT 'pending',
+    delayed_task_attempts int4 NOT NULL DEFAULT 0,
+    delayed_task_last_error text NULL,
+    delayed_task_created_at timestamptz NOT NULL DEFAULT now(),
+    delayed_task_claimed_at timestamptz NULL,
+    delayed_task_finished_at timestamptz NULL,
+    CONSTRAINT delayed_tasks_pkey PRIMARY KEY (delayed_task_id),
+    CONSTRAINT delayed_tasks_status_check CHECK (delayed_task_status IN ('pending', 'running', 'done', 'failed'))
+);
+
+CREATE INDEX idx_delayed_tasks_pending_run_at ON public.delayed_tasks (delayed_task_run_at)
+    WHERE delayed_task_status = 'pending';
+CREATE INDEX idx_delayed_tasks_finished_at ON public.delayed_tasks (delayed_task_finished_at)
+    WHERE delayed_task_finished_at IS NOT NULL;
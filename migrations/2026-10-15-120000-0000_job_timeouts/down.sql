@@ -0,0 +1,5 @@
+UPDATE public.job_executions SET job_outcome = 'failure'
+    WHERE job_outcome IN ('timed_out', 'cancelled');
+ALTER TABLE public.job_executions DROP CONSTRAINT job_executions_outcome_check;
+ALTER TABLE public.job_executions ADD CONSTRAINT job_executions_outcome_check
+    CHECK (job_outcome IN ('success', 'failure', 'panicked', 'skipped'));
@@ -0,0 +1,4 @@
+-- Runs aborted for exceeding their timeout, or cancelled by an admin.
+ALTER TABLE public.job_executions DROP CONSTRAINT job_executions_outcome_check;
+ALTER TABLE public.job_executions ADD CONSTRAINT job_executions_outcome_check
+    CHECK (job_outcome IN ('success', 'failure', 'panicked', 'skipped', 'timed_out', 'cancelled'));
@@ -0,0 +1 @@
+ALTER TABLE public.job_executions DROP COLUMN job_details;
@@ -0,0 +1,2 @@
+-- Job-specific facts about a run (e.g. backup size), as reported by the job.
+ALTER TABLE public.job_executions ADD COLUMN job_details jsonb;
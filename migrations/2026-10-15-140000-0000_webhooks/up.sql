@@ -0,0 +1,33 @@
+-- Outgoing webhooks: endpoints registered by a superuser and one row per
+-- event delivered to each. Deliveries are sent by the delayed task queue
+-- (`deliver_signed_webhook`) and go away with their webhook.
+CREATE TABLE public.webhooks (
+    webhook_id uuid NOT NULL DEFAULT uuidv7(),
+    webhook_url text NOT NULL,
+    webhook_secret text NOT NULL,
+    webhook_event_types text[] NOT NULL,
+    webhook_enabled bool NOT NULL DEFAULT true,
+    webhook_created_at timestamptz NOT NULL DEFAULT now(),
+    webhook_updated_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT webhooks_pkey PRIMARY KEY (webhook_id)
+);
+
+CREATE TABLE public.webhook_deliveries (
+    webhook_delivery_id uuid NOT NULL,
+    webhook_id uuid NOT NULL,
+    webhook_delivery_event_type varchar(64) NOT NULL,
+    webhook_delivery_payload jsonb NOT NULL,
+    webhook_delivery_status varchar(16) NOT NULL DEFAULT 'pending',
+    webhook_delivery_attempts int4 NOT NULL DEFAULT 0,
+    webhook_delivery_response_status int4 NULL,
+    webhook_delivery_last_error text NULL,
+    webhook_delivery_created_at timestamptz NOT NULL DEFAULT now(),
+    webhook_delivery_delivered_at timestamptz NULL,
+    CONSTRAINT webhook_deliveries_pkey PRIMARY KEY (webhook_delivery_id),
+    CONSTRAINT webhook_deliveries_webhook_id_fkey FOREIGN KEY (webhook_id)
+        REFERENCES public.webhooks (webhook_id) ON DELETE CASCADE,
+    CONSTRAINT webhook_deliveries_status_check CHECK (webhook_delivery_status IN ('pending', 'succeeded', 'failed'))
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook_id_created_at
+    ON public.webhook_deliveries (webhook_id, webhook_delivery_created_at DESC);
@@ -0,0 +1,22 @@
+-- Audit trail of superuser mutations, written by the handlers through
+-- `ServerState::record_admin_action`. Rows outlive the actor's account.
+CREATE TABLE public.admin_audit (
+    admin_audit_id uuid NOT NULL DEFAULT uuidv7(),
+    admin_audit_actor_id uuid NULL,
+    admin_audit_action varchar(64) NOT NULL,
+    admin_audit_target_type varchar(32) NOT NULL,
+    admin_audit_target_id text NULL,
+    admin_audit_summary jsonb NOT NULL DEFAULT '{}'::jsonb,
+    admin_audit_ip inet NULL,
+    admin_audit_created_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT admin_audit_pkey PRIMARY KEY (admin_audit_id),
+    CONSTRAINT admin_audit_actor_id_fkey FOREIGN KEY (admin_audit_actor_id)
+        REFERENCES public.users (user_id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_admin_audit_created_at
+    ON public.admin_audit (admin_audit_created_at DESC);
+CREATE INDEX idx_admin_audit_actor_id_created_at
+    ON public.admin_audit (admin_audit_actor_id, admin_audit_created_at DESC);
+CREATE INDEX idx_admin_audit_target
+    ON public.admin_audit (admin_audit_target_type, admin_audit_target_id);
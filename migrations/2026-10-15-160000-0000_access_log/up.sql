@@ -0,0 +1,24 @@
+-- Sampled request log for traffic analysis, filled in batches from
+-- `log_middleware` by the FLUSH_ACCESS_LOG job and pruned by
+-- PRUNE_ACCESS_LOG after `ACCESS_LOG_RETENTION_DAYS`.
+CREATE TABLE public.access_log (
+    access_log_id uuid NOT NULL DEFAULT uuidv7(),
+    access_log_method varchar(16) NOT NULL,
+    access_log_path text NOT NULL,
+    access_log_status smallint NOT NULL,
+    access_log_latency_ms double precision NOT NULL,
+    access_log_country_code varchar(8) NULL,
+    access_log_user_id uuid NULL,
+    access_log_created_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT access_log_pkey PRIMARY KEY (access_log_id),
+    CONSTRAINT access_log_user_id_fkey FOREIGN KEY (access_log_user_id)
+        REFERENCES public.users (user_id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_access_log_created_at
+    ON public.access_log (access_log_created_at DESC);
+CREATE INDEX idx_access_log_path_created_at
+    ON public.access_log (access_log_path, access_log_created_at DESC);
+CREATE INDEX idx_access_log_user_id_created_at
+    ON public.access_log (access_log_user_id, access_log_created_at DESC)
+    WHERE access_log_user_id IS NOT NULL;
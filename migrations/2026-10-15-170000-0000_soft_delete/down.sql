@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS public.idx_photographs_deleted_at;
+DROP INDEX IF EXISTS public.idx_comments_deleted_at;
+DROP INDEX IF EXISTS public.idx_posts_deleted_at;
+
+ALTER TABLE public.photographs DROP COLUMN IF EXISTS photograph_deleted_at;
+ALTER TABLE public.comments DROP COLUMN IF EXISTS comment_deleted_at;
+ALTER TABLE public.posts DROP COLUMN IF EXISTS post_deleted_at;
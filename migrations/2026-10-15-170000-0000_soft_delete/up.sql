@@ -0,0 +1,16 @@
+-- Soft delete for user-facing content. Deleting sets `*_deleted_at`; reads
+-- skip those rows, an admin can restore them, and PURGE_SOFT_DELETED removes
+-- them for good after `SOFT_DELETE_RETENTION_DAYS`.
+ALTER TABLE public.posts ADD COLUMN post_deleted_at timestamptz NULL;
+ALTER TABLE public.comments ADD COLUMN comment_deleted_at timestamptz NULL;
+ALTER TABLE public.photographs ADD COLUMN photograph_deleted_at timestamptz NULL;
+
+CREATE INDEX idx_posts_deleted_at
+    ON public.posts (post_deleted_at)
+    WHERE post_deleted_at IS NOT NULL;
+CREATE INDEX idx_comments_deleted_at
+    ON public.comments (comment_deleted_at)
+    WHERE comment_deleted_at IS NOT NULL;
+CREATE INDEX idx_photographs_deleted_at
+    ON public.photographs (photograph_deleted_at)
+    WHERE photograph_deleted_at IS NOT NULL;
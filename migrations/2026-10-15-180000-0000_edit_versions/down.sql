@@ -0,0 +1,3 @@
+ALTER TABLE public.wasm_module DROP COLUMN IF EXISTS wasm_module_version;
+ALTER TABLE public.comments DROP COLUMN IF EXISTS comment_version;
+ALTER TABLE public.posts DROP COLUMN IF EXISTS post_version;
@@ -0,0 +1,5 @@
+-- Optimistic concurrency for edits. Every update bumps `*_version`; an edit
+-- that names an older version than the row holds is refused as EDIT_CONFLICT.
+ALTER TABLE public.posts ADD COLUMN post_version int4 NOT NULL DEFAULT 1;
+ALTER TABLE public.comments ADD COLUMN comment_version int4 NOT NULL DEFAULT 1;
+ALTER TABLE public.wasm_module ADD COLUMN wasm_module_version int4 NOT NULL DEFAULT 1;
@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS public.idx_comments_post_id_created_at_id;
+
+CREATE INDEX IF NOT EXISTS idx_visitation_data_visited_at
+    ON public.visitation_data (visited_at);
+DROP INDEX IF EXISTS public.idx_visitation_data_visited_at_id;
+
+CREATE INDEX IF NOT EXISTS idx_access_log_created_at
+    ON public.access_log (access_log_created_at DESC);
+DROP INDEX IF EXISTS public.idx_access_log_created_at_id;
@@ -0,0 +1,12 @@
+-- Indexes matching the `(created_at, id)` keyset walks in `util::db::keyset`.
+CREATE INDEX idx_access_log_created_at_id
+    ON public.access_log (access_log_created_at DESC, access_log_id DESC);
+DROP INDEX IF EXISTS public.idx_access_log_created_at;
+
+CREATE INDEX idx_visitation_data_visited_at_id
+    ON public.visitation_data (visited_at, visitation_data_id);
+DROP INDEX IF EXISTS public.idx_visitation_data_visited_at;
+
+CREATE INDEX idx_comments_post_id_created_at_id
+    ON public.comments (post_id, comment_created_at, comment_id)
+    WHERE comment_deleted_at IS NULL;
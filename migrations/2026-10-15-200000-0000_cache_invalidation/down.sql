@@ -0,0 +1,10 @@
+DROP TRIGGER IF EXISTS i18n_strings_cache_invalidation ON public.i18n_strings;
+DROP TRIGGER IF EXISTS iso_currency_cache_invalidation ON public.iso_currency;
+DROP TRIGGER IF EXISTS iso_language_cache_invalidation ON public.iso_language;
+DROP TRIGGER IF EXISTS iso_country_subdivision_cache_invalidation ON public.iso_country_subdivision;
+DROP TRIGGER IF EXISTS iso_country_cache_invalidation ON public.iso_country;
+DROP TRIGGER IF EXISTS tags_cache_invalidation ON public.tags;
+DROP TRIGGER IF EXISTS post_tags_cache_invalidation ON public.post_tags;
+DROP TRIGGER IF EXISTS posts_cache_invalidation_update ON public.posts;
+DROP TRIGGER IF EXISTS posts_cache_invalidation ON public.posts;
+DROP FUNCTION IF EXISTS public.notify_cache_invalidation();
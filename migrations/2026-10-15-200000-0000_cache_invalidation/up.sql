@@ -0,0 +1,72 @@
+-- Cross-instance cache invalidation. Changes to the tables behind the post,
+-- country and i18n caches are announced on the `cache_invalidation` channel
+-- as {"table": ..., "id": ...}; every instance LISTENs and refreshes the
+-- matching cache. `id` is the changed row's key column named by the trigger
+-- argument, or null for statement-level triggers.
+CREATE OR REPLACE FUNCTION public.notify_cache_invalidation() RETURNS trigger
+LANGUAGE plpgsql AS $$
+DECLARE
+    changed jsonb;
+BEGIN
+    IF TG_LEVEL = 'ROW' THEN
+        IF TG_OP = 'DELETE' THEN
+            changed := to_jsonb(OLD);
+        ELSE
+            changed := to_jsonb(NEW);
+        END IF;
+    END IF;
+    PERFORM pg_notify(
+        'cache_invalidation',
+        json_build_object(
+            'table', TG_TABLE_NAME,
+            'id', CASE WHEN TG_NARGS > 0 THEN changed ->> TG_ARGV[0] END
+        )::text
+    );
+    RETURN NULL;
+END;
+$$;
+
+-- View, share and vote counters change on every read and vote; the cached
+-- copies are refreshed on their own, so those updates are not announced.
+CREATE TRIGGER posts_cache_invalidation
+AFTER INSERT OR DELETE ON public.posts
+FOR EACH ROW EXECUTE FUNCTION public.notify_cache_invalidation('post_id');
+
+CREATE TRIGGER posts_cache_invalidation_update
+AFTER UPDATE ON public.posts
+FOR EACH ROW
+WHEN (
+    (to_jsonb(OLD) - ARRAY['post_view_count', 'post_share_count', 'total_upvotes', 'total_downvotes'])
+    IS DISTINCT FROM
+    (to_jsonb(NEW) - ARRAY['post_view_count', 'post_share_count', 'total_upvotes', 'total_downvotes'])
+)
+EXECUTE FUNCTION public.notify_cache_invalidation('post_id');
+
+CREATE TRIGGER post_tags_cache_invalidation
+AFTER INSERT OR UPDATE OR DELETE ON public.post_tags
+FOR EACH ROW EXECUTE FUNCTION public.notify_cache_invalidation('post_id');
+
+-- A renamed tag touches every post carrying it; the listener reloads them all.
+CREATE TRIGGER tags_cache_invalidation
+AFTER UPDATE OR DELETE ON public.tags
+FOR EACH STATEMENT EXECUTE FUNCTION public.notify_cache_invalidation();
+
+CREATE TRIGGER iso_country_cache_invalidation
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON public.iso_country
+FOR EACH STATEMENT EXECUTE FUNCTION public.notify_cache_invalidation();
+
+CREATE TRIGGER iso_country_subdivision_cache_invalidation
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON public.iso_country_subdivision
+FOR EACH STATEMENT EXECUTE FUNCTION public.notify_cache_invalidation();
+
+CREATE TRIGGER iso_language_cache_invalidation
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON public.iso_language
+FOR EACH STATEMENT EXECUTE FUNCTION public.notify_cache_invalidation();
+
+CREATE TRIGGER iso_currency_cache_invalidation
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON public.iso_currency
+FOR EACH STATEMENT EXECUTE FUNCTION public.notify_cache_invalidation();
+
+CREATE TRIGGER i18n_strings_cache_invalidation
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON public.i18n_strings
+FOR EACH STATEMENT EXECUTE FUNCTION public.notify_cache_invalidation();
@@ -0,0 +1,26 @@
+-- Transactional emails waiting to be sent. Handlers insert a row instead of
+-- talking to SMTP; the outbox sender claims due rows with FOR UPDATE SKIP
+-- LOCKED, renders them in the recipient's language and sends them, retrying
+-- with backoff until the row is `sent` or, out of attempts, `dead`.
+CREATE TABLE public.email_outbox (
+    email_outbox_id uuid NOT NULL,
+    email_outbox_template varchar(64) NOT NULL,
+    email_outbox_recipient varchar(320) NOT NULL,
+    email_outbox_locale varchar(16) NULL,
+    email_outbox_vars jsonb NOT NULL DEFAULT '{}',
+    email_outbox_status varchar(16) NOT NULL DEFAULT 'pending',
+    email_outbox_attempts int4 NOT NULL DEFAULT 0,
+    email_outbox_next_attempt_at timestamptz NOT NULL DEFAULT now(),
+    email_outbox_last_error text NULL,
+    email_outbox_created_at timestamptz NOT NULL DEFAULT now(),
+    email_outbox_claimed_at timestamptz NULL,
+    email_outbox_sent_at timestamptz NULL,
+    CONSTRAINT email_outbox_pkey PRIMARY KEY (email_outbox_id),
+    CONSTRAINT email_outbox_status_check CHECK (email_outbox_status IN ('pending', 'sending', 'sent', 'dead'))
+);
+
+CREATE INDEX idx_email_outbox_pending_next_attempt ON public.email_outbox (email_outbox_next_attempt_at)
+    WHERE email_outbox_status = 'pending';
+CREATE INDEX idx_email_outbox_sent_at ON public.email_outbox (email_outbox_sent_at)
+    WHERE email_outbox_sent_at IS NOT NULL;
+CREATE INDEX idx_email_outbox_created_at ON public.email_outbox (email_outbox_created_at DESC);
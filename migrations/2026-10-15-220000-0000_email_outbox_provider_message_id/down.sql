@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS public.idx_email_outbox_provider_message_id;
+ALTER TABLE public.email_outbox DROP COLUMN IF EXISTS email_outbox_provider_message_id;
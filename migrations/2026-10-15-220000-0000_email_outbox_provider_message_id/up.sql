@@ -0,0 +1,6 @@
+-- Provider message id of a sent outbox email (SES `MessageId`), so delivery
+-- events reported later can be matched back to the row. NULL over SMTP.
+ALTER TABLE public.email_outbox ADD COLUMN email_outbox_provider_message_id varchar(255) NULL;
+
+CREATE INDEX idx_email_outbox_provider_message_id ON public.email_outbox (email_outbox_provider_message_id)
+    WHERE email_outbox_provider_message_id IS NOT NULL;
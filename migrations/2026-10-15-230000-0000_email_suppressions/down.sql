@@ -0,0 +1,7 @@
+DELETE FROM public.email_outbox WHERE email_outbox_status = 'suppressed';
+ALTER TABLE public.email_outbox DROP CONSTRAINT email_outbox_status_check;
+ALTER TABLE public.email_outbox ADD CONSTRAINT email_outbox_status_check
+    CHECK (email_outbox_status IN ('pending', 'sending', 'sent', 'dead'));
+
+DROP TABLE IF EXISTS public.email_suppressions;
+DROP TABLE IF EXISTS public.email_feedback_events;
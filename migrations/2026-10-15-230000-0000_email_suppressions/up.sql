@@ -0,0 +1,40 @@
+-- Bounce and complaint notifications from SES (via SNS), one row per
+-- affected recipient. Kept for the record; `email_suppressions` holds the
+-- resulting state.
+CREATE TABLE public.email_feedback_events (
+    email_feedback_event_id uuid NOT NULL,
+    email_feedback_event_kind varchar(16) NOT NULL,
+    email_feedback_event_type varchar(32) NULL,
+    email_feedback_event_subtype varchar(64) NULL,
+    email_feedback_event_recipient varchar(320) NOT NULL,
+    email_feedback_event_provider_message_id varchar(255) NULL,
+    email_feedback_event_diagnostic text NULL,
+    email_feedback_event_payload jsonb NOT NULL,
+    email_feedback_event_occurred_at timestamptz NOT NULL,
+    email_feedback_event_created_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT email_feedback_events_pkey PRIMARY KEY (email_feedback_event_id),
+    CONSTRAINT email_feedback_events_kind_check CHECK (email_feedback_event_kind IN ('bounce', 'complaint'))
+);
+
+CREATE INDEX idx_email_feedback_events_recipient ON public.email_feedback_events (email_feedback_event_recipient, email_feedback_event_created_at DESC);
+CREATE INDEX idx_email_feedback_events_created_at ON public.email_feedback_events (email_feedback_event_created_at DESC);
+
+-- Addresses the email subsystem will not send to: hard bounces and
+-- complaints. Addresses are stored lowercased.
+CREATE TABLE public.email_suppressions (
+    email_suppression_address varchar(320) NOT NULL,
+    email_suppression_reason varchar(16) NOT NULL,
+    email_suppression_detail text NULL,
+    email_suppression_event_count int4 NOT NULL DEFAULT 1,
+    email_suppression_created_at timestamptz NOT NULL DEFAULT now(),
+    email_suppression_last_event_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT email_suppressions_pkey PRIMARY KEY (email_suppression_address),
+    CONSTRAINT email_suppressions_reason_check CHECK (email_suppression_reason IN ('bounce', 'complaint'))
+);
+
+CREATE INDEX idx_email_suppressions_created_at ON public.email_suppressions (email_suppression_created_at DESC);
+
+-- Outbox rows skipped because their recipient is suppressed.
+ALTER TABLE public.email_outbox DROP CONSTRAINT email_outbox_status_check;
+ALTER TABLE public.email_outbox ADD CONSTRAINT email_outbox_status_check
+    CHECK (email_outbox_status IN ('pending', 'sending', 'sent', 'dead', 'suppressed'));
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS public.user_notification_preferences;
+DROP TABLE IF EXISTS public.notifications;
@@ -0,0 +1,30 @@
+-- In-app notification center: every notification sent to a user, pushed
+-- over `/ws/notifications` when they are connected and listed by
+-- `GET /api/notifications` either way.
+CREATE TABLE public.notifications (
+    notification_id uuid NOT NULL,
+    user_id uuid NOT NULL REFERENCES public.users(user_id) ON DELETE CASCADE,
+    notification_type varchar(32) NOT NULL,
+    notification_payload jsonb NOT NULL,
+    notification_created_at timestamptz NOT NULL DEFAULT now(),
+    notification_read_at timestamptz NULL,
+    CONSTRAINT notifications_pkey PRIMARY KEY (notification_id)
+);
+
+CREATE INDEX idx_notifications_user_created_at ON public.notifications (user_id, notification_created_at DESC, notification_id DESC);
+CREATE INDEX idx_notifications_user_unread ON public.notifications (user_id, notification_created_at)
+    WHERE notification_read_at IS NULL;
+
+-- Per-user notification switches. Users without a row get the defaults.
+CREATE TABLE public.user_notification_preferences (
+    user_id uuid NOT NULL REFERENCES public.users(user_id) ON DELETE CASCADE,
+    notify_mentions bool NOT NULL DEFAULT true,
+    notify_replies bool NOT NULL DEFAULT true,
+    notify_digest bool NOT NULL DEFAULT false,
+    digest_sent_at timestamptz NULL,
+    preferences_updated_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT user_notification_preferences_pkey PRIMARY KEY (user_id)
+);
+
+CREATE INDEX idx_user_notification_preferences_digest ON public.user_notification_preferences (user_id)
+    WHERE notify_digest;
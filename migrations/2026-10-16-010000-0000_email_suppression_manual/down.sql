@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS public.idx_email_suppressions_reason;
+
+DELETE FROM public.email_suppressions WHERE email_suppression_reason = 'manual';
+ALTER TABLE public.email_suppressions DROP CONSTRAINT email_suppressions_reason_check;
+ALTER TABLE public.email_suppressions ADD CONSTRAINT email_suppressions_reason_check
+    CHECK (email_suppression_reason IN ('bounce', 'complaint'));
@@ -0,0 +1,6 @@
+-- Addresses a superuser suppressed by hand, alongside bounces and complaints.
+ALTER TABLE public.email_suppressions DROP CONSTRAINT email_suppressions_reason_check;
+ALTER TABLE public.email_suppressions ADD CONSTRAINT email_suppressions_reason_check
+    CHECK (email_suppression_reason IN ('bounce', 'complaint', 'manual'));
+
+CREATE INDEX idx_email_suppressions_reason ON public.email_suppressions (email_suppression_reason, email_suppression_created_at DESC);
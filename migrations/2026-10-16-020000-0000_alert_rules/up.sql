@@ -0,0 +1,23 @@
+-- Alert rules over the in-memory endpoint stats, evaluated every minute by
+-- EVALUATE_ALERT_RULES on each instance. `alert_rule_last_fired_at` is the
+-- shared cooldown: an instance fires only after claiming it.
+CREATE TABLE public.alert_rules (
+    alert_rule_id uuid NOT NULL DEFAULT uuidv7(),
+    alert_rule_name varchar(100) NOT NULL,
+    alert_rule_metric varchar(32) NOT NULL,
+    alert_rule_threshold float8 NOT NULL,
+    alert_rule_window varchar(8) NOT NULL DEFAULT '5m',
+    alert_rule_method varchar(16) NULL,
+    alert_rule_route text NULL,
+    alert_rule_min_requests int4 NOT NULL DEFAULT 20,
+    alert_rule_cooldown_minutes int4 NOT NULL DEFAULT 30,
+    alert_rule_emails text[] NOT NULL DEFAULT '{}',
+    alert_rule_webhook_url text NULL,
+    alert_rule_enabled bool NOT NULL DEFAULT true,
+    alert_rule_last_fired_at timestamptz NULL,
+    alert_rule_created_at timestamptz NOT NULL DEFAULT now(),
+    alert_rule_updated_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT alert_rules_pkey PRIMARY KEY (alert_rule_id),
+    CONSTRAINT alert_rules_metric_check CHECK (alert_rule_metric IN ('p50_ms', 'p95_ms', 'p99_ms', 'mean_ms', 'client_error_rate', 'server_error_rate')),
+    CONSTRAINT alert_rules_window_check CHECK (alert_rule_window IN ('1m', '5m', '15m', '1h'))
+);
@@ -0,0 +1,5 @@
+-- The declared MIME type of an upload accepted for queued processing, so a
+-- job recovered after a restart encodes and stores the original as the
+-- request described it.
+ALTER TABLE public.photographs
+    ADD COLUMN photograph_upload_content_type text;
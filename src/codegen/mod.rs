@@ -0,0 +1,54 @@
+//! Client generation from the OpenAPI spec in `docs.rs`.
+//!
+//! `rust-be-template codegen [--out DIR]` writes `openapi.json`, a typed
+//! TypeScript client (`api-client.ts`) and the GraphQL schema
+//! (`schema.graphql`) to `DIR` (default `./generated`) and
+//! exits without loading configuration or touching the database, so build
+//! scripts and CI can run it on a fresh checkout. The frontend build copies
+//! the client in; a DTO rename then fails its type check instead of breaking
+//! at runtime.
+
+pub mod typescript;
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use utoipa::OpenApi;
+
+use crate::docs::ApiDoc;
+
+const DEFAULT_OUT_DIR: &str = "./generated";
+
+/// Entry point for the `codegen` subcommand; `args` follow the subcommand.
+pub fn run(mut args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let mut out_dir = PathBuf::from(DEFAULT_OUT_DIR);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => {
+                out_dir = args
+                    .next()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| anyhow!("--out needs a directory"))?;
+            }
+            other => return Err(anyhow!("Unknown codegen argument: {other}")),
+        }
+    }
+
+    let spec = ApiDoc::openapi();
+    let spec_json = spec.to_pretty_json()?;
+    let spec_value: serde_json::Value = serde_json::from_str(&spec_json)?;
+
+    std::fs::create_dir_all(&out_dir)
+        .map_err(|e| anyhow!("Failed to create {}: {e}", out_dir.display()))?;
+    for (file_name, contents) in [
+        ("openapi.json", spec_json),
+        ("api-client.ts", typescript::client(&spec_value)),
+        ("schema.graphql", crate::graphql::schema().sdl()),
+    ] {
+        let path = out_dir.join(file_name);
+        std::fs::write(&path, contents)
+            .map_err(|e| anyhow!("Failed to write {}: {e}", path.display()))?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
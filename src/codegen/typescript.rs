@@ -0,0 +1,439 @@
+//! TypeScript client emitter.
+//!
+//! Works on the serialized spec (OpenAPI 3.1 as produced by utoipa), so it
+//! sees exactly what `/api/openapi.json` serves. Every component schema
+//! becomes an exported type with the Rust field names, and every operation a
+//! method on `ApiClient`. Handlers that answer through `http_resp` document
+//! their `data` type only, so JSON responses are wrapped in `ApiResponse<T>`
+//! unless listed in [`UNWRAPPED_OPERATIONS`]; non-JSON responses resolve to
+//! the raw `Response`. Error bodies (`CodeErrorResp`) reject as `ApiError`.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+
+use serde_json::Value;
+
+/// Operations whose handlers return `Json` directly instead of `http_resp`.
+const UNWRAPPED_OPERATIONS: &[&str] = &["healthcheck", "readyz"];
+
+const PRELUDE: &str = r#"// Generated by `rust-be-template codegen` from the OpenAPI spec. Do not edit.
+
+export interface ResponseMeta<M = null> {
+  time_to_process: string;
+  timestamp: string;
+  metadata: M;
+}
+
+/** Success envelope written by `http_resp`. */
+export interface ApiResponse<D, M = null> {
+  success: true;
+  data: D;
+  meta: ResponseMeta<M>;
+}
+
+/** Error envelope (`CodeErrorResp`). */
+export interface ApiErrorBody {
+  success: false;
+  error_code: number;
+  message: string;
+}
+
+export class ApiError extends Error {
+  constructor(
+    public readonly status: number,
+    public readonly body: ApiErrorBody | null,
+  ) {
+    super(body?.message ?? `HTTP ${status}`);
+  }
+}
+
+export interface ClientOptions {
+  /** Origin of the API; empty for same-origin requests. */
+  baseUrl?: string;
+  fetch?: typeof fetch;
+  headers?: Record<string, string>;
+}
+
+type Query = Record<string, string | number | boolean | null | undefined>;
+"#;
+
+const CLIENT_PRELUDE: &str = r#"
+export class ApiClient {
+  constructor(private readonly options: ClientOptions = {}) {}
+
+  private async send(method: string, path: string, query?: Query, body?: unknown): Promise<Response> {
+    let url = (this.options.baseUrl ?? "") + path;
+    if (query) {
+      const params = new URLSearchParams();
+      for (const [key, value] of Object.entries(query)) {
+        if (value !== undefined && value !== null) params.append(key, String(value));
+      }
+      const search = params.toString();
+      if (search) url += `?${search}`;
+    }
+    const headers: Record<string, string> = { ...this.options.headers };
+    let payload: BodyInit | undefined;
+    if (body instanceof FormData) {
+      payload = body;
+    } else if (body !== undefined) {
+      headers["content-type"] = "application/json";
+      payload = JSON.stringify(body);
+    }
+    const response = await (this.options.fetch ?? fetch)(url, {
+      method,
+      headers,
+      body: payload,
+      credentials: "include",
+    });
+    if (!response.ok) {
+      const error = await response.json().catch(() => null);
+      throw new ApiError(response.status, error as ApiErrorBody | null);
+    }
+    return response;
+  }
+
+  private async json<T>(method: string, path: string, query?: Query, body?: unknown): Promise<T> {
+    const response = await this.send(method, path, query, body);
+    return (await response.json()) as T;
+  }
+"#;
+
+/// The whole `api-client.ts` for `spec`.
+pub fn client(spec: &Value) -> String {
+    let mut out = String::from(PRELUDE);
+
+    let schemas = spec
+        .pointer("/components/schemas")
+        .and_then(Value::as_object);
+    for (name, schema) in schemas.into_iter().flatten() {
+        out.push('\n');
+        write_doc(&mut out, schema, "");
+        let name = type_name(name);
+        match object_body(schema, "") {
+            Some(body) => {
+                let _ = writeln!(out, "export interface {name} {body}");
+            }
+            None => {
+                let _ = writeln!(out, "export type {name} = {};", ts_type(schema));
+            }
+        }
+    }
+
+    out.push_str(CLIENT_PRELUDE);
+    let mut seen = HashSet::new();
+    let paths = spec.get("paths").and_then(Value::as_object);
+    for (path, item) in paths.into_iter().flatten() {
+        for (method, operation) in item.as_object().into_iter().flatten() {
+            if matches!(method.as_str(), "get" | "post" | "put" | "patch" | "delete") {
+                write_operation(&mut out, path, method, operation, &mut seen);
+            }
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn write_operation(
+    out: &mut String,
+    path: &str,
+    method: &str,
+    operation: &Value,
+    seen: &mut HashSet<String>,
+) {
+    let operation_id = operation
+        .get("operationId")
+        .and_then(Value::as_str)
+        .unwrap_or(path);
+    let mut name = camel_case(operation_id);
+    if !seen.insert(name.clone()) {
+        name = format!("{name}{}", camel_case(&format!("_{method}")));
+        seen.insert(name.clone());
+    }
+
+    let mut args = Vec::new();
+    let mut url = path.to_string();
+    let mut query_fields = BTreeMap::new();
+    let mut query_required = false;
+    for param in operation
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let Some(param_name) = param.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        let param_type = param.get("schema").map_or("unknown".to_string(), ts_type);
+        let required = param.get("required").and_then(Value::as_bool) == Some(true);
+        match param.get("in").and_then(Value::as_str) {
+            Some("path") => {
+                let arg = identifier(param_name);
+                url = url.replace(
+                    &format!("{{{param_name}}}"),
+                    &format!("${{encodeURIComponent(String({arg}))}}"),
+                );
+                args.push(format!("{arg}: {param_type}"));
+            }
+            Some("query") => {
+                query_required |= required;
+                let optional = if required { "" } else { "?" };
+                query_fields.insert(
+                    param_name.to_string(),
+                    format!("{}{optional}: {param_type}", property_name(param_name)),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let body = match operation.pointer("/requestBody/content") {
+        Some(content) if content.get("multipart/form-data").is_some() => {
+            args.push("body: FormData".to_string());
+            "body"
+        }
+        Some(content) => {
+            let body_type = content
+                .get("application/json")
+                .and_then(|media| media.get("schema"))
+                .map_or("unknown".to_string(), ts_type);
+            args.push(format!("body: {body_type}"));
+            "body"
+        }
+        None => "undefined",
+    };
+    // Last, since it may be optional.
+    let query = if query_fields.is_empty() {
+        "undefined"
+    } else {
+        let fields: Vec<_> = query_fields.into_values().collect();
+        let optional = if query_required { "" } else { "?" };
+        args.push(format!("query{optional}: {{ {} }}", fields.join("; ")));
+        "query"
+    };
+
+    let success = ["200", "201", "202"]
+        .iter()
+        .find_map(|status| operation.pointer(&format!("/responses/{status}")));
+    let json_schema = success.and_then(|response| match response.get("content") {
+        Some(content) => content
+            .get("application/json")
+            .map(|media| media.get("schema").map_or("unknown".to_string(), ts_type)),
+        None => Some("null".to_string()),
+    });
+
+    out.push('\n');
+    if let Some(summary) = operation
+        .get("summary")
+        .or_else(|| operation.get("description"))
+        .and_then(Value::as_str)
+    {
+        write_comment(out, summary, "  ");
+    }
+    let url = format!("`{url}`");
+    let method = method.to_ascii_uppercase();
+    let args = args.join(", ");
+    let call = match json_schema {
+        Some(data) if UNWRAPPED_OPERATIONS.contains(&operation_id) => format!(
+            "{name}({args}): Promise<{data}> {{\n    return this.json(\"{method}\", {url}, {query}, {body});\n  }}"
+        ),
+        Some(data) => format!(
+            "{name}({args}): Promise<ApiResponse<{data}>> {{\n    return this.json(\"{method}\", {url}, {query}, {body});\n  }}"
+        ),
+        None => format!(
+            "{name}({args}): Promise<Response> {{\n    return this.send(\"{method}\", {url}, {query}, {body});\n  }}"
+        ),
+    };
+    let _ = writeln!(out, "  {call}");
+}
+
+/// `{ field: T; ... }` for a schema with properties, else `None`.
+fn object_body(schema: &Value, indent: &str) -> Option<String> {
+    let properties = schema.get("properties")?.as_object()?;
+    let required: HashSet<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+
+    let inner = format!("{indent}  ");
+    let mut body = String::from("{\n");
+    for (name, property) in properties {
+        write_doc(&mut body, property, &inner);
+        let optional = if required.contains(name.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        let property_type = object_body(property, &inner).unwrap_or_else(|| ts_type(property));
+        let _ = writeln!(
+            body,
+            "{inner}{}{optional}: {property_type};",
+            property_name(name)
+        );
+    }
+    body.push_str(indent);
+    body.push('}');
+    Some(body)
+}
+
+/// TypeScript for a JSON schema.
+fn ts_type(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .map_or("unknown".to_string(), type_name);
+    }
+    for (key, separator) in [("oneOf", " | "), ("anyOf", " | "), ("allOf", " & ")] {
+        if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+            let parts: Vec<String> = variants
+                .iter()
+                .map(|variant| {
+                    let variant_type = object_body(variant, "").unwrap_or_else(|| ts_type(variant));
+                    format!("({variant_type})")
+                })
+                .collect();
+            return parts.join(separator);
+        }
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(single)) => vec![single.as_str()],
+        Some(Value::Array(many)) => many.iter().filter_map(Value::as_str).collect(),
+        _ => return "unknown".to_string(),
+    };
+    let parts: Vec<String> = types
+        .into_iter()
+        .map(|kind| match kind {
+            "string" => "string".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => {
+                let item = schema.get("items").map_or("unknown".to_string(), ts_type);
+                format!("Array<{item}>")
+            }
+            "object" => match schema.get("additionalProperties") {
+                _ if schema.get("properties").is_some() => {
+                    object_body(schema, "").unwrap_or_default()
+                }
+                Some(value @ Value::Object(_)) => format!("Record<string, {}>", ts_type(value)),
+                _ => "Record<string, unknown>".to_string(),
+            },
+            _ => "unknown".to_string(),
+        })
+        .collect();
+    parts.join(" | ")
+}
+
+fn write_doc(out: &mut String, schema: &Value, indent: &str) {
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        write_comment(out, description, indent);
+    }
+}
+
+fn write_comment(out: &mut String, text: &str, indent: &str) {
+    let text = text.trim().replace("*/", "*\\/");
+    if text.contains('\n') {
+        let _ = writeln!(out, "{indent}/**");
+        for line in text.lines() {
+            let _ = writeln!(out, "{indent} * {line}");
+        }
+        let _ = writeln!(out, "{indent} */");
+    } else {
+        let _ = writeln!(out, "{indent}/** {text} */");
+    }
+}
+
+/// Schema names may carry generic arguments (`Response_String`, `Foo<Bar>`).
+fn type_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn property_name(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        format!("{name:?}")
+    }
+}
+
+fn identifier(name: &str) -> String {
+    let name = type_name(name);
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// `get_posts` -> `getPosts`.
+fn camel_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            upper = !out.is_empty();
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn maps_schemas_to_typescript() {
+        assert_eq!(
+            ts_type(&json!({"type": ["string", "null"]})),
+            "string | null"
+        );
+        assert_eq!(
+            ts_type(&json!({"type": "array", "items": {"$ref": "#/components/schemas/Post"}})),
+            "Array<Post>"
+        );
+        assert_eq!(ts_type(&json!({"enum": ["a", "b"]})), "\"a\" | \"b\"");
+        assert_eq!(
+            object_body(
+                &json!({"properties": {"id": {"type": "integer"}, "title": {"type": "string"}}, "required": ["id"]}),
+                ""
+            )
+            .as_deref(),
+            Some("{\n  id: number;\n  title?: string;\n}")
+        );
+    }
+
+    #[test]
+    fn camel_cases_operation_ids() {
+        assert_eq!(camel_case("get_posts"), "getPosts");
+        assert_eq!(camel_case("/api/site"), "apiSite");
+    }
+}
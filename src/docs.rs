@@ -3,49 +3,113 @@
 //! Important: Utoipa only exposes operations you list in `#[openapi(paths(...))]`.
 //! Handler functions still need their own `#[utoipa::path(...)]` attributes.
 
-use utoipa::OpenApi;
+use utoipa::{Modify, OpenApi};
+
+use crate::routers::main_router::{API_ALIAS_PREFIX, API_V1_PREFIX};
 
 // ---- handlers (for `paths(...)`) ----
 use crate::handlers::{
-    admin::sync_i18n_cache,
+    admin::{
+        access_log, admin_audit, alert_rules, db_pool, email_outbox, email_preview,
+        email_suppressions, endpoint_stats, jobs, log_files, logging, sent_emails, session_purges,
+        session_stats, stats, storage_orphans, sync_i18n_cache, tasks, tls, trash, webhooks,
+    },
+    album::{
+        create_album, delete_album, get_album, get_albums, set_album_photographs, update_album,
+    },
     auth::{
         check_if_user_exists, is_superuser, login, logout, me, reset_password,
         reset_password_request, signup, verify_user_email,
     },
     blog::{
-        delete_comment, delete_post, get_posts, read_post, rescind_comment_vote, rescind_post_vote,
-        submit_comment, submit_post, update_comment, update_post, vote_comment, vote_post,
+        delete_comment, delete_post, get_posts, list_comments, live_events, read_post,
+        rescind_comment_vote, rescind_post_vote, submit_comment, submit_post, update_comment,
+        update_post, vote_comment, vote_post,
     },
     countries::{
         get_countries, get_country, get_language, get_languages, get_subdivisions_for_country,
     },
+    email::{ses_feedback, unsubscribe},
     geo_ip::lookup_ip,
     i18n::get_ui_text_bundle,
+    notifications::{list_notifications, mark_notifications_read, notification_preferences},
     photography::{
-        batch_list, batch_status, batch_upload, delete_photograph_comment, delete_photographs,
-        get_photographs, read_photograph, rescind_photograph_comment_vote, rescind_photograph_vote,
+        batch_list, batch_status, batch_upload, confirm_photograph_upload,
+        delete_photograph_comment, delete_photographs, download_photograph_original,
+        get_photograph_duplicates, get_photograph_processing, get_photograph_variant,
+        get_photographs, get_photographs_in_bounds, presign_photograph_upload, read_photograph,
+        rescind_photograph_comment_vote, rescind_photograph_vote, set_photograph_tags,
         submit_photograph_comment, update_photograph_comment, upload_photograph, vote_photograph,
         vote_photograph_comment,
     },
-    server::{get_host_fastfetch, healthcheck, lookup_ip_loc, root, visitor_board},
+    server::{
+        get_host_fastfetch, healthcheck, lookup_ip_loc, metrics, probes, root, site, visitor_board,
+    },
+    upload::get_upload_progress,
     user::{get_user_info, upload_profile_picture},
 };
 
 // ---- schemas (for `components(schemas(...))`) ----
 use crate::domain::{
+    access_log::access_log::AccessLogItem,
+    album::album::Album,
+    alert::alert_rule::{AlertMetric, AlertRuleItem},
+    audit::audit::AdminAuditItem,
     auth::user::{User, UserInfo, UserProfilePicture},
     blog::blog::{
         Comment, CommentResponse, Post, PostInfo, PostInfoWithVote, Tag, UserBadgeInfo, VoteState,
     },
+    blog::live_event::LiveEvent,
     country::{
         CountryAndSubdivisions, IsoCountry, IsoCountrySubdivision, IsoCurrency, IsoLanguage,
     },
+    email::email_outbox::{EmailOutboxItem, EmailOutboxStatus},
+    email::feedback::EmailSuppression,
+    job::delayed_task::{DelayedTaskItem, DelayedTaskStatus},
+    job::execution::{JobExecution, JobOutcome},
+    notification::notification::{
+        CommentLocation, Notification, NotificationEnvelope, NotificationItem,
+    },
+    notification::preferences::NotificationPreferences,
     photography::batch::status::ProcessingStatus,
-    photography::photographs::Photograph,
+    photography::photographs::{Photograph, PhotographProcessingStatus, PhotographRendition},
     photography::social::{PhotographComment, PhotographCommentResponse},
+    soft_delete::soft_delete::SoftDeleteKind,
+    stats::dashboard::{DailyStat, DashboardStats, DashboardTotals, RangeTotals, StatsRange},
+    upload_progress::{UploadKind, UploadProgress, UploadStage},
+    webhook::delivery::{WebhookDeliveryItem, WebhookDeliveryStatus},
+    webhook::webhook::{WebhookEventType, WebhookItem},
 };
 use crate::dto::{
     requests::{
+        admin::{
+            admin_stats_request::AdminStatsRequest,
+            alert_rule_request::{CreateAlertRuleRequest, UpdateAlertRuleRequest},
+            email_preview_request::EmailPreviewRequest,
+            endpoint_stats_request::EndpointStatsRequest,
+            list_access_log_request::ListAccessLogRequest,
+            list_admin_audit_request::ListAdminAuditRequest,
+            list_email_outbox_request::ListEmailOutboxRequest,
+            list_email_suppressions_request::{
+                CreateEmailSuppressionRequest, ListEmailSuppressionsRequest,
+            },
+            list_jobs_request::ListJobsRequest,
+            list_sent_emails_request::ListSentEmailsRequest,
+            list_session_purges_request::ListSessionPurgesRequest,
+            list_tasks_request::ListTasksRequest,
+            log_file_request::DownloadLogFileRequest,
+            scan_storage_orphans_request::ScanStorageOrphansRequest,
+            session_stats_request::SessionStatsRequest,
+            update_logging_request::UpdateLoggingRequest,
+            webhook_request::{
+                CreateWebhookRequest, ListWebhookDeliveriesRequest, UpdateWebhookRequest,
+            },
+        },
+        album::{
+            create_album_request::CreateAlbumRequest,
+            set_album_photographs_request::SetAlbumPhotographsRequest,
+            update_album_request::UpdateAlbumRequest,
+        },
         auth::{
             check_if_user_exists_request::CheckIfUserExistsRequest, login_request::LoginRequest,
             reset_password::ResetPasswordProcessRequest,
@@ -58,14 +122,68 @@ use crate::dto::{
             update_post_request::UpdatePostRequest, upvote_comment_request::UpvoteCommentRequest,
             upvote_post_request::UpvotePostRequest,
         },
+        email::unsubscribe_request::UnsubscribeRequest,
         i18n::get_ui_text_bundle_request::GetUiTextBundleRequest,
+        notifications::{
+            list_notifications_request::ListNotificationsRequest,
+            mark_notifications_read_request::MarkNotificationsReadRequest,
+        },
+        photography::confirm_photograph_upload_request::ConfirmPhotographUploadRequest,
         photography::delete_photographs_request::DeletePhotographsRequest,
+        photography::download_photograph_original_request::{
+            DownloadPhotographOriginalRequest, OriginalDownloadMode,
+        },
+        photography::photograph_duplicates_request::PhotographDuplicatesRequest,
+        photography::photograph_variant_request::PhotographVariantRequest,
+        photography::photographs_in_bounds_request::PhotographsInBoundsRequest,
+        photography::presign_photograph_upload_request::PresignPhotographUploadRequest,
+        photography::set_photograph_tags_request::SetPhotographTagsRequest,
         photography::submit_photograph_comment_request::SubmitPhotographCommentRequest,
         photography::update_photograph_comment_request::UpdatePhotographCommentRequest,
         photography::vote_photograph_request::VotePhotographRequest,
     },
     responses::{
-        admin::sync_i18n_cache_response::SyncI18nCacheResponse,
+        admin::{
+            access_log_response::ListAccessLogResponse,
+            admin_audit_response::ListAdminAuditResponse,
+            admin_stats_response::AdminStatsResponse,
+            alert_rule_response::{
+                AlertRuleResponse, DeleteAlertRuleResponse, ListAlertRulesResponse,
+            },
+            db_pool_response::{
+                DbPoolStats, DbPoolStatsResponse, PoolCheckoutFailure, PoolWaitPercentiles,
+            },
+            email_outbox_response::{ListEmailOutboxResponse, RequeueEmailResponse},
+            email_preview_response::EmailPreviewResponse,
+            email_suppression_response::{
+                CreateEmailSuppressionResponse, DeleteEmailSuppressionResponse,
+                ListEmailSuppressionsResponse,
+            },
+            endpoint_stats_response::EndpointStatsResponse,
+            job_status_response::{
+                CancelJobResponse, JobStatusItem, ListJobsResponse, SetJobPausedResponse,
+            },
+            log_files_response::{LogFileItem, LogFilesResponse},
+            logging_response::{LayerFilters, LoggingResponse},
+            restore_response::RestoreResponse,
+            sent_email_response::{ListSentEmailsResponse, SentEmailResponse},
+            session_purge_response::{SessionPurgeHistoryResponse, SessionPurgeReport},
+            session_stats_response::{
+                SessionCountryCount, SessionEventCounts, SessionRateBucket, SessionStatsResponse,
+            },
+            storage_orphan_report::{StorageOrphan, StorageOrphanReport},
+            sync_i18n_cache_response::SyncI18nCacheResponse,
+            task_response::{ListTasksResponse, RequeueTaskResponse},
+            tls_response::TlsStatus,
+            webhook_response::{
+                DeleteWebhookResponse, ListWebhookDeliveriesResponse, ListWebhooksResponse,
+                WebhookResponse,
+            },
+        },
+        album::{
+            album_response::AlbumItem, get_album_response::GetAlbumResponse,
+            get_albums_response::GetAlbumsResponse,
+        },
         auth::{
             is_superuser_response::IsSuperuserResponse, login_response::LoginResponse,
             logout_response::LogoutResponse, me_response::MeResponse,
@@ -74,26 +192,46 @@ use crate::dto::{
         },
         blog::{
             delete_comment_response::DeleteCommentResponse,
-            delete_post_response::DeletePostResponse, get_posts::GetPostsResponse,
-            read_post_response::ReadPostResponse, submit_post_response::SubmitPostResponse,
-            vote_comment_response::VoteCommentResponse, vote_post_response::VotePostResponse,
+            delete_post_response::DeletePostResponse, read_post_response::ReadPostResponse,
+            submit_post_response::SubmitPostResponse, vote_comment_response::VoteCommentResponse,
+            vote_post_response::VotePostResponse,
+        },
+        email::{
+            ses_feedback_response::SesFeedbackResponse, unsubscribe_response::UnsubscribeResponse,
         },
         i18n::ui_text_bundle_response::UiTextBundleResponse,
+        notifications::notifications_response::{
+            ListNotificationsResponse, MarkNotificationsReadResponse,
+            NotificationPreferencesResponse,
+        },
+        pagination::PageInfo,
         photography::batch_status_response::{
             BatchItemStatus, BatchListResponse, BatchStatusResponse, BatchUploadItem,
             BatchUploadResponse,
         },
         photography::delete_photograph_comment_response::DeletePhotographCommentResponse,
-        photography::get_photograph_response::{
-            GetPhotographsResponse, PaginationMeta, PhotographItem,
+        photography::get_photograph_response::PhotographItem,
+        photography::photograph_duplicates_response::{
+            PhotographDuplicateCluster, PhotographDuplicateMember, PhotographDuplicatesResponse,
+        },
+        photography::photograph_processing_response::PhotographProcessingResponse,
+        photography::photographs_in_bounds_response::{
+            PhotographMapCluster, PhotographMapPoint, PhotographsInBoundsResponse,
         },
+        photography::presign_photograph_upload_response::PresignPhotographUploadResponse,
         photography::read_photograph_response::ReadPhotographResponse,
+        photography::set_photograph_tags_response::SetPhotographTagsResponse,
         photography::vote_photograph_response::VotePhotographResponse,
         user::public_user_info_response::PublicUserInfoResponse,
     },
 };
 use crate::errors::code_error::CodeErrorResp;
+use crate::handlers::album::delete_album::DeleteAlbumResponse;
+use crate::init::log_levels::{LogFilterSpec, LogLevel};
+use crate::util::email::transport::file::SinkEmail;
 use crate::util::geographic::ip_info_lookup::IpInfo;
+use crate::util::http::endpoint_stats::{EndpointStatsWindow, EndpointSummary, StatusClassCounts};
+use crate::util::log_files::LogCompression;
 
 /// Central OpenAPI document for Swagger UI.
 #[derive(OpenApi)]
@@ -102,7 +240,11 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
     paths(
         // --- server ---
         healthcheck::healthcheck,
+        probes::livez,
+        probes::readyz,
+        site::get_site_info,
         root::root_handler,
+        metrics::get_metrics,
         get_host_fastfetch::get_host_fastfetch,
         visitor_board::get_visitor_board_entries,
         lookup_ip_loc::lookup_ip_location,
@@ -131,6 +273,7 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
         // --- blog ---
         get_posts::get_posts,
         read_post::read_post,
+        list_comments::list_comments,
         submit_post::submit_post,
         vote_post::vote_post,
         vote_comment::vote_comment,
@@ -139,22 +282,81 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
         update_comment::update_comment,
         delete_post::delete_post,
         update_post::update_post,
+        live_events::get_live_events,
         submit_comment::submit_comment,
         rescind_comment_vote::rescind_comment_vote,
 
         // --- i18n ---
         get_ui_text_bundle::get_ui_text_bundle,
 
+        // --- email ---
+        ses_feedback::ses_feedback,
+        unsubscribe::check_unsubscribe,
+        unsubscribe::unsubscribe,
+
+        // --- notifications ---
+        list_notifications::list_notifications,
+        mark_notifications_read::mark_notifications_read,
+        mark_notifications_read::mark_notification_read,
+        notification_preferences::get_notification_preferences,
+        notification_preferences::update_notification_preferences,
+
         // --- admin ---
         sync_i18n_cache::sync_i18n_cache,
+        storage_orphans::get_storage_orphan_report,
+        storage_orphans::scan_storage_orphans,
+        jobs::list_jobs,
+        jobs::pause_job,
+        jobs::resume_job,
+        jobs::cancel_job,
+        tasks::list_tasks,
+        tasks::requeue_task,
+        email_outbox::list_email_outbox,
+        email_outbox::requeue_email,
+        email_preview::preview_email,
+        sent_emails::list_sent_emails,
+        sent_emails::get_sent_email,
+        email_suppressions::list_email_suppressions,
+        email_suppressions::create_email_suppression,
+        email_suppressions::delete_email_suppression,
+        trash::restore_soft_deleted,
+        session_purges::list_session_purges,
+        session_stats::get_session_stats,
+        db_pool::get_db_pool_stats,
+        stats::get_admin_stats,
+        endpoint_stats::get_endpoint_stats,
+        alert_rules::list_alert_rules,
+        alert_rules::create_alert_rule,
+        alert_rules::update_alert_rule,
+        alert_rules::delete_alert_rule,
+        logging::get_logging,
+        logging::update_logging,
+        log_files::get_log_files,
+        log_files::download_log_file,
+        tls::get_tls_status,
+        tls::reload_tls,
+        webhooks::list_webhooks,
+        webhooks::create_webhook,
+        webhooks::update_webhook,
+        webhooks::delete_webhook,
+        webhooks::list_webhook_deliveries,
+        admin_audit::list_admin_audit,
+        access_log::list_access_log,
 
         // --- photography ---
         get_photographs::get_photographs,
+        get_photographs_in_bounds::get_photographs_in_bounds,
         upload_photograph::upload_photograph,
+        get_photograph_processing::get_photograph_processing,
+        get_photograph_variant::get_photograph_variant,
+        get_photograph_duplicates::get_photograph_duplicates,
         delete_photographs::delete_photographs,
+        download_photograph_original::download_photograph_original,
         batch_upload::batch_upload,
         batch_status::batch_status,
         batch_list::batch_list,
+        presign_photograph_upload::presign_photograph_upload,
+        confirm_photograph_upload::confirm_photograph_upload,
         read_photograph::read_photograph,
         vote_photograph::vote_photograph,
         rescind_photograph_vote::rescind_photograph_vote,
@@ -163,6 +365,18 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
         submit_photograph_comment::submit_photograph_comment,
         update_photograph_comment::update_photograph_comment,
         delete_photograph_comment::delete_photograph_comment,
+        set_photograph_tags::set_photograph_tags,
+
+        // --- upload ---
+        get_upload_progress::get_upload_progress,
+
+        // --- album ---
+        get_albums::get_albums,
+        get_album::get_album,
+        create_album::create_album,
+        update_album::update_album,
+        delete_album::delete_album,
+        set_album_photographs::set_album_photographs,
 
         // --- user ---
         get_user_info::get_user_info,
@@ -173,6 +387,10 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
             // shared error response
             CodeErrorResp,
 
+            // shared list envelopes (`Paginated<T>`, `CursorPage<T>`) are
+            // collected from the paths that return them
+            PageInfo,
+
             // --- auth DTOs ---
             SignupRequest,
             SignupResponse,
@@ -190,7 +408,6 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
 
             // --- blog DTOs ---
             GetPostsRequest,
-            GetPostsResponse,
             ReadPostResponse,
             SubmitPostRequest,
             SubmitPostResponse,
@@ -210,18 +427,130 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
 
             // --- admin DTOs ---
             SyncI18nCacheResponse,
+            ScanStorageOrphansRequest,
+            StorageOrphanReport,
+            StorageOrphan,
+            ListJobsRequest,
+            ListJobsResponse,
+            JobStatusItem,
+            SetJobPausedResponse,
+            CancelJobResponse,
+            ListTasksRequest,
+            ListTasksResponse,
+            RequeueTaskResponse,
+            RestoreResponse,
+            SoftDeleteKind,
+            DelayedTaskItem,
+            DelayedTaskStatus,
+            ListEmailOutboxRequest,
+            ListEmailOutboxResponse,
+            RequeueEmailResponse,
+            EmailOutboxItem,
+            EmailPreviewRequest,
+            EmailPreviewResponse,
+            ListSentEmailsRequest,
+            ListSentEmailsResponse,
+            SentEmailResponse,
+            SinkEmail,
+            ListEmailSuppressionsRequest,
+            CreateEmailSuppressionRequest,
+            CreateEmailSuppressionResponse,
+            ListEmailSuppressionsResponse,
+            DeleteEmailSuppressionResponse,
+            EmailSuppression,
+            SesFeedbackResponse,
+            UnsubscribeRequest,
+            UnsubscribeResponse,
+            EmailOutboxStatus,
+            ListSessionPurgesRequest,
+            SessionPurgeHistoryResponse,
+            SessionPurgeReport,
+            SessionStatsRequest,
+            SessionStatsResponse,
+            SessionCountryCount,
+            SessionEventCounts,
+            SessionRateBucket,
+            DbPoolStatsResponse,
+            DbPoolStats,
+            PoolWaitPercentiles,
+            PoolCheckoutFailure,
+            AdminStatsRequest,
+            AdminStatsResponse,
+            DashboardStats,
+            DashboardTotals,
+            RangeTotals,
+            DailyStat,
+            StatsRange,
+            EndpointStatsRequest,
+            EndpointStatsResponse,
+            EndpointStatsWindow,
+            EndpointSummary,
+            StatusClassCounts,
+            CreateAlertRuleRequest,
+            UpdateAlertRuleRequest,
+            ListAlertRulesResponse,
+            AlertRuleResponse,
+            DeleteAlertRuleResponse,
+            AlertRuleItem,
+            AlertMetric,
+            UpdateLoggingRequest,
+            LoggingResponse,
+            LayerFilters,
+            LogFilterSpec,
+            LogLevel,
+            LogFilesResponse,
+            LogFileItem,
+            LogCompression,
+            DownloadLogFileRequest,
+            TlsStatus,
+            JobExecution,
+            JobOutcome,
+            CreateWebhookRequest,
+            UpdateWebhookRequest,
+            ListWebhookDeliveriesRequest,
+            ListWebhooksResponse,
+            WebhookResponse,
+            DeleteWebhookResponse,
+            ListWebhookDeliveriesResponse,
+            WebhookItem,
+            WebhookEventType,
+            WebhookDeliveryItem,
+            WebhookDeliveryStatus,
+            ListAdminAuditRequest,
+            ListAdminAuditResponse,
+            AdminAuditItem,
+            ListAccessLogRequest,
+            ListAccessLogResponse,
+            AccessLogItem,
 
             // --- photography DTOs ---
-            GetPhotographsResponse,
             PhotographItem,
-            PaginationMeta,
             DeletePhotographsRequest,
+            PhotographsInBoundsRequest,
+            PhotographsInBoundsResponse,
+            PhotographMapPoint,
+            PhotographMapCluster,
             BatchUploadResponse,
             BatchUploadItem,
             BatchStatusResponse,
             BatchItemStatus,
             BatchListResponse,
             ProcessingStatus,
+            PhotographProcessingResponse,
+            PhotographProcessingStatus,
+            PhotographRendition,
+            DownloadPhotographOriginalRequest,
+            OriginalDownloadMode,
+            PhotographDuplicatesRequest,
+            PhotographVariantRequest,
+            PhotographDuplicatesResponse,
+            PhotographDuplicateCluster,
+            PhotographDuplicateMember,
+            PresignPhotographUploadRequest,
+            PresignPhotographUploadResponse,
+            ConfirmPhotographUploadRequest,
+            SetPhotographTagsRequest,
+            SetPhotographTagsResponse,
             VotePhotographRequest,
             VotePhotographResponse,
             SubmitPhotographCommentRequest,
@@ -231,6 +560,20 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
             PhotographComment,
             PhotographCommentResponse,
 
+            // --- upload DTOs ---
+            UploadProgress,
+            UploadStage,
+            UploadKind,
+
+            // --- album DTOs ---
+            AlbumItem,
+            GetAlbumsResponse,
+            GetAlbumResponse,
+            CreateAlbumRequest,
+            UpdateAlbumRequest,
+            SetAlbumPhotographsRequest,
+            DeleteAlbumResponse,
+
             // --- domain models used in responses ---
             PublicUserInfoResponse,
 
@@ -254,8 +597,20 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
             Tag,
             UserBadgeInfo,
             VoteState,
+            LiveEvent,
+            NotificationEnvelope,
+            Notification,
+            CommentLocation,
+            NotificationItem,
+            NotificationPreferences,
+            ListNotificationsRequest,
+            ListNotificationsResponse,
+            MarkNotificationsReadRequest,
+            MarkNotificationsReadResponse,
+            NotificationPreferencesResponse,
 
             Photograph,
+            Album,
         )
     ),
     tags(
@@ -265,9 +620,31 @@ use crate::util::geographic::ip_info_lookup::IpInfo;
         (name = "auth", description = "Authentication endpoints"),
         (name = "blog", description = "Blog endpoints"),
         (name = "i18n", description = "Internationalization endpoints"),
+        (name = "email", description = "Email delivery feedback and unsubscribe endpoints"),
+        (name = "notifications", description = "In-app notification endpoints"),
         (name = "admin", description = "Admin endpoints"),
         (name = "photography", description = "Photography endpoints"),
+        (name = "album", description = "Photo album endpoints"),
+        (name = "upload", description = "Upload progress endpoints"),
         (name = "user", description = "User endpoints")
-    )
+    ),
+    modifiers(&ApiV1Paths)
 )]
 pub struct ApiDoc;
+
+/// Documents API paths under the canonical `/api/v1` prefix. Handlers'
+/// `#[utoipa::path]` attributes keep the shorter `/api` alias.
+struct ApiV1Paths;
+
+impl Modify for ApiV1Paths {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let paths = std::mem::take(&mut openapi.paths.paths);
+        openapi.paths.paths = paths
+            .into_iter()
+            .map(|(path, item)| match path.strip_prefix(API_ALIAS_PREFIX) {
+                Some(rest) if rest.starts_with('/') => (format!("{API_V1_PREFIX}{rest}"), item),
+                _ => (path, item),
+            })
+            .collect();
+    }
+}
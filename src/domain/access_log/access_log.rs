@@ -0,0 +1,83 @@
+//! Sampled request log (`access_log`). `log_middleware` keeps
+//! `ACCESS_LOG_SAMPLE_RATE` of completed API requests in
+//! `ServerState::access_log_buffer`; the FLUSH_ACCESS_LOG job writes them in
+//! one batch per minute.
+
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::access_log;
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = access_log)]
+pub struct AccessLogInsertable {
+    pub access_log_method: String,
+    pub access_log_path: String,
+    pub access_log_status: i16,
+    pub access_log_latency_ms: f64,
+    pub access_log_country_code: Option<String>,
+    pub access_log_user_id: Option<Uuid>,
+    /// When the request completed, not when the batch was written.
+    pub access_log_created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = access_log)]
+pub struct AccessLog {
+    pub access_log_id: Uuid,
+    pub access_log_method: String,
+    pub access_log_path: String,
+    pub access_log_status: i16,
+    pub access_log_latency_ms: f64,
+    pub access_log_country_code: Option<String>,
+    pub access_log_user_id: Option<Uuid>,
+    pub access_log_created_at: DateTime<Utc>,
+}
+
+/// An entry as shown by `GET /api/admin/access-log`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccessLogItem {
+    pub access_log_id: Uuid,
+    pub method: String,
+    pub path: String,
+    pub status: i16,
+    pub latency_ms: f64,
+    /// ISO 3166-1 alpha-2 code from the geo-IP tables.
+    pub country_code: Option<String>,
+    /// Null for anonymous requests and once the account is deleted.
+    pub user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AccessLog> for AccessLogItem {
+    fn from(entry: AccessLog) -> Self {
+        Self {
+            access_log_id: entry.access_log_id,
+            method: entry.access_log_method,
+            path: entry.access_log_path,
+            status: entry.access_log_status,
+            latency_ms: entry.access_log_latency_ms,
+            country_code: entry.access_log_country_code,
+            user_id: entry.access_log_user_id,
+            created_at: entry.access_log_created_at,
+        }
+    }
+}
+
+/// Filters for `ServerState::list_access_log`; `None` matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct AccessLogFilter {
+    pub path_prefix: Option<String>,
+    pub method: Option<String>,
+    /// Exact status, or with `status_class` a range (`5` for 5xx).
+    pub status: Option<i16>,
+    pub status_class: Option<i16>,
+    pub country_code: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries strictly older than this.
+    pub before: Option<DateTime<Utc>>,
+}
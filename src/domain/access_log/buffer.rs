@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::sync::Mutex;
+
+use super::access_log::AccessLogInsertable;
+
+/// Sampled requests awaiting the next flush. Bounded: once `capacity`
+/// entries are pending, further ones are counted and dropped.
+pub struct AccessLogBuffer {
+    entries: Mutex<Vec<AccessLogInsertable>>,
+    capacity: usize,
+    dropped: AtomicU64,
+}
+
+impl AccessLogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+            capacity,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn push(&self, entry: AccessLogInsertable) {
+        let mut entries = self.entries.lock().await;
+        if entries.len() >= self.capacity {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        entries.push(entry);
+    }
+
+    /// Everything pending, plus how many entries were dropped since the last
+    /// drain.
+    pub async fn drain(&self) -> (Vec<AccessLogInsertable>, u64) {
+        let entries = std::mem::take(&mut *self.entries.lock().await);
+        (entries, self.dropped.swap(0, Ordering::Relaxed))
+    }
+
+    /// Put back entries a failed flush took, ahead of newer ones, as far as
+    /// the capacity allows.
+    pub async fn requeue(&self, mut failed: Vec<AccessLogInsertable>) {
+        let mut entries = self.entries.lock().await;
+        let room = self.capacity.saturating_sub(entries.len());
+        if failed.len() > room {
+            let excess = failed.len() - room;
+            self.dropped.fetch_add(excess as u64, Ordering::Relaxed);
+            failed.truncate(room);
+        }
+        failed.append(&mut entries);
+        *entries = failed;
+    }
+}
@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+pub mod access_log;
+pub mod buffer;
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    AsChangeset, ExpressionMethods, Insertable, QueryDsl, Queryable, QueryableByName, Selectable,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    domain::photography::photographs::{Photograph, PhotographProcessingStatus},
+    schema::{albums, photographs},
+};
+
+#[derive(Clone, Serialize, Deserialize, QueryableByName, Queryable, Selectable, ToSchema)]
+#[diesel(table_name = albums)]
+pub struct Album {
+    pub album_id: Uuid,
+    pub user_id: Uuid,
+    pub album_title: String,
+    pub album_description: String,
+    pub album_cover_photograph_id: Option<Uuid>,
+    pub album_sort_order: i32,
+    pub album_created_at: DateTime<Utc>,
+    pub album_updated_at: DateTime<Utc>,
+}
+
+impl Album {
+    /// Photographs in this album, in album order.
+    pub async fn photographs(
+        conn: &mut AsyncPgConnection,
+        album_id: Uuid,
+    ) -> Result<Vec<Photograph>, diesel::result::Error> {
+        photographs::table
+            .filter(photographs::album_id.eq(album_id))
+            .filter(photographs::photograph_processing_status.eq(PhotographProcessingStatus::Ready))
+            .filter(photographs::photograph_deleted_at.is_null())
+            .order((
+                photographs::photograph_album_position.asc(),
+                photographs::photograph_id.asc(),
+            ))
+            .load::<Photograph>(conn)
+            .await
+    }
+
+    /// Thumbnail links of the given albums' cover photographs, keyed by
+    /// photograph id.
+    pub async fn cover_thumbnail_links(
+        conn: &mut AsyncPgConnection,
+        albums: &[Album],
+    ) -> Result<HashMap<Uuid, String>, diesel::result::Error> {
+        let cover_ids: Vec<Uuid> = albums
+            .iter()
+            .filter_map(|a| a.album_cover_photograph_id)
+            .collect();
+
+        if cover_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(Uuid, String)> = photographs::table
+            .filter(photographs::photograph_id.eq_any(cover_ids))
+            .filter(photographs::photograph_deleted_at.is_null())
+            .select((
+                photographs::photograph_id,
+                photographs::photograph_thumbnail_link,
+            ))
+            .load(conn)
+            .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Number of photographs in each of the given albums, keyed by album id.
+    /// Empty albums are absent from the map.
+    pub async fn photograph_counts(
+        conn: &mut AsyncPgConnection,
+        album_ids: Vec<Uuid>,
+    ) -> Result<HashMap<Uuid, i64>, diesel::result::Error> {
+        if album_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(Option<Uuid>, i64)> = photographs::table
+            .filter(photographs::album_id.eq_any(album_ids))
+            .filter(photographs::photograph_deleted_at.is_null())
+            .group_by(photographs::album_id)
+            .select((photographs::album_id, diesel::dsl::count_star()))
+            .load(conn)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(album_id, count)| album_id.map(|id| (id, count)))
+            .collect())
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = albums)]
+pub struct AlbumInsertable {
+    pub user_id: Uuid,
+    pub album_title: String,
+    pub album_description: String,
+    pub album_cover_photograph_id: Option<Uuid>,
+    pub album_sort_order: i32,
+}
+
+/// `album_cover_photograph_id` is doubly optional: `None` leaves the cover
+/// untouched, `Some(None)` clears it.
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = albums)]
+pub struct AlbumChangeset {
+    pub album_title: Option<String>,
+    pub album_description: Option<String>,
+    pub album_cover_photograph_id: Option<Option<Uuid>>,
+    pub album_sort_order: Option<i32>,
+    pub album_updated_at: Option<DateTime<Utc>>,
+}
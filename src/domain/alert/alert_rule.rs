@@ -0,0 +1,290 @@
+//! Alert rules over the in-memory endpoint stats (`alert_rules`), evaluated
+//! every minute by `jobs::monitoring::evaluate_alert_rules`.
+
+use chrono::{DateTime, Utc};
+use diesel::{AsChangeset, Insertable, Queryable, Selectable};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    schema::alert_rules,
+    util::http::endpoint_stats::{EndpointStatsWindow, EndpointSummary},
+};
+
+/// Most breaching endpoints one alert lists.
+pub const MAX_BREACHES_PER_ALERT: usize = 10;
+
+/// What a rule compares against its threshold. Latencies are milliseconds,
+/// rates shares from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    P50Ms,
+    P95Ms,
+    P99Ms,
+    MeanMs,
+    ClientErrorRate,
+    ServerErrorRate,
+}
+
+impl AlertMetric {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::P50Ms => "p50_ms",
+            Self::P95Ms => "p95_ms",
+            Self::P99Ms => "p99_ms",
+            Self::MeanMs => "mean_ms",
+            Self::ClientErrorRate => "client_error_rate",
+            Self::ServerErrorRate => "server_error_rate",
+        }
+    }
+
+    /// Inverse of [`Self::as_str`].
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "p50_ms" => Some(Self::P50Ms),
+            "p95_ms" => Some(Self::P95Ms),
+            "p99_ms" => Some(Self::P99Ms),
+            "mean_ms" => Some(Self::MeanMs),
+            "client_error_rate" => Some(Self::ClientErrorRate),
+            "server_error_rate" => Some(Self::ServerErrorRate),
+            _ => None,
+        }
+    }
+
+    pub fn is_rate(self) -> bool {
+        matches!(self, Self::ClientErrorRate | Self::ServerErrorRate)
+    }
+
+    fn value(self, summary: &EndpointSummary) -> f64 {
+        match self {
+            Self::P50Ms => summary.p50_ms,
+            Self::P95Ms => summary.p95_ms,
+            Self::P99Ms => summary.p99_ms,
+            Self::MeanMs => summary.mean_ms,
+            Self::ClientErrorRate => summary.client_error_rate,
+            Self::ServerErrorRate => summary.server_error_rate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = alert_rules)]
+pub struct AlertRule {
+    pub alert_rule_id: Uuid,
+    pub alert_rule_name: String,
+    pub alert_rule_metric: String,
+    pub alert_rule_threshold: f64,
+    pub alert_rule_window: String,
+    pub alert_rule_method: Option<String>,
+    pub alert_rule_route: Option<String>,
+    pub alert_rule_min_requests: i32,
+    pub alert_rule_cooldown_minutes: i32,
+    pub alert_rule_emails: Vec<String>,
+    pub alert_rule_webhook_url: Option<String>,
+    pub alert_rule_enabled: bool,
+    pub alert_rule_last_fired_at: Option<DateTime<Utc>>,
+    pub alert_rule_created_at: DateTime<Utc>,
+    pub alert_rule_updated_at: DateTime<Utc>,
+}
+
+/// One endpoint over a rule's threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertBreach {
+    pub method: String,
+    pub route: String,
+    pub requests: u64,
+    pub value: f64,
+}
+
+impl AlertRule {
+    pub fn metric(&self) -> Option<AlertMetric> {
+        AlertMetric::parse(&self.alert_rule_metric)
+    }
+
+    pub fn window(&self) -> Option<EndpointStatsWindow> {
+        EndpointStatsWindow::parse(&self.alert_rule_window)
+    }
+
+    /// Endpoints in `summaries` (one window's) that match the rule's method
+    /// and route, saw at least `min_requests` and are over the threshold,
+    /// worst first.
+    pub fn breaches(&self, summaries: &[EndpointSummary]) -> Vec<AlertBreach> {
+        let Some(metric) = self.metric() else {
+            return Vec::new();
+        };
+        let min_requests = self.alert_rule_min_requests.max(1) as u64;
+        let mut breaches: Vec<AlertBreach> = summaries
+            .iter()
+            .filter(|s| {
+                self.alert_rule_method
+                    .as_deref()
+                    .is_none_or(|method| method == s.method)
+            })
+            .filter(|s| {
+                self.alert_rule_route
+                    .as_deref()
+                    .is_none_or(|route| route == s.route)
+            })
+            .filter(|s| s.requests >= min_requests)
+            .map(|s| AlertBreach {
+                method: s.method.clone(),
+                route: s.route.clone(),
+                requests: s.requests,
+                value: metric.value(s),
+            })
+            .filter(|breach| breach.value > self.alert_rule_threshold)
+            .collect();
+        breaches.sort_by(|a, b| b.value.total_cmp(&a.value));
+        breaches
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = alert_rules)]
+pub struct AlertRuleInsertable {
+    pub alert_rule_name: String,
+    pub alert_rule_metric: String,
+    pub alert_rule_threshold: f64,
+    pub alert_rule_window: String,
+    pub alert_rule_method: Option<String>,
+    pub alert_rule_route: Option<String>,
+    pub alert_rule_min_requests: i32,
+    pub alert_rule_cooldown_minutes: i32,
+    pub alert_rule_emails: Vec<String>,
+    pub alert_rule_webhook_url: Option<String>,
+    pub alert_rule_enabled: bool,
+}
+
+/// `Some(None)` clears a nullable column.
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = alert_rules)]
+pub struct AlertRuleChangeset {
+    pub alert_rule_name: Option<String>,
+    pub alert_rule_metric: Option<String>,
+    pub alert_rule_threshold: Option<f64>,
+    pub alert_rule_window: Option<String>,
+    pub alert_rule_method: Option<Option<String>>,
+    pub alert_rule_route: Option<Option<String>>,
+    pub alert_rule_min_requests: Option<i32>,
+    pub alert_rule_cooldown_minutes: Option<i32>,
+    pub alert_rule_emails: Option<Vec<String>>,
+    pub alert_rule_webhook_url: Option<Option<String>>,
+    pub alert_rule_enabled: Option<bool>,
+    pub alert_rule_updated_at: Option<DateTime<Utc>>,
+}
+
+/// A rule as shown by the admin API.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AlertRuleItem {
+    pub alert_rule_id: Uuid,
+    pub name: String,
+    pub metric: String,
+    pub threshold: f64,
+    /// `1m`, `5m`, `15m` or `1h`.
+    pub window: String,
+    /// Only this method; every method when unset.
+    pub method: Option<String>,
+    /// Only this route template; every route when unset.
+    pub route: Option<String>,
+    pub min_requests: i32,
+    pub cooldown_minutes: i32,
+    pub emails: Vec<String>,
+    pub webhook_url: Option<String>,
+    pub enabled: bool,
+    pub last_fired_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AlertRule> for AlertRuleItem {
+    fn from(row: AlertRule) -> Self {
+        Self {
+            alert_rule_id: row.alert_rule_id,
+            name: row.alert_rule_name,
+            metric: row.alert_rule_metric,
+            threshold: row.alert_rule_threshold,
+            window: row.alert_rule_window,
+            method: row.alert_rule_method,
+            route: row.alert_rule_route,
+            min_requests: row.alert_rule_min_requests,
+            cooldown_minutes: row.alert_rule_cooldown_minutes,
+            emails: row.alert_rule_emails,
+            webhook_url: row.alert_rule_webhook_url,
+            enabled: row.alert_rule_enabled,
+            last_fired_at: row.alert_rule_last_fired_at,
+            created_at: row.alert_rule_created_at,
+            updated_at: row.alert_rule_updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::http::endpoint_stats::StatusClassCounts;
+
+    fn summary(method: &str, route: &str, requests: u64, p95_ms: f64) -> EndpointSummary {
+        EndpointSummary {
+            method: method.to_string(),
+            route: route.to_string(),
+            requests,
+            p50_ms: 0.0,
+            p95_ms,
+            p99_ms: 0.0,
+            mean_ms: 0.0,
+            max_ms: 0.0,
+            status_classes: StatusClassCounts::default(),
+            client_error_rate: 0.0,
+            server_error_rate: 0.0,
+            status_codes: Default::default(),
+        }
+    }
+
+    #[test]
+    fn breaches_respect_filters_and_min_requests() {
+        let now = Utc::now();
+        let mut rule = AlertRule {
+            alert_rule_id: Uuid::nil(),
+            alert_rule_name: "slow".to_string(),
+            alert_rule_metric: "p95_ms".to_string(),
+            alert_rule_threshold: 500.0,
+            alert_rule_window: "5m".to_string(),
+            alert_rule_method: None,
+            alert_rule_route: None,
+            alert_rule_min_requests: 10,
+            alert_rule_cooldown_minutes: 30,
+            alert_rule_emails: Vec::new(),
+            alert_rule_webhook_url: None,
+            alert_rule_enabled: true,
+            alert_rule_last_fired_at: None,
+            alert_rule_created_at: now,
+            alert_rule_updated_at: now,
+        };
+        let summaries = [
+            summary("GET", "/api/a", 100, 600.0),
+            summary("POST", "/api/b", 100, 900.0),
+            summary("GET", "/api/c", 5, 2000.0),
+            summary("GET", "/api/d", 100, 400.0),
+        ];
+
+        let routes: Vec<_> = rule
+            .breaches(&summaries)
+            .into_iter()
+            .map(|b| b.route)
+            .collect();
+        assert_eq!(routes, ["/api/b", "/api/a"]);
+
+        rule.alert_rule_method = Some("GET".to_string());
+        let routes: Vec<_> = rule
+            .breaches(&summaries)
+            .into_iter()
+            .map(|b| b.route)
+            .collect();
+        assert_eq!(routes, ["/api/a"]);
+
+        rule.alert_rule_route = Some("/api/d".to_string());
+        assert!(rule.breaches(&summaries).is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+//! Audit trail of superuser mutations (`admin_audit`), one row per action,
+//! written by the handlers through `ServerState::record_admin_action`.
+
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::admin_audit;
+
+/// What a superuser did. The stored name is `"{target_type}.{verb}"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAction {
+    I18nSync,
+    JobPause,
+    JobResume,
+    JobCancel,
+    TaskRequeue,
+    EmailRequeue,
+    /// A superuser put an address on the suppression list by hand.
+    EmailSuppress,
+    /// A superuser lifted an address's suppression.
+    EmailUnsuppress,
+    TlsReload,
+    /// A superuser changed this instance's log filters.
+    LoggingUpdate,
+    StorageOrphanScan,
+    AlertRuleCreate,
+    AlertRuleUpdate,
+    AlertRuleDelete,
+    WebhookCreate,
+    WebhookUpdate,
+    WebhookDelete,
+    PostCreate,
+    PostUpdate,
+    PostDelete,
+    PostRestore,
+    /// A superuser deleted another user's comment.
+    CommentDelete,
+    CommentRestore,
+    /// A superuser deleted another user's photograph comment.
+    PhotographCommentDelete,
+    PhotographUpload,
+    PhotographBatchUpload,
+    PhotographDelete,
+    PhotographRestore,
+    PhotographTagsSet,
+    AlbumCreate,
+    AlbumUpdate,
+    AlbumDelete,
+    AlbumPhotographsSet,
+    WasmModuleUpload,
+    WasmModuleUpdate,
+    WasmModuleAssetsUpdate,
+    WasmModuleDelete,
+}
+
+impl AdminAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::I18nSync => "i18n.sync",
+            Self::JobPause => "job.pause",
+            Self::JobResume => "job.resume",
+            Self::JobCancel => "job.cancel",
+            Self::TaskRequeue => "task.requeue",
+            Self::EmailRequeue => "email.requeue",
+            Self::EmailSuppress => "email_suppression.create",
+            Self::EmailUnsuppress => "email_suppression.delete",
+            Self::TlsReload => "tls.reload",
+            Self::LoggingUpdate => "logging.update",
+            Self::StorageOrphanScan => "storage.orphan_scan",
+            Self::AlertRuleCreate => "alert_rule.create",
+            Self::AlertRuleUpdate => "alert_rule.update",
+            Self::AlertRuleDelete => "alert_rule.delete",
+            Self::WebhookCreate => "webhook.create",
+            Self::WebhookUpdate => "webhook.update",
+            Self::WebhookDelete => "webhook.delete",
+            Self::PostCreate => "post.create",
+            Self::PostUpdate => "post.update",
+            Self::PostDelete => "post.delete",
+            Self::PostRestore => "post.restore",
+            Self::CommentDelete => "comment.delete",
+            Self::CommentRestore => "comment.restore",
+            Self::PhotographCommentDelete => "photograph_comment.delete",
+            Self::PhotographUpload => "photograph.upload",
+            Self::PhotographBatchUpload => "photograph.batch_upload",
+            Self::PhotographDelete => "photograph.delete",
+            Self::PhotographRestore => "photograph.restore",
+            Self::PhotographTagsSet => "photograph.tags_set",
+            Self::AlbumCreate => "album.create",
+            Self::AlbumUpdate => "album.update",
+            Self::AlbumDelete => "album.delete",
+            Self::AlbumPhotographsSet => "album.photographs_set",
+            Self::WasmModuleUpload => "wasm_module.upload",
+            Self::WasmModuleUpdate => "wasm_module.update",
+            Self::WasmModuleAssetsUpdate => "wasm_module.assets_update",
+            Self::WasmModuleDelete => "wasm_module.delete",
+        }
+    }
+
+    /// The kind of thing acted on: the part of the name before the dot.
+    pub fn target_type(self) -> &'static str {
+        self.as_str()
+            .split_once('.')
+            .map_or(self.as_str(), |(target_type, _)| target_type)
+    }
+}
+
+/// Summary of a partial update: `fields` minus the ones left unset (null),
+/// so the entry lists only what the request changed.
+pub fn changed_fields(fields: serde_json::Value) -> serde_json::Value {
+    match fields {
+        serde_json::Value::Object(map) => {
+            serde_json::Value::Object(map.into_iter().filter(|(_, v)| !v.is_null()).collect())
+        }
+        other => other,
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = admin_audit)]
+pub struct AdminAuditInsertable {
+    pub admin_audit_actor_id: Option<Uuid>,
+    pub admin_audit_action: String,
+    pub admin_audit_target_type: String,
+    pub admin_audit_target_id: Option<String>,
+    pub admin_audit_summary: serde_json::Value,
+    pub admin_audit_ip: Option<ipnet::IpNet>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = admin_audit)]
+pub struct AdminAudit {
+    pub admin_audit_id: Uuid,
+    pub admin_audit_actor_id: Option<Uuid>,
+    pub admin_audit_action: String,
+    pub admin_audit_target_type: String,
+    pub admin_audit_target_id: Option<String>,
+    pub admin_audit_summary: serde_json::Value,
+    pub admin_audit_ip: Option<ipnet::IpNet>,
+    pub admin_audit_created_at: DateTime<Utc>,
+}
+
+/// An entry as shown by `GET /api/admin/audit`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminAuditItem {
+    pub audit_id: Uuid,
+    /// Null once the actor's account is deleted.
+    pub actor_id: Option<Uuid>,
+    pub actor_user_name: Option<String>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    /// What changed, per action (e.g. the fields set by an update).
+    #[schema(value_type = Object)]
+    pub summary: serde_json::Value,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AdminAuditItem {
+    pub fn new(audit: AdminAudit, actor_user_name: Option<String>) -> Self {
+        Self {
+            audit_id: audit.admin_audit_id,
+            actor_id: audit.admin_audit_actor_id,
+            actor_user_name,
+            action: audit.admin_audit_action,
+            target_type: audit.admin_audit_target_type,
+            target_id: audit.admin_audit_target_id,
+            summary: audit.admin_audit_summary,
+            ip: audit.admin_audit_ip.map(|ip| ip.addr().to_string()),
+            created_at: audit.admin_audit_created_at,
+        }
+    }
+}
+
+/// Filters for `ServerState::list_admin_audit`; `None` matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct AdminAuditFilter {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    /// Only entries strictly older than this, for paging back in time.
+    pub before: Option<DateTime<Utc>>,
+}
@@ -2,9 +2,10 @@ use chrono::{DateTime, Utc};
 use utoipa::ToSchema;
 
 use diesel::{
-    Insertable, Selectable,
+    ExpressionMethods, Insertable, QueryDsl, Selectable,
     prelude::{Queryable, QueryableByName},
 };
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
 
 use crate::schema::{comment_votes, post_tags, post_votes, posts, tags};
 
@@ -26,6 +27,10 @@ pub struct Post {
     pub post_metadata: serde_json::Value,
     pub total_upvotes: i64,
     pub total_downvotes: i64,
+    /// Set while the post is soft-deleted.
+    pub post_deleted_at: Option<DateTime<Utc>>,
+    /// Bumped by every edit; send it back with `PATCH` to detect conflicts.
+    pub post_version: i32,
 }
 
 // TODO: return user info w. profile picture link and stuff
@@ -220,6 +225,10 @@ pub struct Comment {
     pub parent_comment_id: Option<uuid::Uuid>,
     pub total_upvotes: i64,
     pub total_downvotes: i64,
+    /// Set while the comment is soft-deleted.
+    pub comment_deleted_at: Option<DateTime<Utc>>,
+    /// Bumped by every edit; send it back with `PATCH` to detect conflicts.
+    pub comment_version: i32,
 }
 #[derive(Clone, serde_derive::Serialize, ToSchema)]
 pub struct CommentResponse {
@@ -229,6 +238,7 @@ pub struct CommentResponse {
     pub comment_content: String,
     pub comment_created_at: DateTime<Utc>,
     pub comment_updated_at: Option<DateTime<Utc>>,
+    pub comment_version: i32,
     pub parent_comment_id: Option<uuid::Uuid>,
     pub total_upvotes: i64,
     pub total_downvotes: i64,
@@ -250,6 +260,7 @@ impl CommentResponse {
             comment_content: comment.comment_content,
             comment_created_at: comment.comment_created_at,
             comment_updated_at: comment.comment_updated_at,
+            comment_version: comment.comment_version,
             parent_comment_id: comment.parent_comment_id,
             total_upvotes: comment.total_upvotes,
             total_downvotes: comment.total_downvotes,
@@ -359,6 +370,51 @@ impl<'a> NewPostTag<'a> {
     }
 }
 
+impl PostTag {
+    /// Replace a post's tags with `tag_names` (already normalized), creating
+    /// missing `tags` rows. Run inside a transaction.
+    pub async fn replace_for_post(
+        conn: &mut AsyncPgConnection,
+        post_id: uuid::Uuid,
+        tag_names: &[String],
+    ) -> Result<(), diesel::result::Error> {
+        diesel::delete(post_tags::table.filter(post_tags::post_id.eq(post_id)))
+            .execute(conn)
+            .await?;
+
+        if tag_names.is_empty() {
+            return Ok(());
+        }
+
+        let new_tags: Vec<NewTag<'_>> = tag_names.iter().map(|tag| NewTag::new(tag)).collect();
+
+        diesel::insert_into(tags::table)
+            .values(&new_tags)
+            .on_conflict(tags::tag_name)
+            .do_nothing()
+            .execute(conn)
+            .await?;
+
+        let tag_ids: Vec<i16> = tags::table
+            .filter(tags::tag_name.eq_any(tag_names))
+            .select(tags::tag_id)
+            .load(conn)
+            .await?;
+
+        let links: Vec<NewPostTag<'_>> = tag_ids
+            .iter()
+            .map(|tag_id| NewPostTag::new(&post_id, tag_id))
+            .collect();
+
+        diesel::insert_into(post_tags::table)
+            .values(&links)
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+}
+
 #[repr(u8)]
 #[derive(Clone, ToSchema)]
 pub enum VoteState {
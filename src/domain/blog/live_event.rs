@@ -0,0 +1,54 @@
+//! Blog changes pushed to `GET /api/events` subscribers, so the UI can update
+//! counts, comment threads and the post list without polling.
+
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::blog::{CommentResponse, PostInfo};
+
+/// Capacity of the broadcast channel; a subscriber further behind than this
+/// is sent a `resync` event instead of the events it missed.
+pub const LIVE_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Clone, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    /// A post became public (created published, or published later).
+    PostPublished { post: PostInfo },
+    PostVotesChanged {
+        post_id: Uuid,
+        upvote_count: i64,
+        downvote_count: i64,
+    },
+    /// `comment.vote_state` is always 2 (did not vote).
+    CommentAdded { comment: CommentResponse },
+    CommentVotesChanged {
+        post_id: Uuid,
+        comment_id: Uuid,
+        upvote_count: i64,
+        downvote_count: i64,
+    },
+}
+
+impl LiveEvent {
+    /// The SSE `event:` name, equal to the serialized `type`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PostPublished { .. } => "post_published",
+            Self::PostVotesChanged { .. } => "post_votes_changed",
+            Self::CommentAdded { .. } => "comment_added",
+            Self::CommentVotesChanged { .. } => "comment_votes_changed",
+        }
+    }
+
+    pub fn post_id(&self) -> Uuid {
+        match self {
+            Self::PostPublished { post } => post.post_id,
+            Self::CommentAdded { comment } => comment.post_id,
+            Self::PostVotesChanged { post_id, .. } | Self::CommentVotesChanged { post_id, .. } => {
+                *post_id
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
 #[allow(clippy::module_inception)]
 pub mod blog;
+pub mod live_event;
 pub mod service;
@@ -298,6 +298,11 @@ impl CountryAndSubdivisionsTable {
         self.by_country_alpha3.get(code).map(|&idx| &self.rows[idx])
     }
 
+    /// Lookup a combined record by its country's numeric code.
+    pub fn lookup_by_code(&self, code: i32) -> Option<&CountryAndSubdivisions> {
+        self.by_id.get(&code).map(|&idx| &self.rows[idx])
+    }
+
     /// Optionally retrieve the JSON representation on demand.
     pub fn as_dispatch_json(&self) -> serde_json::Value {
         serde_json::json!({ "countries": self.rows })
@@ -0,0 +1,137 @@
+//! Transactional emails queued for sending (`email_outbox`). Handlers enqueue
+//! with `jobs::tasks::email`; the outbox sender (`jobs::job_funcs::email_outbox`)
+//! delivers them.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, QueryableByName, Selectable};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::i18n::ui_text::locale::UiLocale;
+use crate::schema::email_outbox;
+use crate::util::email::{message::EmailMessage, template::EmailTemplate};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailOutboxStatus {
+    Pending,
+    /// Claimed by a sender.
+    Sending,
+    Sent,
+    /// Out of attempts; an admin can requeue it.
+    Dead,
+    /// Not sent: the recipient is on the suppression list.
+    Suppressed,
+}
+
+impl EmailOutboxStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sending => "sending",
+            Self::Sent => "sent",
+            Self::Dead => "dead",
+            Self::Suppressed => "suppressed",
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = email_outbox)]
+pub struct EmailOutboxInsertable {
+    pub email_outbox_id: Uuid,
+    pub email_outbox_template: String,
+    pub email_outbox_recipient: String,
+    pub email_outbox_locale: Option<String>,
+    pub email_outbox_vars: serde_json::Value,
+}
+
+impl EmailOutboxInsertable {
+    pub fn new(message: &EmailMessage) -> anyhow::Result<Self> {
+        Ok(Self {
+            email_outbox_id: Uuid::new_v4(),
+            email_outbox_template: message.template().as_str().to_string(),
+            email_outbox_recipient: message.recipient().to_string(),
+            email_outbox_locale: message
+                .locale_override()
+                .map(|locale| locale.as_tag().to_string()),
+            email_outbox_vars: serde_json::to_value(message.vars())?,
+        })
+    }
+}
+
+/// Also `QueryableByName`, for the `RETURNING *` of the claim query.
+#[derive(Debug, Clone, Queryable, QueryableByName, Selectable)]
+#[diesel(table_name = email_outbox)]
+pub struct EmailOutboxRow {
+    pub email_outbox_id: Uuid,
+    pub email_outbox_template: String,
+    pub email_outbox_recipient: String,
+    pub email_outbox_locale: Option<String>,
+    pub email_outbox_vars: serde_json::Value,
+    pub email_outbox_status: String,
+    /// Including the current claim.
+    pub email_outbox_attempts: i32,
+    pub email_outbox_next_attempt_at: DateTime<Utc>,
+    pub email_outbox_last_error: Option<String>,
+    pub email_outbox_created_at: DateTime<Utc>,
+    pub email_outbox_claimed_at: Option<DateTime<Utc>>,
+    pub email_outbox_sent_at: Option<DateTime<Utc>>,
+    pub email_outbox_provider_message_id: Option<String>,
+}
+
+impl EmailOutboxRow {
+    /// The message to render; fails for a template this build does not know.
+    pub fn message(&self) -> anyhow::Result<EmailMessage> {
+        let template = EmailTemplate::parse(&self.email_outbox_template).ok_or_else(|| {
+            anyhow::anyhow!("unknown email template {}", self.email_outbox_template)
+        })?;
+        let vars: BTreeMap<String, String> =
+            serde_json::from_value(self.email_outbox_vars.clone())?;
+        Ok(EmailMessage::from_parts(
+            template,
+            self.email_outbox_recipient.clone(),
+            self.email_outbox_locale
+                .as_deref()
+                .map(|tag| UiLocale::parse(Some(tag))),
+            vars,
+        ))
+    }
+}
+
+/// An outbox row as shown by `GET /api/admin/email-outbox`. The variables are
+/// left out: they hold verification and reset links.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailOutboxItem {
+    pub email_id: Uuid,
+    pub template: String,
+    pub recipient: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    /// Provider message id (SES `MessageId`); absent over SMTP.
+    pub provider_message_id: Option<String>,
+}
+
+impl From<EmailOutboxRow> for EmailOutboxItem {
+    fn from(row: EmailOutboxRow) -> Self {
+        Self {
+            email_id: row.email_outbox_id,
+            template: row.email_outbox_template,
+            recipient: row.email_outbox_recipient,
+            status: row.email_outbox_status,
+            attempts: row.email_outbox_attempts,
+            next_attempt_at: row.email_outbox_next_attempt_at,
+            last_error: row.email_outbox_last_error,
+            created_at: row.email_outbox_created_at,
+            sent_at: row.email_outbox_sent_at,
+            provider_message_id: row.email_outbox_provider_message_id,
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Bounces and complaints reported by SES (`email_feedback_events`) and the
+//! addresses they suppress (`email_suppressions`), with any a superuser
+//! suppressed by hand.
+//!
+//! SES publishes them to SNS either as identity notifications
+//! (`notificationType`) or as configuration-set events (`eventType`); both
+//! carry the same `mail`, `bounce` and `complaint` objects. Permanent bounces
+//! and complaints suppress the recipient; transient and undetermined bounces
+//! are only recorded.
+
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::{email_feedback_events, email_suppressions};
+
+/// `email_suppression_reason` of an address a superuser suppressed.
+pub const MANUAL_SUPPRESSION: &str = "manual";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailFeedbackKind {
+    Bounce,
+    Complaint,
+}
+
+impl EmailFeedbackKind {
+    /// Stored name, as in `email_feedback_event_kind` and
+    /// `email_suppression_reason` (which also takes [`MANUAL_SUPPRESSION`]).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bounce => "bounce",
+            Self::Complaint => "complaint",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesNotification {
+    notification_type: Option<String>,
+    event_type: Option<String>,
+    mail: SesMail,
+    bounce: Option<SesBounce>,
+    complaint: Option<SesComplaint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesMail {
+    message_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesBounce {
+    bounce_type: String,
+    bounce_sub_type: Option<String>,
+    bounced_recipients: Vec<SesRecipient>,
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesComplaint {
+    complained_recipients: Vec<SesRecipient>,
+    complaint_feedback_type: Option<String>,
+    complaint_sub_type: Option<String>,
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesRecipient {
+    email_address: String,
+    diagnostic_code: Option<String>,
+}
+
+/// One recipient's bounce or complaint, ready to record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailFeedback {
+    pub kind: EmailFeedbackKind,
+    /// Bounce type (`Permanent`, `Transient`, `Undetermined`) or complaint
+    /// feedback type (`abuse`, ...).
+    pub feedback_type: Option<String>,
+    pub subtype: Option<String>,
+    /// Lowercased.
+    pub recipient: String,
+    pub provider_message_id: Option<String>,
+    pub diagnostic: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    /// Whether the recipient should no longer be sent to.
+    pub suppress: bool,
+}
+
+impl EmailFeedback {
+    /// Read an SES notification (the SNS `Message`). Deliveries, sends and
+    /// other event types yield nothing.
+    pub fn parse_ses(raw: &str) -> anyhow::Result<Vec<Self>> {
+        let notification: SesNotification = serde_json::from_str(raw)?;
+        let kind = notification
+            .notification_type
+            .or(notification.event_type)
+            .unwrap_or_default();
+        let message_id = notification.mail.message_id;
+
+        let feedback = match (kind.as_str(), notification.bounce, notification.complaint) {
+            ("Bounce", Some(bounce), _) => {
+                let suppress = bounce.bounce_type == "Permanent";
+                bounce
+                    .bounced_recipients
+                    .into_iter()
+                    .map(|recipient| Self {
+                        kind: EmailFeedbackKind::Bounce,
+                        feedback_type: Some(bounce.bounce_type.clone()),
+                        subtype: bounce.bounce_sub_type.clone(),
+                        recipient: recipient.email_address.trim().to_lowercase(),
+                        provider_message_id: message_id.clone(),
+                        diagnostic: recipient.diagnostic_code,
+                        occurred_at: bounce.timestamp,
+                        suppress,
+                    })
+                    .collect()
+            }
+            ("Complaint", _, Some(complaint)) => complaint
+                .complained_recipients
+                .into_iter()
+                .map(|recipient| Self {
+                    kind: EmailFeedbackKind::Complaint,
+                    feedback_type: complaint.complaint_feedback_type.clone(),
+                    subtype: complaint.complaint_sub_type.clone(),
+                    recipient: recipient.email_address.trim().to_lowercase(),
+                    provider_message_id: message_id.clone(),
+                    diagnostic: None,
+                    occurred_at: complaint.timestamp,
+                    suppress: true,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok(feedback)
+    }
+
+    pub fn insertable(&self, payload: &serde_json::Value) -> EmailFeedbackEventInsertable {
+        EmailFeedbackEventInsertable {
+            email_feedback_event_id: Uuid::new_v4(),
+            email_feedback_event_kind: self.kind.as_str().to_string(),
+            email_feedback_event_type: self.feedback_type.clone(),
+            email_feedback_event_subtype: self.subtype.clone(),
+            email_feedback_event_recipient: self.recipient.clone(),
+            email_feedback_event_provider_message_id: self.provider_message_id.clone(),
+            email_feedback_event_diagnostic: self.diagnostic.clone(),
+            email_feedback_event_payload: payload.clone(),
+            email_feedback_event_occurred_at: self.occurred_at,
+        }
+    }
+
+    /// What the suppression list says about this recipient.
+    pub fn suppression_detail(&self) -> String {
+        [self.feedback_type.as_deref(), self.subtype.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = email_feedback_events)]
+pub struct EmailFeedbackEventInsertable {
+    pub email_feedback_event_id: Uuid,
+    pub email_feedback_event_kind: String,
+    pub email_feedback_event_type: Option<String>,
+    pub email_feedback_event_subtype: Option<String>,
+    pub email_feedback_event_recipient: String,
+    pub email_feedback_event_provider_message_id: Option<String>,
+    pub email_feedback_event_diagnostic: Option<String>,
+    pub email_feedback_event_payload: serde_json::Value,
+    pub email_feedback_event_occurred_at: DateTime<Utc>,
+}
+
+/// A suppressed address, as listed by `GET /api/admin/email-suppressions`.
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = email_suppressions)]
+pub struct EmailSuppression {
+    pub email_suppression_address: String,
+    /// `bounce`, `complaint` or `manual`.
+    pub email_suppression_reason: String,
+    /// Bounce or complaint type and subtype, e.g. `Permanent/General`, or
+    /// the note given with a manual suppression.
+    pub email_suppression_detail: Option<String>,
+    pub email_suppression_event_count: i32,
+    pub email_suppression_created_at: DateTime<Utc>,
+    pub email_suppression_last_event_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permanent_bounces_and_complaints_suppress() {
+        let bounce = r#"{
+            "notificationType": "Bounce",
+            "mail": {"messageId": "m-1"},
+            "bounce": {
+                "bounceType": "Permanent",
+                "bounceSubType": "General",
+                "timestamp": "2026-10-15T00:00:00.000Z",
+                "bouncedRecipients": [{"emailAddress": "A@Example.com", "diagnosticCode": "550"}]
+            }
+        }"#;
+        let feedback = EmailFeedback::parse_ses(bounce).unwrap();
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].recipient, "a@example.com");
+        assert!(feedback[0].suppress);
+        assert_eq!(feedback[0].suppression_detail(), "Permanent/General");
+
+        let transient = bounce.replace("Permanent", "Transient");
+        assert!(!EmailFeedback::parse_ses(&transient).unwrap()[0].suppress);
+
+        let complaint = r#"{
+            "eventType": "Complaint",
+            "mail": {"messageId": "m-2"},
+            "complaint": {
+                "timestamp": "2026-10-15T00:00:00.000Z",
+                "complainedRecipients": [{"emailAddress": "b@example.com"}]
+            }
+        }"#;
+        let feedback = EmailFeedback::parse_ses(complaint).unwrap();
+        assert_eq!(feedback[0].kind, EmailFeedbackKind::Complaint);
+        assert!(feedback[0].suppress);
+
+        let delivery = r#"{"notificationType": "Delivery", "mail": {"messageId": "m-3"}}"#;
+        assert!(EmailFeedback::parse_ses(delivery).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod email_outbox;
+pub mod feedback;
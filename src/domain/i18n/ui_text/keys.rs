@@ -313,4 +313,21 @@ pub const REQUIRED_UI_TEXT_KEYS: &[&str] = &[
     "live_chat.you",
     "live_chat.typing_one",
     "live_chat.typing_many",
+    "email.common.footer",
+    "email.common.ignore",
+    "email.common.unsubscribe",
+    "email.verify_email.subject",
+    "email.verify_email.heading",
+    "email.verify_email.body",
+    "email.verify_email.button",
+    "email.verify_email.expires",
+    "email.password_reset.subject",
+    "email.password_reset.heading",
+    "email.password_reset.body",
+    "email.password_reset.button",
+    "email.notification_digest.subject",
+    "email.notification_digest.heading",
+    "email.notification_digest.body",
+    "email.notification_digest.button",
+    "email.notification_digest.opt_out",
 ];
@@ -13,6 +13,8 @@ pub enum UiLocale {
 }
 
 impl UiLocale {
+    pub const ALL: [UiLocale; 2] = [UiLocale::EnUs, UiLocale::KoKr];
+
     pub fn parse(value: Option<&str>) -> Self {
         match value {
             Some("ko") | Some("ko-KR") | Some("ko_kr") | Some("ko-kr") => UiLocale::KoKr,
@@ -21,6 +23,15 @@ impl UiLocale {
         }
     }
 
+    /// Locale for a `users.user_language` code; en-US for anything without
+    /// its own bundle.
+    pub fn from_language_code(code: i32) -> Self {
+        match code {
+            KO_KR_LANGUAGE_CODE => UiLocale::KoKr,
+            _ => UiLocale::EnUs,
+        }
+    }
+
     pub fn as_tag(self) -> &'static str {
         match self {
             UiLocale::EnUs => "en-US",
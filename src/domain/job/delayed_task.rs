@@ -0,0 +1,156 @@
+//! Queued and delayed tasks (`delayed_tasks`), enqueued with
+//! `jobs::job_funcs::delayed::{enqueue, schedule_once_at, schedule_after}` and
+//! run by the delayed task dispatcher.
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    Insertable, Queryable, QueryableByName, Selectable,
+    sql_types::{Int4, Jsonb, Timestamptz, Uuid as SqlUuid},
+};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::delayed_tasks;
+
+/// Work a handler can defer. Stored as JSON, so variants and their fields
+/// must stay deserializable across deploys: add new variants rather than
+/// changing existing ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DelayedTask {
+    /// Delete the account if it is still unverified and has no live
+    /// verification token.
+    PurgeUnverifiedUser { user_id: Uuid },
+    /// Email the verification link for a new account. No longer enqueued:
+    /// emails go through the email outbox. Rows left from before are moved
+    /// there when they run.
+    SendValidationEmail {
+        user_email: String,
+        token_id: Uuid,
+        valid_until: DateTime<Utc>,
+    },
+    /// Email a password reset link. Moved to the email outbox like
+    /// `SendValidationEmail`.
+    SendPasswordResetEmail { user_email: String, token_id: Uuid },
+    /// POST `body` as JSON to `url`; any non-2xx status is a failed attempt.
+    DeliverWebhook {
+        url: String,
+        body: serde_json::Value,
+    },
+    /// Send a `webhook_deliveries` row to its webhook, signed with the
+    /// webhook's current secret.
+    DeliverSignedWebhook { delivery_id: Uuid },
+}
+
+impl DelayedTask {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::PurgeUnverifiedUser { .. } => "purge_unverified_user",
+            Self::SendValidationEmail { .. } => "send_validation_email",
+            Self::SendPasswordResetEmail { .. } => "send_password_reset_email",
+            Self::DeliverWebhook { .. } => "deliver_webhook",
+            Self::DeliverSignedWebhook { .. } => "deliver_signed_webhook",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DelayedTaskStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+impl DelayedTaskStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Done => "done",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = delayed_tasks)]
+pub struct DelayedTaskInsertable {
+    pub delayed_task_id: Uuid,
+    pub delayed_task_kind: String,
+    pub delayed_task_payload: serde_json::Value,
+    pub delayed_task_run_at: DateTime<Utc>,
+}
+
+impl DelayedTaskInsertable {
+    pub fn new(task: &DelayedTask, run_at: DateTime<Utc>) -> anyhow::Result<Self> {
+        Ok(Self {
+            delayed_task_id: Uuid::new_v4(),
+            delayed_task_kind: task.kind().to_string(),
+            delayed_task_payload: serde_json::to_value(task)?,
+            delayed_task_run_at: run_at,
+        })
+    }
+}
+
+/// A row claimed by the dispatcher (`RETURNING` of the claim query).
+#[derive(Debug, Clone, QueryableByName)]
+pub struct ClaimedDelayedTask {
+    #[diesel(sql_type = SqlUuid)]
+    pub delayed_task_id: Uuid,
+    #[diesel(sql_type = Jsonb)]
+    pub delayed_task_payload: serde_json::Value,
+    #[diesel(sql_type = Timestamptz)]
+    pub delayed_task_run_at: DateTime<Utc>,
+    /// Including this claim.
+    #[diesel(sql_type = Int4)]
+    pub delayed_task_attempts: i32,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = delayed_tasks)]
+pub struct DelayedTaskRow {
+    pub delayed_task_id: Uuid,
+    pub delayed_task_kind: String,
+    pub delayed_task_payload: serde_json::Value,
+    pub delayed_task_run_at: DateTime<Utc>,
+    pub delayed_task_status: String,
+    pub delayed_task_attempts: i32,
+    pub delayed_task_last_error: Option<String>,
+    pub delayed_task_created_at: DateTime<Utc>,
+    pub delayed_task_claimed_at: Option<DateTime<Utc>>,
+    pub delayed_task_finished_at: Option<DateTime<Utc>>,
+}
+
+/// A task as shown by `GET /api/admin/tasks`. The payload is left out: it can
+/// hold email addresses and tokens.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DelayedTaskItem {
+    pub task_id: Uuid,
+    pub kind: String,
+    pub status: String,
+    pub run_at: DateTime<Utc>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl From<DelayedTaskRow> for DelayedTaskItem {
+    fn from(row: DelayedTaskRow) -> Self {
+        Self {
+            task_id: row.delayed_task_id,
+            kind: row.delayed_task_kind,
+            status: row.delayed_task_status,
+            run_at: row.delayed_task_run_at,
+            attempts: row.delayed_task_attempts,
+            last_error: row.delayed_task_last_error,
+            created_at: row.delayed_task_created_at,
+            claimed_at: row.delayed_task_claimed_at,
+            finished_at: row.delayed_task_finished_at,
+        }
+    }
+}
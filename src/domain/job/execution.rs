@@ -0,0 +1,136 @@
+//! Scheduled-task executions (`job_executions`), written by
+//! `jobs::job_funcs::job_runner::run_job`.
+
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::schema::job_executions;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobOutcome {
+    Success,
+    /// The task returned an error.
+    Failure,
+    Panicked,
+    /// A cluster-exclusive tick another instance held or already ran.
+    Skipped,
+    /// Aborted after exceeding the job's timeout.
+    TimedOut,
+    /// Aborted through `POST /api/admin/jobs/{job_name}/cancel`.
+    Cancelled,
+}
+
+impl JobOutcome {
+    pub const ALL: [Self; 6] = [
+        Self::Success,
+        Self::Failure,
+        Self::Panicked,
+        Self::Skipped,
+        Self::TimedOut,
+        Self::Cancelled,
+    ];
+
+    /// Counts toward a job's consecutive-failure streak.
+    pub fn is_failure(self) -> bool {
+        matches!(self, Self::Failure | Self::Panicked | Self::TimedOut)
+    }
+
+    /// Position in [`Self::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Panicked => "panicked",
+            Self::Skipped => "skipped",
+            Self::TimedOut => "timed_out",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "success" => Self::Success,
+            "panicked" => Self::Panicked,
+            "skipped" => Self::Skipped,
+            "timed_out" => Self::TimedOut,
+            "cancelled" => Self::Cancelled,
+            _ => Self::Failure,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = job_executions)]
+pub struct JobExecutionInsertable {
+    pub job_name: String,
+    pub job_scheduled_for: DateTime<Utc>,
+    pub job_started_at: DateTime<Utc>,
+    pub job_duration_ms: i64,
+    pub job_outcome: String,
+    pub job_error: Option<String>,
+    pub job_lock_wait_ms: Option<i64>,
+    pub job_details: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = job_executions)]
+pub struct JobExecutionRow {
+    pub job_scheduled_for: DateTime<Utc>,
+    pub job_started_at: DateTime<Utc>,
+    pub job_duration_ms: i64,
+    pub job_outcome: String,
+    pub job_error: Option<String>,
+    pub job_lock_wait_ms: Option<i64>,
+    pub job_details: Option<serde_json::Value>,
+}
+
+/// One execution as returned by `/api/admin/jobs`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobExecution {
+    pub scheduled_for: DateTime<Utc>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub outcome: JobOutcome,
+    pub error: Option<String>,
+    /// Time spent taking the advisory lock, for cluster-exclusive jobs.
+    pub lock_wait_ms: Option<i64>,
+    /// What a successful run reported about itself, if anything.
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+}
+
+impl From<JobExecutionRow> for JobExecution {
+    fn from(row: JobExecutionRow) -> Self {
+        Self {
+            scheduled_for: row.job_scheduled_for,
+            started_at: row.job_started_at,
+            duration_ms: row.job_duration_ms,
+            outcome: JobOutcome::from_db(&row.job_outcome),
+            error: row.job_error,
+            lock_wait_ms: row.job_lock_wait_ms,
+            details: row.job_details,
+        }
+    }
+}
+
+impl JobExecutionInsertable {
+    pub fn new(job_name: &str, execution: &JobExecution) -> Self {
+        Self {
+            job_name: job_name.to_string(),
+            job_scheduled_for: execution.scheduled_for,
+            job_started_at: execution.started_at,
+            job_duration_ms: execution.duration_ms,
+            job_outcome: execution.outcome.as_str().to_string(),
+            job_error: execution.error.clone(),
+            job_lock_wait_ms: execution.lock_wait_ms,
+            job_details: execution.details.clone(),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Per-job execution metrics kept by `JobMonitor` and exported on
+//! `GET /api/metrics`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{
+    domain::job::execution::{JobExecution, JobOutcome},
+    util::metrics::{Histogram, MetricsWriter},
+};
+
+const DURATION_BOUNDS: [f64; 13] = [
+    0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 900.0, 3600.0,
+];
+const LAG_BOUNDS: [f64; 10] = [0.001, 0.01, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0];
+
+pub struct JobMetrics {
+    /// Runs that executed, by outcome; skipped ticks only count here.
+    outcomes: [AtomicU64; JobOutcome::ALL.len()],
+    duration: Histogram,
+    /// Actual start minus the scheduled mark (includes jitter and lock wait).
+    start_lag: Histogram,
+}
+
+impl JobMetrics {
+    pub fn new() -> Self {
+        Self {
+            outcomes: std::array::from_fn(|_| AtomicU64::new(0)),
+            duration: Histogram::new(&DURATION_BOUNDS),
+            start_lag: Histogram::new(&LAG_BOUNDS),
+        }
+    }
+
+    pub fn record(&self, execution: &JobExecution) {
+        self.outcomes[execution.outcome.index()].fetch_add(1, Ordering::Relaxed);
+        if execution.outcome == JobOutcome::Skipped {
+            return;
+        }
+        self.duration.observe(execution.duration_ms as f64 / 1000.0);
+        let lag = execution.started_at - execution.scheduled_for;
+        self.start_lag
+            .observe(lag.num_milliseconds() as f64 / 1000.0);
+    }
+}
+
+impl Default for JobMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Write the job metric families for `jobs` (sorted by name).
+pub fn write_job_metrics(w: &mut MetricsWriter, jobs: &[(String, Arc<JobMetrics>)]) {
+    w.header(
+        "job_runs_total",
+        "counter",
+        "Scheduled job runs by outcome.",
+    );
+    for (name, metrics) in jobs {
+        for outcome in JobOutcome::ALL {
+            let count = metrics.outcomes[outcome.index()].load(Ordering::Relaxed);
+            w.sample(
+                "job_runs_total",
+                &[("job", name), ("outcome", outcome.as_str())],
+                count as f64,
+            );
+        }
+    }
+
+    w.header(
+        "job_duration_seconds",
+        "histogram",
+        "Scheduled job run duration.",
+    );
+    for (name, metrics) in jobs {
+        w.histogram("job_duration_seconds", &[("job", name)], &metrics.duration);
+    }
+
+    w.header(
+        "job_start_lag_seconds",
+        "histogram",
+        "Delay between a job's scheduled mark and its actual start.",
+    );
+    for (name, metrics) in jobs {
+        w.histogram(
+            "job_start_lag_seconds",
+            &[("job", name)],
+            &metrics.start_lag,
+        );
+    }
+}
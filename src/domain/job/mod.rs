@@ -0,0 +1,4 @@
+pub mod delayed_task;
+pub mod execution;
+pub mod metrics;
+pub mod monitor;
@@ -0,0 +1,247 @@
+//! In-memory status of the scheduled jobs started by `task_init`: schedule,
+//! next mark, whether a run is in flight, the last execution and the current
+//! failure streak. Persistent history lives in `job_executions`.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use chrono::{DateTime, Utc};
+use scc::hash_map::Entry;
+use tokio_util::sync::CancellationToken;
+
+use crate::domain::job::execution::{JobExecution, JobOutcome};
+use crate::domain::job::metrics::{JobMetrics, write_job_metrics};
+use crate::util::metrics::MetricsWriter;
+
+#[derive(Debug, Clone)]
+pub struct JobStatus {
+    /// Human-readable schedule, e.g. `every day at 06:30:00 UTC`.
+    pub schedule: String,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub running: bool,
+    /// Cancels the run in flight; `None` when idle.
+    pub cancel: Option<CancellationToken>,
+    pub last_execution: Option<JobExecution>,
+    /// Whether successful runs are written to `job_executions` (failures
+    /// always are).
+    pub persist_successes: bool,
+    /// Failed, panicked or timed-out runs since the last success.
+    pub consecutive_failures: u32,
+    /// The newest failures of the current streak, oldest first.
+    pub failure_streak: Vec<JobExecution>,
+}
+
+/// Serializes the runs of one job (see `jobs::job_funcs::overlap`).
+#[derive(Default)]
+pub struct RunSlot {
+    pub lock: Arc<tokio::sync::Mutex<()>>,
+    /// A tick is waiting for the lock.
+    pub queued: AtomicBool,
+}
+
+/// Failures of a streak kept for the alert summary.
+const MAX_FAILURE_STREAK_KEPT: usize = 10;
+
+/// What `finish_run` tells the runner.
+pub struct FinishedRun {
+    pub persist_successes: bool,
+    pub consecutive_failures: u32,
+    pub failure_streak: Vec<JobExecution>,
+}
+
+/// Bounded by the number of jobs `task_init` registers.
+pub struct JobMonitor {
+    jobs: scc::HashMap<String, JobStatus>,
+    /// Set once by `task_init` before the schedulers start.
+    cluster_exclusive: scc::HashSet<String>,
+    /// Jobs whose ticks are skipped; toggled by the admin pause/resume
+    /// endpoints.
+    paused: scc::HashSet<String>,
+    metrics: scc::HashMap<String, Arc<JobMetrics>>,
+    run_slots: scc::HashMap<String, Arc<RunSlot>>,
+    /// Jobs whose overlapping ticks wait instead of being dropped.
+    queue_overlaps: scc::HashSet<String>,
+}
+
+impl JobMonitor {
+    pub fn new() -> Self {
+        Self {
+            jobs: scc::HashMap::new(),
+            cluster_exclusive: scc::HashSet::new(),
+            paused: scc::HashSet::new(),
+            metrics: scc::HashMap::new(),
+            run_slots: scc::HashMap::new(),
+            queue_overlaps: scc::HashSet::new(),
+        }
+    }
+
+    /// Mark a job as cluster-exclusive (see `jobs::job_funcs::job_lock`).
+    pub async fn mark_cluster_exclusive(&self, job_name: &str) {
+        let _ = self
+            .cluster_exclusive
+            .insert_async(job_name.to_string())
+            .await;
+    }
+
+    pub async fn is_cluster_exclusive(&self, job_name: &str) -> bool {
+        self.cluster_exclusive.contains_async(job_name).await
+    }
+
+    /// Let overlapping ticks of a job wait for the run in flight.
+    pub async fn mark_queue_overlaps(&self, job_name: &str) {
+        let _ = self.queue_overlaps.insert_async(job_name.to_string()).await;
+    }
+
+    pub async fn queues_overlaps(&self, job_name: &str) -> bool {
+        self.queue_overlaps.contains_async(job_name).await
+    }
+
+    pub async fn run_slot(&self, job_name: &str) -> Arc<RunSlot> {
+        Arc::clone(
+            self.run_slots
+                .entry_async(job_name.to_string())
+                .await
+                .or_insert_with(|| Arc::new(RunSlot::default()))
+                .get(),
+        )
+    }
+
+    pub async fn set_paused(&self, job_name: &str, paused: bool) {
+        if paused {
+            let _ = self.paused.insert_async(job_name.to_string()).await;
+        } else {
+            self.paused.remove_async(job_name).await;
+        }
+    }
+
+    pub async fn is_paused(&self, job_name: &str) -> bool {
+        self.paused.contains_async(job_name).await
+    }
+
+    pub async fn is_registered(&self, job_name: &str) -> bool {
+        self.jobs.contains_async(job_name).await
+    }
+
+    /// Register a job when its scheduler starts. A supervisor restart keeps
+    /// the last execution.
+    pub async fn register(&self, job_name: &str, schedule: String, persist_successes: bool) {
+        match self.jobs.entry_async(job_name.to_string()).await {
+            Entry::Occupied(mut occ) => {
+                let status = occ.get_mut();
+                status.schedule = schedule;
+                status.persist_successes = persist_successes;
+                status.running = false;
+                status.cancel = None;
+            }
+            Entry::Vacant(vac) => {
+                vac.insert_entry(JobStatus {
+                    schedule,
+                    next_run_at: None,
+                    running: false,
+                    cancel: None,
+                    last_execution: None,
+                    persist_successes,
+                    consecutive_failures: 0,
+                    failure_streak: Vec::new(),
+                });
+            }
+        }
+    }
+
+    pub async fn set_next_run(&self, job_name: &str, next_run_at: DateTime<Utc>) {
+        self.jobs
+            .update_async(job_name, |_, status| status.next_run_at = Some(next_run_at))
+            .await;
+    }
+
+    /// Mark the job running; the returned token cancels this run.
+    pub async fn start_run(&self, job_name: &str) -> CancellationToken {
+        let cancel = CancellationToken::new();
+        self.jobs
+            .update_async(job_name, |_, status| {
+                status.running = true;
+                status.cancel = Some(cancel.clone());
+            })
+            .await;
+        cancel
+    }
+
+    /// Cancel the job's run in flight. Returns `false` when it is idle.
+    pub async fn cancel_run(&self, job_name: &str) -> bool {
+        self.jobs
+            .read_async(job_name, |_, status| status.cancel.clone())
+            .await
+            .flatten()
+            .map(|cancel| cancel.cancel())
+            .is_some()
+    }
+
+    /// Store `execution` as the job's last one, update its failure streak and
+    /// count it in the job's metrics.
+    pub async fn finish_run(&self, job_name: &str, execution: &JobExecution) -> FinishedRun {
+        self.metrics
+            .entry_async(job_name.to_string())
+            .await
+            .or_insert_with(|| Arc::new(JobMetrics::new()))
+            .get()
+            .record(execution);
+        self.jobs
+            .update_async(job_name, |_, status| {
+                status.running = false;
+                status.cancel = None;
+                status.last_execution = Some(execution.clone());
+                if execution.outcome.is_failure() {
+                    status.consecutive_failures += 1;
+                    if status.failure_streak.len() == MAX_FAILURE_STREAK_KEPT {
+                        status.failure_streak.remove(0);
+                    }
+                    status.failure_streak.push(execution.clone());
+                } else if execution.outcome == JobOutcome::Success {
+                    status.consecutive_failures = 0;
+                    status.failure_streak.clear();
+                }
+                FinishedRun {
+                    persist_successes: status.persist_successes,
+                    consecutive_failures: status.consecutive_failures,
+                    failure_streak: status.failure_streak.clone(),
+                }
+            })
+            .await
+            .unwrap_or(FinishedRun {
+                persist_successes: true,
+                consecutive_failures: 0,
+                failure_streak: Vec::new(),
+            })
+    }
+
+    pub async fn write_metrics(&self, w: &mut MetricsWriter) {
+        let mut jobs = Vec::new();
+        self.metrics
+            .iter_async(|name, metrics| {
+                jobs.push((name.clone(), Arc::clone(metrics)));
+                true
+            })
+            .await;
+        jobs.sort_by(|a, b| a.0.cmp(&b.0));
+        write_job_metrics(w, &jobs);
+    }
+
+    /// Every registered job, sorted by name.
+    pub async fn snapshot(&self) -> Vec<(String, JobStatus)> {
+        let mut jobs = Vec::new();
+        self.jobs
+            .iter_async(|name, status| {
+                jobs.push((name.clone(), status.clone()));
+                true
+            })
+            .await;
+        jobs.sort_by(|a, b| a.0.cmp(&b.0));
+        jobs
+    }
+}
+
+impl Default for JobMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
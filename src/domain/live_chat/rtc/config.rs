@@ -1,14 +1,17 @@
-//! Configuration for the in-process WebRTC SFU, loaded from the environment.
+//! Configuration for the in-process WebRTC SFU, loaded from the `[rtc]`
+//! config section.
 
 use nutype::nutype;
 use tracing::warn;
 
+use crate::init::app_config::RtcSection;
+
 /// Default upper bound on simultaneous participants in a single room call.
 const DEFAULT_RTC_MAX_PARTICIPANTS: usize = 16;
 
 /// Validated maximum number of participants allowed in one room call. Used to
-/// range-check the `RTC_MAX_PARTICIPANTS` env value at parse time; the validated
-/// inner value is stored as a plain `usize` on [`RtcConfig`].
+/// range-check `rtc.max_participants` (`RTC_MAX_PARTICIPANTS`) at startup; the
+/// validated inner value is stored as a plain `usize` on [`RtcConfig`].
 #[nutype(
     validate(greater_or_equal = 1, less_or_equal = 64),
     derive(Debug, Clone, Copy, PartialEq, Eq)
@@ -40,40 +43,27 @@ pub struct RtcConfig {
 }
 
 impl RtcConfig {
-    /// Load SFU configuration from environment variables.
+    /// Load SFU configuration from the `[rtc]` config section.
     ///
-    /// `RTC_ENABLE` gates the whole feature (default off). When enabled,
-    /// `RTC_PUBLIC_IP` and `RTC_UDP_MUX_PORT` are required; missing/invalid
-    /// values disable the SFU rather than aborting startup, so the rest of the
+    /// `enabled` (`RTC_ENABLE`) gates the whole feature (default off). When
+    /// enabled, `public_ip` and `udp_mux_port` are required; missing values
+    /// disable the SFU rather than aborting startup, so the rest of the
     /// server still comes up.
-    pub fn from_env() -> Self {
-        let enabled = std::env::var("RTC_ENABLE")
-            .ok()
-            .map(|value| {
-                matches!(
-                    value.trim().to_ascii_lowercase().as_str(),
-                    "1" | "true" | "yes" | "on"
-                )
-            })
-            .unwrap_or(false);
+    pub fn from_config(config: &RtcSection) -> Self {
+        let enabled = config.enabled;
 
-        let max_participants = std::env::var("RTC_MAX_PARTICIPANTS")
-            .ok()
-            .and_then(|value| value.trim().parse::<usize>().ok())
+        let max_participants = config
+            .max_participants
             .and_then(|value| MaxParticipants::try_new(value).ok())
             .map(MaxParticipants::into_inner)
             .unwrap_or(DEFAULT_RTC_MAX_PARTICIPANTS);
 
-        let turn = match (
-            std::env::var("RTC_TURN_URL").ok(),
-            std::env::var("RTC_TURN_USER").ok(),
-            std::env::var("RTC_TURN_PASS").ok(),
-        ) {
+        let turn = match (&config.turn_url, &config.turn_user, &config.turn_pass) {
             (Some(url), Some(username), Some(credential)) if !url.trim().is_empty() => {
                 Some(TurnConfig {
-                    url,
-                    username,
-                    credential,
+                    url: url.clone(),
+                    username: username.clone(),
+                    credential: credential.clone(),
                 })
             }
             _ => None,
@@ -83,18 +73,15 @@ impl RtcConfig {
             return Self::disabled(max_participants, turn);
         }
 
-        let public_ip = match std::env::var("RTC_PUBLIC_IP") {
-            Ok(value) if !value.trim().is_empty() => value,
+        let public_ip = match &config.public_ip {
+            Some(value) if !value.trim().is_empty() => value.clone(),
             _ => {
                 warn!("RTC_ENABLE set but RTC_PUBLIC_IP missing; disabling SFU");
                 return Self::disabled(max_participants, turn);
             }
         };
 
-        let udp_mux_port = match std::env::var("RTC_UDP_MUX_PORT")
-            .ok()
-            .and_then(|value| value.trim().parse::<u16>().ok())
-        {
+        let udp_mux_port = match config.udp_mux_port {
             Some(port) if port != 0 => port,
             _ => {
                 warn!("RTC_ENABLE set but RTC_UDP_MUX_PORT missing/invalid; disabling SFU");
@@ -1,9 +1,20 @@
+pub mod access_log;
+pub mod album;
+pub mod alert;
+pub mod audit;
 pub mod auth;
 pub mod blog;
 pub mod country;
 pub mod domain_traits;
+pub mod email;
 pub mod geo;
 pub mod i18n;
+pub mod job;
 pub mod live_chat;
+pub mod notification;
 pub mod photography;
+pub mod soft_delete;
+pub mod stats;
+pub mod upload_progress;
 pub mod wasm_module;
+pub mod webhook;
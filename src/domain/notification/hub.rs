@@ -0,0 +1,62 @@
+//! Per-user fan-out of notifications to open `/ws/notifications` sockets.
+//!
+//! Each user with at least one socket has a broadcast channel, so every tab
+//! gets every notification. The hub only delivers live; a user with no socket
+//! open on this instance reads the stored copy from `GET /api/notifications`.
+
+use std::sync::Arc;
+
+use scc::hash_map::Entry;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use super::notification::NotificationEnvelope;
+
+/// Per-user channel capacity; a socket further behind than this skips ahead.
+pub const NOTIFICATION_CHANNEL_CAPACITY: usize = 32;
+
+pub struct NotificationHub {
+    channels: scc::HashMap<Uuid, broadcast::Sender<Arc<NotificationEnvelope>>>,
+}
+
+impl NotificationHub {
+    pub fn new() -> Self {
+        Self {
+            channels: scc::HashMap::new(),
+        }
+    }
+
+    pub async fn subscribe(&self, user_id: Uuid) -> broadcast::Receiver<Arc<NotificationEnvelope>> {
+        match self.channels.entry_async(user_id).await {
+            Entry::Occupied(occ) => occ.get().subscribe(),
+            Entry::Vacant(vac) => {
+                let (sender, receiver) = broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY);
+                vac.insert_entry(sender);
+                receiver
+            }
+        }
+    }
+
+    /// Call after dropping a socket's receiver; removes the user's channel
+    /// once no socket listens on it.
+    pub async fn unsubscribe(&self, user_id: Uuid) {
+        self.channels
+            .remove_if_async(&user_id, |sender| sender.receiver_count() == 0)
+            .await;
+    }
+
+    /// Returns whether any socket received the notification.
+    pub async fn notify(&self, user_id: Uuid, envelope: NotificationEnvelope) -> bool {
+        let envelope = Arc::new(envelope);
+        self.channels
+            .read_async(&user_id, |_, sender| sender.send(envelope).is_ok())
+            .await
+            .unwrap_or(false)
+    }
+}
+
+impl Default for NotificationHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,4 @@
+pub mod hub;
+#[allow(clippy::module_inception)]
+pub mod notification;
+pub mod preferences;
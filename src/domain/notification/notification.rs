@@ -0,0 +1,150 @@
+//! Notifications sent to a signed-in user: stored in `notifications` for
+//! `GET /api/notifications` and pushed over `GET /ws/notifications`.
+
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::notifications;
+
+/// Where a comment lives: blog posts and photographs have separate threads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CommentLocation {
+    Post { post_id: Uuid },
+    Photograph { photograph_id: Uuid },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Notification {
+    /// `@user_name` in a new comment.
+    Mention {
+        location: CommentLocation,
+        comment_id: Uuid,
+        by_user_name: String,
+    },
+    /// A new comment answering one of the user's comments.
+    CommentReply {
+        location: CommentLocation,
+        comment_id: Uuid,
+        parent_comment_id: Uuid,
+        by_user_name: String,
+    },
+    /// A superuser deleted one of the user's comments.
+    CommentRemoved {
+        location: CommentLocation,
+        comment_id: Uuid,
+    },
+    /// Someone upvoted the user's post or photograph, or with `comment_id`
+    /// one of their comments on it. Voters are not named; further upvotes
+    /// of the same thing collapse into the unread notification.
+    Upvote {
+        location: CommentLocation,
+        comment_id: Option<Uuid>,
+    },
+    /// The user was banned from live chat; `expires_at` is null for a
+    /// permanent ban.
+    LiveChatBanned {
+        reason: String,
+        expires_at: Option<DateTime<Utc>>,
+    },
+}
+
+impl Notification {
+    /// Stored name, as in `notifications.notification_type`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Mention { .. } => "mention",
+            Self::CommentReply { .. } => "comment_reply",
+            Self::CommentRemoved { .. } => "comment_removed",
+            Self::Upvote { .. } => "upvote",
+            Self::LiveChatBanned { .. } => "live_chat_banned",
+        }
+    }
+}
+
+/// The frame sent over the socket: the notification plus its id and time.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct NotificationEnvelope {
+    pub notification_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub notification: Notification,
+}
+
+impl NotificationEnvelope {
+    pub fn new(notification: Notification) -> Self {
+        Self {
+            notification_id: Uuid::now_v7(),
+            created_at: Utc::now(),
+            notification,
+        }
+    }
+
+    pub fn insertable(&self, user_id: Uuid) -> anyhow::Result<NotificationInsertable> {
+        Ok(NotificationInsertable {
+            notification_id: self.notification_id,
+            user_id,
+            notification_type: self.notification.kind().to_string(),
+            notification_payload: serde_json::to_value(&self.notification)?,
+            notification_created_at: self.created_at,
+        })
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = notifications)]
+pub struct NotificationInsertable {
+    pub notification_id: Uuid,
+    pub user_id: Uuid,
+    pub notification_type: String,
+    pub notification_payload: serde_json::Value,
+    pub notification_created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = notifications)]
+pub struct NotificationRow {
+    pub notification_id: Uuid,
+    pub notification_payload: serde_json::Value,
+    pub notification_created_at: DateTime<Utc>,
+    pub notification_read_at: Option<DateTime<Utc>>,
+}
+
+/// A stored notification as listed by `GET /api/notifications`: the socket
+/// frame plus when it was read.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotificationItem {
+    pub notification_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// `None` while unread.
+    pub read_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub notification: Notification,
+}
+
+impl TryFrom<NotificationRow> for NotificationItem {
+    type Error = serde_json::Error;
+
+    fn try_from(row: NotificationRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            notification_id: row.notification_id,
+            created_at: row.notification_created_at,
+            read_at: row.notification_read_at,
+            notification: serde_json::from_value(row.notification_payload)?,
+        })
+    }
+}
+
+/// A freshly submitted comment, as far as notifications are concerned.
+pub struct PostedComment {
+    pub location: CommentLocation,
+    pub comment_id: Uuid,
+    pub parent_comment_id: Option<Uuid>,
+    pub author_id: Uuid,
+    pub author_name: String,
+    pub content: String,
+}
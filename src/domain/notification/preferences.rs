@@ -0,0 +1,91 @@
+//! Per-user notification switches (`user_notification_preferences`).
+
+use diesel::{Queryable, Selectable};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::notification::Notification;
+use crate::schema::user_notification_preferences;
+
+/// What a user wants to hear about. Users without a stored row get
+/// [`Default`]: mentions and replies on, digest off.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Queryable, Selectable, Serialize, Deserialize, ToSchema,
+)]
+#[diesel(table_name = user_notification_preferences)]
+pub struct NotificationPreferences {
+    /// `mention` notifications.
+    #[diesel(column_name = notify_mentions)]
+    pub mentions: bool,
+    /// `comment_reply` notifications.
+    #[diesel(column_name = notify_replies)]
+    pub replies: bool,
+    /// A daily email listing how many notifications are still unread.
+    #[diesel(column_name = notify_digest)]
+    pub digest: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            mentions: true,
+            replies: true,
+            digest: false,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    /// Whether `notification` should be stored and pushed at all. Upvotes and
+    /// moderation notices cannot be turned off.
+    pub fn allows(&self, notification: &Notification) -> bool {
+        match notification {
+            Notification::Mention { .. } => self.mentions,
+            Notification::CommentReply { .. } => self.replies,
+            Notification::CommentRemoved { .. }
+            | Notification::Upvote { .. }
+            | Notification::LiveChatBanned { .. } => true,
+        }
+    }
+}
+
+/// A user due a digest email: opted in, with unread notifications newer
+/// than their last digest.
+#[derive(Debug, Clone)]
+pub struct DigestRecipient {
+    pub user_id: Uuid,
+    pub user_email: String,
+    pub unread: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::notification::notification::CommentLocation;
+
+    #[test]
+    fn switches_gate_only_their_own_kind() {
+        let location = CommentLocation::Post {
+            post_id: Uuid::nil(),
+        };
+        let mention = Notification::Mention {
+            location,
+            comment_id: Uuid::nil(),
+            by_user_name: "a".into(),
+        };
+        let upvote = Notification::Upvote {
+            location,
+            comment_id: None,
+        };
+
+        let quiet = NotificationPreferences {
+            mentions: false,
+            replies: false,
+            digest: false,
+        };
+        assert!(!quiet.allows(&mention));
+        assert!(quiet.allows(&upvote));
+        assert!(NotificationPreferences::default().allows(&mention));
+    }
+}
@@ -0,0 +1,60 @@
+//! Full-resolution original downloads and their audit trail
+//! (`photograph_download_events`).
+
+use chrono::{DateTime, Utc};
+use diesel::Insertable;
+use ipnet::IpNet;
+use uuid::Uuid;
+
+use crate::{schema::photograph_download_events, util::storage::StorageBackend};
+
+use super::photographs::Photograph;
+
+/// How the original reached the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadMethod {
+    /// Redirect to a presigned storage URL.
+    Redirect,
+    /// Proxied through this server.
+    Stream,
+}
+
+impl DownloadMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Redirect => "redirect",
+            Self::Stream => "stream",
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = photograph_download_events)]
+pub struct PhotographDownloadEventInsertable {
+    pub photograph_download_event_id: Uuid,
+    pub photograph_id: Uuid,
+    pub user_id: Uuid,
+    pub photograph_download_object_key: String,
+    pub photograph_download_method: String,
+    pub photograph_download_ip: Option<IpNet>,
+    pub photograph_downloaded_at: DateTime<Utc>,
+}
+
+/// Storage key of the best available original, most faithful first: the
+/// private unwatermarked original, then the published RAW, then the main
+/// encoded image (non-RAW, unwatermarked uploads keep nothing else).
+pub fn original_object_key(
+    photograph: &Photograph,
+    storage: &dyn StorageBackend,
+) -> Option<String> {
+    photograph
+        .photograph_private_original_key
+        .clone()
+        .or_else(|| {
+            photograph
+                .photograph_raw_link
+                .as_deref()
+                .and_then(|link| storage.key_from_url(link))
+        })
+        .or_else(|| storage.key_from_url(&photograph.photograph_link))
+}
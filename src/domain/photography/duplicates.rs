@@ -0,0 +1,157 @@
+//! Near-duplicate photograph detection on top of the stored perceptual hash
+//! (`photographs.photograph_phash`, see `util::image::phash`).
+//!
+//! Uploads consult [`DuplicatePolicy`]: `warn` records the closest existing
+//! match in `photograph_duplicate_of`, `reject` fails the upload. Only ready
+//! gallery (`photography` context) photographs are compared; editor images are
+//! routinely reused across posts.
+
+use diesel::{QueryResult, QueryableByName, sql_query, sql_types};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::init::app_config::ImagesSection;
+use crate::util::image::phash::hamming_distance;
+
+/// Default Hamming distance (of 64 bits) at or below which two photographs
+/// are treated as near-duplicates.
+pub const DEFAULT_DUPLICATE_THRESHOLD: u32 = 6;
+/// Upper bound accepted from env and the admin endpoint; beyond this nearly
+/// everything "matches".
+pub const MAX_DUPLICATE_THRESHOLD: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateAction {
+    Off,
+    Warn,
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DuplicatePolicy {
+    pub action: DuplicateAction,
+    pub threshold: u32,
+}
+
+impl DuplicatePolicy {
+    /// `images.duplicate_policy` = `off` | `warn` (default) | `reject`,
+    /// `images.duplicate_threshold` = max Hamming distance (default 6).
+    pub fn from_config(config: &ImagesSection) -> Self {
+        let action = match config.duplicate_policy.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" | "disabled" => DuplicateAction::Off,
+            "reject" | "deny" => DuplicateAction::Reject,
+            _ => DuplicateAction::Warn,
+        };
+        let threshold = config.duplicate_threshold.min(MAX_DUPLICATE_THRESHOLD);
+        Self { action, threshold }
+    }
+}
+
+/// Stored hashes are the `u64` bit pattern reinterpreted as `bigint`.
+pub fn phash_to_db(hash: u64) -> i64 {
+    hash as i64
+}
+
+pub fn phash_from_db(hash: i64) -> u64 {
+    hash as u64
+}
+
+#[derive(QueryableByName)]
+struct NearestMatchRow {
+    #[diesel(sql_type = sql_types::Uuid)]
+    photograph_id: Uuid,
+    #[diesel(sql_type = sql_types::Integer)]
+    distance: i32,
+}
+
+/// Closest ready gallery photograph within `threshold` bits of `hash`, if any.
+pub async fn find_near_duplicate(
+    conn: &mut AsyncPgConnection,
+    hash: u64,
+    threshold: u32,
+) -> QueryResult<Option<(Uuid, u32)>> {
+    // Popcount of the XOR via the bit-string text form (works on every
+    // supported Postgres version, unlike `bit_count`).
+    let rows: Vec<NearestMatchRow> = sql_query(
+        "SELECT photograph_id, distance FROM ( \
+            SELECT photograph_id, \
+                length(replace(((photograph_phash # $1)::bit(64))::text, '0', ''))::int4 \
+                    AS distance \
+            FROM photographs \
+            WHERE photograph_phash IS NOT NULL \
+              AND photograph_context = 'photography' \
+              AND photograph_processing_status = 'ready' \
+              AND photograph_deleted_at IS NULL \
+         ) d \
+         WHERE distance <= $2 \
+         ORDER BY distance ASC \
+         LIMIT 1",
+    )
+    .bind::<sql_types::BigInt, _>(phash_to_db(hash))
+    .bind::<sql_types::Integer, _>(threshold as i32)
+    .load(conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .next()
+        .map(|row| (row.photograph_id, row.distance.max(0) as u32)))
+}
+
+/// Group hashes into clusters of near-duplicates (single linkage: any pair
+/// within `threshold` joins their clusters). Returns index groups into
+/// `hashes` with at least two members, largest first.
+pub fn cluster_hashes(hashes: &[u64], threshold: u32) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..hashes.len()).collect();
+
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..hashes.len() {
+        for j in (i + 1)..hashes.len() {
+            if hamming_distance(hashes[i], hashes[j]) <= threshold {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                if a != b {
+                    parent[b] = a;
+                }
+            }
+        }
+    }
+
+    let mut groups: std::collections::HashMap<usize, Vec<usize>> = std::collections::HashMap::new();
+    for i in 0..hashes.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    let mut clusters: Vec<Vec<usize>> = groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .collect();
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clusters_near_duplicates_by_single_linkage() {
+        let hashes = [
+            0b0000_0000,
+            u64::MAX,
+            0b0000_0011, // 2 bits from [0]
+            0b0000_1111, // 2 bits from [2], 4 from [0]
+            u64::MAX ^ 1,
+            0xF0F0_F0F0,
+        ];
+        assert_eq!(cluster_hashes(&hashes, 2), vec![vec![0, 2, 3], vec![1, 4]]);
+        assert!(cluster_hashes(&hashes, 0).is_empty());
+    }
+}
@@ -1,3 +1,7 @@
 pub mod batch;
+pub mod downloads;
+pub mod duplicates;
 pub mod photographs;
+pub mod presigned_upload;
 pub mod social;
+pub mod tags;
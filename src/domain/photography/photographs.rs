@@ -3,22 +3,29 @@ use diesel::FromSqlRow;
 use diesel::deserialize::{FromSql, Result as DeserializeResult};
 use diesel::expression::AsExpression;
 use diesel::pg::{Pg, PgValue};
-use diesel::prelude::{Insertable, Queryable, QueryableByName};
+use diesel::prelude::{AsChangeset, Insertable, Queryable, QueryableByName, Selectable};
 use diesel::query_builder::QueryId;
 use diesel::serialize::{IsNull, Output, ToSql};
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::io::Write;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::schema::photographs;
 use crate::schema::sql_types::PhotographContext as PhotographContextSql;
+use crate::schema::sql_types::PhotographProcessingStatus as PhotographProcessingStatusSql;
 
 impl QueryId for PhotographContextSql {
     type QueryId = PhotographContextSql;
     const HAS_STATIC_QUERY_ID: bool = true;
 }
 
+impl QueryId for PhotographProcessingStatusSql {
+    type QueryId = PhotographProcessingStatusSql;
+    const HAS_STATIC_QUERY_ID: bool = true;
+}
+
 #[derive(
     Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq, AsExpression, FromSqlRow,
 )]
@@ -60,6 +67,51 @@ impl FromSql<PhotographContextSql, Pg> for PhotographContext {
     }
 }
 
+/// Lifecycle of the encoded derivatives (AVIF main image + thumbnail) of a
+/// photograph row. Only `Ready` rows have usable links and are listed publicly.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq, AsExpression, FromSqlRow,
+)]
+#[serde(rename_all = "snake_case")]
+#[diesel(sql_type = PhotographProcessingStatusSql)]
+pub enum PhotographProcessingStatus {
+    Pending,
+    Processing,
+    Ready,
+    Failed,
+}
+
+impl PhotographProcessingStatus {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Ready | Self::Failed)
+    }
+}
+
+impl ToSql<PhotographProcessingStatusSql, Pg> for PhotographProcessingStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        let value = match self {
+            PhotographProcessingStatus::Pending => "pending",
+            PhotographProcessingStatus::Processing => "processing",
+            PhotographProcessingStatus::Ready => "ready",
+            PhotographProcessingStatus::Failed => "failed",
+        };
+        out.write_all(value.as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<PhotographProcessingStatusSql, Pg> for PhotographProcessingStatus {
+    fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+        match bytes.as_bytes() {
+            b"pending" => Ok(PhotographProcessingStatus::Pending),
+            b"processing" => Ok(PhotographProcessingStatus::Processing),
+            b"ready" => Ok(PhotographProcessingStatus::Ready),
+            b"failed" => Ok(PhotographProcessingStatus::Failed),
+            _ => Err("Unrecognized photograph_processing_status enum value".into()),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, QueryableByName, Queryable, ToSchema)]
 #[diesel(table_name = photographs)]
 pub struct Photograph {
@@ -79,6 +131,101 @@ pub struct Photograph {
     pub photograph_view_count: i64,
     pub photograph_total_upvotes: i64,
     pub photograph_total_downvotes: i64,
+    pub album_id: Option<Uuid>,
+    pub photograph_album_position: i32,
+    pub photograph_camera_make: Option<String>,
+    pub photograph_camera_model: Option<String>,
+    pub photograph_lens_model: Option<String>,
+    pub photograph_focal_length_mm: Option<f64>,
+    pub photograph_aperture_f_number: Option<f64>,
+    pub photograph_exposure_time_seconds: Option<f64>,
+    pub photograph_iso: Option<i32>,
+    pub photograph_processing_status: PhotographProcessingStatus,
+    pub photograph_processing_error: Option<String>,
+    /// JSONB map of rendition size (`small`/`medium`/`large`) to
+    /// [`PhotographRendition`]; `{}` for rows uploaded before renditions.
+    #[schema(value_type = Object)]
+    pub photograph_renditions: serde_json::Value,
+    /// `cr2`/`nef`/`arw` when the original was a camera RAW.
+    pub photograph_raw_format: Option<String>,
+    /// S3 link of the untouched RAW original; set once processing finishes.
+    pub photograph_raw_link: Option<String>,
+    /// Whether the published derivatives carry the configured watermark.
+    pub photograph_watermarked: bool,
+    /// S3 key of the unwatermarked original under `private/originals/`.
+    #[serde(skip_serializing)]
+    pub photograph_private_original_key: Option<String>,
+    /// Perceptual hash (u64 bit pattern); `None` until processed.
+    pub photograph_phash: Option<i64>,
+    /// Closest near-duplicate found at ingest time under the `warn` policy.
+    pub photograph_duplicate_of: Option<Uuid>,
+    /// Set while the photograph is soft-deleted; its objects stay in storage
+    /// until the purge.
+    pub photograph_deleted_at: Option<DateTime<Utc>>,
+    /// Declared MIME type of an upload accepted for queued processing.
+    #[serde(skip_serializing)]
+    pub photograph_upload_content_type: Option<String>,
+}
+
+impl Photograph {
+    /// Typed view of `photograph_renditions`; malformed JSON yields an empty map.
+    pub fn renditions(&self) -> PhotographRenditions {
+        serde_json::from_value(self.photograph_renditions.clone()).unwrap_or_default()
+    }
+}
+
+/// The stored objects behind a photograph row, loaded before it is removed.
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = photographs)]
+pub struct PhotographObjects {
+    pub photograph_link: String,
+    pub photograph_thumbnail_link: String,
+    pub photograph_renditions: serde_json::Value,
+    pub photograph_raw_link: Option<String>,
+    pub photograph_private_original_key: Option<String>,
+}
+
+impl PhotographObjects {
+    /// Storage keys of every object; `key_from_url` maps a public link to its
+    /// key. The private original is stored as a bare key already.
+    pub fn storage_keys(self, key_from_url: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        keys.extend(key_from_url(&self.photograph_link));
+        keys.extend(self.photograph_private_original_key);
+        keys.extend(self.photograph_raw_link.as_deref().and_then(&key_from_url));
+        keys.extend(key_from_url(&self.photograph_thumbnail_link));
+        let renditions: PhotographRenditions =
+            serde_json::from_value(self.photograph_renditions).unwrap_or_default();
+        keys.extend(
+            renditions
+                .values()
+                .filter_map(|rendition| key_from_url(&rendition.url)),
+        );
+        keys
+    }
+}
+
+/// One responsive rendition of a photograph.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct PhotographRendition {
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Rendition size name -> rendition, ordered by name for stable JSON.
+pub type PhotographRenditions = BTreeMap<String, PhotographRendition>;
+
+/// `srcset` attribute value (`url 400w, url 800w, ...`), narrowest first.
+pub fn renditions_srcset(renditions: &PhotographRenditions) -> String {
+    let mut items: Vec<&PhotographRendition> = renditions.values().collect();
+    items.sort_by_key(|r| r.width);
+    items.dedup_by_key(|r| r.width);
+    items
+        .iter()
+        .map(|r| format!("{} {}w", r.url, r.width))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 #[derive(Insertable)]
@@ -94,4 +241,29 @@ pub struct PhotographInsertable {
     pub photograph_lat: f64,
     pub photograph_lon: f64,
     pub photograph_thumbnail_link: String,
+    #[diesel(embed)]
+    pub photograph_exif: PhotographExif,
+    pub photograph_processing_status: PhotographProcessingStatus,
+    pub photograph_renditions: serde_json::Value,
+    pub photograph_raw_format: Option<String>,
+    pub photograph_raw_link: Option<String>,
+    pub photograph_watermarked: bool,
+    pub photograph_private_original_key: Option<String>,
+    pub photograph_phash: Option<i64>,
+    pub photograph_duplicate_of: Option<Uuid>,
+    pub photograph_upload_content_type: Option<String>,
+}
+
+/// Camera/exposure EXIF fields captured at upload. Every field is optional;
+/// missing or unparseable tags stay `None`.
+#[derive(Debug, Clone, Default, PartialEq, Insertable, AsChangeset)]
+#[diesel(table_name = photographs)]
+pub struct PhotographExif {
+    pub photograph_camera_make: Option<String>,
+    pub photograph_camera_model: Option<String>,
+    pub photograph_lens_model: Option<String>,
+    pub photograph_focal_length_mm: Option<f64>,
+    pub photograph_aperture_f_number: Option<f64>,
+    pub photograph_exposure_time_seconds: Option<f64>,
+    pub photograph_iso: Option<i32>,
 }
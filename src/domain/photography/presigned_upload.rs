@@ -0,0 +1,38 @@
+//! In-memory record of an outstanding presigned photograph upload.
+//!
+//! `POST /api/photographs/presign` mints an upload token and a presigned S3
+//! `PUT` URL for a staging key under [`PRESIGNED_UPLOAD_PREFIX`]. The client
+//! uploads the original directly to S3, then calls the confirm endpoint with
+//! the token, at which point the server pulls the staged object back, runs the
+//! normal encode/thumbnail/insert path, and deletes the staging object.
+//!
+//! Like batch sessions, these records are intentionally ephemeral: a restart
+//! loses outstanding tokens and the staged objects are reaped by the prune job
+//! (or an S3 lifecycle rule on the prefix).
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Bucket-relative prefix for originals uploaded via presigned URLs.
+pub const PRESIGNED_UPLOAD_PREFIX: &str = "uploads/";
+
+/// How long a presigned `PUT` URL (and its upload token) stays valid.
+pub const PRESIGNED_UPLOAD_TTL_SECONDS: i64 = 15 * 60;
+
+/// An upload token that has been issued but not yet confirmed.
+#[derive(Debug, Clone)]
+pub struct PresignedUpload {
+    pub upload_token: Uuid,
+    pub owner: Uuid,
+    pub object_key: String,
+    pub content_type: String,
+    pub file_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PresignedUpload {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
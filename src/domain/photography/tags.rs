@@ -0,0 +1,147 @@
+//! Photograph tags. Tag names live in the shared `tags` table (the same
+//! vocabulary blog posts use); `photograph_tags` holds the many-to-many links.
+
+use std::collections::{HashMap, HashSet};
+
+use diesel::{ExpressionMethods, Insertable, QueryDsl, Queryable, Selectable};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde_derive::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::blog::NewTag,
+    schema::{photograph_tags, tags},
+};
+
+#[derive(Clone, Serialize, Queryable, Selectable)]
+#[diesel(table_name = photograph_tags)]
+pub struct PhotographTag {
+    pub photograph_id: Uuid,
+    pub tag_id: i16,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = photograph_tags)]
+pub struct NewPhotographTag {
+    pub photograph_id: Uuid,
+    pub tag_id: i16,
+}
+
+/// Trim, lowercase, drop empties and dedupe while keeping first-seen order
+/// (same normalization as blog post tags).
+pub fn normalize_tags<S: AsRef<str>>(raw: &[S]) -> Vec<String> {
+    let mut seen: HashSet<String> = HashSet::new();
+    raw.iter()
+        .map(|tag| tag.as_ref().trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .filter(|tag| seen.insert(tag.clone()))
+        .collect()
+}
+
+/// Split a comma-separated tag list (multipart field / query string value).
+pub fn parse_tag_list(raw: &str) -> Vec<String> {
+    normalize_tags(&raw.split(',').collect::<Vec<&str>>())
+}
+
+impl PhotographTag {
+    /// Replace a photograph's tags with `tag_names` (already normalized),
+    /// creating missing `tags` rows. Run inside a transaction.
+    pub async fn replace_for_photograph(
+        conn: &mut AsyncPgConnection,
+        photograph_id: Uuid,
+        tag_names: &[String],
+    ) -> Result<(), diesel::result::Error> {
+        diesel::delete(
+            photograph_tags::table.filter(photograph_tags::photograph_id.eq(photograph_id)),
+        )
+        .execute(conn)
+        .await?;
+
+        if tag_names.is_empty() {
+            return Ok(());
+        }
+
+        let new_tags: Vec<NewTag<'_>> = tag_names.iter().map(|tag| NewTag::new(tag)).collect();
+
+        diesel::insert_into(tags::table)
+            .values(&new_tags)
+            .on_conflict(tags::tag_name)
+            .do_nothing()
+            .execute(conn)
+            .await?;
+
+        let tag_ids: Vec<i16> = tags::table
+            .filter(tags::tag_name.eq_any(tag_names))
+            .select(tags::tag_id)
+            .load(conn)
+            .await?;
+
+        let links: Vec<NewPhotographTag> = tag_ids
+            .into_iter()
+            .map(|tag_id| NewPhotographTag {
+                photograph_id,
+                tag_id,
+            })
+            .collect();
+
+        diesel::insert_into(photograph_tags::table)
+            .values(&links)
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Tag names for each of the given photographs. Untagged photographs are
+    /// absent from the map.
+    pub async fn names_for_photographs(
+        conn: &mut AsyncPgConnection,
+        photograph_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<String>>, diesel::result::Error> {
+        if photograph_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(Uuid, String)> = photograph_tags::table
+            .inner_join(tags::table)
+            .filter(photograph_tags::photograph_id.eq_any(photograph_ids))
+            .order(tags::tag_name.asc())
+            .select((photograph_tags::photograph_id, tags::tag_name))
+            .load(conn)
+            .await?;
+
+        let mut by_photograph: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for (photograph_id, tag_name) in rows {
+            by_photograph
+                .entry(photograph_id)
+                .or_default()
+                .push(tag_name);
+        }
+        Ok(by_photograph)
+    }
+
+    /// Ids of photographs carrying every one of `tag_names` (already
+    /// normalized and deduplicated).
+    pub async fn photograph_ids_with_all(
+        conn: &mut AsyncPgConnection,
+        tag_names: &[String],
+    ) -> Result<Vec<Uuid>, diesel::result::Error> {
+        let rows: Vec<Uuid> = photograph_tags::table
+            .inner_join(tags::table)
+            .filter(tags::tag_name.eq_any(tag_names))
+            .select(photograph_tags::photograph_id)
+            .load(conn)
+            .await?;
+
+        let mut hits: HashMap<Uuid, usize> = HashMap::new();
+        for photograph_id in rows {
+            *hits.entry(photograph_id).or_default() += 1;
+        }
+
+        Ok(hits
+            .into_iter()
+            .filter(|(_, count)| *count == tag_names.len())
+            .map(|(photograph_id, _)| photograph_id)
+            .collect())
+    }
+}
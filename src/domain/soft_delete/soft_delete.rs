@@ -0,0 +1,47 @@
+//! Soft delete for posts, comments and photographs. Deleting sets the row's
+//! `*_deleted_at`; every read skips such rows, `POST
+//! /api/admin/trash/{kind}/{id}/restore` clears it again, and the
+//! PURGE_SOFT_DELETED job removes rows (and a photograph's stored objects)
+//! once they have been deleted for `SOFT_DELETE_RETENTION_DAYS`.
+
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::audit::audit::AdminAction;
+
+/// What can be soft-deleted, as named in the restore path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum SoftDeleteKind {
+    #[serde(rename = "posts")]
+    Post,
+    #[serde(rename = "comments")]
+    Comment,
+    #[serde(rename = "photographs")]
+    Photograph,
+}
+
+impl SoftDeleteKind {
+    pub fn restore_action(self) -> AdminAction {
+        match self {
+            Self::Post => AdminAction::PostRestore,
+            Self::Comment => AdminAction::CommentRestore,
+            Self::Photograph => AdminAction::PhotographRestore,
+        }
+    }
+}
+
+/// Rows removed by one PURGE_SOFT_DELETED run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SoftDeletePurge {
+    pub posts: usize,
+    pub comments: usize,
+    pub photographs: usize,
+    /// Stored photograph objects deleted alongside the rows.
+    pub objects: usize,
+}
+
+impl SoftDeletePurge {
+    pub fn total_rows(&self) -> usize {
+        self.posts + self.comments + self.photographs
+    }
+}
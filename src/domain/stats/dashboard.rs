@@ -0,0 +1,159 @@
+//! The admin overview (`GET /api/admin/stats`): site totals, what was added
+//! in a chosen range and daily signups and visits over it. Aggregated in SQL
+//! and kept per range in [`DashboardStatsCache`] for a short while, since the
+//! page is reloaded far more often than the numbers move.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde_derive::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+
+/// How far back the range totals and daily series go, today included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ToSchema)]
+pub enum StatsRange {
+    #[serde(rename = "7d")]
+    Week,
+    #[default]
+    #[serde(rename = "30d")]
+    Month,
+    #[serde(rename = "90d")]
+    Quarter,
+    #[serde(rename = "365d")]
+    Year,
+}
+
+impl StatsRange {
+    pub fn days(self) -> u64 {
+        match self {
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::Quarter => 90,
+            Self::Year => 365,
+        }
+    }
+
+    /// First day of the range ending on `today`.
+    pub fn first_day(self, today: NaiveDate) -> NaiveDate {
+        today - Days::new(self.days() - 1)
+    }
+}
+
+/// All-time counts. Soft-deleted posts, comments and photographs are left
+/// out.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DashboardTotals {
+    pub users: i64,
+    pub verified_users: i64,
+    pub posts: i64,
+    pub published_posts: i64,
+    pub comments: i64,
+    pub photographs: i64,
+}
+
+/// Counts of what was created within the range.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RangeTotals {
+    pub new_users: i64,
+    pub new_posts: i64,
+    pub new_comments: i64,
+    pub new_photographs: i64,
+    /// Rows in `visitation_data`, one per recorded visit.
+    pub visits: i64,
+}
+
+/// One UTC day of the series.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DailyStat {
+    pub date: NaiveDate,
+    pub signups: i64,
+    pub visits: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DashboardStats {
+    pub range: StatsRange,
+    /// First and last (today) UTC day of the range.
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub totals: DashboardTotals,
+    pub in_range: RangeTotals,
+    /// Every day from `from` to `to`, days without activity included.
+    pub daily: Vec<DailyStat>,
+    /// When the numbers were aggregated; they may be this old.
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Zero-filled series from `from` to `to` out of per-day `signups` and
+/// `visits` counts, which list only the days that had any.
+pub fn daily_series(
+    from: NaiveDate,
+    to: NaiveDate,
+    signups: &[(NaiveDate, i64)],
+    visits: &[(NaiveDate, i64)],
+) -> Vec<DailyStat> {
+    let signups: HashMap<_, _> = signups.iter().copied().collect();
+    let visits: HashMap<_, _> = visits.iter().copied().collect();
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| DailyStat {
+            date,
+            signups: signups.get(&date).copied().unwrap_or(0),
+            visits: visits.get(&date).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+/// The last aggregation per range.
+#[derive(Default)]
+pub struct DashboardStatsCache {
+    entries: RwLock<HashMap<StatsRange, Arc<DashboardStats>>>,
+}
+
+impl DashboardStatsCache {
+    /// The cached stats for `range` if computed within `max_age` and still
+    /// for today's range.
+    pub async fn get(
+        &self,
+        range: StatsRange,
+        max_age: chrono::Duration,
+        now: DateTime<Utc>,
+    ) -> Option<Arc<DashboardStats>> {
+        let entries = self.entries.read().await;
+        entries
+            .get(&range)
+            .filter(|stats| now - stats.computed_at < max_age && stats.to == now.date_naive())
+            .cloned()
+    }
+
+    pub async fn put(&self, stats: Arc<DashboardStats>) {
+        self.entries.write().await.insert(stats.range, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_covers_every_day_of_the_range() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let from = StatsRange::Week.first_day(today);
+        assert_eq!(from, NaiveDate::from_ymd_opt(2026, 2, 23).unwrap());
+
+        let series = daily_series(from, today, &[(today, 2)], &[(from, 5), (today, 1)]);
+        assert_eq!(series.len(), 7);
+        assert_eq!(series[0].visits, 5);
+        assert_eq!(series[0].signups, 0);
+        assert_eq!(
+            series[6],
+            DailyStat {
+                date: today,
+                signups: 2,
+                visits: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Live progress of large uploads (gallery photographs, WASM bundles), pushed
+//! to the admin UI over `GET /api/uploads/{upload_id}/progress` (SSE).
+//!
+//! The client picks the upload id (a UUID), may subscribe first, and sends it
+//! as the `X-Upload-Id` header on the upload request. Each id is backed by a
+//! `watch` channel, so slow subscribers only ever see the latest state.
+//! Entries are owner-scoped and ephemeral: the prune job drops them shortly
+//! after they finish, or if no upload ever attaches.
+
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use tokio::sync::watch;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Header carrying the client-chosen upload id.
+pub const UPLOAD_ID_HEADER: &str = "x-upload-id";
+
+/// Finished entries stay subscribable this long, so a late subscriber still
+/// sees the outcome.
+pub const FINISHED_UPLOAD_PROGRESS_TTL_SECONDS: i64 = 60;
+/// Entries no upload attached to (or that stopped reporting) are dropped after
+/// this long.
+pub const IDLE_UPLOAD_PROGRESS_TTL_SECONDS: i64 = 10 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadKind {
+    Photograph,
+    WasmModule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadStage {
+    /// Subscribed, upload request not seen yet.
+    Waiting,
+    /// Request body arriving; see `bytes_received`.
+    Receiving,
+    /// Waiting for a processing slot (gallery photographs).
+    Queued,
+    /// Decoding/encoding/validating.
+    Processing,
+    /// Writing objects and rows.
+    Storing,
+    Done,
+    Failed,
+}
+
+impl UploadStage {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Done | Self::Failed)
+    }
+}
+
+/// One progress event.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UploadProgress {
+    pub upload_id: Uuid,
+    pub kind: Option<UploadKind>,
+    pub stage: UploadStage,
+    /// File bytes received so far.
+    pub bytes_received: u64,
+    /// Request `Content-Length` (the whole multipart body, so slightly more
+    /// than the file itself).
+    pub bytes_total: Option<u64>,
+    /// Photograph or WASM module id, once known.
+    pub resource_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UploadProgress {
+    pub fn waiting(upload_id: Uuid) -> Self {
+        Self {
+            upload_id,
+            kind: None,
+            stage: UploadStage::Waiting,
+            bytes_received: 0,
+            bytes_total: None,
+            resource_id: None,
+            error: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Registry entry: the owning user and the channel.
+pub struct UploadProgressEntry {
+    pub owner: Uuid,
+    pub sender: watch::Sender<UploadProgress>,
+}
+
+/// Reporting side handed to an upload. Dropping it before a terminal stage
+/// (handler error, client disconnect) reports `failed`.
+pub struct UploadProgressReporter {
+    sender: watch::Sender<UploadProgress>,
+}
+
+impl UploadProgressReporter {
+    pub fn new(sender: watch::Sender<UploadProgress>) -> Self {
+        Self { sender }
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut UploadProgress)) {
+        self.sender.send_modify(|progress| {
+            apply(progress);
+            progress.updated_at = Utc::now();
+        });
+    }
+
+    pub fn received(&self, bytes_received: u64) {
+        self.update(|p| p.bytes_received = bytes_received);
+    }
+
+    pub fn stage(&self, stage: UploadStage) {
+        self.update(|p| p.stage = stage);
+    }
+
+    pub fn resource(&self, resource_id: Uuid) {
+        self.update(|p| p.resource_id = Some(resource_id));
+    }
+
+    pub fn done(self, resource_id: Uuid) {
+        self.update(|p| {
+            p.stage = UploadStage::Done;
+            p.resource_id = Some(resource_id);
+        });
+    }
+
+    pub fn failed(self, error: impl Into<String>) {
+        let error = error.into();
+        self.update(|p| {
+            p.stage = UploadStage::Failed;
+            p.error = Some(error);
+        });
+    }
+}
+
+impl Drop for UploadProgressReporter {
+    fn drop(&mut self) {
+        if !self.sender.borrow().stage.is_terminal() {
+            self.update(|p| {
+                p.stage = UploadStage::Failed;
+                p.error.get_or_insert_with(|| "upload aborted".to_string());
+            });
+        }
+    }
+}
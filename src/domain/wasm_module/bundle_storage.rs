@@ -0,0 +1,69 @@
+//! Where WASM bundle bytes live.
+//!
+//! `WASM_BUNDLE_STORAGE` = `db` (default) keeps the gzipped bundle in
+//! `wasm_module.wasm_module_bundle_gz`; `storage` writes it through the
+//! configured `StorageBackend` under [`WASM_BUNDLE_KEY_PREFIX`] and leaves only
+//! the key, hash and size on the row. The setting only affects new uploads
+//! and replacements, so rows written under either mode keep working. Bundles
+//! in object storage are fetched on first serve rather than at startup.
+//!
+//! The Brotli copy made at upload time follows the same mode
+//! (`wasm_module_bundle_br` or `wasm_module_bundle_br_key`).
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::init::app_config::WasmSection;
+
+/// Bundles are private objects: access is checked by `serve_wasm`.
+pub const WASM_BUNDLE_KEY_PREFIX: &str = "private/wasm-bundles/";
+pub const WASM_BUNDLE_OBJECT_CONTENT_TYPE: &str = "application/gzip";
+pub const WASM_BUNDLE_BR_OBJECT_CONTENT_TYPE: &str = "application/x-brotli";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasmBundleStorage {
+    Database,
+    ObjectStorage,
+}
+
+impl WasmBundleStorage {
+    pub fn from_config(config: &WasmSection) -> Self {
+        match config.bundle_storage.trim().to_ascii_lowercase().as_str() {
+            "storage" | "s3" | "object" => Self::ObjectStorage,
+            _ => Self::Database,
+        }
+    }
+}
+
+/// Hex SHA-256 of the gzipped bundle, as stored in `wasm_module_bundle_sha256`.
+pub fn bundle_sha256(gz_bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(gz_bytes))
+}
+
+/// Content-addressed, so a replacement never overwrites the object a running
+/// request may still be reading.
+pub fn bundle_key(wasm_module_id: Uuid, sha256: &str) -> String {
+    format!("{WASM_BUNDLE_KEY_PREFIX}{wasm_module_id}/{sha256}.gz")
+}
+
+/// Key of the Brotli copy; `sha256` is still the gzip hash.
+pub fn bundle_br_key(wasm_module_id: Uuid, sha256: &str) -> String {
+    format!("{WASM_BUNDLE_KEY_PREFIX}{wasm_module_id}/{sha256}.br")
+}
+
+/// Column values for a freshly normalized bundle.
+pub struct StoredWasmBundle {
+    pub bundle_gz: Option<Vec<u8>>,
+    pub bundle_key: Option<String>,
+    pub bundle_br: Option<Vec<u8>>,
+    pub bundle_br_key: Option<String>,
+    pub sha256: String,
+    pub size_bytes: i64,
+}
+
+impl StoredWasmBundle {
+    /// Objects written for this bundle (none in DB mode).
+    pub fn object_keys(&self) -> impl Iterator<Item = &String> {
+        self.bundle_key.iter().chain(&self.bundle_br_key)
+    }
+}
@@ -0,0 +1,47 @@
+//! Files of multi-file WASM modules (see `util::wasm_archive`).
+//!
+//! Each file is its own object under the module's bundle prefix, keyed by
+//! content hash, so a replacement never overwrites an object a running
+//! request may still be reading and identical files share one object. The
+//! entry page is also stored as the module's regular bundle, so
+//! `/api/wasm-modules/{id}/wasm` keeps working; the module link points at the
+//! entry under `/files/` so relative URLs resolve against the file tree.
+
+use diesel::{Insertable, Queryable, Selectable};
+use uuid::Uuid;
+
+use crate::domain::wasm_module::bundle_storage::WASM_BUNDLE_KEY_PREFIX;
+use crate::schema::wasm_module_files;
+
+pub fn wasm_module_file_key(wasm_module_id: Uuid, sha256: &str) -> String {
+    format!("{WASM_BUNDLE_KEY_PREFIX}{wasm_module_id}/files/{sha256}")
+}
+
+/// Link of a module: its entry page under `/files/` for multi-file modules,
+/// the single bundle otherwise.
+pub fn wasm_module_link(wasm_module_id: Uuid, entry: Option<&str>) -> String {
+    match entry {
+        Some(entry) => format!("/api/wasm-modules/{wasm_module_id}/files/{entry}"),
+        None => format!("/api/wasm-modules/{wasm_module_id}/wasm"),
+    }
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = wasm_module_files)]
+pub struct WasmModuleFileInsertable {
+    pub wasm_module_id: Uuid,
+    pub wasm_module_file_path: String,
+    pub wasm_module_file_key: String,
+    pub wasm_module_file_content_type: String,
+    pub wasm_module_file_size_bytes: i64,
+    pub wasm_module_file_sha256: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = wasm_module_files)]
+pub struct WasmModuleFile {
+    pub wasm_module_file_key: String,
+    pub wasm_module_file_content_type: String,
+    pub wasm_module_file_size_bytes: i64,
+    pub wasm_module_file_sha256: String,
+}
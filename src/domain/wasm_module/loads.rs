@@ -0,0 +1,34 @@
+//! Buffered WASM bundle load counts (see `ServerState::record_wasm_module_load`).
+
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::Insertable;
+use uuid::Uuid;
+
+use crate::schema::wasm_module_loads;
+
+/// Country code stored when geo-IP has no answer.
+pub const UNKNOWN_LOAD_COUNTRY: &str = "ZZ";
+
+/// One `wasm_module_loads` row: module, UTC day and country.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WasmModuleLoadKey {
+    pub wasm_module_id: Uuid,
+    pub load_date: NaiveDate,
+    pub country_code: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct WasmModuleLoadBatch {
+    pub count: u64,
+    pub last_loaded_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = wasm_module_loads)]
+pub struct WasmModuleLoadInsertable {
+    pub wasm_module_id: Uuid,
+    pub wasm_module_load_date: NaiveDate,
+    pub wasm_module_load_country_code: String,
+    pub wasm_module_load_count: i64,
+    pub wasm_module_last_loaded_at: DateTime<Utc>,
+}
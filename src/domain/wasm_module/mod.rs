@@ -1,2 +1,5 @@
+pub mod bundle_storage;
+pub mod files;
+pub mod loads;
 #[allow(clippy::module_inception)]
 pub mod wasm_module;
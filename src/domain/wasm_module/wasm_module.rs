@@ -1,11 +1,87 @@
+use std::io::Write;
+
 use chrono::{DateTime, Utc};
-use diesel::{AsChangeset, Insertable, Queryable, QueryableByName, Selectable};
+use diesel::deserialize::{FromSql, Result as DeserializeResult};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::query_builder::QueryId;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::{AsChangeset, FromSqlRow, Insertable, Queryable, QueryableByName, Selectable};
 use serde_derive::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::init::search::WasmModuleSearchDoc;
+use crate::schema::sql_types::WasmModuleVisibility as WasmModuleVisibilitySql;
 use crate::schema::wasm_module;
 
+impl QueryId for WasmModuleVisibilitySql {
+    type QueryId = WasmModuleVisibilitySql;
+    const HAS_STATIC_QUERY_ID: bool = true;
+}
+
+/// Who may list and load a module. Users on the module's allowed list
+/// (`wasm_module_allowed_users`) and superusers always can.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    PartialEq,
+    Eq,
+    AsExpression,
+    FromSqlRow,
+)]
+#[serde(rename_all = "snake_case")]
+#[diesel(sql_type = WasmModuleVisibilitySql)]
+pub enum WasmModuleVisibility {
+    /// Listed and served to everyone.
+    #[default]
+    Public,
+    /// Served to anyone with the link, but not listed.
+    Unlisted,
+    /// Neither listed nor served.
+    SuperuserOnly,
+}
+
+impl WasmModuleVisibility {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "public" => Some(Self::Public),
+            "unlisted" => Some(Self::Unlisted),
+            "superuser_only" | "superuser-only" | "private" => Some(Self::SuperuserOnly),
+            _ => None,
+        }
+    }
+}
+
+impl ToSql<WasmModuleVisibilitySql, Pg> for WasmModuleVisibility {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        let value = match self {
+            WasmModuleVisibility::Public => "public",
+            WasmModuleVisibility::Unlisted => "unlisted",
+            WasmModuleVisibility::SuperuserOnly => "superuser_only",
+        };
+        out.write_all(value.as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<WasmModuleVisibilitySql, Pg> for WasmModuleVisibility {
+    fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+        match bytes.as_bytes() {
+            b"public" => Ok(WasmModuleVisibility::Public),
+            b"unlisted" => Ok(WasmModuleVisibility::Unlisted),
+            b"superuser_only" => Ok(WasmModuleVisibility::SuperuserOnly),
+            _ => Err("Unrecognized wasm_module_visibility enum value".into()),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, QueryableByName, Queryable, Selectable, ToSchema)]
 #[diesel(table_name = wasm_module)]
 pub struct WasmModule {
@@ -17,7 +93,29 @@ pub struct WasmModule {
     pub wasm_module_updated_at: DateTime<Utc>,
     pub wasm_module_thumbnail_link: String,
     pub wasm_module_title: String,
-    pub wasm_module_bundle_gz: Vec<u8>,
+    pub wasm_module_bundle_gz: Option<Vec<u8>>,
+    pub wasm_module_visibility: WasmModuleVisibility,
+    pub wasm_module_bundle_key: Option<String>,
+    pub wasm_module_bundle_sha256: String,
+    pub wasm_module_bundle_size_bytes: i64,
+    pub wasm_module_category: Option<String>,
+    pub wasm_module_tags: Vec<String>,
+    pub wasm_module_bundle_br: Option<Vec<u8>>,
+    pub wasm_module_bundle_br_key: Option<String>,
+    pub wasm_module_bundle_integrity: Option<String>,
+    pub wasm_module_version: i32,
+}
+
+impl WasmModule {
+    pub fn search_doc(&self) -> WasmModuleSearchDoc<'_> {
+        WasmModuleSearchDoc {
+            wasm_module_id: self.wasm_module_id,
+            title: &self.wasm_module_title,
+            description: &self.wasm_module_description,
+            category: self.wasm_module_category.as_deref(),
+            tags: &self.wasm_module_tags,
+        }
+    }
 }
 
 #[derive(Insertable)]
@@ -31,7 +129,16 @@ pub struct WasmModuleInsertable {
     pub wasm_module_updated_at: DateTime<Utc>,
     pub wasm_module_thumbnail_link: String,
     pub wasm_module_title: String,
-    pub wasm_module_bundle_gz: Vec<u8>,
+    pub wasm_module_bundle_gz: Option<Vec<u8>>,
+    pub wasm_module_visibility: WasmModuleVisibility,
+    pub wasm_module_bundle_key: Option<String>,
+    pub wasm_module_bundle_sha256: String,
+    pub wasm_module_bundle_size_bytes: i64,
+    pub wasm_module_category: Option<String>,
+    pub wasm_module_tags: Vec<String>,
+    pub wasm_module_bundle_br: Option<Vec<u8>>,
+    pub wasm_module_bundle_br_key: Option<String>,
+    pub wasm_module_bundle_integrity: Option<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Queryable, Selectable)]
@@ -45,6 +152,23 @@ pub struct WasmModuleMetadata {
     pub wasm_module_updated_at: DateTime<Utc>,
     pub wasm_module_thumbnail_link: String,
     pub wasm_module_title: String,
+    pub wasm_module_visibility: WasmModuleVisibility,
+    pub wasm_module_category: Option<String>,
+    pub wasm_module_tags: Vec<String>,
+    pub wasm_module_bundle_integrity: Option<String>,
+    pub wasm_module_version: i32,
+}
+
+impl WasmModuleMetadata {
+    pub fn search_doc(&self) -> WasmModuleSearchDoc<'_> {
+        WasmModuleSearchDoc {
+            wasm_module_id: self.wasm_module_id,
+            title: &self.wasm_module_title,
+            description: &self.wasm_module_description,
+            category: self.wasm_module_category.as_deref(),
+            tags: &self.wasm_module_tags,
+        }
+    }
 }
 
 #[derive(AsChangeset, Default)]
@@ -52,5 +176,83 @@ pub struct WasmModuleMetadata {
 pub struct WasmModuleChangeset {
     pub wasm_module_title: Option<String>,
     pub wasm_module_description: Option<String>,
+    pub wasm_module_visibility: Option<WasmModuleVisibility>,
+    pub wasm_module_category: Option<Option<String>>,
+    pub wasm_module_tags: Option<Vec<String>>,
     pub wasm_module_updated_at: Option<DateTime<Utc>>,
 }
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::wasm_module_allowed_users)]
+pub struct WasmModuleAllowedUserInsertable {
+    pub wasm_module_id: Uuid,
+    pub user_id: Uuid,
+}
+
+/// Cached access rule of one module, checked on every bundle load.
+#[derive(Debug, Clone, Default)]
+pub struct WasmModuleAccess {
+    pub visibility: WasmModuleVisibility,
+    pub allowed_user_ids: Vec<Uuid>,
+}
+
+impl WasmModuleAccess {
+    /// Whether `viewer` (`None` when logged out) may load the bundle.
+    pub fn can_load(&self, viewer: Option<Uuid>, is_superuser: bool) -> bool {
+        match self.visibility {
+            WasmModuleVisibility::Public | WasmModuleVisibility::Unlisted => true,
+            WasmModuleVisibility::SuperuserOnly => is_superuser || self.is_allowed(viewer),
+        }
+    }
+
+    /// Whether the module shows up in `viewer`'s listing.
+    pub fn can_list(&self, viewer: Option<Uuid>, is_superuser: bool) -> bool {
+        match self.visibility {
+            WasmModuleVisibility::Public => true,
+            WasmModuleVisibility::Unlisted | WasmModuleVisibility::SuperuserOnly => {
+                is_superuser || self.is_allowed(viewer)
+            }
+        }
+    }
+
+    fn is_allowed(&self, viewer: Option<Uuid>) -> bool {
+        viewer.is_some_and(|id| self.allowed_user_ids.contains(&id))
+    }
+}
+
+/// Longest category or tag accepted.
+pub const MAX_WASM_MODULE_LABEL_LEN: usize = 64;
+/// Most tags one module may carry.
+pub const MAX_WASM_MODULE_TAGS: usize = 16;
+
+/// Trimmed, lowercased category or tag; `Ok(None)` when blank.
+pub fn normalize_wasm_module_label(value: &str) -> Result<Option<String>, &'static str> {
+    let label = value.trim().to_lowercase();
+    if label.is_empty() {
+        return Ok(None);
+    }
+    if label.chars().count() > MAX_WASM_MODULE_LABEL_LEN {
+        return Err("Categories and tags must be at most 64 characters");
+    }
+    Ok(Some(label))
+}
+
+/// Normalized, sorted and deduplicated tags; blank entries are dropped.
+pub fn normalize_wasm_module_tags<I, S>(tags: I) -> Result<Vec<String>, &'static str>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized = Vec::new();
+    for tag in tags {
+        if let Some(tag) = normalize_wasm_module_label(tag.as_ref())? {
+            normalized.push(tag);
+        }
+    }
+    normalized.sort_unstable();
+    normalized.dedup();
+    if normalized.len() > MAX_WASM_MODULE_TAGS {
+        return Err("A WASM module may have at most 16 tags");
+    }
+    Ok(normalized)
+}
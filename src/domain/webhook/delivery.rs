@@ -0,0 +1,84 @@
+//! One event sent to one webhook (`webhook_deliveries`). Attempts are made by
+//! `DelayedTask::DeliverSignedWebhook`, so retries follow the task queue's
+//! backoff.
+
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::webhook_deliveries;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = webhook_deliveries)]
+pub struct WebhookDeliveryInsertable {
+    pub webhook_delivery_id: Uuid,
+    pub webhook_id: Uuid,
+    pub webhook_delivery_event_type: String,
+    pub webhook_delivery_payload: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = webhook_deliveries)]
+pub struct WebhookDelivery {
+    pub webhook_delivery_id: Uuid,
+    pub webhook_id: Uuid,
+    pub webhook_delivery_event_type: String,
+    pub webhook_delivery_payload: serde_json::Value,
+    pub webhook_delivery_status: String,
+    pub webhook_delivery_attempts: i32,
+    pub webhook_delivery_response_status: Option<i32>,
+    pub webhook_delivery_last_error: Option<String>,
+    pub webhook_delivery_created_at: DateTime<Utc>,
+    pub webhook_delivery_delivered_at: Option<DateTime<Utc>>,
+}
+
+/// A delivery as shown by `GET /api/admin/webhooks/{webhook_id}/deliveries`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookDeliveryItem {
+    pub delivery_id: Uuid,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: i32,
+    /// HTTP status of the last attempt; absent when no response arrived.
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryItem {
+    fn from(row: WebhookDelivery) -> Self {
+        Self {
+            delivery_id: row.webhook_delivery_id,
+            event_type: row.webhook_delivery_event_type,
+            status: row.webhook_delivery_status,
+            attempts: row.webhook_delivery_attempts,
+            response_status: row.webhook_delivery_response_status,
+            last_error: row.webhook_delivery_last_error,
+            payload: row.webhook_delivery_payload,
+            created_at: row.webhook_delivery_created_at,
+            delivered_at: row.webhook_delivery_delivered_at,
+        }
+    }
+}
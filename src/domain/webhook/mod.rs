@@ -0,0 +1,3 @@
+pub mod delivery;
+#[allow(clippy::module_inception)]
+pub mod webhook;
@@ -0,0 +1,143 @@
+//! Outgoing webhooks registered by a superuser (`webhooks`) and the events
+//! they can subscribe to.
+
+use chrono::{DateTime, Utc};
+use diesel::{AsChangeset, Insertable, Queryable, Selectable};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::blog::{Comment, Post},
+    schema::webhooks,
+};
+
+/// An event a webhook can subscribe to. Stored in `webhook_event_types` and
+/// sent as the `event` field and `X-Webhook-Event` header by its `as_str` name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEventType {
+    #[serde(rename = "post.published")]
+    PostPublished,
+    #[serde(rename = "comment.created")]
+    CommentCreated,
+    #[serde(rename = "photograph.uploaded")]
+    PhotographUploaded,
+}
+
+impl WebhookEventType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PostPublished => "post.published",
+            Self::CommentCreated => "comment.created",
+            Self::PhotographUploaded => "photograph.uploaded",
+        }
+    }
+}
+
+/// Something that happened, with the `data` sent to subscribers.
+#[derive(Debug, Clone)]
+pub struct WebhookEvent {
+    pub event_type: WebhookEventType,
+    pub data: serde_json::Value,
+}
+
+impl WebhookEvent {
+    pub fn post_published(post: &Post) -> Self {
+        Self {
+            event_type: WebhookEventType::PostPublished,
+            data: serde_json::json!({
+                "post_id": post.post_id,
+                "user_id": post.user_id,
+                "post_title": post.post_title,
+                "post_slug": post.post_slug,
+                "post_published_at": post.post_published_at,
+            }),
+        }
+    }
+
+    pub fn comment_created(comment: &Comment) -> Self {
+        Self {
+            event_type: WebhookEventType::CommentCreated,
+            data: serde_json::json!({
+                "comment_id": comment.comment_id,
+                "post_id": comment.post_id,
+                "user_id": comment.user_id,
+                "parent_comment_id": comment.parent_comment_id,
+                "comment_created_at": comment.comment_created_at,
+            }),
+        }
+    }
+
+    pub fn photograph_uploaded(
+        photograph_id: Uuid,
+        user_id: Uuid,
+        photograph_link: &str,
+        photograph_thumbnail_link: &str,
+    ) -> Self {
+        Self {
+            event_type: WebhookEventType::PhotographUploaded,
+            data: serde_json::json!({
+                "photograph_id": photograph_id,
+                "user_id": user_id,
+                "photograph_link": photograph_link,
+                "photograph_thumbnail_link": photograph_thumbnail_link,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = webhooks)]
+pub struct Webhook {
+    pub webhook_id: Uuid,
+    pub webhook_url: String,
+    pub webhook_secret: String,
+    pub webhook_event_types: Vec<String>,
+    pub webhook_enabled: bool,
+    pub webhook_created_at: DateTime<Utc>,
+    pub webhook_updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = webhooks)]
+pub struct WebhookInsertable {
+    pub webhook_url: String,
+    pub webhook_secret: String,
+    pub webhook_event_types: Vec<String>,
+    pub webhook_enabled: bool,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = webhooks)]
+pub struct WebhookChangeset {
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub webhook_event_types: Option<Vec<String>>,
+    pub webhook_enabled: Option<bool>,
+    pub webhook_updated_at: Option<DateTime<Utc>>,
+}
+
+/// A webhook as shown by the admin API. The secret is only returned once,
+/// when it is created or rotated.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookItem {
+    pub webhook_id: Uuid,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Webhook> for WebhookItem {
+    fn from(row: Webhook) -> Self {
+        Self {
+            webhook_id: row.webhook_id,
+            url: row.webhook_url,
+            event_types: row.webhook_event_types,
+            enabled: row.webhook_enabled,
+            created_at: row.webhook_created_at,
+            updated_at: row.webhook_updated_at,
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domain::stats::dashboard::StatsRange;
+
+/// Query for `GET /api/admin/stats`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct AdminStatsRequest {
+    /// `7d`, `30d` (default), `90d` or `365d`.
+    pub range: Option<StatsRange>,
+    /// Recompute instead of serving numbers up to a minute old.
+    #[serde(default)]
+    pub refresh: bool,
+}
@@ -0,0 +1,54 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::alert::alert_rule::AlertMetric, util::http::endpoint_stats::EndpointStatsWindow,
+};
+
+/// Body of `POST /api/admin/alerts/rules`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAlertRuleRequest {
+    pub name: String,
+    pub metric: AlertMetric,
+    /// Fires when the metric is above this: milliseconds for latencies, a
+    /// share from 0 to 1 for rates.
+    pub threshold: f64,
+    /// Stats window compared; defaults to `5m`.
+    pub window: Option<EndpointStatsWindow>,
+    /// Only this method (e.g. `GET`); every method when omitted.
+    pub method: Option<String>,
+    /// Only this route template (e.g. `/api/blog/{post_id}`); every route when
+    /// omitted.
+    pub route: Option<String>,
+    /// Requests an endpoint needs in the window to be judged; defaults to 20.
+    pub min_requests: Option<i32>,
+    /// Minutes between two alerts from the rule; defaults to 30.
+    pub cooldown_minutes: Option<i32>,
+    /// Addresses emailed when it fires.
+    pub emails: Option<Vec<String>>,
+    /// `http(s)` URL the alert is POSTed to as JSON.
+    pub webhook_url: Option<String>,
+    /// Defaults to true.
+    pub enabled: Option<bool>,
+}
+
+/// Body of `PATCH /api/admin/alerts/rules/{alert_rule_id}`; omitted fields
+/// are left as they are.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateAlertRuleRequest {
+    pub name: Option<String>,
+    pub metric: Option<AlertMetric>,
+    pub threshold: Option<f64>,
+    pub window: Option<EndpointStatsWindow>,
+    /// An empty string clears it.
+    pub method: Option<String>,
+    /// An empty string clears it.
+    pub route: Option<String>,
+    pub min_requests: Option<i32>,
+    pub cooldown_minutes: Option<i32>,
+    /// Replaces the address list when present.
+    pub emails: Option<Vec<String>>,
+    /// An empty string clears it.
+    pub webhook_url: Option<String>,
+    pub enabled: Option<bool>,
+}
@@ -0,0 +1,9 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/admin/emails/preview/{template}`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct EmailPreviewRequest {
+    /// UI locale tag (`en-US`, `ko-KR`); en-US when omitted or unknown.
+    pub lang: Option<String>,
+}
@@ -0,0 +1,11 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::util::http::endpoint_stats::EndpointStatsWindow;
+
+/// Query for `GET /api/admin/endpoints/stats`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct EndpointStatsRequest {
+    /// `1m`, `5m`, `15m` (default) or `1h`.
+    pub window: Option<EndpointStatsWindow>,
+}
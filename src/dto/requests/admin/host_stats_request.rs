@@ -0,0 +1,12 @@
+use serde_derive::Deserialize;
+
+/// Query for the `/ws/host-stats` upgrade.
+#[derive(Debug, Default, Deserialize)]
+pub struct HostStatsSocketRequest {
+    /// Push interval; defaults to `host_stats.interval_ms` and is raised to
+    /// `host_stats.min_interval_ms` if lower.
+    pub interval_ms: Option<u64>,
+    /// Past samples to send before the live ones; defaults to
+    /// `host_stats.backfill`.
+    pub backfill: Option<usize>,
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Query for `GET /api/admin/access-log`; every filter is optional.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListAccessLogRequest {
+    /// Only paths starting with this, e.g. `/api/blog/`.
+    pub path_prefix: Option<String>,
+    /// e.g. `GET`.
+    pub method: Option<String>,
+    /// Exact status code.
+    pub status: Option<i16>,
+    /// Status class 1 to 5, e.g. `5` for every 5xx.
+    pub status_class: Option<i16>,
+    /// ISO 3166-1 alpha-2, e.g. `KR`.
+    pub country_code: Option<String>,
+    pub user_id: Option<Uuid>,
+    /// Only entries at or after this.
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries older than this.
+    pub before: Option<DateTime<Utc>>,
+    /// `next_cursor` from the previous response, to page back in time.
+    pub cursor: Option<String>,
+    /// Entries returned, newest first (default 100, max 1000).
+    pub limit: Option<i64>,
+}
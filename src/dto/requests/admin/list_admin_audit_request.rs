@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Query for `GET /api/admin/audit`; every filter is optional.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListAdminAuditRequest {
+    /// Only actions by this user.
+    pub actor_id: Option<Uuid>,
+    /// Exact action name, e.g. `photograph.delete`.
+    pub action: Option<String>,
+    /// e.g. `wasm_module`, `photograph`, `webhook`.
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    /// Only entries older than this; pass the last `created_at` to page back.
+    pub before: Option<DateTime<Utc>>,
+    /// Entries returned, newest first (default 50, max 500).
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,13 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domain::email::email_outbox::EmailOutboxStatus;
+
+/// Query for `GET /api/admin/email-outbox`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListEmailOutboxRequest {
+    /// Only emails in this state (e.g. `dead`); all when omitted.
+    pub status: Option<EmailOutboxStatus>,
+    /// Emails returned, newest first (default 50, max 500).
+    pub limit: Option<i64>,
+}
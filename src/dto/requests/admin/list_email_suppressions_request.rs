@@ -0,0 +1,21 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/admin/email-suppressions`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListEmailSuppressionsRequest {
+    /// Addresses returned, most recently suppressed first (default 50, max 500).
+    pub limit: Option<i64>,
+    /// Only this reason: `bounce`, `complaint` or `manual`.
+    pub reason: Option<String>,
+    /// Only addresses containing this text (case-insensitive).
+    pub address: Option<String>,
+}
+
+/// Body of `POST /api/admin/email-suppressions`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateEmailSuppressionRequest {
+    pub address: String,
+    /// Why, kept as the entry's detail.
+    pub note: Option<String>,
+}
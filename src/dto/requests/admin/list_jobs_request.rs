@@ -0,0 +1,9 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/admin/jobs`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListJobsRequest {
+    /// Recent executions returned per job (default 10, max 100).
+    pub history: Option<i64>,
+}
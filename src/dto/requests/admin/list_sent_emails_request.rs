@@ -0,0 +1,9 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/admin/emails/sent`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListSentEmailsRequest {
+    /// Messages returned, newest first (default 50, max 500).
+    pub limit: Option<i64>,
+}
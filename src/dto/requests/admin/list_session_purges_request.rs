@@ -0,0 +1,9 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/admin/sessions/purges`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListSessionPurgesRequest {
+    /// Runs returned, newest first (default 48, max 1000).
+    pub limit: Option<usize>,
+}
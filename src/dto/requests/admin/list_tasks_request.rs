@@ -0,0 +1,13 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domain::job::delayed_task::DelayedTaskStatus;
+
+/// Query for `GET /api/admin/tasks`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListTasksRequest {
+    /// Only tasks in this state (e.g. `failed`); all when omitted.
+    pub status: Option<DelayedTaskStatus>,
+    /// Tasks returned, newest first (default 50, max 500).
+    pub limit: Option<i64>,
+}
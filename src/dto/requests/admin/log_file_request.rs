@@ -0,0 +1,9 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/admin/logs/download`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct DownloadLogFileRequest {
+    /// A `name` from `GET /api/admin/logs`.
+    pub name: String,
+}
@@ -0,0 +1,18 @@
+pub mod admin_stats_request;
+pub mod alert_rule_request;
+pub mod email_preview_request;
+pub mod endpoint_stats_request;
+pub mod host_stats_request;
+pub mod list_access_log_request;
+pub mod list_admin_audit_request;
+pub mod list_email_outbox_request;
+pub mod list_email_suppressions_request;
+pub mod list_jobs_request;
+pub mod list_sent_emails_request;
+pub mod list_session_purges_request;
+pub mod list_tasks_request;
+pub mod log_file_request;
+pub mod scan_storage_orphans_request;
+pub mod session_stats_request;
+pub mod update_logging_request;
+pub mod webhook_request;
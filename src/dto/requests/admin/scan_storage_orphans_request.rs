@@ -0,0 +1,9 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `POST /api/admin/storage/orphans/scan`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ScanStorageOrphansRequest {
+    /// Report orphans without deleting them (default `true`).
+    pub dry_run: Option<bool>,
+}
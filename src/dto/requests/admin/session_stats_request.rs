@@ -0,0 +1,10 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/admin/sessions/stats`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct SessionStatsRequest {
+    /// Hourly buckets returned, the current hour included (default 24, max
+    /// 24).
+    pub hours: Option<usize>,
+}
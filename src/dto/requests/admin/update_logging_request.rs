@@ -0,0 +1,11 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+
+use crate::init::log_levels::LogFilterSpec;
+
+/// Body of `PUT /api/admin/logging`. A layer left out keeps its filter.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateLoggingRequest {
+    pub console: Option<LogFilterSpec>,
+    pub file: Option<LogFilterSpec>,
+}
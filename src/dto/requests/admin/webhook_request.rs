@@ -0,0 +1,38 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domain::webhook::{delivery::WebhookDeliveryStatus, webhook::WebhookEventType};
+
+/// Body of `POST /api/admin/webhooks`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// `http(s)` URL the events are POSTed to.
+    pub url: String,
+    /// At least one event.
+    pub event_types: Vec<WebhookEventType>,
+    /// Signing secret (at least 16 characters); generated when omitted.
+    pub secret: Option<String>,
+    /// Defaults to true.
+    pub enabled: Option<bool>,
+}
+
+/// Body of `PATCH /api/admin/webhooks/{webhook_id}`; omitted fields are left
+/// as they are.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<WebhookEventType>>,
+    pub enabled: Option<bool>,
+    /// Replace the signing secret with a generated one, returned once.
+    #[serde(default)]
+    pub rotate_secret: bool,
+}
+
+/// Query for `GET /api/admin/webhooks/{webhook_id}/deliveries`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListWebhookDeliveriesRequest {
+    /// Only deliveries in this state (e.g. `failed`); all when omitted.
+    pub status: Option<WebhookDeliveryStatus>,
+    /// Deliveries returned, newest first (default 50, max 500).
+    pub limit: Option<i64>,
+}
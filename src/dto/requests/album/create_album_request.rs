@@ -0,0 +1,12 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAlbumRequest {
+    pub album_title: String,
+    pub album_description: Option<String>,
+    pub album_cover_photograph_id: Option<Uuid>,
+    /// Lower values list first; defaults to 0.
+    pub album_sort_order: Option<i32>,
+}
@@ -0,0 +1,3 @@
+pub mod create_album_request;
+pub mod set_album_photographs_request;
+pub mod update_album_request;
@@ -0,0 +1,10 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Full, ordered membership of an album. Photographs previously in the album
+/// but missing from `photograph_ids` are detached from it.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetAlbumPhotographsRequest {
+    pub photograph_ids: Vec<Uuid>,
+}
@@ -0,0 +1,15 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateAlbumRequest {
+    pub album_title: Option<String>,
+    pub album_description: Option<String>,
+    pub album_cover_photograph_id: Option<Uuid>,
+    /// Remove the cover photograph; takes precedence over
+    /// `album_cover_photograph_id`.
+    #[serde(default)]
+    pub clear_album_cover: bool,
+    pub album_sort_order: Option<i32>,
+}
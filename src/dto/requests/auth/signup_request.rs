@@ -1,10 +1,16 @@
 use utoipa::ToSchema;
+use validator::Validate;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[derive(serde_derive::Deserialize, Zeroize, ZeroizeOnDrop, ToSchema)]
+use crate::util::string::validations::rules;
+
+#[derive(serde_derive::Deserialize, Zeroize, ZeroizeOnDrop, ToSchema, Validate)]
 pub struct SignupRequest {
+    #[validate(custom(function = "rules::user_name"))]
     pub user_name: String,
+    #[validate(custom(function = "rules::email"))]
     pub user_email: String,
+    #[validate(custom(function = "rules::password"))]
     pub user_password: String,
     pub user_country: i32,
     pub user_language: i32,
@@ -4,4 +4,8 @@ use utoipa::ToSchema;
 #[derive(Deserialize, ToSchema)]
 pub struct UpdateCommentRequest {
     pub comment_content: String,
+    /// `comment_version` the edit is based on. When it is no longer current
+    /// the update is refused with `EDIT_CONFLICT` (409) carrying the latest
+    /// comment; omit it to overwrite unconditionally.
+    pub comment_version: Option<i32>,
 }
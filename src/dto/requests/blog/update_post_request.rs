@@ -7,4 +7,8 @@ pub struct UpdatePostRequest {
     pub post_content: String,
     pub post_tags: Vec<String>,
     pub post_is_published: bool,
+    /// `post_version` the edit is based on. When it is no longer current the
+    /// update is refused with `EDIT_CONFLICT` (409) carrying the latest post;
+    /// omit it to overwrite unconditionally.
+    pub post_version: Option<i32>,
 }
@@ -0,0 +1,9 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `/api/email/unsubscribe`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct UnsubscribeRequest {
+    /// The signed token from the email's unsubscribe link.
+    pub token: String,
+}
@@ -1,6 +1,10 @@
+pub mod admin;
+pub mod album;
 pub mod auth;
 pub mod blog;
+pub mod email;
 pub mod i18n;
 pub mod live_chat;
+pub mod notifications;
 pub mod photography;
 pub mod wasm_module;
@@ -0,0 +1,14 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/notifications`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct ListNotificationsRequest {
+    /// Only notifications not yet marked read.
+    #[serde(default)]
+    pub unread_only: bool,
+    /// `next_cursor` from the previous response, to page back in time.
+    pub cursor: Option<String>,
+    /// Notifications returned, newest first (default 20, max 100).
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,10 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Body of `POST /api/notifications/read`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MarkNotificationsReadRequest {
+    /// Notifications to mark read; omit to mark every unread one.
+    pub notification_ids: Option<Vec<Uuid>>,
+}
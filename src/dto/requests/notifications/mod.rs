@@ -0,0 +1,2 @@
+pub mod list_notifications_request;
+pub mod mark_notifications_read_request;
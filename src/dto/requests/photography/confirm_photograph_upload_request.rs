@@ -0,0 +1,19 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+
+/// Body for `POST /api/photographs/presign/{upload_token}/confirm`.
+///
+/// Mirrors the text fields of the multipart `upload_photograph` form.
+#[derive(Deserialize, ToSchema)]
+pub struct ConfirmPhotographUploadRequest {
+    pub comments: Option<String>,
+    /// Omit both `lat` and `lon` to use the original's EXIF GPS location.
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Accepts the same aliases as `PhotographContext::from_str`; defaults to photography.
+    pub context: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Stamp the configured watermark; defaults to `WATERMARK_DEFAULT`.
+    pub watermark: Option<bool>,
+}
@@ -0,0 +1,19 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OriginalDownloadMode {
+    /// 307 to a short-lived presigned storage URL (falls back to `stream`
+    /// when the backend cannot presign).
+    Redirect,
+    /// Proxy the bytes through the server as an attachment.
+    Stream,
+}
+
+/// Query for `GET /api/photographs/{photograph_id}/original`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct DownloadPhotographOriginalRequest {
+    /// Defaults to `redirect`.
+    pub mode: Option<OriginalDownloadMode>,
+}
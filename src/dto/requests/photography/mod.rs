@@ -1,4 +1,11 @@
+pub mod confirm_photograph_upload_request;
 pub mod delete_photographs_request;
+pub mod download_photograph_original_request;
+pub mod photograph_duplicates_request;
+pub mod photograph_variant_request;
+pub mod photographs_in_bounds_request;
+pub mod presign_photograph_upload_request;
+pub mod set_photograph_tags_request;
 pub mod submit_photograph_comment_request;
 pub mod update_photograph_comment_request;
 pub mod vote_photograph_request;
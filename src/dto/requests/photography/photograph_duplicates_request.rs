@@ -0,0 +1,10 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/admin/photographs/duplicates`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct PhotographDuplicatesRequest {
+    /// Max Hamming distance (of 64 bits) for two photographs to be grouped;
+    /// defaults to `PHOTOGRAPH_DUPLICATE_THRESHOLD`, capped at 16.
+    pub threshold: Option<u32>,
+}
@@ -0,0 +1,14 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /img/{photograph_id}`. Dimensions are maximums (aspect ratio
+/// is kept, images are never upscaled) and are rounded up to a multiple of 16.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct PhotographVariantRequest {
+    /// Maximum width in pixels (1-2400).
+    pub w: Option<u32>,
+    /// Maximum height in pixels (1-2400).
+    pub h: Option<u32>,
+    /// `avif` (default), `webp`, `jpeg` or `png`.
+    pub fmt: Option<String>,
+}
@@ -0,0 +1,15 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Viewport for `GET /api/photographs/in-bounds`. `min_lon > max_lon` means
+/// the viewport crosses the antimeridian.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct PhotographsInBoundsRequest {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+    /// Cluster grid cells per viewport side once the viewport holds too many
+    /// photographs to return individually (default 16, max 64).
+    pub grid: Option<u32>,
+}
@@ -0,0 +1,13 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+
+/// Body for `POST /api/photographs/presign`.
+#[derive(Deserialize, ToSchema)]
+pub struct PresignPhotographUploadRequest {
+    /// Original file name; used for the post-image comment fallback.
+    pub file_name: Option<String>,
+    /// MIME type the client will send with the `PUT`; must be an allowed image type.
+    pub content_type: String,
+    /// Size of the original in bytes; checked against the single-upload cap.
+    pub content_length: u64,
+}
@@ -0,0 +1,8 @@
+use serde_derive::Deserialize;
+use utoipa::ToSchema;
+
+/// Replaces the photograph's full tag set; an empty list clears it.
+#[derive(Deserialize, ToSchema)]
+pub struct SetPhotographTagsRequest {
+    pub photograph_tags: Vec<String>,
+}
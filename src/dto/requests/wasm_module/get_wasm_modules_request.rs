@@ -0,0 +1,11 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/wasm-modules`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct GetWasmModulesRequest {
+    /// Only modules in this category (case-insensitive).
+    pub category: Option<String>,
+    /// Only modules carrying this tag (case-insensitive).
+    pub tag: Option<String>,
+}
@@ -1,3 +1,9 @@
+pub mod get_wasm_modules_request;
+pub mod search_wasm_modules_request;
 pub mod update_wasm_module_request;
+pub mod wasm_module_stats_request;
 
+pub use get_wasm_modules_request::GetWasmModulesRequest;
+pub use search_wasm_modules_request::SearchWasmModulesRequest;
 pub use update_wasm_module_request::UpdateWasmModuleRequest;
+pub use wasm_module_stats_request::WasmModuleStatsRequest;
@@ -0,0 +1,17 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/wasm-modules/search`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct SearchWasmModulesRequest {
+    /// Words to find in titles and descriptions; the last may be a prefix.
+    /// May be omitted when filtering by category or tags only.
+    pub q: Option<String>,
+    pub category: Option<String>,
+    /// Comma-separated; every tag must match.
+    pub tags: Option<String>,
+    /// 1-based, default 1.
+    pub page: Option<usize>,
+    /// Default 20, at most 100.
+    pub limit: Option<usize>,
+}
@@ -1,8 +1,22 @@
 use serde_derive::Deserialize;
 use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::wasm_module::wasm_module::WasmModuleVisibility;
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateWasmModuleRequest {
     pub wasm_module_title: Option<String>,
     pub wasm_module_description: Option<String>,
+    pub wasm_module_visibility: Option<WasmModuleVisibility>,
+    /// Sets the category; an empty string clears it.
+    pub wasm_module_category: Option<String>,
+    /// Replaces the tag list when present.
+    pub wasm_module_tags: Option<Vec<String>>,
+    /// Replaces the list of users the module is shared with when present.
+    pub wasm_module_allowed_user_ids: Option<Vec<Uuid>>,
+    /// `wasm_module_version` the edit is based on. When it is no longer
+    /// current the update is refused with `EDIT_CONFLICT` (409) carrying the
+    /// latest module; omit it to overwrite unconditionally.
+    pub wasm_module_version: Option<i32>,
 }
@@ -0,0 +1,9 @@
+use serde_derive::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query for `GET /api/wasm-modules/{wasm_module_id}/stats`.
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct WasmModuleStatsRequest {
+    /// Days to cover, counting today (UTC). Defaults to 30, at most 365.
+    pub days: Option<u32>,
+}
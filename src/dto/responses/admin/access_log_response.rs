@@ -0,0 +1,13 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::access_log::access_log::AccessLogItem;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListAccessLogResponse {
+    /// `ACCESS_LOG_SAMPLE_RATE`, to scale counts back to total traffic.
+    pub sample_rate: f32,
+    pub entries: Vec<AccessLogItem>,
+    /// Pass back as `cursor` for older entries; `None` on the last page.
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,9 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::audit::audit::AdminAuditItem;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListAdminAuditResponse {
+    pub entries: Vec<AdminAuditItem>,
+}
@@ -0,0 +1,14 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::stats::dashboard::DashboardStats;
+
+/// Everything the admin overview page shows.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminStatsResponse {
+    pub stats: DashboardStats,
+    /// Unexpired sessions held by this instance, counted live.
+    pub active_sessions: usize,
+    /// Distinct users behind `active_sessions`.
+    pub active_users: usize,
+}
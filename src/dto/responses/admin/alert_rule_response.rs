@@ -0,0 +1,20 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::alert::alert_rule::AlertRuleItem;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListAlertRulesResponse {
+    pub rules: Vec<AlertRuleItem>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AlertRuleResponse {
+    pub rule: AlertRuleItem,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeleteAlertRuleResponse {
+    pub alert_rule_id: Uuid,
+}
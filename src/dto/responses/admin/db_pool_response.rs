@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// A pool checkout that errored or timed out.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PoolCheckoutFailure {
+    pub failed_at: DateTime<Utc>,
+    /// How long the checkout waited before failing.
+    pub waited_ms: u64,
+    pub error: String,
+}
+
+/// Checkout waits over the most recent samples, in milliseconds.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PoolWaitPercentiles {
+    pub samples: usize,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DbPoolStats {
+    /// `primary` or `replica`.
+    pub pool: String,
+    pub max_size: u32,
+    /// Open connections, idle or checked out.
+    pub connections: u32,
+    pub idle_connections: u32,
+    pub in_use: u32,
+    /// Circuit breaker state: `closed`, `half_open` or `open`.
+    pub circuit: String,
+    /// Checkouts attempted since startup (not counting breaker rejections).
+    pub checkouts_total: u64,
+    pub failures_total: u64,
+    pub wait_ms: PoolWaitPercentiles,
+    /// Recent failures, newest first.
+    pub recent_failures: Vec<PoolCheckoutFailure>,
+}
+
+/// Result of `GET /api/admin/db/pool`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DbPoolStatsResponse {
+    /// The primary, then the read replica when one is configured.
+    pub pools: Vec<DbPoolStats>,
+}
@@ -0,0 +1,15 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::email::email_outbox::EmailOutboxItem;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListEmailOutboxResponse {
+    pub emails: Vec<EmailOutboxItem>,
+}
+
+/// Result of `POST /api/admin/email-outbox/{email_id}/requeue`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RequeueEmailResponse {
+    pub email: EmailOutboxItem,
+}
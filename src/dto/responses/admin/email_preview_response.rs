@@ -0,0 +1,20 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// An email rendered with sample data, exactly as it would be sent.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailPreviewResponse {
+    pub template: &'static str,
+    /// Locale the texts were taken from.
+    pub lang: &'static str,
+    /// The configured sender, with this environment's tag.
+    pub from: String,
+    pub reply_to: Option<String>,
+    /// With this environment's tag.
+    pub subject: String,
+    pub html: String,
+    /// The plain-text alternative.
+    pub text: String,
+    /// The sample values filled in for the template's vars.
+    pub vars: std::collections::BTreeMap<String, String>,
+}
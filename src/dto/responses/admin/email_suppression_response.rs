@@ -0,0 +1,24 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::email::feedback::EmailSuppression;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListEmailSuppressionsResponse {
+    pub suppressions: Vec<EmailSuppression>,
+}
+
+/// Result of `POST /api/admin/email-suppressions`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CreateEmailSuppressionResponse {
+    pub suppression: EmailSuppression,
+    /// False if the address was already suppressed; its entry is unchanged.
+    pub created: bool,
+}
+
+/// Result of `DELETE /api/admin/email-suppressions/{address}`: the entry
+/// that was lifted.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeleteEmailSuppressionResponse {
+    pub suppression: EmailSuppression,
+}
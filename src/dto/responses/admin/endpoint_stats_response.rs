@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::util::http::endpoint_stats::{EndpointStatsWindow, EndpointSummary};
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EndpointStatsResponse {
+    pub window: EndpointStatsWindow,
+    pub generated_at: DateTime<Utc>,
+    /// Routes with traffic in the window on this instance, busiest first.
+    pub endpoints: Vec<EndpointSummary>,
+}
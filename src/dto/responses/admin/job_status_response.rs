@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::job::execution::JobExecution;
+
+/// One scheduled job as seen by `GET /api/admin/jobs`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobStatusItem {
+    pub job_name: String,
+    /// Human-readable schedule, e.g. `every day at 06:30:00 UTC`.
+    pub schedule: String,
+    pub running: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// Only one instance runs each tick (Postgres advisory lock).
+    pub cluster_exclusive: bool,
+    /// Ticks are skipped until resumed.
+    pub paused: bool,
+    /// Failed, panicked or timed-out runs since the last success.
+    pub consecutive_failures: u32,
+    /// Last execution in this process, else the newest recorded one.
+    pub last_run: Option<JobExecution>,
+    /// Recorded executions, newest first. Successful runs of every-second
+    /// jobs are not recorded.
+    pub recent_runs: Vec<JobExecution>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListJobsResponse {
+    pub jobs: Vec<JobStatusItem>,
+}
+
+/// Result of `POST /api/admin/jobs/{job_name}/pause` and `/resume`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SetJobPausedResponse {
+    pub job_name: String,
+    pub paused: bool,
+}
+
+/// Result of `POST /api/admin/jobs/{job_name}/cancel`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CancelJobResponse {
+    pub job_name: String,
+    /// `false` when no run was in flight.
+    pub cancelled: bool,
+}
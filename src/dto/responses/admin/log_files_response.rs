@@ -0,0 +1,38 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::util::log_files::{LogCompression, LogFile};
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LogFileItem {
+    /// Path below the logs directory; pass it to `GET /api/admin/logs/download`.
+    pub name: String,
+    /// The day the file holds.
+    pub date: Option<NaiveDate>,
+    pub size_bytes: u64,
+    pub modified_at: Option<DateTime<Utc>>,
+    /// Unset for logs not yet compressed.
+    pub compression: Option<LogCompression>,
+}
+
+impl From<LogFile> for LogFileItem {
+    fn from(file: LogFile) -> Self {
+        Self {
+            name: file.name,
+            date: file.date,
+            size_bytes: file.size_bytes,
+            modified_at: file.modified_at,
+            compression: file.compression,
+        }
+    }
+}
+
+/// Rolled-over log files on the instance that answered, by name.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LogFilesResponse {
+    pub compress_after_days: i64,
+    pub retention_days: i64,
+    pub total_bytes: u64,
+    pub files: Vec<LogFileItem>,
+}
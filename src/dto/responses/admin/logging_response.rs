@@ -0,0 +1,27 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::init::log_levels::{LogFilterSpec, ReloadableFilter};
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LayerFilters {
+    pub current: LogFilterSpec,
+    /// What the layer started with; `PUT` it back to undo changes.
+    pub startup: LogFilterSpec,
+}
+
+impl From<&ReloadableFilter> for LayerFilters {
+    fn from(filter: &ReloadableFilter) -> Self {
+        Self {
+            current: filter.current(),
+            startup: filter.startup().clone(),
+        }
+    }
+}
+
+/// The console and file log filters of the instance that answered.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LoggingResponse {
+    pub console: LayerFilters,
+    pub file: LayerFilters,
+}
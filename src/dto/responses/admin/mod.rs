@@ -1 +1,21 @@
+pub mod access_log_response;
+pub mod admin_audit_response;
+pub mod admin_stats_response;
+pub mod alert_rule_response;
+pub mod db_pool_response;
+pub mod email_outbox_response;
+pub mod email_preview_response;
+pub mod email_suppression_response;
+pub mod endpoint_stats_response;
+pub mod job_status_response;
+pub mod log_files_response;
+pub mod logging_response;
+pub mod restore_response;
+pub mod sent_email_response;
+pub mod session_purge_response;
+pub mod session_stats_response;
+pub mod storage_orphan_report;
 pub mod sync_i18n_cache_response;
+pub mod task_response;
+pub mod tls_response;
+pub mod webhook_response;
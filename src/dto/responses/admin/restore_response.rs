@@ -0,0 +1,12 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::soft_delete::soft_delete::SoftDeleteKind;
+
+/// Result of `POST /api/admin/trash/{kind}/{id}/restore`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RestoreResponse {
+    pub kind: SoftDeleteKind,
+    pub restored_id: Uuid,
+}
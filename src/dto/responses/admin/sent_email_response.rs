@@ -0,0 +1,20 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::util::email::transport::file::SinkEmail;
+
+/// Messages captured by the local email sink.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListSentEmailsResponse {
+    /// The sink directory the `.eml` files are in.
+    pub dir: String,
+    pub emails: Vec<SinkEmail>,
+}
+
+/// One captured message with its full MIME source.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SentEmailResponse {
+    pub email: SinkEmail,
+    /// The `.eml` file as written.
+    pub raw: String,
+}
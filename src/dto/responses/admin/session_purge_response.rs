@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// One run of the expired-session purge.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionPurgeReport {
+    pub purged_at: DateTime<Utc>,
+    /// Expired sessions removed by this run.
+    pub purged: usize,
+    /// Live sessions left after it.
+    pub retained: usize,
+    pub duration_ms: u64,
+}
+
+/// Result of `GET /api/admin/sessions/purges`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionPurgeHistoryResponse {
+    /// Human-readable purge schedule; `None` before the scheduler starts.
+    pub schedule: Option<String>,
+    /// Sessions purged since startup.
+    pub purged_total: u64,
+    /// Sessions in memory right now.
+    pub active_sessions: usize,
+    /// Recent runs, newest first.
+    pub reports: Vec<SessionPurgeReport>,
+}
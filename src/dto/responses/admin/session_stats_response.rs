@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// Session lifecycle events, over an hour or since startup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SessionEventCounts {
+    /// Sessions opened by a login.
+    pub created: u64,
+    /// Sessions closed by a logout or replaced by a new login.
+    pub ended: u64,
+    /// Expired sessions removed by the purge job.
+    pub expired: u64,
+}
+
+/// One UTC hour of session events.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionRateBucket {
+    pub hour_start: DateTime<Utc>,
+    #[serde(flatten)]
+    pub counts: SessionEventCounts,
+}
+
+/// Live sessions from one country (`user_country`).
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionCountryCount {
+    pub country_code: i32,
+    /// `None` when the code is not in the country table.
+    pub country_alpha2: Option<String>,
+    pub country_name: Option<String>,
+    pub country_flag: Option<String>,
+    pub sessions: usize,
+}
+
+/// Result of `GET /api/admin/sessions/stats`, for the answering instance.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionStatsResponse {
+    /// Unexpired sessions in memory.
+    pub active_sessions: usize,
+    /// Expired sessions the purge job has not removed yet.
+    pub expired_awaiting_purge: usize,
+    /// Users with at least one active session.
+    pub distinct_users: usize,
+    /// Mean age of the active sessions, in seconds.
+    pub average_age_secs: f64,
+    pub oldest_age_secs: i64,
+    /// Active sessions per country, most first.
+    pub countries: Vec<SessionCountryCount>,
+    /// Since startup.
+    pub totals: SessionEventCounts,
+    /// The last `hours` hours, oldest first, the current one included.
+    pub hourly: Vec<SessionRateBucket>,
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// A stored object no database row references.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StorageOrphan {
+    pub key: String,
+    pub size_bytes: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// Result of one storage/DB reconciliation pass.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StorageOrphanReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// `true` when orphans were only reported, not deleted.
+    pub dry_run: bool,
+    pub backend: String,
+    pub scanned_objects: usize,
+    /// Unreferenced objects younger than the minimum age; left alone because
+    /// their row may not be committed yet.
+    pub skipped_recent: usize,
+    pub orphan_count: usize,
+    pub orphan_bytes: u64,
+    pub deleted_count: usize,
+    /// Up to `MAX_REPORTED_ORPHANS` orphans, oldest first.
+    pub orphans: Vec<StorageOrphan>,
+    pub orphans_truncated: bool,
+}
@@ -0,0 +1,15 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::job::delayed_task::DelayedTaskItem;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListTasksResponse {
+    pub tasks: Vec<DelayedTaskItem>,
+}
+
+/// Result of `POST /api/admin/tasks/{task_id}/requeue`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RequeueTaskResponse {
+    pub task: DelayedTaskItem,
+}
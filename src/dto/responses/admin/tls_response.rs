@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// The certificate the HTTPS listener is serving.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TlsStatus {
+    pub cert_path: String,
+    pub key_path: String,
+    /// When the current certificate was loaded (startup or last reload).
+    pub loaded_at: DateTime<Utc>,
+    /// Successful reloads since startup.
+    pub reloads: u64,
+    /// The most recent failed reload, cleared by the next success.
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
@@ -0,0 +1,28 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::webhook::{delivery::WebhookDeliveryItem, webhook::WebhookItem};
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListWebhooksResponse {
+    pub webhooks: Vec<WebhookItem>,
+}
+
+/// Result of creating or updating a webhook.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub webhook: WebhookItem,
+    /// The signing secret, only when it was just created or rotated.
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeleteWebhookResponse {
+    pub webhook_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListWebhookDeliveriesResponse {
+    pub deliveries: Vec<WebhookDeliveryItem>,
+}
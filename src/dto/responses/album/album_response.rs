@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::album::album::Album;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AlbumItem {
+    pub album_id: Uuid,
+    pub user_id: Uuid,
+    pub album_title: String,
+    pub album_description: String,
+    pub album_cover_photograph_id: Option<Uuid>,
+    /// Thumbnail of the cover photograph, when one is set.
+    pub album_cover_thumbnail_link: Option<String>,
+    pub album_sort_order: i32,
+    pub album_photograph_count: i64,
+    pub album_created_at: DateTime<Utc>,
+    pub album_updated_at: DateTime<Utc>,
+}
+
+impl AlbumItem {
+    pub fn new(
+        album: Album,
+        album_cover_thumbnail_link: Option<String>,
+        album_photograph_count: i64,
+    ) -> Self {
+        Self {
+            album_id: album.album_id,
+            user_id: album.user_id,
+            album_title: album.album_title,
+            album_description: album.album_description,
+            album_cover_photograph_id: album.album_cover_photograph_id,
+            album_cover_thumbnail_link,
+            album_sort_order: album.album_sort_order,
+            album_photograph_count,
+            album_created_at: album.album_created_at,
+            album_updated_at: album.album_updated_at,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use super::album_response::AlbumItem;
+use crate::dto::responses::photography::get_photograph_response::PhotographItem;
+
+/// An album plus its photographs in album order.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GetAlbumResponse {
+    pub album: AlbumItem,
+    pub photographs: Vec<PhotographItem>,
+}
@@ -0,0 +1,9 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use super::album_response::AlbumItem;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GetAlbumsResponse {
+    pub items: Vec<AlbumItem>,
+}
@@ -0,0 +1,3 @@
+pub mod album_response;
+pub mod get_album_response;
+pub mod get_albums_response;
@@ -1,6 +1,5 @@
 pub mod delete_comment_response;
 pub mod delete_post_response;
-pub mod get_posts;
 pub mod read_post_response;
 pub mod submit_post_response;
 pub mod vote_comment_response;
@@ -11,4 +11,5 @@ pub struct SubmitPostResponse {
     pub post_created_at: DateTime<Utc>,
     pub post_updated_at: DateTime<Utc>,
     pub post_is_published: bool,
+    pub post_version: i32,
 }
@@ -0,0 +1,2 @@
+pub mod ses_feedback_response;
+pub mod unsubscribe_response;
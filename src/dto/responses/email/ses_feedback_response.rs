@@ -0,0 +1,11 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// Result of `POST /api/email/ses-feedback`. SNS only looks at the status.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SesFeedbackResponse {
+    /// Bounce and complaint recipients recorded from this message.
+    pub recorded: usize,
+    /// Addresses added to (or refreshed on) the suppression list.
+    pub suppressed: usize,
+}
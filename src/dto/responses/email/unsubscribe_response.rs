@@ -0,0 +1,10 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// What an unsubscribe link is for and whether its recipient still gets it.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UnsubscribeResponse {
+    /// The email the link opts out of, e.g. `notification_digest`.
+    pub topic: &'static str,
+    pub subscribed: bool,
+}
@@ -1,8 +1,12 @@
 pub mod admin;
+pub mod album;
 pub mod auth;
 pub mod blog;
+pub mod email;
 pub mod i18n;
 pub mod live_chat;
+pub mod notifications;
+pub mod pagination;
 pub mod photography;
 pub mod response_data;
 pub mod response_meta;
@@ -0,0 +1,27 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::notification::{
+    notification::NotificationItem, preferences::NotificationPreferences,
+};
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ListNotificationsResponse {
+    pub notifications: Vec<NotificationItem>,
+    /// All of the user's unread notifications, not only this page's.
+    pub unread_count: i64,
+    /// Pass back as `cursor` for older notifications; `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MarkNotificationsReadResponse {
+    /// Notifications that were unread and now are not.
+    pub marked: usize,
+    pub unread_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotificationPreferencesResponse {
+    pub preferences: NotificationPreferences,
+}
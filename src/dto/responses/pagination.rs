@@ -0,0 +1,152 @@
+//! Shared list envelopes: `Paginated<T>` for page-numbered lists and
+//! `CursorPage<T>` for keyset lists, so every list endpoint puts its items
+//! under `items` and its position in the same fields.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// A page-numbered slice of a list.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub pagination: PageInfo,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(items: Vec<T>, page: usize, page_size: usize, total_items: usize) -> Self {
+        Self {
+            items,
+            pagination: PageInfo::new(page, page_size, total_items),
+        }
+    }
+}
+
+/// Position of a `Paginated` page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct PageInfo {
+    /// Current page number (1-based).
+    pub page: usize,
+    /// Page size used.
+    pub page_size: usize,
+    /// Total number of items matching the query.
+    pub total_items: usize,
+    /// Total number of pages given `total_items` and `page_size`.
+    pub total_pages: usize,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+impl PageInfo {
+    pub fn new(page: usize, page_size: usize, total_items: usize) -> Self {
+        let page = page.max(1);
+        let page_size = page_size.max(1);
+        let total_pages = total_items.div_ceil(page_size);
+        Self {
+            page,
+            page_size,
+            total_items,
+            total_pages,
+            has_next: page < total_pages,
+            has_prev: page > 1 && total_pages > 0,
+        }
+    }
+}
+
+/// Index of the first item on 1-based `page`.
+pub fn page_offset(page: usize, page_size: usize) -> usize {
+    page.max(1).saturating_sub(1).saturating_mul(page_size)
+}
+
+/// A keyset page: pass `next_cursor` back as `cursor` for the next one.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    /// `None` on the last page.
+    pub next_cursor: Option<String>,
+    pub has_next: bool,
+    /// Items matching the query across all pages, on endpoints that count them.
+    pub total_items: Option<usize>,
+}
+
+impl<T> CursorPage<T> {
+    /// Builds the page from up to `page_size + 1` fetched rows; the extra row
+    /// only signals that another page follows. `cursor_of` encodes the last
+    /// kept item's sort position.
+    pub fn from_overfetched(
+        mut items: Vec<T>,
+        page_size: usize,
+        cursor_of: impl Fn(&T) -> String,
+    ) -> Self {
+        let has_next = items.len() > page_size;
+        items.truncate(page_size);
+        let next_cursor = if has_next {
+            items.last().map(|item| encode_cursor(&cursor_of(item)))
+        } else {
+            None
+        };
+        Self {
+            items,
+            next_cursor,
+            has_next,
+            total_items: None,
+        }
+    }
+
+    pub fn with_total_items(mut self, total_items: usize) -> Self {
+        self.total_items = Some(total_items);
+        self
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> CursorPage<U> {
+        CursorPage {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            has_next: self.has_next,
+            total_items: self.total_items,
+        }
+    }
+}
+
+/// Opaque form of a handler's cursor key; clients should not parse cursors.
+pub fn encode_cursor(key: &str) -> String {
+    URL_SAFE_NO_PAD.encode(key)
+}
+
+/// Inverse of `encode_cursor`; `None` for anything it did not produce.
+pub fn decode_cursor(cursor: &str) -> Option<String> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor.trim()).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_info_math() {
+        let info = PageInfo::new(2, 10, 25);
+        assert_eq!(info.total_pages, 3);
+        assert!(info.has_next && info.has_prev);
+
+        let empty = PageInfo::new(1, 10, 0);
+        assert_eq!(empty.total_pages, 0);
+        assert!(!empty.has_next && !empty.has_prev);
+
+        assert_eq!(page_offset(3, 20), 40);
+        assert_eq!(page_offset(0, 20), 0);
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let page = CursorPage::from_overfetched(vec![1, 2, 3], 2, |n| n.to_string());
+        assert_eq!(page.items, vec![1, 2]);
+        assert!(page.has_next);
+        let cursor = page.next_cursor.unwrap();
+        assert_eq!(decode_cursor(&cursor).as_deref(), Some("2"));
+
+        let last = CursorPage::from_overfetched(vec![1], 2, |n| n.to_string());
+        assert!(!last.has_next && last.next_cursor.is_none());
+        assert_eq!(decode_cursor("not base64!"), None);
+    }
+}
@@ -3,6 +3,10 @@ use serde_derive::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::domain::photography::photographs::{
+    Photograph, PhotographRenditions, renditions_srcset,
+};
+
 /// A single photograph item as exposed to API consumers.
 ///
 /// This is intentionally decoupled from the DB `Photograph` struct so we can
@@ -21,36 +25,61 @@ pub struct PhotographItem {
     pub photograph_lat: f64,
     pub photograph_lon: f64,
     pub photograph_thumbnail_link: String,
+    /// Responsive renditions keyed by size (`small`/`medium`/`large`); empty
+    /// for photographs uploaded before renditions existed.
+    pub photograph_renditions: PhotographRenditions,
+    /// Ready-made `srcset` built from `photograph_renditions`; empty when there
+    /// are none (fall back to `photograph_thumbnail_link`).
+    pub photograph_srcset: String,
     /// Persisted view count (excludes not-yet-flushed RAM deltas; the detail
     /// endpoint returns the live count).
     pub photograph_view_count: i64,
     pub photograph_total_upvotes: i64,
     pub photograph_total_downvotes: i64,
+    pub album_id: Option<Uuid>,
+    pub photograph_album_position: i32,
+    pub photograph_tags: Vec<String>,
+    pub photograph_camera_make: Option<String>,
+    pub photograph_camera_model: Option<String>,
+    pub photograph_lens_model: Option<String>,
+    pub photograph_focal_length_mm: Option<f64>,
+    pub photograph_aperture_f_number: Option<f64>,
+    pub photograph_exposure_time_seconds: Option<f64>,
+    pub photograph_iso: Option<i32>,
 }
 
-/// Pagination metadata for list endpoints.
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub struct PaginationMeta {
-    /// Current page number (1-based).
-    pub page: i64,
-    /// Page size requested/used.
-    pub page_size: i64,
-    /// Total number of items matching the query.
-    pub total_items: i64,
-    /// Total number of pages given `total_items` and `page_size`.
-    pub total_pages: i64,
-    /// Whether there is a next page after `page`.
-    pub has_next: bool,
-    /// Whether there is a previous page before `page`.
-    pub has_prev: bool,
-}
-
-/// Response DTO for paginated photograph queries.
-///
-/// This is what `GET /photographs` should serialize as the `data` portion
-/// of your `http_resp`.
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-pub struct GetPhotographsResponse {
-    pub items: Vec<PhotographItem>,
-    pub pagination: PaginationMeta,
+impl PhotographItem {
+    pub fn new(p: Photograph, photograph_tags: Vec<String>) -> Self {
+        let photograph_renditions = p.renditions();
+        let photograph_srcset = renditions_srcset(&photograph_renditions);
+        Self {
+            photograph_id: p.photograph_id,
+            user_id: p.user_id,
+            photograph_shot_at: p.photograph_shot_at,
+            photograph_created_at: p.photograph_created_at,
+            photograph_updated_at: p.photograph_updated_at,
+            photograph_image_type: p.photograph_image_type,
+            photograph_is_on_cloud: p.photograph_is_on_cloud,
+            photograph_link: p.photograph_link,
+            photograph_comments: p.photograph_comments,
+            photograph_lat: p.photograph_lat,
+            photograph_lon: p.photograph_lon,
+            photograph_thumbnail_link: p.photograph_thumbnail_link,
+            photograph_renditions,
+            photograph_srcset,
+            photograph_view_count: p.photograph_view_count,
+            photograph_total_upvotes: p.photograph_total_upvotes,
+            photograph_total_downvotes: p.photograph_total_downvotes,
+            album_id: p.album_id,
+            photograph_album_position: p.photograph_album_position,
+            photograph_tags,
+            photograph_camera_make: p.photograph_camera_make,
+            photograph_camera_model: p.photograph_camera_model,
+            photograph_lens_model: p.photograph_lens_model,
+            photograph_focal_length_mm: p.photograph_focal_length_mm,
+            photograph_aperture_f_number: p.photograph_aperture_f_number,
+            photograph_exposure_time_seconds: p.photograph_exposure_time_seconds,
+            photograph_iso: p.photograph_iso,
+        }
+    }
 }
@@ -1,5 +1,10 @@
 pub mod batch_status_response;
 pub mod delete_photograph_comment_response;
 pub mod get_photograph_response;
+pub mod photograph_duplicates_response;
+pub mod photograph_processing_response;
+pub mod photographs_in_bounds_response;
+pub mod presign_photograph_upload_response;
 pub mod read_photograph_response;
+pub mod set_photograph_tags_response;
 pub mod vote_photograph_response;
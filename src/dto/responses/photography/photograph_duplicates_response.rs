@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// One photograph inside a duplicate cluster.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PhotographDuplicateMember {
+    pub photograph_id: Uuid,
+    pub photograph_thumbnail_link: String,
+    pub photograph_comments: String,
+    pub photograph_created_at: DateTime<Utc>,
+    /// Hamming distance to the cluster's oldest photograph (0 for itself).
+    pub distance: u32,
+}
+
+/// Photographs whose perceptual hashes are within the threshold of each other
+/// (transitively), oldest first.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PhotographDuplicateCluster {
+    pub photographs: Vec<PhotographDuplicateMember>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PhotographDuplicatesResponse {
+    pub threshold: u32,
+    /// Hashed photographs compared.
+    pub scanned: usize,
+    /// Largest cluster first.
+    pub clusters: Vec<PhotographDuplicateCluster>,
+}
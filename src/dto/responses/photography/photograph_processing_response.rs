@@ -0,0 +1,37 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::photography::photographs::{Photograph, PhotographProcessingStatus};
+
+/// Processing state of an asynchronously ingested photograph.
+///
+/// Returned with **202** when an upload is accepted and by the poll endpoint
+/// at `status_url`. The links are only set once the status is `ready`.
+#[derive(Serialize, ToSchema)]
+pub struct PhotographProcessingResponse {
+    pub photograph_id: Uuid,
+    pub photograph_processing_status: PhotographProcessingStatus,
+    pub photograph_processing_error: Option<String>,
+    pub status_url: String,
+    pub photograph_link: Option<String>,
+    pub photograph_thumbnail_link: Option<String>,
+    /// Existing photograph this one is a likely near-duplicate of (warning
+    /// only; set once processing finishes).
+    pub photograph_duplicate_of: Option<Uuid>,
+}
+
+impl PhotographProcessingResponse {
+    pub fn new(photograph: &Photograph) -> Self {
+        let ready = photograph.photograph_processing_status == PhotographProcessingStatus::Ready;
+        Self {
+            photograph_id: photograph.photograph_id,
+            photograph_processing_status: photograph.photograph_processing_status,
+            photograph_processing_error: photograph.photograph_processing_error.clone(),
+            status_url: format!("/api/photographs/{}/processing", photograph.photograph_id),
+            photograph_link: ready.then(|| photograph.photograph_link.clone()),
+            photograph_thumbnail_link: ready.then(|| photograph.photograph_thumbnail_link.clone()),
+            photograph_duplicate_of: photograph.photograph_duplicate_of,
+        }
+    }
+}
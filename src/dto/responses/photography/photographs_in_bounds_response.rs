@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A single photograph pin on the map.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PhotographMapPoint {
+    pub photograph_id: Uuid,
+    pub photograph_lat: f64,
+    pub photograph_lon: f64,
+    pub photograph_thumbnail_link: String,
+    pub photograph_shot_at: Option<DateTime<Utc>>,
+}
+
+/// A grid cell summarising several photographs; the bounds let the client
+/// zoom to the cluster.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PhotographMapCluster {
+    pub photograph_count: i64,
+    pub center_lat: f64,
+    pub center_lon: f64,
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+    /// Most recently shot photograph in the cell, for a preview thumbnail.
+    pub sample_photograph_id: Uuid,
+    pub sample_thumbnail_link: String,
+}
+
+/// Either `points` (sparse viewport) or `clusters` (dense viewport) is filled.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PhotographsInBoundsResponse {
+    pub total: i64,
+    pub clustered: bool,
+    pub points: Vec<PhotographMapPoint>,
+    pub clusters: Vec<PhotographMapCluster>,
+}
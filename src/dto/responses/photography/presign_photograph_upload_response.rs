@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Response to `POST /api/photographs/presign`.
+///
+/// The client must `PUT` the original to `upload_url` with the same
+/// `Content-Type` it declared, then confirm with `upload_token` before
+/// `expires_at`.
+#[derive(Serialize, ToSchema)]
+pub struct PresignPhotographUploadResponse {
+    pub upload_token: Uuid,
+    pub upload_url: String,
+    pub upload_method: &'static str,
+    pub content_type: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -5,12 +5,15 @@ use crate::domain::photography::photographs::Photograph;
 use crate::domain::photography::social::PhotographCommentResponse;
 
 /// Detail response for a single photograph: the row (incl. denormalized view +
-/// vote counts), the caller's vote state, the enriched flat comment list, and
-/// the photograph author's badge. Comments are threaded client-side via
-/// `parent_photograph_comment_id`.
+/// vote counts), its tags, the caller's vote state, the enriched flat comment
+/// list, and the photograph author's badge. Comments are threaded client-side
+/// via `parent_photograph_comment_id`.
 #[derive(serde_derive::Serialize, ToSchema)]
 pub struct ReadPhotographResponse {
     pub photograph: Photograph,
+    pub photograph_tags: Vec<String>,
+    /// `srcset` built from `photograph.photograph_renditions`.
+    pub photograph_srcset: String,
     pub vote_state: VoteState,
     pub comments: Vec<PhotographCommentResponse>,
     pub user_badge_info: UserBadgeInfo,
@@ -0,0 +1,9 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, ToSchema)]
+pub struct SetPhotographTagsResponse {
+    pub photograph_id: Uuid,
+    pub photograph_tags: Vec<String>,
+}
@@ -1,5 +1,9 @@
 pub mod get_wasm_modules_response;
 pub mod wasm_module_response;
+pub mod wasm_module_stats_response;
 
 pub use get_wasm_modules_response::GetWasmModulesResponse;
 pub use wasm_module_response::WasmModuleItem;
+pub use wasm_module_stats_response::{
+    WasmModuleCountryLoads, WasmModuleDailyLoads, WasmModuleStatsResponse,
+};
@@ -3,7 +3,9 @@ use serde_derive::Serialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::domain::wasm_module::wasm_module::{WasmModule, WasmModuleMetadata};
+use crate::domain::wasm_module::wasm_module::{
+    WasmModule, WasmModuleMetadata, WasmModuleVisibility,
+};
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct WasmModuleItem {
@@ -15,6 +17,19 @@ pub struct WasmModuleItem {
     pub wasm_module_thumbnail_link: String,
     pub wasm_module_created_at: DateTime<Utc>,
     pub wasm_module_updated_at: DateTime<Utc>,
+    pub wasm_module_visibility: WasmModuleVisibility,
+    pub wasm_module_category: Option<String>,
+    pub wasm_module_tags: Vec<String>,
+    /// Subresource Integrity value of the bundle (`sha384-...`), for the
+    /// `integrity` attribute of the embedding page. Missing for bundles
+    /// uploaded before it was recorded.
+    pub wasm_module_bundle_integrity: Option<String>,
+    /// Bumped by every metadata edit; send it back with `PATCH` to detect
+    /// conflicts.
+    pub wasm_module_version: i32,
+    /// Users the module is shared with; only filled in for superusers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wasm_module_allowed_user_ids: Option<Vec<Uuid>>,
 }
 
 impl From<WasmModule> for WasmModuleItem {
@@ -28,6 +43,12 @@ impl From<WasmModule> for WasmModuleItem {
             wasm_module_thumbnail_link: m.wasm_module_thumbnail_link,
             wasm_module_created_at: m.wasm_module_created_at,
             wasm_module_updated_at: m.wasm_module_updated_at,
+            wasm_module_visibility: m.wasm_module_visibility,
+            wasm_module_category: m.wasm_module_category,
+            wasm_module_tags: m.wasm_module_tags,
+            wasm_module_bundle_integrity: m.wasm_module_bundle_integrity,
+            wasm_module_version: m.wasm_module_version,
+            wasm_module_allowed_user_ids: None,
         }
     }
 }
@@ -43,6 +64,12 @@ impl From<WasmModuleMetadata> for WasmModuleItem {
             wasm_module_thumbnail_link: m.wasm_module_thumbnail_link,
             wasm_module_created_at: m.wasm_module_created_at,
             wasm_module_updated_at: m.wasm_module_updated_at,
+            wasm_module_visibility: m.wasm_module_visibility,
+            wasm_module_category: m.wasm_module_category,
+            wasm_module_tags: m.wasm_module_tags,
+            wasm_module_bundle_integrity: m.wasm_module_bundle_integrity,
+            wasm_module_version: m.wasm_module_version,
+            wasm_module_allowed_user_ids: None,
         }
     }
 }
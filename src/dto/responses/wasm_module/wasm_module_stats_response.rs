@@ -0,0 +1,31 @@
+use chrono::NaiveDate;
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WasmModuleDailyLoads {
+    pub date: NaiveDate,
+    pub load_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WasmModuleCountryLoads {
+    /// ISO 3166-1 alpha-2; `ZZ` when geo-IP had no answer.
+    pub country_code: String,
+    pub load_count: i64,
+}
+
+/// Bundle loads over the requested window. Loads from the last minute may not
+/// be flushed yet.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WasmModuleStatsResponse {
+    pub wasm_module_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub total_loads: i64,
+    /// One entry per day in the window, oldest first (zero-filled).
+    pub daily: Vec<WasmModuleDailyLoads>,
+    /// Most loads first.
+    pub countries: Vec<WasmModuleCountryLoads>,
+}
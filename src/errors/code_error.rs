@@ -7,6 +7,9 @@ use std::fmt::{self, Debug};
 use tracing::Level;
 use utoipa::ToSchema;
 
+use crate::errors::problem::problem_response;
+use crate::init::db_breaker::DbUnavailable;
+
 pub type HandlerResponse<T> = Result<T, CodeErrorResp>;
 
 #[derive(Copy, Clone, Debug)]
@@ -376,6 +379,263 @@ impl CodeError {
         message: "Photograph not found!",
         log_level: Level::INFO,
     };
+    pub const PRESIGNED_UPLOAD_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 51,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Upload token not found or expired!",
+        log_level: Level::INFO,
+    };
+    pub const ALBUM_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 52,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Album not found!",
+        log_level: Level::INFO,
+    };
+
+    pub const PROCESSING_QUEUE_FULL: CodeError = CodeError {
+        success: false,
+        error_code: 53,
+        http_status_code: StatusCode::SERVICE_UNAVAILABLE,
+        message: "Image processing queue is full; retry later!",
+        log_level: Level::WARN,
+    };
+
+    pub const DUPLICATE_PHOTOGRAPH: CodeError = CodeError {
+        success: false,
+        error_code: 54,
+        http_status_code: StatusCode::CONFLICT,
+        message: "A near-duplicate of this photograph already exists!",
+        log_level: Level::INFO,
+    };
+
+    pub const STORAGE_READ_ERROR: CodeError = CodeError {
+        success: false,
+        error_code: 55,
+        http_status_code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: "Could not read stored file!",
+        log_level: Level::ERROR,
+    };
+
+    pub const ORPHAN_SCAN_IN_PROGRESS: CodeError = CodeError {
+        success: false,
+        error_code: 56,
+        http_status_code: StatusCode::CONFLICT,
+        message: "A storage orphan scan is already running!",
+        log_level: Level::INFO,
+    };
+
+    pub const UPLOAD_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 57,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Upload not found!",
+        log_level: Level::INFO,
+    };
+    pub const JOB_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 58,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Job not found!",
+        log_level: Level::INFO,
+    };
+    pub const TASK_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 59,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Task not found!",
+        log_level: Level::INFO,
+    };
+    pub const TASK_NOT_FAILED: CodeError = CodeError {
+        success: false,
+        error_code: 60,
+        http_status_code: StatusCode::CONFLICT,
+        message: "Only failed tasks can be requeued!",
+        log_level: Level::INFO,
+    };
+    pub const RATE_LIMITED: CodeError = CodeError {
+        success: false,
+        error_code: 61,
+        http_status_code: StatusCode::TOO_MANY_REQUESTS,
+        message: "Too many requests!",
+        log_level: Level::INFO,
+    };
+    pub const DB_UNAVAILABLE: CodeError = CodeError {
+        success: false,
+        error_code: 62,
+        http_status_code: StatusCode::SERVICE_UNAVAILABLE,
+        message: "Database temporarily unavailable; retry later!",
+        log_level: Level::WARN,
+    };
+    pub const TLS_RELOAD_ERROR: CodeError = CodeError {
+        success: false,
+        error_code: 63,
+        http_status_code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: "Failed to reload the TLS certificate; the previous one is still served.",
+        log_level: Level::ERROR,
+    };
+    pub const TLS_NOT_CONFIGURED: CodeError = CodeError {
+        success: false,
+        error_code: 64,
+        http_status_code: StatusCode::CONFLICT,
+        message: "This server listens on a unix socket; TLS is terminated by the reverse proxy.",
+        log_level: Level::INFO,
+    };
+    pub const WEBHOOK_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 65,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Webhook not found!",
+        log_level: Level::INFO,
+    };
+    pub const INVALID_WEBHOOK: CodeError = CodeError {
+        success: false,
+        error_code: 66,
+        http_status_code: StatusCode::BAD_REQUEST,
+        message: "Invalid webhook configuration!",
+        log_level: Level::INFO,
+    };
+    pub const VALIDATION_FAILED: CodeError = CodeError {
+        success: false,
+        error_code: 67,
+        http_status_code: StatusCode::UNPROCESSABLE_ENTITY,
+        message: "Request validation failed!",
+        log_level: Level::INFO,
+    };
+    pub const REQUEST_TIMEOUT: CodeError = CodeError {
+        success: false,
+        error_code: 68,
+        http_status_code: StatusCode::GATEWAY_TIMEOUT,
+        message: "The request took too long and was cancelled; retry later!",
+        log_level: Level::WARN,
+    };
+    pub const EDIT_CONFLICT: CodeError = CodeError {
+        success: false,
+        error_code: 69,
+        http_status_code: StatusCode::CONFLICT,
+        message: "This was changed by someone else since you loaded it!",
+        log_level: Level::INFO,
+    };
+    pub const EMAIL_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 70,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Email not found in the outbox!",
+        log_level: Level::INFO,
+    };
+    pub const EMAIL_NOT_DEAD: CodeError = CodeError {
+        success: false,
+        error_code: 71,
+        http_status_code: StatusCode::CONFLICT,
+        message: "Only dead emails can be requeued!",
+        log_level: Level::INFO,
+    };
+    pub const INVALID_SNS_MESSAGE: CodeError = CodeError {
+        success: false,
+        error_code: 72,
+        http_status_code: StatusCode::BAD_REQUEST,
+        message: "Invalid or unsigned SNS message!",
+        log_level: Level::WARN,
+    };
+    pub const SNS_TOPIC_NOT_ALLOWED: CodeError = CodeError {
+        success: false,
+        error_code: 73,
+        http_status_code: StatusCode::FORBIDDEN,
+        message: "SNS topic is not accepted here!",
+        log_level: Level::WARN,
+    };
+    pub const SNS_UNREACHABLE: CodeError = CodeError {
+        success: false,
+        error_code: 74,
+        http_status_code: StatusCode::BAD_GATEWAY,
+        message: "Could not reach SNS!",
+        log_level: Level::ERROR,
+    };
+    pub const EMAIL_SUPPRESSION_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 75,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Address is not suppressed!",
+        log_level: Level::INFO,
+    };
+    pub const EMAIL_TEMPLATE_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 76,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Email template not found!",
+        log_level: Level::INFO,
+    };
+    pub const EMAIL_RENDER_ERROR: CodeError = CodeError {
+        success: false,
+        error_code: 77,
+        http_status_code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: "Could not render the email template!",
+        log_level: Level::ERROR,
+    };
+    pub const EMAIL_SINK_NOT_CONFIGURED: CodeError = CodeError {
+        success: false,
+        error_code: 78,
+        http_status_code: StatusCode::CONFLICT,
+        message: "Emails are not being written to the local sink!",
+        log_level: Level::INFO,
+    };
+    pub const SENT_EMAIL_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 79,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Sent email not found!",
+        log_level: Level::INFO,
+    };
+    pub const INVALID_UNSUBSCRIBE_TOKEN: CodeError = CodeError {
+        success: false,
+        error_code: 80,
+        http_status_code: StatusCode::BAD_REQUEST,
+        message: "Unsubscribe link is invalid!",
+        log_level: Level::INFO,
+    };
+    pub const HOST_STATS_SUBSCRIBERS_FULL: CodeError = CodeError {
+        success: false,
+        error_code: 81,
+        http_status_code: StatusCode::SERVICE_UNAVAILABLE,
+        message: "Too many host stats subscribers; retry later!",
+        log_level: Level::WARN,
+    };
+    pub const LOG_FILTER_RELOAD_ERROR: CodeError = CodeError {
+        success: false,
+        error_code: 82,
+        http_status_code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: "Could not apply the log filter!",
+        log_level: Level::ERROR,
+    };
+    pub const LOG_FILE_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 83,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Log file not found!",
+        log_level: Level::INFO,
+    };
+    pub const ALERT_RULE_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 84,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "Alert rule not found!",
+        log_level: Level::INFO,
+    };
+    pub const INVALID_ALERT_RULE: CodeError = CodeError {
+        success: false,
+        error_code: 85,
+        http_status_code: StatusCode::BAD_REQUEST,
+        message: "Invalid alert rule!",
+        log_level: Level::INFO,
+    };
+    pub const WASM_MODULE_NOT_FOUND: CodeError = CodeError {
+        success: false,
+        error_code: 86,
+        http_status_code: StatusCode::NOT_FOUND,
+        message: "WASM module not found!",
+        log_level: Level::INFO,
+    };
 }
 
 pub fn code_err(cerr: CodeError, e: impl ToString) -> CodeErrorResp {
@@ -386,6 +646,18 @@ pub fn code_err(cerr: CodeError, e: impl ToString) -> CodeErrorResp {
         message: cerr.message.to_string(),
         error_message: e.to_string(),
         log_level: cerr.log_level,
+        field_errors: Vec::new(),
+        latest: None,
+    }
+}
+
+/// Maps a `get_conn` failure: `DB_UNAVAILABLE` while the circuit breaker is
+/// open, `POOL_ERROR` otherwise.
+pub fn pool_err(e: anyhow::Error) -> CodeErrorResp {
+    if e.is::<DbUnavailable>() {
+        code_err(CodeError::DB_UNAVAILABLE, e)
+    } else {
+        code_err(CodeError::POOL_ERROR, e)
     }
 }
 
@@ -409,6 +681,36 @@ pub struct CodeErrorResp {
     pub error_message: String,
     #[serde(skip_serializing)]
     pub log_level: Level,
+    /// Per-field problems from request validation; omitted when empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub field_errors: Vec<FieldError>,
+    /// Current state of the edited item on `EDIT_CONFLICT`; omitted otherwise.
+    /// Boxed so the rarely set field does not grow every `Err`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub latest: Option<Box<serde_json::Value>>,
+}
+
+/// One failed validation rule on a request field.
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct FieldError {
+    /// Field name as sent by the client; `None` for rules that span fields.
+    pub field: Option<String>,
+    /// Rule that failed, e.g. `length`, `range` or `email`.
+    pub code: String,
+    pub message: String,
+}
+
+impl CodeErrorResp {
+    pub fn with_field_errors(mut self, field_errors: Vec<FieldError>) -> Self {
+        self.field_errors = field_errors;
+        self
+    }
+
+    pub fn with_latest(mut self, latest: &impl serde::Serialize) -> Self {
+        self.latest = serde_json::to_value(latest).ok().map(Box::new);
+        self
+    }
 }
 
 // Implement std::fmt::Display for CodeErrorResp
@@ -424,8 +726,10 @@ impl Error for CodeErrorResp {}
 // Implement IntoResponse for CodeErrorResp
 impl IntoResponse for CodeErrorResp {
     fn into_response(self) -> axum::response::Response {
-        let body = Json(&self);
-        let mut response = (self.http_status_code, body).into_response();
+        let mut response = match problem_response(&self) {
+            Some(response) => response,
+            None => (self.http_status_code, Json(&self)).into_response(),
+        };
 
         response.extensions_mut().insert(CodeErrorLogContext {
             log_level: self.log_level,
@@ -449,10 +753,20 @@ impl From<CodeError> for CodeErrorResp {
             message: cerr.message.to_string(),
             error_message: "".to_string(),
             log_level: cerr.log_level,
+            field_errors: Vec::new(),
+            latest: None,
         }
     }
 }
 
+// Implement From<diesel::result::Error> for CodeErrorResp, so `?` carries diesel errors out of
+// `ServerState::transaction`. Statements inside it should still pick their own code with `code_err`.
+impl From<diesel::result::Error> for CodeErrorResp {
+    fn from(e: diesel::result::Error) -> Self {
+        code_err(CodeError::DB_QUERY_ERROR, e)
+    }
+}
+
 // Implement IntoResponse for CodeError
 impl IntoResponse for CodeError {
     fn into_response(self) -> axum::response::Response {
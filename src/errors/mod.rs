@@ -1 +1,2 @@
 pub mod code_error;
+pub mod problem;
@@ -0,0 +1,158 @@
+//! RFC 9457 `application/problem+json` rendering for `CodeErrorResp`.
+//!
+//! The SPA keeps getting the `{success, error_code, message}` body. A client
+//! that asks for `application/problem+json` in `Accept` (and prefers it over
+//! `application/json`) gets a problem document instead. `log_middleware`
+//! decides per request and, when it applies, runs the rest of the request
+//! inside a [`PROBLEM_CONTEXT`] scope that `CodeErrorResp::into_response`
+//! reads. Errors rendered outside that scope (outer layers, spawned tasks)
+//! fall back to the default body.
+
+use axum::{
+    Json,
+    http::{HeaderMap, HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use serde_derive::Serialize;
+
+use crate::{
+    DOMAIN_NAME,
+    errors::code_error::{CodeErrorResp, FieldError},
+};
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+tokio::task_local! {
+    pub static PROBLEM_CONTEXT: ProblemContext;
+}
+
+#[derive(Debug, Clone)]
+pub struct ProblemContext {
+    pub request_id: String,
+}
+
+#[derive(Serialize)]
+struct ProblemDetails<'a> {
+    #[serde(rename = "type")]
+    problem_type: String,
+    title: &'a str,
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<&'a str>,
+    instance: String,
+    error_code: u8,
+    #[serde(skip_serializing_if = "<[FieldError]>::is_empty")]
+    errors: &'a [FieldError],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest: Option<&'a serde_json::Value>,
+}
+
+/// Whether `Accept` lists `application/problem+json` with a non-zero q-value
+/// at least as high as `application/json`'s. Wildcards never select it.
+pub fn prefers_problem_json(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+
+    let mut problem_q = 0.0_f32;
+    let mut json_q = 0.0_f32;
+    for item in accept.split(',') {
+        let mut parts = item.split(';');
+        let media_type = parts.next().unwrap_or_default().trim();
+        let q = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if media_type.eq_ignore_ascii_case(PROBLEM_JSON) {
+            problem_q = problem_q.max(q);
+        } else if media_type.eq_ignore_ascii_case("application/json") {
+            json_q = json_q.max(q);
+        }
+    }
+    problem_q > 0.0 && problem_q >= json_q
+}
+
+/// `type` URI for an application error code. Not meant to be dereferenced.
+pub fn problem_type_uri(error_code: u8) -> String {
+    format!("https://{DOMAIN_NAME}/problems/{error_code}")
+}
+
+/// `instance` URI for a request id, which may be client-supplied.
+fn instance_uri(request_id: &str) -> String {
+    let mut uri = String::from("urn:request:");
+    for byte in request_id.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+/// The problem document for `err`, or `None` when the current request did not
+/// ask for one. The private `error_message` is only exposed as `detail` for
+/// 4xx errors, where it explains what to fix; 5xx details stay in the logs.
+pub fn problem_response(err: &CodeErrorResp) -> Option<Response> {
+    let request_id = PROBLEM_CONTEXT
+        .try_with(|context| context.request_id.clone())
+        .ok()?;
+
+    let detail = (err.http_status_code.is_client_error() && !err.error_message.is_empty())
+        .then_some(err.error_message.as_str());
+    let body = ProblemDetails {
+        problem_type: problem_type_uri(err.error_code),
+        title: &err.message,
+        status: err.http_status_code.as_u16(),
+        detail,
+        instance: instance_uri(&request_id),
+        error_code: err.error_code,
+        errors: &err.field_errors,
+        latest: err.latest.as_deref(),
+    };
+
+    let mut response = (err.http_status_code, Json(body)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    headers.append(header::VARY, HeaderValue::from_static("accept"));
+    Some(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn negotiates_problem_json() {
+        assert!(!prefers_problem_json(&HeaderMap::new()));
+        assert!(!prefers_problem_json(&accept(
+            "application/json, text/plain, */*"
+        )));
+        assert!(!prefers_problem_json(&accept("*/*")));
+        assert!(prefers_problem_json(&accept("application/problem+json")));
+        assert!(prefers_problem_json(&accept(
+            "application/problem+json, application/json;q=0.9"
+        )));
+        assert!(!prefers_problem_json(&accept(
+            "application/json, application/problem+json;q=0.5"
+        )));
+        assert!(!prefers_problem_json(&accept(
+            "application/problem+json;q=0"
+        )));
+    }
+
+    #[test]
+    fn encodes_request_id_in_instance() {
+        assert_eq!(instance_uri("abc-123"), "urn:request:abc-123");
+        assert_eq!(instance_uri("a b/c"), "urn:request:a%20b%2Fc");
+    }
+}
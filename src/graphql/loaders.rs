@@ -0,0 +1,117 @@
+use std::{collections::HashMap, fmt::Display, sync::Arc};
+
+use async_graphql::dataloader::Loader;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use tracing::error;
+use uuid::Uuid;
+
+use super::schema::Author;
+use crate::{
+    domain::blog::blog::Comment,
+    init::state::ServerState,
+    schema::{comments, user_profile_pictures, users},
+};
+
+/// Logs the cause and hands the client a generic error, as the REST
+/// handlers' `DB_QUERY_ERROR` does.
+pub(super) fn db_error(e: impl Display) -> async_graphql::Error {
+    error!(error = %e, "GraphQL database query failed");
+    async_graphql::Error::new("Database query failed")
+}
+
+/// Author badges by user id: names and country codes from `users`, the
+/// newest profile picture, and flags from the country cache.
+pub struct AuthorLoader {
+    state: Arc<ServerState>,
+}
+
+impl AuthorLoader {
+    pub fn new(state: Arc<ServerState>) -> Self {
+        Self { state }
+    }
+}
+
+impl Loader<Uuid> for AuthorLoader {
+    type Value = Author;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Author>, Self::Error> {
+        let mut conn = self.state.get_read_conn().await.map_err(db_error)?;
+
+        let authors: Vec<(Uuid, String, i32)> = users::table
+            .filter(users::user_id.eq_any(keys))
+            .select((users::user_id, users::user_name, users::user_country))
+            .load(&mut conn)
+            .await
+            .map_err(db_error)?;
+
+        let pictures: Vec<(Uuid, Option<String>)> = user_profile_pictures::table
+            .filter(user_profile_pictures::user_id.eq_any(keys))
+            .order(user_profile_pictures::user_profile_picture_updated_at.desc())
+            .select((
+                user_profile_pictures::user_id,
+                user_profile_pictures::user_profile_picture_link,
+            ))
+            .load(&mut conn)
+            .await
+            .map_err(db_error)?;
+        drop(conn);
+
+        // Newest first, so the first link seen per user wins.
+        let mut picture_map: HashMap<Uuid, String> = HashMap::new();
+        for (user_id, link) in pictures {
+            if let Some(link) = link {
+                picture_map.entry(user_id).or_insert(link);
+            }
+        }
+
+        let country_map = self.state.country_map.read().await;
+        Ok(authors
+            .into_iter()
+            .map(|(user_id, user_name, user_country)| {
+                let author = Author {
+                    user_id,
+                    user_name,
+                    profile_picture_url: picture_map.remove(&user_id),
+                    country_flag: country_map.get_flag_by_code(user_country),
+                };
+                (user_id, author)
+            })
+            .collect())
+    }
+}
+
+/// Comments by post id, oldest first.
+pub struct CommentLoader {
+    state: Arc<ServerState>,
+}
+
+impl CommentLoader {
+    pub fn new(state: Arc<ServerState>) -> Self {
+        Self { state }
+    }
+}
+
+impl Loader<Uuid> for CommentLoader {
+    type Value = Vec<Comment>;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Vec<Comment>>, Self::Error> {
+        let mut conn = self.state.get_read_conn().await.map_err(db_error)?;
+
+        let rows: Vec<Comment> = comments::table
+            .filter(comments::post_id.eq_any(keys))
+            .filter(comments::comment_deleted_at.is_null())
+            .order(comments::comment_created_at.asc())
+            .load(&mut conn)
+            .await
+            .map_err(db_error)?;
+
+        let mut by_post: HashMap<Uuid, Vec<Comment>> = HashMap::new();
+        for comment in rows {
+            by_post.entry(comment.post_id).or_default().push(comment);
+        }
+        Ok(by_post)
+    }
+}
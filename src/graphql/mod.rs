@@ -0,0 +1,58 @@
+//! GraphQL over the blog domain, at `/api/graphql` (GET or POST).
+//!
+//! Posts, comments, tags and authors in one schema so a frontend page can
+//! fetch a post list with each post's author and comments in one round-trip.
+//! Posts and tags come straight from the post cache; authors and comments
+//! go through per-request `DataLoader`s, so a page of posts costs one users
+//! query and one comments query no matter how many posts it holds. As with
+//! the REST reads, unpublished posts are visible to superusers only. There
+//! are no mutations; writes stay on the REST API.
+
+pub mod loaders;
+pub mod schema;
+
+use std::sync::{Arc, LazyLock};
+
+use async_graphql::dataloader::DataLoader;
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::{Extension, extract::State};
+
+use crate::{init::state::ServerState, routers::middleware::is_logged_in::AuthSession};
+use loaders::{AuthorLoader, CommentLoader};
+use schema::{BlogSchema, Viewer};
+
+static SCHEMA: LazyLock<BlogSchema> = LazyLock::new(schema::build_schema);
+
+/// The schema, built on first use. Holds no state; everything a query needs
+/// is attached per request.
+pub fn schema() -> &'static BlogSchema {
+    &SCHEMA
+}
+
+pub async fn graphql_handler(
+    Extension(auth_session): Extension<Option<AuthSession>>,
+    State(state): State<Arc<ServerState>>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let include_unpublished =
+        auth_session.is_some_and(|auth_session| auth_session.role_type.is_superuser());
+
+    // Loaders are per request: batching spans one query, and nothing cached
+    // in them outlives it.
+    let request = request
+        .into_inner()
+        .data(Viewer {
+            include_unpublished,
+        })
+        .data(DataLoader::new(
+            AuthorLoader::new(Arc::clone(&state)),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            CommentLoader::new(Arc::clone(&state)),
+            tokio::spawn,
+        ))
+        .data(state);
+
+    schema().execute(request).await.into()
+}
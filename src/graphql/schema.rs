@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Object, Result, Schema, SimpleObject,
+    dataloader::DataLoader,
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::loaders::{AuthorLoader, CommentLoader};
+use crate::{
+    domain::blog::blog::{CachedPostInfo, Comment},
+    dto::responses::pagination::page_offset,
+    init::state::ServerState,
+};
+
+pub type BlogSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Enough for posts { author, comments { author } } with room to spare,
+/// but not for pathological nesting.
+const MAX_DEPTH: usize = 8;
+const MAX_COMPLEXITY: usize = 1000;
+const MAX_PAGE_SIZE: u32 = 100;
+
+pub fn build_schema() -> BlogSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Per-request view of the caller.
+pub struct Viewer {
+    pub include_unpublished: bool,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Posts newest first, optionally only those tagged `tag`.
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 1)] page: u32,
+        #[graphql(default = 20)] per_page: u32,
+        tag: Option<String>,
+    ) -> Result<PostPage> {
+        let state = ctx.data::<Arc<ServerState>>()?;
+        let viewer = ctx.data::<Viewer>()?;
+        let page = page.max(1) as usize;
+        let per_page = per_page.clamp(1, MAX_PAGE_SIZE) as usize;
+
+        let (posts, total) = match tag.as_deref().map(str::trim) {
+            // The search index holds published posts only.
+            Some(tag) if !tag.is_empty() => {
+                state
+                    .search_posts_by_tag(tag, page_offset(page, per_page), per_page)
+                    .await
+            }
+            _ => {
+                state
+                    .get_posts_from_cache(page, per_page, viewer.include_unpublished)
+                    .await
+            }
+        };
+
+        Ok(PostPage {
+            posts: posts.into_iter().map(PostNode).collect(),
+            available_pages: total.div_ceil(per_page),
+        })
+    }
+
+    /// One post by id or slug.
+    async fn post(
+        &self,
+        ctx: &Context<'_>,
+        id: Option<Uuid>,
+        slug: Option<String>,
+    ) -> Result<Option<PostNode>> {
+        let state = ctx.data::<Arc<ServerState>>()?;
+        let viewer = ctx.data::<Viewer>()?;
+        let post = match (id, slug) {
+            (Some(id), None) => state.get_post_from_cache(&id).await,
+            (None, Some(slug)) => state.get_post_from_cache_by_slug(&slug).await,
+            _ => return Err("Pass exactly one of id or slug".into()),
+        };
+        Ok(post
+            .filter(|post| post.post_is_published || viewer.include_unpublished)
+            .map(PostNode))
+    }
+
+    /// Tags in use, most used first.
+    async fn tags(&self, ctx: &Context<'_>) -> Result<Vec<Tag>> {
+        let state = ctx.data::<Arc<ServerState>>()?;
+        let viewer = ctx.data::<Viewer>()?;
+        Ok(state
+            .post_tag_counts(viewer.include_unpublished)
+            .await
+            .into_iter()
+            .map(|(name, post_count)| Tag { name, post_count })
+            .collect())
+    }
+
+    async fn author(&self, ctx: &Context<'_>, user_id: Uuid) -> Result<Option<Author>> {
+        ctx.data::<DataLoader<AuthorLoader>>()?
+            .load_one(user_id)
+            .await
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct PostPage {
+    pub posts: Vec<PostNode>,
+    pub available_pages: usize,
+}
+
+#[derive(SimpleObject)]
+pub struct Tag {
+    pub name: String,
+    pub post_count: usize,
+}
+
+#[derive(Clone, SimpleObject)]
+pub struct Author {
+    pub user_id: Uuid,
+    pub user_name: String,
+    pub profile_picture_url: Option<String>,
+    pub country_flag: Option<String>,
+}
+
+/// A cached post; the body stays on `GET /api/blog/posts/{post_id}`.
+pub struct PostNode(CachedPostInfo);
+
+#[Object(name = "Post")]
+impl PostNode {
+    async fn id(&self) -> Uuid {
+        self.0.post_id
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.post_title
+    }
+
+    async fn slug(&self) -> &str {
+        &self.0.post_slug
+    }
+
+    async fn summary(&self) -> Option<&str> {
+        self.0.post_summary.as_deref()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.post_created_at
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.0.post_updated_at
+    }
+
+    async fn published_at(&self) -> Option<DateTime<Utc>> {
+        self.0.post_published_at
+    }
+
+    async fn is_published(&self) -> bool {
+        self.0.post_is_published
+    }
+
+    async fn view_count(&self) -> i64 {
+        self.0.post_view_count
+    }
+
+    async fn share_count(&self) -> i64 {
+        self.0.post_share_count
+    }
+
+    async fn total_upvotes(&self) -> i64 {
+        self.0.total_upvotes
+    }
+
+    async fn total_downvotes(&self) -> i64 {
+        self.0.total_downvotes
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.0.post_tags
+    }
+
+    async fn author(&self, ctx: &Context<'_>) -> Result<Option<Author>> {
+        ctx.data::<DataLoader<AuthorLoader>>()?
+            .load_one(self.0.user_id)
+            .await
+    }
+
+    async fn comments(&self, ctx: &Context<'_>) -> Result<Vec<CommentNode>> {
+        let comments = ctx
+            .data::<DataLoader<CommentLoader>>()?
+            .load_one(self.0.post_id)
+            .await?
+            .unwrap_or_default();
+        Ok(comments.into_iter().map(CommentNode).collect())
+    }
+}
+
+pub struct CommentNode(Comment);
+
+#[Object(name = "Comment")]
+impl CommentNode {
+    async fn id(&self) -> Uuid {
+        self.0.comment_id
+    }
+
+    async fn post_id(&self) -> Uuid {
+        self.0.post_id
+    }
+
+    async fn parent_id(&self) -> Option<Uuid> {
+        self.0.parent_comment_id
+    }
+
+    async fn content(&self) -> &str {
+        &self.0.comment_content
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.comment_created_at
+    }
+
+    async fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.0.comment_updated_at
+    }
+
+    async fn total_upvotes(&self) -> i64 {
+        self.0.total_upvotes
+    }
+
+    async fn total_downvotes(&self) -> i64 {
+        self.0.total_downvotes
+    }
+
+    async fn author(&self, ctx: &Context<'_>) -> Result<Option<Author>> {
+        ctx.data::<DataLoader<AuthorLoader>>()?
+            .load_one(self.0.user_id)
+            .await
+    }
+}
@@ -0,0 +1,112 @@
+use std::{net::IpAddr, str::FromStr, sync::Arc};
+
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use super::proto::{
+    GetI18nBundleRequest, GetI18nBundleResponse, LookupIpRequest, LookupIpResponse,
+    ValidateSessionRequest, ValidateSessionResponse, internal_server::Internal,
+};
+use crate::{domain::i18n::ui_text::locale::UiLocale, init::state::ServerState};
+
+pub struct InternalService {
+    state: Arc<ServerState>,
+}
+
+impl InternalService {
+    pub fn new(state: Arc<ServerState>) -> Self {
+        Self { state }
+    }
+
+    /// Same keys as `api_key_check_middleware`.
+    async fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let api_key = request
+            .metadata()
+            .get("x-api-key")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|key| Uuid::parse_str(key).ok())
+            .ok_or_else(|| Status::unauthenticated("Missing or malformed x-api-key"))?;
+        if self.state.check_api_key(&api_key).await {
+            Ok(())
+        } else {
+            Err(Status::unauthenticated("Invalid x-api-key"))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Internal for InternalService {
+    /// The auth middleware's checks, reported as `valid`/`reason` rather
+    /// than as an error so callers can tell a bad session from a failed call.
+    async fn validate_session(
+        &self,
+        request: Request<ValidateSessionRequest>,
+    ) -> Result<Response<ValidateSessionResponse>, Status> {
+        self.authorize(&request).await?;
+        let session_id = Uuid::from_str(request.get_ref().session_id.trim())
+            .map_err(|_| Status::invalid_argument("session_id is not a UUID"))?;
+
+        let invalid = |reason: &str| ValidateSessionResponse {
+            reason: reason.to_string(),
+            ..Default::default()
+        };
+        let response = match self.state.get_session(&session_id).await {
+            Err(_) => invalid("not_found"),
+            Ok(session) if !session.is_unexpired() => invalid("expired"),
+            Ok(session) if !session.get_is_email_verified() => invalid("unverified"),
+            Ok(session) => ValidateSessionResponse {
+                valid: true,
+                reason: String::new(),
+                user_id: session.user_id.to_string(),
+                role: session.role_type as u32,
+                user_name: session.user_name,
+                user_country: session.user_country,
+                user_language: session.user_language,
+                expires_at_unix: session.expires_at.timestamp(),
+            },
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn lookup_ip(
+        &self,
+        request: Request<LookupIpRequest>,
+    ) -> Result<Response<LookupIpResponse>, Status> {
+        self.authorize(&request).await?;
+        let ip = IpAddr::from_str(request.get_ref().ip.trim())
+            .map_err(|_| Status::invalid_argument("ip is not an IP address"))?;
+
+        let response = match self.state.lookup_ip_location(ip) {
+            Some(info) => LookupIpResponse {
+                found: true,
+                country_code: info.country_code,
+                country_name: info.country_name,
+                state: info.state,
+                city: info.city,
+                postal: info.postal,
+                latitude: info.latitude,
+                longitude: info.longitude,
+            },
+            None => LookupIpResponse::default(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_i18n_bundle(
+        &self,
+        request: Request<GetI18nBundleRequest>,
+    ) -> Result<Response<GetI18nBundleResponse>, Status> {
+        self.authorize(&request).await?;
+        let locale = UiLocale::parse(Some(request.get_ref().locale.as_str()));
+
+        let texts = self.state.ui_text_bundle(locale).await;
+        if texts.is_empty() {
+            return Err(Status::unavailable("UI text cache returned no rows"));
+        }
+        Ok(Response::new(GetI18nBundleResponse {
+            locale: locale.as_tag().to_string(),
+            fallback_locale: UiLocale::EnUs.as_tag().to_string(),
+            texts,
+        }))
+    }
+}
@@ -0,0 +1,45 @@
+//! Internal gRPC service for other backend services (`proto/internal.proto`).
+//!
+//! With `server.grpc_port` (`GRPC_PORT`) set, a tonic server on its own
+//! port answers session validation, geo-IP lookups and UI text bundles from
+//! the same `ServerState` as the HTTP API, so callers skip the HTTP envelope
+//! and TLS handshake. It speaks plain HTTP/2 and is meant for a private
+//! network; every call must carry one of the API keys (`X_API_KEY`) as
+//! `x-api-key` metadata. It stops accepting calls when shutdown begins.
+
+pub mod internal;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use crate::init::state::ServerState;
+
+pub mod proto {
+    tonic::include_proto!("internal.v1");
+}
+
+/// Serve the internal service on `addr` on its own task. A bind failure is
+/// fatal, like the API listener's.
+pub async fn spawn_grpc_server(addr: SocketAddr, state: Arc<ServerState>) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to bind gRPC listener on {addr}: {e}"))?;
+    info!(%addr, "Listening for internal gRPC calls");
+
+    let service = proto::internal_server::InternalServer::new(internal::InternalService::new(
+        Arc::clone(&state),
+    ));
+    tokio::spawn(async move {
+        let incoming = tonic::transport::server::TcpIncoming::from(listener);
+        let result = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_incoming_shutdown(incoming, state.shutdown.cancelled())
+            .await;
+        if let Err(e) = result {
+            error!(error = %e, "gRPC server exited with error");
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Read side of the sampled request log. Entries are buffered by
+//! `log_middleware` and written by the FLUSH_ACCESS_LOG job.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+use crate::{
+    domain::access_log::access_log::{AccessLogFilter, AccessLogItem},
+    dto::{
+        requests::admin::list_access_log_request::ListAccessLogRequest,
+        responses::{
+            admin::access_log_response::ListAccessLogResponse, pagination::CursorPage,
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{db::keyset::Keyset, time::now::tokio_now},
+};
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1_000;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/access-log",
+    tag = "admin",
+    params(ListAccessLogRequest),
+    responses(
+        (status = 200, description = "Sampled requests, newest first", body = ListAccessLogResponse),
+        (status = 400, description = "Invalid status class or cursor", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_access_log(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListAccessLogRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    if request
+        .status_class
+        .is_some_and(|class| !(1..=5).contains(&class))
+    {
+        return Err(code_err(
+            CodeError::INVALID_REQUEST,
+            "status_class must be between 1 and 5",
+        ));
+    }
+
+    let after = match &request.cursor {
+        None => None,
+        Some(raw) => Some(
+            Keyset::from_cursor(raw)
+                .ok_or_else(|| code_err(CodeError::INVALID_REQUEST, "Malformed cursor"))?,
+        ),
+    };
+
+    let filter = AccessLogFilter {
+        path_prefix: request.path_prefix.filter(|p| !p.is_empty()),
+        method: request.method,
+        status: request.status,
+        status_class: request.status_class,
+        country_code: request.country_code,
+        user_id: request.user_id,
+        since: request.since,
+        before: request.before,
+    };
+    // One extra row tells whether older entries remain.
+    let rows = state
+        .list_access_log(filter, after, limit + 1)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    let page = CursorPage::from_overfetched(rows, limit as usize, |entry| {
+        Keyset::new(entry.access_log_created_at, entry.access_log_id).to_string()
+    })
+    .map(AccessLogItem::from);
+
+    Ok(http_resp(
+        ListAccessLogResponse {
+            sample_rate: state.config.access_log.sample_rate,
+            entries: page.items,
+            next_cursor: page.next_cursor,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,62 @@
+//! Read side of the superuser audit trail. Entries are written by the
+//! audited handlers through `ServerState::record_admin_action`.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+use crate::{
+    domain::audit::audit::{AdminAuditFilter, AdminAuditItem},
+    dto::{
+        requests::admin::list_admin_audit_request::ListAdminAuditRequest,
+        responses::{
+            admin::admin_audit_response::ListAdminAuditResponse, response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::time::now::tokio_now,
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit",
+    tag = "admin",
+    params(ListAdminAuditRequest),
+    responses(
+        (status = 200, description = "Audit entries, newest first", body = ListAdminAuditResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_admin_audit(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListAdminAuditRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let filter = AdminAuditFilter {
+        actor_id: request.actor_id,
+        action: request.action,
+        target_type: request.target_type,
+        target_id: request.target_id,
+        before: request.before,
+    };
+    let entries = state
+        .list_admin_audit(filter, limit)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .into_iter()
+        .map(|(audit, actor_user_name)| AdminAuditItem::new(audit, actor_user_name))
+        .collect();
+
+    Ok(http_resp(ListAdminAuditResponse { entries }, (), start))
+}
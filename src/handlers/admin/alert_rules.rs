@@ -0,0 +1,378 @@
+//! Superuser CRUD for the endpoint alert rules evaluated every minute by
+//! `jobs::monitoring::evaluate_alert_rules`.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use lettre::message::Mailbox;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        alert::alert_rule::{AlertMetric, AlertRuleChangeset, AlertRuleInsertable, AlertRuleItem},
+        audit::audit::{AdminAction, changed_fields},
+    },
+    dto::{
+        requests::admin::alert_rule_request::{CreateAlertRuleRequest, UpdateAlertRuleRequest},
+        responses::{
+            admin::alert_rule_response::{
+                AlertRuleResponse, DeleteAlertRuleResponse, ListAlertRulesResponse,
+            },
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{extract::AdminActor, http::endpoint_stats::EndpointStatsWindow, time::now::tokio_now},
+};
+
+const MAX_NAME_LEN: usize = 100;
+const DEFAULT_MIN_REQUESTS: i32 = 20;
+const DEFAULT_COOLDOWN_MINUTES: i32 = 30;
+/// One week.
+const MAX_COOLDOWN_MINUTES: i32 = 7 * 24 * 60;
+
+fn invalid(message: impl ToString) -> CodeErrorResp {
+    code_err(CodeError::INVALID_ALERT_RULE, message)
+}
+
+fn validate_name(name: &str) -> Result<String, CodeErrorResp> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(invalid(format!(
+            "name must be 1 to {MAX_NAME_LEN} characters"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_threshold(metric: AlertMetric, threshold: f64) -> Result<f64, CodeErrorResp> {
+    if !threshold.is_finite() || threshold < 0.0 {
+        return Err(invalid("threshold must be a non-negative number"));
+    }
+    if metric.is_rate() && threshold > 1.0 {
+        return Err(invalid(format!(
+            "threshold for {} is a share from 0 to 1",
+            metric.as_str()
+        )));
+    }
+    Ok(threshold)
+}
+
+fn validate_min_requests(min_requests: i32) -> Result<i32, CodeErrorResp> {
+    if min_requests < 1 {
+        return Err(invalid("min_requests must be at least 1"));
+    }
+    Ok(min_requests)
+}
+
+fn validate_cooldown(minutes: i32) -> Result<i32, CodeErrorResp> {
+    if !(1..=MAX_COOLDOWN_MINUTES).contains(&minutes) {
+        return Err(invalid(format!(
+            "cooldown_minutes must be 1 to {MAX_COOLDOWN_MINUTES}"
+        )));
+    }
+    Ok(minutes)
+}
+
+/// Trimmed, uppercased; `None` for an empty string.
+fn normalize_method(method: &str) -> Option<String> {
+    let method = method.trim();
+    (!method.is_empty()).then(|| method.to_ascii_uppercase())
+}
+
+/// Trimmed; `None` for an empty string.
+fn validate_route(route: &str) -> Result<Option<String>, CodeErrorResp> {
+    let route = route.trim();
+    if route.is_empty() {
+        return Ok(None);
+    }
+    if !route.starts_with('/') {
+        return Err(invalid("route must be a route template starting with /"));
+    }
+    Ok(Some(route.to_string()))
+}
+
+fn validate_emails(emails: &[String]) -> Result<Vec<String>, CodeErrorResp> {
+    let mut addresses = Vec::with_capacity(emails.len());
+    for email in emails.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        email
+            .parse::<Mailbox>()
+            .map_err(|e| invalid(format!("invalid email {email:?}: {e}")))?;
+        if !addresses.iter().any(|a: &String| a == email) {
+            addresses.push(email.to_string());
+        }
+    }
+    Ok(addresses)
+}
+
+/// Trimmed; `None` for an empty string.
+fn validate_webhook_url(url: &str) -> Result<Option<String>, CodeErrorResp> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Ok(None);
+    }
+    let parsed =
+        reqwest::Url::parse(url).map_err(|e| invalid(format!("invalid webhook_url: {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(invalid("webhook_url must be an http(s) URL with a host"));
+    }
+    Ok(Some(url.to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/alerts/rules",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Alert rules, oldest first", body = ListAlertRulesResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_alert_rules(
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let rules = state
+        .list_alert_rules()
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .into_iter()
+        .map(AlertRuleItem::from)
+        .collect();
+
+    Ok(http_resp(ListAlertRulesResponse { rules }, (), start))
+}
+
+/// Create an alert rule. It is checked from the next minute on, against each
+/// instance's own endpoint stats.
+#[utoipa::path(
+    post,
+    path = "/api/admin/alerts/rules",
+    tag = "admin",
+    request_body = CreateAlertRuleRequest,
+    responses(
+        (status = 200, description = "Alert rule created", body = AlertRuleResponse),
+        (status = 400, description = "Invalid rule", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn create_alert_rule(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<CreateAlertRuleRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let rule = AlertRuleInsertable {
+        alert_rule_name: validate_name(&request.name)?,
+        alert_rule_metric: request.metric.as_str().to_string(),
+        alert_rule_threshold: validate_threshold(request.metric, request.threshold)?,
+        alert_rule_window: request
+            .window
+            .unwrap_or(EndpointStatsWindow::FiveMinutes)
+            .as_str()
+            .to_string(),
+        alert_rule_method: request.method.as_deref().and_then(normalize_method),
+        alert_rule_route: request
+            .route
+            .as_deref()
+            .map(validate_route)
+            .transpose()?
+            .flatten(),
+        alert_rule_min_requests: validate_min_requests(
+            request.min_requests.unwrap_or(DEFAULT_MIN_REQUESTS),
+        )?,
+        alert_rule_cooldown_minutes: validate_cooldown(
+            request.cooldown_minutes.unwrap_or(DEFAULT_COOLDOWN_MINUTES),
+        )?,
+        alert_rule_emails: validate_emails(request.emails.as_deref().unwrap_or_default())?,
+        alert_rule_webhook_url: request
+            .webhook_url
+            .as_deref()
+            .map(validate_webhook_url)
+            .transpose()?
+            .flatten(),
+        alert_rule_enabled: request.enabled.unwrap_or(true),
+    };
+
+    let rule = state
+        .insert_alert_rule(rule)
+        .await
+        .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+    info!(alert_rule_id = %rule.alert_rule_id, name = %rule.alert_rule_name, "Alert rule created");
+    let rule = AlertRuleItem::from(rule);
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::AlertRuleCreate,
+            Some(rule.alert_rule_id.to_string()),
+            serde_json::to_value(&rule).unwrap_or_default(),
+        )
+        .await;
+
+    Ok(http_resp(AlertRuleResponse { rule }, (), start))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/alerts/rules/{alert_rule_id}",
+    tag = "admin",
+    params(
+        ("alert_rule_id" = Uuid, Path, description = "Alert rule UUID")
+    ),
+    request_body = UpdateAlertRuleRequest,
+    responses(
+        (status = 200, description = "Alert rule updated", body = AlertRuleResponse),
+        (status = 400, description = "Invalid rule", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Alert rule not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn update_alert_rule(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(alert_rule_id): Path<Uuid>,
+    Json(request): Json<UpdateAlertRuleRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    // The threshold is checked against the metric the rule ends up with.
+    let current = state
+        .get_alert_rule(alert_rule_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .ok_or_else(|| code_err(CodeError::ALERT_RULE_NOT_FOUND, "Alert rule not found"))?;
+    let metric = request
+        .metric
+        .or_else(|| current.metric())
+        .ok_or_else(|| invalid("metric is required; the stored one is unknown"))?;
+    let threshold = validate_threshold(
+        metric,
+        request.threshold.unwrap_or(current.alert_rule_threshold),
+    )?;
+
+    let changeset = AlertRuleChangeset {
+        alert_rule_name: request.name.as_deref().map(validate_name).transpose()?,
+        alert_rule_metric: request.metric.map(|m| m.as_str().to_string()),
+        alert_rule_threshold: request.threshold.map(|_| threshold),
+        alert_rule_window: request.window.map(|w| w.as_str().to_string()),
+        alert_rule_method: request.method.as_deref().map(normalize_method),
+        alert_rule_route: request.route.as_deref().map(validate_route).transpose()?,
+        alert_rule_min_requests: request
+            .min_requests
+            .map(validate_min_requests)
+            .transpose()?,
+        alert_rule_cooldown_minutes: request
+            .cooldown_minutes
+            .map(validate_cooldown)
+            .transpose()?,
+        alert_rule_emails: request.emails.as_deref().map(validate_emails).transpose()?,
+        alert_rule_webhook_url: request
+            .webhook_url
+            .as_deref()
+            .map(validate_webhook_url)
+            .transpose()?,
+        alert_rule_enabled: request.enabled,
+        alert_rule_updated_at: Some(Utc::now()),
+    };
+
+    let summary = changed_fields(serde_json::json!({
+        "name": changeset.alert_rule_name,
+        "metric": changeset.alert_rule_metric,
+        "threshold": changeset.alert_rule_threshold,
+        "window": changeset.alert_rule_window,
+        "method": request.method,
+        "route": request.route,
+        "min_requests": changeset.alert_rule_min_requests,
+        "cooldown_minutes": changeset.alert_rule_cooldown_minutes,
+        "emails": changeset.alert_rule_emails,
+        "webhook_url": request.webhook_url,
+        "enabled": changeset.alert_rule_enabled,
+    }));
+
+    let rule = state
+        .update_alert_rule(alert_rule_id, changeset)
+        .await
+        .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?
+        .ok_or_else(|| code_err(CodeError::ALERT_RULE_NOT_FOUND, "Alert rule not found"))?;
+    info!(%alert_rule_id, "Alert rule updated");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::AlertRuleUpdate,
+            Some(alert_rule_id.to_string()),
+            summary,
+        )
+        .await;
+
+    Ok(http_resp(
+        AlertRuleResponse {
+            rule: AlertRuleItem::from(rule),
+        },
+        (),
+        start,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/alerts/rules/{alert_rule_id}",
+    tag = "admin",
+    params(
+        ("alert_rule_id" = Uuid, Path, description = "Alert rule UUID")
+    ),
+    responses(
+        (status = 200, description = "Alert rule deleted", body = DeleteAlertRuleResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Alert rule not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn delete_alert_rule(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(alert_rule_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    if !state
+        .delete_alert_rule(alert_rule_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_DELETION_ERROR, e))?
+    {
+        return Err(code_err(
+            CodeError::ALERT_RULE_NOT_FOUND,
+            "Alert rule not found",
+        ));
+    }
+    info!(%alert_rule_id, "Alert rule deleted");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::AlertRuleDelete,
+            Some(alert_rule_id.to_string()),
+            serde_json::json!({}),
+        )
+        .await;
+
+    Ok(http_resp(
+        DeleteAlertRuleResponse { alert_rule_id },
+        (),
+        start,
+    ))
+}
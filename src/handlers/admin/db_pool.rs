@@ -0,0 +1,33 @@
+//! Superuser view of the connection pools (`init::db_pool`).
+
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse};
+
+use crate::{
+    dto::responses::{admin::db_pool_response::DbPoolStatsResponse, response_data::http_resp},
+    errors::code_error::{CodeErrorResp, HandlerResponse},
+    init::state::ServerState,
+    util::time::now::tokio_now,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/db/pool",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Connection counts, checkout wait percentiles and recent failures per pool", body = DbPoolStatsResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp)
+    )
+)]
+pub async fn get_db_pool_stats(
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let mut pools = vec![state.pool.stats()];
+    pools.extend(state.read_pool.as_ref().map(|pool| pool.stats()));
+
+    Ok(http_resp(DbPoolStatsResponse { pools }, (), start))
+}
@@ -0,0 +1,118 @@
+//! Superuser view of the email outbox (`email_outbox`): list queued, sent and
+//! dead emails and requeue dead ones.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::{audit::audit::AdminAction, email::email_outbox::EmailOutboxItem},
+    dto::{
+        requests::admin::list_email_outbox_request::ListEmailOutboxRequest,
+        responses::{
+            admin::email_outbox_response::{ListEmailOutboxResponse, RequeueEmailResponse},
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/email-outbox",
+    tag = "admin",
+    params(ListEmailOutboxRequest),
+    responses(
+        (status = 200, description = "Outbox emails, newest first", body = ListEmailOutboxResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_email_outbox(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListEmailOutboxRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let emails = state
+        .list_email_outbox(request.status, limit)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .into_iter()
+        .map(EmailOutboxItem::from)
+        .collect();
+
+    Ok(http_resp(ListEmailOutboxResponse { emails }, (), start))
+}
+
+/// Give a dead email a fresh set of attempts, due on the next poll.
+#[utoipa::path(
+    post,
+    path = "/api/admin/email-outbox/{email_id}/requeue",
+    tag = "admin",
+    params(
+        ("email_id" = Uuid, Path, description = "Outbox email UUID")
+    ),
+    responses(
+        (status = 200, description = "Email requeued", body = RequeueEmailResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Email not found", body = CodeErrorResp),
+        (status = 409, description = "Email is not dead", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn requeue_email(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(email_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let Some(row) = state
+        .requeue_dead_email(email_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?
+    else {
+        let existing = state
+            .get_email_outbox(email_id)
+            .await
+            .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+        return Err(match existing {
+            Some(row) => code_err(
+                CodeError::EMAIL_NOT_DEAD,
+                format!("email is {}", row.email_outbox_status),
+            ),
+            None => code_err(CodeError::EMAIL_NOT_FOUND, "Email not found"),
+        });
+    };
+    info!(%email_id, template = %row.email_outbox_template, "Dead email requeued");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::EmailRequeue,
+            Some(email_id.to_string()),
+            serde_json::json!({ "template": row.email_outbox_template }),
+        )
+        .await;
+
+    Ok(http_resp(
+        RequeueEmailResponse {
+            email: EmailOutboxItem::from(row),
+        },
+        (),
+        start,
+    ))
+}
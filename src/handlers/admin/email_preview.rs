@@ -0,0 +1,79 @@
+//! Render a transactional email with sample data without sending it, so
+//! template and translation changes can be reviewed before they reach users.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+
+use crate::{
+    domain::i18n::ui_text::locale::UiLocale,
+    dto::{
+        requests::admin::email_preview_request::EmailPreviewRequest,
+        responses::{
+            admin::email_preview_response::EmailPreviewResponse, response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{
+        email::template::{EmailTemplate, render},
+        time::now::tokio_now,
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/emails/preview/{template}",
+    tag = "admin",
+    params(
+        ("template" = String, Path, description = "Template name (`verify_email`, `password_reset`)"),
+        EmailPreviewRequest
+    ),
+    responses(
+        (status = 200, description = "Rendered email", body = EmailPreviewResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Unknown template", body = CodeErrorResp),
+        (status = 500, description = "Template failed to render", body = CodeErrorResp)
+    )
+)]
+pub async fn preview_email(
+    State(state): State<Arc<ServerState>>,
+    Path(template): Path<String>,
+    Query(request): Query<EmailPreviewRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let Some(template) = EmailTemplate::parse(&template) else {
+        let known: Vec<&str> = EmailTemplate::ALL.iter().map(|t| t.as_str()).collect();
+        return Err(code_err(
+            CodeError::EMAIL_TEMPLATE_NOT_FOUND,
+            format!("{template}; known templates: {}", known.join(", ")),
+        ));
+    };
+    let locale = UiLocale::parse(request.lang.as_deref());
+    let texts = state.ui_text_bundle(locale).await;
+    let vars = template.sample_vars();
+
+    let rendered = render(template, locale.as_tag(), &texts, &vars)
+        .map_err(|e| code_err(CodeError::EMAIL_RENDER_ERROR, format!("{e:#}")))?;
+
+    let sender = state.email_sender();
+    Ok(http_resp(
+        EmailPreviewResponse {
+            template: template.as_str(),
+            lang: locale.as_tag(),
+            from: sender.from.to_string(),
+            reply_to: sender.reply_to.as_ref().map(ToString::to_string),
+            subject: sender.subject(&rendered.subject),
+            html: rendered.html,
+            text: rendered.text,
+            vars,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,182 @@
+//! Superuser view of the email suppression list (`email_suppressions`), fed
+//! by SES bounces and complaints: list suppressed addresses, suppress one by
+//! hand and lift one.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use tracing::info;
+
+use crate::{
+    domain::audit::audit::AdminAction,
+    dto::{
+        requests::admin::list_email_suppressions_request::{
+            CreateEmailSuppressionRequest, ListEmailSuppressionsRequest,
+        },
+        responses::{
+            admin::email_suppression_response::{
+                CreateEmailSuppressionResponse, DeleteEmailSuppressionResponse,
+                ListEmailSuppressionsResponse,
+            },
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/email-suppressions",
+    tag = "admin",
+    params(ListEmailSuppressionsRequest),
+    responses(
+        (status = 200, description = "Suppressed addresses, newest first", body = ListEmailSuppressionsResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_email_suppressions(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListEmailSuppressionsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let reason = request
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    let address = request
+        .address
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty());
+
+    let suppressions = state
+        .list_email_suppressions(reason, address, limit)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+
+    Ok(http_resp(
+        ListEmailSuppressionsResponse { suppressions },
+        (),
+        start,
+    ))
+}
+
+/// Stop sending to `address`, e.g. on a request made outside the
+/// unsubscribe links. Covers every email, transactional ones included.
+#[utoipa::path(
+    post,
+    path = "/api/admin/email-suppressions",
+    tag = "admin",
+    request_body = CreateEmailSuppressionRequest,
+    responses(
+        (status = 200, description = "Address suppressed (or already was)", body = CreateEmailSuppressionResponse),
+        (status = 400, description = "Invalid email address", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn create_email_suppression(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<CreateEmailSuppressionRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    if !email_address::EmailAddress::is_valid(request.address.trim()) {
+        return Err(CodeError::EMAIL_INVALID.into());
+    }
+    let note = request
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+
+    let (suppression, created) = state
+        .add_email_suppression(&request.address, note)
+        .await
+        .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+    if created {
+        info!(address = %suppression.email_suppression_address, "Email suppressed by hand");
+        state
+            .record_admin_action(
+                &actor,
+                AdminAction::EmailSuppress,
+                Some(suppression.email_suppression_address.clone()),
+                serde_json::json!({ "note": note }),
+            )
+            .await;
+    }
+
+    Ok(http_resp(
+        CreateEmailSuppressionResponse {
+            suppression,
+            created,
+        },
+        (),
+        start,
+    ))
+}
+
+/// Let the address receive email again, e.g. after the user fixed their
+/// mailbox. Another hard bounce or complaint suppresses it anew.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/email-suppressions/{address}",
+    tag = "admin",
+    params(
+        ("address" = String, Path, description = "Suppressed email address")
+    ),
+    responses(
+        (status = 200, description = "Suppression lifted", body = DeleteEmailSuppressionResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Address is not suppressed", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn delete_email_suppression(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(address): Path<String>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let Some(suppression) = state
+        .delete_email_suppression(&address)
+        .await
+        .map_err(|e| code_err(CodeError::DB_DELETION_ERROR, e))?
+    else {
+        return Err(CodeError::EMAIL_SUPPRESSION_NOT_FOUND.into());
+    };
+    info!(address = %suppression.email_suppression_address, "Email suppression lifted");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::EmailUnsuppress,
+            Some(suppression.email_suppression_address.clone()),
+            serde_json::json!({
+                "reason": suppression.email_suppression_reason,
+                "detail": suppression.email_suppression_detail,
+            }),
+        )
+        .await;
+
+    Ok(http_resp(
+        DeleteEmailSuppressionResponse { suppression },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,53 @@
+//! Latency percentiles and error rates per route, from the counters
+//! `log_middleware` keeps on this instance.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use chrono::Utc;
+
+use crate::{
+    dto::{
+        requests::admin::endpoint_stats_request::EndpointStatsRequest,
+        responses::{
+            admin::endpoint_stats_response::EndpointStatsResponse, response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeErrorResp, HandlerResponse},
+    init::state::ServerState,
+    util::time::now::tokio_now,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/endpoints/stats",
+    tag = "admin",
+    params(EndpointStatsRequest),
+    responses(
+        (status = 200, description = "Per-route latency and status counts", body = EndpointStatsResponse),
+        (status = 400, description = "Unknown window", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp)
+    )
+)]
+pub async fn get_endpoint_stats(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<EndpointStatsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let window = request.window.unwrap_or_default();
+    let endpoints = state.endpoint_stats.summary(window).await;
+
+    Ok(http_resp(
+        EndpointStatsResponse {
+            window,
+            generated_at: Utc::now(),
+            endpoints,
+        },
+        (),
+        start,
+    ))
+}
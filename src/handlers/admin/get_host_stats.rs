@@ -1,68 +1,203 @@
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 
 use axum::{
     body::Bytes,
     extract::{
-        State,
+        Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use tokio::time::{self, Duration};
-use tracing::debug;
+use tokio::time::{self, Duration, MissedTickBehavior};
+use tracing::{debug, warn};
 
-use crate::init::state::ServerState;
+use crate::{
+    dto::requests::admin::host_stats_request::HostStatsSocketRequest,
+    errors::code_error::{CodeError, code_err},
+    init::{load_cache::system_info::SystemInfo, state::ServerState},
+    util::system::get_disk_usage::DiskUsage,
+};
 
 pub struct HostStats {
     pub cpu_usage: f32,
     pub mem_total: u64,
     pub mem_free: u64,
+    pub load_average: [f32; 3],
+    pub net_rx_per_sec: u64,
+    pub net_tx_per_sec: u64,
+    pub open_fds: u64,
+    pub fd_limit: u64,
+    pub disks: Vec<DiskUsage>,
+    pub sampled_at_ms: i64,
 }
 
 impl HostStats {
+    fn from_sample(mem_total: u64, info: SystemInfo) -> Self {
+        HostStats {
+            cpu_usage: info.cpu_usage as f32,
+            mem_total,
+            mem_free: mem_total.saturating_sub(info.memory_usage),
+            load_average: info.load_average.map(|load| load as f32),
+            net_rx_per_sec: info.network_rx_bytes_per_sec,
+            net_tx_per_sec: info.network_tx_bytes_per_sec,
+            open_fds: info.open_fds.open,
+            fd_limit: info.open_fds.limit,
+            disks: info.disks,
+            sampled_at_ms: info.sampled_at.timestamp_millis(),
+        }
+    }
+
+    /// Big-endian frame. The first 20 bytes are the original layout, so older
+    /// readers keep working:
+    ///
+    /// - `f32` cpu %, `u64` mem total, `u64` mem free
+    /// - `f32` load 1m, 5m, 15m
+    /// - `u64` net rx bytes/s, `u64` net tx bytes/s
+    /// - `u64` open fds, `u64` fd soft limit
+    /// - `u16` disk count, then per disk: `u64` total, `u64` used,
+    ///   `u64` available, `u16` mount point length, mount point UTF-8
+    /// - `i64` sample time, Unix milliseconds
     fn to_bytes(&self) -> Vec<u8> {
-        let mut buf = Vec::with_capacity(24);
+        let mut buf = Vec::with_capacity(74 + self.disks.len() * 40);
         buf.extend_from_slice(&self.cpu_usage.to_be_bytes());
         buf.extend_from_slice(&self.mem_total.to_be_bytes());
         buf.extend_from_slice(&self.mem_free.to_be_bytes());
+        for load in self.load_average {
+            buf.extend_from_slice(&load.to_be_bytes());
+        }
+        buf.extend_from_slice(&self.net_rx_per_sec.to_be_bytes());
+        buf.extend_from_slice(&self.net_tx_per_sec.to_be_bytes());
+        buf.extend_from_slice(&self.open_fds.to_be_bytes());
+        buf.extend_from_slice(&self.fd_limit.to_be_bytes());
+        let disks = &self.disks[..self.disks.len().min(u16::MAX as usize)];
+        buf.extend_from_slice(&(disks.len() as u16).to_be_bytes());
+        for disk in disks {
+            buf.extend_from_slice(&disk.total_bytes.to_be_bytes());
+            buf.extend_from_slice(&disk.used_bytes.to_be_bytes());
+            buf.extend_from_slice(&disk.available_bytes.to_be_bytes());
+            let mount = disk.mount_point.as_bytes();
+            let mount = &mount[..mount.len().min(u16::MAX as usize)];
+            buf.extend_from_slice(&(mount.len() as u16).to_be_bytes());
+            buf.extend_from_slice(mount);
+        }
+        buf.extend_from_slice(&self.sampled_at_ms.to_be_bytes());
         buf
     }
 }
 
-// TODO: system information querying is unusually heavyweight for some reason. switch to cat proc or something lol
-// run on spawn_blocking; heavily blocking function fsr
-pub async fn get_host_stats(state: Arc<ServerState>) -> HostStats {
-    let mem_total = state.system_info_state.get_total_memory();
-    let mem_usage = state.system_info_state.get_memory_usage().await;
-    let cpu_usage = state.system_info_state.get_cpu_usage().await;
-    HostStats {
-        cpu_usage: cpu_usage as f32,
-        mem_total,
-        mem_free: mem_total.saturating_sub(mem_usage),
+/// One of the `host_stats.max_subscribers` places; released on drop.
+struct SubscriberSlot(Arc<ServerState>);
+
+impl SubscriberSlot {
+    fn claim(state: &Arc<ServerState>) -> Option<Self> {
+        let max = state.config.host_stats.max_subscribers;
+        state
+            .host_stats_subscribers
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |open| {
+                (open < max).then_some(open + 1)
+            })
+            .ok()
+            .map(|_| Self(Arc::clone(state)))
+    }
+}
+
+impl Drop for SubscriberSlot {
+    fn drop(&mut self) {
+        self.0.host_stats_subscribers.fetch_sub(1, Ordering::AcqRel);
     }
 }
 
+/// Superuser only. Sends up to `backfill` past samples, oldest first, then
+/// the newest sample every `interval_ms` (skipped while the sampler has not
+/// moved on). Anything the client sends other than a close is ignored.
 pub async fn ws_host_stats_handler(
     State(state): State<Arc<ServerState>>,
+    Query(request): Query<HostStatsSocketRequest>,
     ws: WebSocketUpgrade,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_host_stats_socket(socket, state.clone()))
+    let Some(slot) = SubscriberSlot::claim(&state) else {
+        warn!(
+            max_subscribers = state.config.host_stats.max_subscribers,
+            "Host stats WebSocket refused; subscriber cap reached"
+        );
+        return code_err(
+            CodeError::HOST_STATS_SUBSCRIBERS_FULL,
+            "host stats subscriber cap reached",
+        )
+        .into_response();
+    };
+    let config = &state.config.host_stats;
+    let interval = Duration::from_millis(
+        request
+            .interval_ms
+            .unwrap_or(config.interval_ms)
+            .max(config.min_interval_ms),
+    );
+    let backfill = request.backfill.unwrap_or(config.backfill);
+    ws.on_upgrade(move |socket| handle_host_stats_socket(socket, slot, interval, backfill))
 }
 
-async fn handle_host_stats_socket(mut socket: WebSocket, state: Arc<ServerState>) {
-    let mut interval = time::interval(Duration::from_millis(1000));
-    loop {
-        interval.tick().await;
-
-        let host_stats = get_host_stats(state.clone()).await;
+async fn handle_host_stats_socket(
+    mut socket: WebSocket,
+    slot: SubscriberSlot,
+    interval: Duration,
+    backfill: usize,
+) {
+    let state = Arc::clone(&slot.0);
+    let mem_total = state.system_info_state.get_total_memory();
 
-        // Fix: Bytes::from for Message::Binary
-        if let Err(e) = socket
-            .send(Message::Binary(Bytes::from(host_stats.to_bytes())))
-            .await
-        {
-            debug!(error = ?e, "Host stats WebSocket disconnected");
+    let mut last_sent = None;
+    for info in state.system_info_state.recent(backfill).await {
+        last_sent = Some(info.sampled_at);
+        if send_sample(&mut socket, mem_total, info).await.is_err() {
             return;
         }
     }
+
+    let mut ticker = time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let Some(info) = state.system_info_state.latest().await else {
+                    continue;
+                };
+                if last_sent == Some(info.sampled_at) {
+                    continue;
+                }
+                last_sent = Some(info.sampled_at);
+                if send_sample(&mut socket, mem_total, info).await.is_err() {
+                    break;
+                }
+            }
+            inbound = socket.recv() => {
+                match inbound {
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        debug!(error = ?e, "Host stats WebSocket receive error");
+                        break;
+                    }
+                }
+            }
+            _ = state.shutdown.cancelled() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+        }
+    }
+    drop(slot);
+}
+
+async fn send_sample(
+    socket: &mut WebSocket,
+    mem_total: u64,
+    info: SystemInfo,
+) -> Result<(), axum::Error> {
+    let frame = HostStats::from_sample(mem_total, info).to_bytes();
+    socket
+        .send(Message::Binary(Bytes::from(frame)))
+        .await
+        .inspect_err(|e| debug!(error = ?e, "Host stats WebSocket disconnected"))
 }
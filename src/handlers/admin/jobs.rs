@@ -0,0 +1,208 @@
+//! Superuser view of the scheduler: registered jobs with their schedule, next
+//! mark, last run and recent history (`job_executions`), plus pause/resume and
+//! cancelling a run in flight.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use tracing::info;
+
+use crate::{
+    domain::{audit::audit::AdminAction, job::execution::JobExecution},
+    dto::{
+        requests::admin::list_jobs_request::ListJobsRequest,
+        responses::{
+            admin::job_status_response::{
+                CancelJobResponse, JobStatusItem, ListJobsResponse, SetJobPausedResponse,
+            },
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+const DEFAULT_HISTORY: i64 = 10;
+const MAX_HISTORY: i64 = 100;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/jobs",
+    tag = "admin",
+    params(ListJobsRequest),
+    responses(
+        (status = 200, description = "Registered jobs, sorted by name", body = ListJobsResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_jobs(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListJobsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let history = request
+        .history
+        .unwrap_or(DEFAULT_HISTORY)
+        .clamp(0, MAX_HISTORY);
+
+    let mut jobs = Vec::new();
+    for (job_name, status) in state.job_monitor.snapshot().await {
+        let recent_runs: Vec<JobExecution> = if history > 0 {
+            state
+                .recent_job_executions(&job_name, history)
+                .await
+                .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+                .into_iter()
+                .map(JobExecution::from)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let last_run = status
+            .last_execution
+            .or_else(|| recent_runs.first().cloned());
+        let cluster_exclusive = state.job_monitor.is_cluster_exclusive(&job_name).await;
+        let paused = state.job_monitor.is_paused(&job_name).await;
+        jobs.push(JobStatusItem {
+            job_name,
+            schedule: status.schedule,
+            running: status.running,
+            next_run_at: status.next_run_at,
+            cluster_exclusive,
+            paused,
+            consecutive_failures: status.consecutive_failures,
+            last_run,
+            recent_runs,
+        });
+    }
+
+    Ok(http_resp(ListJobsResponse { jobs }, (), start))
+}
+
+/// Skip the job's ticks in this process until it is resumed. A run already in
+/// flight finishes. Not persisted: a restart applies `JOB_PAUSED_<JOB_NAME>`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/jobs/{job_name}/pause",
+    tag = "admin",
+    params(
+        ("job_name" = String, Path, description = "Job name, e.g. COMPRESS_OLD_LOGS")
+    ),
+    responses(
+        (status = 200, description = "Job paused", body = SetJobPausedResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Job not found", body = CodeErrorResp)
+    )
+)]
+pub async fn pause_job(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(job_name): Path<String>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let response = set_job_paused(&state, &actor, job_name, true).await?;
+    Ok(http_resp(response, (), start))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/jobs/{job_name}/resume",
+    tag = "admin",
+    params(
+        ("job_name" = String, Path, description = "Job name, e.g. COMPRESS_OLD_LOGS")
+    ),
+    responses(
+        (status = 200, description = "Job resumed", body = SetJobPausedResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Job not found", body = CodeErrorResp)
+    )
+)]
+pub async fn resume_job(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(job_name): Path<String>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let response = set_job_paused(&state, &actor, job_name, false).await?;
+    Ok(http_resp(response, (), start))
+}
+
+async fn set_job_paused(
+    state: &ServerState,
+    actor: &AdminActor,
+    job_name: String,
+    paused: bool,
+) -> HandlerResponse<SetJobPausedResponse> {
+    if !state.job_monitor.is_registered(&job_name).await {
+        return Err(code_err(CodeError::JOB_NOT_FOUND, "Job not found"));
+    }
+    state.job_monitor.set_paused(&job_name, paused).await;
+    info!(task_name = %job_name, paused, "Job pause state changed");
+    let action = if paused {
+        AdminAction::JobPause
+    } else {
+        AdminAction::JobResume
+    };
+    state
+        .record_admin_action(actor, action, Some(job_name.clone()), serde_json::json!({}))
+        .await;
+
+    Ok(SetJobPausedResponse { job_name, paused })
+}
+
+/// Abort the job's run in flight; it is recorded as `cancelled` and the job
+/// keeps its schedule.
+#[utoipa::path(
+    post,
+    path = "/api/admin/jobs/{job_name}/cancel",
+    tag = "admin",
+    params(
+        ("job_name" = String, Path, description = "Job name, e.g. COMPRESS_OLD_LOGS")
+    ),
+    responses(
+        (status = 200, description = "Cancellation requested (`cancelled` is false when idle)", body = CancelJobResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Job not found", body = CodeErrorResp)
+    )
+)]
+pub async fn cancel_job(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(job_name): Path<String>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    if !state.job_monitor.is_registered(&job_name).await {
+        return Err(code_err(CodeError::JOB_NOT_FOUND, "Job not found"));
+    }
+    let cancelled = state.job_monitor.cancel_run(&job_name).await;
+    if cancelled {
+        info!(task_name = %job_name, "Job run cancellation requested");
+    }
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::JobCancel,
+            Some(job_name.clone()),
+            serde_json::json!({ "cancelled": cancelled }),
+        )
+        .await;
+
+    Ok(http_resp(
+        CancelJobResponse {
+            job_name,
+            cancelled,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,115 @@
+//! Superuser listing and download of the rolled-over log files of the
+//! instance that answers (`util::log_files`). Today's file, still being
+//! written, is neither listed nor served.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{HeaderValue, Response, header},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    LOGS_DIR,
+    dto::{
+        requests::admin::log_file_request::DownloadLogFileRequest,
+        responses::{
+            admin::log_files_response::{LogFileItem, LogFilesResponse},
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{
+        log_files::{LogFile, list_log_files},
+        time::now::tokio_now,
+    },
+};
+
+async fn archived_log_files() -> Result<Vec<LogFile>, CodeErrorResp> {
+    let today = Utc::now().date_naive();
+    let files = tokio::task::spawn_blocking(|| list_log_files(Path::new(LOGS_DIR)))
+        .await
+        .map_err(|e| code_err(CodeError::JOIN_ERROR, e))?;
+    Ok(files
+        .into_iter()
+        .filter(|file| file.is_archived(today))
+        .collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/logs",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Rolled-over log files of this instance", body = LogFilesResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp)
+    )
+)]
+pub async fn get_log_files(
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let files = archived_log_files().await?;
+    let policy = &state.config.log_files;
+    let response = LogFilesResponse {
+        compress_after_days: policy.compress_after_days,
+        retention_days: policy.retention_days,
+        total_bytes: files.iter().map(|file| file.size_bytes).sum(),
+        files: files.into_iter().map(LogFileItem::from).collect(),
+    };
+    Ok(http_resp(response, (), start))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/logs/download",
+    tag = "admin",
+    params(DownloadLogFileRequest),
+    responses(
+        (status = 200, description = "The file as stored: gzip, zstd or JSON lines", content_type = "application/octet-stream"),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "No rolled-over log file by that name", body = CodeErrorResp),
+        (status = 500, description = "The file could not be opened", body = CodeErrorResp)
+    )
+)]
+pub async fn download_log_file(
+    Query(request): Query<DownloadLogFileRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    // Only names the listing shows are served, so nothing outside the logs
+    // directory (symlinks are not followed) can be reached.
+    let file = archived_log_files()
+        .await?
+        .into_iter()
+        .find(|file| file.name == request.name)
+        .ok_or_else(|| code_err(CodeError::LOG_FILE_NOT_FOUND, &request.name))?;
+
+    let handle = tokio::fs::File::open(&file.path)
+        .await
+        .map_err(|e| code_err(CodeError::STORAGE_READ_ERROR, e))?;
+
+    let mut response = Response::new(Body::from_stream(ReaderStream::new(handle)));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(file.content_type()),
+    );
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(file.size_bytes));
+    let file_name = file.name.rsplit('/').next().unwrap_or(&file.name);
+    if let Ok(disposition) = HeaderValue::from_str(&format!("attachment; filename=\"{file_name}\""))
+    {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-store"),
+    );
+    Ok(response)
+}
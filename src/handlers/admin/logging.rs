@@ -0,0 +1,99 @@
+//! Superuser view and runtime change of the console and file log filters
+//! (`init::log_levels`), e.g. DEBUG for one misbehaving module in prod.
+
+use std::sync::Arc;
+
+use axum::{Json, extract::State, response::IntoResponse};
+
+use crate::{
+    domain::audit::audit::AdminAction,
+    dto::{
+        requests::admin::update_logging_request::UpdateLoggingRequest,
+        responses::{admin::logging_response::LoggingResponse, response_data::http_resp},
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::{log_levels::LogLevels, state::ServerState},
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+fn logging_response(levels: &LogLevels) -> LoggingResponse {
+    LoggingResponse {
+        console: (&levels.console).into(),
+        file: (&levels.file).into(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/logging",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Current and startup log filters of this instance", body = LoggingResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp)
+    )
+)]
+pub async fn get_logging(
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    Ok(http_resp(logging_response(&state.log_levels), (), start))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/logging",
+    tag = "admin",
+    request_body = UpdateLoggingRequest,
+    responses(
+        (status = 200, description = "Filters applied on this instance until restart", body = LoggingResponse),
+        (status = 400, description = "Too many targets or a target that is not a module path", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "The subscriber refused the new filter", body = CodeErrorResp)
+    )
+)]
+pub async fn update_logging(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<UpdateLoggingRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    for spec in [&request.console, &request.file].into_iter().flatten() {
+        spec.validate()
+            .map_err(|e| code_err(CodeError::INVALID_REQUEST, e))?;
+    }
+    let levels = &state.log_levels;
+    if let Some(console) = request.console.clone() {
+        levels
+            .console
+            .set(console)
+            .map_err(|e| code_err(CodeError::LOG_FILTER_RELOAD_ERROR, e))?;
+    }
+    if let Some(file) = request.file.clone() {
+        levels
+            .file
+            .set(file)
+            .map_err(|e| code_err(CodeError::LOG_FILTER_RELOAD_ERROR, e))?;
+    }
+    tracing::warn!(
+        actor = %actor.user_id,
+        console = ?request.console,
+        file = ?request.file,
+        "Log filters changed at runtime"
+    );
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::LoggingUpdate,
+            None,
+            serde_json::json!({
+                "console": request.console,
+                "file": request.file,
+            }),
+        )
+        .await;
+
+    Ok(http_resp(logging_response(levels), (), start))
+}
@@ -1,2 +1,22 @@
+pub mod access_log;
+pub mod admin_audit;
+pub mod alert_rules;
+pub mod db_pool;
+pub mod email_outbox;
+pub mod email_preview;
+pub mod email_suppressions;
+pub mod endpoint_stats;
 pub mod get_host_stats;
+pub mod jobs;
+pub mod log_files;
+pub mod logging;
+pub mod sent_emails;
+pub mod session_purges;
+pub mod session_stats;
+pub mod stats;
+pub mod storage_orphans;
 pub mod sync_i18n_cache;
+pub mod tasks;
+pub mod tls;
+pub mod trash;
+pub mod webhooks;
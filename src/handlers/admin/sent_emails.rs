@@ -0,0 +1,105 @@
+//! Superuser view of the local email sink (`EMAIL_TRANSPORT=file`): what
+//! would have been delivered, read back from the `.eml` files.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+
+use crate::{
+    dto::{
+        requests::admin::list_sent_emails_request::ListSentEmailsRequest,
+        responses::{
+            admin::sent_email_response::{ListSentEmailsResponse, SentEmailResponse},
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{email::transport::file::FileTransport, time::now::tokio_now},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+fn file_sink(state: &ServerState) -> Result<&FileTransport, CodeErrorResp> {
+    let transport = state.email_transport();
+    transport.as_file_sink().ok_or_else(|| {
+        code_err(
+            CodeError::EMAIL_SINK_NOT_CONFIGURED,
+            format!("email transport is {}", transport.name()),
+        )
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/emails/sent",
+    tag = "admin",
+    params(ListSentEmailsRequest),
+    responses(
+        (status = 200, description = "Captured emails, newest first", body = ListSentEmailsResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 409, description = "Email transport is not the local sink", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_sent_emails(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListSentEmailsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let sink = file_sink(&state)?;
+
+    let emails = sink
+        .list(limit as usize)
+        .await
+        .map_err(|e| code_err(CodeError::STORAGE_READ_ERROR, e))?;
+
+    Ok(http_resp(
+        ListSentEmailsResponse {
+            dir: sink.dir().display().to_string(),
+            emails,
+        },
+        (),
+        start,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/emails/sent/{id}",
+    tag = "admin",
+    params(
+        ("id" = String, Path, description = "Captured email id, as listed")
+    ),
+    responses(
+        (status = 200, description = "Captured email with its MIME source", body = SentEmailResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "No such email", body = CodeErrorResp),
+        (status = 409, description = "Email transport is not the local sink", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_sent_email(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let sink = file_sink(&state)?;
+
+    let Some((email, raw)) = sink
+        .read(&id)
+        .await
+        .map_err(|e| code_err(CodeError::STORAGE_READ_ERROR, e))?
+    else {
+        return Err(code_err(CodeError::SENT_EMAIL_NOT_FOUND, id));
+    };
+
+    Ok(http_resp(SentEmailResponse { email, raw }, (), start))
+}
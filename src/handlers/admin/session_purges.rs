@@ -0,0 +1,66 @@
+//! Superuser view of the expired-session purge
+//! (`jobs::auth::invalidate_sessions`).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+use crate::{
+    dto::{
+        requests::admin::list_session_purges_request::ListSessionPurgesRequest,
+        responses::{
+            admin::session_purge_response::SessionPurgeHistoryResponse, response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeErrorResp, HandlerResponse},
+    init::state::ServerState,
+    jobs::auth::invalidate_sessions::MAX_PURGE_REPORTS,
+    util::time::now::tokio_now,
+};
+
+const DEFAULT_LIMIT: usize = 48;
+const PURGE_JOB_NAME: &str = "INVALIDATE_EXPIRED_SESSIONS";
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/sessions/purges",
+    tag = "admin",
+    params(ListSessionPurgesRequest),
+    responses(
+        (status = 200, description = "Purge schedule, totals and recent runs, newest first", body = SessionPurgeHistoryResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp)
+    )
+)]
+pub async fn list_session_purges(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListSessionPurgesRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, MAX_PURGE_REPORTS);
+
+    let schedule = state
+        .job_monitor
+        .snapshot()
+        .await
+        .into_iter()
+        .find(|(name, _)| name == PURGE_JOB_NAME)
+        .map(|(_, status)| status.schedule);
+
+    Ok(http_resp(
+        SessionPurgeHistoryResponse {
+            schedule,
+            purged_total: state.session_purges.purged_total(),
+            active_sessions: state.get_session_length(),
+            reports: state.session_purges.recent(limit).await,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,84 @@
+//! Superuser view of the in-memory sessions: what is live now, by country
+//! and age, and how fast sessions are opened, closed and expired
+//! (`init::state::session_stats`). Answers for this instance only.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+use crate::{
+    dto::{
+        requests::admin::session_stats_request::SessionStatsRequest,
+        responses::{
+            admin::session_stats_response::{SessionCountryCount, SessionStatsResponse},
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeErrorResp, HandlerResponse},
+    init::state::{ServerState, session_stats::SESSION_STATS_HOURS},
+    util::time::now::tokio_now,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/sessions/stats",
+    tag = "admin",
+    params(SessionStatsRequest),
+    responses(
+        (status = 200, description = "Live session counts and hourly session events", body = SessionStatsResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp)
+    )
+)]
+pub async fn get_session_stats(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<SessionStatsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let hours = request
+        .hours
+        .unwrap_or(SESSION_STATS_HOURS)
+        .clamp(1, SESSION_STATS_HOURS);
+
+    let breakdown = state.session_breakdown().await;
+    let mut countries: Vec<SessionCountryCount> = {
+        let country_map = state.country_map.read().await;
+        breakdown
+            .by_country
+            .iter()
+            .map(|(&country_code, &sessions)| {
+                let country = country_map.lookup_by_code(country_code).map(|c| &c.country);
+                SessionCountryCount {
+                    country_code,
+                    country_alpha2: country.map(|c| c.country_alpha2.clone()),
+                    country_name: country.map(|c| c.country_eng_name.clone()),
+                    country_flag: country.map(|c| c.country_flag.clone()),
+                    sessions,
+                }
+            })
+            .collect()
+    };
+    countries.sort_unstable_by(|a, b| {
+        b.sessions
+            .cmp(&a.sessions)
+            .then_with(|| a.country_code.cmp(&b.country_code))
+    });
+
+    Ok(http_resp(
+        SessionStatsResponse {
+            active_sessions: breakdown.active,
+            expired_awaiting_purge: breakdown.expired,
+            distinct_users: breakdown.users.len(),
+            average_age_secs: breakdown.average_age_secs(),
+            oldest_age_secs: breakdown.oldest_age_secs,
+            countries,
+            totals: state.session_stats.totals(),
+            hourly: state.session_stats.hourly(hours),
+        },
+        (),
+        start,
+    ))
+}
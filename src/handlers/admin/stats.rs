@@ -0,0 +1,56 @@
+//! The admin overview: site totals, growth over a range and live sessions in
+//! one response, so the page needs a single request.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+use crate::{
+    dto::{
+        requests::admin::admin_stats_request::AdminStatsRequest,
+        responses::{admin::admin_stats_response::AdminStatsResponse, response_data::http_resp},
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::time::now::tokio_now,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/stats",
+    tag = "admin",
+    params(AdminStatsRequest),
+    responses(
+        (status = 200, description = "Overview statistics", body = AdminStatsResponse),
+        (status = 400, description = "Unknown range", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_admin_stats(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<AdminStatsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let range = request.range.unwrap_or_default();
+
+    let stats = state
+        .dashboard_stats(range, request.refresh)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    let (active_sessions, active_users) = state.active_session_counts().await;
+
+    Ok(http_resp(
+        AdminStatsResponse {
+            stats: stats.as_ref().clone(),
+            active_sessions,
+            active_users,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,89 @@
+//! Superuser view of the storage/DB reconciliation job
+//! (`jobs::maintenance::reconcile_storage_orphans`).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+use crate::{
+    domain::audit::audit::AdminAction,
+    dto::{
+        requests::admin::scan_storage_orphans_request::ScanStorageOrphansRequest,
+        responses::{admin::storage_orphan_report::StorageOrphanReport, response_data::http_resp},
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    jobs::maintenance::reconcile_storage_orphans::reconcile_storage_orphans,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/storage/orphans",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Most recent orphan report; null before the first pass", body = Option<StorageOrphanReport>),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp)
+    )
+)]
+pub async fn get_storage_orphan_report(
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let report = state.storage_orphans.last_report().await;
+    Ok(http_resp(report, (), start))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/storage/orphans/scan",
+    tag = "admin",
+    params(ScanStorageOrphansRequest),
+    responses(
+        (status = 200, description = "Reconciliation pass finished", body = StorageOrphanReport),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 409, description = "A pass is already running", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn scan_storage_orphans(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ScanStorageOrphansRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let report = reconcile_storage_orphans(&state, request.dry_run.unwrap_or(true))
+        .await
+        .map_err(|e| code_err(CodeError::STORAGE_READ_ERROR, e))?
+        .ok_or_else(|| {
+            code_err(
+                CodeError::ORPHAN_SCAN_IN_PROGRESS,
+                "A storage orphan scan is already running",
+            )
+        })?;
+
+    // A dry run changes nothing, so only deleting scans are audited.
+    if !report.dry_run {
+        state
+            .record_admin_action(
+                &actor,
+                AdminAction::StorageOrphanScan,
+                None,
+                serde_json::json!({
+                    "backend": report.backend,
+                    "orphan_count": report.orphan_count,
+                    "orphan_bytes": report.orphan_bytes,
+                    "deleted_count": report.deleted_count,
+                }),
+            )
+            .await;
+    }
+
+    Ok(http_resp(report, (), start))
+}
@@ -3,12 +3,13 @@ use std::sync::Arc;
 use axum::{extract::State, response::IntoResponse};
 
 use crate::{
+    domain::audit::audit::AdminAction,
     dto::responses::{
         admin::sync_i18n_cache_response::SyncI18nCacheResponse, response_data::http_resp,
     },
     errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
     init::state::ServerState,
-    util::time::now::tokio_now,
+    util::{extract::AdminActor, time::now::tokio_now},
 };
 
 #[utoipa::path(
@@ -21,6 +22,7 @@ use crate::{
     )
 )]
 pub async fn sync_i18n_cache(
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
@@ -35,5 +37,14 @@ pub async fn sync_i18n_cache(
         .await
         .map_err(|e| code_err(CodeError::COULD_NOT_SYNC_18N_CACHE, e))?;
 
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::I18nSync,
+            None,
+            serde_json::json!({ "num_rows": num_rows }),
+        )
+        .await;
+
     Ok(http_resp(SyncI18nCacheResponse { num_rows }, (), start))
 }
@@ -0,0 +1,118 @@
+//! Superuser view of the task queue (`delayed_tasks`): list tasks and requeue
+//! failed ones.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::{audit::audit::AdminAction, job::delayed_task::DelayedTaskItem},
+    dto::{
+        requests::admin::list_tasks_request::ListTasksRequest,
+        responses::{
+            admin::task_response::{ListTasksResponse, RequeueTaskResponse},
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/tasks",
+    tag = "admin",
+    params(ListTasksRequest),
+    responses(
+        (status = 200, description = "Queued tasks, newest first", body = ListTasksResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_tasks(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListTasksRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let tasks = state
+        .list_delayed_tasks(request.status, limit)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .into_iter()
+        .map(DelayedTaskItem::from)
+        .collect();
+
+    Ok(http_resp(ListTasksResponse { tasks }, (), start))
+}
+
+/// Give a failed task a fresh set of attempts, due on the next poll.
+#[utoipa::path(
+    post,
+    path = "/api/admin/tasks/{task_id}/requeue",
+    tag = "admin",
+    params(
+        ("task_id" = Uuid, Path, description = "Task UUID")
+    ),
+    responses(
+        (status = 200, description = "Task requeued", body = RequeueTaskResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Task not found", body = CodeErrorResp),
+        (status = 409, description = "Task is not failed", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn requeue_task(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(task_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let Some(row) = state
+        .requeue_failed_delayed_task(task_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?
+    else {
+        let existing = state
+            .get_delayed_task(task_id)
+            .await
+            .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+        return Err(match existing {
+            Some(row) => code_err(
+                CodeError::TASK_NOT_FAILED,
+                format!("task is {}", row.delayed_task_status),
+            ),
+            None => code_err(CodeError::TASK_NOT_FOUND, "Task not found"),
+        });
+    };
+    info!(%task_id, kind = %row.delayed_task_kind, "Failed task requeued");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::TaskRequeue,
+            Some(task_id.to_string()),
+            serde_json::json!({ "kind": row.delayed_task_kind }),
+        )
+        .await;
+
+    Ok(http_resp(
+        RequeueTaskResponse {
+            task: DelayedTaskItem::from(row),
+        },
+        (),
+        start,
+    ))
+}
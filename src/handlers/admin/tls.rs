@@ -0,0 +1,78 @@
+//! Superuser view and manual reload of the HTTPS certificate
+//! (`init::tls_reload`).
+
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse};
+
+use crate::{
+    domain::audit::audit::AdminAction,
+    dto::responses::{admin::tls_response::TlsStatus, response_data::http_resp},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/tls",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Certificate paths, load time and the last reload error; null on a unix socket listener", body = Option<TlsStatus>),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp)
+    )
+)]
+pub async fn get_tls_status(
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    Ok(http_resp(
+        state.tls.as_ref().map(|tls| tls.status()),
+        (),
+        start,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/tls/reload",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Certificate and key re-read from disk", body = TlsStatus),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 409, description = "Serving on a unix socket; there is no certificate", body = CodeErrorResp),
+        (status = 500, description = "Reload failed; the previous certificate is still served", body = CodeErrorResp)
+    )
+)]
+pub async fn reload_tls(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let Some(tls) = state.tls.as_ref() else {
+        return Err(code_err(
+            CodeError::TLS_NOT_CONFIGURED,
+            "no TLS listener to reload",
+        ));
+    };
+    tls.reload()
+        .await
+        .map_err(|e| code_err(CodeError::TLS_RELOAD_ERROR, e))?;
+    let status = tls.status();
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::TlsReload,
+            None,
+            serde_json::json!({
+                "cert_path": status.cert_path,
+                "loaded_at": status.loaded_at,
+            }),
+        )
+        .await;
+
+    Ok(http_resp(status, (), start))
+}
@@ -0,0 +1,75 @@
+//! Superuser restore of soft-deleted posts, comments and photographs.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::soft_delete::soft_delete::SoftDeleteKind,
+    dto::responses::{admin::restore_response::RestoreResponse, response_data::http_resp},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+/// Undo a delete that has not been purged yet (`SOFT_DELETE_RETENTION_DAYS`).
+#[utoipa::path(
+    post,
+    path = "/api/admin/trash/{kind}/{id}/restore",
+    tag = "admin",
+    params(
+        ("kind" = SoftDeleteKind, Path, description = "posts, comments or photographs"),
+        ("id" = Uuid, Path, description = "ID of the deleted row")
+    ),
+    responses(
+        (status = 200, description = "Row restored", body = RestoreResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "No deleted row with that ID", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn restore_soft_deleted(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path((kind, id)): Path<(SoftDeleteKind, Uuid)>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let restored = state
+        .restore_soft_deleted(kind, id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
+    if !restored {
+        let not_found = match kind {
+            SoftDeleteKind::Post => CodeError::POST_NOT_FOUND,
+            SoftDeleteKind::Comment => CodeError::COMMENT_NOT_FOUND,
+            SoftDeleteKind::Photograph => CodeError::PHOTOGRAPH_NOT_FOUND,
+        };
+        return Err(code_err(not_found, "No deleted row with that ID"));
+    }
+
+    info!(?kind, %id, "Soft-deleted row restored");
+    state
+        .record_admin_action(
+            &actor,
+            kind.restore_action(),
+            Some(id.to_string()),
+            serde_json::json!({}),
+        )
+        .await;
+
+    Ok(http_resp(
+        RestoreResponse {
+            kind,
+            restored_id: id,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,329 @@
+//! Superuser registry of outgoing webhooks and their delivery log. Events are
+//! queued by `jobs::tasks::webhook::emit_webhook_event`.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        audit::audit::{AdminAction, changed_fields},
+        webhook::{
+            delivery::WebhookDeliveryItem,
+            webhook::{WebhookChangeset, WebhookEventType, WebhookInsertable, WebhookItem},
+        },
+    },
+    dto::{
+        requests::admin::webhook_request::{
+            CreateWebhookRequest, ListWebhookDeliveriesRequest, UpdateWebhookRequest,
+        },
+        responses::{
+            admin::webhook_response::{
+                DeleteWebhookResponse, ListWebhookDeliveriesResponse, ListWebhooksResponse,
+                WebhookResponse,
+            },
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    jobs::tasks::webhook::generate_webhook_secret,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+const MIN_SECRET_LEN: usize = 16;
+
+fn validate_url(url: &str) -> Result<String, CodeErrorResp> {
+    let url = url.trim();
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| code_err(CodeError::INVALID_WEBHOOK, format!("invalid url: {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(code_err(
+            CodeError::INVALID_WEBHOOK,
+            "url must be an http(s) URL with a host",
+        ));
+    }
+    Ok(url.to_string())
+}
+
+fn validate_event_types(event_types: &[WebhookEventType]) -> Result<Vec<String>, CodeErrorResp> {
+    let mut names: Vec<String> = event_types
+        .iter()
+        .map(|event_type| event_type.as_str().to_string())
+        .collect();
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        return Err(code_err(
+            CodeError::INVALID_WEBHOOK,
+            "at least one event type is required",
+        ));
+    }
+    Ok(names)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/webhooks",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Registered webhooks, oldest first", body = ListWebhooksResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_webhooks(
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let webhooks = state
+        .list_webhooks()
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .into_iter()
+        .map(WebhookItem::from)
+        .collect();
+
+    Ok(http_resp(ListWebhooksResponse { webhooks }, (), start))
+}
+
+/// Register a webhook. The signing secret is only ever returned here and when
+/// it is rotated.
+#[utoipa::path(
+    post,
+    path = "/api/admin/webhooks",
+    tag = "admin",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 200, description = "Webhook created", body = WebhookResponse),
+        (status = 400, description = "Invalid url, event types or secret", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn create_webhook(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let url = validate_url(&request.url)?;
+    let event_types = validate_event_types(&request.event_types)?;
+    let secret = match request.secret {
+        Some(secret) if secret.len() < MIN_SECRET_LEN => {
+            return Err(code_err(
+                CodeError::INVALID_WEBHOOK,
+                format!("secret must be at least {MIN_SECRET_LEN} characters"),
+            ));
+        }
+        Some(secret) => secret,
+        None => generate_webhook_secret(),
+    };
+
+    let webhook = state
+        .insert_webhook(WebhookInsertable {
+            webhook_url: url,
+            webhook_secret: secret.clone(),
+            webhook_event_types: event_types,
+            webhook_enabled: request.enabled.unwrap_or(true),
+        })
+        .await
+        .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+    info!(webhook_id = %webhook.webhook_id, url = %webhook.webhook_url, "Webhook created");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::WebhookCreate,
+            Some(webhook.webhook_id.to_string()),
+            serde_json::json!({
+                "url": webhook.webhook_url,
+                "event_types": webhook.webhook_event_types,
+                "enabled": webhook.webhook_enabled,
+            }),
+        )
+        .await;
+
+    Ok(http_resp(
+        WebhookResponse {
+            webhook: WebhookItem::from(webhook),
+            secret: Some(secret),
+        },
+        (),
+        start,
+    ))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/webhooks/{webhook_id}",
+    tag = "admin",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook UUID")
+    ),
+    request_body = UpdateWebhookRequest,
+    responses(
+        (status = 200, description = "Webhook updated", body = WebhookResponse),
+        (status = 400, description = "Invalid url or event types", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Webhook not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn update_webhook(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(webhook_id): Path<Uuid>,
+    Json(request): Json<UpdateWebhookRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let secret = request.rotate_secret.then(generate_webhook_secret);
+    let changeset = WebhookChangeset {
+        webhook_url: request.url.as_deref().map(validate_url).transpose()?,
+        webhook_secret: secret.clone(),
+        webhook_event_types: request
+            .event_types
+            .as_deref()
+            .map(validate_event_types)
+            .transpose()?,
+        webhook_enabled: request.enabled,
+        webhook_updated_at: Some(Utc::now()),
+    };
+
+    // The secret itself is never written to the audit trail.
+    let summary = changed_fields(serde_json::json!({
+        "url": changeset.webhook_url,
+        "event_types": changeset.webhook_event_types,
+        "enabled": changeset.webhook_enabled,
+        "secret_rotated": secret.is_some().then_some(true),
+    }));
+
+    let webhook = state
+        .update_webhook(webhook_id, changeset)
+        .await
+        .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?
+        .ok_or_else(|| code_err(CodeError::WEBHOOK_NOT_FOUND, "Webhook not found"))?;
+    info!(%webhook_id, secret_rotated = secret.is_some(), "Webhook updated");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::WebhookUpdate,
+            Some(webhook_id.to_string()),
+            summary,
+        )
+        .await;
+
+    Ok(http_resp(
+        WebhookResponse {
+            webhook: WebhookItem::from(webhook),
+            secret,
+        },
+        (),
+        start,
+    ))
+}
+
+/// Delete a webhook together with its delivery log. Queued deliveries are
+/// dropped.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/webhooks/{webhook_id}",
+    tag = "admin",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook UUID")
+    ),
+    responses(
+        (status = 200, description = "Webhook deleted", body = DeleteWebhookResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Webhook not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn delete_webhook(
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(webhook_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    if !state
+        .delete_webhook(webhook_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_DELETION_ERROR, e))?
+    {
+        return Err(code_err(CodeError::WEBHOOK_NOT_FOUND, "Webhook not found"));
+    }
+    info!(%webhook_id, "Webhook deleted");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::WebhookDelete,
+            Some(webhook_id.to_string()),
+            serde_json::json!({}),
+        )
+        .await;
+
+    Ok(http_resp(DeleteWebhookResponse { webhook_id }, (), start))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/webhooks/{webhook_id}/deliveries",
+    tag = "admin",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook UUID"),
+        ListWebhookDeliveriesRequest
+    ),
+    responses(
+        (status = 200, description = "Deliveries, newest first", body = ListWebhookDeliveriesResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Webhook not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_webhook_deliveries(
+    State(state): State<Arc<ServerState>>,
+    Path(webhook_id): Path<Uuid>,
+    Query(request): Query<ListWebhookDeliveriesRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    if !state
+        .webhook_exists(webhook_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+    {
+        return Err(code_err(CodeError::WEBHOOK_NOT_FOUND, "Webhook not found"));
+    }
+
+    let deliveries = state
+        .list_webhook_deliveries(webhook_id, request.status, limit)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .into_iter()
+        .map(WebhookDeliveryItem::from)
+        .collect();
+
+    Ok(http_resp(
+        ListWebhookDeliveriesResponse { deliveries },
+        (),
+        start,
+    ))
+}
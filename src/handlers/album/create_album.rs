@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, response::IntoResponse};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        album::album::{Album, AlbumInsertable},
+        audit::audit::AdminAction,
+    },
+    dto::{
+        requests::album::create_album_request::CreateAlbumRequest,
+        responses::{album::album_response::AlbumItem, response_data::http_resp},
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::{albums, photographs},
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+use super::get_albums::album_items;
+
+/// Reject a cover id that does not reference an existing photograph.
+pub(crate) async fn ensure_cover_photograph_exists(
+    conn: &mut AsyncPgConnection,
+    cover_photograph_id: Uuid,
+) -> Result<(), CodeErrorResp> {
+    let exists: i64 = photographs::table
+        .filter(photographs::photograph_id.eq(cover_photograph_id))
+        .count()
+        .get_result(conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, photograph_id = %cover_photograph_id, "Failed to query cover photograph");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?;
+
+    if exists == 0 {
+        return Err(code_err(
+            CodeError::PHOTOGRAPH_NOT_FOUND,
+            "Cover photograph not found",
+        ));
+    }
+
+    Ok(())
+}
+
+/// POST /api/albums
+/// Superuser only - creates an empty album
+#[utoipa::path(
+    post,
+    path = "/api/albums",
+    tag = "album",
+    request_body = CreateAlbumRequest,
+    responses(
+        (status = 200, description = "Album created", body = AlbumItem),
+        (status = 400, description = "Invalid request", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Cover photograph not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn create_album(
+    Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Json(body): Json<CreateAlbumRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let album_title = body.album_title.trim().to_string();
+    if album_title.is_empty() {
+        return Err(code_err(
+            CodeError::INVALID_REQUEST,
+            "Album title is required",
+        ));
+    }
+
+    let mut conn = state.get_conn().await.map_err(|e| {
+        error!(error = ?e, "Failed to get DB connection");
+        pool_err(e)
+    })?;
+
+    if let Some(cover_id) = body.album_cover_photograph_id {
+        ensure_cover_photograph_exists(&mut conn, cover_id).await?;
+    }
+
+    let album: Album = diesel::insert_into(albums::table)
+        .values(AlbumInsertable {
+            user_id,
+            album_title,
+            album_description: body.album_description.unwrap_or_default(),
+            album_cover_photograph_id: body.album_cover_photograph_id,
+            album_sort_order: body.album_sort_order.unwrap_or(0),
+        })
+        .returning(Album::as_returning())
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, user_id = %user_id, "Failed to insert album");
+            code_err(CodeError::DB_INSERTION_ERROR, e)
+        })?;
+
+    let album_id = album.album_id;
+
+    let item = album_items(&state, &mut conn, vec![album])
+        .await
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to query album cover");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?
+        .pop()
+        .ok_or_else(|| code_err(CodeError::ALBUM_NOT_FOUND, "Album not found"))?;
+
+    drop(conn);
+
+    info!(album_id = %album_id, user_id = %user_id, "Album created");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::AlbumCreate,
+            Some(album_id.to_string()),
+            serde_json::json!({ "title": item.album_title }),
+        )
+        .await;
+
+    Ok(http_resp(item, (), start))
+}
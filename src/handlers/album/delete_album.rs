@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use serde_derive::Serialize;
+use tracing::{error, info};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    domain::audit::audit::AdminAction,
+    dto::responses::response_data::http_resp,
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::albums,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteAlbumResponse {
+    pub deleted_album_id: Uuid,
+}
+
+/// DELETE /api/albums/{album_id}
+/// Superuser only - deletes an album; its photographs are kept and detached
+#[utoipa::path(
+    delete,
+    path = "/api/albums/{album_id}",
+    tag = "album",
+    params(
+        ("album_id" = Uuid, Path, description = "Album UUID")
+    ),
+    responses(
+        (status = 200, description = "Album deleted", body = DeleteAlbumResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Album not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn delete_album(
+    Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(album_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let mut conn = state.get_conn().await.map_err(|e| {
+        error!(error = ?e, "Failed to get DB connection");
+        pool_err(e)
+    })?;
+
+    // `photographs.album_id` is ON DELETE SET NULL, so members are detached.
+    let deleted_count = diesel::delete(albums::table.filter(albums::album_id.eq(album_id)))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to delete album from DB");
+            code_err(CodeError::DB_DELETION_ERROR, e)
+        })?;
+
+    drop(conn);
+
+    if deleted_count == 0 {
+        return Err(code_err(CodeError::ALBUM_NOT_FOUND, "Album not found"));
+    }
+
+    info!(album_id = %album_id, user_id = %user_id, "Album deleted");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::AlbumDelete,
+            Some(album_id.to_string()),
+            serde_json::json!({}),
+        )
+        .await;
+
+    Ok(http_resp(
+        DeleteAlbumResponse {
+            deleted_album_id: album_id,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    domain::{album::album::Album, photography::tags::PhotographTag},
+    dto::responses::{
+        album::{album_response::AlbumItem, get_album_response::GetAlbumResponse},
+        photography::get_photograph_response::PhotographItem,
+        response_data::http_resp,
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::albums,
+    util::time::now::tokio_now,
+};
+
+/// Load an album with its ordered photographs; `ALBUM_NOT_FOUND` if missing.
+pub(crate) async fn load_album_response(
+    state: &ServerState,
+    conn: &mut AsyncPgConnection,
+    album_id: Uuid,
+) -> Result<GetAlbumResponse, CodeErrorResp> {
+    let album: Album = albums::table
+        .filter(albums::album_id.eq(album_id))
+        .select(Album::as_select())
+        .first(conn)
+        .await
+        .optional()
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to query album");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?
+        .ok_or_else(|| code_err(CodeError::ALBUM_NOT_FOUND, "Album not found"))?;
+
+    let photographs = Album::photographs(conn, album_id).await.map_err(|e| {
+        error!(error = ?e, album_id = %album_id, "Failed to query album photographs");
+        code_err(CodeError::DB_QUERY_ERROR, e)
+    })?;
+
+    // The cover may be a photograph outside the album, so look it up directly.
+    let cover = Album::cover_thumbnail_links(conn, std::slice::from_ref(&album))
+        .await
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to query album cover");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?
+        .into_values()
+        .next()
+        .map(|link| state.image_url(&link));
+
+    let photograph_ids: Vec<Uuid> = photographs.iter().map(|p| p.photograph_id).collect();
+    let mut tags_by_photograph = PhotographTag::names_for_photographs(conn, &photograph_ids)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to query album photograph tags");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?;
+
+    let count = photographs.len() as i64;
+
+    Ok(GetAlbumResponse {
+        album: AlbumItem::new(album, cover, count),
+        photographs: photographs
+            .into_iter()
+            .map(|mut p| {
+                state.deliver_photograph_links(&mut p);
+                let tags = tags_by_photograph
+                    .remove(&p.photograph_id)
+                    .unwrap_or_default();
+                PhotographItem::new(p, tags)
+            })
+            .collect(),
+    })
+}
+
+/// GET /api/albums/{album_id}
+/// Public endpoint - album details with its photographs in album order
+#[utoipa::path(
+    get,
+    path = "/api/albums/{album_id}",
+    tag = "album",
+    params(
+        ("album_id" = Uuid, Path, description = "Album UUID")
+    ),
+    responses(
+        (status = 200, description = "Album with ordered photographs", body = GetAlbumResponse),
+        (status = 404, description = "Album not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_album(
+    State(state): State<Arc<ServerState>>,
+    Path(album_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let mut conn = state.get_conn().await.map_err(|e| {
+        error!(error = ?e, "Failed to get DB connection");
+        pool_err(e)
+    })?;
+
+    let response = load_album_response(&state, &mut conn, album_id).await?;
+
+    drop(conn);
+
+    Ok(http_resp(response, (), start))
+}
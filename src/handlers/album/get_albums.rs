@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use tracing::error;
+
+use crate::{
+    domain::album::album::Album,
+    dto::responses::{
+        album::{album_response::AlbumItem, get_albums_response::GetAlbumsResponse},
+        response_data::http_resp,
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::albums,
+    util::time::now::tokio_now,
+};
+
+/// Attach cover thumbnails and photograph counts to album rows.
+pub(crate) async fn album_items(
+    state: &ServerState,
+    conn: &mut AsyncPgConnection,
+    albums: Vec<Album>,
+) -> Result<Vec<AlbumItem>, diesel::result::Error> {
+    let covers = Album::cover_thumbnail_links(conn, &albums).await?;
+    let counts =
+        Album::photograph_counts(conn, albums.iter().map(|a| a.album_id).collect()).await?;
+
+    Ok(albums
+        .into_iter()
+        .map(|a| {
+            let cover = a
+                .album_cover_photograph_id
+                .and_then(|id| covers.get(&id))
+                .map(|link| state.image_url(link));
+            let count = counts.get(&a.album_id).copied().unwrap_or(0);
+            AlbumItem::new(a, cover, count)
+        })
+        .collect())
+}
+
+/// GET /api/albums
+/// Public endpoint - lists all albums by sort order, newest first within ties
+#[utoipa::path(
+    get,
+    path = "/api/albums",
+    tag = "album",
+    responses(
+        (status = 200, description = "List of albums", body = GetAlbumsResponse),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_albums(
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let mut conn = state.get_conn().await.map_err(|e| {
+        error!(error = ?e, "Failed to get DB connection");
+        pool_err(e)
+    })?;
+
+    let rows: Vec<Album> = albums::table
+        .select(Album::as_select())
+        .order((
+            albums::album_sort_order.asc(),
+            albums::album_created_at.desc(),
+        ))
+        .load(&mut conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, "Failed to query albums");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?;
+
+    let items = album_items(&state, &mut conn, rows).await.map_err(|e| {
+        error!(error = ?e, "Failed to query album covers and counts");
+        code_err(CodeError::DB_QUERY_ERROR, e)
+    })?;
+
+    drop(conn);
+
+    Ok(http_resp(GetAlbumsResponse { items }, (), start))
+}
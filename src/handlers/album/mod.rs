@@ -0,0 +1,13 @@
+pub mod create_album;
+pub mod delete_album;
+pub mod get_album;
+pub mod get_albums;
+pub mod set_album_photographs;
+pub mod update_album;
+
+pub use create_album::create_album;
+pub use delete_album::delete_album;
+pub use get_album::get_album;
+pub use get_albums::get_albums;
+pub use set_album_photographs::set_album_photographs;
+pub use update_album::update_album;
@@ -0,0 +1,154 @@
+use std::{collections::HashSet, sync::Arc};
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    domain::audit::audit::AdminAction,
+    dto::{
+        requests::album::set_album_photographs_request::SetAlbumPhotographsRequest,
+        responses::{album::get_album_response::GetAlbumResponse, response_data::http_resp},
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::{albums, photographs},
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+use super::get_album::load_album_response;
+
+/// PUT /api/albums/{album_id}/photographs
+/// Superuser only - replaces album membership; list order becomes album order
+#[utoipa::path(
+    put,
+    path = "/api/albums/{album_id}/photographs",
+    tag = "album",
+    params(
+        ("album_id" = Uuid, Path, description = "Album UUID")
+    ),
+    request_body = SetAlbumPhotographsRequest,
+    responses(
+        (status = 200, description = "Album photographs set", body = GetAlbumResponse),
+        (status = 400, description = "Duplicate photograph ids", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Album or photograph not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn set_album_photographs(
+    Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(album_id): Path<Uuid>,
+    Json(body): Json<SetAlbumPhotographsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let unique: HashSet<Uuid> = body.photograph_ids.iter().copied().collect();
+    if unique.len() != body.photograph_ids.len() {
+        return Err(code_err(
+            CodeError::INVALID_REQUEST,
+            "Duplicate photograph ids in album order",
+        ));
+    }
+
+    let mut conn = state.get_conn().await.map_err(|e| {
+        error!(error = ?e, "Failed to get DB connection");
+        pool_err(e)
+    })?;
+
+    let album_count: i64 = albums::table
+        .filter(albums::album_id.eq(album_id))
+        .count()
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to query album");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?;
+
+    if album_count == 0 {
+        return Err(code_err(CodeError::ALBUM_NOT_FOUND, "Album not found"));
+    }
+
+    let existing: i64 = photographs::table
+        .filter(photographs::photograph_id.eq_any(&body.photograph_ids))
+        .count()
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to query photographs");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?;
+
+    if existing != body.photograph_ids.len() as i64 {
+        return Err(code_err(
+            CodeError::PHOTOGRAPH_NOT_FOUND,
+            "One or more photographs not found",
+        ));
+    }
+
+    let photograph_ids = body.photograph_ids;
+    conn.transaction::<_, diesel::result::Error, _>(async |conn| {
+        // Detach current members first so removed photographs leave the album.
+        diesel::update(photographs::table.filter(photographs::album_id.eq(album_id)))
+            .set((
+                photographs::album_id.eq(None::<Uuid>),
+                photographs::photograph_album_position.eq(0),
+            ))
+            .execute(&mut *conn)
+            .await?;
+
+        for (position, photograph_id) in photograph_ids.iter().enumerate() {
+            diesel::update(photographs::table.filter(photographs::photograph_id.eq(photograph_id)))
+                .set((
+                    photographs::album_id.eq(Some(album_id)),
+                    photographs::photograph_album_position.eq(position as i32),
+                ))
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        diesel::update(albums::table.filter(albums::album_id.eq(album_id)))
+            .set(albums::album_updated_at.eq(Utc::now()))
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| {
+        error!(error = ?e, album_id = %album_id, "Failed to set album photographs");
+        code_err(CodeError::DB_UPDATE_ERROR, e)
+    })?;
+
+    let response = load_album_response(&state, &mut conn, album_id).await?;
+
+    drop(conn);
+
+    info!(
+        album_id = %album_id,
+        user_id = %user_id,
+        photograph_count = photograph_ids.len(),
+        "Album photographs set"
+    );
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::AlbumPhotographsSet,
+            Some(album_id.to_string()),
+            serde_json::json!({ "photograph_ids": photograph_ids }),
+        )
+        .await;
+
+    Ok(http_resp(response, (), start))
+}
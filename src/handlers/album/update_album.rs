@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        album::album::{Album, AlbumChangeset},
+        audit::audit::{AdminAction, changed_fields},
+    },
+    dto::{
+        requests::album::update_album_request::UpdateAlbumRequest,
+        responses::{album::album_response::AlbumItem, response_data::http_resp},
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::albums,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+use super::{create_album::ensure_cover_photograph_exists, get_albums::album_items};
+
+/// PATCH /api/albums/{album_id}
+/// Superuser only - updates album title/description/cover/sort order
+#[utoipa::path(
+    patch,
+    path = "/api/albums/{album_id}",
+    tag = "album",
+    params(
+        ("album_id" = Uuid, Path, description = "Album UUID")
+    ),
+    request_body = UpdateAlbumRequest,
+    responses(
+        (status = 200, description = "Album updated", body = AlbumItem),
+        (status = 400, description = "Invalid request", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Album or cover photograph not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn update_album(
+    Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(album_id): Path<Uuid>,
+    Json(body): Json<UpdateAlbumRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let album_title = match body.album_title {
+        Some(title) => {
+            let title = title.trim().to_string();
+            if title.is_empty() {
+                return Err(code_err(
+                    CodeError::INVALID_REQUEST,
+                    "Album title is required",
+                ));
+            }
+            Some(title)
+        }
+        None => None,
+    };
+
+    let album_cover_photograph_id = if body.clear_album_cover {
+        Some(None)
+    } else {
+        body.album_cover_photograph_id.map(Some)
+    };
+
+    let changeset = AlbumChangeset {
+        album_title,
+        album_description: body.album_description,
+        album_cover_photograph_id,
+        album_sort_order: body.album_sort_order,
+        album_updated_at: Some(Utc::now()),
+    };
+
+    let mut conn = state.get_conn().await.map_err(|e| {
+        error!(error = ?e, "Failed to get DB connection");
+        pool_err(e)
+    })?;
+
+    if let Some(Some(cover_id)) = changeset.album_cover_photograph_id {
+        ensure_cover_photograph_exists(&mut conn, cover_id).await?;
+    }
+
+    let updated: Album = diesel::update(albums::table.filter(albums::album_id.eq(album_id)))
+        .set(&changeset)
+        .returning(Album::as_returning())
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to update album");
+            match e {
+                diesel::result::Error::NotFound => {
+                    code_err(CodeError::ALBUM_NOT_FOUND, "Album not found")
+                }
+                _ => code_err(CodeError::DB_UPDATE_ERROR, e),
+            }
+        })?;
+
+    let item = album_items(&state, &mut conn, vec![updated])
+        .await
+        .map_err(|e| {
+            error!(error = ?e, album_id = %album_id, "Failed to query album cover and count");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?
+        .pop()
+        .ok_or_else(|| code_err(CodeError::ALBUM_NOT_FOUND, "Album not found"))?;
+
+    drop(conn);
+
+    info!(album_id = %album_id, user_id = %user_id, "Album updated");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::AlbumUpdate,
+            Some(album_id.to_string()),
+            changed_fields(serde_json::json!({
+                "title": changeset.album_title,
+                "description": changeset.album_description,
+                // "" when cleared.
+                "cover_photograph_id": changeset
+                    .album_cover_photograph_id
+                    .map(|cover| cover.map(|id| id.to_string()).unwrap_or_default()),
+                "sort_order": changeset.album_sort_order,
+            })),
+        )
+        .await;
+
+    Ok(http_resp(item, (), start))
+}
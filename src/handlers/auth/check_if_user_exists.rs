@@ -9,7 +9,7 @@ use crate::{
         requests::auth::check_if_user_exists_request::CheckIfUserExistsRequest,
         responses::response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::users,
     util::time::now::tokio_now,
@@ -42,10 +42,7 @@ pub async fn check_if_user_exists_handler(
         return Err(CodeError::EMAIL_INVALID.into());
     }
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     #[rustfmt::skip]
     let email_exists: bool = diesel::select(
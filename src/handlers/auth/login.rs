@@ -7,7 +7,7 @@ use crate::{
         requests::auth::login_request::LoginRequest,
         responses::{auth::login_response::LoginResponse, response_data::http_resp_with_cookies},
     },
-    errors::code_error::{CodeError, HandlerResponse, code_err},
+    errors::code_error::{CodeError, HandlerResponse, code_err, pool_err},
     init::state::{DeploymentEnvironment, ServerState},
     schema::users,
     util::{
@@ -51,10 +51,7 @@ pub async fn login(
         return Err(CodeError::PASSWORD_INVALID.into());
     }
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let user: User = match users::table
         .filter(users::user_email.eq(&request.user_email))
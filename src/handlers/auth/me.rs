@@ -10,7 +10,7 @@ use crate::{
     build_info::{BUILD_TIME_UTC, LIB_VERSION_MAP, RUSTC_VERSION},
     domain::auth::user::{UserInfo, UserProfilePicture},
     dto::responses::{auth::me_response::MeResponse, response_data::http_resp},
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     routers::middleware::is_logged_in::AuthStatus,
     schema::{user_profile_pictures, users},
@@ -40,10 +40,7 @@ pub async fn me_handler(
 
     // If not logged in, return None for all user data fields
     if let Some(user_id) = user_id {
-        let mut conn = state
-            .get_conn()
-            .await
-            .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+        let mut conn = state.get_conn().await.map_err(pool_err)?;
 
         let user_info: Option<UserInfo> = users::table
             .filter(users::user_id.eq(user_id))
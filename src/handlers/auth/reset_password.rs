@@ -13,7 +13,7 @@ use crate::{
             auth::reset_password_response::ResetPasswordResponse, response_data::http_resp,
         },
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::users,
     util::{
@@ -47,10 +47,7 @@ pub async fn reset_password(
     let start = tokio_now();
     let now = Utc::now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     if !validate_password_form(&request.new_password) {
         return Err(CodeError::PASSWORD_INVALID.into());
@@ -4,12 +4,10 @@ use axum::{Json, extract::State, response::IntoResponse};
 use chrono::{DateTime, Utc};
 use diesel::{ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
-use lettre::AsyncTransport;
 use tracing::error;
 use uuid::Uuid;
 
 use crate::{
-    DOMAIN_NAME,
     domain::auth::user::{NewPasswordResetToken, User},
     dto::{
         requests::auth::reset_password_request::ResetPasswordRequest,
@@ -18,10 +16,11 @@ use crate::{
             response_data::http_resp,
         },
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
+    jobs::tasks::email::queue_password_reset_email,
     schema::{password_reset_tokens, users},
-    util::{email::emails::PasswordResetEmail, time::now::tokio_now},
+    util::time::now::tokio_now,
 };
 
 const PASSWORD_RESET_TOKEN_VALID_DURATION: chrono::TimeDelta = chrono::Duration::minutes(30);
@@ -52,10 +51,7 @@ pub async fn reset_password_request_process(
 
     let password_reset_token: Uuid = uuid::Uuid::new_v4();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let user: User = match users::table
         .filter(users::user_email.eq(&request.user_email))
@@ -90,30 +86,11 @@ pub async fn reset_password_request_process(
 
     drop(conn);
 
-    let user_email = request.user_email.clone();
-
-    tokio::spawn(async move {
-        let email_client = state.get_email_client();
-        let password_reset_email = match PasswordResetEmail::new()
-            .set_link(&format!(
-                "https://{DOMAIN_NAME}/reset-password?token={password_reset_token}"
-            ))
-            .to_message(&user_email)
-        {
-            Ok(password_reset_email) => password_reset_email,
-            Err(e) => {
-                error!(error = %e, "Could not build password reset email");
-                return;
-            }
-        };
-
-        match email_client.send(password_reset_email).await {
-            Ok(_) => (),
-            Err(e) => {
-                error!(error = %e, "Could not send email.")
-            }
-        };
-    });
+    if let Err(e) =
+        queue_password_reset_email(&state, &request.user_email, password_reset_token).await
+    {
+        error!(user_id = %user.user_id, error = ?e, "Could not queue password reset email");
+    }
 
     Ok(http_resp(
         ResetPasswordRequestResponse {
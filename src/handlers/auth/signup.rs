@@ -1,28 +1,27 @@
 use std::sync::Arc;
 
-use axum::{Extension, Json, extract::State, response::IntoResponse};
+use axum::{Extension, extract::State, response::IntoResponse};
 use chrono::{DateTime, Utc};
 use diesel::{ExpressionMethods, QueryDsl, dsl::exists};
 use diesel_async::RunQueryDsl;
-use lettre::{AsyncTransport, Message};
-use tracing::error;
+use tracing::{error, warn};
 use uuid::Uuid;
 use zeroize::Zeroize;
 
 use crate::{
-    domain::auth::user::{NewEmailVerificationToken, User},
+    domain::{
+        auth::user::{NewEmailVerificationToken, User},
+        job::delayed_task::DelayedTask,
+    },
     dto::{
         requests::auth::signup_request::SignupRequest,
         responses::{auth::signup_response::SignupResponse, response_data::http_resp},
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
+    jobs::{job_funcs::delayed::schedule_once_at, tasks::email::queue_validation_email},
     schema::{email_verification_tokens, users},
-    util::{
-        email::emails::ValidateEmailEmail,
-        string::validations::{validate_password_form, validate_username},
-        time::now::tokio_now,
-    },
+    util::{extract::ValidatedJson, time::now::tokio_now},
 };
 
 const EMAIL_VERIFICATION_TOKEN_VALID_DURATION: chrono::TimeDelta = chrono::Duration::days(1);
@@ -36,33 +35,19 @@ const EMAIL_VERIFICATION_TOKEN_VALID_DURATION: chrono::TimeDelta = chrono::Durat
     request_body = SignupRequest,
     responses(
         (status = 200, description = "User successfully signed up", body = SignupResponse),
-        (status = 400, description = "Invalid input or email already exists", body = CodeErrorResp),
+        (status = 400, description = "Malformed body or email already exists", body = CodeErrorResp),
+        (status = 422, description = "User name, email or password failed validation", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
 pub async fn signup_handler(
     Extension(request_received_time): Extension<DateTime<Utc>>,
     State(state): State<Arc<ServerState>>,
-    Json(mut request): Json<SignupRequest>,
+    ValidatedJson(mut request): ValidatedJson<SignupRequest>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    if !validate_username(&request.user_name) {
-        return Err(CodeError::USER_NAME_INVALID.into());
-    }
-
-    if !validate_password_form(&request.user_password) {
-        return Err(CodeError::PASSWORD_INVALID.into());
-    }
-
-    if !email_address::EmailAddress::is_valid(&request.user_email) {
-        return Err(CodeError::EMAIL_INVALID.into());
-    };
-
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let email_exists: bool = diesel::select(exists(
         users::table.filter(users::user_email.eq(&request.user_email)),
@@ -96,33 +81,30 @@ pub async fn signup_handler(
 
     drop(conn);
 
-    // TODO: Email resend handler in case this fails
-    // TODO: Send a proper bloody email
-    let user_email = request.user_email.clone();
-
-    let validation_email: ValidateEmailEmail = ValidateEmailEmail::new().set_fields(
+    // The hourly purge still catches the account if this insert fails.
+    if let Err(e) = schedule_once_at(
+        &state,
+        DelayedTask::PurgeUnverifiedUser {
+            user_id: new_user_id,
+        },
         inserted_email_verification_token_verify_by,
-        email_verification_token,
-    );
+    )
+    .await
+    {
+        warn!(user_id = %new_user_id, error = ?e, "Failed to schedule unverified user purge");
+    }
 
-    tokio::spawn(async move {
-        let email_client = state.get_email_client();
-
-        let email: Message = match validation_email.to_message(&user_email) {
-            Ok(email) => email,
-            Err(e) => {
-                error!(error = %e, "Could not build validation email");
-                return;
-            }
-        };
-
-        match email_client.send(email).await {
-            Ok(_) => (),
-            Err(e) => {
-                error!(error = %e, "Could not send email.")
-            }
-        };
-    });
+    // TODO: Email resend handler in case this fails
+    if let Err(e) = queue_validation_email(
+        &state,
+        &request.user_email,
+        email_verification_token,
+        inserted_email_verification_token_verify_by,
+    )
+    .await
+    {
+        error!(user_id = %new_user_id, error = ?e, "Could not queue validation email");
+    }
 
     let user_name = request.user_name.clone();
     let user_email = request.user_email.clone();
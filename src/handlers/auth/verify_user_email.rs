@@ -8,7 +8,7 @@ use crate::{
             EmailValidateResponse, hydrate_email_validate_response_page,
         },
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::{email_verification_tokens, users},
     util::time::now::tokio_now,
@@ -43,10 +43,7 @@ pub async fn verify_user_email(
     let start = tokio_now();
     let now = Utc::now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let email_verification_token: EmailVerificationToken = email_verification_tokens::table
         .filter(
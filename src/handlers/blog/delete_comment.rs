@@ -10,14 +10,18 @@ use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
 use crate::{
-    domain::auth::role::RoleType,
+    domain::{
+        audit::audit::AdminAction,
+        auth::role::RoleType,
+        notification::notification::{CommentLocation, Notification},
+    },
     dto::responses::{
         blog::delete_comment_response::DeleteCommentResponse, response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::comments,
-    util::time::now::tokio_now,
+    util::{extract::AdminActor, time::now::tokio_now},
 };
 
 #[utoipa::path(
@@ -39,22 +43,21 @@ use crate::{
 pub async fn delete_comment(
     Extension(requester_id): Extension<Uuid>,
     Extension(role_type): Extension<RoleType>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
-    Path((_post_id, comment_id)): Path<(Uuid, Uuid)>,
+    Path((post_id, comment_id)): Path<(Uuid, Uuid)>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
     let is_superuser = role_type.is_superuser();
 
     // 1. Check comment author against requester ID.
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let author_id: Uuid = comments::table
         .select(comments::user_id)
         .filter(comments::comment_id.eq(comment_id))
+        .filter(comments::comment_deleted_at.is_null())
         .first(&mut conn)
         .await
         .optional()
@@ -62,8 +65,9 @@ pub async fn delete_comment(
         .ok_or_else(|| code_err(CodeError::COMMENT_NOT_FOUND, "Comment not found"))?;
 
     if author_id == requester_id || is_superuser {
-        // 2. Delete comment!
-        match diesel::delete(comments::table.filter(comments::comment_id.eq(comment_id)))
+        // 2. Soft-delete the comment; PURGE_SOFT_DELETED removes it for good.
+        match diesel::update(comments::table.filter(comments::comment_id.eq(comment_id)))
+            .set(comments::comment_deleted_at.eq(chrono::Utc::now()))
             .execute(&mut conn)
             .await
         {
@@ -86,6 +90,27 @@ pub async fn delete_comment(
 
     drop(conn);
 
+    // Only moderation (a superuser removing someone else's comment) is audited.
+    if author_id != requester_id {
+        state
+            .record_admin_action(
+                &actor,
+                AdminAction::CommentDelete,
+                Some(comment_id.to_string()),
+                serde_json::json!({ "author_id": author_id }),
+            )
+            .await;
+        state
+            .notify_user(
+                author_id,
+                Notification::CommentRemoved {
+                    location: CommentLocation::Post { post_id },
+                    comment_id,
+                },
+            )
+            .await;
+    }
+
     Ok(http_resp(
         DeleteCommentResponse {
             deleted_comment_id: comment_id,
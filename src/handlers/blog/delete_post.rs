@@ -10,12 +10,12 @@ use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
 use crate::{
-    domain::auth::role::RoleType,
+    domain::{audit::audit::AdminAction, auth::role::RoleType},
     dto::responses::{blog::delete_post_response::DeletePostResponse, response_data::http_resp},
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::posts,
-    util::time::now::tokio_now,
+    util::{extract::AdminActor, time::now::tokio_now},
 };
 
 #[utoipa::path(
@@ -36,6 +36,7 @@ use crate::{
 pub async fn delete_post(
     Extension(requester_id): Extension<Uuid>,
     Extension(role_type): Extension<RoleType>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
     Path(post_id): Path<Uuid>,
 ) -> HandlerResponse<impl IntoResponse> {
@@ -44,14 +45,12 @@ pub async fn delete_post(
     let is_superuser = role_type.is_superuser();
 
     // 1. Check post author against requester ID.
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let author_id: Uuid = posts::table
         .select(posts::user_id)
         .filter(posts::post_id.eq(post_id))
+        .filter(posts::post_deleted_at.is_null())
         .first(&mut conn)
         .await
         .optional()
@@ -59,8 +58,9 @@ pub async fn delete_post(
         .ok_or_else(|| code_err(CodeError::POST_NOT_FOUND, "Post not found"))?;
 
     if author_id == requester_id || is_superuser {
-        // 2. Delete comment!
-        match diesel::delete(posts::table.filter(posts::post_id.eq(post_id)))
+        // 2. Soft-delete the post; PURGE_SOFT_DELETED removes it for good.
+        match diesel::update(posts::table.filter(posts::post_id.eq(post_id)))
+            .set(posts::post_deleted_at.eq(chrono::Utc::now()))
             .execute(&mut conn)
             .await
         {
@@ -85,6 +85,16 @@ pub async fn delete_post(
 
     // delete from state
     state.delete_post_from_cache(post_id).await;
+    if is_superuser {
+        state
+            .record_admin_action(
+                &actor,
+                AdminAction::PostDelete,
+                Some(post_id.to_string()),
+                serde_json::json!({ "author_id": author_id }),
+            )
+            .await;
+    }
 
     Ok(http_resp(
         DeletePostResponse {
@@ -4,11 +4,14 @@ use crate::{
     domain::blog::blog::{CachedPostInfo, PostInfoWithVote, UserBadgeInfo, VoteState},
     dto::{
         requests::blog::get_posts_request::GetPostsRequest,
-        responses::{blog::get_posts::GetPostsResponse, response_data::http_resp},
+        responses::{pagination::Paginated, response_data::http_resp},
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::{db_breaker::DbUnavailable, state::ServerState},
+    routers::middleware::{
+        etag::SkipEtag,
+        is_logged_in::{AuthSession, AuthStatus},
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
-    init::state::ServerState,
-    routers::middleware::is_logged_in::{AuthSession, AuthStatus},
     schema::{post_votes, user_profile_pictures, users},
     util::time::now::tokio_now,
 };
@@ -18,7 +21,7 @@ use axum::{
     response::IntoResponse,
 };
 use diesel::{ExpressionMethods, QueryDsl};
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
 use uuid::Uuid;
 
 #[utoipa::path(
@@ -30,7 +33,7 @@ use uuid::Uuid;
         ("posts_per_page" = Option<usize>, Query, description = "Posts per page")
     ),
     responses(
-        (status = 200, description = "List of blog posts", body = GetPostsResponse),
+        (status = 200, description = "List of blog posts", body = Paginated<PostInfoWithVote>),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
@@ -47,7 +50,7 @@ pub async fn get_posts(
         None => false,
     };
 
-    let (post_infos, available_pages): (Vec<CachedPostInfo>, usize) = state
+    let (post_infos, total_posts): (Vec<CachedPostInfo>, usize) = state
         .get_posts_from_cache(request.page, request.posts_per_page, include_unpublished)
         .await;
 
@@ -60,16 +63,95 @@ pub async fn get_posts(
     user_ids.sort();
     user_ids.dedup();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    // Degraded mode: the list itself comes from the post cache, so while the
+    // database circuit is open it is served without author badges or votes.
+    let (
+        PostExtras {
+            author_map,
+            author_country_map,
+            author_pic_map,
+            vote_map,
+        },
+        degraded,
+    ) = match state.get_read_conn().await {
+        Ok(mut conn) => (
+            load_post_extras(&mut conn, &user_ids, &post_ids, is_logged_in).await?,
+            false,
+        ),
+        Err(e) if e.is::<DbUnavailable>() => (PostExtras::default(), true),
+        Err(e) => return Err(pool_err(e)),
+    };
+
+    // Get country flag lookup from cache
+    let country_map = state.country_map.read().await;
+
+    let posts: Vec<PostInfoWithVote> = post_infos
+        .into_iter()
+        .map(|post| {
+            let vote_state = vote_map
+                .get(&post.post_id)
+                .cloned()
+                .unwrap_or(VoteState::DidNotVote);
+
+            let user_name = author_map
+                .get(&post.user_id)
+                .cloned()
+                .unwrap_or_else(|| "Unknown".to_string());
+            let user_profile_picture_url = author_pic_map
+                .get(&post.user_id)
+                .cloned()
+                .unwrap_or_default();
+            let user_country_flag = author_country_map
+                .get(&post.user_id)
+                .and_then(|&code| country_map.get_flag_by_code(code));
+
+            PostInfoWithVote::from_cached_info_with_vote(
+                post,
+                vote_state,
+                UserBadgeInfo {
+                    user_name,
+                    user_profile_picture_url,
+                    user_country_flag,
+                },
+            )
+        })
+        .collect();
+
+    drop(country_map);
+
+    let mut response = http_resp(
+        Paginated::new(posts, request.page, request.posts_per_page, total_posts),
+        (),
+        start,
+    )
+    .into_response();
+    // Missing badges and votes would stick behind the healthy response's tag.
+    if degraded {
+        response.extensions_mut().insert(SkipEtag);
+    }
+    Ok(response)
+}
+
+/// Author badges and the caller's votes for a page of cached posts.
+#[derive(Default)]
+struct PostExtras {
+    author_map: HashMap<Uuid, String>,
+    author_country_map: HashMap<Uuid, i32>,
+    author_pic_map: HashMap<Uuid, String>,
+    vote_map: HashMap<Uuid, VoteState>,
+}
 
+async fn load_post_extras(
+    conn: &mut AsyncPgConnection,
+    user_ids: &[Uuid],
+    post_ids: &[Uuid],
+    is_logged_in: AuthStatus,
+) -> Result<PostExtras, CodeErrorResp> {
     // Fetch user names and country codes
     let authors: Vec<(Uuid, String, i32)> = users::table
-        .filter(users::user_id.eq_any(&user_ids))
+        .filter(users::user_id.eq_any(user_ids))
         .select((users::user_id, users::user_name, users::user_country))
-        .load(&mut conn)
+        .load(conn)
         .await
         .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
 
@@ -82,13 +164,13 @@ pub async fn get_posts(
 
     // Fetch profile pictures
     let author_pics: Vec<(Uuid, Option<String>)> = user_profile_pictures::table
-        .filter(user_profile_pictures::user_id.eq_any(&user_ids))
+        .filter(user_profile_pictures::user_id.eq_any(user_ids))
         .order(user_profile_pictures::user_profile_picture_updated_at.desc())
         .select((
             user_profile_pictures::user_id,
             user_profile_pictures::user_profile_picture_link,
         ))
-        .load(&mut conn)
+        .load(conn)
         .await
         .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
 
@@ -103,10 +185,10 @@ pub async fn get_posts(
 
     let vote_map = if let AuthStatus::LoggedIn(user_id) = is_logged_in {
         let user_votes: Vec<(Uuid, bool)> = post_votes::table
-            .filter(post_votes::post_id.eq_any(&post_ids))
+            .filter(post_votes::post_id.eq_any(post_ids))
             .filter(post_votes::user_id.eq(user_id))
             .select((post_votes::post_id, post_votes::is_upvote))
-            .load::<(Uuid, bool)>(&mut conn)
+            .load::<(Uuid, bool)>(conn)
             .await
             .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
 
@@ -125,51 +207,10 @@ pub async fn get_posts(
         HashMap::new()
     };
 
-    drop(conn);
-
-    // Get country flag lookup from cache
-    let country_map = state.country_map.read().await;
-
-    let posts: Vec<PostInfoWithVote> = post_infos
-        .into_iter()
-        .map(|post| {
-            let vote_state = vote_map
-                .get(&post.post_id)
-                .cloned()
-                .unwrap_or(VoteState::DidNotVote);
-
-            let user_name = author_map
-                .get(&post.user_id)
-                .cloned()
-                .unwrap_or_else(|| "Unknown".to_string());
-            let user_profile_picture_url = author_pic_map
-                .get(&post.user_id)
-                .cloned()
-                .unwrap_or_default();
-            let user_country_flag = author_country_map
-                .get(&post.user_id)
-                .and_then(|&code| country_map.get_flag_by_code(code));
-
-            PostInfoWithVote::from_cached_info_with_vote(
-                post,
-                vote_state,
-                UserBadgeInfo {
-                    user_name,
-                    user_profile_picture_url,
-                    user_country_flag,
-                },
-            )
-        })
-        .collect();
-
-    drop(country_map);
-
-    Ok(http_resp(
-        GetPostsResponse {
-            posts,
-            available_pages,
-        },
-        (),
-        start,
-    ))
+    Ok(PostExtras {
+        author_map,
+        author_country_map,
+        author_pic_map,
+        vote_map,
+    })
 }
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use serde_derive::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    domain::blog::blog::{Comment, CommentResponse, UserBadgeInfo, VoteState},
+    dto::responses::{pagination::CursorPage, response_data::http_resp},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    routers::middleware::is_logged_in::{AuthSession, AuthStatus},
+    schema::{comment_votes, comments, posts, user_profile_pictures, users},
+    util::{
+        db::keyset::{Keyset, KeysetOrder, KeysetPaginate},
+        extract::ValidatedQuery,
+        time::now::tokio_now,
+    },
+};
+
+#[derive(Deserialize, IntoParams, Validate)]
+pub struct ListCommentsRequest {
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    /// Comments per page (default 50, max 200)
+    #[serde(default = "default_limit")]
+    #[validate(range(min = 1, max = 200))]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Comments of one post, oldest first, a page at a time. `read_post` returns
+/// every comment at once; this is for posts with long threads.
+#[utoipa::path(
+    get,
+    path = "/api/blog/posts/{post_id}/comments",
+    tag = "blog",
+    params(
+        ("post_id" = Uuid, Path, description = "ID of the post"),
+        ListCommentsRequest
+    ),
+    responses(
+        (status = 200, description = "A page of comments, oldest first", body = CursorPage<CommentResponse>),
+        (status = 400, description = "Malformed cursor", body = CodeErrorResp),
+        (status = 404, description = "Post not found", body = CodeErrorResp),
+        (status = 422, description = "Invalid limit", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_comments(
+    Extension(is_logged_in): Extension<AuthStatus>,
+    Extension(auth_session): Extension<Option<AuthSession>>,
+    State(state): State<Arc<ServerState>>,
+    Path(post_id): Path<Uuid>,
+    ValidatedQuery(request): ValidatedQuery<ListCommentsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let after = match &request.cursor {
+        None => None,
+        Some(raw) => Some(
+            Keyset::<Uuid>::from_cursor(raw)
+                .ok_or_else(|| code_err(CodeError::INVALID_REQUEST, "Malformed cursor"))?,
+        ),
+    };
+    let include_unpublished = auth_session
+        .as_ref()
+        .is_some_and(|session| session.role_type.is_superuser());
+
+    let mut conn = state.get_read_conn().await.map_err(pool_err)?;
+
+    let mut post_query = posts::table
+        .filter(posts::post_id.eq(post_id))
+        .filter(posts::post_deleted_at.is_null())
+        .into_boxed();
+    if !include_unpublished {
+        post_query = post_query.filter(posts::post_is_published.eq(true));
+    }
+    let post_exists: bool = diesel::dsl::select(diesel::dsl::exists(post_query))
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    if !post_exists {
+        return Err(code_err(CodeError::POST_NOT_FOUND, "Post not found"));
+    }
+
+    // One extra row tells whether another page follows.
+    let rows: Vec<Comment> = comments::table
+        .filter(comments::post_id.eq(post_id))
+        .filter(comments::comment_deleted_at.is_null())
+        .select(Comment::as_select())
+        .into_boxed()
+        .keyset_page(
+            (comments::comment_created_at, comments::comment_id),
+            after.as_ref(),
+            KeysetOrder::OldestFirst,
+            request.limit + 1,
+        )
+        .load(&mut conn)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    let page = CursorPage::from_overfetched(rows, request.limit as usize, |comment| {
+        Keyset::new(comment.comment_created_at, comment.comment_id).to_string()
+    });
+
+    let mut author_ids: Vec<Uuid> = page.items.iter().map(|c| c.user_id).collect();
+    author_ids.sort();
+    author_ids.dedup();
+    let comment_ids: Vec<Uuid> = page.items.iter().map(|c| c.comment_id).collect();
+
+    let authors: HashMap<Uuid, (String, i32)> = users::table
+        .filter(users::user_id.eq_any(&author_ids))
+        .select((users::user_id, users::user_name, users::user_country))
+        .load::<(Uuid, String, i32)>(&mut conn)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .into_iter()
+        .map(|(uid, name, country)| (uid, (name, country)))
+        .collect();
+
+    let pictures: HashMap<Uuid, String> = user_profile_pictures::table
+        .filter(user_profile_pictures::user_id.eq_any(&author_ids))
+        .distinct_on(user_profile_pictures::user_id)
+        .order((
+            user_profile_pictures::user_id,
+            user_profile_pictures::user_profile_picture_updated_at.desc(),
+        ))
+        .select((
+            user_profile_pictures::user_id,
+            user_profile_pictures::user_profile_picture_link,
+        ))
+        .load::<(Uuid, Option<String>)>(&mut conn)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .into_iter()
+        .filter_map(|(uid, link)| Some((uid, link?)))
+        .collect();
+
+    let votes: HashMap<Uuid, VoteState> = if let AuthStatus::LoggedIn(user_id) = is_logged_in {
+        comment_votes::table
+            .filter(comment_votes::comment_id.eq_any(&comment_ids))
+            .filter(comment_votes::user_id.eq(user_id))
+            .select((comment_votes::comment_id, comment_votes::is_upvote))
+            .load::<(Uuid, bool)>(&mut conn)
+            .await
+            .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+            .into_iter()
+            .map(|(cid, is_upvote)| {
+                let vote_state = if is_upvote {
+                    VoteState::Upvoted
+                } else {
+                    VoteState::Downvoted
+                };
+                (cid, vote_state)
+            })
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    drop(conn);
+
+    let country_map = state.country_map.read().await;
+    let page = page.map(|comment| {
+        let (user_name, user_country_flag) = match authors.get(&comment.user_id) {
+            Some((name, country)) => (name.clone(), country_map.get_flag_by_code(*country)),
+            None => ("Unknown".to_string(), None),
+        };
+        let vote_state = votes
+            .get(&comment.comment_id)
+            .cloned()
+            .unwrap_or(VoteState::DidNotVote);
+        let user_profile_picture_url = pictures.get(&comment.user_id).cloned().unwrap_or_default();
+        CommentResponse::from_comment_votestate_and_badge_info(
+            comment,
+            vote_state,
+            UserBadgeInfo {
+                user_name,
+                user_profile_picture_url,
+                user_country_flag,
+            },
+        )
+    });
+    drop(country_map);
+
+    Ok(http_resp(page, (), start))
+}
@@ -0,0 +1,59 @@
+//! `GET /api/events` — Server-Sent Events stream of blog changes (see
+//! `domain::blog::live_event`).
+//!
+//! Each event's `event:` name is the `type` of its JSON `data`. A subscriber
+//! that falls more than `LIVE_EVENT_CHANNEL_CAPACITY` events behind gets a
+//! `resync` event (data: the number skipped) and should refetch what it shows.
+//! The stream ends when the server starts shutting down; browsers reconnect.
+
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use axum::{
+    extract::State,
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures_util::stream;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::debug;
+
+use crate::{domain::blog::live_event::LiveEvent, init::state::ServerState};
+
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "blog",
+    responses(
+        (status = 200, description = "text/event-stream of live blog events", body = LiveEvent, content_type = "text/event-stream")
+    )
+)]
+pub async fn get_live_events(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    let receiver = state.subscribe_live_events();
+
+    let events = stream::unfold((state, receiver), |(state, mut receiver)| async move {
+        let event = tokio::select! {
+            // Do not hold graceful shutdown open.
+            _ = state.shutdown.cancelled() => return None,
+            received = receiver.recv() => match received {
+                Ok(event) => live_event(&event),
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(skipped, "Live event subscriber lagged; asking it to resync");
+                    Event::default().event("resync").data(skipped.to_string())
+                }
+                Err(RecvError::Closed) => return None,
+            },
+        };
+        Some((Ok::<_, Infallible>(event), (state, receiver)))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+}
+
+fn live_event(event: &LiveEvent) -> Event {
+    Event::default()
+        .event(event.name())
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().event("resync").data("0"))
+}
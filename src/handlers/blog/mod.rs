@@ -1,6 +1,8 @@
 pub mod delete_comment;
 pub mod delete_post;
 pub mod get_posts;
+pub mod list_comments;
+pub mod live_events;
 pub mod read_post;
 pub mod rescind_comment_vote;
 pub mod rescind_post_vote;
@@ -1,8 +1,9 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
 use axum::{
     Extension,
-    extract::{Path, State},
+    extract::{ConnectInfo, Path, State},
+    http::HeaderMap,
     response::IntoResponse,
 };
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
@@ -15,13 +16,13 @@ use crate::{
         CachedPostInfo, Comment, CommentResponse, PostInfo, UserBadgeInfo, VoteState,
     },
     dto::responses::{blog::read_post_response::ReadPostResponse, response_data::http_resp},
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     routers::middleware::is_logged_in::{AuthSession, AuthStatus},
     schema::{
         comment_votes, comments, post_tags, post_votes, posts, tags, user_profile_pictures, users,
     },
-    util::time::now::tokio_now,
+    util::{extract::client_ip::extract_client_ip, time::now::tokio_now},
 };
 
 #[derive(Clone, Debug)]
@@ -66,6 +67,8 @@ pub async fn read_post(
     Extension(is_logged_in): Extension<AuthStatus>,
     Extension(auth_session): Extension<Option<AuthSession>>,
     State(state): State<Arc<ServerState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Path(post_lookup_key): Path<PostLookupKey>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
@@ -76,13 +79,11 @@ pub async fn read_post(
             match state.get_post_id_by_slug_from_cache(&post_slug).await {
                 Some(post_id) => post_id,
                 None => {
-                    let mut conn = state
-                        .get_conn()
-                        .await
-                        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+                    let mut conn = state.get_read_conn().await.map_err(pool_err)?;
 
                     let post_id_opt: Option<Uuid> = posts::table
                         .filter(posts::post_slug.eq(&post_slug))
+                        .filter(posts::post_deleted_at.is_null())
                         .select(posts::post_id)
                         .first(&mut conn)
                         .await
@@ -100,30 +101,52 @@ pub async fn read_post(
         }
     };
 
-    let include_unpublished = match auth_session {
+    let include_unpublished = match &auth_session {
         Some(auth_session) => auth_session.role_type.is_superuser(),
         None => false,
     };
 
+    // Repeat reads by the same viewer within the dedup window leave the count alone.
+    let viewer = match &auth_session {
+        Some(auth_session) => format!("user:{}", auth_session.user_id),
+        None => format!(
+            "ip:{}",
+            extract_client_ip(&headers, addr).unwrap_or(addr.ip())
+        ),
+    };
+    let count_view = state.claim_post_view(post_id, &viewer).await;
+
     let post_handle = {
         let state = Arc::clone(&state);
         tokio::spawn(async move {
-            let mut conn = state
-                .get_conn()
+            // Primary: this bumps the view count. The other reads here may lag.
+            let mut conn = state.get_conn().await.map_err(pool_err)?;
+
+            let update_result = if !count_view {
+                let mut query = posts::table
+                    .filter(posts::post_id.eq(post_id))
+                    .filter(posts::post_deleted_at.is_null())
+                    .into_boxed();
+                if !include_unpublished {
+                    query = query.filter(posts::post_is_published.eq(true));
+                }
+                query.first(&mut conn).await
+            } else if include_unpublished {
+                diesel::update(
+                    posts::table
+                        .filter(posts::post_id.eq(post_id))
+                        .filter(posts::post_deleted_at.is_null()),
+                )
+                .set(posts::post_view_count.eq(posts::post_view_count + 1))
+                .returning(posts::all_columns)
+                .get_result(&mut conn)
                 .await
-                .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
-
-            let update_result = if include_unpublished {
-                diesel::update(posts::table.filter(posts::post_id.eq(post_id)))
-                    .set(posts::post_view_count.eq(posts::post_view_count + 1))
-                    .returning(posts::all_columns)
-                    .get_result(&mut conn)
-                    .await
             } else {
                 diesel::update(
                     posts::table
                         .filter(posts::post_id.eq(post_id))
-                        .filter(posts::post_is_published.eq(true)),
+                        .filter(posts::post_is_published.eq(true))
+                        .filter(posts::post_deleted_at.is_null()),
                 )
                 .set(posts::post_view_count.eq(posts::post_view_count + 1))
                 .returning(posts::all_columns)
@@ -141,13 +164,11 @@ pub async fn read_post(
     let comments_handle = {
         let state = Arc::clone(&state);
         tokio::spawn(async move {
-            let mut conn = state
-                .get_conn()
-                .await
-                .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+            let mut conn = state.get_read_conn().await.map_err(pool_err)?;
 
             comments::table
                 .filter(comments::post_id.eq(post_id))
+                .filter(comments::comment_deleted_at.is_null())
                 .load::<Comment>(&mut conn)
                 .await
                 .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))
@@ -191,10 +212,7 @@ pub async fn read_post(
             cached.post_tags
         } else {
             // Fetch from DB if not in cache
-            let mut conn = state
-                .get_conn()
-                .await
-                .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+            let mut conn = state.get_read_conn().await.map_err(pool_err)?;
 
             let tag_names: Vec<String> = post_tags::table
                 .inner_join(tags::table)
@@ -222,10 +240,7 @@ pub async fn read_post(
     relevant_user_ids.sort();
     relevant_user_ids.dedup();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_read_conn().await.map_err(pool_err)?;
 
     // Fetch user names and country codes
     let users_info: Vec<(Uuid, String, i32)> = users::table
@@ -272,10 +287,7 @@ pub async fn read_post(
     // Fetch vote state for comments if logged in
     let vote_map = if let AuthStatus::LoggedIn(user_id) = is_logged_in {
         let comment_ids: Vec<Uuid> = comments.iter().map(|c| c.comment_id).collect();
-        let mut conn = state
-            .get_conn()
-            .await
-            .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+        let mut conn = state.get_read_conn().await.map_err(pool_err)?;
 
         let user_votes: Vec<(Uuid, bool)> = comment_votes::table
             .filter(comment_votes::comment_id.eq_any(&comment_ids))
@@ -350,10 +362,7 @@ pub async fn read_post(
     drop(country_map);
 
     let post_vote_state = if let AuthStatus::LoggedIn(user_id) = is_logged_in {
-        let mut conn = state
-            .get_conn()
-            .await
-            .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+        let mut conn = state.get_read_conn().await.map_err(pool_err)?;
         let opt = post_votes::table
             .filter(post_votes::post_id.eq(post_id))
             .filter(post_votes::user_id.eq(user_id))
@@ -10,8 +10,9 @@ use diesel_async::{AsyncConnection, RunQueryDsl};
 use uuid::Uuid;
 
 use crate::{
+    domain::blog::live_event::LiveEvent,
     dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::{comment_votes::dsl as cu, comments},
     util::time::now::tokio_now,
@@ -47,12 +48,9 @@ pub async fn rescind_comment_vote(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
-    match conn
+    let (counts, post_id): (VoteCounts, Uuid) = match conn
         .transaction::<_, diesel::result::Error, _>(async |conn| {
             let affected_rows = diesel::delete(
                 cu::comment_votes
@@ -76,24 +74,35 @@ pub async fn rescind_comment_vote(
             .get_result(&mut *conn)
             .await?;
 
-            diesel::update(comments::table.filter(comments::comment_id.eq(comment_id)))
-                .set((
-                    comments::total_upvotes.eq(counts.upvote_count),
-                    comments::total_downvotes.eq(counts.downvote_count),
-                ))
-                .execute(&mut *conn)
-                .await?;
+            let post_id: Uuid =
+                diesel::update(comments::table.filter(comments::comment_id.eq(comment_id)))
+                    .set((
+                        comments::total_upvotes.eq(counts.upvote_count),
+                        comments::total_downvotes.eq(counts.downvote_count),
+                    ))
+                    .returning(comments::post_id)
+                    .get_result(&mut *conn)
+                    .await?;
 
-            Ok(())
+            Ok((counts, post_id))
         })
         .await
     {
-        Ok(()) => {}
+        Ok(result) => result,
         Err(diesel::result::Error::NotFound) => {
             return Err(CodeError::UPVOTE_DOES_NOT_EXIST.into());
         }
         Err(e) => return Err(code_err(CodeError::DB_DELETION_ERROR, e)),
-    }
+    };
+
+    state
+        .publish_live_event(LiveEvent::CommentVotesChanged {
+            post_id,
+            comment_id,
+            upvote_count: counts.upvote_count,
+            downvote_count: counts.downvote_count,
+        })
+        .await;
 
     Ok(http_resp((), (), start))
 }
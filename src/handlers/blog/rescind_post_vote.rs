@@ -10,8 +10,9 @@ use diesel_async::{AsyncConnection, RunQueryDsl};
 use uuid::Uuid;
 
 use crate::{
+    domain::blog::live_event::LiveEvent,
     dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::{post_votes, posts},
     util::time::now::tokio_now,
@@ -46,10 +47,7 @@ pub async fn rescind_post_vote(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     // Existence check only; counts are updated in-place below to avoid clobbering
     // concurrent updates to other cached fields with a stale snapshot write-back.
@@ -106,12 +104,15 @@ pub async fn rescind_post_vote(
 
     // Update only the vote counts on the live cache entry in place; other fields
     // are left untouched and the order/search index is not resynced (votes do not
-    // affect ordering). update_async is a no-op if the post was deleted meanwhile.
-    let _ = state
-        .blog_posts_cache
-        .update_async(&post_id, |_, cached| {
-            cached.total_upvotes = upvote_count;
-            cached.total_downvotes = downvote_count;
+    // affect ordering).
+    state
+        .update_post_vote_counts(post_id, upvote_count, downvote_count)
+        .await;
+    state
+        .publish_live_event(LiveEvent::PostVotesChanged {
+            post_id,
+            upvote_count,
+            downvote_count,
         })
         .await;
 
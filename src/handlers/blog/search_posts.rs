@@ -1,38 +1,42 @@
 use std::{collections::HashMap, sync::Arc};
 
-use axum::{
-    Extension,
-    extract::{Query, State},
-    response::IntoResponse,
-};
+use axum::{Extension, extract::State, response::IntoResponse};
 use diesel::{ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 use serde_derive::Deserialize;
-use utoipa::{IntoParams, ToSchema};
+use utoipa::IntoParams;
 use uuid::Uuid;
+use validator::{Validate, ValidationError};
 
 use crate::{
     domain::blog::blog::{CachedPostInfo, PostInfoWithVote, UserBadgeInfo, VoteState},
-    dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    dto::responses::{
+        pagination::{Paginated, page_offset},
+        response_data::http_resp,
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     routers::middleware::is_logged_in::AuthStatus,
     schema::{post_votes, user_profile_pictures, users},
-    util::time::now::tokio_now,
+    util::{extract::ValidatedQuery, time::now::tokio_now},
 };
 
-#[derive(Deserialize, IntoParams)]
+#[derive(Deserialize, IntoParams, Validate)]
+#[validate(schema(function = "query_or_tags"))]
 pub struct SearchPostsRequest {
     /// The search query string
     pub q: String,
     /// Search type: "title" for title search, "tag" for tag search
     #[serde(default = "default_search_type")]
+    #[validate(custom(function = "search_type"))]
     pub search_type: String,
     /// Maximum number of results (default 20, max 100)
     #[serde(default = "default_limit")]
+    #[validate(range(min = 1, max = 100))]
     pub limit: usize,
     /// Page number (1-based)
     #[serde(default = "default_page")]
+    #[validate(range(min = 1))]
     pub page: usize,
     /// Optional comma-separated tags to filter by
     pub tags: Option<String>,
@@ -50,13 +54,24 @@ fn default_page() -> usize {
     1
 }
 
-#[derive(serde_derive::Serialize, ToSchema)]
-pub struct SearchPostsResponse {
-    pub posts: Vec<PostInfoWithVote>,
-    pub query: String,
-    pub search_type: String,
-    pub available_pages: usize,
-    pub page: usize,
+fn search_type(value: &str) -> Result<(), ValidationError> {
+    if value.eq_ignore_ascii_case("title") || value.eq_ignore_ascii_case("tag") {
+        Ok(())
+    } else {
+        Err(ValidationError::new("search_type").with_message("must be 'title' or 'tag'".into()))
+    }
+}
+
+fn query_or_tags(request: &SearchPostsRequest) -> Result<(), ValidationError> {
+    let has_tags = request
+        .tags
+        .as_deref()
+        .is_some_and(|tags| tags.split(',').any(|t| !t.trim().is_empty()));
+    if request.q.trim().is_empty() && !has_tags {
+        Err(ValidationError::new("required").with_message("provide q or tags".into()))
+    } else {
+        Ok(())
+    }
 }
 
 #[utoipa::path(
@@ -65,15 +80,16 @@ pub struct SearchPostsResponse {
     tag = "blog",
     params(SearchPostsRequest),
     responses(
-        (status = 200, description = "Search results", body = SearchPostsResponse),
-        (status = 400, description = "Invalid search parameters", body = CodeErrorResp),
+        (status = 200, description = "Search results", body = Paginated<PostInfoWithVote>),
+        (status = 400, description = "Malformed query string", body = CodeErrorResp),
+        (status = 422, description = "Invalid search parameters", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
 pub async fn search_posts(
     Extension(is_logged_in): Extension<AuthStatus>,
     State(state): State<Arc<ServerState>>,
-    Query(request): Query<SearchPostsRequest>,
+    ValidatedQuery(request): ValidatedQuery<SearchPostsRequest>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
@@ -88,31 +104,13 @@ pub async fn search_posts(
         .map(|t| t.to_lowercase())
         .collect::<Vec<_>>();
 
-    if query.is_empty() && tags.is_empty() {
-        return Err(code_err(
-            CodeError::INVALID_REQUEST,
-            "Search query cannot be empty",
-        ));
-    }
-
-    let limit = request.limit.clamp(1, 100);
-    let page = request.page.max(1);
-    let offset = (page - 1).saturating_mul(limit);
+    let limit = request.limit;
+    let page = request.page;
+    let offset = page_offset(page, limit);
     let search_type = request.search_type.to_lowercase();
 
     // Perform search based on type
     let (matching_posts, total_matches): (Vec<CachedPostInfo>, usize) = match search_type.as_str() {
-        "title" => {
-            if !query.is_empty() && !tags.is_empty() {
-                state
-                    .search_posts_by_title_and_tags(query, &tags, offset, limit)
-                    .await
-            } else if !query.is_empty() {
-                state.search_posts_by_title(query, offset, limit).await
-            } else {
-                state.search_posts_by_tags(&tags, offset, limit).await
-            }
-        }
         "tag" => {
             let mut all_tags = tags;
             if !query.is_empty() {
@@ -123,24 +121,22 @@ pub async fn search_posts(
             }
             state.search_posts_by_tags(&all_tags, offset, limit).await
         }
+        // "title"; other values fail the `search_type` rule.
         _ => {
-            return Err(code_err(
-                CodeError::INVALID_REQUEST,
-                "Invalid search_type. Use 'title' or 'tag'",
-            ));
+            if !query.is_empty() && !tags.is_empty() {
+                state
+                    .search_posts_by_title_and_tags(query, &tags, offset, limit)
+                    .await
+            } else if !query.is_empty() {
+                state.search_posts_by_title(query, offset, limit).await
+            } else {
+                state.search_posts_by_tags(&tags, offset, limit).await
+            }
         }
     };
-    let available_pages = total_matches.div_ceil(limit);
-
     if matching_posts.is_empty() {
         return Ok(http_resp(
-            SearchPostsResponse {
-                posts: vec![],
-                query: query.to_string(),
-                search_type,
-                available_pages,
-                page,
-            },
+            Paginated::<PostInfoWithVote>::new(vec![], page, limit, total_matches),
             (),
             start,
         ));
@@ -153,10 +149,7 @@ pub async fn search_posts(
 
     let post_ids: Vec<Uuid> = matching_posts.iter().map(|p| p.post_id).collect();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     // Fetch user names and country codes
     let authors: Vec<(Uuid, String, i32)> = users::table
@@ -259,13 +252,7 @@ pub async fn search_posts(
     drop(country_map);
 
     Ok(http_resp(
-        SearchPostsResponse {
-            posts,
-            query: query.to_string(),
-            search_type,
-            available_pages,
-            page,
-        },
+        Paginated::new(posts, page, limit, total_matches),
         (),
         start,
     ))
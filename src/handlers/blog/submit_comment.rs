@@ -11,14 +11,22 @@ use uuid::Uuid;
 use diesel_async::RunQueryDsl;
 
 use crate::{
-    domain::blog::blog::{Comment as DbComment, CommentResponse, UserBadgeInfo, VoteState},
+    domain::{
+        blog::{
+            blog::{Comment as DbComment, CommentResponse, UserBadgeInfo, VoteState},
+            live_event::LiveEvent,
+        },
+        notification::notification::{CommentLocation, PostedComment},
+        webhook::webhook::WebhookEvent,
+    },
     dto::{
         requests::blog::submit_comment::SubmitCommentRequest, responses::response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
+    jobs::tasks::webhook::emit_webhook_event,
     routers::middleware::is_logged_in::AuthSession,
-    schema::{comments, user_profile_pictures},
+    schema::{comments, posts, user_profile_pictures},
     util::time::now::tokio_now,
 };
 
@@ -43,6 +51,7 @@ struct NewComment<'a> {
     responses(
         (status = 200, description = "Comment submitted successfully", body = CommentResponse),
         (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 404, description = "Post or parent comment not found", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
@@ -54,10 +63,7 @@ pub async fn submit_comment(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     if request.is_guest {
         return Err(CodeError::UNAUTHORIZED_ACCESS.into());
@@ -70,6 +76,41 @@ pub async fn submit_comment(
     let user_id = auth_session.user_id;
     let user_country = auth_session.user_country;
 
+    // Soft-deleted posts and comments keep their rows, so the foreign keys no
+    // longer stop a comment on a deleted post or a reply to a deleted comment.
+    let mut post_query = posts::table
+        .filter(posts::post_id.eq(post_id))
+        .filter(posts::post_deleted_at.is_null())
+        .into_boxed();
+    if !auth_session.role_type.is_superuser() {
+        post_query = post_query.filter(posts::post_is_published.eq(true));
+    }
+    let post_exists: bool = diesel::dsl::select(diesel::dsl::exists(post_query))
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    if !post_exists {
+        return Err(code_err(CodeError::POST_NOT_FOUND, "Post not found"));
+    }
+
+    if let Some(parent_comment_id) = request.parent_comment_id {
+        let parent_exists: bool = diesel::dsl::select(diesel::dsl::exists(
+            comments::table
+                .filter(comments::comment_id.eq(parent_comment_id))
+                .filter(comments::post_id.eq(post_id))
+                .filter(comments::comment_deleted_at.is_null()),
+        ))
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+        if !parent_exists {
+            return Err(code_err(
+                CodeError::COMMENT_NOT_FOUND,
+                "Parent comment not found on this post",
+            ));
+        }
+    }
+
     let new_comment = NewComment {
         post_id: &post_id,
         user_id: &user_id,
@@ -96,6 +137,16 @@ pub async fn submit_comment(
 
     drop(conn);
 
+    emit_webhook_event(&state, WebhookEvent::comment_created(&inserted_comment));
+    state.notify_comment_audience(PostedComment {
+        location: CommentLocation::Post { post_id },
+        comment_id: inserted_comment.comment_id,
+        parent_comment_id: inserted_comment.parent_comment_id,
+        author_id: user_id,
+        author_name: auth_session.user_name.clone(),
+        content: inserted_comment.comment_content.clone(),
+    });
+
     // Look up country flag from cache
     let country_map = state.country_map.read().await;
     let user_country_flag = country_map.get_flag_by_code(user_country);
@@ -111,5 +162,11 @@ pub async fn submit_comment(
         },
     );
 
+    state
+        .publish_live_event(LiveEvent::CommentAdded {
+            comment: response.clone(),
+        })
+        .await;
+
     Ok(http_resp(response, (), start))
 }
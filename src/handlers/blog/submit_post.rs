@@ -1,7 +1,4 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::{collections::HashSet, sync::Arc};
 
 use axum::{Extension, Json, extract::State, response::IntoResponse};
 use diesel::{ExpressionMethods, QueryDsl};
@@ -10,15 +7,26 @@ use uuid::Uuid;
 use diesel_async::RunQueryDsl;
 
 use crate::{
-    domain::blog::blog::{CachedPostInfo, NewPost, NewPostTag, NewTag, Post, PostInfo},
+    domain::{
+        audit::audit::AdminAction,
+        blog::{
+            blog::{CachedPostInfo, NewPost, Post, PostInfo, PostTag},
+            live_event::LiveEvent,
+        },
+        webhook::webhook::WebhookEvent,
+    },
     dto::{
         requests::blog::submit_post_request::SubmitPostRequest,
         responses::{blog::submit_post_response::SubmitPostResponse, response_data::http_resp},
     },
     errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
     init::state::ServerState,
-    schema::{post_tags, posts, tags},
-    util::{string::generate_slug::generate_slug, time::now::tokio_now},
+    jobs::tasks::webhook::emit_webhook_event,
+    schema::posts,
+    util::{
+        db::transaction::ScopedFutureExt, extract::AdminActor,
+        string::generate_slug::generate_slug, time::now::tokio_now,
+    },
 };
 
 // .route("/blog/submit-post", post(submit_post))
@@ -46,6 +54,7 @@ use crate::{
 )]
 pub async fn submit_post(
     Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
     Json(request): Json<SubmitPostRequest>,
 ) -> HandlerResponse<impl IntoResponse> {
@@ -84,11 +93,6 @@ pub async fn submit_post(
         (Some(_), None) => true,
     };
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
-
     // Generate slug (only for new posts or if title changed)
     let slug: String = generate_slug(&request.post_title);
     let now = chrono::Utc::now();
@@ -98,143 +102,113 @@ pub async fn submit_post(
         "markdown_content": request.post_content
     });
 
-    let post: Post = match request.post_id {
-        // CASE: Editing an existing post
-        Some(post_id) => {
-            // First, verify the post exists and belongs to this user
-            diesel::dsl::select(diesel::dsl::exists(
-                posts::table
-                    .filter(posts::post_id.eq(post_id))
-                    .filter(posts::user_id.eq(user_id)),
-            ))
-            .get_result::<bool>(&mut conn)
-            .await
-            .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
-            .then_some(())
-            .ok_or_else(|| {
-                code_err(
-                    CodeError::POST_NOT_FOUND,
-                    "Post not found or not owned by user",
-                )
-            })?;
-
-            let existing_published_at: Option<chrono::DateTime<chrono::Utc>> = posts::table
-                .filter(posts::post_id.eq(post_id))
-                .filter(posts::user_id.eq(user_id))
-                .select(posts::post_published_at)
-                .first::<Option<chrono::DateTime<chrono::Utc>>>(&mut conn)
-                .await
-                .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    // The post row and its tag links change together or not at all.
+    let (post, newly_published): (Post, bool) = state
+        .transaction(|conn| {
+            let requested_tags = &requested_tags;
+            async move {
+                let (post, newly_published): (Post, bool) = match request.post_id {
+                    // CASE: Editing an existing post
+                    Some(post_id) => {
+                        // First, verify the post exists and belongs to this user
+                        diesel::dsl::select(diesel::dsl::exists(
+                            posts::table
+                                .filter(posts::post_id.eq(post_id))
+                                .filter(posts::user_id.eq(user_id))
+                                .filter(posts::post_deleted_at.is_null()),
+                        ))
+                        .get_result::<bool>(&mut *conn)
+                        .await
+                        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+                        .then_some(())
+                        .ok_or_else(|| {
+                            code_err(
+                                CodeError::POST_NOT_FOUND,
+                                "Post not found or not owned by user",
+                            )
+                        })?;
 
-            let new_published_at = if request.post_is_published {
-                existing_published_at.or(Some(now))
-            } else {
-                None
-            };
+                        let existing_published_at: Option<chrono::DateTime<chrono::Utc>> =
+                            posts::table
+                                .filter(posts::post_id.eq(post_id))
+                                .filter(posts::user_id.eq(user_id))
+                                .select(posts::post_published_at)
+                                .first::<Option<chrono::DateTime<chrono::Utc>>>(&mut *conn)
+                                .await
+                                .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
 
-            // Update the existing post
-            diesel::update(posts::table.filter(posts::post_id.eq(post_id)))
-                .set((
-                    posts::post_title.eq(&request.post_title),
-                    posts::post_slug.eq(&slug),
-                    posts::post_content.eq(&rendered_markdown),
-                    posts::post_is_published.eq(request.post_is_published),
-                    posts::post_published_at.eq(new_published_at),
-                    posts::post_updated_at.eq(chrono::Utc::now()),
-                    posts::post_metadata.eq(&post_metadata),
-                ))
-                .returning(posts::all_columns)
-                .get_result(&mut conn)
-                .await
-                .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?
-        }
-        // CASE: Creating a new post
-        None => {
-            let new_published_at = if request.post_is_published {
-                Some(now)
-            } else {
-                None
-            };
-            let new_post = NewPost::new(
-                &user_id,
-                &request.post_title,
-                &slug,
-                &rendered_markdown,
-                new_published_at,
-                request.post_is_published,
-                &post_metadata,
-            );
+                        let new_published_at = if request.post_is_published {
+                            existing_published_at.or(Some(now))
+                        } else {
+                            None
+                        };
 
-            diesel::insert_into(posts::table)
-                .values(new_post)
-                .returning(posts::all_columns)
-                .get_result(&mut conn)
-                .await
-                .map_err(|e| match e {
-                    diesel::result::Error::DatabaseError(
-                        diesel::result::DatabaseErrorKind::UniqueViolation,
-                        _,
-                    ) => code_err(CodeError::POST_TITLE_NOT_UNIQUE, e),
-                    _ => code_err(CodeError::DB_INSERTION_ERROR, e),
-                })?
-        }
-    };
-
-    if tags_changed {
-        // Replace post<->tag relations only when effective tags changed.
-        diesel::delete(post_tags::table.filter(post_tags::post_id.eq(post.post_id)))
-            .execute(&mut conn)
-            .await
-            .map_err(|e| code_err(CodeError::DB_DELETION_ERROR, e))?;
-
-        if !requested_tags.is_empty() {
-            let new_tags: Vec<NewTag<'_>> =
-                requested_tags.iter().map(|tag| NewTag::new(tag)).collect();
-
-            diesel::insert_into(tags::table)
-                .values(&new_tags)
-                .on_conflict(tags::tag_name)
-                .do_nothing()
-                .execute(&mut conn)
-                .await
-                .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
-
-            let tag_rows: Vec<(i16, String)> = tags::table
-                .filter(tags::tag_name.eq_any(&requested_tags))
-                .select((tags::tag_id, tags::tag_name))
-                .load(&mut conn)
-                .await
-                .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
-
-            let tag_id_by_name: HashMap<String, i16> =
-                tag_rows.into_iter().map(|(id, name)| (name, id)).collect();
-
-            let tag_ids: Vec<i16> = requested_tags
-                .iter()
-                .map(|tag| {
-                    tag_id_by_name.get(tag).copied().ok_or_else(|| {
-                        code_err(
-                            CodeError::DB_QUERY_ERROR,
-                            format!("Tag ID not found after upsert for tag '{tag}'"),
+                        // Update the existing post
+                        let post = diesel::update(posts::table.filter(posts::post_id.eq(post_id)))
+                            .set((
+                                posts::post_title.eq(&request.post_title),
+                                posts::post_slug.eq(&slug),
+                                posts::post_content.eq(&rendered_markdown),
+                                posts::post_is_published.eq(request.post_is_published),
+                                posts::post_published_at.eq(new_published_at),
+                                posts::post_updated_at.eq(chrono::Utc::now()),
+                                posts::post_metadata.eq(&post_metadata),
+                                posts::post_version.eq(posts::post_version + 1),
+                            ))
+                            .returning(posts::all_columns)
+                            .get_result(&mut *conn)
+                            .await
+                            .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
+                        (
+                            post,
+                            request.post_is_published && existing_published_at.is_none(),
                         )
-                    })
-                })
-                .collect::<Result<Vec<i16>, CodeErrorResp>>()?;
+                    }
+                    // CASE: Creating a new post
+                    None => {
+                        let new_published_at = if request.post_is_published {
+                            Some(now)
+                        } else {
+                            None
+                        };
+                        let new_post = NewPost::new(
+                            &user_id,
+                            &request.post_title,
+                            &slug,
+                            &rendered_markdown,
+                            new_published_at,
+                            request.post_is_published,
+                            &post_metadata,
+                        );
 
-            let new_post_tags: Vec<NewPostTag> = tag_ids
-                .iter()
-                .map(|tag_id| NewPostTag::new(&post.post_id, tag_id))
-                .collect();
+                        let post = diesel::insert_into(posts::table)
+                            .values(new_post)
+                            .returning(posts::all_columns)
+                            .get_result(&mut *conn)
+                            .await
+                            .map_err(|e| match e {
+                                diesel::result::Error::DatabaseError(
+                                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                                    _,
+                                ) => code_err(CodeError::POST_TITLE_NOT_UNIQUE, e),
+                                _ => code_err(CodeError::DB_INSERTION_ERROR, e),
+                            })?;
+                        (post, request.post_is_published)
+                    }
+                };
 
-            diesel::insert_into(post_tags::table)
-                .values(&new_post_tags)
-                .execute(&mut conn)
-                .await
-                .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
-        }
-    }
+                // Replace post<->tag relations only when effective tags changed.
+                if tags_changed {
+                    PostTag::replace_for_post(&mut *conn, post.post_id, requested_tags)
+                        .await
+                        .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+                }
 
-    drop(conn);
+                Ok((post, newly_published))
+            }
+            .scope_boxed()
+        })
+        .await?;
 
     let final_tags: Vec<String> = if tags_changed {
         requested_tags.clone()
@@ -245,6 +219,31 @@ pub async fn submit_post(
     let post_info: PostInfo = post.clone().into();
     let cached_post = CachedPostInfo::from_post_info_with_tags(post_info, final_tags.clone());
     state.insert_post_to_cache(&cached_post).await;
+    if newly_published {
+        emit_webhook_event(&state, WebhookEvent::post_published(&post));
+        state
+            .publish_live_event(LiveEvent::PostPublished {
+                post: PostInfo::from(post.clone()),
+            })
+            .await;
+    }
+    state
+        .record_admin_action(
+            &actor,
+            if request.post_id.is_some() {
+                AdminAction::PostUpdate
+            } else {
+                AdminAction::PostCreate
+            },
+            Some(post.post_id.to_string()),
+            serde_json::json!({
+                "title": post.post_title,
+                "slug": post.post_slug,
+                "is_published": post.post_is_published,
+                "tags": final_tags,
+            }),
+        )
+        .await;
 
     Ok(http_resp(
         SubmitPostResponse {
@@ -254,6 +253,7 @@ pub async fn submit_post(
             post_created_at: post.post_created_at,
             post_updated_at: post.post_updated_at,
             post_is_published: post.post_is_published,
+            post_version: post.post_version,
         },
         (),
         start,
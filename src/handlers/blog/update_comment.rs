@@ -5,7 +5,7 @@ use axum::{
     extract::{Path, State},
     response::IntoResponse,
 };
-use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
@@ -16,10 +16,10 @@ use crate::{
         requests::blog::update_comment_request::UpdateCommentRequest,
         responses::response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::{comments, user_profile_pictures, users},
-    util::time::now::tokio_now,
+    util::{db::transaction::ScopedFutureExt, time::now::tokio_now},
 };
 
 #[utoipa::path(
@@ -36,6 +36,7 @@ use crate::{
         (status = 401, description = "Unauthorized", body = CodeErrorResp),
         (status = 403, description = "Forbidden", body = CodeErrorResp),
         (status = 404, description = "Comment not found", body = CodeErrorResp),
+        (status = 409, description = "Comment changed since `comment_version`; `latest` holds the current comment", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
@@ -48,41 +49,64 @@ pub async fn update_comment(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
-
     let is_superuser = role_type.is_superuser();
 
-    // Check authorship
-    let author_id: Uuid = comments::table
-        .select(comments::user_id)
-        .filter(comments::comment_id.eq(comment_id))
-        .first(&mut conn)
-        .await
-        .optional()
-        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
-        .ok_or_else(|| code_err(CodeError::COMMENT_NOT_FOUND, "Comment not found"))?;
+    let updated_comment: DbComment = state
+        .transaction(|conn| {
+            async move {
+                // Locked so a concurrent edit cannot slip in between the version
+                // check and the update.
+                let current: DbComment = comments::table
+                    .filter(comments::comment_id.eq(comment_id))
+                    .filter(comments::comment_deleted_at.is_null())
+                    .select(DbComment::as_select())
+                    .for_update()
+                    .first(&mut *conn)
+                    .await
+                    .optional()
+                    .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+                    .ok_or_else(|| code_err(CodeError::COMMENT_NOT_FOUND, "Comment not found"))?;
+
+                // Check authorship
+                if current.user_id != requester_id && !is_superuser {
+                    return Err(code_err(
+                        CodeError::UNAUTHORIZED_ACCESS,
+                        "User is not authorized to edit this comment",
+                    ));
+                }
+
+                if request
+                    .comment_version
+                    .is_some_and(|version| version != current.comment_version)
+                {
+                    return Err(code_err(
+                        CodeError::EDIT_CONFLICT,
+                        format!(
+                            "comment {comment_id} is at version {}, edit was based on {:?}",
+                            current.comment_version, request.comment_version
+                        ),
+                    )
+                    .with_latest(&current));
+                }
 
-    if author_id != requester_id && !is_superuser {
-        return Err(code_err(
-            CodeError::UNAUTHORIZED_ACCESS,
-            "User is not authorized to edit this comment",
-        ));
-    }
+                // Update comment
+                diesel::update(comments::table.filter(comments::comment_id.eq(comment_id)))
+                    .set((
+                        comments::comment_content.eq(&request.comment_content),
+                        comments::comment_updated_at.eq(chrono::Utc::now()),
+                        comments::comment_version.eq(comments::comment_version + 1),
+                    ))
+                    .returning(comments::all_columns)
+                    .get_result(&mut *conn)
+                    .await
+                    .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))
+            }
+            .scope_boxed()
+        })
+        .await?;
+    let author_id = updated_comment.user_id;
 
-    // Update comment
-    let updated_comment: DbComment =
-        diesel::update(comments::table.filter(comments::comment_id.eq(comment_id)))
-            .set((
-                comments::comment_content.eq(&request.comment_content),
-                comments::comment_updated_at.eq(chrono::Utc::now()),
-            ))
-            .returning(comments::all_columns)
-            .get_result(&mut conn)
-            .await
-            .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     // Get user info for response
     let (user_name, user_country): (String, i32) = users::table
@@ -1,27 +1,35 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::{collections::HashSet, sync::Arc};
 
 use axum::{
     Extension, Json,
     extract::{Path, State},
     response::IntoResponse,
 };
-use diesel::{ExpressionMethods, QueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
 use crate::{
-    domain::blog::blog::{CachedPostInfo, NewPostTag, NewTag, Post, PostInfo},
+    domain::{
+        audit::audit::{AdminAction, changed_fields},
+        blog::{
+            blog::{CachedPostInfo, Post, PostInfo, PostTag},
+            live_event::LiveEvent,
+        },
+        webhook::webhook::WebhookEvent,
+    },
     dto::{
         requests::blog::update_post_request::UpdatePostRequest,
         responses::{blog::submit_post_response::SubmitPostResponse, response_data::http_resp},
     },
     errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
     init::state::ServerState,
-    schema::{post_tags, posts, tags},
-    util::{string::generate_slug::generate_slug, time::now::tokio_now},
+    jobs::tasks::webhook::emit_webhook_event,
+    schema::posts,
+    util::{
+        db::transaction::ScopedFutureExt, extract::AdminActor,
+        string::generate_slug::generate_slug, time::now::tokio_now,
+    },
 };
 
 #[utoipa::path(
@@ -37,11 +45,13 @@ use crate::{
         (status = 401, description = "Unauthorized", body = CodeErrorResp),
         (status = 403, description = "Forbidden", body = CodeErrorResp),
         (status = 404, description = "Post not found", body = CodeErrorResp),
+        (status = 409, description = "Post changed since `post_version`; `latest` holds the current post", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
 pub async fn update_post(
     Extension(_user_id): Extension<Uuid>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
     Path(post_id): Path<Uuid>,
     Json(request): Json<UpdatePostRequest>,
@@ -76,11 +86,6 @@ pub async fn update_post(
         None => true,
     };
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
-
     // Generate slug from title
     let slug: String = generate_slug(&request.post_title);
     let now = chrono::Utc::now();
@@ -91,90 +96,73 @@ pub async fn update_post(
         "markdown_content": request.post_content
     });
 
-    let existing_published_at: Option<chrono::DateTime<chrono::Utc>> = posts::table
-        .filter(posts::post_id.eq(post_id))
-        .select(posts::post_published_at)
-        .first::<Option<chrono::DateTime<chrono::Utc>>>(&mut conn)
-        .await
-        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
-
-    let new_published_at = if request.post_is_published {
-        existing_published_at.or(Some(now))
-    } else {
-        None
-    };
-
-    // Update the existing post
-    let post: Post = diesel::update(posts::table.filter(posts::post_id.eq(post_id)))
-        .set((
-            posts::post_title.eq(&request.post_title),
-            posts::post_slug.eq(&slug),
-            posts::post_content.eq(&rendered_markdown),
-            posts::post_is_published.eq(request.post_is_published),
-            posts::post_published_at.eq(new_published_at),
-            posts::post_updated_at.eq(now),
-            posts::post_metadata.eq(&post_metadata),
-        ))
-        .returning(posts::all_columns)
-        .get_result(&mut conn)
-        .await
-        .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
-
-    if tags_changed {
-        // Replace post<->tag relations only when effective tags changed.
-        diesel::delete(post_tags::table.filter(post_tags::post_id.eq(post.post_id)))
-            .execute(&mut conn)
-            .await
-            .map_err(|e| code_err(CodeError::DB_DELETION_ERROR, e))?;
-
-        if !requested_tags.is_empty() {
-            let new_tags: Vec<NewTag<'_>> =
-                requested_tags.iter().map(|tag| NewTag::new(tag)).collect();
-
-            diesel::insert_into(tags::table)
-                .values(&new_tags)
-                .on_conflict(tags::tag_name)
-                .do_nothing()
-                .execute(&mut conn)
-                .await
-                .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
-
-            let tag_rows: Vec<(i16, String)> = tags::table
-                .filter(tags::tag_name.eq_any(&requested_tags))
-                .select((tags::tag_id, tags::tag_name))
-                .load(&mut conn)
-                .await
-                .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
-
-            let tag_id_by_name: HashMap<String, i16> =
-                tag_rows.into_iter().map(|(id, name)| (name, id)).collect();
-
-            let tag_ids: Vec<i16> = requested_tags
-                .iter()
-                .map(|tag| {
-                    tag_id_by_name.get(tag).copied().ok_or_else(|| {
-                        code_err(
-                            CodeError::DB_QUERY_ERROR,
-                            format!("Tag ID not found after upsert for tag '{tag}'"),
-                        )
-                    })
-                })
-                .collect::<Result<Vec<i16>, CodeErrorResp>>()?;
-
-            let new_post_tags: Vec<NewPostTag> = tag_ids
-                .iter()
-                .map(|tag_id| NewPostTag::new(&post.post_id, tag_id))
-                .collect();
-
-            diesel::insert_into(post_tags::table)
-                .values(&new_post_tags)
-                .execute(&mut conn)
-                .await
-                .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
-        }
-    }
-
-    drop(conn);
+    // The post row and its tag links change together or not at all.
+    let (post, existing_published_at): (Post, Option<chrono::DateTime<chrono::Utc>>) = state
+        .transaction(|conn| {
+            let requested_tags = &requested_tags;
+            async move {
+                // Locked so a concurrent edit cannot slip in between the version
+                // check and the update.
+                let current: Post = posts::table
+                    .filter(posts::post_id.eq(post_id))
+                    .filter(posts::post_deleted_at.is_null())
+                    .select(Post::as_select())
+                    .for_update()
+                    .first(&mut *conn)
+                    .await
+                    .optional()
+                    .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+                    .ok_or_else(|| code_err(CodeError::POST_NOT_FOUND, "Post not found"))?;
+                if request
+                    .post_version
+                    .is_some_and(|version| version != current.post_version)
+                {
+                    return Err(code_err(
+                        CodeError::EDIT_CONFLICT,
+                        format!(
+                            "post {post_id} is at version {}, edit was based on {:?}",
+                            current.post_version, request.post_version
+                        ),
+                    )
+                    .with_latest(&current));
+                }
+                let existing_published_at = current.post_published_at;
+
+                let new_published_at = if request.post_is_published {
+                    existing_published_at.or(Some(now))
+                } else {
+                    None
+                };
+
+                // Update the existing post
+                let post: Post = diesel::update(posts::table.filter(posts::post_id.eq(post_id)))
+                    .set((
+                        posts::post_title.eq(&request.post_title),
+                        posts::post_slug.eq(&slug),
+                        posts::post_content.eq(&rendered_markdown),
+                        posts::post_is_published.eq(request.post_is_published),
+                        posts::post_published_at.eq(new_published_at),
+                        posts::post_updated_at.eq(now),
+                        posts::post_metadata.eq(&post_metadata),
+                        posts::post_version.eq(posts::post_version + 1),
+                    ))
+                    .returning(posts::all_columns)
+                    .get_result(&mut *conn)
+                    .await
+                    .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
+
+                // Replace post<->tag relations only when effective tags changed.
+                if tags_changed {
+                    PostTag::replace_for_post(&mut *conn, post.post_id, requested_tags)
+                        .await
+                        .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+                }
+
+                Ok((post, existing_published_at))
+            }
+            .scope_boxed()
+        })
+        .await?;
 
     let final_tags: Vec<String> = if tags_changed {
         requested_tags.clone()
@@ -184,6 +172,27 @@ pub async fn update_post(
     let post_info = PostInfo::from(post.clone());
     let cached_post = CachedPostInfo::from_post_info_with_tags(post_info, final_tags);
     state.insert_post_to_cache(&cached_post).await;
+    if request.post_is_published && existing_published_at.is_none() {
+        emit_webhook_event(&state, WebhookEvent::post_published(&post));
+        state
+            .publish_live_event(LiveEvent::PostPublished {
+                post: PostInfo::from(post.clone()),
+            })
+            .await;
+    }
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::PostUpdate,
+            Some(post_id.to_string()),
+            changed_fields(serde_json::json!({
+                "title": post.post_title,
+                "slug": post.post_slug,
+                "is_published": post.post_is_published,
+                "tags": tags_changed.then_some(&requested_tags),
+            })),
+        )
+        .await;
 
     Ok(http_resp(
         SubmitPostResponse {
@@ -193,6 +202,7 @@ pub async fn update_post(
             post_created_at: post.post_created_at,
             post_updated_at: post.post_updated_at,
             post_is_published: post.post_is_published,
+            post_version: post.post_version,
         },
         (),
         start,
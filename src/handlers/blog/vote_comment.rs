@@ -10,11 +10,12 @@ use diesel_async::{AsyncConnection, RunQueryDsl};
 use uuid::Uuid;
 
 use crate::{
+    domain::{blog::live_event::LiveEvent, notification::notification::CommentLocation},
     dto::{
         requests::blog::upvote_comment_request::UpvoteCommentRequest,
         responses::{blog::vote_comment_response::VoteCommentResponse, response_data::http_resp},
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::comments,
     util::time::now::tokio_now,
@@ -39,6 +40,7 @@ pub struct CountRow {
     responses(
         (status = 200, description = "Vote recorded", body = VoteCommentResponse),
         (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 404, description = "Comment not found", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
@@ -50,12 +52,9 @@ pub async fn vote_comment(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
-    let count_row: CountRow = match conn
+    let (count_row, post_id): (CountRow, Uuid) = match conn
         .transaction::<_, diesel::result::Error, _>(async |conn| {
             let is_upvote = request.is_upvote;
 
@@ -84,23 +83,45 @@ pub async fn vote_comment(
             .get_result(&mut *conn)
             .await?;
 
-            // 3. Update the comments table with the new counts
-            diesel::update(comments::table.filter(comments::comment_id.eq(comment_id)))
-                .set((
-                    comments::total_upvotes.eq(counts.upvote_count),
-                    comments::total_downvotes.eq(counts.downvote_count),
-                ))
-                .execute(&mut *conn)
-                .await?;
+            // 3. Update the comments table with the new counts. A soft-deleted
+            // comment matches no row; the `NotFound` rolls the vote back.
+            let post_id: Uuid = diesel::update(
+                comments::table
+                    .filter(comments::comment_id.eq(comment_id))
+                    .filter(comments::comment_deleted_at.is_null()),
+            )
+            .set((
+                comments::total_upvotes.eq(counts.upvote_count),
+                comments::total_downvotes.eq(counts.downvote_count),
+            ))
+            .returning(comments::post_id)
+            .get_result(&mut *conn)
+            .await?;
 
-            Ok(counts)
+            Ok((counts, post_id))
         })
         .await
     {
         Ok(crow) => crow,
+        Err(diesel::result::Error::NotFound) => {
+            return Err(code_err(CodeError::COMMENT_NOT_FOUND, "Comment not found"));
+        }
         Err(e) => return Err(code_err(CodeError::DB_INSERTION_ERROR, e)), // Simplified error handling
     };
 
+    state
+        .publish_live_event(LiveEvent::CommentVotesChanged {
+            post_id,
+            comment_id,
+            upvote_count: count_row.upvote_count,
+            downvote_count: count_row.downvote_count,
+        })
+        .await;
+
+    if request.is_upvote {
+        state.notify_upvote(user_id, CommentLocation::Post { post_id }, Some(comment_id));
+    }
+
     Ok(http_resp(
         VoteCommentResponse {
             upvote_count: count_row.upvote_count,
@@ -10,11 +10,12 @@ use diesel_async::{AsyncConnection, RunQueryDsl};
 use uuid::Uuid;
 
 use crate::{
+    domain::{blog::live_event::LiveEvent, notification::notification::CommentLocation},
     dto::{
         requests::blog::upvote_post_request::UpvotePostRequest,
         responses::{blog::vote_post_response::VotePostResponse, response_data::http_resp},
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::posts,
     util::time::now::tokio_now,
@@ -51,10 +52,7 @@ pub async fn vote_post(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     // Existence check only: the count fields are updated in-place below, so we
     // must not retain (and later write back) a full pre-transaction snapshot of
@@ -126,14 +124,21 @@ pub async fn vote_post(
     // None if the entry is absent (e.g. post deleted between the DB transaction
     // and this write); the DB stays authoritative and the next
     // synchronize_post_info_cache reconciles.
-    let _ = state
-        .blog_posts_cache
-        .update_async(&post_id, |_, cached| {
-            cached.total_upvotes = upvote_count;
-            cached.total_downvotes = downvote_count;
+    state
+        .update_post_vote_counts(post_id, upvote_count, downvote_count)
+        .await;
+    state
+        .publish_live_event(LiveEvent::PostVotesChanged {
+            post_id,
+            upvote_count,
+            downvote_count,
         })
         .await;
 
+    if request.is_upvote {
+        state.notify_upvote(user_id, CommentLocation::Post { post_id }, None);
+    }
+
     Ok(http_resp(
         VotePostResponse {
             upvote_count,
@@ -0,0 +1,2 @@
+pub mod ses_feedback;
+pub mod unsubscribe;
@@ -0,0 +1,128 @@
+//! SNS endpoint for SES bounce and complaint notifications.
+//!
+//! Subscribe it (HTTPS) to the SNS topics SES publishes feedback to and list
+//! them in `AWS_SES_SNS_TOPIC_ARNS`. Every message must carry a valid SNS
+//! signature from an allowed topic. Subscription confirmations are confirmed
+//! by fetching their `SubscribeURL`; notifications are recorded in
+//! `email_feedback_events`, and hard bounces and complaints put the address
+//! on the suppression list the email sender checks.
+
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse};
+use tracing::{info, warn};
+
+use crate::{
+    domain::email::feedback::EmailFeedback,
+    dto::responses::{email::ses_feedback_response::SesFeedbackResponse, response_data::http_resp},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{
+        email::sns::{SnsMessage, SnsVerifyError, is_sns_url},
+        time::now::tokio_now,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/email/ses-feedback",
+    tag = "email",
+    request_body(content = String, description = "SNS message (JSON sent as text/plain)", content_type = "text/plain"),
+    responses(
+        (status = 200, description = "Message accepted", body = SesFeedbackResponse),
+        (status = 400, description = "Malformed or badly signed SNS message", body = CodeErrorResp),
+        (status = 403, description = "Topic not in AWS_SES_SNS_TOPIC_ARNS", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp),
+        (status = 502, description = "SNS unreachable (signing certificate or SubscribeURL)", body = CodeErrorResp)
+    )
+)]
+pub async fn ses_feedback(
+    State(state): State<Arc<ServerState>>,
+    body: String,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let message: SnsMessage =
+        serde_json::from_str(&body).map_err(|e| code_err(CodeError::INVALID_SNS_MESSAGE, e))?;
+    if !state
+        .config
+        .email
+        .sns_topic_arns
+        .iter()
+        .any(|arn| arn.trim() == message.topic_arn)
+    {
+        return Err(code_err(
+            CodeError::SNS_TOPIC_NOT_ALLOWED,
+            format!("topic {}", message.topic_arn),
+        ));
+    }
+    message
+        .verify(state.get_request_client())
+        .await
+        .map_err(|e| match e {
+            SnsVerifyError::Invalid(reason) => code_err(CodeError::INVALID_SNS_MESSAGE, reason),
+            SnsVerifyError::CertUnavailable(e) => code_err(CodeError::SNS_UNREACHABLE, e),
+        })?;
+
+    let mut response = SesFeedbackResponse {
+        recorded: 0,
+        suppressed: 0,
+    };
+    if message.is_subscription_confirmation() {
+        confirm_subscription(&state, &message).await?;
+    } else if message.is_notification() {
+        let feedback = EmailFeedback::parse_ses(&message.message)
+            .map_err(|e| code_err(CodeError::INVALID_SNS_MESSAGE, e))?;
+        if !feedback.is_empty() {
+            let payload: serde_json::Value = serde_json::from_str(&message.message)
+                .map_err(|e| code_err(CodeError::INVALID_SNS_MESSAGE, e))?;
+            response.suppressed = state
+                .record_email_feedback(&feedback, &payload)
+                .await
+                .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+            response.recorded = feedback.len();
+            for item in &feedback {
+                info!(
+                    kind = item.kind.as_str(),
+                    recipient = %item.recipient,
+                    feedback_type = ?item.feedback_type,
+                    suppressed = item.suppress,
+                    "SES feedback recorded"
+                );
+            }
+        }
+    } else {
+        info!(kind = %message.kind, topic = %message.topic_arn, "SNS message acknowledged");
+    }
+
+    Ok(http_resp(response, (), start))
+}
+
+async fn confirm_subscription(
+    state: &ServerState,
+    message: &SnsMessage,
+) -> Result<(), CodeErrorResp> {
+    let url = message.subscribe_url.as_deref().unwrap_or_default();
+    if !is_sns_url(url) {
+        return Err(code_err(
+            CodeError::INVALID_SNS_MESSAGE,
+            format!("SubscribeURL {url} is not an SNS URL"),
+        ));
+    }
+    match state
+        .get_request_client()
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+    {
+        Ok(_) => {
+            info!(topic = %message.topic_arn, "SNS subscription confirmed");
+            Ok(())
+        }
+        Err(e) => {
+            warn!(topic = %message.topic_arn, error = %e, "SNS subscription confirmation failed");
+            Err(code_err(CodeError::SNS_UNREACHABLE, e))
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Unsubscribe links from optional emails, followed without logging in.
+//!
+//! The token in the link is signed for one user and one kind of email (see
+//! `util/email/unsubscribe`). `GET` tells the confirmation page what the link
+//! is for; `POST` opts out, and doubles as the RFC 8058 one-click target of
+//! the `List-Unsubscribe` header, so its body is ignored.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    dto::{
+        requests::email::unsubscribe_request::UnsubscribeRequest,
+        responses::{email::unsubscribe_response::UnsubscribeResponse, response_data::http_resp},
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{email::unsubscribe::EmailTopic, time::now::tokio_now},
+};
+
+fn verify_token(state: &ServerState, token: &str) -> Result<(Uuid, EmailTopic), CodeErrorResp> {
+    state
+        .unsubscribe_key()
+        .verify(token)
+        .ok_or_else(|| CodeError::INVALID_UNSUBSCRIBE_TOKEN.into())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/email/unsubscribe",
+    tag = "email",
+    params(UnsubscribeRequest),
+    responses(
+        (status = 200, description = "What the link unsubscribes from", body = UnsubscribeResponse),
+        (status = 400, description = "Invalid token", body = CodeErrorResp),
+        (status = 429, description = "Too many requests", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn check_unsubscribe(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<UnsubscribeRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let (user_id, topic) = verify_token(&state, &request.token)?;
+
+    let subscribed = match topic {
+        EmailTopic::NotificationDigest => {
+            state
+                .notification_preferences(user_id)
+                .await
+                .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+                .digest
+        }
+    };
+
+    Ok(http_resp(
+        UnsubscribeResponse {
+            topic: topic.as_str(),
+            subscribed,
+        },
+        (),
+        start,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/email/unsubscribe",
+    tag = "email",
+    params(UnsubscribeRequest),
+    responses(
+        (status = 200, description = "Unsubscribed", body = UnsubscribeResponse),
+        (status = 400, description = "Invalid token, or the user no longer exists", body = CodeErrorResp),
+        (status = 429, description = "Too many requests", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn unsubscribe(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<UnsubscribeRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let (user_id, topic) = verify_token(&state, &request.token)?;
+
+    let found = match topic {
+        EmailTopic::NotificationDigest => state
+            .unsubscribe_notification_digest(user_id)
+            .await
+            .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?,
+    };
+    if !found {
+        return Err(code_err(
+            CodeError::INVALID_UNSUBSCRIBE_TOKEN,
+            "user no longer exists",
+        ));
+    }
+    info!(%user_id, topic = topic.as_str(), "Unsubscribed by email link");
+
+    Ok(http_resp(
+        UnsubscribeResponse {
+            topic: topic.as_str(),
+            subscribed: false,
+        },
+        (),
+        start,
+    ))
+}
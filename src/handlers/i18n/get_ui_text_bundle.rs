@@ -3,10 +3,7 @@ use std::sync::Arc;
 use axum::{extract::Query, extract::State, response::IntoResponse};
 
 use crate::{
-    domain::i18n::ui_text::{
-        keys::REQUIRED_UI_TEXT_KEYS,
-        locale::{EN_US_COUNTRY_CODE, EN_US_LANGUAGE_CODE, UiLocale},
-    },
+    domain::i18n::ui_text::locale::UiLocale,
     dto::{
         requests::i18n::get_ui_text_bundle_request::GetUiTextBundleRequest,
         responses::{
@@ -34,14 +31,7 @@ pub async fn get_ui_text_bundle(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
     let locale = UiLocale::parse(request.locale.as_deref());
-    let i18n_cache = state.i18n_cache.read().await;
-    let texts = i18n_cache.ui_text_bundle(
-        locale.country_code(),
-        locale.language_code(),
-        EN_US_COUNTRY_CODE,
-        EN_US_LANGUAGE_CODE,
-        REQUIRED_UI_TEXT_KEYS,
-    );
+    let texts = state.ui_text_bundle(locale).await;
 
     if texts.is_empty() {
         return Err(code_err(
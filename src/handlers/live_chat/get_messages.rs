@@ -19,7 +19,7 @@ use crate::{
             response_data::http_resp,
         },
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::live_chat_messages,
     util::time::now::tokio_now,
@@ -84,10 +84,7 @@ async fn get_messages_before_from_db(
     before_message_id: Uuid,
     limit: usize,
 ) -> HandlerResponse<Vec<CachedChatMessage>> {
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let before_row: Option<LiveChatMessage> = live_chat_messages::table
         .filter(live_chat_messages::live_chat_message_id.eq(before_message_id))
@@ -15,9 +15,12 @@ use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::{
-    domain::live_chat::{
-        binary_codec::LIVE_CHAT_BINARY_PROTOCOL,
-        cache::{ChatActor, ChatConnectionState, DEFAULT_LIVE_CHAT_ROOM, LiveChatServerEvent},
+    domain::{
+        live_chat::{
+            binary_codec::LIVE_CHAT_BINARY_PROTOCOL,
+            cache::{ChatActor, ChatConnectionState, DEFAULT_LIVE_CHAT_ROOM, LiveChatServerEvent},
+        },
+        notification::notification::Notification,
     },
     init::state::ServerState,
     routers::middleware::is_logged_in::{AuthSession, AuthStatus},
@@ -366,6 +369,17 @@ async fn handle_send_message(
         .await
     {
         if let Some(ban) = persist_live_chat_ban(state.clone(), &actor, client_ip).await {
+            if let Some(user_id) = ban.user_id {
+                state
+                    .notify_user(
+                        user_id,
+                        Notification::LiveChatBanned {
+                            reason: ban.reason.clone(),
+                            expires_at: ban.expires_at,
+                        },
+                    )
+                    .await;
+            }
             state.live_chat_cache.cache_ban(ban).await;
         }
         let event = LiveChatServerEvent::Error {
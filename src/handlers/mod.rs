@@ -1,11 +1,15 @@
 pub mod admin;
+pub mod album;
 pub mod auth;
 pub mod blog;
 pub mod countries;
+pub mod email;
 pub mod geo_ip;
 pub mod i18n;
 pub mod live_chat;
+pub mod notifications;
 pub mod photography;
 pub mod server;
+pub mod upload;
 pub mod user;
 pub mod wasm_module;
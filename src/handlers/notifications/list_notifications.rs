@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    domain::notification::notification::NotificationItem,
+    dto::{
+        requests::notifications::list_notifications_request::ListNotificationsRequest,
+        responses::{
+            notifications::notifications_response::ListNotificationsResponse,
+            pagination::CursorPage, response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::{db::keyset::Keyset, time::now::tokio_now},
+};
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    tag = "notifications",
+    params(ListNotificationsRequest),
+    responses(
+        (status = 200, description = "The user's notifications, newest first", body = ListNotificationsResponse),
+        (status = 400, description = "Malformed cursor", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn list_notifications(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<ListNotificationsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let after = match &request.cursor {
+        None => None,
+        Some(raw) => Some(
+            Keyset::from_cursor(raw)
+                .ok_or_else(|| code_err(CodeError::INVALID_REQUEST, "Malformed cursor"))?,
+        ),
+    };
+
+    // One extra row tells whether older notifications remain.
+    let rows = state
+        .list_notifications(user_id, request.unread_only, after, limit + 1)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    let page = CursorPage::from_overfetched(rows, limit as usize, |row| {
+        Keyset::new(row.notification_created_at, row.notification_id).to_string()
+    });
+    let notifications = page
+        .items
+        .into_iter()
+        .map(NotificationItem::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    let unread_count = state
+        .unread_notification_count(user_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+
+    Ok(http_resp(
+        ListNotificationsResponse {
+            notifications,
+            unread_count,
+            next_cursor: page.next_cursor,
+        },
+        (),
+        start,
+    ))
+}
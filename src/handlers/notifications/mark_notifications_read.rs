@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    dto::{
+        requests::notifications::mark_notifications_read_request::MarkNotificationsReadRequest,
+        responses::{
+            notifications::notifications_response::MarkNotificationsReadResponse,
+            response_data::{Response, http_resp},
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::time::now::tokio_now,
+};
+
+/// Largest `notification_ids` list accepted in one request.
+const MAX_IDS: usize = 500;
+
+#[utoipa::path(
+    post,
+    path = "/api/notifications/read",
+    tag = "notifications",
+    request_body = MarkNotificationsReadRequest,
+    responses(
+        (status = 200, description = "Notifications marked read", body = MarkNotificationsReadResponse),
+        (status = 400, description = "Too many ids", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn mark_notifications_read(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<MarkNotificationsReadRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    if request
+        .notification_ids
+        .as_ref()
+        .is_some_and(|ids| ids.len() > MAX_IDS)
+    {
+        return Err(code_err(
+            CodeError::INVALID_REQUEST,
+            format!("At most {MAX_IDS} notification_ids per request"),
+        ));
+    }
+
+    mark_read(&state, user_id, request.notification_ids.as_deref(), start).await
+}
+
+/// Marking a notification that is already read, or not the user's, marks
+/// nothing and still succeeds.
+#[utoipa::path(
+    post,
+    path = "/api/notifications/{notification_id}/read",
+    tag = "notifications",
+    params(
+        ("notification_id" = Uuid, Path, description = "Notification to mark read")
+    ),
+    responses(
+        (status = 200, description = "Notification marked read", body = MarkNotificationsReadResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn mark_notification_read(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<ServerState>>,
+    Path(notification_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    mark_read(&state, user_id, Some(&[notification_id]), start).await
+}
+
+async fn mark_read(
+    state: &ServerState,
+    user_id: Uuid,
+    notification_ids: Option<&[Uuid]>,
+    start: tokio::time::Instant,
+) -> HandlerResponse<Response<MarkNotificationsReadResponse, ()>> {
+    let marked = state
+        .mark_notifications_read(user_id, notification_ids)
+        .await
+        .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
+    let unread_count = state
+        .unread_notification_count(user_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+
+    Ok(http_resp(
+        MarkNotificationsReadResponse {
+            marked,
+            unread_count,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,9 @@
+pub mod list_notifications;
+pub mod mark_notifications_read;
+pub mod notification_preferences;
+pub mod ws;
+
+pub use list_notifications::list_notifications;
+pub use mark_notifications_read::{mark_notification_read, mark_notifications_read};
+pub use notification_preferences::{get_notification_preferences, update_notification_preferences};
+pub use ws::ws_notifications_handler;
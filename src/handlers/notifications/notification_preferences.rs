@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, response::IntoResponse};
+use uuid::Uuid;
+
+use crate::{
+    domain::notification::preferences::NotificationPreferences,
+    dto::responses::{
+        notifications::notifications_response::NotificationPreferencesResponse,
+        response_data::http_resp,
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+    util::time::now::tokio_now,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications/preferences",
+    tag = "notifications",
+    responses(
+        (status = 200, description = "The user's notification preferences", body = NotificationPreferencesResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_notification_preferences(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<ServerState>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    let preferences = state
+        .notification_preferences(user_id)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+
+    Ok(http_resp(
+        NotificationPreferencesResponse { preferences },
+        (),
+        start,
+    ))
+}
+
+/// Replaces every switch. Turning `mentions` or `replies` off stops those
+/// notifications from being stored at all, not only from being pushed.
+#[utoipa::path(
+    put,
+    path = "/api/notifications/preferences",
+    tag = "notifications",
+    request_body = NotificationPreferences,
+    responses(
+        (status = 200, description = "Preferences saved", body = NotificationPreferencesResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn update_notification_preferences(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<ServerState>>,
+    Json(preferences): Json<NotificationPreferences>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    state
+        .set_notification_preferences(user_id, preferences)
+        .await
+        .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
+
+    Ok(http_resp(
+        NotificationPreferencesResponse { preferences },
+        (),
+        start,
+    ))
+}
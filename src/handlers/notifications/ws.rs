@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::Response,
+};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::init::state::ServerState;
+
+/// Server-to-client only: each notification is one JSON text frame shaped as
+/// `NotificationEnvelope`. Anything the client sends other than a close is
+/// ignored.
+pub async fn ws_notifications_handler(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<ServerState>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    ws.on_upgrade(move |socket| handle_notifications_socket(socket, state, user_id))
+}
+
+async fn handle_notifications_socket(
+    mut socket: WebSocket,
+    state: Arc<ServerState>,
+    user_id: Uuid,
+) {
+    let mut receiver = state.notifications.subscribe(user_id).await;
+
+    loop {
+        tokio::select! {
+            notification = receiver.recv() => {
+                match notification {
+                    Ok(envelope) => {
+                        let Ok(text) = serde_json::to_string(envelope.as_ref()) else {
+                            continue;
+                        };
+                        if socket.send(Message::Text(text.into())).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(%user_id, skipped, "Notification receiver lagged; notifications dropped");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+            inbound = socket.recv() => {
+                match inbound {
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        debug!(%user_id, error = ?e, "Notifications WebSocket receive error");
+                        break;
+                    }
+                }
+            }
+            _ = state.shutdown.cancelled() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+        }
+    }
+
+    drop(receiver);
+    state.notifications.unsubscribe(user_id).await;
+}
@@ -21,10 +21,13 @@ use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::{
-    domain::photography::{
-        batch::session::{BatchItem, BatchSession},
-        batch::status::ProcessingStatus,
-        photographs::PhotographContext,
+    domain::{
+        audit::audit::AdminAction,
+        photography::{
+            batch::session::{BatchItem, BatchSession},
+            batch::status::ProcessingStatus,
+            photographs::PhotographContext,
+        },
     },
     dto::responses::{
         photography::batch_status_response::{BatchUploadItem, BatchUploadResponse},
@@ -33,9 +36,11 @@ use crate::{
     errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
     init::state::ServerState,
     util::{
+        extract::AdminActor,
         image::batch_pipeline::{
             BatchPipelineItem, append_chunk, batch_temp_dir, open_staging_file, spawn_batch,
         },
+        image::photograph_ingest::{parse_form_bool, resolve_watermark},
         time::now::tokio_now,
     },
 };
@@ -96,6 +101,7 @@ struct StagedFile {
 )]
 pub async fn batch_upload(
     Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
     mut multipart: Multipart,
 ) -> HandlerResponse<impl IntoResponse> {
@@ -106,6 +112,7 @@ pub async fn batch_upload(
     let mut staged: Vec<StagedFile> = Vec::new();
     let mut meta: Option<Vec<BatchMetaEntry>> = None;
     let mut context = PhotographContext::Photography;
+    let mut watermark_requested: Option<bool> = None;
 
     while let Some(mut field) = multipart.next_field().await.map_err(|e| {
         error!(error = ?e, user_id = %user_id, "Failed to fetch next multipart field");
@@ -213,6 +220,24 @@ pub async fn batch_upload(
                 }
             }
 
+            Some("watermark") => {
+                let text = field.text().await.map_err(|e| {
+                    error!(error = ?e, user_id = %user_id, "Failed reading watermark field");
+                    code_err(CodeError::FILE_UPLOAD_ERROR, e)
+                })?;
+                match parse_form_bool(&text) {
+                    Some(v) => watermark_requested = Some(v),
+                    None => {
+                        let _ = tokio::fs::remove_dir_all(&dir).await;
+                        warn!(user_id = %user_id, value = %text, "Invalid watermark value");
+                        return Err(code_err(
+                            CodeError::INVALID_REQUEST,
+                            "Invalid watermark value",
+                        ));
+                    }
+                }
+            }
+
             Some(other) => {
                 warn!(user_id = %user_id, field = other, "Unexpected batch multipart field");
             }
@@ -222,6 +247,14 @@ pub async fn batch_upload(
         }
     }
 
+    let watermark = match resolve_watermark(&state, watermark_requested) {
+        Ok(watermark) => watermark,
+        Err(e) => {
+            let _ = tokio::fs::remove_dir_all(&dir).await;
+            return Err(e);
+        }
+    };
+
     if staged.is_empty() {
         let _ = tokio::fs::remove_dir_all(&dir).await;
         warn!(user_id = %user_id, "Batch contained no files");
@@ -343,9 +376,18 @@ pub async fn batch_upload(
         pipeline_items,
         user_id,
         context,
+        watermark,
     );
 
     info!(user_id = %user_id, batch_id = %batch_id, total, "Accepted batch upload; processing started");
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::PhotographBatchUpload,
+            Some(batch_id.to_string()),
+            serde_json::json!({ "context": context, "total": total }),
+        )
+        .await;
 
     let resp = BatchUploadResponse {
         batch_id,
@@ -0,0 +1,243 @@
+//! `POST /api/photographs/presign/{upload_token}/confirm` — finish a presigned
+//! upload.
+//!
+//! Pulls the staged original back from storage, then hands it to the same
+//! ingest path as the multipart `upload_photograph` handler (queued processing
+//! for gallery photographs, synchronous for post images). The token is
+//! consumed up front and the staged object is deleted on every exit path after
+//! that, so a failed confirm requires a fresh presign.
+//!
+//! Returns 404 (`PRESIGNED_UPLOAD_NOT_FOUND`) when the token is absent,
+//! expired, or not owned by the caller; never 403 (see
+//! `server_state/photography_batches.rs`).
+
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        audit::audit::AdminAction,
+        photography::{
+            photographs::{Photograph, PhotographContext},
+            tags::normalize_tags,
+        },
+    },
+    dto::{
+        requests::photography::confirm_photograph_upload_request::ConfirmPhotographUploadRequest,
+        responses::{
+            photography::photograph_processing_response::PhotographProcessingResponse,
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    handlers::photography::upload_photograph::MAX_SIZE_OF_UPLOADABLE_PHOTOGRPAH,
+    init::state::ServerState,
+    util::{
+        extract::AdminActor,
+        image::photograph_ingest::{
+            PhotographIngestInput, accept_photograph_for_processing, ingest_photograph,
+            resolve_photograph_metadata, resolve_watermark,
+        },
+        image::raw_decode::RawFormat,
+        time::now::tokio_now,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/photographs/presign/{upload_token}/confirm",
+    tag = "photography",
+    params(("upload_token" = Uuid, Path, description = "Token returned by the presign endpoint")),
+    request_body = ConfirmPhotographUploadRequest,
+    responses(
+        (status = 200, description = "Post image processed and stored", body = Photograph),
+        (status = 202, description = "Photograph accepted; processing queued", body = PhotographProcessingResponse),
+        (status = 400, description = "Invalid upload payload", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Upload token not found or expired", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp),
+        (status = 503, description = "Processing queue full", body = CodeErrorResp)
+    )
+)]
+pub async fn confirm_photograph_upload(
+    Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(upload_token): Path<Uuid>,
+    Json(request): Json<ConfirmPhotographUploadRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let photograph_context = match request.context.as_deref() {
+        None => PhotographContext::Photography,
+        Some(text) => PhotographContext::from_str(text).ok_or_else(|| {
+            warn!(user_id = %user_id, value = %text, "Invalid photograph context");
+            code_err(CodeError::FILE_UPLOAD_ERROR, "Invalid photograph context")
+        })?,
+    };
+
+    // Validate before consuming the token so a rejected toggle can be retried.
+    let watermark = resolve_watermark(&state, request.watermark)?;
+
+    let upload = state
+        .take_owned_presigned_upload(upload_token, user_id, Utc::now())
+        .await
+        .ok_or_else(|| {
+            code_err(
+                CodeError::PRESIGNED_UPLOAD_NOT_FOUND,
+                format!("upload token {upload_token} not found for requester"),
+            )
+        })?;
+
+    let original = fetch_staged_original(&state, user_id, &upload.object_key).await;
+
+    // The staged original is never needed again, whatever happens next.
+    if let Err(e) = state.storage.delete(&upload.object_key).await {
+        error!(
+            error = ?e,
+            user_id = %user_id,
+            backend = state.storage.name(),
+            key = %upload.object_key,
+            "Failed to delete staged presigned upload"
+        );
+    }
+
+    let original = original?;
+
+    info!(
+        user_id = %user_id,
+        upload_token = %upload_token,
+        original_size_bytes = original.len() as u64,
+        "Fetched presigned photograph upload from storage"
+    );
+
+    let (photograph_comments, photograph_lat, photograph_lon) = resolve_photograph_metadata(
+        user_id,
+        photograph_context,
+        request.comments,
+        request.lat,
+        request.lon,
+        upload.file_name.as_deref(),
+        &original,
+    )
+    .await?;
+
+    let audit_summary = serde_json::json!({
+        "context": photograph_context,
+        "file_name": upload.file_name,
+        "original_size_bytes": original.len(),
+        "upload_token": upload_token,
+    });
+
+    let raw_format = RawFormat::detect(Some(&upload.content_type), upload.file_name.as_deref());
+    let input = PhotographIngestInput {
+        user_id,
+        content_type: Some(upload.content_type),
+        comments: photograph_comments,
+        lat: photograph_lat,
+        lon: photograph_lon,
+        context: photograph_context,
+        tags: normalize_tags(&request.tags),
+        raw_format,
+        watermark,
+    };
+
+    // Same split as `upload_photograph`: gallery photographs are processed
+    // asynchronously, editor images synchronously.
+    match photograph_context {
+        PhotographContext::Photography => {
+            let photograph =
+                accept_photograph_for_processing(Arc::clone(&state), original, input, None).await?;
+            state
+                .record_admin_action(
+                    &actor,
+                    AdminAction::PhotographUpload,
+                    Some(photograph.photograph_id.to_string()),
+                    audit_summary,
+                )
+                .await;
+            Ok((
+                StatusCode::ACCEPTED,
+                http_resp(PhotographProcessingResponse::new(&photograph), (), start),
+            )
+                .into_response())
+        }
+        PhotographContext::Post => {
+            let mut photograph: Photograph =
+                ingest_photograph(state.clone(), original, input).await?;
+            state
+                .record_admin_action(
+                    &actor,
+                    AdminAction::PhotographUpload,
+                    Some(photograph.photograph_id.to_string()),
+                    audit_summary,
+                )
+                .await;
+            state.deliver_photograph_links(&mut photograph);
+            Ok(http_resp(photograph, (), start).into_response())
+        }
+    }
+}
+
+/// Download the staged original, enforcing the single-upload size cap before
+/// pulling the body (the presigned `PUT` itself cannot bound the size).
+async fn fetch_staged_original(
+    state: &ServerState,
+    user_id: Uuid,
+    object_key: &str,
+) -> Result<Vec<u8>, CodeErrorResp> {
+    let content_length = state
+        .storage
+        .content_length(object_key)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, user_id = %user_id, key = %object_key, "Failed to stat presigned upload");
+            code_err(CodeError::FILE_UPLOAD_ERROR, e)
+        })?
+        .ok_or_else(|| {
+            warn!(
+                user_id = %user_id,
+                key = %object_key,
+                "Presigned upload object missing; client never uploaded?"
+            );
+            code_err(CodeError::FILE_UPLOAD_ERROR, "Uploaded object not found")
+        })?;
+
+    if content_length == 0 {
+        return Err(code_err(CodeError::FILE_UPLOAD_ERROR, "File is empty!"));
+    }
+    if content_length > MAX_SIZE_OF_UPLOADABLE_PHOTOGRPAH as u64 {
+        warn!(
+            user_id = %user_id,
+            content_length,
+            limit_bytes = MAX_SIZE_OF_UPLOADABLE_PHOTOGRPAH,
+            "Presigned upload exceeds maximum allowed size"
+        );
+        return Err(code_err(
+            CodeError::FILE_UPLOAD_ERROR,
+            "Uploaded file exceeds maximum allowed size",
+        ));
+    }
+
+    let object = state
+        .storage
+        .get(object_key)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, user_id = %user_id, key = %object_key, "Failed to fetch presigned upload");
+            code_err(CodeError::FILE_UPLOAD_ERROR, e)
+        })?
+        .ok_or_else(|| code_err(CodeError::FILE_UPLOAD_ERROR, "Uploaded object not found"))?;
+
+    Ok(object.bytes)
+}
@@ -14,15 +14,19 @@ use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
 use crate::{
-    domain::auth::role::RoleType,
+    domain::{
+        audit::audit::AdminAction,
+        auth::role::RoleType,
+        notification::notification::{CommentLocation, Notification},
+    },
     dto::responses::{
         photography::delete_photograph_comment_response::DeletePhotographCommentResponse,
         response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::photograph_comments,
-    util::time::now::tokio_now,
+    util::{extract::AdminActor, time::now::tokio_now},
 };
 
 #[utoipa::path(
@@ -42,17 +46,15 @@ use crate::{
 pub async fn delete_photograph_comment(
     Extension(requester_id): Extension<Uuid>,
     Extension(role_type): Extension<RoleType>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
-    Path((_photograph_id, comment_id)): Path<(Uuid, Uuid)>,
+    Path((photograph_id, comment_id)): Path<(Uuid, Uuid)>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
     let is_superuser = role_type.is_superuser();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let author_id: Uuid = photograph_comments::table
         .select(photograph_comments::user_id)
@@ -80,6 +82,27 @@ pub async fn delete_photograph_comment(
 
     drop(conn);
 
+    // Only moderation (a superuser removing someone else's comment) is audited.
+    if author_id != requester_id {
+        state
+            .record_admin_action(
+                &actor,
+                AdminAction::PhotographCommentDelete,
+                Some(comment_id.to_string()),
+                serde_json::json!({ "author_id": author_id }),
+            )
+            .await;
+        state
+            .notify_user(
+                author_id,
+                Notification::CommentRemoved {
+                    location: CommentLocation::Photograph { photograph_id },
+                    comment_id,
+                },
+            )
+            .await;
+    }
+
     Ok(http_resp(
         DeletePhotographCommentResponse {
             deleted_photograph_comment_id: comment_id,
@@ -1,18 +1,20 @@
 use std::sync::Arc;
 
 use axum::{Json, extract::State, response::IntoResponse};
+use chrono::Utc;
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 
 use crate::{
+    domain::audit::audit::AdminAction,
     dto::{
         requests::photography::delete_photographs_request::DeletePhotographsRequest,
         responses::response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::photographs::dsl::*,
-    util::time::now::tokio_now,
+    util::{extract::AdminActor, time::now::tokio_now},
 };
 
 #[utoipa::path(
@@ -21,176 +23,68 @@ use crate::{
     tag = "photography",
     request_body = DeletePhotographsRequest,
     responses(
-        (status = 200, description = "Photographs deleted successfully"),
+        (status = 200, description = "Photographs soft-deleted; restorable until purged"),
         (status = 401, description = "Unauthorized", body = CodeErrorResp),
         (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
 pub async fn delete_photographs(
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
     Json(body): Json<DeletePhotographsRequest>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     if body.photograph_ids.is_empty() {
         return Ok(http_resp(
-            serde_json::json!({
-                "deleted_count": 0usize,
-                "s3_deleted_count": 0usize
-            }),
+            serde_json::json!({ "deleted_count": 0usize }),
             (),
             start,
         ));
     }
 
-    // Load links for all requested photographs
-    let target_photographs: Vec<(String, String)> = photographs
-        .filter(photograph_id.eq_any(&body.photograph_ids))
-        .select((photograph_link, photograph_thumbnail_link))
-        .load::<(String, String)>(&mut conn)
-        .await
-        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
-
-    // 2. Perform a single batch delete in the DB
-    let deleted_rows =
-        diesel::delete(photographs.filter(photograph_id.eq_any(&body.photograph_ids)))
-            .execute(&mut conn)
-            .await
-            .map_err(|e| code_err(CodeError::DB_DELETION_ERROR, e))?;
+    // Soft delete: the rows and their stored objects stay until
+    // PURGE_SOFT_DELETED, so an admin can still restore them.
+    let deleted_rows = diesel::update(
+        photographs
+            .filter(photograph_id.eq_any(&body.photograph_ids))
+            .filter(photograph_deleted_at.is_null()),
+    )
+    .set(photograph_deleted_at.eq(Utc::now()))
+    .execute(&mut conn)
+    .await
+    .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
 
     drop(conn);
 
-    // 3. After DB deletion succeeds, delete objects from S3.
-    //    We treat S3 deletion as a best-effort side effect. Failures are logged
-    //    but do not roll back the DB (which already reflects the authoritative state).
-    use aws_sdk_s3::{Client, types::ObjectIdentifier};
-
-    let aws_config = state.aws_profile_picture_config.clone();
-    let s3_client = Client::new(&aws_config);
+    state
+        .image_variant_cache
+        .purge_photographs(&body.photograph_ids)
+        .await;
 
-    // Use the same bucket that upload_photograph.rs (and the profile/wasm handlers)
-    // write to; otherwise deletions target the wrong bucket and orphan objects.
-    use crate::util::s3::AWS_S3_BUCKET_NAME;
-    let bucket = AWS_S3_BUCKET_NAME.to_string();
-
-    // Helper: convert full URL to bucket-relative key (strip leading '/')
-    fn url_to_key(url_str: &str) -> Option<String> {
-        if url_str.trim().is_empty() {
-            return None;
-        }
-
-        match reqwest::Url::parse(url_str) {
-            Ok(u) => {
-                let path = u.path().trim_start_matches('/');
-                if path.is_empty() {
-                    None
-                } else {
-                    Some(path.to_string())
-                }
-            }
-            Err(e) => {
-                tracing::warn!(
-                    url = url_str,
-                    error = %e,
-                    "Failed to parse photograph S3 URL; skipping key"
-                );
-                None
-            }
-        }
-    }
-
-    let mut object_keys: Vec<String> = Vec::new();
-    for (link, thumb) in target_photographs {
-        if let Some(k) = url_to_key(&link) {
-            object_keys.push(k);
-        }
-        if let Some(k) = url_to_key(&thumb) {
-            object_keys.push(k);
-        }
-    }
-
-    let s3_deleted_count: usize = if object_keys.is_empty() {
-        0
-    } else {
-        let mut total_deleted = 0usize;
-
-        for chunk in object_keys.chunks(1000) {
-            let mut identifiers: Vec<ObjectIdentifier> = Vec::with_capacity(chunk.len());
-            for k in chunk {
-                match ObjectIdentifier::builder().key(k).build() {
-                    Ok(obj_id) => identifiers.push(obj_id),
-                    Err(e) => {
-                        tracing::error!(
-                            key = %k,
-                            error = %e,
-                            "Failed to build S3 ObjectIdentifier; skipping key"
-                        );
-                    }
-                }
-            }
-
-            let delete = match aws_sdk_s3::types::Delete::builder()
-                .set_objects(Some(identifiers))
-                .build()
-            {
-                Ok(d) => d,
-                Err(e) => {
-                    tracing::error!(
-                        error = %e,
-                        "Failed to build S3 Delete request; skipping batch"
-                    );
-                    continue;
-                }
-            };
-
-            let resp = s3_client
-                .delete_objects()
-                .bucket(&bucket)
-                .set_delete(Some(delete))
-                .send()
-                .await;
-
-            match resp {
-                Ok(output) => {
-                    total_deleted += output.deleted().len();
-                    for err in output.errors() {
-                        tracing::error!(
-                            key = ?err.key(),
-                            code = ?err.code(),
-                            message = ?err.message(),
-                            "Failed to delete S3 object for photograph"
-                        );
-                    }
-                }
-                Err(e) => {
-                    tracing::error!(
-                        error = %e,
-                        "S3 batch deletion for photographs failed"
-                    );
-                }
-            }
-        }
-
-        total_deleted
+    tracing::info!(deleted_db_rows = deleted_rows, "Soft-deleted photographs");
+    // A batch is one entry; it is targeted only when it names one photograph.
+    let target = match body.photograph_ids.as_slice() {
+        [single] => Some(single.to_string()),
+        _ => None,
     };
-
-    tracing::info!(
-        deleted_db_rows = deleted_rows,
-        s3_deleted_objects = s3_deleted_count,
-        "Completed batch deletion of photographs from DB and S3"
-    );
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::PhotographDelete,
+            target,
+            serde_json::json!({
+                "photograph_ids": body.photograph_ids,
+                "deleted_count": deleted_rows,
+            }),
+        )
+        .await;
 
     Ok(http_resp(
-        serde_json::json!({
-            "deleted_count": deleted_rows,
-            "s3_deleted_count": s3_deleted_count
-        }),
+        serde_json::json!({ "deleted_count": deleted_rows }),
         (),
         start,
     ))
@@ -0,0 +1,189 @@
+//! `GET /api/photographs/{photograph_id}/original` — download the
+//! full-resolution original. Owner or superuser only.
+//!
+//! Every successful download is recorded in `photograph_download_events`
+//! before anything is served; if the audit row cannot be written the download
+//! is refused.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    Extension,
+    body::Body,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, HeaderValue, Response, header},
+    response::{IntoResponse, Redirect},
+};
+use chrono::Utc;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        auth::role::RoleType,
+        photography::{
+            downloads::{DownloadMethod, PhotographDownloadEventInsertable, original_object_key},
+            photographs::{Photograph, PhotographProcessingStatus},
+        },
+    },
+    dto::requests::photography::download_photograph_original_request::{
+        DownloadPhotographOriginalRequest, OriginalDownloadMode,
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::{photograph_download_events, photographs},
+    util::extract::client_ip::extract_client_ip,
+};
+
+/// Lifetime of the presigned redirect target.
+const PRESIGNED_DOWNLOAD_TTL: Duration = Duration::from_secs(300);
+
+#[utoipa::path(
+    get,
+    path = "/api/photographs/{photograph_id}/original",
+    tag = "photography",
+    params(
+        ("photograph_id" = Uuid, Path, description = "Photograph id"),
+        DownloadPhotographOriginalRequest
+    ),
+    responses(
+        (status = 200, description = "Original file (stream mode)", content_type = "application/octet-stream"),
+        (status = 307, description = "Redirect to a presigned download URL"),
+        (status = 401, description = "Not the owner or a superuser", body = CodeErrorResp),
+        (status = 404, description = "Photograph or original not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn download_photograph_original(
+    Extension(requester_id): Extension<Uuid>,
+    Extension(role_type): Extension<RoleType>,
+    State(state): State<Arc<ServerState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(photograph_id): Path<Uuid>,
+    Query(request): Query<DownloadPhotographOriginalRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
+
+    let photograph: Photograph = photographs::table
+        .filter(photographs::photograph_id.eq(photograph_id))
+        .filter(photographs::photograph_processing_status.eq(PhotographProcessingStatus::Ready))
+        .filter(photographs::photograph_deleted_at.is_null())
+        .first::<Photograph>(&mut conn)
+        .await
+        .optional()
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .ok_or_else(|| code_err(CodeError::PHOTOGRAPH_NOT_FOUND, "Photograph not found"))?;
+
+    if photograph.user_id != requester_id && !role_type.is_superuser() {
+        warn!(
+            requester_id = %requester_id,
+            photograph_id = %photograph_id,
+            "Rejected original download by non-owner"
+        );
+        return Err(code_err(
+            CodeError::UNAUTHORIZED_ACCESS,
+            "User is not authorized to download this original",
+        ));
+    }
+
+    let object_key = original_object_key(&photograph, state.storage.as_ref()).ok_or_else(|| {
+        code_err(
+            CodeError::PHOTOGRAPH_NOT_FOUND,
+            "Photograph has no stored original",
+        )
+    })?;
+
+    // Resolve how the file will be delivered before auditing, so the event
+    // records what actually happened.
+    let presigned_url = match request.mode.unwrap_or(OriginalDownloadMode::Redirect) {
+        OriginalDownloadMode::Redirect => {
+            match state
+                .storage
+                .presign_get(&object_key, PRESIGNED_DOWNLOAD_TTL)
+                .await
+            {
+                Ok(url) => Some(url),
+                Err(e) => {
+                    warn!(error = ?e, key = %object_key, "Presign failed; streaming original instead");
+                    None
+                }
+            }
+        }
+        OriginalDownloadMode::Stream => None,
+    };
+    let method = match presigned_url {
+        Some(_) => DownloadMethod::Redirect,
+        None => DownloadMethod::Stream,
+    };
+
+    diesel::insert_into(photograph_download_events::table)
+        .values(PhotographDownloadEventInsertable {
+            photograph_download_event_id: Uuid::new_v4(),
+            photograph_id,
+            user_id: requester_id,
+            photograph_download_object_key: object_key.clone(),
+            photograph_download_method: method.as_str().to_string(),
+            photograph_download_ip: extract_client_ip(&headers, addr).map(ipnet::IpNet::from),
+            photograph_downloaded_at: Utc::now(),
+        })
+        .execute(&mut conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, photograph_id = %photograph_id, "Failed to record download event");
+            code_err(CodeError::DB_INSERTION_ERROR, e)
+        })?;
+
+    drop(conn);
+
+    info!(
+        requester_id = %requester_id,
+        photograph_id = %photograph_id,
+        key = %object_key,
+        method = method.as_str(),
+        "Serving photograph original"
+    );
+
+    if let Some(url) = presigned_url {
+        return Ok(Redirect::temporary(&url).into_response());
+    }
+
+    let object = state
+        .storage
+        .get(&object_key)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, key = %object_key, "Failed to fetch photograph original");
+            code_err(CodeError::STORAGE_READ_ERROR, e)
+        })?
+        .ok_or_else(|| {
+            code_err(
+                CodeError::PHOTOGRAPH_NOT_FOUND,
+                "Stored original is missing",
+            )
+        })?;
+
+    let file_name = object_key.rsplit('/').next().unwrap_or(&object_key);
+    let mut response = Response::new(Body::from(object.bytes));
+    let content_type = object
+        .content_type
+        .and_then(|ct| HeaderValue::from_str(&ct).ok())
+        .unwrap_or_else(|| HeaderValue::from_static("application/octet-stream"));
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, content_type);
+    if let Ok(disposition) = HeaderValue::from_str(&format!("attachment; filename=\"{file_name}\""))
+    {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-store"),
+    );
+
+    Ok(response.into_response())
+}
@@ -0,0 +1,132 @@
+//! `GET /api/admin/photographs/duplicates` — clusters of likely duplicate
+//! gallery photographs by perceptual hash distance.
+//!
+//! Clustering is done in memory over every hashed ready photograph
+//! (`O(n^2)` popcounts, cheap at gallery scale).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::{
+    domain::photography::{
+        duplicates::{MAX_DUPLICATE_THRESHOLD, cluster_hashes, phash_from_db},
+        photographs::{PhotographContext, PhotographProcessingStatus},
+    },
+    dto::{
+        requests::photography::photograph_duplicates_request::PhotographDuplicatesRequest,
+        responses::{
+            photography::photograph_duplicates_response::{
+                PhotographDuplicateCluster, PhotographDuplicateMember, PhotographDuplicatesResponse,
+            },
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::photographs,
+    util::{image::phash::hamming_distance, time::now::tokio_now},
+};
+
+type HashedPhotographRow = (Uuid, Option<i64>, String, String, DateTime<Utc>);
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/photographs/duplicates",
+    tag = "photography",
+    params(PhotographDuplicatesRequest),
+    responses(
+        (status = 200, description = "Likely duplicate clusters", body = PhotographDuplicatesResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_photograph_duplicates(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<PhotographDuplicatesRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let threshold = request
+        .threshold
+        .unwrap_or(state.duplicate_policy.threshold)
+        .min(MAX_DUPLICATE_THRESHOLD);
+
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
+
+    let rows: Vec<HashedPhotographRow> = photographs::table
+        .filter(photographs::photograph_context.eq(PhotographContext::Photography))
+        .filter(photographs::photograph_processing_status.eq(PhotographProcessingStatus::Ready))
+        .filter(photographs::photograph_deleted_at.is_null())
+        .filter(photographs::photograph_phash.is_not_null())
+        .order((
+            photographs::photograph_created_at.asc(),
+            photographs::photograph_id.asc(),
+        ))
+        .select((
+            photographs::photograph_id,
+            photographs::photograph_phash,
+            photographs::photograph_thumbnail_link,
+            photographs::photograph_comments,
+            photographs::photograph_created_at,
+        ))
+        .load(&mut conn)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+
+    drop(conn);
+
+    let hashes: Vec<u64> = rows
+        .iter()
+        .map(|(_, phash, _, _, _)| phash.map(phash_from_db).unwrap_or_default())
+        .collect();
+
+    let clusters = tokio::task::spawn_blocking({
+        let hashes = hashes.clone();
+        move || cluster_hashes(&hashes, threshold)
+    })
+    .await
+    .map_err(|e| code_err(CodeError::JOIN_ERROR, e))?;
+
+    // Members are indices into the created_at-ordered rows, so the first
+    // member of each cluster is its oldest photograph.
+    let clusters = clusters
+        .into_iter()
+        .map(|members| {
+            let anchor = hashes[members[0]];
+            PhotographDuplicateCluster {
+                photographs: members
+                    .into_iter()
+                    .map(|i| {
+                        let (photograph_id, _, thumbnail_link, comments, created_at) = &rows[i];
+                        PhotographDuplicateMember {
+                            photograph_id: *photograph_id,
+                            photograph_thumbnail_link: state.image_url(thumbnail_link),
+                            photograph_comments: comments.clone(),
+                            photograph_created_at: *created_at,
+                            distance: hamming_distance(anchor, hashes[i]),
+                        }
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(http_resp(
+        PhotographDuplicatesResponse {
+            threshold,
+            scanned: rows.len(),
+            clusters,
+        },
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,64 @@
+//! `GET /api/photographs/{photograph_id}/processing` — poll an asynchronously
+//! ingested photograph until it is `ready` or `failed`.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::{
+    domain::photography::photographs::Photograph,
+    dto::responses::{
+        photography::photograph_processing_response::PhotographProcessingResponse,
+        response_data::http_resp,
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::photographs,
+    util::time::now::tokio_now,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/photographs/{photograph_id}/processing",
+    tag = "photography",
+    params(("photograph_id" = Uuid, Path, description = "Photograph id")),
+    responses(
+        (status = 200, description = "Current processing state", body = PhotographProcessingResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Photograph not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_photograph_processing(
+    State(state): State<Arc<ServerState>>,
+    Path(photograph_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
+
+    let mut photograph: Photograph = photographs::table
+        .filter(photographs::photograph_id.eq(photograph_id))
+        .first::<Photograph>(&mut conn)
+        .await
+        .optional()
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .ok_or_else(|| code_err(CodeError::PHOTOGRAPH_NOT_FOUND, "Photograph not found"))?;
+
+    drop(conn);
+
+    state.deliver_photograph_links(&mut photograph);
+
+    Ok(http_resp(
+        PhotographProcessingResponse::new(&photograph),
+        (),
+        start,
+    ))
+}
@@ -0,0 +1,134 @@
+//! `GET /img/{photograph_id}?w=&h=&fmt=` — resize proxy over the published
+//! (watermarked, where applicable) photograph derivatives. Variants are
+//! rendered on first request and cached (see `util::image::variants`).
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{HeaderValue, Response, header},
+    response::IntoResponse,
+};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    domain::photography::photographs::{Photograph, PhotographProcessingStatus},
+    dto::requests::photography::photograph_variant_request::PhotographVariantRequest,
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::photographs,
+    util::image::{
+        process_uploaded_images::render_image_variant,
+        variants::{VariantSpec, variant_source_key},
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/img/{photograph_id}",
+    tag = "photography",
+    params(
+        ("photograph_id" = Uuid, Path, description = "Photograph id"),
+        PhotographVariantRequest
+    ),
+    responses(
+        (status = 200, description = "Resized image", content_type = "image/avif"),
+        (status = 400, description = "Invalid dimensions or format", body = CodeErrorResp),
+        (status = 404, description = "Photograph not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_photograph_variant(
+    State(state): State<Arc<ServerState>>,
+    Path(photograph_id): Path<Uuid>,
+    Query(request): Query<PhotographVariantRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let spec = VariantSpec::from_params(request.w, request.h, request.fmt.as_deref())
+        .map_err(|e| code_err(CodeError::INVALID_REQUEST, e))?;
+
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
+
+    let photograph: Photograph = photographs::table
+        .filter(photographs::photograph_id.eq(photograph_id))
+        .filter(photographs::photograph_processing_status.eq(PhotographProcessingStatus::Ready))
+        .filter(photographs::photograph_deleted_at.is_null())
+        .first::<Photograph>(&mut conn)
+        .await
+        .optional()
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+        .ok_or_else(|| code_err(CodeError::PHOTOGRAPH_NOT_FOUND, "Photograph not found"))?;
+
+    drop(conn);
+
+    let source_key =
+        variant_source_key(&photograph, &spec, state.storage.as_ref()).ok_or_else(|| {
+            code_err(
+                CodeError::PHOTOGRAPH_NOT_FOUND,
+                "Photograph has no stored image",
+            )
+        })?;
+    let cache_key = spec.cache_key(photograph_id, &source_key);
+    let cache = &state.image_variant_cache;
+
+    let bytes = match cache.get(state.storage.as_ref(), &cache_key).await {
+        Some(bytes) => bytes,
+        None => {
+            let _permit = cache.render_permit().await;
+            // Another request may have rendered it while we waited.
+            match cache.get(state.storage.as_ref(), &cache_key).await {
+                Some(bytes) => bytes,
+                None => {
+                    let source = state
+                        .storage
+                        .get(&source_key)
+                        .await
+                        .map_err(|e| {
+                            error!(error = ?e, key = %source_key, "Failed to fetch variant source");
+                            code_err(CodeError::STORAGE_READ_ERROR, e)
+                        })?
+                        .ok_or_else(|| {
+                            code_err(CodeError::PHOTOGRAPH_NOT_FOUND, "Stored image is missing")
+                        })?;
+
+                    let (max_width, max_height) = spec.render_bounds();
+                    let rendered = render_image_variant(
+                        source.bytes,
+                        max_width,
+                        max_height,
+                        spec.format.image_format(),
+                    )
+                    .await
+                    .map_err(|e| code_err(CodeError::COULD_NOT_PROCESS_IMAGE, e))?;
+
+                    cache
+                        .put(
+                            state.storage.as_ref(),
+                            &cache_key,
+                            rendered.clone(),
+                            spec.format.mime_type(),
+                        )
+                        .await;
+                    rendered
+                }
+            }
+        }
+    };
+
+    let mut response = Response::new(Body::from(bytes));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(spec.format.mime_type()),
+    );
+    // Same URL after a replace resolves to a new source, so no `immutable`.
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=86400"),
+    );
+
+    Ok(response.into_response())
+}
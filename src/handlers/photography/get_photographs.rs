@@ -5,32 +5,98 @@ use axum::{
     response::IntoResponse,
 };
 
-use diesel::{ExpressionMethods, QueryDsl};
+use chrono::{DateTime, Utc};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, QueryDsl, dsl::sql, pg::Pg, sql_types::Timestamptz,
+};
 
 use diesel_async::RunQueryDsl;
+use uuid::Uuid;
 
 use crate::{
-    domain::photography::photographs::{Photograph, PhotographContext},
-    dto::responses::photography::get_photograph_response::{
-        GetPhotographsResponse, PaginationMeta, PhotographItem,
+    domain::photography::{
+        photographs::{Photograph, PhotographContext, PhotographProcessingStatus},
+        tags::{PhotographTag, parse_tag_list},
     },
+    dto::responses::pagination::CursorPage,
+    dto::responses::photography::get_photograph_response::PhotographItem,
     dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::photographs::dsl::*,
-    util::time::now::tokio_now,
+    util::{db::keyset::Keyset, time::now::tokio_now},
 };
 
+/// Sort key for the listing. `ShotAt` falls back to the upload time for
+/// photographs without an EXIF shot date, so the key is never NULL and keyset
+/// cursors stay well-defined.
+#[derive(Clone, Copy)]
+enum PhotographSort {
+    ShotAt,
+    Uploaded,
+}
+
+impl PhotographSort {
+    fn from_param(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "shot_at" | "shot" | "taken" => Some(Self::ShotAt),
+            "uploaded" | "created_at" | "upload" => Some(Self::Uploaded),
+            _ => None,
+        }
+    }
+
+    fn key_sql(self) -> &'static str {
+        match self {
+            Self::ShotAt => {
+                "COALESCE(photographs.photograph_shot_at, photographs.photograph_created_at)"
+            }
+            Self::Uploaded => "photographs.photograph_created_at",
+        }
+    }
+
+    fn key_of(self, p: &Photograph) -> DateTime<Utc> {
+        match self {
+            Self::ShotAt => p.photograph_shot_at.unwrap_or(p.photograph_created_at),
+            Self::Uploaded => p.photograph_created_at,
+        }
+    }
+}
+
+fn parse_datetime_param(
+    params: &HashMap<String, String>,
+    name: &str,
+) -> Result<Option<DateTime<Utc>>, CodeErrorResp> {
+    match params.get(name) {
+        None => Ok(None),
+        Some(raw) => DateTime::parse_from_rfc3339(raw)
+            .map(|dt| Some(dt.with_timezone(&Utc)))
+            .map_err(|e| {
+                code_err(
+                    CodeError::INVALID_REQUEST,
+                    format!("`{name}` must be an RFC 3339 timestamp: {e}"),
+                )
+            }),
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/api/photographs/get",
     tag = "photography",
     params(
-        ("page" = Option<i64>, Query, description = "Page number (default: 1)"),
-        ("page_size" = Option<i64>, Query, description = "Items per page (default: 20, max: 100)")
+        ("page" = Option<i64>, Query, description = "Page number (default: 1); ignored when `cursor` is set"),
+        ("page_size" = Option<i64>, Query, description = "Items per page (default: 20, max: 100); `limit` is an alias"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` from a previous response, for keyset pagination"),
+        ("sort" = Option<String>, Query, description = "`shot_at` (default) or `uploaded`"),
+        ("order" = Option<String>, Query, description = "`desc` (default) or `asc`"),
+        ("from" = Option<String>, Query, description = "RFC 3339 lower bound (inclusive) on the sort key"),
+        ("to" = Option<String>, Query, description = "RFC 3339 upper bound (exclusive) on the sort key"),
+        ("user_id" = Option<Uuid>, Query, description = "Only photographs uploaded by this user"),
+        ("tags" = Option<String>, Query, description = "Comma-separated tags; only photographs carrying all of them are returned")
     ),
     responses(
-        (status = 200, description = "Successfully retrieved photographs", body = GetPhotographsResponse),
+        (status = 200, description = "Successfully retrieved photographs", body = CursorPage<PhotographItem>),
+        (status = 400, description = "Invalid filter or cursor", body = CodeErrorResp),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -50,73 +116,141 @@ pub async fn get_photographs(
 
     let page_size: i64 = params
         .get("page_size")
+        .or_else(|| params.get("limit"))
         .and_then(|s| s.parse::<i64>().ok())
         .filter(|s| *s > 0 && *s <= 100)
         .unwrap_or(20);
 
+    let sort = params
+        .get("sort")
+        .and_then(|s| PhotographSort::from_param(s))
+        .unwrap_or(PhotographSort::ShotAt);
+
+    let ascending = params
+        .get("order")
+        .is_some_and(|s| s.eq_ignore_ascii_case("asc"));
+
+    // The sort key need not be the upload time, so the keyset predicate is
+    // built below rather than with `KeysetPaginate`; only the cursor is shared.
+    let cursor: Option<Keyset<Uuid>> = match params.get("cursor") {
+        None => None,
+        Some(raw) => Some(
+            Keyset::from_cursor(raw)
+                .ok_or_else(|| code_err(CodeError::INVALID_REQUEST, "Malformed cursor"))?,
+        ),
+    };
+
+    let from_filter = parse_datetime_param(&params, "from")?;
+    let to_filter = parse_datetime_param(&params, "to")?;
+
+    let uploader_filter: Option<Uuid> =
+        match params.get("user_id") {
+            None => None,
+            Some(raw) => Some(Uuid::parse_str(raw).map_err(|e| {
+                code_err(CodeError::INVALID_REQUEST, format!("Invalid user_id: {e}"))
+            })?),
+        };
+
     let offset_val = (page - 1) * page_size;
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_read_conn().await.map_err(pool_err)?;
+
+    // Resolve the tag filter to a photograph id set up front so the count and
+    // page queries share it.
+    let tag_filter: Vec<String> = params
+        .get("tags")
+        .map(|raw| parse_tag_list(raw))
+        .unwrap_or_default();
+
+    let tagged_ids: Option<Vec<Uuid>> = if tag_filter.is_empty() {
+        None
+    } else {
+        Some(
+            PhotographTag::photograph_ids_with_all(&mut conn, &tag_filter)
+                .await
+                .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?,
+        )
+    };
+
+    let sort_key = || sql::<Timestamptz>(sort.key_sql());
+
+    // Filters shared by the count and page queries (everything but the cursor).
+    let base_query = || -> crate::schema::photographs::BoxedQuery<'static, Pg> {
+        let mut query = photographs
+            .filter(photograph_context.eq(PhotographContext::Photography))
+            .filter(photograph_processing_status.eq(PhotographProcessingStatus::Ready))
+            .filter(photograph_deleted_at.is_null())
+            .into_boxed();
+        if let Some(ids) = &tagged_ids {
+            query = query.filter(photograph_id.eq_any(ids.clone()));
+        }
+        if let Some(uploader) = uploader_filter {
+            query = query.filter(user_id.eq(uploader));
+        }
+        if let Some(from) = from_filter {
+            query = query.filter(sort_key().ge(from));
+        }
+        if let Some(to) = to_filter {
+            query = query.filter(sort_key().lt(to));
+        }
+        query
+    };
 
     // Get total count for pagination metadata
-    let total_items: i64 = photographs
-        .filter(photograph_context.eq(PhotographContext::Photography))
+    let total_items: i64 = base_query()
         .count()
         .get_result::<i64>(&mut conn)
         .await
         .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
 
-    // Fetch a single page of photographs ordered by most recently shot
-    let results: Result<Vec<Photograph>, diesel::result::Error> = photographs
-        .filter(photograph_context.eq(PhotographContext::Photography))
-        .order((photograph_shot_at.desc(), photograph_id.desc()))
-        .offset(offset_val)
-        .limit(page_size)
+    let mut page_query = base_query();
+    page_query = if ascending {
+        page_query.order((sort_key().asc(), photograph_id.asc()))
+    } else {
+        page_query.order((sort_key().desc(), photograph_id.desc()))
+    };
+    page_query = match cursor {
+        // Keyset pagination: strictly after the cursor in sort order.
+        Some(Keyset { at, id: after_id }) if ascending => page_query.filter(
+            sort_key()
+                .gt(at)
+                .or(sort_key().eq(at).and(photograph_id.gt(after_id))),
+        ),
+        Some(Keyset { at, id: after_id }) => page_query.filter(
+            sort_key()
+                .lt(at)
+                .or(sort_key().eq(at).and(photograph_id.lt(after_id))),
+        ),
+        None => page_query.offset(offset_val),
+    };
+
+    // Fetch one extra row to learn whether another page follows.
+    let results: Result<Vec<Photograph>, diesel::result::Error> = page_query
+        .limit(page_size + 1)
         .load::<Photograph>(&mut conn)
         .await;
 
-    let photographs_vec = results.map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    let listing = CursorPage::from_overfetched(
+        results.map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?,
+        page_size as usize,
+        |p| Keyset::new(sort.key_of(p), p.photograph_id).to_string(),
+    )
+    .with_total_items(total_items as usize);
 
-    let total_pages = if total_items == 0 {
-        0
-    } else {
-        ((total_items + page_size - 1) / page_size).max(1)
-    };
+    let page_ids: Vec<Uuid> = listing.items.iter().map(|p| p.photograph_id).collect();
+    let mut tags_by_photograph = PhotographTag::names_for_photographs(&mut conn, &page_ids)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
 
-    let pagination = PaginationMeta {
-        page,
-        page_size,
-        total_items,
-        total_pages,
-        has_next: page < total_pages,
-        has_prev: page > 1 && total_pages > 0,
-    };
+    drop(conn);
 
-    let items: Vec<PhotographItem> = photographs_vec
-        .into_iter()
-        .map(|p| PhotographItem {
-            photograph_id: p.photograph_id,
-            user_id: p.user_id,
-            photograph_shot_at: p.photograph_shot_at,
-            photograph_created_at: p.photograph_created_at,
-            photograph_updated_at: p.photograph_updated_at,
-            photograph_image_type: p.photograph_image_type,
-            photograph_is_on_cloud: p.photograph_is_on_cloud,
-            photograph_link: p.photograph_link,
-            photograph_comments: p.photograph_comments,
-            photograph_lat: p.photograph_lat,
-            photograph_lon: p.photograph_lon,
-            photograph_thumbnail_link: p.photograph_thumbnail_link,
-            photograph_view_count: p.photograph_view_count,
-            photograph_total_upvotes: p.photograph_total_upvotes,
-            photograph_total_downvotes: p.photograph_total_downvotes,
-        })
-        .collect();
-
-    let response = GetPhotographsResponse { items, pagination };
+    let response: CursorPage<PhotographItem> = listing.map(|mut p| {
+        state.deliver_photograph_links(&mut p);
+        let tags = tags_by_photograph
+            .remove(&p.photograph_id)
+            .unwrap_or_default();
+        PhotographItem::new(p, tags)
+    });
 
     Ok(http_resp(response, (), start))
 }
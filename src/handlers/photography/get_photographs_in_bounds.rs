@@ -0,0 +1,269 @@
+//! `GET /api/photographs/in-bounds` — photographs inside a map viewport.
+//!
+//! Sparse viewports return individual pins; once the viewport holds more than
+//! `MAX_UNCLUSTERED_POINTS` photographs the response switches to grid clusters
+//! computed in SQL, so a zoomed-out world map never ships every row.
+//! Range scans are backed by `idx_photographs_lat_lon`.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, QueryDsl, QueryableByName, pg::Pg, sql_query,
+    sql_types,
+};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::{
+    domain::photography::photographs::{PhotographContext, PhotographProcessingStatus},
+    dto::{
+        requests::photography::photographs_in_bounds_request::PhotographsInBoundsRequest,
+        responses::{
+            photography::photographs_in_bounds_response::{
+                PhotographMapCluster, PhotographMapPoint, PhotographsInBoundsResponse,
+            },
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::photographs,
+    util::time::now::tokio_now,
+};
+
+const MAX_UNCLUSTERED_POINTS: i64 = 300;
+const DEFAULT_CLUSTER_GRID: u32 = 16;
+const MAX_CLUSTER_GRID: u32 = 64;
+
+/// (id, lat, lon, thumbnail link, shot at) of an unclustered point.
+type PointRow = (Uuid, f64, f64, String, Option<DateTime<Utc>>);
+
+#[derive(QueryableByName)]
+struct ClusterRow {
+    #[diesel(sql_type = sql_types::BigInt)]
+    photograph_count: i64,
+    #[diesel(sql_type = sql_types::Double)]
+    center_lat: f64,
+    #[diesel(sql_type = sql_types::Double)]
+    center_lon: f64,
+    #[diesel(sql_type = sql_types::Double)]
+    min_lat: f64,
+    #[diesel(sql_type = sql_types::Double)]
+    min_lon: f64,
+    #[diesel(sql_type = sql_types::Double)]
+    max_lat: f64,
+    #[diesel(sql_type = sql_types::Double)]
+    max_lon: f64,
+    #[diesel(sql_type = sql_types::Uuid)]
+    sample_photograph_id: Uuid,
+    #[diesel(sql_type = sql_types::Text)]
+    sample_thumbnail_link: String,
+}
+
+/// Map a longitude that was shifted by +360 (antimeridian viewports) back into
+/// `[-180, 180]`.
+fn unwrap_lon(lon: f64) -> f64 {
+    if lon > 180.0 { lon - 360.0 } else { lon }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/photographs/in-bounds",
+    tag = "photography",
+    params(PhotographsInBoundsRequest),
+    responses(
+        (status = 200, description = "Photographs or clusters inside the viewport", body = PhotographsInBoundsResponse),
+        (status = 400, description = "Invalid bounds", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_photographs_in_bounds(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<PhotographsInBoundsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let PhotographsInBoundsRequest {
+        min_lat,
+        min_lon,
+        max_lat,
+        max_lon,
+        grid,
+    } = request;
+
+    let lat_ok = |v: f64| (-90.0..=90.0).contains(&v);
+    let lon_ok = |v: f64| (-180.0..=180.0).contains(&v);
+    if !(lat_ok(min_lat) && lat_ok(max_lat) && lon_ok(min_lon) && lon_ok(max_lon))
+        || min_lat >= max_lat
+        || min_lon == max_lon
+    {
+        return Err(code_err(
+            CodeError::INVALID_REQUEST,
+            "Bounds must be valid coordinates with min < max latitude",
+        ));
+    }
+
+    let crosses_antimeridian = min_lon > max_lon;
+
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
+
+    let in_bounds = || -> photographs::BoxedQuery<'static, Pg> {
+        let query = photographs::table
+            .filter(photographs::photograph_context.eq(PhotographContext::Photography))
+            .filter(photographs::photograph_lat.between(min_lat, max_lat))
+            .filter(photographs::photograph_processing_status.eq(PhotographProcessingStatus::Ready))
+            .filter(photographs::photograph_deleted_at.is_null())
+            .into_boxed();
+        if crosses_antimeridian {
+            query.filter(
+                photographs::photograph_lon
+                    .ge(min_lon)
+                    .or(photographs::photograph_lon.le(max_lon)),
+            )
+        } else {
+            query.filter(photographs::photograph_lon.between(min_lon, max_lon))
+        }
+    };
+
+    let total: i64 = in_bounds()
+        .count()
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+
+    if total <= MAX_UNCLUSTERED_POINTS {
+        let rows: Vec<PointRow> = in_bounds()
+            .order((
+                photographs::photograph_shot_at.desc(),
+                photographs::photograph_id.desc(),
+            ))
+            .select((
+                photographs::photograph_id,
+                photographs::photograph_lat,
+                photographs::photograph_lon,
+                photographs::photograph_thumbnail_link,
+                photographs::photograph_shot_at,
+            ))
+            .load(&mut conn)
+            .await
+            .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+
+        drop(conn);
+
+        let points = rows
+            .into_iter()
+            .map(
+                |(photograph_id, photograph_lat, photograph_lon, thumbnail_link, shot_at)| {
+                    PhotographMapPoint {
+                        photograph_id,
+                        photograph_lat,
+                        photograph_lon,
+                        photograph_thumbnail_link: state.image_url(&thumbnail_link),
+                        photograph_shot_at: shot_at,
+                    }
+                },
+            )
+            .collect();
+
+        return Ok(http_resp(
+            PhotographsInBoundsResponse {
+                total,
+                clustered: false,
+                points,
+                clusters: Vec::new(),
+            },
+            (),
+            start,
+        ));
+    }
+
+    let grid = grid
+        .unwrap_or(DEFAULT_CLUSTER_GRID)
+        .clamp(1, MAX_CLUSTER_GRID);
+    let lon_span = if crosses_antimeridian {
+        max_lon + 360.0 - min_lon
+    } else {
+        max_lon - min_lon
+    };
+    let lat_cell = (max_lat - min_lat) / f64::from(grid);
+    let lon_cell = lon_span / f64::from(grid);
+
+    // `lon_n` shifts the western half of an antimeridian viewport by +360 so
+    // the grid is contiguous; for ordinary viewports it equals the longitude.
+    let clusters: Vec<ClusterRow> = sql_query(
+        "SELECT \
+            COUNT(*)::int8 AS photograph_count, \
+            AVG(photograph_lat) AS center_lat, \
+            AVG(lon_n) AS center_lon, \
+            MIN(photograph_lat) AS min_lat, \
+            MIN(lon_n) AS min_lon, \
+            MAX(photograph_lat) AS max_lat, \
+            MAX(lon_n) AS max_lon, \
+            (ARRAY_AGG(photograph_id ORDER BY photograph_shot_at DESC NULLS LAST))[1] \
+                AS sample_photograph_id, \
+            (ARRAY_AGG(photograph_thumbnail_link ORDER BY photograph_shot_at DESC NULLS LAST))[1] \
+                AS sample_thumbnail_link \
+         FROM ( \
+            SELECT photograph_id, photograph_lat, photograph_thumbnail_link, photograph_shot_at, \
+                CASE WHEN photograph_lon < $2 THEN photograph_lon + 360 ELSE photograph_lon END \
+                    AS lon_n \
+            FROM photographs \
+            WHERE photograph_context = 'photography' \
+              AND photograph_processing_status = 'ready' \
+              AND photograph_deleted_at IS NULL \
+              AND photograph_lat BETWEEN $1 AND $3 \
+              AND ( \
+                ($5 AND (photograph_lon >= $2 OR photograph_lon <= $4)) \
+                OR (NOT $5 AND photograph_lon BETWEEN $2 AND $4) \
+              ) \
+         ) p \
+         GROUP BY \
+            LEAST(FLOOR((photograph_lat - $1) / $6), $8 - 1), \
+            LEAST(FLOOR((lon_n - $2) / $7), $8 - 1) \
+         ORDER BY photograph_count DESC",
+    )
+    .bind::<sql_types::Double, _>(min_lat)
+    .bind::<sql_types::Double, _>(min_lon)
+    .bind::<sql_types::Double, _>(max_lat)
+    .bind::<sql_types::Double, _>(max_lon)
+    .bind::<sql_types::Bool, _>(crosses_antimeridian)
+    .bind::<sql_types::Double, _>(lat_cell)
+    .bind::<sql_types::Double, _>(lon_cell)
+    .bind::<sql_types::Double, _>(f64::from(grid))
+    .load(&mut conn)
+    .await
+    .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+
+    drop(conn);
+
+    let clusters = clusters
+        .into_iter()
+        .map(|c| PhotographMapCluster {
+            photograph_count: c.photograph_count,
+            center_lat: c.center_lat,
+            center_lon: unwrap_lon(c.center_lon),
+            min_lat: c.min_lat,
+            min_lon: unwrap_lon(c.min_lon),
+            max_lat: c.max_lat,
+            max_lon: unwrap_lon(c.max_lon),
+            sample_photograph_id: c.sample_photograph_id,
+            sample_thumbnail_link: state.image_url(&c.sample_thumbnail_link),
+        })
+        .collect();
+
+    Ok(http_resp(
+        PhotographsInBoundsResponse {
+            total,
+            clustered: true,
+            points: Vec::new(),
+            clusters,
+        },
+        (),
+        start,
+    ))
+}
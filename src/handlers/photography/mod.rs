@@ -1,12 +1,20 @@
 pub mod batch_list;
 pub mod batch_status;
 pub mod batch_upload;
+pub mod confirm_photograph_upload;
 pub mod delete_photograph_comment;
 pub mod delete_photographs;
+pub mod download_photograph_original;
+pub mod get_photograph_duplicates;
+pub mod get_photograph_processing;
+pub mod get_photograph_variant;
 pub mod get_photographs;
+pub mod get_photographs_in_bounds;
+pub mod presign_photograph_upload;
 pub mod read_photograph;
 pub mod rescind_photograph_comment_vote;
 pub mod rescind_photograph_vote;
+pub mod set_photograph_tags;
 pub mod submit_photograph_comment;
 pub mod update_photograph_comment;
 pub mod upload_photograph;
@@ -0,0 +1,139 @@
+//! `POST /api/photographs/presign` — issue a presigned storage `PUT` for an
+//! original (S3 backend only).
+//!
+//! Large originals then bypass the server entirely on the way in; the server
+//! only downloads the staged object once, in the confirm step, to encode it.
+
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::State, response::IntoResponse};
+use chrono::Utc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::photography::presigned_upload::{
+        PRESIGNED_UPLOAD_PREFIX, PRESIGNED_UPLOAD_TTL_SECONDS, PresignedUpload,
+    },
+    dto::{
+        requests::photography::presign_photograph_upload_request::PresignPhotographUploadRequest,
+        responses::{
+            photography::presign_photograph_upload_response::PresignPhotographUploadResponse,
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    handlers::photography::upload_photograph::{
+        ALLOWED_MIME_TYPES, MAX_SIZE_OF_UPLOADABLE_PHOTOGRPAH,
+    },
+    init::state::ServerState,
+    util::{image::raw_decode::RawFormat, time::now::tokio_now},
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/photographs/presign",
+    tag = "photography",
+    request_body = PresignPhotographUploadRequest,
+    responses(
+        (status = 200, description = "Presigned upload issued", body = PresignPhotographUploadResponse),
+        (status = 400, description = "Invalid upload request", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn presign_photograph_upload(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<PresignPhotographUploadRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let is_raw =
+        RawFormat::detect(Some(&request.content_type), request.file_name.as_deref()).is_some();
+    if !is_raw && !ALLOWED_MIME_TYPES.contains(&request.content_type.as_str()) {
+        warn!(
+            user_id = %user_id,
+            mime = %request.content_type,
+            "Unsupported image type; rejecting presign request"
+        );
+        return Err(code_err(
+            CodeError::FILE_UPLOAD_ERROR,
+            "Unsupported image type; no PSDs!",
+        ));
+    }
+
+    if request.content_length == 0 {
+        return Err(code_err(CodeError::FILE_UPLOAD_ERROR, "File is empty!"));
+    }
+
+    if request.content_length > MAX_SIZE_OF_UPLOADABLE_PHOTOGRPAH as u64 {
+        warn!(
+            user_id = %user_id,
+            content_length = request.content_length,
+            limit_bytes = MAX_SIZE_OF_UPLOADABLE_PHOTOGRPAH,
+            "Presign request exceeds maximum allowed size"
+        );
+        return Err(code_err(
+            CodeError::FILE_UPLOAD_ERROR,
+            "Uploaded file exceeds maximum allowed size",
+        ));
+    }
+
+    let upload_token = Uuid::new_v4();
+    let object_key = format!("{PRESIGNED_UPLOAD_PREFIX}{user_id}/{upload_token}.orig");
+    let now = Utc::now();
+    let expires_at = now + chrono::Duration::seconds(PRESIGNED_UPLOAD_TTL_SECONDS);
+
+    let upload_url = state
+        .storage
+        .presign_put(
+            &object_key,
+            &request.content_type,
+            std::time::Duration::from_secs(PRESIGNED_UPLOAD_TTL_SECONDS as u64),
+        )
+        .await
+        .map_err(|e| {
+            error!(
+                error = ?e,
+                user_id = %user_id,
+                backend = state.storage.name(),
+                key = %object_key,
+                "Failed to presign photograph upload"
+            );
+            code_err(CodeError::FILE_UPLOAD_ERROR, e)
+        })?;
+
+    state
+        .register_presigned_upload(PresignedUpload {
+            upload_token,
+            owner: user_id,
+            object_key: object_key.clone(),
+            content_type: request.content_type.clone(),
+            file_name: request.file_name,
+            created_at: now,
+            expires_at,
+        })
+        .await;
+
+    info!(
+        user_id = %user_id,
+        upload_token = %upload_token,
+        key = %object_key,
+        content_length = request.content_length,
+        "Issued presigned photograph upload"
+    );
+
+    Ok(http_resp(
+        PresignPhotographUploadResponse {
+            upload_token,
+            upload_url,
+            upload_method: "PUT",
+            content_type: request.content_type,
+            expires_at,
+        },
+        (),
+        start,
+    ))
+}
@@ -21,14 +21,15 @@ use crate::{
     domain::{
         blog::blog::{UserBadgeInfo, VoteState},
         photography::{
-            photographs::Photograph,
+            photographs::{Photograph, PhotographProcessingStatus, renditions_srcset},
             social::{PhotographComment, PhotographCommentResponse},
+            tags::PhotographTag,
         },
     },
     dto::responses::{
         photography::read_photograph_response::ReadPhotographResponse, response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     routers::middleware::is_logged_in::AuthStatus,
     schema::{
@@ -56,15 +57,16 @@ pub async fn read_photograph(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     // Read the persisted row; the view itself is buffered in RAM and flushed to
     // the DB by a periodic job, so the hot read path does no per-view write.
     let mut photograph: Photograph = photographs::table
         .filter(photographs::photograph_id.eq(photograph_id))
+        // Rows still being processed have no links yet; they only surface
+        // through the processing status endpoint.
+        .filter(photographs::photograph_processing_status.eq(PhotographProcessingStatus::Ready))
+        .filter(photographs::photograph_deleted_at.is_null())
         .select(photographs::all_columns)
         .first::<Photograph>(&mut conn)
         .await
@@ -174,6 +176,13 @@ pub async fn read_photograph(
         VoteState::DidNotVote
     };
 
+    let photograph_tags: Vec<String> =
+        PhotographTag::names_for_photographs(&mut conn, &[photograph_id])
+            .await
+            .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+            .remove(&photograph_id)
+            .unwrap_or_default();
+
     drop(conn);
 
     let country_map = state.country_map.read().await;
@@ -207,9 +216,13 @@ pub async fn read_photograph(
     let author_badge = badge_for(&photograph.user_id);
     drop(country_map);
 
+    state.deliver_photograph_links(&mut photograph);
+
     Ok(http_resp(
         ReadPhotographResponse {
+            photograph_srcset: renditions_srcset(&photograph.renditions()),
             photograph,
+            photograph_tags,
             vote_state: photograph_vote_state,
             comments: comment_responses,
             user_badge_info: author_badge,
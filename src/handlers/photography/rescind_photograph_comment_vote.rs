@@ -15,7 +15,7 @@ use uuid::Uuid;
 use crate::{
     domain::photography::social::VoteCounts,
     dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::{photograph_comment_votes, photograph_comments},
     util::time::now::tokio_now,
@@ -42,10 +42,7 @@ pub async fn rescind_photograph_comment_vote(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     match conn
         .transaction::<_, diesel::result::Error, _>(async |conn| {
@@ -14,7 +14,7 @@ use uuid::Uuid;
 use crate::{
     domain::photography::social::VoteCounts,
     dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::{photograph_votes, photographs},
     util::time::now::tokio_now,
@@ -38,10 +38,7 @@ pub async fn rescind_photograph_vote(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     match conn
         .transaction::<_, diesel::result::Error, _>(async |conn| {
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        audit::audit::AdminAction,
+        photography::tags::{PhotographTag, normalize_tags},
+    },
+    dto::{
+        requests::photography::set_photograph_tags_request::SetPhotographTagsRequest,
+        responses::{
+            photography::set_photograph_tags_response::SetPhotographTagsResponse,
+            response_data::http_resp,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::photographs,
+    util::{extract::AdminActor, time::now::tokio_now},
+};
+
+#[utoipa::path(
+    put,
+    path = "/api/photographs/{photograph_id}/tags",
+    tag = "photography",
+    params(("photograph_id" = Uuid, Path, description = "Photograph id")),
+    request_body = SetPhotographTagsRequest,
+    responses(
+        (status = 200, description = "Photograph tags replaced", body = SetPhotographTagsResponse),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "Photograph not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn set_photograph_tags(
+    Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
+    State(state): State<Arc<ServerState>>,
+    Path(photograph_id): Path<Uuid>,
+    Json(request): Json<SetPhotographTagsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let photograph_tags = normalize_tags(&request.photograph_tags);
+
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
+
+    conn.transaction::<_, diesel::result::Error, _>(async |conn| {
+        // Touching the row doubles as the existence check.
+        let updated =
+            diesel::update(photographs::table.filter(photographs::photograph_id.eq(photograph_id)))
+                .set(photographs::photograph_updated_at.eq(Utc::now()))
+                .execute(&mut *conn)
+                .await?;
+
+        if updated == 0 {
+            return Err(diesel::result::Error::NotFound);
+        }
+
+        PhotographTag::replace_for_photograph(&mut *conn, photograph_id, &photograph_tags).await
+    })
+    .await
+    .map_err(|e| match e {
+        diesel::result::Error::NotFound => {
+            code_err(CodeError::PHOTOGRAPH_NOT_FOUND, "Photograph not found")
+        }
+        e => {
+            error!(error = ?e, photograph_id = %photograph_id, "Failed to replace photograph tags");
+            code_err(CodeError::DB_UPDATE_ERROR, e)
+        }
+    })?;
+
+    drop(conn);
+
+    info!(
+        photograph_id = %photograph_id,
+        user_id = %user_id,
+        tag_count = photograph_tags.len(),
+        "Photograph tags replaced"
+    );
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::PhotographTagsSet,
+            Some(photograph_id.to_string()),
+            serde_json::json!({ "tags": photograph_tags }),
+        )
+        .await;
+
+    Ok(http_resp(
+        SetPhotographTagsResponse {
+            photograph_id,
+            photograph_tags,
+        },
+        (),
+        start,
+    ))
+}
@@ -15,15 +15,16 @@ use uuid::Uuid;
 use crate::{
     domain::{
         blog::blog::{UserBadgeInfo, VoteState},
+        notification::notification::{CommentLocation, PostedComment},
         photography::social::{NewPhotographComment, PhotographComment, PhotographCommentResponse},
     },
     dto::{
         requests::photography::submit_photograph_comment_request::SubmitPhotographCommentRequest,
         responses::response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
-    schema::{photograph_comments, user_profile_pictures, users},
+    schema::{photograph_comments, photographs, user_profile_pictures, users},
     util::time::now::tokio_now,
 };
 
@@ -36,6 +37,7 @@ use crate::{
     responses(
         (status = 200, description = "Comment created", body = PhotographCommentResponse),
         (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 404, description = "Photograph not found", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
@@ -54,10 +56,23 @@ pub async fn submit_photograph_comment(
         ));
     }
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
+
+    // The row outlives a soft delete, so the foreign key alone would accept it.
+    let photograph_exists: bool = diesel::dsl::select(diesel::dsl::exists(
+        photographs::table
+            .filter(photographs::photograph_id.eq(photograph_id))
+            .filter(photographs::photograph_deleted_at.is_null()),
+    ))
+    .get_result(&mut conn)
+    .await
+    .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?;
+    if !photograph_exists {
+        return Err(code_err(
+            CodeError::PHOTOGRAPH_NOT_FOUND,
+            "Photograph not found",
+        ));
+    }
 
     let new_comment = NewPhotographComment {
         photograph_id: &photograph_id,
@@ -95,6 +110,17 @@ pub async fn submit_photograph_comment(
 
     drop(conn);
 
+    state.notify_comment_audience(PostedComment {
+        location: CommentLocation::Photograph {
+            photograph_id: inserted.photograph_id,
+        },
+        comment_id: inserted.photograph_comment_id,
+        parent_comment_id: inserted.parent_photograph_comment_id,
+        author_id: user_id,
+        author_name: user_name.clone(),
+        content: inserted.photograph_comment_content.clone(),
+    });
+
     let country_map = state.country_map.read().await;
     let user_country_flag = country_map.get_flag_by_code(user_country);
     drop(country_map);
@@ -22,7 +22,7 @@ use crate::{
         requests::photography::update_photograph_comment_request::UpdatePhotographCommentRequest,
         responses::response_data::http_resp,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::{photograph_comment_votes, photograph_comments, user_profile_pictures, users},
     util::time::now::tokio_now,
@@ -61,10 +61,7 @@ pub async fn update_photograph_comment(
 
     let is_superuser = role_type.is_superuser();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let author_id: Uuid = photograph_comments::table
         .select(photograph_comments::user_id)
@@ -15,14 +15,14 @@ use diesel_async::{AsyncConnection, RunQueryDsl};
 use uuid::Uuid;
 
 use crate::{
-    domain::photography::social::VoteCounts,
+    domain::{notification::notification::CommentLocation, photography::social::VoteCounts},
     dto::{
         requests::photography::vote_photograph_request::VotePhotographRequest,
         responses::{
             photography::vote_photograph_response::VotePhotographResponse, response_data::http_resp,
         },
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::photographs,
     util::time::now::tokio_now,
@@ -37,6 +37,7 @@ use crate::{
     responses(
         (status = 200, description = "Vote recorded", body = VotePhotographResponse),
         (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 404, description = "Photograph not found", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
@@ -48,10 +49,7 @@ pub async fn vote_photograph(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let counts: VoteCounts = match conn
         .transaction::<_, diesel::result::Error, _>(async |conn| {
@@ -80,13 +78,22 @@ pub async fn vote_photograph(
             .get_result(&mut *conn)
             .await?;
 
-            diesel::update(photographs::table.filter(photographs::photograph_id.eq(photograph_id)))
-                .set((
-                    photographs::photograph_total_upvotes.eq(counts.upvote_count),
-                    photographs::photograph_total_downvotes.eq(counts.downvote_count),
-                ))
-                .execute(&mut *conn)
-                .await?;
+            // A soft-deleted photograph matches no row; the `NotFound` rolls
+            // the vote back.
+            let updated = diesel::update(
+                photographs::table
+                    .filter(photographs::photograph_id.eq(photograph_id))
+                    .filter(photographs::photograph_deleted_at.is_null()),
+            )
+            .set((
+                photographs::photograph_total_upvotes.eq(counts.upvote_count),
+                photographs::photograph_total_downvotes.eq(counts.downvote_count),
+            ))
+            .execute(&mut *conn)
+            .await?;
+            if updated == 0 {
+                return Err(diesel::result::Error::NotFound);
+            }
 
             Ok(counts)
         })
@@ -98,10 +105,20 @@ pub async fn vote_photograph(
                 diesel::result::DatabaseErrorKind::UniqueViolation,
                 _,
             ) => return Err(CodeError::UPVOTE_MUST_BE_UNIQUE.into()),
+            diesel::result::Error::NotFound => {
+                return Err(code_err(
+                    CodeError::PHOTOGRAPH_NOT_FOUND,
+                    "Photograph not found",
+                ));
+            }
             e => return Err(code_err(CodeError::DB_INSERTION_ERROR, e)),
         },
     };
 
+    if request.is_upvote {
+        state.notify_upvote(user_id, CommentLocation::Photograph { photograph_id }, None);
+    }
+
     Ok(http_resp(
         VotePhotographResponse {
             upvote_count: counts.upvote_count,
@@ -12,14 +12,14 @@ use diesel_async::{AsyncConnection, RunQueryDsl};
 use uuid::Uuid;
 
 use crate::{
-    domain::photography::social::VoteCounts,
+    domain::{notification::notification::CommentLocation, photography::social::VoteCounts},
     dto::{
         requests::photography::vote_photograph_request::VotePhotographRequest,
         responses::{
             photography::vote_photograph_response::VotePhotographResponse, response_data::http_resp,
         },
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::photograph_comments,
     util::time::now::tokio_now,
@@ -43,15 +43,12 @@ use crate::{
 pub async fn vote_photograph_comment(
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<ServerState>>,
-    Path((_photograph_id, comment_id)): Path<(Uuid, Uuid)>,
+    Path((photograph_id, comment_id)): Path<(Uuid, Uuid)>,
     Json(request): Json<VotePhotographRequest>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let counts: VoteCounts = match conn
         .transaction::<_, diesel::result::Error, _>(async |conn| {
@@ -99,6 +96,14 @@ pub async fn vote_photograph_comment(
         Err(e) => return Err(code_err(CodeError::DB_INSERTION_ERROR, e)),
     };
 
+    if request.is_upvote {
+        state.notify_upvote(
+            user_id,
+            CommentLocation::Photograph { photograph_id },
+            Some(comment_id),
+        );
+    }
+
     Ok(http_resp(
         VotePhotographResponse {
             upvote_count: counts.upvote_count,
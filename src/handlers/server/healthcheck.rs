@@ -1,37 +1,152 @@
-use axum::{Json, http::StatusCode, response::IntoResponse};
-use serde_derive::Serialize;
-use utoipa::ToSchema;
+use std::{future::Future, sync::Arc, time::Duration};
 
-use crate::build_info::{BUILD_TIME_UTC, LIB_VERSION_MAP, RUSTC_VERSION};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use diesel_async::RunQueryDsl;
+use serde_derive::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    build_info::{BUILD_TIME_UTC, LIB_VERSION_MAP, RUSTC_VERSION},
+    init::state::ServerState,
+    util::time::now::tokio_now,
+};
+
+/// Upper bound on each dependency probe in deep mode.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HealthcheckRequest {
+    /// Also probe the database, object storage, the email transport, the
+    /// search indexes and the geo-IP tables. Answers 503 when any of them is
+    /// down.
+    pub deep: Option<bool>,
+}
 
 #[derive(Serialize, ToSchema)]
 pub struct ServerHealthcheckResponse {
     pub build_time: &'static str,
     pub axum_version: String,
     pub rust_version: &'static str,
+    /// Present only with `deep=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<ComponentHealth>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ComponentHealth {
+    /// `database`, `storage`, `email`, `search_index` or `geo_ip`.
+    pub component: &'static str,
+    pub healthy: bool,
+    pub latency_ms: f64,
+    /// Short public reason when unhealthy; the full error is logged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
 }
 
 #[utoipa::path(
     get,
     path = "/api/healthcheck/server",
     tag = "server",
+    params(HealthcheckRequest),
     responses(
-        (status = 200, description = "Server is healthy", body = ServerHealthcheckResponse)
+        (status = 200, description = "Server (and with `deep=true`, every dependency) is healthy", body = ServerHealthcheckResponse),
+        (status = 503, description = "A dependency probe failed (`deep=true` only)", body = ServerHealthcheckResponse)
     )
 )]
-pub async fn healthcheck() -> impl IntoResponse {
+pub async fn healthcheck(
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<HealthcheckRequest>,
+) -> impl IntoResponse {
     let axum_version: Option<&crate::build_info::LibVersion> = LIB_VERSION_MAP.get("axum");
     let axum_version = match axum_version {
         Some(lib) => [lib.get_name(), lib.get_version()].concat(),
         None => String::from("Unknown"),
     };
 
+    let components = if request.deep.unwrap_or(false) {
+        Some(probe_dependencies(&state).await)
+    } else {
+        None
+    };
+    let status = match &components {
+        Some(components) if components.iter().any(|c| !c.healthy) => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        _ => StatusCode::OK,
+    };
+
     (
-        StatusCode::OK,
+        status,
         Json(ServerHealthcheckResponse {
             build_time: BUILD_TIME_UTC,
             axum_version,
             rust_version: RUSTC_VERSION,
+            components,
         }),
     )
 }
+
+/// Runs every probe concurrently, each under [`PROBE_TIMEOUT`].
+async fn probe_dependencies(state: &ServerState) -> Vec<ComponentHealth> {
+    let (database, storage, email, search_index) = tokio::join!(
+        probe("database", async {
+            let mut conn = state.get_conn().await?;
+            diesel::sql_query("SELECT 1").execute(&mut conn).await?;
+            Ok(())
+        }),
+        probe("storage", state.storage.health_check()),
+        probe("email", state.email_transport().health_check()),
+        probe("search_index", async {
+            state.search_index.check_reader()?;
+            state.wasm_module_search_index.check_reader()?;
+            Ok(())
+        }),
+    );
+    let geo_ip = probe("geo_ip", async {
+        if state.geo_ip_db.v4.is_empty() || state.geo_ip_db.v6.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Geo-IP tables not loaded (v4: {}, v6: {})",
+                state.geo_ip_db.v4.len(),
+                state.geo_ip_db.v6.len()
+            ));
+        }
+        Ok(())
+    })
+    .await;
+
+    vec![database, storage, email, search_index, geo_ip]
+}
+
+async fn probe(
+    component: &'static str,
+    check: impl Future<Output = anyhow::Result<()>>,
+) -> ComponentHealth {
+    let start = tokio_now();
+    let result = tokio::time::timeout(PROBE_TIMEOUT, check).await;
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let reason = match result {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => {
+            warn!(component, error = ?e, "Healthcheck probe failed");
+            Some("probe failed")
+        }
+        Err(_) => {
+            warn!(component, timeout = ?PROBE_TIMEOUT, "Healthcheck probe timed out");
+            Some("timed out")
+        }
+    };
+
+    ComponentHealth {
+        component,
+        healthy: reason.is_none(),
+        latency_ms,
+        reason,
+    }
+}
@@ -0,0 +1,60 @@
+//! Prometheus scrape endpoint. Requires an `x-api-key` header holding one of
+//! the keys in `api_keys_set` (`X_API_KEY`).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+
+use crate::{
+    errors::code_error::CodeErrorResp,
+    init::{db_breaker, state::ServerState},
+    util::metrics::{CONTENT_TYPE, MetricsWriter},
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/metrics",
+    tag = "server",
+    responses(
+        (status = 200, description = "Metrics in Prometheus text format", content_type = "text/plain", body = String),
+        (status = 401, description = "Missing or invalid x-api-key", body = CodeErrorResp)
+    )
+)]
+pub async fn get_metrics(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    let mut w = MetricsWriter::new();
+
+    state.responses.write_metrics(&mut w);
+    w.header(
+        "process_uptime_seconds",
+        "gauge",
+        "Seconds since the server started.",
+    );
+    w.sample(
+        "process_uptime_seconds",
+        &[],
+        state.server_start_time.elapsed().as_secs_f64(),
+    );
+
+    state
+        .session_purges
+        .write_metrics(&mut w, state.get_session_length());
+    state.session_stats.write_metrics(&mut w);
+    state.job_monitor.write_metrics(&mut w).await;
+    state.rate_limiter.write_metrics(&mut w);
+    state.request_timeouts.write_metrics(&mut w);
+    state.slow_queries.write_metrics(&mut w);
+    state.response_cache.write_metrics(&mut w);
+    let mut breakers = vec![state.pool.breaker()];
+    breakers.extend(state.read_pool.as_ref().map(|pool| pool.breaker()));
+    db_breaker::write_metrics(&mut w, &breakers);
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        w.finish(),
+    )
+}
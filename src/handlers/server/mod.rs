@@ -2,5 +2,9 @@ pub mod fallback;
 pub mod get_host_fastfetch;
 pub mod healthcheck;
 pub mod lookup_ip_loc;
+pub mod metrics;
+pub mod probes;
 pub mod root;
+pub mod serve_storage_object;
+pub mod site;
 pub mod visitor_board;
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+
+use crate::init::readiness::{Readiness, ReadinessReport};
+
+/// Liveness: the process is up and its runtime answers. Never checks
+/// dependencies, so a slow start or a database outage does not get the
+/// instance restarted.
+#[utoipa::path(
+    get,
+    path = "/livez",
+    tag = "server",
+    responses(
+        (status = 200, description = "Process is up", body = String)
+    )
+)]
+pub async fn livez() -> impl IntoResponse {
+    (StatusCode::OK, "ok")
+}
+
+/// Readiness: whether traffic should be routed here.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "server",
+    responses(
+        (status = 200, description = "Ready to serve traffic", body = ReadinessReport),
+        (status = 503, description = "Still initializing, or draining for shutdown", body = ReadinessReport)
+    )
+)]
+pub async fn readyz(State(readiness): State<Arc<Readiness>>) -> impl IntoResponse {
+    let report = readiness.report();
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use axum::{extract::State, response::IntoResponse};
@@ -9,9 +10,12 @@ use utoipa::ToSchema;
 
 use crate::{
     dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
-    util::{time::duration_formatter::format_duration, time::now::tokio_now},
+    util::{
+        http::endpoint_stats::StatusClassCounts, time::duration_formatter::format_duration,
+        time::now::tokio_now,
+    },
 };
 
 #[derive(Serialize, ToSchema)]
@@ -19,6 +23,10 @@ pub struct RootHandlerResponse {
     timestamp: DateTime<Utc>,
     server_uptime: String, // TODO: ISO-compliance
     responses_handled: u64,
+    /// Responses since startup by status class.
+    responses_by_status_class: StatusClassCounts,
+    /// Responses since startup by request method; unlisted methods have none.
+    responses_by_method: BTreeMap<String, u64>,
     users_logged_in: usize,
     db_version: String,
     db_latency: String,
@@ -44,10 +52,7 @@ pub async fn root_handler(
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let db_start = tokio_now();
     let version: Version = sql_query("SELECT current_setting('server_version') AS version")
@@ -63,6 +68,8 @@ pub async fn root_handler(
             timestamp: Utc::now(),
             server_uptime: format_duration(state.get_uptime()),
             responses_handled: state.get_responses_handled(),
+            responses_by_status_class: state.responses.by_status_class(),
+            responses_by_method: state.responses.by_method(),
             users_logged_in: state.get_session_length(),
             db_version: version.version,
             db_latency: format!("{db_elapsed:?}"),
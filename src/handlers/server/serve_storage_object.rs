@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{Response, StatusCode, header},
+    response::IntoResponse,
+};
+use tracing::error;
+
+use crate::{
+    domain::photography::presigned_upload::PRESIGNED_UPLOAD_PREFIX, init::state::ServerState,
+    util::storage::normalize_key,
+};
+
+/// Prefixes that are never public, whatever the backend.
+const NON_PUBLIC_PREFIXES: [&str; 2] = ["private/", PRESIGNED_UPLOAD_PREFIX];
+
+/// GET /storage/{*key}
+/// Public objects of the local storage backend (S3 serves its own). 404 for
+/// other backends, private originals and staged presigned uploads.
+pub async fn serve_storage_object(
+    State(state): State<Arc<ServerState>>,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    if !state.storage.serves_objects() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    // Checked on the normalized key: `/private/x` and `//private/x` name the
+    // same file as `private/x`.
+    let Some(key) = normalize_key(&key) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if NON_PUBLIC_PREFIXES.iter().any(|p| key.starts_with(p)) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match state.storage.get(&key).await {
+        Ok(Some(object)) => {
+            let mut response = Response::new(Body::from(object.bytes));
+            if let Some(content_type) = object
+                .content_type
+                .and_then(|ct| header::HeaderValue::from_str(&ct).ok())
+            {
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, content_type);
+            }
+            // Keys are content-unique (UUID paths) except in-place thumbnail
+            // overwrites, so keep the cache short.
+            response.headers_mut().insert(
+                header::CACHE_CONTROL,
+                header::HeaderValue::from_static("public, max-age=300"),
+            );
+            response.into_response()
+        }
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!(error = ?e, key = %key, "Failed to read local storage object");
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{Extension, response::IntoResponse};
+
+use crate::{
+    dto::responses::response_data::http_resp,
+    errors::code_error::{CodeErrorResp, HandlerResponse},
+    init::state::sites::{Site, SiteInfo},
+    util::time::now::tokio_now,
+};
+
+/// The site serving this request's host, for the frontend's title.
+#[utoipa::path(
+    get,
+    path = "/api/site",
+    tag = "server",
+    responses(
+        (status = 200, description = "Site resolved from the Host header", body = SiteInfo),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_site_info(
+    Extension(site): Extension<Arc<Site>>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+    Ok(http_resp(site.info(), (), start))
+}
@@ -0,0 +1,76 @@
+//! `GET /api/uploads/{upload_id}/progress` — Server-Sent Events stream of an
+//! upload's progress (see `domain::upload_progress`).
+//!
+//! Emits the current state immediately, then one `progress` event per change,
+//! and ends after the `done`/`failed` event. Subscribing before the upload
+//! starts is allowed and reserves the id for the caller.
+
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures_util::stream;
+use uuid::Uuid;
+
+use crate::{
+    domain::upload_progress::UploadProgress,
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    init::state::ServerState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/uploads/{upload_id}/progress",
+    tag = "upload",
+    params(("upload_id" = Uuid, Path, description = "Client-chosen upload id (sent as X-Upload-Id)")),
+    responses(
+        (status = 200, description = "text/event-stream of `progress` events", body = UploadProgress, content_type = "text/event-stream"),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 404, description = "Upload id belongs to another user", body = CodeErrorResp)
+    )
+)]
+pub async fn get_upload_progress(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<ServerState>>,
+    Path(upload_id): Path<Uuid>,
+) -> HandlerResponse<impl IntoResponse> {
+    let receiver = state
+        .subscribe_upload_progress(upload_id, user_id)
+        .await
+        .ok_or_else(|| code_err(CodeError::UPLOAD_NOT_FOUND, "Upload not found"))?;
+
+    // (receiver, first poll, finished)
+    let events = stream::unfold(
+        (receiver, true, false),
+        |(mut receiver, first, finished)| async move {
+            if finished {
+                return None;
+            }
+            if !first && receiver.changed().await.is_err() {
+                // Entry pruned; nothing more will arrive.
+                return None;
+            }
+            let progress = receiver.borrow_and_update().clone();
+            let finished = progress.stage.is_terminal();
+            Some((
+                Ok::<_, Infallible>(progress_event(&progress)),
+                (receiver, false, finished),
+            ))
+        },
+    );
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(Duration::from_secs(15))))
+}
+
+fn progress_event(progress: &UploadProgress) -> Event {
+    Event::default()
+        .event("progress")
+        .json_data(progress)
+        .unwrap_or_else(|_| Event::default().event("progress").data("{}"))
+}
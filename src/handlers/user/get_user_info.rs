@@ -11,7 +11,7 @@ use crate::{
     dto::responses::{
         response_data::http_resp, user::public_user_info_response::PublicUserInfoResponse,
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::{user_profile_pictures, users},
     util::time::now::tokio_now,
@@ -38,10 +38,7 @@ pub async fn get_user_info(
         return Err(CodeError::USER_NAME_INVALID.into());
     }
 
-    let mut conn = state
-        .get_conn()
-        .await
-        .map_err(|e| code_err(CodeError::POOL_ERROR, e))?;
+    let mut conn = state.get_conn().await.map_err(pool_err)?;
 
     let user_row: Option<(uuid::Uuid, String, chrono::DateTime<chrono::Utc>, i32)> = users::table
         .filter(users::user_name.eq(&user_name))
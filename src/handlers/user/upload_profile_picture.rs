@@ -12,8 +12,8 @@ use uuid::Uuid;
 use crate::{
     domain::auth::user::UserProfilePictureInsertable,
     dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
-    init::state::ServerState,
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::{ServerState, cache_versions::CachedResource, sites::Site},
     schema::user_profile_pictures,
     util::{
         image::{
@@ -46,8 +46,6 @@ const ALLOWED_MIME_TYPES: [&str; 16] = [
     "image/vnd.zbrush.pcx",     // PCX
 ];
 
-const AWS_S3_BUCKET_NAME: &str = "cyhdev-img";
-
 // TODO: STREAM to file, don't keep the whole damn thing around
 // TODO: DELETE old S3 objects
 #[utoipa::path(
@@ -64,6 +62,7 @@ const AWS_S3_BUCKET_NAME: &str = "cyhdev-img";
 )]
 pub async fn upload_profile_picture(
     Extension(user_id): Extension<Uuid>,
+    Extension(site): Extension<Arc<Site>>,
     State(state): State<Arc<ServerState>>,
     mut multipart: Multipart,
 ) -> HandlerResponse<impl IntoResponse> {
@@ -153,51 +152,36 @@ pub async fn upload_profile_picture(
         code_err(CodeError::COULD_NOT_PROCESS_IMAGE, e)
     })?;
 
-    // store in filesystem or S3
+    // store in the site's object storage (S3 or local filesystem)
     let image_id: Uuid = uuid::Uuid::new_v4();
     let (extension, image_type_db_id) = map_image_format_to_str(IMAGE_ENCODING_FORMAT);
 
     let image_path = format!("images/{image_id}.{extension}");
 
-    // upload to S3 here
-    // Initialize AWS S3 client from environment and upload the image
-    let s3_client = aws_sdk_s3::Client::new(&state.aws_profile_picture_config);
-
-    s3_client
-        .put_object()
-        .bucket(AWS_S3_BUCKET_NAME)
-        .key(&image_path)
-        .content_type(mime.as_deref().unwrap_or("application/octet-stream"))
-        .body(aws_sdk_s3::primitives::ByteStream::from(processed_image))
-        .send()
+    state
+        .storage
+        .put(
+            &image_path,
+            processed_image,
+            mime.as_deref().unwrap_or("application/octet-stream"),
+        )
         .await
         .map_err(|e| {
             error!(
                 error = ?e,
                 user_id = %user_id,
-                bucket = AWS_S3_BUCKET_NAME,
+                backend = site.storage.name(),
                 key = %image_path,
-                "Failed to upload profile picture to S3"
+                "Failed to upload profile picture"
             );
             code_err(CodeError::FILE_UPLOAD_ERROR, e)
         })?;
 
-    // Assemble the public S3 object URL
-    // Replace `<region>` below with your actual AWS region as appropriate
-    let s3_region: String = state
-        .aws_profile_picture_config
-        .region()
-        .map(|r| r.to_string())
-        .unwrap_or_else(|| "us-west-1".to_string());
-
-    let object_url: String = format!(
-        "https://{}.s3.{}.amazonaws.com/{}",
-        AWS_S3_BUCKET_NAME, s3_region, image_path
-    );
+    let object_url: String = site.storage.public_url(&image_path);
 
     let mut conn = state.get_conn().await.map_err(|e| {
         error!(error = ?e, user_id = %user_id, "Failed to get DB connection from pool");
-        code_err(CodeError::POOL_ERROR, e)
+        pool_err(e)
     })?;
 
     let db_result: Result<Uuid, diesel::result::Error> =
@@ -220,20 +204,14 @@ pub async fn upload_profile_picture(
                 key = %image_path,
                 "Failed to insert user profile picture row into DB"
             );
-            // Clean up the orphaned S3 object if DB insertion fails
-            if let Err(del_err) = s3_client
-                .delete_object()
-                .bucket(AWS_S3_BUCKET_NAME)
-                .key(&image_path)
-                .send()
-                .await
-            {
+            // Clean up the orphaned object if DB insertion fails
+            if let Err(del_err) = site.storage.delete(&image_path).await {
                 error!(
                     error = ?del_err,
                     user_id = %user_id,
-                    bucket = AWS_S3_BUCKET_NAME,
+                    backend = site.storage.name(),
                     key = %image_path,
-                    "Failed to delete orphaned S3 object after DB insertion failure"
+                    "Failed to delete orphaned object after DB insertion failure"
                 );
             }
             return Err(code_err(CodeError::DB_INSERTION_ERROR, e));
@@ -245,6 +223,9 @@ pub async fn upload_profile_picture(
 
     drop(conn);
 
+    // Author badges in the posts list carry the picture.
+    state.cache_versions.bump(CachedResource::Posts);
+
     // TODO: define response dto later
     Ok(http_resp((), (), start))
 }
@@ -5,7 +5,7 @@ use axum::{
     extract::{Path, State},
     response::IntoResponse,
 };
-use diesel::{ExpressionMethods, QueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::RunQueryDsl;
 use serde_derive::Serialize;
 use tracing::{error, info};
@@ -13,11 +13,12 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    domain::audit::audit::AdminAction,
     dto::responses::response_data::http_resp,
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::wasm_module,
-    util::time::now::tokio_now,
+    util::{extract::AdminActor, time::now::tokio_now},
 };
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -26,7 +27,7 @@ pub struct DeleteWasmModuleResponse {
 }
 
 /// DELETE /api/wasm-modules/{wasm_module_id}
-/// Superuser only - deletes a WASM module (DB record and cache)
+/// Superuser only - deletes a WASM module (DB record, stored bundle and file objects, and cache)
 #[utoipa::path(
     delete,
     path = "/api/wasm-modules/{wasm_module_id}",
@@ -44,22 +45,38 @@ pub struct DeleteWasmModuleResponse {
 )]
 pub async fn delete_wasm_module(
     Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
     Path(wasm_module_id): Path<Uuid>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
+    // File rows go with the module (ON DELETE CASCADE); keep their objects' keys.
+    let file_keys = state
+        .wasm_module_file_keys(wasm_module_id)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to query WASM module files");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?;
+
     let mut conn = state.get_conn().await.map_err(|e| {
         error!(error = ?e, "Failed to get DB connection");
-        code_err(CodeError::POOL_ERROR, e)
+        pool_err(e)
     })?;
 
     // Delete from database
-    let deleted_count = diesel::delete(
+    let deleted_row: Option<(Option<String>, Option<String>, String)> = diesel::delete(
         wasm_module::table.filter(wasm_module::wasm_module_id.eq(wasm_module_id)),
     )
-    .execute(&mut conn)
+    .returning((
+        wasm_module::wasm_module_bundle_key,
+        wasm_module::wasm_module_bundle_br_key,
+        wasm_module::wasm_module_title,
+    ))
+    .get_result(&mut conn)
     .await
+    .optional()
     .map_err(|e| {
         error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to delete WASM module from DB");
         code_err(CodeError::DB_DELETION_ERROR, e)
@@ -67,18 +84,30 @@ pub async fn delete_wasm_module(
 
     drop(conn);
 
-    if deleted_count == 0 {
+    let Some((bundle_key, bundle_br_key, title)) = deleted_row else {
         return Err(code_err(CodeError::DB_QUERY_ERROR, "WASM module not found"));
-    }
+    };
 
     // Remove from cache
     state.invalidate_wasm_module(wasm_module_id).await;
+    state.unindex_wasm_module(wasm_module_id);
+    for key in bundle_key.into_iter().chain(bundle_br_key).chain(file_keys) {
+        state.delete_wasm_bundle_object(&key).await;
+    }
 
     info!(
         wasm_module_id = %wasm_module_id,
         user_id = %user_id,
         "WASM module deleted"
     );
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::WasmModuleDelete,
+            Some(wasm_module_id.to_string()),
+            serde_json::json!({ "title": title }),
+        )
+        .await;
 
     Ok(http_resp(
         DeleteWasmModuleResponse {
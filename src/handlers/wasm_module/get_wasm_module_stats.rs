@@ -0,0 +1,145 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use chrono::{Duration, NaiveDate, Utc};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    dto::{
+        requests::wasm_module::WasmModuleStatsRequest,
+        responses::{
+            response_data::http_resp,
+            wasm_module::{WasmModuleCountryLoads, WasmModuleDailyLoads, WasmModuleStatsResponse},
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    init::state::ServerState,
+    schema::{wasm_module, wasm_module_loads},
+    util::time::now::tokio_now,
+};
+
+const DEFAULT_STATS_DAYS: u32 = 30;
+const MAX_STATS_DAYS: u32 = 365;
+
+/// GET /api/wasm-modules/{wasm_module_id}/stats
+/// Superuser only - daily bundle load counts and a country breakdown
+#[utoipa::path(
+    get,
+    path = "/api/wasm-modules/{wasm_module_id}/stats",
+    tag = "wasm_module",
+    params(
+        ("wasm_module_id" = Uuid, Path, description = "WASM module UUID"),
+        WasmModuleStatsRequest
+    ),
+    responses(
+        (status = 200, description = "WASM module load stats", body = WasmModuleStatsResponse),
+        (status = 400, description = "Invalid window", body = CodeErrorResp),
+        (status = 401, description = "Unauthorized", body = CodeErrorResp),
+        (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
+        (status = 404, description = "WASM module not found", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn get_wasm_module_stats(
+    State(state): State<Arc<ServerState>>,
+    Path(wasm_module_id): Path<Uuid>,
+    Query(request): Query<WasmModuleStatsRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let days = request.days.unwrap_or(DEFAULT_STATS_DAYS);
+    if days == 0 || days > MAX_STATS_DAYS {
+        return Err(code_err(
+            CodeError::INVALID_REQUEST,
+            "days must be between 1 and 365",
+        ));
+    }
+    let to = Utc::now().date_naive();
+    let from = to - Duration::days(i64::from(days) - 1);
+
+    let mut conn = state.get_conn().await.map_err(|e| {
+        error!(error = ?e, "Failed to get DB connection");
+        pool_err(e)
+    })?;
+
+    wasm_module::table
+        .select(wasm_module::wasm_module_id)
+        .filter(wasm_module::wasm_module_id.eq(wasm_module_id))
+        .first::<Uuid>(&mut conn)
+        .await
+        .optional()
+        .map_err(|e| {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to query WASM module");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?
+        .ok_or_else(|| code_err(CodeError::WASM_MODULE_NOT_FOUND, "WASM module not found"))?;
+
+    // At most `days` x countries rows; aggregated here rather than in SQL
+    // (SUM(bigint) is numeric in Postgres).
+    let rows: Vec<(NaiveDate, String, i64)> = wasm_module_loads::table
+        .select((
+            wasm_module_loads::wasm_module_load_date,
+            wasm_module_loads::wasm_module_load_country_code,
+            wasm_module_loads::wasm_module_load_count,
+        ))
+        .filter(wasm_module_loads::wasm_module_id.eq(wasm_module_id))
+        .filter(wasm_module_loads::wasm_module_load_date.between(from, to))
+        .load(&mut conn)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to query WASM module loads");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?;
+
+    drop(conn);
+
+    let mut by_day: HashMap<NaiveDate, i64> = HashMap::new();
+    let mut by_country: HashMap<String, i64> = HashMap::new();
+    let mut total_loads = 0i64;
+    for (date, country_code, count) in rows {
+        *by_day.entry(date).or_default() += count;
+        *by_country.entry(country_code).or_default() += count;
+        total_loads += count;
+    }
+
+    let daily: Vec<WasmModuleDailyLoads> = from
+        .iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| WasmModuleDailyLoads {
+            date,
+            load_count: by_day.get(&date).copied().unwrap_or(0),
+        })
+        .collect();
+
+    let mut countries: Vec<WasmModuleCountryLoads> = by_country
+        .into_iter()
+        .map(|(country_code, load_count)| WasmModuleCountryLoads {
+            country_code,
+            load_count,
+        })
+        .collect();
+    countries.sort_by(|a, b| {
+        b.load_count
+            .cmp(&a.load_count)
+            .then_with(|| a.country_code.cmp(&b.country_code))
+    });
+
+    Ok(http_resp(
+        WasmModuleStatsResponse {
+            wasm_module_id,
+            from,
+            to,
+            total_loads,
+            daily,
+            countries,
+        },
+        (),
+        start,
+    ))
+}
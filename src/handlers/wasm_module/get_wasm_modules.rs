@@ -1,56 +1,125 @@
 use std::sync::Arc;
 
-use axum::{extract::State, response::IntoResponse};
-use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use diesel::{ExpressionMethods, PgArrayExpressionMethods, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 use tracing::error;
+use uuid::Uuid;
 
 use crate::{
-    domain::wasm_module::wasm_module::WasmModuleMetadata,
-    dto::responses::{
-        response_data::http_resp,
-        wasm_module::{GetWasmModulesResponse, WasmModuleItem},
+    domain::wasm_module::wasm_module::{
+        WasmModuleAccess, WasmModuleMetadata, normalize_wasm_module_label,
+    },
+    dto::{
+        requests::wasm_module::GetWasmModulesRequest,
+        responses::{
+            response_data::http_resp,
+            wasm_module::{GetWasmModulesResponse, WasmModuleItem},
+        },
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
+    routers::middleware::is_logged_in::AuthSession,
     schema::wasm_module,
     util::time::now::tokio_now,
 };
 
 /// GET /api/wasm-modules
-/// Public endpoint - lists all WASM modules
+/// Public endpoint - lists the WASM modules visible to the caller: public ones,
+/// plus unlisted/superuser-only ones shared with them (all for superusers),
+/// optionally narrowed to one category and/or tag
 #[utoipa::path(
     get,
     path = "/api/wasm-modules",
     tag = "wasm_module",
+    params(GetWasmModulesRequest),
     responses(
         (status = 200, description = "List of WASM modules", body = GetWasmModulesResponse),
+        (status = 400, description = "Invalid category or tag", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
 pub async fn get_wasm_modules(
+    Extension(auth_session): Extension<Option<AuthSession>>,
     State(state): State<Arc<ServerState>>,
+    Query(request): Query<GetWasmModulesRequest>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
+    let label = |value: Option<String>| {
+        value
+            .map(|v| normalize_wasm_module_label(&v))
+            .transpose()
+            .map(Option::flatten)
+            .map_err(|e| code_err(CodeError::INVALID_REQUEST, e))
+    };
+    let category = label(request.category)?;
+    let tag = label(request.tag)?;
+
+    let viewer = auth_session.as_ref().map(|s| s.user_id);
+    let is_superuser = auth_session
+        .as_ref()
+        .is_some_and(|s| s.role_type.is_superuser());
+
     let mut conn = state.get_conn().await.map_err(|e| {
         error!(error = ?e, "Failed to get DB connection");
-        code_err(CodeError::POOL_ERROR, e)
+        pool_err(e)
     })?;
 
-    let modules: Vec<WasmModuleMetadata> = wasm_module::table
+    let mut query = wasm_module::table
         .select(WasmModuleMetadata::as_select())
         .order(wasm_module::wasm_module_created_at.desc())
-        .load(&mut conn)
-        .await
-        .map_err(|e| {
-            error!(error = ?e, "Failed to query WASM modules");
-            code_err(CodeError::DB_QUERY_ERROR, e)
-        })?;
+        .into_boxed();
+    if let Some(category) = category {
+        query = query.filter(wasm_module::wasm_module_category.eq(category));
+    }
+    if let Some(tag) = tag {
+        query = query.filter(wasm_module::wasm_module_tags.contains(vec![tag]));
+    }
+    let modules: Vec<WasmModuleMetadata> = query.load(&mut conn).await.map_err(|e| {
+        error!(error = ?e, "Failed to query WASM modules");
+        code_err(CodeError::DB_QUERY_ERROR, e)
+    })?;
 
     drop(conn);
 
-    let items: Vec<WasmModuleItem> = modules.into_iter().map(WasmModuleItem::from).collect();
+    let items = visible_wasm_module_items(&state, modules, viewer, is_superuser).await;
 
     Ok(http_resp(GetWasmModulesResponse { items }, (), start))
 }
+
+/// The modules `viewer` may list, as response items (allowed users included
+/// for superusers), in input order.
+pub(crate) async fn visible_wasm_module_items(
+    state: &ServerState,
+    modules: Vec<WasmModuleMetadata>,
+    viewer: Option<Uuid>,
+    is_superuser: bool,
+) -> Vec<WasmModuleItem> {
+    let mut items: Vec<WasmModuleItem> = Vec::with_capacity(modules.len());
+    for module in modules {
+        // The row is authoritative for visibility; the cache supplies the
+        // allowed users.
+        let access = WasmModuleAccess {
+            visibility: module.wasm_module_visibility,
+            allowed_user_ids: state
+                .get_wasm_module_access(module.wasm_module_id)
+                .await
+                .map(|a| a.allowed_user_ids)
+                .unwrap_or_default(),
+        };
+        if !access.can_list(viewer, is_superuser) {
+            continue;
+        }
+        let mut item = WasmModuleItem::from(module);
+        if is_superuser {
+            item.wasm_module_allowed_user_ids = Some(access.allowed_user_ids);
+        }
+        items.push(state.deliver_wasm_module_item(item));
+    }
+    items
+}
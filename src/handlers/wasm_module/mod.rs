@@ -1,13 +1,19 @@
 pub mod delete_wasm_module;
+pub mod get_wasm_module_stats;
 pub mod get_wasm_modules;
+pub mod search_wasm_modules;
 pub mod serve_wasm;
+pub mod serve_wasm_file;
 pub mod update_wasm_module;
 pub mod update_wasm_module_assets;
 pub mod upload_wasm_module;
 
 pub use delete_wasm_module::delete_wasm_module;
+pub use get_wasm_module_stats::get_wasm_module_stats;
 pub use get_wasm_modules::get_wasm_modules;
+pub use search_wasm_modules::search_wasm_modules;
 pub use serve_wasm::serve_wasm;
+pub use serve_wasm_file::serve_wasm_file;
 pub use update_wasm_module::update_wasm_module;
 pub use update_wasm_module_assets::update_wasm_module_assets;
 pub use upload_wasm_module::upload_wasm_module;
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use tracing::error;
+
+use crate::{
+    domain::wasm_module::wasm_module::{
+        WasmModuleMetadata, normalize_wasm_module_label, normalize_wasm_module_tags,
+    },
+    dto::{
+        requests::wasm_module::SearchWasmModulesRequest,
+        responses::{
+            pagination::{Paginated, page_offset},
+            response_data::http_resp,
+            wasm_module::WasmModuleItem,
+        },
+    },
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
+    handlers::wasm_module::get_wasm_modules::visible_wasm_module_items,
+    init::state::ServerState,
+    routers::middleware::is_logged_in::AuthSession,
+    schema::wasm_module,
+    util::time::now::tokio_now,
+};
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+/// Matches considered before visibility filtering and paging.
+const MAX_MATCHES: usize = 1000;
+
+/// GET /api/wasm-modules/search
+/// Public endpoint - full-text search over module titles and descriptions,
+/// optionally filtered by category and tags, ranked by relevance. Only
+/// modules the caller may list are returned.
+#[utoipa::path(
+    get,
+    path = "/api/wasm-modules/search",
+    tag = "wasm_module",
+    params(SearchWasmModulesRequest),
+    responses(
+        (status = 200, description = "Matching WASM modules", body = Paginated<WasmModuleItem>),
+        (status = 400, description = "Invalid search parameters", body = CodeErrorResp),
+        (status = 500, description = "Internal server error", body = CodeErrorResp)
+    )
+)]
+pub async fn search_wasm_modules(
+    Extension(auth_session): Extension<Option<AuthSession>>,
+    State(state): State<Arc<ServerState>>,
+    Query(request): Query<SearchWasmModulesRequest>,
+) -> HandlerResponse<impl IntoResponse> {
+    let start = tokio_now();
+
+    let query = request.q.unwrap_or_default().trim().to_string();
+    let category = request
+        .category
+        .map(|c| normalize_wasm_module_label(&c))
+        .transpose()
+        .map_err(|e| code_err(CodeError::INVALID_REQUEST, e))?
+        .flatten();
+    let tags = normalize_wasm_module_tags(request.tags.as_deref().unwrap_or("").split(','))
+        .map_err(|e| code_err(CodeError::INVALID_REQUEST, e))?;
+    if query.is_empty() && category.is_none() && tags.is_empty() {
+        return Err(code_err(
+            CodeError::INVALID_REQUEST,
+            "Provide a query, a category or tags",
+        ));
+    }
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let page = request.page.unwrap_or(1).max(1);
+
+    let ids = state.search_wasm_modules(&query, category.as_deref(), &tags, MAX_MATCHES);
+
+    let modules = if ids.is_empty() {
+        Vec::new()
+    } else {
+        let mut conn = state.get_conn().await.map_err(|e| {
+            error!(error = ?e, "Failed to get DB connection");
+            pool_err(e)
+        })?;
+        let rows: Vec<WasmModuleMetadata> = wasm_module::table
+            .select(WasmModuleMetadata::as_select())
+            .filter(wasm_module::wasm_module_id.eq_any(&ids))
+            .load(&mut conn)
+            .await
+            .map_err(|e| {
+                error!(error = ?e, "Failed to query WASM modules");
+                code_err(CodeError::DB_QUERY_ERROR, e)
+            })?;
+        drop(conn);
+
+        // Back into relevance order.
+        let mut by_id: HashMap<_, _> = rows.into_iter().map(|m| (m.wasm_module_id, m)).collect();
+        ids.iter().filter_map(|id| by_id.remove(id)).collect()
+    };
+
+    let viewer = auth_session.as_ref().map(|s| s.user_id);
+    let is_superuser = auth_session
+        .as_ref()
+        .is_some_and(|s| s.role_type.is_superuser());
+    let visible = visible_wasm_module_items(&state, modules, viewer, is_superuser).await;
+
+    let total = visible.len();
+    let items = visible
+        .into_iter()
+        .skip(page_offset(page, limit))
+        .take(limit)
+        .collect();
+
+    Ok(http_resp(
+        Paginated::<WasmModuleItem>::new(items, page, limit, total),
+        (),
+        start,
+    ))
+}
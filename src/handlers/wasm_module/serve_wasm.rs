@@ -1,19 +1,43 @@
-use std::sync::Arc;
+use std::{io::SeekFrom, net::SocketAddr, ops::Range, path::PathBuf, sync::Arc};
 
 use axum::{
+    Extension,
     body::{Body, Bytes},
-    extract::{Path, State},
-    http::{HeaderMap, Response, StatusCode, header},
+    extract::{ConnectInfo, Path, State},
+    http::{HeaderMap, Response, StatusCode, header, response::Builder},
     response::IntoResponse,
 };
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
 use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::init::state::ServerState;
+use crate::{
+    domain::wasm_module::wasm_module::WasmModuleVisibility,
+    init::state::ServerState,
+    routers::middleware::is_logged_in::AuthSession,
+    util::{
+        extract::client_ip::extract_client_ip,
+        http::conditional::{RangeRequest, content_range, if_none_match_matches, resolve_range},
+        wasm_bundle::identity_etag,
+        wasm_bundle_cache::WasmBundleSource,
+    },
+};
+
+/// SRI value of the decoded bundle, matching `wasm_module_bundle_integrity`.
+const BUNDLE_INTEGRITY_HEADER: &str = "x-bundle-integrity";
 
 /// GET /api/wasm-modules/{wasm_module_id}/wasm
-/// Public endpoint - serves the WASM bundle from the in-memory cache (DB-backed)
-/// Bundles are stored and served as pre-compressed .gz for smaller transfer size
+/// Public endpoint - serves the WASM bundle from the bundle cache (DB-backed)
+/// Hot bundles are shared from memory; ones evicted to the disk tier are streamed
+/// Bundles are stored and served as pre-compressed .gz for smaller transfer size,
+/// or as their Brotli copy (when they have one) to clients accepting `br`
+/// Superuser-only modules answer 404 unless the caller is a superuser or on the
+/// module's allowed list, and are never publicly cacheable
+/// Each served bundle counts as one load for `/api/wasm-modules/{id}/stats`
+/// Responses carry a strong ETag per encoding (304 on `If-None-Match`) and
+/// honor a single `Range` (with `If-Range`) so interrupted loads can resume
+/// `X-Bundle-Integrity` carries the bundle's SRI hash when one was recorded
 #[utoipa::path(
     get,
     path = "/api/wasm-modules/{wasm_module_id}/wasm",
@@ -23,51 +47,109 @@ use crate::init::state::ServerState;
     ),
     responses(
         (status = 200, description = "WASM bundle", content_type = "application/wasm"),
-        (status = 404, description = "WASM module not found")
+        (status = 206, description = "Requested byte range of the bundle", content_type = "application/wasm"),
+        (status = 304, description = "Client copy matches the ETag"),
+        (status = 404, description = "WASM module not found"),
+        (status = 416, description = "Range not satisfiable")
     )
 )]
 pub async fn serve_wasm(
+    Extension(auth_session): Extension<Option<AuthSession>>,
     State(state): State<Arc<ServerState>>,
     Path(wasm_module_id): Path<Uuid>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
 ) -> impl IntoResponse {
+    let viewer = auth_session.as_ref().map(|s| s.user_id);
+    let is_superuser = auth_session
+        .as_ref()
+        .is_some_and(|s| s.role_type.is_superuser());
+    // Hidden modules look exactly like missing ones.
+    let visibility = match state.get_wasm_module_access(wasm_module_id).await {
+        Some(access) if access.can_load(viewer, is_superuser) => Some(access.visibility),
+        _ => None,
+    };
+
     // Get from cache or load from filesystem
-    match state.get_wasm_module(wasm_module_id).await {
-        Some((bytes, is_gzipped, content_type)) => {
+    let module = match visibility {
+        Some(_) => state.get_wasm_module(wasm_module_id).await,
+        None => None,
+    };
+    match module {
+        Some(source) => {
             info!(
                 wasm_module_id = %wasm_module_id,
-                size_bytes = bytes.len(),
-                is_gzipped = is_gzipped,
-                content_type = content_type,
+                size_bytes = source.stored_len(),
+                is_gzipped = source.is_gzipped(),
+                content_type = source.content_type(),
+                from_disk = matches!(source, WasmBundleSource::Disk(_)),
                 "Serving WASM module bundle"
             );
 
-            // Negotiate Content-Encoding: only emit gzip when the client advertises it.
-            // Bundles are stored pre-compressed, so a non-gzip client must receive
-            // decompressed (identity) bytes or it cannot decode the body.
-            let accepts_gzip = headers
-                .get(header::ACCEPT_ENCODING)
-                .and_then(|v| v.to_str().ok())
-                .map(|ae| {
-                    ae.split(',').any(|e| {
-                        let name = match e.trim().split(';').next() {
-                            Some(value) => value.trim(),
-                            None => "",
-                        };
-                        name.eq_ignore_ascii_case("gzip") || name.eq_ignore_ascii_case("x-gzip")
-                    })
-                })
-                .unwrap_or(false);
-
-            let serve_gzipped = is_gzipped && accepts_gzip;
-            let out_bytes: Arc<[u8]> = if is_gzipped && !accepts_gzip {
-                let gz = bytes.clone();
-                match tokio::task::spawn_blocking(move || {
-                    crate::util::wasm_bundle::gzip_decompress_limited(&gz, 256 * 1024 * 1024)
-                })
-                .await
-                {
-                    Ok(Ok(decoded)) => Arc::from(decoded.into_boxed_slice()),
+            // Negotiate Content-Encoding: Brotli when the bundle has a copy and
+            // the client takes it, else gzip when the client advertises it.
+            // Bundles are stored pre-compressed, so a client accepting neither
+            // must receive decompressed (identity) bytes or it cannot decode the body.
+            let brotli_etag = source
+                .brotli_etag()
+                .filter(|_| accepts_encoding(&headers, &["br"]))
+                .map(str::to_string);
+            let serve_brotli = brotli_etag.is_some();
+            let accepts_gzip = accepts_encoding(&headers, &["gzip", "x-gzip"]);
+
+            let serve_gzipped = !serve_brotli && source.is_gzipped() && accepts_gzip;
+            let decompress = !serve_brotli && source.is_gzipped() && !accepts_gzip;
+            // Each encoding is its own representation, so each gets its own tag.
+            let etag = match brotli_etag {
+                Some(etag) => etag,
+                None if decompress => identity_etag(source.etag()),
+                None => source.etag().to_string(),
+            };
+
+            let restricted = visibility == Some(WasmModuleVisibility::SuperuserOnly);
+            let mut response = Response::builder()
+                .header(header::ETAG, etag.as_str())
+                .header(header::VARY, header::ACCEPT_ENCODING.as_str());
+            if let Some(integrity) = source.integrity() {
+                response = response.header(BUNDLE_INTEGRITY_HEADER, integrity);
+            }
+            response = if restricted {
+                response.header(header::CACHE_CONTROL, "private, max-age=3600")
+            } else {
+                response
+                    .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
+                    .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+                    .header(
+                        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                        BUNDLE_INTEGRITY_HEADER,
+                    )
+            };
+
+            // The client's copy is current; checked before decompressing.
+            if if_none_match_matches(&headers, &etag) {
+                return build_response(
+                    response.status(StatusCode::NOT_MODIFIED),
+                    Body::empty(),
+                    wasm_module_id,
+                );
+            }
+
+            let content_type = source.content_type();
+            let payload = if decompress {
+                let decoded = match source.read_all().await {
+                    Ok(gz) => {
+                        tokio::task::spawn_blocking(move || {
+                            crate::util::wasm_bundle::gzip_decompress_limited(
+                                &gz,
+                                256 * 1024 * 1024,
+                            )
+                        })
+                        .await
+                    }
+                    Err(e) => Ok(Err(e.into())),
+                };
+                match decoded {
+                    Ok(Ok(decoded)) => Payload::Bytes(Bytes::from(decoded)),
                     other => {
                         error!(
                             wasm_module_id = %wasm_module_id,
@@ -81,32 +163,79 @@ pub async fn serve_wasm(
                     }
                 }
             } else {
-                bytes
+                match source {
+                    WasmBundleSource::Memory(bundle) => match bundle.brotli {
+                        Some(br) if serve_brotli => Payload::Bytes(br.bytes),
+                        _ => Payload::Bytes(bundle.bytes),
+                    },
+                    WasmBundleSource::Disk(spilled) => match spilled.brotli {
+                        Some(br) if serve_brotli => Payload::File {
+                            path: br.path,
+                            len: br.len,
+                        },
+                        _ => Payload::File {
+                            path: spilled.path,
+                            len: spilled.len,
+                        },
+                    },
+                }
             };
 
-            let body = Body::from(Bytes::from_owner(out_bytes));
-
-            let mut response = Response::builder()
-                .status(StatusCode::OK)
+            response = response
                 .header(header::CONTENT_TYPE, content_type)
-                .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
-                .header(header::VARY, header::ACCEPT_ENCODING.as_str())
-                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
-
-            // Add Content-Encoding only when serving pre-compressed content to a gzip client.
-            if serve_gzipped {
+                .header(header::ACCEPT_RANGES, "bytes");
+            // Add Content-Encoding only when serving pre-compressed content to a client
+            // that accepts it. Ranges then address the compressed stream, which is
+            // what the client stores.
+            if serve_brotli {
+                response = response.header(header::CONTENT_ENCODING, "br");
+            } else if serve_gzipped {
                 response = response.header(header::CONTENT_ENCODING, "gzip");
             }
 
-            match response.body(body) {
-                Ok(response) => response,
+            let len = payload.len();
+            let (response, body, counts_as_load) = match resolve_range(&headers, len, &etag) {
+                RangeRequest::Full => (
+                    response.status(StatusCode::OK),
+                    payload.into_body(0..len).await,
+                    true,
+                ),
+                RangeRequest::Partial(range) => {
+                    // Only the first chunk of a resumed download counts.
+                    let first_chunk = range.start == 0;
+                    (
+                        response
+                            .status(StatusCode::PARTIAL_CONTENT)
+                            .header(header::CONTENT_RANGE, content_range(&range, len)),
+                        payload.into_body(range).await,
+                        first_chunk,
+                    )
+                }
+                RangeRequest::Unsatisfiable => (
+                    response
+                        .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                        .header(header::CONTENT_RANGE, format!("bytes */{len}")),
+                    Ok(Body::empty()),
+                    false,
+                ),
+            };
+            let body = match body {
+                Ok(body) => body,
                 Err(e) => {
-                    error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to build WASM response");
-                    let mut response = Response::new(Body::from("Failed to build WASM response"));
+                    error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to open spilled WASM bundle");
+                    let mut response = Response::new(Body::from("Failed to read WASM bundle"));
                     *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
-                    response
+                    return response;
                 }
+            };
+
+            if counts_as_load {
+                state
+                    .record_wasm_module_load(wasm_module_id, extract_client_ip(&headers, addr))
+                    .await;
             }
+
+            build_response(response, body, wasm_module_id)
         }
         None => match Response::builder()
             .status(StatusCode::NOT_FOUND)
@@ -120,3 +249,65 @@ pub async fn serve_wasm(
         },
     }
 }
+
+/// Whether `Accept-Encoding` lists one of `names` without refusing it (`q=0`).
+fn accepts_encoding(headers: &HeaderMap, names: &[&str]) -> bool {
+    headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ae| {
+            ae.split(',').any(|entry| {
+                let mut parts = entry.split(';');
+                let name = parts.next().unwrap_or("").trim();
+                let refused = parts.any(|param| {
+                    param
+                        .trim()
+                        .strip_prefix("q=")
+                        .and_then(|q| q.trim().parse::<f32>().ok())
+                        .is_some_and(|q| q <= 0.0)
+                });
+                !refused && names.iter().any(|n| name.eq_ignore_ascii_case(n))
+            })
+        })
+}
+
+/// Body source: shared bytes, or a spilled file streamed from disk.
+enum Payload {
+    Bytes(Bytes),
+    File { path: PathBuf, len: u64 },
+}
+
+impl Payload {
+    fn len(&self) -> usize {
+        match self {
+            Self::Bytes(bytes) => bytes.len(),
+            Self::File { len, .. } => *len as usize,
+        }
+    }
+
+    async fn into_body(self, range: Range<usize>) -> std::io::Result<Body> {
+        match self {
+            Self::Bytes(bytes) => Ok(Body::from(bytes.slice(range))),
+            Self::File { path, .. } => {
+                let mut file = tokio::fs::File::open(&path).await?;
+                if range.start > 0 {
+                    file.seek(SeekFrom::Start(range.start as u64)).await?;
+                }
+                let reader = file.take((range.end - range.start) as u64);
+                Ok(Body::from_stream(ReaderStream::new(reader)))
+            }
+        }
+    }
+}
+
+fn build_response(builder: Builder, body: Body, wasm_module_id: Uuid) -> Response<Body> {
+    match builder.body(body) {
+        Ok(response) => response,
+        Err(e) => {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to build WASM response");
+            let mut response = Response::new(Body::from("Failed to build WASM response"));
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        }
+    }
+}
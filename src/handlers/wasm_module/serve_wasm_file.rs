@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::{HeaderMap, Response, StatusCode, header, response::Builder},
+    response::IntoResponse,
+};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    domain::wasm_module::wasm_module::WasmModuleVisibility,
+    init::state::ServerState,
+    routers::middleware::is_logged_in::AuthSession,
+    util::{
+        http::conditional::{RangeRequest, content_range, if_none_match_matches, resolve_range},
+        wasm_archive::normalize_archive_path,
+    },
+};
+
+/// GET /api/wasm-modules/{wasm_module_id}/files/{*file_path}
+/// Public endpoint - serves one file of a module uploaded as a zip, with the
+/// MIME type recorded at upload. A path ending in `/` means its `index.html`
+/// Same visibility rules as `/wasm`: hidden modules answer 404
+/// Files carry their SHA-256 as a strong ETag (304 on `If-None-Match`) and
+/// honor a single `Range`
+#[utoipa::path(
+    get,
+    path = "/api/wasm-modules/{wasm_module_id}/files/{file_path}",
+    tag = "wasm_module",
+    params(
+        ("wasm_module_id" = Uuid, Path, description = "WASM module UUID"),
+        ("file_path" = String, Path, description = "Path within the uploaded archive")
+    ),
+    responses(
+        (status = 200, description = "Module file"),
+        (status = 206, description = "Requested byte range of the file"),
+        (status = 304, description = "Client copy matches the ETag"),
+        (status = 404, description = "WASM module or file not found"),
+        (status = 416, description = "Range not satisfiable")
+    )
+)]
+pub async fn serve_wasm_file(
+    Extension(auth_session): Extension<Option<AuthSession>>,
+    State(state): State<Arc<ServerState>>,
+    Path((wasm_module_id, file_path)): Path<(Uuid, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let viewer = auth_session.as_ref().map(|s| s.user_id);
+    let is_superuser = auth_session
+        .as_ref()
+        .is_some_and(|s| s.role_type.is_superuser());
+    // Hidden modules look exactly like missing ones.
+    let visibility = match state.get_wasm_module_access(wasm_module_id).await {
+        Some(access) if access.can_load(viewer, is_superuser) => access.visibility,
+        _ => return status_response(StatusCode::NOT_FOUND, "WASM module not found"),
+    };
+
+    let requested = if file_path.is_empty() || file_path.ends_with('/') {
+        format!("{file_path}index.html")
+    } else {
+        file_path
+    };
+    let Some(path) = normalize_archive_path(&requested) else {
+        return status_response(StatusCode::NOT_FOUND, "File not found");
+    };
+
+    let file = match state.get_wasm_module_file(wasm_module_id, &path).await {
+        Ok(Some(file)) => file,
+        Ok(None) => return status_response(StatusCode::NOT_FOUND, "File not found"),
+        Err(e) => {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, path = %path, "Failed to look up WASM module file");
+            return status_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load file");
+        }
+    };
+
+    let etag = format!("\"{}\"", file.wasm_module_file_sha256);
+    let mut response = Response::builder()
+        .header(header::ETAG, etag.as_str())
+        .header(
+            header::CONTENT_TYPE,
+            file.wasm_module_file_content_type.as_str(),
+        )
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header(header::ACCEPT_RANGES, "bytes");
+    // Paths are stable across re-uploads while their content is not, so
+    // clients revalidate instead of caching forever.
+    response = if visibility == WasmModuleVisibility::SuperuserOnly {
+        response.header(header::CACHE_CONTROL, "private, max-age=3600")
+    } else {
+        response
+            .header(header::CACHE_CONTROL, "public, max-age=3600")
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+    };
+
+    if if_none_match_matches(&headers, &etag) {
+        return build_response(response.status(StatusCode::NOT_MODIFIED), Body::empty());
+    }
+
+    let bytes = match state.storage.get(&file.wasm_module_file_key).await {
+        Ok(Some(object)) => Bytes::from(object.bytes),
+        Ok(None) => {
+            error!(wasm_module_id = %wasm_module_id, key = %file.wasm_module_file_key, "WASM module file object is missing");
+            return status_response(StatusCode::NOT_FOUND, "File not found");
+        }
+        Err(e) => {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, key = %file.wasm_module_file_key, "Failed to fetch WASM module file");
+            return status_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load file");
+        }
+    };
+
+    let len = bytes.len();
+    match resolve_range(&headers, len, &etag) {
+        RangeRequest::Full => build_response(response.status(StatusCode::OK), Body::from(bytes)),
+        RangeRequest::Partial(range) => build_response(
+            response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, content_range(&range, len)),
+            Body::from(bytes.slice(range)),
+        ),
+        RangeRequest::Unsatisfiable => build_response(
+            response
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{len}")),
+            Body::empty(),
+        ),
+    }
+}
+
+fn status_response(status: StatusCode, message: &'static str) -> Response<Body> {
+    let mut response = Response::new(Body::from(message));
+    *response.status_mut() = status;
+    response
+}
+
+fn build_response(builder: Builder, body: Body) -> Response<Body> {
+    match builder.body(body) {
+        Ok(response) => response,
+        Err(e) => {
+            error!(error = ?e, "Failed to build WASM module file response");
+            status_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to build WASM module file response",
+            )
+        }
+    }
+}
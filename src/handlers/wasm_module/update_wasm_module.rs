@@ -6,25 +6,33 @@ use axum::{
     response::IntoResponse,
 };
 use chrono::Utc;
-use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 use tracing::{error, info};
 use uuid::Uuid;
 
 use crate::{
-    domain::wasm_module::wasm_module::{WasmModuleChangeset, WasmModuleMetadata},
+    domain::{
+        audit::audit::{AdminAction, changed_fields},
+        wasm_module::wasm_module::{
+            WasmModuleAllowedUserInsertable, WasmModuleChangeset, WasmModuleMetadata,
+            normalize_wasm_module_label, normalize_wasm_module_tags,
+        },
+    },
     dto::{
         requests::wasm_module::UpdateWasmModuleRequest,
         responses::{response_data::http_resp, wasm_module::WasmModuleItem},
     },
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
-    schema::wasm_module,
-    util::time::now::tokio_now,
+    schema::{users, wasm_module, wasm_module_allowed_users},
+    util::{db::transaction::ScopedFutureExt, extract::AdminActor, time::now::tokio_now},
 };
 
 /// PATCH /api/wasm-modules/{wasm_module_id}
-/// Superuser only - updates WASM module metadata (title/description)
+/// Superuser only - updates WASM module metadata (title/description,
+/// category/tags) and access (visibility, allowed users; lists are replaced
+/// when present)
 #[utoipa::path(
     patch,
     path = "/api/wasm-modules/{wasm_module_id}",
@@ -35,56 +43,191 @@ use crate::{
     request_body = UpdateWasmModuleRequest,
     responses(
         (status = 200, description = "WASM module updated", body = WasmModuleItem),
+        (status = 400, description = "Invalid category or tags", body = CodeErrorResp),
         (status = 401, description = "Unauthorized", body = CodeErrorResp),
         (status = 403, description = "Forbidden (not superuser)", body = CodeErrorResp),
         (status = 404, description = "WASM module not found", body = CodeErrorResp),
+        (status = 409, description = "Module changed since `wasm_module_version`; `latest` holds the current module", body = CodeErrorResp),
         (status = 500, description = "Internal server error", body = CodeErrorResp)
     )
 )]
 pub async fn update_wasm_module(
     Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
     Path(wasm_module_id): Path<Uuid>,
     Json(body): Json<UpdateWasmModuleRequest>,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
+    let category = body
+        .wasm_module_category
+        .map(|c| normalize_wasm_module_label(&c))
+        .transpose()
+        .map_err(|e| code_err(CodeError::INVALID_REQUEST, e))?;
+    let tags = body
+        .wasm_module_tags
+        .map(normalize_wasm_module_tags)
+        .transpose()
+        .map_err(|e| code_err(CodeError::INVALID_REQUEST, e))?;
+
     // Build changeset
     let changeset = WasmModuleChangeset {
         wasm_module_title: body.wasm_module_title,
         wasm_module_description: body.wasm_module_description,
+        wasm_module_visibility: body.wasm_module_visibility,
+        wasm_module_category: category,
+        wasm_module_tags: tags,
         wasm_module_updated_at: Some(Utc::now()),
     };
 
+    let mut allowed_user_ids = body.wasm_module_allowed_user_ids;
+    if let Some(ids) = allowed_user_ids.as_mut() {
+        ids.sort_unstable();
+        ids.dedup();
+    }
+
     let mut conn = state.get_conn().await.map_err(|e| {
         error!(error = ?e, "Failed to get DB connection");
-        code_err(CodeError::POOL_ERROR, e)
+        pool_err(e)
     })?;
 
-    let updated: WasmModuleMetadata = diesel::update(
-        wasm_module::table.filter(wasm_module::wasm_module_id.eq(wasm_module_id)),
-    )
-    .set(&changeset)
-    .returning(WasmModuleMetadata::as_returning())
-    .get_result(&mut conn)
-    .await
-    .map_err(|e| {
-        error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to update WASM module");
-        match e {
-            diesel::result::Error::NotFound => {
-                code_err(CodeError::DB_QUERY_ERROR, "WASM module not found")
-            }
-            _ => code_err(CodeError::DB_UPDATE_ERROR, e),
+    if let Some(ids) = &allowed_user_ids {
+        let existing: i64 = users::table
+            .filter(users::user_id.eq_any(ids))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .map_err(|e| {
+                error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to query allowed users");
+                code_err(CodeError::DB_QUERY_ERROR, e)
+            })?;
+        if existing != ids.len() as i64 {
+            return Err(code_err(
+                CodeError::USER_NOT_FOUND,
+                "One or more allowed users not found",
+            ));
         }
-    })?;
+    }
 
     drop(conn);
 
+    let updated: WasmModuleMetadata = state
+        .transaction(|conn| {
+            let state = &state;
+            let changeset = &changeset;
+            let allowed_user_ids = &allowed_user_ids;
+            async move {
+                // Locked so a concurrent edit cannot slip in between the version
+                // check and the update.
+                let current: WasmModuleMetadata = wasm_module::table
+                    .filter(wasm_module::wasm_module_id.eq(wasm_module_id))
+                    .select(WasmModuleMetadata::as_select())
+                    .for_update()
+                    .first(&mut *conn)
+                    .await
+                    .optional()
+                    .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+                    .ok_or_else(|| code_err(CodeError::DB_QUERY_ERROR, "WASM module not found"))?;
+                if body
+                    .wasm_module_version
+                    .is_some_and(|version| version != current.wasm_module_version)
+                {
+                    let current_version = current.wasm_module_version;
+                    return Err(code_err(
+                        CodeError::EDIT_CONFLICT,
+                        format!(
+                            "WASM module {wasm_module_id} is at version {current_version}, edit was based on {:?}",
+                            body.wasm_module_version
+                        ),
+                    )
+                    .with_latest(&state.deliver_wasm_module_item(WasmModuleItem::from(current))));
+                }
+
+                let updated: WasmModuleMetadata = diesel::update(
+                    wasm_module::table.filter(wasm_module::wasm_module_id.eq(wasm_module_id)),
+                )
+                .set((
+                    changeset,
+                    wasm_module::wasm_module_version.eq(wasm_module::wasm_module_version + 1),
+                ))
+                .returning(WasmModuleMetadata::as_returning())
+                .get_result(&mut *conn)
+                .await
+                .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
+
+                if let Some(ids) = allowed_user_ids {
+                    diesel::delete(
+                        wasm_module_allowed_users::table
+                            .filter(wasm_module_allowed_users::wasm_module_id.eq(wasm_module_id)),
+                    )
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| code_err(CodeError::DB_UPDATE_ERROR, e))?;
+                    let rows: Vec<WasmModuleAllowedUserInsertable> = ids
+                        .iter()
+                        .map(|&user_id| WasmModuleAllowedUserInsertable {
+                            wasm_module_id,
+                            user_id,
+                        })
+                        .collect();
+                    if !rows.is_empty() {
+                        diesel::insert_into(wasm_module_allowed_users::table)
+                            .values(&rows)
+                            .execute(&mut *conn)
+                            .await
+                            .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+                    }
+                }
+
+                Ok(updated)
+            }
+            .scope_boxed()
+        })
+        .await
+        .inspect_err(|e| {
+            if e.http_status_code.is_server_error() {
+                error!(error = %e, wasm_module_id = %wasm_module_id, "Failed to update WASM module");
+            }
+        })?;
+
+    let access = state
+        .refresh_wasm_module_access(wasm_module_id)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to refresh WASM module access");
+            code_err(CodeError::DB_QUERY_ERROR, e)
+        })?;
+
+    state.index_wasm_module(&updated.search_doc());
+
     info!(
         wasm_module_id = %wasm_module_id,
         user_id = %user_id,
         "WASM module updated"
     );
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::WasmModuleUpdate,
+            Some(wasm_module_id.to_string()),
+            changed_fields(serde_json::json!({
+                "title": changeset.wasm_module_title,
+                "description": changeset.wasm_module_description,
+                "visibility": changeset.wasm_module_visibility,
+                // "" when cleared.
+                "category": changeset
+                    .wasm_module_category
+                    .as_ref()
+                    .map(|category| category.as_deref().unwrap_or_default()),
+                "tags": changeset.wasm_module_tags,
+                "allowed_user_ids": allowed_user_ids,
+            })),
+        )
+        .await;
+
+    let mut item = WasmModuleItem::from(updated);
+    item.wasm_module_allowed_user_ids = access.map(|a| a.allowed_user_ids);
 
-    Ok(http_resp(WasmModuleItem::from(updated), (), start))
+    Ok(http_resp(state.deliver_wasm_module_item(item), (), start))
 }
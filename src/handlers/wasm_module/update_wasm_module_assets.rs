@@ -6,18 +6,26 @@ use axum::{
     response::IntoResponse,
 };
 use chrono::Utc;
-use diesel::{AsChangeset, ExpressionMethods, QueryDsl};
+use diesel::{AsChangeset, ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::RunQueryDsl;
 use tracing::{error, info};
 use uuid::Uuid;
 
 use crate::{
-    domain::wasm_module::wasm_module::WasmModule,
+    domain::{
+        audit::audit::{AdminAction, changed_fields},
+        wasm_module::{
+            bundle_storage::StoredWasmBundle,
+            files::{WasmModuleFileInsertable, wasm_module_link},
+            wasm_module::WasmModule,
+        },
+    },
     dto::responses::{response_data::http_resp, wasm_module::WasmModuleItem},
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
     schema::wasm_module,
     util::{
+        extract::AdminActor,
         image::{
             map_image_format_to_db_enum::map_image_format_to_str,
             process_uploaded_images::{
@@ -25,13 +33,13 @@ use crate::{
             },
         },
         time::now::tokio_now,
-        wasm_bundle::{looks_like_html, normalize_bundle_bytes},
+        wasm_archive::{UnpackedArchive, is_zip_archive, prepare_uploaded_bundle},
+        wasm_bundle::{NormalizedBundle, looks_like_html},
     },
 };
 
 const MAX_BUNDLE_SIZE: usize = 1024 * 1024 * 50; // 50MB
 const MAX_THUMBNAIL_SIZE: usize = 1024 * 1024 * 10; // 10MB
-const AWS_S3_BUCKET_NAME: &str = "cyhdev-img";
 
 #[derive(AsChangeset, Default)]
 #[diesel(table_name = wasm_module)]
@@ -39,12 +47,20 @@ struct WasmModuleAssetsChangeset {
     wasm_module_title: Option<String>,
     wasm_module_description: Option<String>,
     wasm_module_thumbnail_link: Option<String>,
-    wasm_module_bundle_gz: Option<Vec<u8>>,
+    wasm_module_link: Option<String>,
+    wasm_module_bundle_gz: Option<Option<Vec<u8>>>,
+    wasm_module_bundle_key: Option<Option<String>>,
+    wasm_module_bundle_br: Option<Option<Vec<u8>>>,
+    wasm_module_bundle_br_key: Option<Option<String>>,
+    wasm_module_bundle_integrity: Option<Option<String>>,
+    wasm_module_bundle_sha256: Option<String>,
+    wasm_module_bundle_size_bytes: Option<i64>,
     wasm_module_updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// POST /api/wasm-modules/{wasm_module_id}/assets
-/// Superuser only - updates WASM module bundle/thumbnail and optional metadata.
+/// Superuser only - updates WASM module bundle (single file or .zip tree)/thumbnail
+/// and optional metadata.
 #[utoipa::path(
     post,
     path = "/api/wasm-modules/{wasm_module_id}/assets",
@@ -64,6 +80,7 @@ struct WasmModuleAssetsChangeset {
 )]
 pub async fn update_wasm_module_assets(
     Extension(_user_id): Extension<Uuid>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
     Path(wasm_module_id): Path<Uuid>,
     mut multipart: Multipart,
@@ -73,6 +90,7 @@ pub async fn update_wasm_module_assets(
     let mut bundle_bytes: Option<Vec<u8>> = None;
     let mut bundle_is_gzipped = false;
     let mut bundle_is_html = false;
+    let mut bundle_is_zip = false;
     let mut thumbnail_bytes: Option<Vec<u8>> = None;
     let mut title: Option<String> = None;
     let mut description: Option<String> = None;
@@ -102,6 +120,19 @@ pub async fn update_wasm_module_assets(
                     ));
                 }
 
+                bundle_is_zip = is_zip_archive(&bytes)
+                    || file_name
+                        .as_deref()
+                        .is_some_and(|name| name.ends_with(".zip"))
+                    || content_type.as_deref().is_some_and(|ct| {
+                        ct.starts_with("application/zip")
+                            || ct.starts_with("application/x-zip-compressed")
+                    });
+                if bundle_is_zip {
+                    bundle_bytes = Some(bytes.to_vec());
+                    continue;
+                }
+
                 let gzip_magic = bytes.len() >= 2 && bytes[0] == 0x1f && bytes[1] == 0x8b;
                 bundle_is_gzipped = gzip_magic
                     || file_name
@@ -216,13 +247,16 @@ pub async fn update_wasm_module_assets(
         }
     }
 
-    let mut bundle_gz_for_db: Option<Vec<u8>> = None;
-    let mut bundle_cache_entry: Option<(Vec<u8>, &'static str)> = None;
+    let mut stored_bundle: Option<StoredWasmBundle> = None;
+    let mut bundle_cache_entry: Option<NormalizedBundle> = None;
+    let mut link: Option<String> = None;
+    let mut file_rows: Option<Vec<WasmModuleFileInsertable>> = None;
 
     if let Some(bundle_bytes) = bundle_bytes {
-        let normalized_bundle = tokio::task::spawn_blocking(move || {
-            normalize_bundle_bytes(
+        let (normalized_bundle, archive) = tokio::task::spawn_blocking(move || {
+            prepare_uploaded_bundle(
                 &bundle_bytes,
+                bundle_is_zip,
                 bundle_is_gzipped,
                 bundle_is_html,
                 MAX_BUNDLE_SIZE,
@@ -241,13 +275,45 @@ pub async fn update_wasm_module_assets(
         info!(
             wasm_module_id = %wasm_module_id,
             size_bytes = normalized_bundle.gz_bytes.len(),
-            is_html = bundle_is_html,
+            br_size_bytes = normalized_bundle.br_bytes.len(),
+            is_html = bundle_is_html || bundle_is_zip,
             is_gzipped = true,
-            "Prepared updated WASM bundle for database storage"
+            archive_files = archive.as_ref().map(|a| a.files.len()),
+            "Prepared updated WASM bundle for storage"
         );
 
-        bundle_gz_for_db = Some(normalized_bundle.gz_bytes.clone());
-        bundle_cache_entry = Some((normalized_bundle.gz_bytes, normalized_bundle.content_type));
+        let stored = state
+            .persist_wasm_bundle(
+                wasm_module_id,
+                normalized_bundle.gz_bytes.clone(),
+                normalized_bundle.br_bytes.clone(),
+            )
+            .await
+            .map_err(|e| {
+                error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to store WASM bundle");
+                code_err(CodeError::FILE_UPLOAD_ERROR, e)
+            })?;
+
+        // Files are swapped in once the row update has gone through.
+        let (entry, rows) = match archive {
+            Some(UnpackedArchive { files, entry }) => {
+                match state.store_wasm_module_files(wasm_module_id, files).await {
+                    Ok(rows) => (Some(entry), rows),
+                    Err(e) => {
+                        error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to store WASM module files");
+                        for key in stored.object_keys() {
+                            state.delete_wasm_bundle_object(key).await;
+                        }
+                        return Err(code_err(CodeError::FILE_UPLOAD_ERROR, e));
+                    }
+                }
+            }
+            None => (None, Vec::new()),
+        };
+        link = Some(wasm_module_link(wasm_module_id, entry.as_deref()));
+        file_rows = Some(rows);
+        stored_bundle = Some(stored);
+        bundle_cache_entry = Some(normalized_bundle);
     }
 
     let mut thumbnail_url: Option<String> = None;
@@ -263,85 +329,155 @@ pub async fn update_wasm_module_assets(
         let (thumb_ext, _) = map_image_format_to_str(IMAGE_ENCODING_FORMAT);
         let thumbnail_path = format!("wasm-thumbnails/{}.{}", wasm_module_id, thumb_ext);
 
-        let s3_client = aws_sdk_s3::Client::new(&state.aws_profile_picture_config);
-        s3_client
-            .put_object()
-            .bucket(AWS_S3_BUCKET_NAME)
-            .key(&thumbnail_path)
-            .content_type("image/avif")
-            .body(aws_sdk_s3::primitives::ByteStream::from(
-                processed_thumbnail,
-            ))
-            .send()
+        state
+            .storage
+            .put(&thumbnail_path, processed_thumbnail, "image/avif")
             .await
             .map_err(|e| {
-                error!(error = ?e, "Failed to upload thumbnail to S3");
+                error!(error = ?e, key = %thumbnail_path, "Failed to upload thumbnail");
                 code_err(CodeError::FILE_UPLOAD_ERROR, e)
             })?;
 
-        let s3_region = state
-            .aws_profile_picture_config
-            .region()
-            .map(|r| r.to_string())
-            .unwrap_or_else(|| "us-west-1".to_string());
-
-        thumbnail_url = Some(format!(
-            "https://{}.s3.{}.amazonaws.com/{}",
-            AWS_S3_BUCKET_NAME, s3_region, thumbnail_path
-        ));
+        thumbnail_url = Some(state.storage.public_url(&thumbnail_path));
+        // Same key as before when the extension is unchanged; drop the cached copy.
+        state.invalidate_cdn_keys(vec![thumbnail_path]);
     }
 
     let mut conn = state.get_conn().await.map_err(|e| {
         error!(error = ?e, "Failed to get DB connection");
-        code_err(CodeError::POOL_ERROR, e)
+        pool_err(e)
     })?;
 
-    let changeset = WasmModuleAssetsChangeset {
+    // Objects the row pointed at before a bundle replacement, if any.
+    let previous_bundle_keys: Vec<String> = if stored_bundle.is_some() {
+        wasm_module::table
+            .select((
+                wasm_module::wasm_module_bundle_key,
+                wasm_module::wasm_module_bundle_br_key,
+            ))
+            .filter(wasm_module::wasm_module_id.eq(wasm_module_id))
+            .first::<(Option<String>, Option<String>)>(&mut conn)
+            .await
+            .optional()
+            .map_err(|e| code_err(CodeError::DB_QUERY_ERROR, e))?
+            .map(|(key, br_key)| key.into_iter().chain(br_key).collect())
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let new_bundle_keys: Vec<String> = stored_bundle
+        .as_ref()
+        .map(|b| b.object_keys().cloned().collect())
+        .unwrap_or_default();
+    let new_file_keys: Vec<String> = file_rows
+        .iter()
+        .flatten()
+        .map(|r| r.wasm_module_file_key.clone())
+        .collect();
+
+    let mut changeset = WasmModuleAssetsChangeset {
         wasm_module_title: title,
         wasm_module_description: description,
         wasm_module_thumbnail_link: thumbnail_url,
-        wasm_module_bundle_gz: bundle_gz_for_db,
+        wasm_module_link: link,
         wasm_module_updated_at: Some(Utc::now()),
+        ..Default::default()
     };
+    if let Some(stored) = stored_bundle {
+        changeset.wasm_module_bundle_gz = Some(stored.bundle_gz);
+        changeset.wasm_module_bundle_key = Some(stored.bundle_key);
+        changeset.wasm_module_bundle_br = Some(stored.bundle_br);
+        changeset.wasm_module_bundle_br_key = Some(stored.bundle_br_key);
+        changeset.wasm_module_bundle_sha256 = Some(stored.sha256);
+        changeset.wasm_module_bundle_size_bytes = Some(stored.size_bytes);
+    }
+    if let Some(bundle) = &bundle_cache_entry {
+        changeset.wasm_module_bundle_integrity = Some(Some(bundle.integrity.clone()));
+    }
 
-    let updated: WasmModule = diesel::update(
-        wasm_module::table.filter(wasm_module::wasm_module_id.eq(wasm_module_id)),
-    )
-    .set(&changeset)
-    .get_result(&mut conn)
-    .await
-    .map_err(|e| {
-        error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to update WASM module");
-        match e {
-            diesel::result::Error::NotFound => {
-                code_err(CodeError::DB_QUERY_ERROR, "WASM module not found")
-            }
-            _ => code_err(CodeError::DB_UPDATE_ERROR, e),
-        }
-    })?;
+    let updated: Result<WasmModule, _> =
+        diesel::update(wasm_module::table.filter(wasm_module::wasm_module_id.eq(wasm_module_id)))
+            .set(&changeset)
+            .get_result(&mut conn)
+            .await;
 
     drop(conn);
 
-    let (cache_bytes, content_type) = match bundle_cache_entry {
-        Some((gz_bytes, content_type)) => (gz_bytes, content_type),
-        None => {
-            let content_type = crate::util::wasm_bundle::sniff_content_type_from_gzip_bytes(
-                &updated.wasm_module_bundle_gz,
-            )
-            .map_err(|e| {
-                error!(
-                    error = ?e,
-                    wasm_module_id = %wasm_module_id,
-                    "Failed to detect bundle content type while refreshing WASM cache"
-                );
-                code_err(CodeError::DB_UPDATE_ERROR, e)
-            })?;
-            (updated.wasm_module_bundle_gz.clone(), content_type)
+    let updated = match updated {
+        Ok(updated) => updated,
+        Err(e) => {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to update WASM module");
+            // Except the very objects the row still points at.
+            for key in new_bundle_keys
+                .iter()
+                .filter(|k| !previous_bundle_keys.contains(k))
+            {
+                state.delete_wasm_bundle_object(key).await;
+            }
+            // New file objects are content-addressed too; ones the old tree
+            // shares stay referenced and are skipped.
+            if let Ok(current) = state.wasm_module_file_keys(wasm_module_id).await {
+                for key in new_file_keys.iter().filter(|k| !current.contains(k)) {
+                    state.delete_wasm_bundle_object(key).await;
+                }
+            }
+            return Err(match e {
+                diesel::result::Error::NotFound => {
+                    code_err(CodeError::DB_QUERY_ERROR, "WASM module not found")
+                }
+                _ => code_err(CodeError::DB_UPDATE_ERROR, e),
+            });
         }
     };
+
+    for previous in previous_bundle_keys
+        .iter()
+        .filter(|k| !new_bundle_keys.contains(k))
+    {
+        state.delete_wasm_bundle_object(previous).await;
+    }
+
+    // A new bundle replaces the whole file tree; a single-file one clears it.
+    if let Some(rows) = file_rows
+        && let Err(e) = state.replace_wasm_module_files(wasm_module_id, rows).await
+    {
+        error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to replace WASM module files");
+        return Err(code_err(CodeError::DB_UPDATE_ERROR, e));
+    }
+
+    state.index_wasm_module(&updated.search_doc());
+
+    // Metadata-only updates leave the cached bundle as it is.
+    if let Some(bundle) = bundle_cache_entry {
+        state
+            .upsert_wasm_module_cache(
+                wasm_module_id,
+                bundle.gz_bytes,
+                Some(bundle.br_bytes),
+                bundle.content_type,
+                Some(bundle.integrity),
+            )
+            .await;
+    }
+
     state
-        .upsert_wasm_module_cache(wasm_module_id, cache_bytes, content_type)
+        .record_admin_action(
+            &actor,
+            AdminAction::WasmModuleAssetsUpdate,
+            Some(wasm_module_id.to_string()),
+            changed_fields(serde_json::json!({
+                "title": changeset.wasm_module_title,
+                "description": changeset.wasm_module_description,
+                "thumbnail_link": changeset.wasm_module_thumbnail_link,
+                "bundle_sha256": changeset.wasm_module_bundle_sha256,
+                "bundle_size_bytes": changeset.wasm_module_bundle_size_bytes,
+            })),
+        )
         .await;
 
-    Ok(http_resp(WasmModuleItem::from(updated), (), start))
+    Ok(http_resp(
+        state.deliver_wasm_module_item(WasmModuleItem::from(updated)),
+        (),
+        start,
+    ))
 }
@@ -3,20 +3,30 @@ use std::sync::Arc;
 use axum::{
     Extension,
     extract::{Multipart, State},
+    http::HeaderMap,
     response::IntoResponse,
 };
 use chrono::Utc;
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncConnection, RunQueryDsl};
 use tracing::{error, info};
 use uuid::Uuid;
 
 use crate::{
-    domain::wasm_module::wasm_module::{WasmModule, WasmModuleInsertable},
+    domain::{
+        audit::audit::AdminAction,
+        upload_progress::{UploadKind, UploadStage},
+        wasm_module::files::wasm_module_link,
+        wasm_module::wasm_module::{
+            WasmModule, WasmModuleAccess, WasmModuleInsertable, WasmModuleVisibility,
+            normalize_wasm_module_label, normalize_wasm_module_tags,
+        },
+    },
     dto::responses::{response_data::http_resp, wasm_module::WasmModuleItem},
-    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err},
+    errors::code_error::{CodeError, CodeErrorResp, HandlerResponse, code_err, pool_err},
     init::state::ServerState,
-    schema::wasm_module,
+    schema::{wasm_module, wasm_module_files},
     util::{
+        extract::AdminActor,
         image::{
             map_image_format_to_db_enum::map_image_format_to_str,
             process_uploaded_images::{
@@ -24,27 +34,34 @@ use crate::{
             },
         },
         time::now::tokio_now,
-        wasm_bundle::{looks_like_html, normalize_bundle_bytes},
+        wasm_archive::{UnpackedArchive, is_zip_archive, prepare_uploaded_bundle},
+        wasm_bundle::looks_like_html,
     },
 };
 
 const MAX_BUNDLE_SIZE: usize = 1024 * 1024 * 50; // 50MB
 const MAX_THUMBNAIL_SIZE: usize = 1024 * 1024 * 10; // 10MB
-const AWS_S3_BUCKET_NAME: &str = "cyhdev-img";
 
 /// POST /api/wasm-modules
 /// Superuser only - uploads a new WASM module bundle with thumbnail
 ///
 /// Multipart fields:
-/// - `bundle_file` or `wasm_file`: The HTML bundle (.html or .html.gz) or WASM file (required)
+/// - `bundle_file` or `wasm_file`: The HTML bundle (.html or .html.gz), WASM file, or a .zip
+///   of wasm + JS glue + assets with an `index.html` entry page (required)
 /// - `thumbnail`: The thumbnail image (required)
 /// - `title`: Module title (required)
 /// - `description`: Module description (required)
+/// - `visibility`: `public` (default), `unlisted` or `superuser_only`
+/// - `category`: Optional category label
+/// - `tags`: Optional comma-separated tags
+///
+/// Send `X-Upload-Id` to follow progress on `/api/uploads/{upload_id}/progress`.
 #[utoipa::path(
     post,
     path = "/api/wasm-modules",
     tag = "wasm_module",
     request_body(content_type = "multipart/form-data"),
+    params(("x-upload-id" = Option<Uuid>, Header, description = "Report progress on /api/uploads/{upload_id}/progress")),
     responses(
         (status = 200, description = "WASM module uploaded successfully", body = WasmModuleItem),
         (status = 400, description = "Invalid upload payload", body = CodeErrorResp),
@@ -55,20 +72,31 @@ const AWS_S3_BUCKET_NAME: &str = "cyhdev-img";
 )]
 pub async fn upload_wasm_module(
     Extension(user_id): Extension<Uuid>,
+    actor: AdminActor,
     State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
     mut multipart: Multipart,
 ) -> HandlerResponse<impl IntoResponse> {
     let start = tokio_now();
 
+    let progress = state
+        .track_upload_progress(&headers, user_id, UploadKind::WasmModule)
+        .await;
+    let mut bytes_received: u64 = 0;
+
     let mut bundle_bytes: Option<Vec<u8>> = None;
     let mut bundle_is_gzipped = false;
     let mut bundle_is_html = false;
+    let mut bundle_is_zip = false;
     let mut thumbnail_bytes: Option<Vec<u8>> = None;
     let mut title: Option<String> = None;
     let mut description: Option<String> = None;
+    let mut visibility = WasmModuleVisibility::Public;
+    let mut category: Option<String> = None;
+    let mut tags: Vec<String> = Vec::new();
 
     // Process multipart fields
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
+    while let Some(mut field) = multipart.next_field().await.map_err(|e| {
         error!(error = ?e, "Failed to read multipart field");
         code_err(CodeError::FILE_UPLOAD_ERROR, e)
     })? {
@@ -78,19 +106,38 @@ pub async fn upload_wasm_module(
             Some("bundle_file") | Some("wasm_file") | Some("wasm") => {
                 let file_name = field.file_name().map(|s| s.to_string());
                 let content_type = field.content_type().map(|s| s.to_string());
-                let bytes = field.bytes().await.map_err(|e| {
+                let mut bytes: Vec<u8> = Vec::new();
+                while let Some(chunk) = field.chunk().await.map_err(|e| {
                     error!(error = ?e, "Failed to read bundle file bytes");
                     code_err(CodeError::FILE_UPLOAD_ERROR, e)
-                })?;
+                })? {
+                    if bytes.len() + chunk.len() > MAX_BUNDLE_SIZE {
+                        return Err(code_err(
+                            CodeError::FILE_UPLOAD_ERROR,
+                            format!(
+                                "Bundle file too large (max {}MB)",
+                                MAX_BUNDLE_SIZE / 1024 / 1024
+                            ),
+                        ));
+                    }
+                    bytes.extend_from_slice(&chunk);
+                    bytes_received += chunk.len() as u64;
+                    if let Some(progress) = &progress {
+                        progress.received(bytes_received);
+                    }
+                }
 
-                if bytes.len() > MAX_BUNDLE_SIZE {
-                    return Err(code_err(
-                        CodeError::FILE_UPLOAD_ERROR,
-                        format!(
-                            "Bundle file too large (max {}MB)",
-                            MAX_BUNDLE_SIZE / 1024 / 1024
-                        ),
-                    ));
+                bundle_is_zip = is_zip_archive(&bytes)
+                    || file_name
+                        .as_deref()
+                        .is_some_and(|name| name.ends_with(".zip"))
+                    || content_type.as_deref().is_some_and(|ct| {
+                        ct.starts_with("application/zip")
+                            || ct.starts_with("application/x-zip-compressed")
+                    });
+                if bundle_is_zip {
+                    bundle_bytes = Some(bytes);
+                    continue;
                 }
 
                 let gzip_magic = bytes.len() >= 2 && bytes[0] == 0x1f && bytes[1] == 0x8b;
@@ -157,7 +204,7 @@ pub async fn upload_wasm_module(
                     }
                 }
 
-                bundle_bytes = Some(bytes.to_vec());
+                bundle_bytes = Some(bytes);
             }
 
             Some("thumbnail") | Some("thumbnail_file") => {
@@ -176,6 +223,10 @@ pub async fn upload_wasm_module(
                     ));
                 }
 
+                bytes_received += bytes.len() as u64;
+                if let Some(progress) = &progress {
+                    progress.received(bytes_received);
+                }
                 thumbnail_bytes = Some(bytes.to_vec());
             }
 
@@ -195,6 +246,37 @@ pub async fn upload_wasm_module(
                 description = Some(text);
             }
 
+            Some("visibility") | Some("wasm_module_visibility") => {
+                let text = field.text().await.map_err(|e| {
+                    error!(error = ?e, "Failed to read visibility field");
+                    code_err(CodeError::FILE_UPLOAD_ERROR, e)
+                })?;
+                visibility = WasmModuleVisibility::from_str(&text).ok_or_else(|| {
+                    code_err(
+                        CodeError::FILE_UPLOAD_ERROR,
+                        "Invalid visibility; expected public, unlisted or superuser_only",
+                    )
+                })?;
+            }
+
+            Some("category") | Some("wasm_module_category") => {
+                let text = field.text().await.map_err(|e| {
+                    error!(error = ?e, "Failed to read category field");
+                    code_err(CodeError::FILE_UPLOAD_ERROR, e)
+                })?;
+                category = normalize_wasm_module_label(&text)
+                    .map_err(|e| code_err(CodeError::FILE_UPLOAD_ERROR, e))?;
+            }
+
+            Some("tags") | Some("wasm_module_tags") => {
+                let text = field.text().await.map_err(|e| {
+                    error!(error = ?e, "Failed to read tags field");
+                    code_err(CodeError::FILE_UPLOAD_ERROR, e)
+                })?;
+                tags = normalize_wasm_module_tags(text.split(','))
+                    .map_err(|e| code_err(CodeError::FILE_UPLOAD_ERROR, e))?;
+            }
+
             Some(other) => {
                 info!(field = other, "Ignoring unknown multipart field");
             }
@@ -219,9 +301,15 @@ pub async fn upload_wasm_module(
     // Generate UUID for the module
     let wasm_module_id = Uuid::new_v4();
 
-    let normalized_bundle = tokio::task::spawn_blocking(move || {
-        normalize_bundle_bytes(
+    if let Some(progress) = &progress {
+        progress.resource(wasm_module_id);
+        progress.stage(UploadStage::Processing);
+    }
+
+    let (normalized_bundle, archive) = tokio::task::spawn_blocking(move || {
+        prepare_uploaded_bundle(
             &bundle_bytes,
+            bundle_is_zip,
             bundle_is_gzipped,
             bundle_is_html,
             MAX_BUNDLE_SIZE,
@@ -240,12 +328,49 @@ pub async fn upload_wasm_module(
     info!(
         wasm_module_id = %wasm_module_id,
         size_bytes = normalized_bundle.gz_bytes.len(),
-        is_html = bundle_is_html,
+        br_size_bytes = normalized_bundle.br_bytes.len(),
+        is_html = bundle_is_html || bundle_is_zip,
         is_gzipped = true,
-        "Prepared WASM bundle for database storage"
+        archive_files = archive.as_ref().map(|a| a.files.len()),
+        "Prepared WASM bundle for storage"
     );
 
-    // Upload thumbnail to S3
+    if let Some(progress) = &progress {
+        progress.stage(UploadStage::Storing);
+    }
+
+    let stored_bundle = state
+        .persist_wasm_bundle(
+            wasm_module_id,
+            normalized_bundle.gz_bytes.clone(),
+            normalized_bundle.br_bytes.clone(),
+        )
+        .await
+        .map_err(|e| {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to store WASM bundle");
+            code_err(CodeError::FILE_UPLOAD_ERROR, e)
+        })?;
+    let mut bundle_object_keys: Vec<String> = stored_bundle.object_keys().cloned().collect();
+
+    let (entry, file_rows) = match archive {
+        Some(UnpackedArchive { files, entry }) => {
+            let rows = match state.store_wasm_module_files(wasm_module_id, files).await {
+                Ok(rows) => rows,
+                Err(e) => {
+                    error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to store WASM module files");
+                    for key in &bundle_object_keys {
+                        state.delete_wasm_bundle_object(key).await;
+                    }
+                    return Err(code_err(CodeError::FILE_UPLOAD_ERROR, e));
+                }
+            };
+            bundle_object_keys.extend(rows.iter().map(|r| r.wasm_module_file_key.clone()));
+            (Some(entry), rows)
+        }
+        None => (None, Vec::new()),
+    };
+
+    // Upload thumbnail to object storage
     let processed_thumbnail =
         process_uploaded_image(thumbnail_bytes, None, CyhdevImageType::DemoThumbnail)
             .await
@@ -256,71 +381,93 @@ pub async fn upload_wasm_module(
 
     let (thumb_ext, _) = map_image_format_to_str(IMAGE_ENCODING_FORMAT);
     let thumbnail_path = format!("wasm-thumbnails/{}.{}", wasm_module_id, thumb_ext);
-    let s3_client = aws_sdk_s3::Client::new(&state.aws_profile_picture_config);
-
-    s3_client
-        .put_object()
-        .bucket(AWS_S3_BUCKET_NAME)
-        .key(&thumbnail_path)
-        .content_type("image/avif")
-        .body(aws_sdk_s3::primitives::ByteStream::from(
-            processed_thumbnail,
-        ))
-        .send()
+    state
+        .storage
+        .put(&thumbnail_path, processed_thumbnail, "image/avif")
         .await
         .map_err(|e| {
-            error!(error = ?e, "Failed to upload thumbnail to S3");
+            error!(error = ?e, key = %thumbnail_path, "Failed to upload thumbnail");
             code_err(CodeError::FILE_UPLOAD_ERROR, e)
         })?;
 
-    let s3_region = state
-        .aws_profile_picture_config
-        .region()
-        .map(|r| r.to_string())
-        .unwrap_or_else(|| "us-west-1".to_string());
-
-    let thumbnail_url = format!(
-        "https://{}.s3.{}.amazonaws.com/{}",
-        AWS_S3_BUCKET_NAME, s3_region, thumbnail_path
-    );
+    let thumbnail_url = state.storage.public_url(&thumbnail_path);
 
     // The WASM link will be served by our backend route
-    let wasm_link = format!("/api/wasm-modules/{}/wasm", wasm_module_id);
+    let wasm_link = wasm_module_link(wasm_module_id, entry.as_deref());
 
     let now = Utc::now();
 
     // Insert into database
     let mut conn = state.get_conn().await.map_err(|e| {
         error!(error = ?e, "Failed to get DB connection");
-        code_err(CodeError::POOL_ERROR, e)
+        pool_err(e)
     })?;
 
-    let module: WasmModule = diesel::insert_into(wasm_module::table)
-        .values(WasmModuleInsertable {
-            wasm_module_id,
-            user_id,
-            wasm_module_link: wasm_link,
-            wasm_module_description: description,
-            wasm_module_created_at: now,
-            wasm_module_updated_at: now,
-            wasm_module_thumbnail_link: thumbnail_url,
-            wasm_module_title: title,
-            wasm_module_bundle_gz: normalized_bundle.gz_bytes.clone(),
+    let insertable = WasmModuleInsertable {
+        wasm_module_id,
+        user_id,
+        wasm_module_link: wasm_link,
+        wasm_module_description: description,
+        wasm_module_created_at: now,
+        wasm_module_updated_at: now,
+        wasm_module_thumbnail_link: thumbnail_url,
+        wasm_module_title: title,
+        wasm_module_bundle_gz: stored_bundle.bundle_gz,
+        wasm_module_visibility: visibility,
+        wasm_module_bundle_key: stored_bundle.bundle_key,
+        wasm_module_bundle_br: stored_bundle.bundle_br,
+        wasm_module_bundle_br_key: stored_bundle.bundle_br_key,
+        wasm_module_bundle_sha256: stored_bundle.sha256,
+        wasm_module_bundle_size_bytes: stored_bundle.size_bytes,
+        wasm_module_category: category,
+        wasm_module_tags: tags,
+        wasm_module_bundle_integrity: Some(normalized_bundle.integrity.clone()),
+    };
+    let module: Result<WasmModule, _> = conn
+        .transaction::<_, diesel::result::Error, _>(async |conn| {
+            let module: WasmModule = diesel::insert_into(wasm_module::table)
+                .values(&insertable)
+                .get_result(&mut *conn)
+                .await?;
+            if !file_rows.is_empty() {
+                diesel::insert_into(wasm_module_files::table)
+                    .values(&file_rows)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+            Ok(module)
         })
-        .get_result(&mut conn)
-        .await
-        .map_err(|e| {
+        .await;
+    let module = match module {
+        Ok(module) => module,
+        Err(e) => {
             error!(error = ?e, "Failed to insert WASM module into DB");
-            code_err(CodeError::DB_INSERTION_ERROR, e)
-        })?;
+            for key in &bundle_object_keys {
+                state.delete_wasm_bundle_object(key).await;
+            }
+            return Err(code_err(CodeError::DB_INSERTION_ERROR, e));
+        }
+    };
 
     drop(conn);
 
+    state
+        .set_wasm_module_access(
+            wasm_module_id,
+            WasmModuleAccess {
+                visibility,
+                allowed_user_ids: Vec::new(),
+            },
+        )
+        .await;
+    state.index_wasm_module(&module.search_doc());
     state
         .upsert_wasm_module_cache(
             wasm_module_id,
             normalized_bundle.gz_bytes,
+            Some(normalized_bundle.br_bytes),
             normalized_bundle.content_type,
+            Some(normalized_bundle.integrity),
         )
         .await;
 
@@ -330,6 +477,27 @@ pub async fn upload_wasm_module(
         title = %module.wasm_module_title,
         "WASM module uploaded successfully"
     );
+    state
+        .record_admin_action(
+            &actor,
+            AdminAction::WasmModuleUpload,
+            Some(wasm_module_id.to_string()),
+            serde_json::json!({
+                "title": module.wasm_module_title,
+                "visibility": module.wasm_module_visibility,
+                "bundle_sha256": module.wasm_module_bundle_sha256,
+                "bundle_size_bytes": module.wasm_module_bundle_size_bytes,
+            }),
+        )
+        .await;
+
+    if let Some(progress) = progress {
+        progress.done(wasm_module_id);
+    }
 
-    Ok(http_resp(WasmModuleItem::from(module), (), start))
+    Ok(http_resp(
+        state.deliver_wasm_module_item(WasmModuleItem::from(module)),
+        (),
+        start,
+    ))
 }
@@ -0,0 +1,113 @@
+//! Environment overrides for [`AppConfig`](super::AppConfig) fields.
+//!
+//! Every field keeps the environment variable it was read from before the
+//! config file existed, so `.env` files and container definitions keep
+//! working; a set variable wins over the file.
+
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+/// A config field that can be parsed from an environment variable.
+pub trait EnvValue: Sized {
+    fn parse_env(raw: &str) -> Result<Self, String>;
+}
+
+impl EnvValue for String {
+    fn parse_env(raw: &str) -> Result<Self, String> {
+        Ok(raw.to_string())
+    }
+}
+
+impl EnvValue for PathBuf {
+    fn parse_env(raw: &str) -> Result<Self, String> {
+        Ok(PathBuf::from(raw.trim()))
+    }
+}
+
+/// `on`/`off` style switches; the spellings accepted before the config file.
+impl EnvValue for bool {
+    fn parse_env(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" | "enabled" => Ok(true),
+            "0" | "false" | "no" | "off" | "disabled" => Ok(false),
+            _ => Err("expected on or off".to_string()),
+        }
+    }
+}
+
+macro_rules! env_value_from_str {
+    ($($ty:ty),*) => {
+        $(impl EnvValue for $ty {
+            fn parse_env(raw: &str) -> Result<Self, String> {
+                raw.trim().parse::<$ty>().map_err(|e| e.to_string())
+            }
+        })*
+    };
+}
+
+env_value_from_str!(u16, u32, u64, usize, i64, f32, IpAddr, Uuid);
+
+/// Comma-separated; blank items are dropped.
+impl EnvValue for Vec<String> {
+    fn parse_env(raw: &str) -> Result<Self, String> {
+        Ok(raw
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect())
+    }
+}
+
+/// An empty variable clears the field.
+impl<T: EnvValue> EnvValue for Option<T> {
+    fn parse_env(raw: &str) -> Result<Self, String> {
+        if raw.trim().is_empty() {
+            Ok(None)
+        } else {
+            T::parse_env(raw).map(Some)
+        }
+    }
+}
+
+/// Collects parse failures so startup reports all of them at once.
+#[derive(Default)]
+pub struct EnvOverrides {
+    pub errors: Vec<String>,
+}
+
+impl EnvOverrides {
+    /// Replace `field` with `var` when it is set.
+    pub fn apply<T: EnvValue>(&mut self, field: &mut T, var: &str) {
+        if let Ok(raw) = std::env::var(var) {
+            self.apply_raw(field, var, &raw);
+        }
+    }
+
+    pub fn apply_raw<T: EnvValue>(&mut self, field: &mut T, var: &str, raw: &str) {
+        match T::parse_env(raw) {
+            Ok(value) => *field = value,
+            Err(e) => self.errors.push(format!("{var}={raw:?}: {e}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_switches_lists_and_blank_options() {
+        assert_eq!(bool::parse_env(" Off "), Ok(false));
+        assert!(bool::parse_env("maybe").is_err());
+        assert_eq!(
+            Vec::<String>::parse_env("a, ,b"),
+            Ok(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(Option::<u16>::parse_env(""), Ok(None));
+        assert_eq!(Option::<u16>::parse_env("443"), Ok(Some(443)));
+        assert!(Option::<u16>::parse_env("https").is_err());
+    }
+}
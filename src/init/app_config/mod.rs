@@ -0,0 +1,1770 @@
+//! Typed application configuration.
+//!
+//! [`AppConfig::load`] reads an optional TOML or YAML file, applies
+//! environment overrides and validates the result, so a typo fails startup
+//! with every problem listed instead of silently falling back to a default.
+//!
+//! The file is `APP_CONFIG` when set (it must exist), otherwise
+//! `./config.toml` when present; without either, configuration comes from
+//! the environment alone, as before. Each field is overridden by the
+//! environment variable named in its doc comment. `config.example.toml`
+//! lists every section.
+//!
+//! Handlers and jobs read `state.config`; code without the state (the job
+//! scheduler helpers, client IP extraction) uses [`config()`]. The `OTEL_*`
+//! variables are read by the OpenTelemetry SDK and stay environment-only, as
+//! does `IS_AWS_ECS`, which decides whether `.env` is loaded at all.
+
+pub mod env;
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+use axum::http::{HeaderName, Method, Uri};
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::DOMAIN_NAME;
+use crate::domain::live_chat::rtc::MaxParticipants;
+use crate::domain::photography::duplicates::{
+    DEFAULT_DUPLICATE_THRESHOLD, MAX_DUPLICATE_THRESHOLD,
+};
+use crate::init::error_reporting::Dsn;
+use crate::init::state::DeploymentEnvironment;
+use crate::init::state::sites::DEFAULT_SITE_NAME;
+use crate::jobs::job_funcs::registry::Schedule;
+use crate::util::http::rate_limit::Limit;
+use crate::util::image::watermark::WatermarkCorner;
+use env::EnvOverrides;
+
+const DEFAULT_CONFIG_PATH: &str = "./config.toml";
+
+static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+/// The installed configuration. Before [`install`] (unit tests) this is
+/// [`AppConfig::default`], and a later `install` fails.
+pub fn config() -> &'static AppConfig {
+    CONFIG.get_or_init(AppConfig::default)
+}
+
+/// Make `config` the process-wide configuration; once, at startup.
+pub fn install(config: AppConfig) -> anyhow::Result<&'static AppConfig> {
+    CONFIG
+        .set(config)
+        .map_err(|_| anyhow!("Configuration was read before it was loaded"))?;
+    Ok(self::config())
+}
+
+/// A field [`AppConfig::validate`] requires; only fails on a config that
+/// skipped validation.
+pub fn required<'a, T>(value: &'a Option<T>, what: &str) -> anyhow::Result<&'a T> {
+    value
+        .as_ref()
+        .ok_or_else(|| anyhow!("{what} is not configured"))
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub app: AppSection,
+    pub server: ServerSection,
+    pub cors: CorsSection,
+    pub database: DatabaseSection,
+    pub backup: BackupSection,
+    pub email: EmailSection,
+    pub aws: AwsSection,
+    pub cache: CacheSection,
+    pub rate_limit: RateLimitSection,
+    pub posts: PostsSection,
+    pub storage: StorageSection,
+    pub cdn: CdnSection,
+    pub images: ImagesSection,
+    pub watermark: WatermarkSection,
+    pub wasm: WasmSection,
+    pub rtc: RtcSection,
+    pub jobs: JobsSection,
+    pub access_log: AccessLogSection,
+    pub soft_delete: SoftDeleteSection,
+    pub host_stats: HostStatsSection,
+    pub log_files: LogFilesSection,
+    pub error_reporting: ErrorReportingSection,
+    /// Extra sites served by host (`[[sites]]`); file only, no environment
+    /// overrides. Requests for any other host get the default site.
+    pub sites: Vec<SiteConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppSection {
+    /// `APP_NAME_VERSION`; names the log directory and the OTLP service.
+    pub name_version: String,
+    /// `CURR_ENV`: local, dev, staging or prod; unset means prod.
+    pub environment: Option<String>,
+    /// `SEARCH_INDEX_PATH`.
+    pub search_index_path: PathBuf,
+    /// `FRONTEND_DIR`: frontend build read from disk, with live reload, in
+    /// the local environment instead of the embedded copy.
+    pub frontend_dir: PathBuf,
+}
+
+impl Default for AppSection {
+    fn default() -> Self {
+        Self {
+            name_version: String::new(),
+            environment: None,
+            search_index_path: PathBuf::from("./data/search_index"),
+            frontend_dir: PathBuf::from("./fe"),
+        }
+    }
+}
+
+impl AppSection {
+    pub fn deployment_environment(&self) -> DeploymentEnvironment {
+        match self.environment.as_deref() {
+            Some(s) => match s.to_ascii_lowercase().as_str() {
+                // Local
+                "local" | "localhost" => DeploymentEnvironment::Local,
+                // Dev
+                "dev" | "develop" | "development" => DeploymentEnvironment::Dev,
+                // Staging
+                "staging" | "stage" | "stg" => DeploymentEnvironment::Staging,
+                // Prod
+                "prd" | "prod" | "production" => DeploymentEnvironment::Prod,
+                // Default fallback: push _ to Local
+                _ => DeploymentEnvironment::Local,
+            },
+            None => DeploymentEnvironment::Prod,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSection {
+    /// `HOST_SOCKET_PATH`: serve plain HTTP on this unix socket instead of
+    /// HTTPS on `host_ip:host_port`, for a reverse proxy on the same host.
+    pub socket_path: Option<PathBuf>,
+    /// `HOST_IP`.
+    pub host_ip: Option<IpAddr>,
+    /// `HOST_PORT`.
+    pub host_port: Option<u16>,
+    /// `CERT_CHAIN_DIR`: PEM certificate chain.
+    pub cert_chain_path: Option<PathBuf>,
+    /// `PRIV_KEY_DIR`: PEM private key.
+    pub priv_key_path: Option<PathBuf>,
+    /// `TLS_RELOAD_POLL_SECS`: how often the certificate files are checked
+    /// for renewal; 0 leaves reloads to the admin endpoint.
+    pub tls_reload_poll_secs: u64,
+    /// `X_API_KEY`: guards `GET /api/metrics`.
+    pub api_key: Option<Uuid>,
+    /// `TRUSTED_PROXY_HOPS`: reverse proxies whose `X-Forwarded-For` hops are
+    /// trusted; 0 uses the socket peer. On a unix socket the local proxy is
+    /// always trusted and this counts the proxies in front of it.
+    pub trusted_proxy_hops: usize,
+    /// `SHUTDOWN_TIMEOUT_SECS`.
+    pub shutdown_timeout_secs: u64,
+    /// `REQUEST_TIMEOUT_SECS`: budget for JSON API requests; 0 disables.
+    pub request_timeout_secs: u64,
+    /// `UPLOAD_TIMEOUT_SECS`: budget for multipart upload routes; 0
+    /// disables.
+    pub upload_timeout_secs: u64,
+    /// `PROBE_PORT`: plain-HTTP port bound before initialization, serving
+    /// only `/livez` and `/readyz` (on `host_ip`, or all interfaces with a
+    /// unix socket).
+    pub probe_port: Option<u16>,
+    /// `API_DOCS`: Swagger UI at `/api/docs` and the spec at
+    /// `/api/openapi.json`. Unset serves them outside prod only; in prod they
+    /// stay behind superuser auth even when enabled.
+    pub api_docs: Option<bool>,
+    /// `GRPC_PORT`: plain-HTTP/2 port for the internal gRPC service
+    /// (`proto/internal.proto`), on `host_ip`, or all interfaces with a unix
+    /// socket. Unset serves no gRPC.
+    pub grpc_port: Option<u16>,
+}
+
+impl Default for ServerSection {
+    fn default() -> Self {
+        Self {
+            socket_path: None,
+            host_ip: None,
+            host_port: None,
+            cert_chain_path: None,
+            priv_key_path: None,
+            tls_reload_poll_secs: 60,
+            api_key: None,
+            trusted_proxy_hops: 0,
+            shutdown_timeout_secs: 30,
+            request_timeout_secs: 5,
+            upload_timeout_secs: 120,
+            probe_port: None,
+            api_docs: None,
+            grpc_port: None,
+        }
+    }
+}
+
+/// CORS for the API routes (`routers::main_router::cors`). Origins are
+/// `scheme://host[:port]`; `*` is accepted outside prod with credentials off.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsSection {
+    /// `CORS_ALLOWED_ORIGINS`: allowed in every environment, on top of
+    /// `https://{DOMAIN_NAME}` and `https://www.{DOMAIN_NAME}`.
+    pub allowed_origins: Vec<String>,
+    /// `CORS_LOCAL_ORIGINS`: also allowed when `app.environment` is local.
+    pub local_origins: Vec<String>,
+    /// `CORS_DEV_ORIGINS`.
+    pub dev_origins: Vec<String>,
+    /// `CORS_STAGING_ORIGINS`.
+    pub staging_origins: Vec<String>,
+    /// `CORS_PROD_ORIGINS`; https only.
+    pub prod_origins: Vec<String>,
+    /// `CORS_ALLOWED_METHODS`.
+    pub allowed_methods: Vec<String>,
+    /// `CORS_ALLOWED_HEADERS`.
+    pub allowed_headers: Vec<String>,
+    /// `CORS_ALLOW_CREDENTIALS`: the session cookie needs it.
+    pub allow_credentials: bool,
+    /// `CORS_MAX_AGE_SECS`: how long browsers may cache a preflight; 0 omits
+    /// the header.
+    pub max_age_secs: u64,
+}
+
+/// The local frontend dev servers.
+const DEV_SERVER_ORIGINS: [&str; 4] = [
+    "http://localhost:3000",
+    "http://127.0.0.1:3000",
+    "http://localhost:5173",
+    "http://127.0.0.1:5173",
+];
+
+impl Default for CorsSection {
+    fn default() -> Self {
+        let dev_servers: Vec<String> = DEV_SERVER_ORIGINS.map(String::from).to_vec();
+        Self {
+            allowed_origins: Vec::new(),
+            local_origins: dev_servers.clone(),
+            dev_origins: dev_servers.clone(),
+            staging_origins: dev_servers,
+            prod_origins: Vec::new(),
+            allowed_methods: ["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"]
+                .map(String::from)
+                .to_vec(),
+            allowed_headers: ["content-type", "authorization"].map(String::from).to_vec(),
+            allow_credentials: true,
+            max_age_secs: 0,
+        }
+    }
+}
+
+impl CorsSection {
+    /// `allowed_origins` plus the list for `env`.
+    pub fn origins(&self, env: DeploymentEnvironment) -> impl Iterator<Item = &str> {
+        let per_env = match env {
+            DeploymentEnvironment::Local => &self.local_origins,
+            DeploymentEnvironment::Dev => &self.dev_origins,
+            DeploymentEnvironment::Staging => &self.staging_origins,
+            DeploymentEnvironment::Prod => &self.prod_origins,
+        };
+        self.allowed_origins
+            .iter()
+            .chain(per_env)
+            .map(|origin| origin.trim())
+            .filter(|origin| !origin.is_empty())
+    }
+}
+
+/// Either `url`, or `host` (a Unix socket directory when it starts with `/`)
+/// with the credentials; see `init::config::DbConfig`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSection {
+    /// `DB_URL`.
+    pub url: Option<String>,
+    /// `DB_HOST`.
+    pub host: Option<String>,
+    /// `DB_PORT`.
+    pub port: Option<u16>,
+    /// `DB_USERNAME`.
+    pub username: Option<String>,
+    /// `DB_PASSWORD`.
+    pub password: Option<String>,
+    /// `DB_NAME`.
+    pub name: Option<String>,
+    /// `DB_READ_URL`: optional read replica.
+    pub read_url: Option<String>,
+    /// `DB_BREAKER_FAILURES`.
+    pub breaker_failures: u32,
+    /// `DB_BREAKER_COOLDOWN_SECS`.
+    pub breaker_cooldown_secs: u64,
+    /// `DB_SLOW_QUERY_MS`: statements at least this slow are logged and
+    /// counted; 0 turns it off.
+    pub slow_query_ms: u64,
+    /// `DB_MIGRATIONS`: `apply`, `check` or `off`; see `init::db_migrations`.
+    pub migrations: String,
+    /// `DB_MIGRATION_LOCK_TIMEOUT_SECS`: how long startup waits for another
+    /// instance's migrations.
+    pub migration_lock_timeout_secs: u64,
+}
+
+impl Default for DatabaseSection {
+    fn default() -> Self {
+        Self {
+            url: None,
+            host: None,
+            port: None,
+            username: None,
+            password: None,
+            name: None,
+            read_url: None,
+            breaker_failures: 5,
+            breaker_cooldown_secs: 10,
+            slow_query_ms: 500,
+            migrations: "apply".to_string(),
+            migration_lock_timeout_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupSection {
+    /// `DB_BACKUP`.
+    pub enabled: bool,
+    /// `DB_BACKUP_PREFIX`.
+    pub prefix: String,
+    /// `DB_BACKUP_KEEP`.
+    pub keep: usize,
+    /// `DB_BACKUP_TABLES` (comma-separated); empty dumps every table.
+    pub tables: Vec<String>,
+    /// `PG_DUMP_PATH`.
+    pub pg_dump_path: String,
+}
+
+impl Default for BackupSection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prefix: "private/backups/db/".to_string(),
+            keep: 14,
+            tables: Vec::new(),
+            pg_dump_path: "pg_dump".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailSection {
+    /// `EMAIL_TRANSPORT`: smtp, ses or file. Unset is file in the local
+    /// environment and smtp everywhere else.
+    pub transport: String,
+    /// `EMAIL_SINK_DIR`: where the file transport writes `.eml` files.
+    pub sink_dir: PathBuf,
+    /// `AWS_SES_SMTP_URL`; required for the smtp transport.
+    pub smtp_url: Option<String>,
+    /// `AWS_SES_SMTP_USERNAME`; required for the smtp transport.
+    pub smtp_username: Option<String>,
+    /// `AWS_SES_SMTP_ACCESS_KEY`; required for the smtp transport.
+    pub smtp_password: Option<String>,
+    /// `AWS_SES_REGION`; unset uses the AWS SDK's region.
+    pub ses_region: Option<String>,
+    /// `AWS_SES_CONFIGURATION_SET`: SES configuration set for event
+    /// publishing and dedicated IPs (ses transport only).
+    pub ses_configuration_set: Option<String>,
+    /// `AWS_SES_SNS_TOPIC_ARNS`: SNS topics whose bounce and complaint
+    /// notifications `POST /api/email/ses-feedback` accepts. Empty turns the
+    /// endpoint off.
+    pub sns_topic_arns: Vec<String>,
+    /// `EMAIL_UNSUBSCRIBE_SECRET`: signs unsubscribe links (at least 32
+    /// characters). Unset derives a key from `X_API_KEY`; changing either
+    /// breaks the links in emails already sent.
+    pub unsubscribe_secret: Option<String>,
+    /// `EMAIL_FROM_ADDRESS`.
+    pub from_address: String,
+    /// `EMAIL_FROM_NAME`: the sender's display name.
+    pub from_name: String,
+    /// `EMAIL_REPLY_TO`: unset sends no Reply-To.
+    pub reply_to: Option<String>,
+    /// `EMAIL_ENVIRONMENT_TAG`: put before the display name and every
+    /// subject, e.g. `[staging]`. Unset tags every environment but prod with
+    /// its name; empty turns the tag off.
+    pub environment_tag: Option<String>,
+    /// Sender settings for one environment (`[email.environments.staging]`),
+    /// over the ones above; file only, no environment overrides.
+    pub environments: BTreeMap<String, EmailSenderOverride>,
+}
+
+/// `[email.environments.<local|dev|staging|prod>]`; unset fields keep the
+/// `[email]` value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailSenderOverride {
+    pub from_address: Option<String>,
+    pub from_name: Option<String>,
+    pub reply_to: Option<String>,
+    pub environment_tag: Option<String>,
+}
+
+impl EmailSection {
+    /// The transport to build in `env`: `transport` lowercased, or the
+    /// environment's default when it is unset.
+    pub fn transport_for(&self, env: DeploymentEnvironment) -> String {
+        match self.transport.trim() {
+            "" => match env {
+                DeploymentEnvironment::Local => "file".to_string(),
+                _ => "smtp".to_string(),
+            },
+            other => other.to_ascii_lowercase(),
+        }
+    }
+}
+
+impl Default for EmailSection {
+    fn default() -> Self {
+        Self {
+            transport: String::new(),
+            sink_dir: PathBuf::from("./outbox"),
+            smtp_url: None,
+            smtp_username: None,
+            smtp_password: None,
+            ses_region: None,
+            ses_configuration_set: None,
+            sns_topic_arns: Vec::new(),
+            unsubscribe_secret: None,
+            from_address: format!("donotreply@{DOMAIN_NAME}"),
+            from_name: DOMAIN_NAME.to_string(),
+            reply_to: None,
+            environment_tag: None,
+            environments: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AwsSection {
+    /// `AWS_IMAGE_UPLOAD_KEY`.
+    pub image_upload_key: Option<String>,
+    /// `AWS_IMAGE_UPLOAD_SECRET_KEY`.
+    pub image_upload_secret_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSection {
+    /// `CACHE_BACKEND`: memory or redis.
+    pub backend: String,
+    /// `REDIS_URL`.
+    pub redis_url: String,
+    /// `CACHE_KEY_PREFIX`.
+    pub key_prefix: String,
+    /// `RESPONSE_CACHE_TTL_SECS`: lifetime of cached public GET responses;
+    /// 0 turns the response cache off.
+    pub response_ttl_secs: u64,
+    /// `CACHE_INVALIDATION_LISTEN`: refresh the post, country and i18n
+    /// caches when another instance changes their rows (Postgres NOTIFY).
+    pub invalidation_listen: bool,
+}
+
+impl Default for CacheSection {
+    fn default() -> Self {
+        Self {
+            backend: "memory".to_string(),
+            redis_url: "redis://127.0.0.1:6379".to_string(),
+            key_prefix: String::new(),
+            response_ttl_secs: 5,
+            invalidation_listen: true,
+        }
+    }
+}
+
+/// Limits are `BURST/PERIOD_SECS`; unset keeps the policy default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitSection {
+    /// `RATE_LIMIT`.
+    pub enabled: bool,
+    /// `RATE_LIMIT_GLOBAL`.
+    pub global: Option<String>,
+    /// `RATE_LIMIT_READ`.
+    pub read: Option<String>,
+    /// `RATE_LIMIT_WRITE`.
+    pub write: Option<String>,
+    /// `RATE_LIMIT_AUTH`.
+    pub auth: Option<String>,
+}
+
+impl Default for RateLimitSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            global: None,
+            read: None,
+            write: None,
+            auth: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PostsSection {
+    /// `POST_VIEW_DEDUP_SECS`; 0 counts every read.
+    pub view_dedup_secs: u64,
+}
+
+impl Default for PostsSection {
+    fn default() -> Self {
+        Self {
+            view_dedup_secs: 30 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageSection {
+    /// `STORAGE_BACKEND`: s3 or local.
+    pub backend: String,
+    /// `S3_BUCKET`.
+    pub s3_bucket: String,
+    /// `LOCAL_STORAGE_ROOT`.
+    pub local_root: PathBuf,
+    /// `LOCAL_STORAGE_PUBLIC_URL`.
+    pub local_public_url: String,
+    /// `STORAGE_ORPHAN_CLEANUP`: off, report or delete.
+    pub orphan_cleanup: String,
+    /// `STORAGE_ORPHAN_MIN_AGE_HOURS`.
+    pub orphan_min_age_hours: i64,
+}
+
+impl Default for StorageSection {
+    fn default() -> Self {
+        Self {
+            backend: "s3".to_string(),
+            s3_bucket: crate::util::s3::AWS_S3_BUCKET_NAME.to_string(),
+            local_root: PathBuf::from("./storage"),
+            local_public_url: "/storage".to_string(),
+            orphan_cleanup: "report".to_string(),
+            orphan_min_age_hours: 24,
+        }
+    }
+}
+
+/// CloudFront delivery; off while `domain` is unset.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CdnSection {
+    /// `CLOUDFRONT_DOMAIN`.
+    pub domain: Option<String>,
+    /// `CLOUDFRONT_KEY_PAIR_ID`.
+    pub key_pair_id: Option<String>,
+    /// `CLOUDFRONT_PRIVATE_KEY_PATH`.
+    pub private_key_path: Option<PathBuf>,
+    /// `CLOUDFRONT_URL_TTL_SECS`.
+    pub url_ttl_secs: i64,
+    /// `CLOUDFRONT_DISTRIBUTION_ID`.
+    pub distribution_id: Option<String>,
+}
+
+impl Default for CdnSection {
+    fn default() -> Self {
+        Self {
+            domain: None,
+            key_pair_id: None,
+            private_key_path: None,
+            url_ttl_secs: 3600,
+            distribution_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImagesSection {
+    /// `IMAGE_VARIANT_CACHE`: storage, disk or off.
+    pub variant_cache: String,
+    /// `IMAGE_VARIANT_CACHE_DIR`.
+    pub variant_cache_dir: PathBuf,
+    /// `IMAGE_VARIANT_RENDER_CONCURRENCY`; unset is half the CPUs.
+    pub variant_render_concurrency: Option<usize>,
+    /// `IMAGE_PROCESSING_QUEUE_CAPACITY`.
+    pub processing_queue_capacity: usize,
+    /// `RAW_CONVERTER_BIN`.
+    pub raw_converter_bin: String,
+    /// `RAW_CONVERTER_TIMEOUT_SECS`: the converter is killed past this.
+    pub raw_converter_timeout_secs: u64,
+    /// `PHOTOGRAPH_DUPLICATE_POLICY`: off, warn or reject.
+    pub duplicate_policy: String,
+    /// `PHOTOGRAPH_DUPLICATE_THRESHOLD`: max Hamming distance.
+    pub duplicate_threshold: u32,
+}
+
+impl Default for ImagesSection {
+    fn default() -> Self {
+        Self {
+            variant_cache: "storage".to_string(),
+            variant_cache_dir: PathBuf::from("./cache/image-variants"),
+            variant_render_concurrency: None,
+            processing_queue_capacity:
+                crate::jobs::queue::image_processing::DEFAULT_IMAGE_PROCESSING_QUEUE_CAPACITY,
+            raw_converter_bin: "dcraw".to_string(),
+            raw_converter_timeout_secs: 120,
+            duplicate_policy: "warn".to_string(),
+            duplicate_threshold: DEFAULT_DUPLICATE_THRESHOLD,
+        }
+    }
+}
+
+/// Off unless `logo_path` or `text` is set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatermarkSection {
+    /// `WATERMARK_LOGO_PATH`.
+    pub logo_path: Option<PathBuf>,
+    /// `WATERMARK_TEXT`; needs `font_path`.
+    pub text: Option<String>,
+    /// `WATERMARK_FONT_PATH`.
+    pub font_path: Option<PathBuf>,
+    /// `WATERMARK_POSITION`: a corner, default bottom-right.
+    pub position: Option<String>,
+    /// `WATERMARK_OPACITY`, in (0, 1].
+    pub opacity: f32,
+    /// `WATERMARK_SCALE`: stamp width over image width, in (0, 1].
+    pub scale: f32,
+    /// `WATERMARK_DEFAULT`: stamp uploads that do not say.
+    pub default_enabled: bool,
+}
+
+impl Default for WatermarkSection {
+    fn default() -> Self {
+        Self {
+            logo_path: None,
+            text: None,
+            font_path: None,
+            position: None,
+            opacity: 0.5,
+            scale: 0.2,
+            default_enabled: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WasmSection {
+    /// `WASM_BUNDLE_STORAGE`: database or storage.
+    pub bundle_storage: String,
+    /// `WASM_MODULE_CACHE_MAX_BYTES`.
+    pub cache_max_bytes: usize,
+    /// `WASM_MODULE_DISK_CACHE`.
+    pub disk_cache: bool,
+    /// `WASM_MODULE_CACHE_DIR`.
+    pub cache_dir: PathBuf,
+}
+
+impl Default for WasmSection {
+    fn default() -> Self {
+        Self {
+            bundle_storage: "database".to_string(),
+            cache_max_bytes: 256 * 1024 * 1024,
+            disk_cache: true,
+            cache_dir: PathBuf::from("./cache/wasm-bundles"),
+        }
+    }
+}
+
+/// The SFU stays off, without failing startup, when enabled without
+/// `public_ip` and `udp_mux_port`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RtcSection {
+    /// `RTC_ENABLE`.
+    pub enabled: bool,
+    /// `RTC_MAX_PARTICIPANTS`.
+    pub max_participants: Option<usize>,
+    /// `RTC_TURN_URL`.
+    pub turn_url: Option<String>,
+    /// `RTC_TURN_USER`.
+    pub turn_user: Option<String>,
+    /// `RTC_TURN_PASS`.
+    pub turn_pass: Option<String>,
+    /// `RTC_PUBLIC_IP`.
+    pub public_ip: Option<String>,
+    /// `RTC_UDP_MUX_PORT`.
+    pub udp_mux_port: Option<u16>,
+}
+
+/// Sampled request log (`domain::access_log`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccessLogSection {
+    /// `ACCESS_LOG_SAMPLE_RATE`: share of API requests persisted, 0 to 1;
+    /// 0 turns the log off.
+    pub sample_rate: f32,
+    /// `ACCESS_LOG_RETENTION_DAYS`.
+    pub retention_days: i64,
+    /// `ACCESS_LOG_BUFFER_CAPACITY`: sampled requests held between flushes;
+    /// further ones are dropped until the next flush.
+    pub buffer_capacity: usize,
+}
+
+impl Default for AccessLogSection {
+    fn default() -> Self {
+        Self {
+            sample_rate: 0.0,
+            retention_days: 30,
+            buffer_capacity: 10_000,
+        }
+    }
+}
+
+/// Soft-deleted posts, comments and photographs (`domain::soft_delete`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoftDeleteSection {
+    /// `SOFT_DELETE_RETENTION_DAYS`: how long deleted content stays
+    /// restorable before PURGE_SOFT_DELETED removes it.
+    pub retention_days: i64,
+}
+
+impl Default for SoftDeleteSection {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+/// The superuser host stats WebSocket (`/ws/host-stats`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HostStatsSection {
+    /// `HOST_STATS_INTERVAL_MS`: push interval when the client asks for none.
+    pub interval_ms: u64,
+    /// `HOST_STATS_MIN_INTERVAL_MS`: floor for a client's `interval_ms`.
+    pub min_interval_ms: u64,
+    /// `HOST_STATS_BACKFILL`: samples sent on connect when the client asks
+    /// for no number; at most the 3600 kept.
+    pub backfill: usize,
+    /// `HOST_STATS_MAX_SUBSCRIBERS`: open sockets per instance; further
+    /// upgrades are refused with 503.
+    pub max_subscribers: usize,
+}
+
+impl Default for HostStatsSection {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            min_interval_ms: 500,
+            backfill: 60,
+            max_subscribers: 8,
+        }
+    }
+}
+
+/// Rolled log files under `./logs/` (`jobs::maintenance::compress_logs`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogFilesSection {
+    /// `LOG_COMPRESS_AFTER_DAYS`: a day's log is gzipped once this many days
+    /// old; 1 is the day after it was written.
+    pub compress_after_days: i64,
+    /// `LOG_RETENTION_DAYS`: a day's log is deleted once this many days old.
+    pub retention_days: i64,
+}
+
+impl Default for LogFilesSection {
+    fn default() -> Self {
+        Self {
+            compress_after_days: 1,
+            retention_days: 30,
+        }
+    }
+}
+
+/// Error events sent to a Sentry-compatible tracker (`init::error_reporting`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ErrorReportingSection {
+    /// `ERROR_REPORTING_DSN`: `https://<key>@<host>/<project>`; unset or
+    /// empty turns reporting off.
+    pub dsn: Option<String>,
+    /// `ERROR_REPORTING_SAMPLE_RATE`: share of ERROR-level `CodeError`
+    /// responses reported, 0 to 1. Panics are always reported.
+    pub sample_rate: f32,
+    /// `ERROR_REPORTING_MAX_PER_MINUTE`: events queued per minute; further
+    /// ones are dropped.
+    pub max_per_minute: u32,
+    /// `ERROR_REPORTING_QUEUE_CAPACITY`: events waiting for delivery.
+    pub queue_capacity: usize,
+}
+
+impl Default for ErrorReportingSection {
+    fn default() -> Self {
+        Self {
+            dsn: None,
+            sample_rate: 1.0,
+            max_per_minute: 60,
+            queue_capacity: 256,
+        }
+    }
+}
+
+impl ErrorReportingSection {
+    pub fn dsn(&self) -> Option<&str> {
+        self.dsn
+            .as_deref()
+            .map(str::trim)
+            .filter(|dsn| !dsn.is_empty())
+    }
+}
+
+/// One `[[sites]]` entry (`init::state::sites`).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SiteConfig {
+    /// Short identifier for logs and `/api/site`.
+    pub name: String,
+    /// `Host` values (without port) routed to this site.
+    pub hosts: Vec<String>,
+    /// Display title for the frontend.
+    pub title: Option<String>,
+    /// Directory under `fe/sites/` holding this site's frontend; unset serves
+    /// the default `fe/` build.
+    pub frontend: Option<String>,
+    /// Bucket for uploads made through this site; unset uses
+    /// `storage.s3_bucket`. Ignored by the local storage backend.
+    pub s3_bucket: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsSection {
+    /// `JOB_CLUSTER_LOCKS`.
+    pub cluster_locks: bool,
+    /// `JOB_HISTORY_RETENTION_DAYS`.
+    pub history_retention_days: i64,
+    /// `SESSION_PURGE_INTERVAL_SECS` (1..=86400); unset purges hourly at :30.
+    pub session_purge_interval_secs: Option<u32>,
+    /// `DELAYED_TASK_POLL_SECS`.
+    pub delayed_task_poll_secs: u64,
+    /// `DELAYED_TASK_VISIBILITY_SECS`; also bounds an outbox email's claim.
+    pub delayed_task_visibility_secs: i64,
+    /// `EMAIL_OUTBOX_POLL_SECS`.
+    pub email_outbox_poll_secs: u64,
+    /// `JOB_ALERT_AFTER_FAILURES`; 0 disables alerts.
+    pub alert_after_failures: u32,
+    /// `JOB_ALERT_EMAIL` (comma-separated).
+    pub alert_email: Vec<String>,
+    /// `JOB_ALERT_WEBHOOK_URL`.
+    pub alert_webhook_url: Option<String>,
+    /// `JOB_JITTER_SECS`.
+    pub jitter_secs: i64,
+    /// `JOB_TIMEOUT_SECS`; 0 lets runs go on indefinitely.
+    pub timeout_secs: u64,
+    /// Per-job settings, keyed by job name (`[jobs.overrides.SYNC_I18N_DATA]`).
+    pub overrides: BTreeMap<String, JobOverride>,
+}
+
+impl Default for JobsSection {
+    fn default() -> Self {
+        Self {
+            cluster_locks: true,
+            history_retention_days: 30,
+            session_purge_interval_secs: None,
+            delayed_task_poll_secs: 5,
+            delayed_task_visibility_secs: 15 * 60,
+            email_outbox_poll_secs: 5,
+            alert_after_failures: 3,
+            alert_email: Vec::new(),
+            alert_webhook_url: None,
+            jitter_secs: 0,
+            timeout_secs: 60 * 60,
+            overrides: BTreeMap::new(),
+        }
+    }
+}
+
+impl JobsSection {
+    pub fn job(&self, job_name: &str) -> Option<&JobOverride> {
+        self.overrides.get(job_name)
+    }
+}
+
+/// Environment variables are `<PREFIX>_<JOB_NAME>`, e.g.
+/// `JOB_SCHEDULE_SYNC_I18N_DATA`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobOverride {
+    /// `JOB_SCHEDULE_`: schedule expression.
+    pub schedule: Option<String>,
+    /// `JOB_PAUSED_`: start paused.
+    pub paused: Option<bool>,
+    /// `JOB_CATCH_UP_`: run once at startup after a missed mark.
+    pub catch_up: Option<bool>,
+    /// `JOB_OVERLAP_`: skip or queue.
+    pub overlap: Option<String>,
+    /// `JOB_JITTER_SECS_`.
+    pub jitter_secs: Option<i64>,
+    /// `JOB_TIMEOUT_SECS_`.
+    pub timeout_secs: Option<u64>,
+}
+
+impl AppConfig {
+    /// File, then environment, then validation.
+    pub fn load() -> anyhow::Result<Self> {
+        let mut config = match config_path()? {
+            Some(path) => Self::from_file(&path)?,
+            None => Self::default(),
+        };
+        config.apply_env()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read config file {}: {e}", path.display()))?;
+        let is_yaml = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yaml" | "yml")
+        );
+        if is_yaml {
+            serde_yaml::from_str(&raw)
+                .map_err(|e| anyhow!("Invalid config file {}: {e}", path.display()))
+        } else {
+            toml::from_str(&raw).map_err(|e| anyhow!("Invalid config file {}: {e}", path.display()))
+        }
+    }
+
+    fn apply_env(&mut self) -> anyhow::Result<()> {
+        let mut env = EnvOverrides::default();
+
+        let app = &mut self.app;
+        env.apply(&mut app.name_version, "APP_NAME_VERSION");
+        env.apply(&mut app.environment, "CURR_ENV");
+        env.apply(&mut app.search_index_path, "SEARCH_INDEX_PATH");
+        env.apply(&mut app.frontend_dir, "FRONTEND_DIR");
+
+        let server = &mut self.server;
+        env.apply(&mut server.socket_path, "HOST_SOCKET_PATH");
+        env.apply(&mut server.host_ip, "HOST_IP");
+        env.apply(&mut server.host_port, "HOST_PORT");
+        env.apply(&mut server.cert_chain_path, "CERT_CHAIN_DIR");
+        env.apply(&mut server.priv_key_path, "PRIV_KEY_DIR");
+        env.apply(&mut server.tls_reload_poll_secs, "TLS_RELOAD_POLL_SECS");
+        env.apply(&mut server.api_key, "X_API_KEY");
+        env.apply(&mut server.trusted_proxy_hops, "TRUSTED_PROXY_HOPS");
+        env.apply(&mut server.shutdown_timeout_secs, "SHUTDOWN_TIMEOUT_SECS");
+        env.apply(&mut server.request_timeout_secs, "REQUEST_TIMEOUT_SECS");
+        env.apply(&mut server.upload_timeout_secs, "UPLOAD_TIMEOUT_SECS");
+        env.apply(&mut server.probe_port, "PROBE_PORT");
+        env.apply(&mut server.api_docs, "API_DOCS");
+        env.apply(&mut server.grpc_port, "GRPC_PORT");
+
+        let cors = &mut self.cors;
+        env.apply(&mut cors.allowed_origins, "CORS_ALLOWED_ORIGINS");
+        env.apply(&mut cors.local_origins, "CORS_LOCAL_ORIGINS");
+        env.apply(&mut cors.dev_origins, "CORS_DEV_ORIGINS");
+        env.apply(&mut cors.staging_origins, "CORS_STAGING_ORIGINS");
+        env.apply(&mut cors.prod_origins, "CORS_PROD_ORIGINS");
+        env.apply(&mut cors.allowed_methods, "CORS_ALLOWED_METHODS");
+        env.apply(&mut cors.allowed_headers, "CORS_ALLOWED_HEADERS");
+        env.apply(&mut cors.allow_credentials, "CORS_ALLOW_CREDENTIALS");
+        env.apply(&mut cors.max_age_secs, "CORS_MAX_AGE_SECS");
+
+        let database = &mut self.database;
+        env.apply(&mut database.url, "DB_URL");
+        env.apply(&mut database.host, "DB_HOST");
+        env.apply(&mut database.port, "DB_PORT");
+        env.apply(&mut database.username, "DB_USERNAME");
+        env.apply(&mut database.password, "DB_PASSWORD");
+        env.apply(&mut database.name, "DB_NAME");
+        env.apply(&mut database.read_url, "DB_READ_URL");
+        env.apply(&mut database.breaker_failures, "DB_BREAKER_FAILURES");
+        env.apply(
+            &mut database.breaker_cooldown_secs,
+            "DB_BREAKER_COOLDOWN_SECS",
+        );
+        env.apply(&mut database.slow_query_ms, "DB_SLOW_QUERY_MS");
+        env.apply(&mut database.migrations, "DB_MIGRATIONS");
+        env.apply(
+            &mut database.migration_lock_timeout_secs,
+            "DB_MIGRATION_LOCK_TIMEOUT_SECS",
+        );
+
+        let backup = &mut self.backup;
+        env.apply(&mut backup.enabled, "DB_BACKUP");
+        env.apply(&mut backup.prefix, "DB_BACKUP_PREFIX");
+        env.apply(&mut backup.keep, "DB_BACKUP_KEEP");
+        env.apply(&mut backup.tables, "DB_BACKUP_TABLES");
+        env.apply(&mut backup.pg_dump_path, "PG_DUMP_PATH");
+
+        let email = &mut self.email;
+        env.apply(&mut email.transport, "EMAIL_TRANSPORT");
+        env.apply(&mut email.sink_dir, "EMAIL_SINK_DIR");
+        env.apply(&mut email.smtp_url, "AWS_SES_SMTP_URL");
+        env.apply(&mut email.smtp_username, "AWS_SES_SMTP_USERNAME");
+        env.apply(&mut email.smtp_password, "AWS_SES_SMTP_ACCESS_KEY");
+        env.apply(&mut email.ses_region, "AWS_SES_REGION");
+        env.apply(
+            &mut email.ses_configuration_set,
+            "AWS_SES_CONFIGURATION_SET",
+        );
+        env.apply(&mut email.sns_topic_arns, "AWS_SES_SNS_TOPIC_ARNS");
+        env.apply(&mut email.unsubscribe_secret, "EMAIL_UNSUBSCRIBE_SECRET");
+        env.apply(&mut email.from_address, "EMAIL_FROM_ADDRESS");
+        env.apply(&mut email.from_name, "EMAIL_FROM_NAME");
+        env.apply(&mut email.reply_to, "EMAIL_REPLY_TO");
+        env.apply(&mut email.environment_tag, "EMAIL_ENVIRONMENT_TAG");
+
+        let aws = &mut self.aws;
+        env.apply(&mut aws.image_upload_key, "AWS_IMAGE_UPLOAD_KEY");
+        env.apply(
+            &mut aws.image_upload_secret_key,
+            "AWS_IMAGE_UPLOAD_SECRET_KEY",
+        );
+
+        let cache = &mut self.cache;
+        env.apply(&mut cache.backend, "CACHE_BACKEND");
+        env.apply(&mut cache.redis_url, "REDIS_URL");
+        env.apply(&mut cache.key_prefix, "CACHE_KEY_PREFIX");
+        env.apply(&mut cache.response_ttl_secs, "RESPONSE_CACHE_TTL_SECS");
+        env.apply(&mut cache.invalidation_listen, "CACHE_INVALIDATION_LISTEN");
+
+        let rate_limit = &mut self.rate_limit;
+        env.apply(&mut rate_limit.enabled, "RATE_LIMIT");
+        env.apply(&mut rate_limit.global, "RATE_LIMIT_GLOBAL");
+        env.apply(&mut rate_limit.read, "RATE_LIMIT_READ");
+        env.apply(&mut rate_limit.write, "RATE_LIMIT_WRITE");
+        env.apply(&mut rate_limit.auth, "RATE_LIMIT_AUTH");
+
+        env.apply(&mut self.posts.view_dedup_secs, "POST_VIEW_DEDUP_SECS");
+
+        let storage = &mut self.storage;
+        env.apply(&mut storage.backend, "STORAGE_BACKEND");
+        env.apply(&mut storage.s3_bucket, "S3_BUCKET");
+        env.apply(&mut storage.local_root, "LOCAL_STORAGE_ROOT");
+        env.apply(&mut storage.local_public_url, "LOCAL_STORAGE_PUBLIC_URL");
+        env.apply(&mut storage.orphan_cleanup, "STORAGE_ORPHAN_CLEANUP");
+        env.apply(
+            &mut storage.orphan_min_age_hours,
+            "STORAGE_ORPHAN_MIN_AGE_HOURS",
+        );
+
+        let cdn = &mut self.cdn;
+        env.apply(&mut cdn.domain, "CLOUDFRONT_DOMAIN");
+        env.apply(&mut cdn.key_pair_id, "CLOUDFRONT_KEY_PAIR_ID");
+        env.apply(&mut cdn.private_key_path, "CLOUDFRONT_PRIVATE_KEY_PATH");
+        env.apply(&mut cdn.url_ttl_secs, "CLOUDFRONT_URL_TTL_SECS");
+        env.apply(&mut cdn.distribution_id, "CLOUDFRONT_DISTRIBUTION_ID");
+
+        let images = &mut self.images;
+        env.apply(&mut images.variant_cache, "IMAGE_VARIANT_CACHE");
+        env.apply(&mut images.variant_cache_dir, "IMAGE_VARIANT_CACHE_DIR");
+        env.apply(
+            &mut images.variant_render_concurrency,
+            "IMAGE_VARIANT_RENDER_CONCURRENCY",
+        );
+        env.apply(
+            &mut images.processing_queue_capacity,
+            "IMAGE_PROCESSING_QUEUE_CAPACITY",
+        );
+        env.apply(&mut images.raw_converter_bin, "RAW_CONVERTER_BIN");
+        env.apply(
+            &mut images.raw_converter_timeout_secs,
+            "RAW_CONVERTER_TIMEOUT_SECS",
+        );
+        env.apply(&mut images.duplicate_policy, "PHOTOGRAPH_DUPLICATE_POLICY");
+        env.apply(
+            &mut images.duplicate_threshold,
+            "PHOTOGRAPH_DUPLICATE_THRESHOLD",
+        );
+
+        let watermark = &mut self.watermark;
+        env.apply(&mut watermark.logo_path, "WATERMARK_LOGO_PATH");
+        env.apply(&mut watermark.text, "WATERMARK_TEXT");
+        env.apply(&mut watermark.font_path, "WATERMARK_FONT_PATH");
+        env.apply(&mut watermark.position, "WATERMARK_POSITION");
+        env.apply(&mut watermark.opacity, "WATERMARK_OPACITY");
+        env.apply(&mut watermark.scale, "WATERMARK_SCALE");
+        env.apply(&mut watermark.default_enabled, "WATERMARK_DEFAULT");
+
+        let wasm = &mut self.wasm;
+        env.apply(&mut wasm.bundle_storage, "WASM_BUNDLE_STORAGE");
+        env.apply(&mut wasm.cache_max_bytes, "WASM_MODULE_CACHE_MAX_BYTES");
+        env.apply(&mut wasm.disk_cache, "WASM_MODULE_DISK_CACHE");
+        env.apply(&mut wasm.cache_dir, "WASM_MODULE_CACHE_DIR");
+
+        let rtc = &mut self.rtc;
+        env.apply(&mut rtc.enabled, "RTC_ENABLE");
+        env.apply(&mut rtc.max_participants, "RTC_MAX_PARTICIPANTS");
+        env.apply(&mut rtc.turn_url, "RTC_TURN_URL");
+        env.apply(&mut rtc.turn_user, "RTC_TURN_USER");
+        env.apply(&mut rtc.turn_pass, "RTC_TURN_PASS");
+        env.apply(&mut rtc.public_ip, "RTC_PUBLIC_IP");
+        env.apply(&mut rtc.udp_mux_port, "RTC_UDP_MUX_PORT");
+
+        let access_log = &mut self.access_log;
+        env.apply(&mut access_log.sample_rate, "ACCESS_LOG_SAMPLE_RATE");
+        env.apply(&mut access_log.retention_days, "ACCESS_LOG_RETENTION_DAYS");
+        env.apply(
+            &mut access_log.buffer_capacity,
+            "ACCESS_LOG_BUFFER_CAPACITY",
+        );
+
+        env.apply(
+            &mut self.soft_delete.retention_days,
+            "SOFT_DELETE_RETENTION_DAYS",
+        );
+
+        let host_stats = &mut self.host_stats;
+        env.apply(&mut host_stats.interval_ms, "HOST_STATS_INTERVAL_MS");
+        env.apply(
+            &mut host_stats.min_interval_ms,
+            "HOST_STATS_MIN_INTERVAL_MS",
+        );
+        env.apply(&mut host_stats.backfill, "HOST_STATS_BACKFILL");
+        env.apply(
+            &mut host_stats.max_subscribers,
+            "HOST_STATS_MAX_SUBSCRIBERS",
+        );
+
+        let log_files = &mut self.log_files;
+        env.apply(
+            &mut log_files.compress_after_days,
+            "LOG_COMPRESS_AFTER_DAYS",
+        );
+        env.apply(&mut log_files.retention_days, "LOG_RETENTION_DAYS");
+
+        let error_reporting = &mut self.error_reporting;
+        env.apply(&mut error_reporting.dsn, "ERROR_REPORTING_DSN");
+        env.apply(
+            &mut error_reporting.sample_rate,
+            "ERROR_REPORTING_SAMPLE_RATE",
+        );
+        env.apply(
+            &mut error_reporting.max_per_minute,
+            "ERROR_REPORTING_MAX_PER_MINUTE",
+        );
+        env.apply(
+            &mut error_reporting.queue_capacity,
+            "ERROR_REPORTING_QUEUE_CAPACITY",
+        );
+
+        let jobs = &mut self.jobs;
+        env.apply(&mut jobs.cluster_locks, "JOB_CLUSTER_LOCKS");
+        env.apply(
+            &mut jobs.history_retention_days,
+            "JOB_HISTORY_RETENTION_DAYS",
+        );
+        env.apply(
+            &mut jobs.session_purge_interval_secs,
+            "SESSION_PURGE_INTERVAL_SECS",
+        );
+        env.apply(&mut jobs.delayed_task_poll_secs, "DELAYED_TASK_POLL_SECS");
+        env.apply(
+            &mut jobs.delayed_task_visibility_secs,
+            "DELAYED_TASK_VISIBILITY_SECS",
+        );
+        env.apply(&mut jobs.email_outbox_poll_secs, "EMAIL_OUTBOX_POLL_SECS");
+        env.apply(&mut jobs.alert_after_failures, "JOB_ALERT_AFTER_FAILURES");
+        env.apply(&mut jobs.alert_email, "JOB_ALERT_EMAIL");
+        env.apply(&mut jobs.alert_webhook_url, "JOB_ALERT_WEBHOOK_URL");
+        env.apply(&mut jobs.jitter_secs, "JOB_JITTER_SECS");
+        env.apply(&mut jobs.timeout_secs, "JOB_TIMEOUT_SECS");
+        for (var, raw) in std::env::vars() {
+            apply_job_override(&mut jobs.overrides, &mut env, &var, &raw);
+        }
+
+        if env.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Invalid environment configuration:\n  - {}",
+                env.errors.join("\n  - ")
+            ))
+        }
+    }
+
+    /// Every problem at once, one per line.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut problems: Vec<String> = Vec::new();
+        let mut require = |missing: bool, what: &str| {
+            if missing {
+                problems.push(format!("{what} is required"));
+            }
+        };
+
+        require(
+            self.app.name_version.trim().is_empty(),
+            "app.name_version (APP_NAME_VERSION)",
+        );
+        // The listen address and certificate only matter for the HTTPS listener.
+        let tcp = self.server.socket_path.is_none();
+        require(
+            tcp && self.server.host_ip.is_none(),
+            "server.host_ip (HOST_IP)",
+        );
+        require(
+            tcp && self.server.host_port.is_none(),
+            "server.host_port (HOST_PORT)",
+        );
+        require(
+            tcp && self.server.cert_chain_path.is_none(),
+            "server.cert_chain_path (CERT_CHAIN_DIR)",
+        );
+        require(
+            tcp && self.server.priv_key_path.is_none(),
+            "server.priv_key_path (PRIV_KEY_DIR)",
+        );
+        require(self.server.api_key.is_none(), "server.api_key (X_API_KEY)");
+        let transport = self.email.transport_for(self.app.deployment_environment());
+        let smtp = transport == "smtp";
+        require(
+            smtp && self.email.smtp_url.is_none(),
+            "email.smtp_url (AWS_SES_SMTP_URL)",
+        );
+        require(
+            smtp && self.email.smtp_username.is_none(),
+            "email.smtp_username (AWS_SES_SMTP_USERNAME)",
+        );
+        require(
+            smtp && self.email.smtp_password.is_none(),
+            "email.smtp_password (AWS_SES_SMTP_ACCESS_KEY)",
+        );
+        require(
+            self.aws.image_upload_key.is_none(),
+            "aws.image_upload_key (AWS_IMAGE_UPLOAD_KEY)",
+        );
+        require(
+            self.aws.image_upload_secret_key.is_none(),
+            "aws.image_upload_secret_key (AWS_IMAGE_UPLOAD_SECRET_KEY)",
+        );
+
+        if let Err(e) = crate::init::config::DbConfig::from_config(&self.database) {
+            problems.push(format!("database: {e}"));
+        }
+        for name in self.email.environments.keys() {
+            if DeploymentEnvironment::parse(name).is_none() {
+                problems.push(format!(
+                    "email.environments.{name} must be local, dev, staging or prod"
+                ));
+            }
+        }
+        if let Err(e) = crate::util::email::sender::SenderIdentity::from_config(
+            &self.email,
+            self.app.deployment_environment(),
+        ) {
+            problems.push(format!("email sender: {e:#}"));
+        }
+
+        let mut check = |ok: bool, what: &str| {
+            if !ok {
+                problems.push(what.to_string());
+            }
+        };
+
+        check(
+            self.database.breaker_failures > 0,
+            "database.breaker_failures (DB_BREAKER_FAILURES) must be at least 1",
+        );
+        check(
+            self.database.breaker_cooldown_secs > 0,
+            "database.breaker_cooldown_secs (DB_BREAKER_COOLDOWN_SECS) must be at least 1",
+        );
+        check(
+            one_of(
+                &self.database.migrations,
+                &["apply", "check", "off", "none", "disabled"],
+            ),
+            "database.migrations (DB_MIGRATIONS) must be apply, check or off",
+        );
+        check(
+            self.database.migration_lock_timeout_secs > 0,
+            "database.migration_lock_timeout_secs (DB_MIGRATION_LOCK_TIMEOUT_SECS) must be at least 1",
+        );
+        // Public storage serves everything outside `private/`, dumps included.
+        check(
+            self.backup
+                .prefix
+                .trim()
+                .trim_start_matches('/')
+                .starts_with("private/"),
+            "backup.prefix (DB_BACKUP_PREFIX) must start with private/",
+        );
+        check(
+            self.backup.keep > 0,
+            "backup.keep (DB_BACKUP_KEEP) must be at least 1",
+        );
+        check(
+            self.email
+                .unsubscribe_secret
+                .as_deref()
+                .is_none_or(|secret| secret.trim().is_empty() || secret.trim().len() >= 32),
+            "email.unsubscribe_secret (EMAIL_UNSUBSCRIBE_SECRET) must be at least 32 characters",
+        );
+        check(
+            one_of(&transport, &["smtp", "ses", "file"]),
+            "email.transport (EMAIL_TRANSPORT) must be smtp, ses or file",
+        );
+        check(
+            one_of(&self.cache.backend, &["memory", "redis"]),
+            "cache.backend (CACHE_BACKEND) must be memory or redis",
+        );
+        check(
+            !one_of(&self.cache.backend, &["redis"]) || !self.cache.redis_url.trim().is_empty(),
+            "cache.redis_url (REDIS_URL) is required for the redis backend",
+        );
+        check(
+            one_of(&self.storage.backend, &["s3", "local", "fs", "filesystem"]),
+            "storage.backend (STORAGE_BACKEND) must be s3 or local",
+        );
+        check(
+            one_of(
+                &self.storage.orphan_cleanup,
+                &["off", "none", "disabled", "report", "delete"],
+            ),
+            "storage.orphan_cleanup (STORAGE_ORPHAN_CLEANUP) must be off, report or delete",
+        );
+        check(
+            self.storage.orphan_min_age_hours >= 0,
+            "storage.orphan_min_age_hours (STORAGE_ORPHAN_MIN_AGE_HOURS) must not be negative",
+        );
+        check(
+            self.cdn.url_ttl_secs > 0,
+            "cdn.url_ttl_secs (CLOUDFRONT_URL_TTL_SECS) must be at least 1",
+        );
+        check(
+            self.cdn.key_pair_id.is_some() == self.cdn.private_key_path.is_some(),
+            "cdn.key_pair_id and cdn.private_key_path must be set together",
+        );
+        check(
+            one_of(
+                &self.images.variant_cache,
+                &["storage", "disk", "fs", "off", "none", "disabled"],
+            ),
+            "images.variant_cache (IMAGE_VARIANT_CACHE) must be storage, disk or off",
+        );
+        check(
+            self.images.variant_render_concurrency != Some(0),
+            "images.variant_render_concurrency (IMAGE_VARIANT_RENDER_CONCURRENCY) must be at least 1",
+        );
+        check(
+            self.images.processing_queue_capacity > 0,
+            "images.processing_queue_capacity (IMAGE_PROCESSING_QUEUE_CAPACITY) must be at least 1",
+        );
+        check(
+            self.images.raw_converter_timeout_secs > 0,
+            "images.raw_converter_timeout_secs (RAW_CONVERTER_TIMEOUT_SECS) must be at least 1",
+        );
+        check(
+            one_of(
+                &self.images.duplicate_policy,
+                &["off", "none", "disabled", "warn", "reject", "deny"],
+            ),
+            "images.duplicate_policy (PHOTOGRAPH_DUPLICATE_POLICY) must be off, warn or reject",
+        );
+        check(
+            self.images.duplicate_threshold <= MAX_DUPLICATE_THRESHOLD,
+            &format!(
+                "images.duplicate_threshold (PHOTOGRAPH_DUPLICATE_THRESHOLD) must be at most {MAX_DUPLICATE_THRESHOLD}"
+            ),
+        );
+        check(
+            self.watermark.text.is_none()
+                || self.watermark.logo_path.is_some()
+                || self.watermark.font_path.is_some(),
+            "watermark.text (WATERMARK_TEXT) requires watermark.font_path (WATERMARK_FONT_PATH)",
+        );
+        check(
+            self.watermark
+                .position
+                .as_deref()
+                .is_none_or(|p| WatermarkCorner::from_param(p).is_some()),
+            "watermark.position (WATERMARK_POSITION) is not a corner",
+        );
+        check(
+            self.watermark.opacity > 0.0 && self.watermark.opacity <= 1.0,
+            "watermark.opacity (WATERMARK_OPACITY) must be in (0, 1]",
+        );
+        check(
+            self.watermark.scale > 0.0 && self.watermark.scale <= 1.0,
+            "watermark.scale (WATERMARK_SCALE) must be in (0, 1]",
+        );
+        check(
+            one_of(
+                &self.wasm.bundle_storage,
+                &["database", "db", "storage", "s3", "object"],
+            ),
+            "wasm.bundle_storage (WASM_BUNDLE_STORAGE) must be database or storage",
+        );
+        check(
+            self.rtc
+                .max_participants
+                .is_none_or(|n| MaxParticipants::try_new(n).is_ok()),
+            "rtc.max_participants (RTC_MAX_PARTICIPANTS) must be in 1..=64",
+        );
+        check(
+            (0.0..=1.0).contains(&self.access_log.sample_rate),
+            "access_log.sample_rate (ACCESS_LOG_SAMPLE_RATE) must be in 0..=1",
+        );
+        check(
+            self.access_log.retention_days > 0,
+            "access_log.retention_days (ACCESS_LOG_RETENTION_DAYS) must be at least 1",
+        );
+        check(
+            self.access_log.buffer_capacity > 0,
+            "access_log.buffer_capacity (ACCESS_LOG_BUFFER_CAPACITY) must be at least 1",
+        );
+        check(
+            self.soft_delete.retention_days > 0,
+            "soft_delete.retention_days (SOFT_DELETE_RETENTION_DAYS) must be at least 1",
+        );
+        check(
+            self.host_stats.min_interval_ms >= 100,
+            "host_stats.min_interval_ms (HOST_STATS_MIN_INTERVAL_MS) must be at least 100",
+        );
+        check(
+            self.host_stats.interval_ms >= self.host_stats.min_interval_ms,
+            "host_stats.interval_ms (HOST_STATS_INTERVAL_MS) must be at least host_stats.min_interval_ms",
+        );
+        check(
+            self.host_stats.max_subscribers > 0,
+            "host_stats.max_subscribers (HOST_STATS_MAX_SUBSCRIBERS) must be at least 1",
+        );
+        check(
+            self.log_files.compress_after_days > 0,
+            "log_files.compress_after_days (LOG_COMPRESS_AFTER_DAYS) must be at least 1",
+        );
+        check(
+            self.log_files.retention_days > self.log_files.compress_after_days,
+            "log_files.retention_days (LOG_RETENTION_DAYS) must be greater than log_files.compress_after_days",
+        );
+        if let Some(dsn) = self.error_reporting.dsn()
+            && let Err(e) = Dsn::parse(dsn)
+        {
+            check(
+                false,
+                &format!("error_reporting.dsn (ERROR_REPORTING_DSN) is invalid: {e}"),
+            );
+        }
+        check(
+            (0.0..=1.0).contains(&self.error_reporting.sample_rate),
+            "error_reporting.sample_rate (ERROR_REPORTING_SAMPLE_RATE) must be in 0..=1",
+        );
+        check(
+            self.error_reporting.max_per_minute > 0,
+            "error_reporting.max_per_minute (ERROR_REPORTING_MAX_PER_MINUTE) must be at least 1",
+        );
+        check(
+            self.error_reporting.queue_capacity > 0,
+            "error_reporting.queue_capacity (ERROR_REPORTING_QUEUE_CAPACITY) must be at least 1",
+        );
+        check(
+            self.jobs.history_retention_days > 0,
+            "jobs.history_retention_days (JOB_HISTORY_RETENTION_DAYS) must be at least 1",
+        );
+        check(
+            self.jobs
+                .session_purge_interval_secs
+                .is_none_or(|secs| (1..=86_400).contains(&secs)),
+            "jobs.session_purge_interval_secs (SESSION_PURGE_INTERVAL_SECS) must be in 1..=86400",
+        );
+        check(
+            self.jobs.delayed_task_poll_secs > 0,
+            "jobs.delayed_task_poll_secs (DELAYED_TASK_POLL_SECS) must be at least 1",
+        );
+        check(
+            self.jobs.delayed_task_visibility_secs > 0,
+            "jobs.delayed_task_visibility_secs (DELAYED_TASK_VISIBILITY_SECS) must be at least 1",
+        );
+        check(
+            self.jobs.email_outbox_poll_secs > 0,
+            "jobs.email_outbox_poll_secs (EMAIL_OUTBOX_POLL_SECS) must be at least 1",
+        );
+        check(
+            self.jobs.jitter_secs >= 0,
+            "jobs.jitter_secs (JOB_JITTER_SECS) must not be negative",
+        );
+
+        for (policy, limit) in [
+            ("global", &self.rate_limit.global),
+            ("read", &self.rate_limit.read),
+            ("write", &self.rate_limit.write),
+            ("auth", &self.rate_limit.auth),
+        ] {
+            if let Some(limit) = limit
+                && Limit::parse(limit).is_none()
+            {
+                problems.push(format!(
+                    "rate_limit.{policy} (RATE_LIMIT_{}) must be BURST/PERIOD_SECS, got {limit:?}",
+                    policy.to_ascii_uppercase()
+                ));
+            }
+        }
+
+        for (key, origins) in [
+            ("allowed_origins", &self.cors.allowed_origins),
+            ("local_origins", &self.cors.local_origins),
+            ("dev_origins", &self.cors.dev_origins),
+            ("staging_origins", &self.cors.staging_origins),
+            ("prod_origins", &self.cors.prod_origins),
+        ] {
+            for origin in origins.iter().map(|o| o.trim()) {
+                if origin != "*" && !is_origin(origin) {
+                    problems.push(format!(
+                        "cors.{key} (CORS_{}): {origin:?} is not scheme://host[:port]",
+                        key.to_ascii_uppercase()
+                    ));
+                }
+            }
+        }
+        for method in &self.cors.allowed_methods {
+            let method = method.trim();
+            if method != "*" && Method::from_bytes(method.as_bytes()).is_err() {
+                problems.push(format!(
+                    "cors.allowed_methods (CORS_ALLOWED_METHODS): {method:?} is not a method"
+                ));
+            }
+        }
+        for header in &self.cors.allowed_headers {
+            let header = header.trim();
+            if header != "*" && HeaderName::from_bytes(header.as_bytes()).is_err() {
+                problems.push(format!(
+                    "cors.allowed_headers (CORS_ALLOWED_HEADERS): {header:?} is not a header name"
+                ));
+            }
+        }
+        // tower-http panics on a wildcard with credentials; in prod a
+        // wildcard origin is refused outright, as is plain http.
+        let mut check = |ok: bool, what: &str| {
+            if !ok {
+                problems.push(what.to_string());
+            }
+        };
+        let env = self.app.deployment_environment();
+        let is_wildcard = |item: &str| item.trim() == "*";
+        let any_origin = self.cors.origins(env).any(is_wildcard);
+        if self.cors.allow_credentials {
+            check(
+                !any_origin,
+                "cors: a * origin requires cors.allow_credentials (CORS_ALLOW_CREDENTIALS) off",
+            );
+            check(
+                !self.cors.allowed_methods.iter().any(|m| is_wildcard(m))
+                    && !self.cors.allowed_headers.iter().any(|h| is_wildcard(h)),
+                "cors: * methods or headers require cors.allow_credentials (CORS_ALLOW_CREDENTIALS) off",
+            );
+        }
+        if matches!(env, DeploymentEnvironment::Prod) {
+            check(!any_origin, "cors: * origins are not allowed in prod");
+            check(
+                self.cors
+                    .origins(env)
+                    .all(|origin| is_wildcard(origin) || origin.starts_with("https://")),
+                "cors: origins must use https in prod",
+            );
+        }
+
+        for (job_name, job) in &self.jobs.overrides {
+            if let Some(schedule) = &job.schedule
+                && let Err(e) = Schedule::parse(schedule)
+            {
+                problems.push(format!(
+                    "jobs.overrides.{job_name}.schedule (JOB_SCHEDULE_{job_name}): {e}"
+                ));
+            }
+            if let Some(overlap) = &job.overlap
+                && !one_of(overlap, &["skip", "queue"])
+            {
+                problems.push(format!(
+                    "jobs.overrides.{job_name}.overlap (JOB_OVERLAP_{job_name}) must be skip or queue"
+                ));
+            }
+            if job.jitter_secs.is_some_and(|secs| secs < 0) {
+                problems.push(format!(
+                    "jobs.overrides.{job_name}.jitter_secs (JOB_JITTER_SECS_{job_name}) must not be negative"
+                ));
+            }
+        }
+
+        let mut site_names = std::collections::HashSet::new();
+        let mut site_hosts = std::collections::HashSet::new();
+        for (i, site) in self.sites.iter().enumerate() {
+            let name = site.name.trim();
+            if name.is_empty() || name.eq_ignore_ascii_case(DEFAULT_SITE_NAME) {
+                problems.push(format!(
+                    "sites[{i}].name must be set and not {DEFAULT_SITE_NAME:?}"
+                ));
+            } else if !site_names.insert(name.to_ascii_lowercase()) {
+                problems.push(format!("sites[{i}].name {name:?} is used twice"));
+            }
+            if site.hosts.is_empty() {
+                problems.push(format!("sites[{i}].hosts must list at least one host"));
+            }
+            for host in &site.hosts {
+                let host = host.trim().to_ascii_lowercase();
+                if host.is_empty() || host.contains([':', '/']) {
+                    problems.push(format!(
+                        "sites[{i}].hosts: {host:?} is not a bare host name"
+                    ));
+                } else if !site_hosts.insert(host.clone()) {
+                    problems.push(format!("sites[{i}].hosts: {host:?} belongs to two sites"));
+                }
+            }
+            if let Some(frontend) = &site.frontend
+                && (frontend.is_empty()
+                    || !frontend
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')))
+            {
+                problems.push(format!(
+                    "sites[{i}].frontend {frontend:?} must be a directory name under fe/sites/"
+                ));
+            }
+            if site
+                .s3_bucket
+                .as_deref()
+                .is_some_and(|b| b.trim().is_empty())
+            {
+                problems.push(format!("sites[{i}].s3_bucket must not be empty when set"));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Invalid configuration:\n  - {}",
+                problems.join("\n  - ")
+            ))
+        }
+    }
+}
+
+fn config_path() -> anyhow::Result<Option<PathBuf>> {
+    match std::env::var("APP_CONFIG") {
+        Ok(path) if !path.trim().is_empty() => {
+            let path = PathBuf::from(path.trim());
+            if path.is_file() {
+                Ok(Some(path))
+            } else {
+                Err(anyhow!("APP_CONFIG {} does not exist", path.display()))
+            }
+        }
+        _ => {
+            let path = PathBuf::from(DEFAULT_CONFIG_PATH);
+            Ok(path.is_file().then_some(path))
+        }
+    }
+}
+
+fn one_of(value: &str, allowed: &[&str]) -> bool {
+    allowed.iter().any(|a| value.trim().eq_ignore_ascii_case(a))
+}
+
+/// `scheme://host[:port]` exactly as browsers send it in `Origin`: no path,
+/// not even a trailing slash.
+fn is_origin(value: &str) -> bool {
+    !value.ends_with('/')
+        && value.parse::<Uri>().is_ok_and(|uri| {
+            uri.scheme().is_some()
+                && uri.authority().is_some()
+                && uri.path_and_query().is_none_or(|pq| pq.as_str() == "/")
+        })
+}
+
+/// `JOB_<SETTING>_<JOB_NAME>` into `overrides`; other variables are ignored.
+fn apply_job_override(
+    overrides: &mut BTreeMap<String, JobOverride>,
+    env: &mut EnvOverrides,
+    var: &str,
+    raw: &str,
+) {
+    // Longest prefixes first: `JOB_JITTER_SECS_X`, not a job named `SECS_X`.
+    const PREFIXES: [&str; 6] = [
+        "JOB_SCHEDULE_",
+        "JOB_PAUSED_",
+        "JOB_CATCH_UP_",
+        "JOB_OVERLAP_",
+        "JOB_JITTER_SECS_",
+        "JOB_TIMEOUT_SECS_",
+    ];
+    let Some((prefix, job_name)) = PREFIXES.iter().find_map(|prefix| {
+        var.strip_prefix(prefix)
+            .filter(|name| !name.is_empty())
+            .map(|name| (*prefix, name))
+    }) else {
+        return;
+    };
+    let job = overrides.entry(job_name.to_string()).or_default();
+    match prefix {
+        "JOB_SCHEDULE_" => env.apply_raw(&mut job.schedule, var, raw),
+        "JOB_PAUSED_" => env.apply_raw(&mut job.paused, var, raw),
+        "JOB_CATCH_UP_" => env.apply_raw(&mut job.catch_up, var, raw),
+        "JOB_OVERLAP_" => env.apply_raw(&mut job.overlap, var, raw),
+        "JOB_JITTER_SECS_" => env.apply_raw(&mut job.jitter_secs, var, raw),
+        _ => env.apply_raw(&mut job.timeout_secs, var, raw),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_toml_sections_with_defaults() {
+        let config: AppConfig = toml::from_str(
+            r#"
+            [server]
+            host_port = 8443
+
+            [jobs.overrides.SYNC_I18N_DATA]
+            paused = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.server.host_port, Some(8443));
+        assert_eq!(config.server.shutdown_timeout_secs, 30);
+        assert_eq!(
+            config.jobs.job("SYNC_I18N_DATA").and_then(|job| job.paused),
+            Some(true)
+        );
+        assert!(toml::from_str::<AppConfig>("[server]\nhost_prot = 1").is_err());
+    }
+
+    #[test]
+    fn routes_job_variables_by_prefix() {
+        let mut overrides = BTreeMap::new();
+        let mut env = EnvOverrides::default();
+        apply_job_override(&mut overrides, &mut env, "JOB_JITTER_SECS_BACKUP", "30");
+        apply_job_override(&mut overrides, &mut env, "JOB_OVERLAP_BACKUP", "queue");
+        apply_job_override(&mut overrides, &mut env, "JOB_CLUSTER_LOCKS", "off");
+        assert!(env.errors.is_empty());
+        assert_eq!(overrides.len(), 1);
+        let job = &overrides["BACKUP"];
+        assert_eq!(job.jitter_secs, Some(30));
+        assert_eq!(job.overlap.as_deref(), Some("queue"));
+    }
+
+    #[test]
+    fn validation_lists_every_problem() {
+        let err = AppConfig::default().validate().unwrap_err().to_string();
+        assert!(err.contains("HOST_IP"));
+        assert!(err.contains("X_API_KEY"));
+        assert!(err.contains("database:"));
+        assert!(!err.contains("DB_BACKUP_PREFIX"));
+    }
+
+    #[test]
+    fn backup_prefix_must_be_private() {
+        let mut config = AppConfig::default();
+        config.backup.prefix = "backups/db/".to_string();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("DB_BACKUP_PREFIX"));
+    }
+}
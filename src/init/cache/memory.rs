@@ -0,0 +1,203 @@
+//! In-process implementation of [`Cache`].
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use scc::hash_map::Entry;
+use tokio::time::Instant;
+
+use super::Cache;
+
+struct MemoryEntry {
+    value: Vec<u8>,
+    expires_at: Instant,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// When the bucket is back to `burst` and can be dropped.
+    full_at: Instant,
+}
+
+impl Bucket {
+    /// Take one token, or return how long until one is available.
+    fn take(&mut self, burst: f64, per_second: f64, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(burst);
+        self.updated = now;
+        let wait = if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / per_second))
+        };
+        self.full_at = now + Duration::from_secs_f64((burst - self.tokens) / per_second);
+        wait
+    }
+}
+
+pub struct MemoryCache {
+    entries: scc::HashMap<String, MemoryEntry>,
+    buckets: scc::HashMap<String, Bucket>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self {
+            entries: scc::HashMap::new(),
+            buckets: scc::HashMap::new(),
+        }
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let now = Instant::now();
+        Ok(self
+            .entries
+            .read_async(key, |_, entry| {
+                (entry.expires_at > now).then(|| entry.value.clone())
+            })
+            .await
+            .flatten())
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> anyhow::Result<()> {
+        let entry = MemoryEntry {
+            value,
+            expires_at: Instant::now() + ttl,
+        };
+        match self.entries.entry_async(key.to_string()).await {
+            Entry::Occupied(mut occ) => *occ.get_mut() = entry,
+            Entry::Vacant(vac) => {
+                vac.insert_entry(entry);
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> anyhow::Result<bool> {
+        let now = Instant::now();
+        let entry = MemoryEntry {
+            value,
+            expires_at: now + ttl,
+        };
+        Ok(match self.entries.entry_async(key.to_string()).await {
+            Entry::Occupied(mut occ) if occ.get().expires_at <= now => {
+                *occ.get_mut() = entry;
+                true
+            }
+            Entry::Occupied(_) => false,
+            Entry::Vacant(vac) => {
+                vac.insert_entry(entry);
+                true
+            }
+        })
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.entries.remove_async(key).await;
+        Ok(())
+    }
+
+    async fn take_token(
+        &self,
+        key: &str,
+        burst: f64,
+        per_second: f64,
+    ) -> anyhow::Result<Option<Duration>> {
+        let now = Instant::now();
+        Ok(match self.buckets.entry_async(key.to_string()).await {
+            Entry::Occupied(mut occ) => occ.get_mut().take(burst, per_second, now),
+            Entry::Vacant(vac) => {
+                let mut bucket = Bucket {
+                    tokens: burst,
+                    updated: now,
+                    full_at: now,
+                };
+                let wait = bucket.take(burst, per_second, now);
+                vac.insert_entry(bucket);
+                wait
+            }
+        })
+    }
+
+    async fn purge_expired(&self) -> usize {
+        let now = Instant::now();
+        let before = self.entries.len() + self.buckets.len();
+        self.entries
+            .retain_async(|_, entry| entry.expires_at > now)
+            .await;
+        self.buckets
+            .retain_async(|_, bucket| bucket.full_at > now)
+            .await;
+        before.saturating_sub(self.entries.len() + self.buckets.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_drains_and_refills() {
+        let start = Instant::now();
+        let mut bucket = Bucket {
+            tokens: 2.0,
+            updated: start,
+            full_at: start,
+        };
+        assert_eq!(bucket.take(2.0, 1.0, start), None);
+        assert_eq!(bucket.take(2.0, 1.0, start), None);
+        assert_eq!(bucket.take(2.0, 1.0, start), Some(Duration::from_secs(1)));
+        assert_eq!(bucket.full_at, start + Duration::from_secs(2));
+
+        let later = start + Duration::from_millis(1500);
+        assert_eq!(bucket.take(2.0, 1.0, later), None);
+        assert!(bucket.take(2.0, 1.0, later).is_some());
+    }
+
+    #[tokio::test]
+    async fn set_if_absent_respects_expiry() {
+        let cache = MemoryCache::new();
+        assert!(
+            cache
+                .set_if_absent("k", vec![1], Duration::from_secs(60))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !cache
+                .set_if_absent("k", vec![2], Duration::from_secs(60))
+                .await
+                .unwrap()
+        );
+        assert_eq!(cache.get("k").await.unwrap(), Some(vec![1]));
+
+        cache.set("gone", vec![3], Duration::ZERO).await.unwrap();
+        assert_eq!(cache.get("gone").await.unwrap(), None);
+        assert!(
+            cache
+                .set_if_absent("gone", vec![4], Duration::from_secs(60))
+                .await
+                .unwrap()
+        );
+    }
+}
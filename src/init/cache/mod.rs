@@ -0,0 +1,72 @@
+//! Key-value cache for state that every instance of a deployment should
+//! agree on: rate-limit buckets, post view dedup markers and rendered i18n
+//! UI text bundles.
+//!
+//! Callers go through the [`Cache`] on `ServerState::cache`. `cache.backend`
+//! (`CACHE_BACKEND`) selects the implementation:
+//! - `memory` (default): [`memory::MemoryCache`], per process. Fine for a
+//!   single instance; expired entries are purged every minute.
+//! - `redis`: [`redis_cache::RedisCache`] at `REDIS_URL` (default
+//!   `redis://127.0.0.1:6379`), shared by all instances. Keys are prefixed
+//!   with `CACHE_KEY_PREFIX` (default empty) so deployments can share a
+//!   server.
+//!
+//! The cache is never the source of truth. Callers treat an error as a miss
+//! (or, for rate limits, as an allowed request) and carry on.
+
+pub mod memory;
+pub mod redis_cache;
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::init::app_config::CacheSection;
+
+#[async_trait]
+pub trait Cache: Send + Sync {
+    /// Short backend name for logs (`memory`, `redis`).
+    fn name(&self) -> &'static str;
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> anyhow::Result<()>;
+
+    /// Store `value` only when `key` is absent. `true` when it was stored.
+    async fn set_if_absent(&self, key: &str, value: Vec<u8>, ttl: Duration)
+    -> anyhow::Result<bool>;
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+
+    /// Take one token from the bucket at `key`, which holds up to `burst`
+    /// tokens and refills at `per_second`. `Some(wait)` when the bucket is
+    /// empty, with the time until the next token.
+    async fn take_token(
+        &self,
+        key: &str,
+        burst: f64,
+        per_second: f64,
+    ) -> anyhow::Result<Option<Duration>>;
+
+    /// Drop expired entries and refilled buckets. Backends that expire keys
+    /// themselves return 0.
+    async fn purge_expired(&self) -> usize {
+        0
+    }
+}
+
+pub async fn cache_from_config(config: &CacheSection) -> anyhow::Result<Arc<dyn Cache>> {
+    let cache: Arc<dyn Cache> = match config.backend.trim().to_ascii_lowercase().as_str() {
+        "" | "memory" => Arc::new(memory::MemoryCache::new()),
+        "redis" => Arc::new(redis_cache::RedisCache::from_config(config).await?),
+        other => {
+            return Err(anyhow!(
+                "Unknown CACHE_BACKEND: {other} (expected memory or redis)"
+            ));
+        }
+    };
+    info!(backend = cache.name(), "Cache configured");
+    Ok(cache)
+}
@@ -0,0 +1,147 @@
+//! Redis implementation of [`Cache`].
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use redis::{Script, aio::ConnectionManager};
+use tracing::info;
+
+use super::Cache;
+use crate::init::app_config::CacheSection;
+
+/// Token bucket kept in a hash (`tokens`, `ts` in ms), refilled against the
+/// server clock so instances with skewed clocks agree. Returns the wait in
+/// ms, 0 when a token was taken. The key expires once the bucket is full.
+const TAKE_TOKEN_SCRIPT: &str = r#"
+local burst = tonumber(ARGV[1])
+local per_ms = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1]) or burst
+local ts = tonumber(state[2]) or now
+tokens = math.min(burst, tokens + math.max(0, now - ts) * per_ms)
+local wait = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+else
+  wait = math.ceil((1 - tokens) / per_ms)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil((burst - tokens) / per_ms) + 1000)
+return wait
+"#;
+
+pub struct RedisCache {
+    connection: ConnectionManager,
+    prefix: String,
+    take_token: Script,
+}
+
+impl RedisCache {
+    /// `cache.redis_url` (default `redis://127.0.0.1:6379`) and
+    /// `cache.key_prefix`.
+    /// Fails when Redis is unreachable at startup; later outages are retried
+    /// by the connection manager.
+    pub async fn from_config(config: &CacheSection) -> anyhow::Result<Self> {
+        let url = config.redis_url.trim();
+        let prefix = config.key_prefix.clone();
+
+        let client = redis::Client::open(url).map_err(|e| anyhow!("Invalid REDIS_URL: {e}"))?;
+        let connection = ConnectionManager::new(client)
+            .await
+            .map_err(|e| anyhow!("Could not connect to Redis: {e}"))?;
+
+        info!(prefix = %prefix, "Using Redis cache");
+
+        Ok(Self {
+            connection,
+            prefix,
+            take_token: Script::new(TAKE_TOKEN_SCRIPT),
+        })
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
+    }
+}
+
+/// Millisecond TTL, at least 1 (Redis rejects `PX 0`).
+fn ttl_millis(ttl: Duration) -> u64 {
+    (ttl.as_millis() as u64).max(1)
+}
+
+#[async_trait]
+impl Cache for RedisCache {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut connection = self.connection.clone();
+        redis::cmd("GET")
+            .arg(self.key(key))
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| anyhow!("Redis GET {key} failed: {e}"))
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> anyhow::Result<()> {
+        let mut connection = self.connection.clone();
+        redis::cmd("SET")
+            .arg(self.key(key))
+            .arg(value)
+            .arg("PX")
+            .arg(ttl_millis(ttl))
+            .query_async::<()>(&mut connection)
+            .await
+            .map_err(|e| anyhow!("Redis SET {key} failed: {e}"))
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> anyhow::Result<bool> {
+        let mut connection = self.connection.clone();
+        let stored: Option<String> = redis::cmd("SET")
+            .arg(self.key(key))
+            .arg(value)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_millis(ttl))
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| anyhow!("Redis SET NX {key} failed: {e}"))?;
+        Ok(stored.is_some())
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let mut connection = self.connection.clone();
+        redis::cmd("DEL")
+            .arg(self.key(key))
+            .query_async::<()>(&mut connection)
+            .await
+            .map_err(|e| anyhow!("Redis DEL {key} failed: {e}"))
+    }
+
+    async fn take_token(
+        &self,
+        key: &str,
+        burst: f64,
+        per_second: f64,
+    ) -> anyhow::Result<Option<Duration>> {
+        let mut connection = self.connection.clone();
+        let wait_ms: u64 = self
+            .take_token
+            .key(self.key(key))
+            .arg(burst)
+            .arg(per_second / 1000.0)
+            .invoke_async(&mut connection)
+            .await
+            .map_err(|e| anyhow!("Redis token bucket {key} failed: {e}"))?;
+        Ok((wait_ms > 0).then(|| Duration::from_millis(wait_ms)))
+    }
+}
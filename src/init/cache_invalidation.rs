@@ -0,0 +1,183 @@
+//! Cross-instance cache invalidation over Postgres LISTEN/NOTIFY.
+//!
+//! Triggers on the tables behind the post, country and i18n caches announce
+//! row changes on the [`CHANNEL`] channel (see the `cache_invalidation`
+//! migration). Each instance holds one dedicated connection LISTENing there
+//! and refreshes the matching [`ServerState`] cache, so an edit made through
+//! one instance shows on the others within a second instead of at the next
+//! scheduled sync. Notifications arriving close together are coalesced into
+//! one refresh per cache.
+//!
+//! NOTIFY is not durable: anything sent while the listener is disconnected is
+//! lost, so every reconnect starts with a full refresh of all three caches.
+//! The scheduled syncs keep running as the backstop.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use futures_util::StreamExt;
+use serde_derive::Deserialize;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::init::state::ServerState;
+
+/// Channel the `notify_cache_invalidation()` trigger function sends on.
+pub const CHANNEL: &str = "cache_invalidation";
+
+/// How long to keep collecting notifications before refreshing.
+const COALESCE_WINDOW: Duration = Duration::from_millis(250);
+
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
+/// Payload of one notification.
+#[derive(Deserialize)]
+struct Invalidation {
+    table: String,
+    id: Option<String>,
+}
+
+/// Caches to refresh after a coalescing window.
+#[derive(Default)]
+struct PendingRefresh {
+    posts: HashSet<Uuid>,
+    all_posts: bool,
+    countries: bool,
+    i18n: bool,
+}
+
+impl PendingRefresh {
+    fn everything() -> Self {
+        Self {
+            posts: HashSet::new(),
+            all_posts: true,
+            countries: true,
+            i18n: true,
+        }
+    }
+
+    fn add(&mut self, payload: &str) {
+        let invalidation: Invalidation = match serde_json::from_str(payload) {
+            Ok(invalidation) => invalidation,
+            Err(e) => {
+                warn!(error = %e, %payload, "Ignoring unreadable cache invalidation");
+                return;
+            }
+        };
+        match invalidation.table.as_str() {
+            "posts" | "post_tags" => match invalidation.id.as_deref().map(Uuid::parse_str) {
+                Some(Ok(post_id)) => {
+                    self.posts.insert(post_id);
+                }
+                _ => self.all_posts = true,
+            },
+            "tags" => self.all_posts = true,
+            "iso_country" | "iso_country_subdivision" | "iso_language" | "iso_currency" => {
+                self.countries = true
+            }
+            "i18n_strings" => self.i18n = true,
+            other => debug!(table = %other, "Ignoring cache invalidation for unknown table"),
+        }
+    }
+
+    async fn apply(self, state: &ServerState) {
+        if self.all_posts {
+            state.synchronize_post_info_cache().await;
+        } else {
+            for post_id in self.posts {
+                if let Err(e) = state.refresh_cached_post(post_id).await {
+                    error!(error = ?e, %post_id, "Could not refresh invalidated post");
+                }
+            }
+        }
+        if self.countries
+            && let Err(e) = state.sync_country_data().await
+        {
+            error!(error = ?e, "Could not refresh invalidated country data");
+        }
+        if self.i18n
+            && let Err(e) = state.sync_i18n_data().await
+        {
+            error!(error = ?e, "Could not refresh invalidated i18n data");
+        }
+    }
+}
+
+/// Start the listener task when `cache.invalidation_listen` is on. Runs until
+/// shutdown, reconnecting with backoff when the connection drops.
+pub fn spawn_cache_invalidation_listener(state: Arc<ServerState>, db_url: String) {
+    if !state.config.cache.invalidation_listen {
+        info!("CACHE_INVALIDATION_LISTEN=false; caches refresh on schedule only.");
+        return;
+    }
+    tokio::spawn(async move {
+        let mut backoff = RECONNECT_MIN;
+        let mut resync = false;
+        while !state.shutdown.is_shutting_down() {
+            let connected = listen(&state, &db_url, resync).await;
+            if state.shutdown.is_shutting_down() {
+                break;
+            }
+            match connected {
+                // It was connected, so retry promptly rather than backing off.
+                Ok(()) => {
+                    warn!("Cache invalidation connection closed; reconnecting");
+                    backoff = RECONNECT_MIN;
+                }
+                Err(e) => {
+                    warn!(error = %e, retry_in = ?backoff, "Cache invalidation listener disconnected")
+                }
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(RECONNECT_MAX);
+            resync = true;
+        }
+    });
+}
+
+/// LISTEN on a fresh connection and apply notifications until it drops.
+/// `Ok` means the connection worked before it was lost.
+async fn listen(state: &ServerState, db_url: &str, resync: bool) -> anyhow::Result<()> {
+    let mut conn = AsyncPgConnection::establish(db_url).await?;
+    diesel::sql_query(format!("LISTEN {CHANNEL}"))
+        .execute(&mut conn)
+        .await?;
+    info!(channel = CHANNEL, "Listening for cache invalidations");
+
+    if resync {
+        PendingRefresh::everything().apply(state).await;
+    }
+
+    let mut notifications = std::pin::pin!(conn.notifications_stream());
+    loop {
+        let Some(first) = notifications.next().await else {
+            return Ok(());
+        };
+        let mut pending = PendingRefresh::default();
+        pending.add(&first?.payload);
+
+        let window = tokio::time::sleep(COALESCE_WINDOW);
+        tokio::pin!(window);
+        let mut closed = false;
+        loop {
+            tokio::select! {
+                _ = &mut window => break,
+                next = notifications.next() => match next {
+                    Some(notification) => pending.add(&notification?.payload),
+                    None => {
+                        closed = true;
+                        break;
+                    }
+                },
+            }
+        }
+
+        pending.apply(state).await;
+        if closed {
+            return Ok(());
+        }
+    }
+}
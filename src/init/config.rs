@@ -1,6 +1,8 @@
 use anyhow::anyhow;
 use lettre::transport::smtp::authentication::Credentials;
 
+use crate::init::app_config::{DatabaseSection, EmailSection, required};
+
 enum DbType {
     Postgres,
     MySql,
@@ -19,37 +21,28 @@ pub struct DbConfig {
 }
 
 impl DbConfig {
-    pub fn from_env() -> anyhow::Result<Self> {
-        let is_socket_path = std::env::var("DB_HOST")
-            .ok()
+    pub fn from_config(config: &DatabaseSection) -> anyhow::Result<Self> {
+        let is_socket_path = config
+            .host
+            .as_deref()
             .is_some_and(|host| host.starts_with('/'));
 
-        if !is_socket_path && let Ok(db_url) = std::env::var("DB_URL") {
-            return Self::from_url(&db_url);
+        if !is_socket_path && let Some(db_url) = &config.url {
+            return Self::from_url(db_url);
         }
 
         let db_type = DbType::Postgres;
-        let db_host = std::env::var("DB_HOST")
-            .map_err(|_| anyhow!("Environment variable DB_HOST not found"))?;
+        let db_host = required(&config.host, "host (DB_HOST)")?.clone();
 
         let db_port = if db_host.starts_with('/') {
             None
         } else {
-            Some(
-                std::env::var("DB_PORT")
-                    .map_err(|_| anyhow!("Environment variable DB_PORT not found"))?
-                    .parse::<u16>()?,
-            )
+            Some(*required(&config.port, "port (DB_PORT)")?)
         };
 
-        let db_username = std::env::var("DB_USERNAME")
-            .map_err(|_| anyhow!("Environment variable DB_USERNAME not found"))?;
-
-        let db_password = std::env::var("DB_PASSWORD")
-            .map_err(|_| anyhow!("Environment variable DB_PASSWORD not found"))?;
-
-        let db_name = std::env::var("DB_NAME")
-            .map_err(|_| anyhow!("Environment variable DB_NAME not found"))?;
+        let db_username = required(&config.username, "username (DB_USERNAME)")?.clone();
+        let db_password = required(&config.password, "password (DB_PASSWORD)")?.clone();
+        let db_name = required(&config.name, "name (DB_NAME)")?.clone();
 
         Ok(DbConfig {
             db_type,
@@ -164,6 +157,21 @@ impl DbConfig {
 
         Ok(db_url)
     }
+
+    /// libpq environment (`PGHOST`, `PGPASSWORD`, ...) for client tools such
+    /// as `pg_dump`, so credentials stay off their command line.
+    pub fn libpq_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            ("PGHOST", self.db_host.clone()),
+            ("PGUSER", self.db_username.clone()),
+            ("PGPASSWORD", self.db_password.clone()),
+            ("PGDATABASE", self.db_name.clone()),
+        ];
+        if let Some(port) = self.db_port {
+            env.push(("PGPORT", port.to_string()));
+        }
+        env
+    }
 }
 
 pub struct EmailConfig {
@@ -173,13 +181,10 @@ pub struct EmailConfig {
 }
 
 impl EmailConfig {
-    pub fn from_env() -> anyhow::Result<Self> {
-        let smtp_url = std::env::var("AWS_SES_SMTP_URL")
-            .map_err(|_| anyhow!("Environment variable AWS_SES_SMTP_URL not found"))?;
-        let smtp_username = std::env::var("AWS_SES_SMTP_USERNAME")
-            .map_err(|_| anyhow!("Environment variable AWS_SES_SMTP_USERNAME not found"))?;
-        let smtp_password = std::env::var("AWS_SES_SMTP_ACCESS_KEY")
-            .map_err(|_| anyhow!("Environment variable AWS_SES_SMTP_ACCESS_KEY not found"))?;
+    pub fn from_config(config: &EmailSection) -> anyhow::Result<Self> {
+        let smtp_url = required(&config.smtp_url, "email.smtp_url")?.clone();
+        let smtp_username = required(&config.smtp_username, "email.smtp_username")?.clone();
+        let smtp_password = required(&config.smtp_password, "email.smtp_password")?.clone();
 
         Ok(EmailConfig {
             smtp_url,
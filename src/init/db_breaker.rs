@@ -0,0 +1,238 @@
+//! Circuit breaker around pool checkouts.
+//!
+//! Without it, a database outage makes every handler wait out the pool's
+//! connection timeout before failing. After `DB_BREAKER_FAILURES` (default 5)
+//! consecutive failed checkouts the breaker opens and `get_conn` fails at once
+//! with [`DbUnavailable`] for `DB_BREAKER_COOLDOWN_SECS` (default 10). The
+//! first checkout after the cooldown is let through as a probe: success closes
+//! the breaker, failure reopens it for another cooldown.
+//!
+//! Endpoints served from in-memory caches (posts list, i18n bundles,
+//! countries) keep answering while the breaker is open. The read-replica pool
+//! has its own breaker; while that one is open, reads go to the primary.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::init::app_config::DatabaseSection;
+use crate::util::metrics::MetricsWriter;
+
+/// Returned (inside `anyhow::Error`) by `get_conn` while the breaker is open.
+#[derive(Debug, Clone, Copy)]
+pub struct DbUnavailable {
+    pub retry_after: Duration,
+}
+
+impl std::fmt::Display for DbUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "database circuit open; retry after {}s",
+            self.retry_after.as_secs().max(1)
+        )
+    }
+}
+
+impl std::error::Error for DbUnavailable {}
+
+#[derive(Debug, Clone, Copy)]
+enum BreakerState {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A probe checkout is in flight. Should it never report back (the
+    /// request was dropped), another probe is allowed after `until`.
+    HalfOpen {
+        until: Instant,
+    },
+}
+
+pub struct DbBreaker {
+    /// `primary` or `replica`, for logs and metric labels.
+    pool: &'static str,
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+    trips: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl DbBreaker {
+    pub fn from_config(pool: &'static str, config: &DatabaseSection) -> Self {
+        Self::new(
+            pool,
+            config.breaker_failures,
+            Duration::from_secs(config.breaker_cooldown_secs),
+        )
+    }
+
+    pub fn new(pool: &'static str, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            pool,
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+            trips: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether a checkout may be attempted now.
+    pub fn admit(&self) -> Result<(), DbUnavailable> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match *state {
+            BreakerState::Closed { .. } => Ok(()),
+            BreakerState::Open { until } | BreakerState::HalfOpen { until } if now >= until => {
+                *state = BreakerState::HalfOpen {
+                    until: now + self.cooldown,
+                };
+                Ok(())
+            }
+            BreakerState::Open { until } | BreakerState::HalfOpen { until } => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                Err(DbUnavailable {
+                    retry_after: until - now,
+                })
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !matches!(*state, BreakerState::Closed { .. }) {
+            info!(pool = self.pool, "Database reachable again; circuit closed");
+        }
+        *state = BreakerState::Closed { failures: 0 };
+    }
+
+    pub fn record_failure(&self) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match *state {
+            BreakerState::Closed { failures } if failures + 1 < self.failure_threshold => {
+                *state = BreakerState::Closed {
+                    failures: failures + 1,
+                };
+            }
+            BreakerState::Closed { .. } | BreakerState::HalfOpen { .. } => {
+                self.trips.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    pool = self.pool,
+                    cooldown_secs = self.cooldown.as_secs(),
+                    "Database checkouts failing; circuit opened"
+                );
+                *state = BreakerState::Open {
+                    until: now + self.cooldown,
+                };
+            }
+            // A checkout started before the breaker opened.
+            BreakerState::Open { .. } => {}
+        }
+    }
+
+    pub fn pool(&self) -> &'static str {
+        self.pool
+    }
+
+    /// `closed`, `half_open` or `open`.
+    pub fn state_name(&self) -> &'static str {
+        match *self.state.lock().unwrap_or_else(|e| e.into_inner()) {
+            BreakerState::Closed { .. } => "closed",
+            BreakerState::HalfOpen { .. } => "half_open",
+            BreakerState::Open { .. } => "open",
+        }
+    }
+
+    fn state_value(&self) -> f64 {
+        match *self.state.lock().unwrap_or_else(|e| e.into_inner()) {
+            BreakerState::Closed { .. } => 0.0,
+            BreakerState::HalfOpen { .. } => 1.0,
+            BreakerState::Open { .. } => 2.0,
+        }
+    }
+}
+
+/// One family per metric, with a `pool` label per breaker.
+pub fn write_metrics(w: &mut MetricsWriter, breakers: &[&DbBreaker]) {
+    w.header(
+        "db_circuit_state",
+        "gauge",
+        "Database circuit breaker state by pool: 0 closed, 1 half-open, 2 open.",
+    );
+    for breaker in breakers {
+        w.sample(
+            "db_circuit_state",
+            &[("pool", breaker.pool)],
+            breaker.state_value(),
+        );
+    }
+    w.header(
+        "db_circuit_trips_total",
+        "counter",
+        "Times the database circuit breaker opened, by pool.",
+    );
+    for breaker in breakers {
+        w.sample(
+            "db_circuit_trips_total",
+            &[("pool", breaker.pool)],
+            breaker.trips.load(Ordering::Relaxed) as f64,
+        );
+    }
+    w.header(
+        "db_circuit_rejections_total",
+        "counter",
+        "Pool checkouts refused while the circuit was open, by pool.",
+    );
+    for breaker in breakers {
+        w.sample(
+            "db_circuit_rejections_total",
+            &[("pool", breaker.pool)],
+            breaker.rejected.load(Ordering::Relaxed) as f64,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expire_cooldown(breaker: &DbBreaker) {
+        let mut state = breaker.state.lock().unwrap();
+        if let BreakerState::Open { until } | BreakerState::HalfOpen { until } = &mut *state {
+            *until = Instant::now();
+        }
+    }
+
+    #[test]
+    fn opens_after_threshold_and_probes_after_cooldown() {
+        let breaker = DbBreaker::new("primary", 2, Duration::from_secs(10));
+        breaker.record_failure();
+        assert!(breaker.admit().is_ok());
+        breaker.record_failure();
+        assert!(breaker.admit().is_err());
+
+        expire_cooldown(&breaker);
+        assert!(
+            breaker.admit().is_ok(),
+            "first caller after cooldown probes"
+        );
+        assert!(breaker.admit().is_err(), "one probe at a time");
+
+        breaker.record_failure();
+        assert!(breaker.admit().is_err());
+
+        expire_cooldown(&breaker);
+        assert!(breaker.admit().is_ok());
+        breaker.record_success();
+        assert_eq!(breaker.state_name(), "closed");
+        assert!(breaker.admit().is_ok());
+    }
+}
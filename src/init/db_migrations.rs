@@ -9,31 +9,127 @@
 //! libpq [`PgConnection`] is established on a blocking thread purely to run the
 //! pending migrations, then dropped; all request-path queries continue to use the
 //! async `bb8` pool.
+//!
+//! `DB_MIGRATIONS` picks what startup does: `apply` (default) runs the pending
+//! migrations, `check` refuses to start while any are pending, and `off` skips
+//! the step for deployments that migrate out of band. Instances starting
+//! together serialise on a Postgres advisory lock, so only the first applies
+//! anything and the rest find the schema current. Each migration runs in its
+//! own transaction; a failure stops startup with a report of what was applied,
+//! what failed and what is still pending.
+
+use std::fmt;
+use std::time::Duration;
 
-use diesel::Connection;
-use diesel::pg::PgConnection;
+use diesel::pg::{Pg, PgConnection};
+use diesel::{Connection, RunQueryDsl, sql_query};
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
-use tracing::info;
+use tracing::{info, warn};
+
+use crate::init::app_config::DatabaseSection;
 
 /// Migrations embedded from the crate-root `migrations/` directory.
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
-/// Apply all pending embedded migrations against `db_url`.
+/// Advisory lock key shared by every instance of this server.
+const MIGRATION_LOCK_KEY: &str = "schema_migrations";
+
+/// What startup does with pending migrations (`DB_MIGRATIONS`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationMode {
+    Apply,
+    Check,
+    Off,
+}
+
+impl MigrationMode {
+    pub fn from_config(config: &DatabaseSection) -> Self {
+        match config.migrations.trim().to_ascii_lowercase().as_str() {
+            "check" => Self::Check,
+            "off" | "none" | "disabled" => Self::Off,
+            _ => Self::Apply,
+        }
+    }
+}
+
+/// Why startup stopped at the migration step.
+#[derive(Debug)]
+pub struct MigrationReport {
+    pub applied: Vec<String>,
+    pub failed: Option<(String, String)>,
+    pub pending: Vec<String>,
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.failed {
+            Some((name, error)) => writeln!(f, "migration {name} failed: {error}")?,
+            None => writeln!(
+                f,
+                "{} pending migration(s) and DB_MIGRATIONS=check",
+                self.pending.len()
+            )?,
+        }
+        writeln!(f, "  applied in this run: {}", list(&self.applied))?;
+        write!(f, "  not applied: {}", list(&self.pending))
+    }
+}
+
+impl std::error::Error for MigrationReport {}
+
+fn list(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Bring the schema at `db_url` up to date as `config` says.
 ///
 /// Runs on a blocking thread (libpq is synchronous). Returns an error if the
-/// connection cannot be established or any migration fails, so the caller can
-/// treat a migration failure as fatal and refuse to start with a stale schema.
-pub async fn run_pending_migrations(db_url: String) -> anyhow::Result<()> {
+/// connection or the lock cannot be had, if a migration fails, or in `check`
+/// mode if anything is pending, so the caller can refuse to start with a
+/// stale schema.
+pub async fn run_pending_migrations(
+    db_url: String,
+    config: &DatabaseSection,
+) -> anyhow::Result<()> {
+    let mode = MigrationMode::from_config(config);
+    if mode == MigrationMode::Off {
+        warn!("DB_MIGRATIONS=off; assuming the schema was migrated out of band.");
+        return Ok(());
+    }
+    let lock_timeout = Duration::from_secs(config.migration_lock_timeout_secs);
+
     let applied = tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<String>> {
         let mut conn = PgConnection::establish(&db_url).map_err(|e| {
             anyhow::anyhow!("Failed to establish sync connection for migrations: {e}")
         })?;
 
-        let versions = conn
-            .run_pending_migrations(MIGRATIONS)
-            .map_err(|e| anyhow::anyhow!("Failed to run pending migrations: {e}"))?;
+        // Another instance may be migrating; wait for it, but not forever.
+        sql_query(format!("SET lock_timeout = {}", lock_timeout.as_millis())).execute(&mut conn)?;
+        sql_query("SELECT pg_advisory_lock(hashtextextended($1, 0))")
+            .bind::<diesel::sql_types::Text, _>(MIGRATION_LOCK_KEY)
+            .execute(&mut conn)
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Could not take the migration lock within {}s: {e}",
+                    lock_timeout.as_secs()
+                )
+            })?;
+        sql_query("RESET lock_timeout").execute(&mut conn)?;
 
-        Ok(versions.iter().map(|version| version.to_string()).collect())
+        let result = apply_locked(&mut conn, mode);
+
+        // Closing the connection would release it too; this is just prompt.
+        if let Err(e) = sql_query("SELECT pg_advisory_unlock(hashtextextended($1, 0))")
+            .bind::<diesel::sql_types::Text, _>(MIGRATION_LOCK_KEY)
+            .execute(&mut conn)
+        {
+            warn!(error = ?e, "Failed to release the migration lock");
+        }
+        result
     })
     .await
     .map_err(|e| anyhow::anyhow!("Migration task panicked or was cancelled: {e}"))??;
@@ -50,3 +146,39 @@ pub async fn run_pending_migrations(db_url: String) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Applies (or in `check` mode only lists) the pending migrations one at a
+/// time, so a failure can name the migration that broke.
+fn apply_locked(conn: &mut PgConnection, mode: MigrationMode) -> anyhow::Result<Vec<String>> {
+    let pending = MigrationHarness::<Pg>::pending_migrations(conn, MIGRATIONS)
+        .map_err(|e| anyhow::anyhow!("Failed to list pending migrations: {e}"))?;
+    let mut remaining: Vec<String> = pending.iter().map(|m| m.name().to_string()).collect();
+    if remaining.is_empty() {
+        return Ok(Vec::new());
+    }
+    if mode == MigrationMode::Check {
+        return Err(MigrationReport {
+            applied: Vec::new(),
+            failed: None,
+            pending: remaining,
+        }
+        .into());
+    }
+
+    info!(count = remaining.len(), pending = ?remaining, "Applying database migrations.");
+    let mut applied = Vec::with_capacity(remaining.len());
+    for migration in &pending {
+        let name = remaining.remove(0);
+        if let Err(e) = conn.run_migration(migration.as_ref()) {
+            return Err(MigrationReport {
+                applied,
+                failed: Some((name, e.to_string())),
+                pending: remaining,
+            }
+            .into());
+        }
+        info!(migration = %name, "Applied database migration.");
+        applied.push(name);
+    }
+    Ok(applied)
+}
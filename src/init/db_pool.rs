@@ -0,0 +1,174 @@
+//! bb8 pool wrapper that records every checkout.
+//!
+//! [`InstrumentedPool`] puts the [`DbBreaker`] in front of the pool and keeps
+//! the last [`WAIT_SAMPLES`] checkout waits and [`MAX_FAILURES`] failures for
+//! `GET /api/admin/db/pool`, so connection exhaustion shows up as rising wait
+//! percentiles before checkouts start timing out.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::Utc;
+use diesel_async::AsyncPgConnection;
+use diesel_async::pooled_connection::bb8::{Pool, PooledConnection};
+use tokio::time::Instant;
+
+use crate::dto::responses::admin::db_pool_response::{
+    DbPoolStats, PoolCheckoutFailure, PoolWaitPercentiles,
+};
+use crate::init::app_config::DatabaseSection;
+use crate::init::db_breaker::DbBreaker;
+
+/// Checkout waits kept for the percentiles.
+pub const WAIT_SAMPLES: usize = 1024;
+/// Failed checkouts kept for the admin endpoint.
+pub const MAX_FAILURES: usize = 50;
+
+pub struct InstrumentedPool {
+    pool: Pool<AsyncPgConnection>,
+    max_size: u32,
+    breaker: DbBreaker,
+    checkouts: AtomicU64,
+    failures_total: AtomicU64,
+    /// Microseconds, newest last.
+    waits: Mutex<VecDeque<u64>>,
+    /// Newest last.
+    failures: Mutex<VecDeque<PoolCheckoutFailure>>,
+}
+
+impl InstrumentedPool {
+    /// `name` is `primary` or `replica`; it labels the breaker and the stats.
+    pub fn new(
+        name: &'static str,
+        pool: Pool<AsyncPgConnection>,
+        max_size: u32,
+        config: &DatabaseSection,
+    ) -> Self {
+        Self {
+            pool,
+            max_size,
+            breaker: DbBreaker::from_config(name, config),
+            checkouts: AtomicU64::new(0),
+            failures_total: AtomicU64::new(0),
+            waits: Mutex::new(VecDeque::with_capacity(WAIT_SAMPLES)),
+            failures: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn breaker(&self) -> &DbBreaker {
+        &self.breaker
+    }
+
+    /// Fails at once with
+    /// [`DbUnavailable`](crate::init::db_breaker::DbUnavailable) while the
+    /// breaker is open.
+    pub async fn get(&self) -> anyhow::Result<PooledConnection<'_, AsyncPgConnection>> {
+        self.breaker.admit()?;
+        let started = Instant::now();
+        let result = self.pool.get().await;
+        self.record(started, result.as_ref().err());
+        Ok(result?)
+    }
+
+    /// Like [`Self::get`], for connections held beyond a borrow of the state
+    /// (advisory locks).
+    pub async fn get_owned(&self) -> anyhow::Result<PooledConnection<'static, AsyncPgConnection>> {
+        self.breaker.admit()?;
+        let started = Instant::now();
+        let result = self.pool.get_owned().await;
+        self.record(started, result.as_ref().err());
+        Ok(result?)
+    }
+
+    fn record(&self, started: Instant, error: Option<&impl std::fmt::Display>) {
+        let waited = started.elapsed();
+        self.checkouts.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut waits = self.waits.lock().unwrap_or_else(|e| e.into_inner());
+            if waits.len() == WAIT_SAMPLES {
+                waits.pop_front();
+            }
+            waits.push_back(waited.as_micros() as u64);
+        }
+        match error {
+            None => self.breaker.record_success(),
+            Some(e) => {
+                self.breaker.record_failure();
+                self.failures_total.fetch_add(1, Ordering::Relaxed);
+                let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+                if failures.len() == MAX_FAILURES {
+                    failures.pop_front();
+                }
+                failures.push_back(PoolCheckoutFailure {
+                    failed_at: Utc::now(),
+                    waited_ms: waited.as_millis() as u64,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    pub fn stats(&self) -> DbPoolStats {
+        let state = self.pool.state();
+        let mut waits: Vec<u64> = self
+            .waits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .copied()
+            .collect();
+        waits.sort_unstable();
+
+        DbPoolStats {
+            pool: self.breaker.pool().to_string(),
+            max_size: self.max_size,
+            connections: state.connections,
+            idle_connections: state.idle_connections,
+            in_use: state.connections.saturating_sub(state.idle_connections),
+            circuit: self.breaker.state_name().to_string(),
+            checkouts_total: self.checkouts.load(Ordering::Relaxed),
+            failures_total: self.failures_total.load(Ordering::Relaxed),
+            wait_ms: PoolWaitPercentiles {
+                samples: waits.len(),
+                p50: percentile_ms(&waits, 0.50),
+                p90: percentile_ms(&waits, 0.90),
+                p99: percentile_ms(&waits, 0.99),
+                max: percentile_ms(&waits, 1.0),
+            },
+            recent_failures: self
+                .failures
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .rev()
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted microsecond samples, in milliseconds.
+fn percentile_ms(sorted_micros: &[u64], quantile: f64) -> f64 {
+    if sorted_micros.is_empty() {
+        return 0.0;
+    }
+    let rank = (quantile * sorted_micros.len() as f64).ceil() as usize;
+    let micros = sorted_micros[rank.clamp(1, sorted_micros.len()) - 1];
+    Duration::from_micros(micros).as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let samples: Vec<u64> = (1..=100).map(|ms| ms * 1000).collect();
+        assert_eq!(percentile_ms(&samples, 0.50), 50.0);
+        assert_eq!(percentile_ms(&samples, 0.99), 99.0);
+        assert_eq!(percentile_ms(&samples, 1.0), 100.0);
+        assert_eq!(percentile_ms(&[], 0.50), 0.0);
+    }
+}
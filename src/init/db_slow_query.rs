@@ -0,0 +1,193 @@
+//! Slow statement log with per-table counters.
+//!
+//! With `DB_SLOW_QUERY_MS` above 0, every pooled connection (primary and
+//! replica) times its statements through a [`SlowQueryTimer`]. A statement
+//! running at least that long is logged at WARN with its SQL, bound values
+//! redacted, and counted against its primary table as
+//! `db_slow_queries_total{table}` and `db_slow_query_seconds_total{table}`.
+//! Slow checkouts show up in the pool's wait percentiles instead.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use diesel::connection::DebugQuery;
+use tracing::warn;
+
+use crate::init::app_config::DatabaseSection;
+use crate::init::telemetry::statement_text;
+use crate::util::metrics::MetricsWriter;
+
+/// Table label for statements with no recognisable table.
+const UNKNOWN_TABLE: &str = "unknown";
+
+#[derive(Default, Clone, Copy)]
+struct TableCounts {
+    count: u64,
+    micros: u64,
+}
+
+/// Threshold and counters shared by every connection's timer.
+pub struct SlowQueries {
+    threshold: Option<Duration>,
+    tables: Mutex<HashMap<String, TableCounts>>,
+}
+
+impl SlowQueries {
+    pub fn from_config(config: &DatabaseSection) -> Arc<Self> {
+        Arc::new(Self {
+            threshold: (config.slow_query_ms > 0)
+                .then(|| Duration::from_millis(config.slow_query_ms)),
+            tables: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// `None` when slow-query logging is off.
+    pub fn threshold(&self) -> Option<Duration> {
+        self.threshold
+    }
+
+    fn record(&self, table: &str, elapsed: Duration) {
+        let mut tables = self.tables.lock().unwrap_or_else(|e| e.into_inner());
+        let counts = tables.entry(table.to_string()).or_default();
+        counts.count += 1;
+        counts.micros += elapsed.as_micros() as u64;
+    }
+
+    pub fn write_metrics(&self, w: &mut MetricsWriter) {
+        let mut tables: Vec<(String, TableCounts)> = self
+            .tables
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(table, counts)| (table.clone(), *counts))
+            .collect();
+        tables.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        w.header(
+            "db_slow_queries_total",
+            "counter",
+            "Statements that ran past DB_SLOW_QUERY_MS, by primary table.",
+        );
+        for (table, counts) in &tables {
+            w.sample(
+                "db_slow_queries_total",
+                &[("table", table)],
+                counts.count as f64,
+            );
+        }
+        w.header(
+            "db_slow_query_seconds_total",
+            "counter",
+            "Time spent in statements that ran past DB_SLOW_QUERY_MS, by primary table.",
+        );
+        for (table, counts) in &tables {
+            w.sample(
+                "db_slow_query_seconds_total",
+                &[("table", table)],
+                counts.micros as f64 / 1_000_000.0,
+            );
+        }
+    }
+}
+
+/// Per-connection statement timer. Pipelined statements on one connection
+/// finish in order, so a stack of start times is enough.
+pub struct SlowQueryTimer {
+    queries: Arc<SlowQueries>,
+    threshold: Duration,
+    started: Vec<Instant>,
+}
+
+impl SlowQueryTimer {
+    /// `None` when slow-query logging is off.
+    pub fn new(queries: Arc<SlowQueries>) -> Option<Self> {
+        let threshold = queries.threshold()?;
+        Some(Self {
+            queries,
+            threshold,
+            started: Vec::new(),
+        })
+    }
+
+    pub fn start(&mut self) {
+        self.started.push(Instant::now());
+    }
+
+    /// The statement is only rendered once it is known to be slow.
+    pub fn finish(&mut self, query: &dyn DebugQuery) {
+        let Some(started) = self.started.pop() else {
+            return;
+        };
+        let elapsed = started.elapsed();
+        if elapsed < self.threshold {
+            return;
+        }
+        let statement = statement_text(query);
+        let table = primary_table(&statement);
+        self.queries.record(&table, elapsed);
+        warn!(
+            table = %table,
+            elapsed_ms = elapsed.as_millis() as u64,
+            threshold_ms = self.threshold.as_millis() as u64,
+            statement = %statement,
+            "Slow query"
+        );
+    }
+}
+
+/// The first table a statement names after `FROM`, `INTO` or `UPDATE`,
+/// unquoted, lowercased and without its schema. Subqueries are skipped over
+/// to the table inside them.
+fn primary_table(sql: &str) -> String {
+    let mut words = sql.split_whitespace();
+    while let Some(word) = words.next() {
+        if !["FROM", "INTO", "UPDATE"]
+            .iter()
+            .any(|keyword| word.eq_ignore_ascii_case(keyword))
+        {
+            continue;
+        }
+        let Some(name) = words.next() else {
+            break;
+        };
+        let name = name.trim_end_matches([',', ';']);
+        if name.starts_with('(') || name.ends_with(')') {
+            continue;
+        }
+        let table = name.rsplit('.').next().unwrap_or(name).trim_matches('"');
+        if !table.is_empty() && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return table.to_ascii_lowercase();
+        }
+    }
+    UNKNOWN_TABLE.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primary_table_of_diesel_statements() {
+        assert_eq!(
+            primary_table(
+                r#"SELECT "users"."user_id" FROM "users" WHERE "users"."user_email" = $1"#
+            ),
+            "users"
+        );
+        assert_eq!(
+            primary_table(r#"INSERT INTO "posts" ("title") VALUES ($1)"#),
+            "posts"
+        );
+        assert_eq!(
+            primary_table(r#"UPDATE "public"."comments" SET "body" = $1"#),
+            "comments"
+        );
+        assert_eq!(
+            primary_table(r#"SELECT COUNT(*) FROM (SELECT 1 FROM "tags" WHERE "tag_id" = $1) t"#),
+            "tags"
+        );
+        assert_eq!(primary_table("SELECT 1"), UNKNOWN_TABLE);
+    }
+}
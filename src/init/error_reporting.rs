@@ -0,0 +1,314 @@
+//! Error reporting to a Sentry-compatible tracker.
+//!
+//! Off unless `ERROR_REPORTING_DSN` is set (`https://<key>@<host>/<project>`).
+//! Two kinds of event are sent: `CodeError` responses logged at ERROR, from
+//! `log_middleware` with the request id, method, route, client IP and user;
+//! and panics, from the hook [`init`] installs, with the same request context
+//! when the panic happened while serving one. Every event carries the release
+//! (`PROJECT_NAME@PROJECT_VERSION`), build time and rustc version from
+//! `build_info`, and the deployment environment.
+//!
+//! `ERROR_REPORTING_SAMPLE_RATE` thins the `CodeError` events; panics are
+//! always sent. At most `ERROR_REPORTING_MAX_PER_MINUTE` events are queued a
+//! minute and `ERROR_REPORTING_QUEUE_CAPACITY` wait for delivery; the rest are
+//! dropped. Delivery is best effort: one envelope POST per event, no retries.
+
+use std::net::IpAddr;
+use std::panic::PanicHookInfo;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::build_info::{BUILD_TIME_UTC, PROJECT_NAME, PROJECT_VERSION, RUSTC_VERSION};
+use crate::errors::code_error::CodeErrorLogContext;
+use crate::init::app_config::AppConfig;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed DSN: where envelopes go and the key that authorizes them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dsn {
+    public_key: String,
+    envelope_url: String,
+}
+
+impl Dsn {
+    pub fn parse(dsn: &str) -> Result<Self, String> {
+        let url = reqwest::Url::parse(dsn.trim()).map_err(|e| e.to_string())?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("scheme must be http or https".to_string());
+        }
+        if url.username().is_empty() {
+            return Err("missing public key".to_string());
+        }
+        let host = url.host_str().ok_or("missing host")?;
+        let path = url.path().trim_matches('/');
+        let (prefix, project) = match path.rsplit_once('/') {
+            Some((prefix, project)) => (format!("/{prefix}"), project),
+            None => (String::new(), path),
+        };
+        if project.is_empty() || !project.chars().all(|c| c.is_ascii_digit()) {
+            return Err("missing numeric project id".to_string());
+        }
+        let port = url
+            .port()
+            .map(|port| format!(":{port}"))
+            .unwrap_or_default();
+        Ok(Self {
+            public_key: url.username().to_string(),
+            envelope_url: format!(
+                "{}://{host}{port}{prefix}/api/{project}/envelope/",
+                url.scheme()
+            ),
+        })
+    }
+}
+
+/// The request being served, as far as `log_middleware` knows it before the
+/// handler runs.
+#[derive(Debug, Clone)]
+pub struct ReportedRequest {
+    pub request_id: String,
+    pub method: String,
+    /// Without the query string, which may hold tokens.
+    pub path: String,
+    pub route: String,
+    pub client_ip: Option<IpAddr>,
+}
+
+tokio::task_local! {
+    /// Set by `log_middleware` around the handler, so a panic can name the
+    /// request it interrupted.
+    pub static CURRENT_REQUEST: ReportedRequest;
+}
+
+struct Reporter {
+    sample_rate: f32,
+    max_per_minute: u64,
+    /// Unix minute in the high 32 bits, events queued in it in the low ones.
+    window: AtomicU64,
+    queue: mpsc::Sender<Value>,
+    environment: &'static str,
+    server_name: String,
+}
+
+static REPORTER: OnceLock<Reporter> = OnceLock::new();
+static IN_FLIGHT: AtomicBool = AtomicBool::new(false);
+
+/// Start the delivery task and install the panic hook, when a DSN is
+/// configured. Call once, inside the runtime, after tracing is set up.
+pub fn init(config: &AppConfig) {
+    let section = &config.error_reporting;
+    let Some(Ok(dsn)) = section.dsn().map(Dsn::parse) else {
+        return;
+    };
+    let (queue, events) = mpsc::channel(section.queue_capacity);
+    let reporter = Reporter {
+        sample_rate: section.sample_rate,
+        max_per_minute: section.max_per_minute as u64,
+        window: AtomicU64::new(0),
+        queue,
+        environment: config.app.deployment_environment().as_str(),
+        server_name: config.app.name_version.clone(),
+    };
+    if REPORTER.set(reporter).is_err() {
+        return;
+    }
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic| {
+        capture_panic(panic);
+        previous(panic);
+    }));
+    info!(sample_rate = section.sample_rate, "Error reporting enabled");
+    tokio::spawn(deliver(dsn, events));
+}
+
+/// Wait up to `timeout` for queued events to go out; returns at once when
+/// reporting is off. Called once, after the server has stopped.
+pub async fn flush(timeout: Duration) {
+    let Some(reporter) = REPORTER.get() else {
+        return;
+    };
+    let deadline = tokio::time::Instant::now() + timeout;
+    while (reporter.queue.capacity() < reporter.queue.max_capacity()
+        || IN_FLIGHT.load(Ordering::Acquire))
+        && tokio::time::Instant::now() < deadline
+    {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Report a `CodeError` response; `log_middleware` calls this for those
+/// logged at ERROR.
+pub fn capture_code_error(
+    request: &ReportedRequest,
+    user_id: Option<Uuid>,
+    status: u16,
+    error: &CodeErrorLogContext,
+) {
+    let Some(reporter) = REPORTER.get() else {
+        return;
+    };
+    if reporter.sample_rate < 1.0 && rand::random::<f32>() >= reporter.sample_rate {
+        return;
+    }
+    let mut event = reporter.event("error", &error.message, &error.detail);
+    event["fingerprint"] = json!(["code_error", error.error_code, request.route]);
+    event["tags"]["error_code"] = json!(error.error_code.to_string());
+    event["tags"]["status_code"] = json!(status.to_string());
+    add_request(&mut event, request);
+    if let Some(user_id) = user_id {
+        event["user"]["id"] = json!(user_id);
+    }
+    reporter.enqueue(event);
+}
+
+fn capture_panic(panic: &PanicHookInfo<'_>) {
+    let Some(reporter) = REPORTER.get() else {
+        return;
+    };
+    let message = panic
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string());
+    let mut event = reporter.event("fatal", "panic", &message);
+    if let Some(location) = panic.location() {
+        event["culprit"] = json!(format!("{}:{}", location.file(), location.line()));
+    }
+    event["tags"]["thread"] = json!(std::thread::current().name().unwrap_or("unnamed"));
+    event["extra"]["backtrace"] = json!(std::backtrace::Backtrace::force_capture().to_string());
+    if let Ok(request) = CURRENT_REQUEST.try_with(Clone::clone) {
+        add_request(&mut event, &request);
+    }
+    reporter.enqueue(event);
+}
+
+impl Reporter {
+    fn event(&self, level: &str, kind: &str, value: &str) -> Value {
+        json!({
+            "event_id": Uuid::new_v4().simple().to_string(),
+            "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            "platform": "rust",
+            "level": level,
+            "logger": PROJECT_NAME,
+            "release": format!("{PROJECT_NAME}@{PROJECT_VERSION}"),
+            "environment": self.environment,
+            "server_name": self.server_name,
+            "exception": { "values": [{ "type": kind, "value": value }] },
+            "contexts": { "runtime": { "name": "rustc", "version": RUSTC_VERSION } },
+            "tags": {},
+            "user": {},
+            "extra": { "build_time": BUILD_TIME_UTC },
+        })
+    }
+
+    fn admit(&self) -> bool {
+        let minute = (Utc::now().timestamp() / 60) as u64;
+        self.window
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |packed| {
+                if packed >> 32 != minute {
+                    Some((minute << 32) | 1)
+                } else if packed & 0xFFFF_FFFF < self.max_per_minute {
+                    Some(packed + 1)
+                } else {
+                    None
+                }
+            })
+            .is_ok()
+    }
+
+    fn enqueue(&self, event: Value) {
+        if self.admit() {
+            // A full queue drops the event; the log still has it.
+            let _ = self.queue.try_send(event);
+        }
+    }
+}
+
+fn add_request(event: &mut Value, request: &ReportedRequest) {
+    event["request"] = json!({ "method": request.method, "url": request.path });
+    event["tags"]["request_id"] = json!(request.request_id);
+    event["tags"]["route"] = json!(request.route);
+    if let Some(ip) = request.client_ip {
+        event["user"]["ip_address"] = json!(ip.to_string());
+    }
+}
+
+async fn deliver(dsn: Dsn, mut events: mpsc::Receiver<Value>) {
+    let client = match reqwest::Client::builder().timeout(SEND_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(error = %e, "Could not build the error reporting client; reports are dropped");
+            return;
+        }
+    };
+    let auth = format!(
+        "Sentry sentry_version=7, sentry_key={}, sentry_client={PROJECT_NAME}/{PROJECT_VERSION}",
+        dsn.public_key
+    );
+    while let Some(event) = events.recv().await {
+        IN_FLIGHT.store(true, Ordering::Release);
+        let envelope = format!(
+            "{}\n{}\n{}\n",
+            json!({ "event_id": event["event_id"], "sent_at": event["timestamp"] }),
+            json!({ "type": "event" }),
+            event
+        );
+        let result = client
+            .post(&dsn.envelope_url)
+            .header("X-Sentry-Auth", &auth)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/x-sentry-envelope",
+            )
+            .body(envelope)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        IN_FLIGHT.store(false, Ordering::Release);
+        if let Err(e) = result {
+            // WARN so a failing tracker does not feed itself.
+            warn!(error = %e, "Could not deliver an error report");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dsn_into_envelope_endpoint() {
+        let dsn = Dsn::parse("https://abc123@o1.ingest.example.com/42").unwrap();
+        assert_eq!(dsn.public_key, "abc123");
+        assert_eq!(
+            dsn.envelope_url,
+            "https://o1.ingest.example.com/api/42/envelope/"
+        );
+
+        let dsn = Dsn::parse("http://key@localhost:9000/tracker/7").unwrap();
+        assert_eq!(
+            dsn.envelope_url,
+            "http://localhost:9000/tracker/api/7/envelope/"
+        );
+
+        for bad in [
+            "",
+            "https://example.com/42",
+            "https://key@example.com/",
+            "https://key@example.com/project",
+            "ftp://key@example.com/42",
+        ] {
+            assert!(Dsn::parse(bad).is_err(), "{bad:?}");
+        }
+    }
+}
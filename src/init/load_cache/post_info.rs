@@ -22,6 +22,7 @@ pub async fn load_post_info(state: &ServerState) -> anyhow::Result<Vec<CachedPos
 
     // Load all posts
     let post_infos: Vec<PostInfo> = match posts::table
+        .filter(posts::post_deleted_at.is_null())
         .select(PostInfo::as_select())
         .order(posts::post_created_at.desc())
         .load::<PostInfo>(&mut conn)
@@ -1,15 +1,22 @@
 use std::collections::VecDeque;
+use std::time::Instant;
 
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 use crate::util::system::get_cpu_usage::get_cpu_usage;
+use crate::util::system::get_disk_usage::{DiskUsage, get_disk_usage};
+use crate::util::system::get_load_average::get_load_average;
 use crate::util::system::get_memory_size::get_memory_size;
 use crate::util::system::get_memory_usage::get_memory_usage;
+use crate::util::system::get_network_bytes::{NetworkBytes, get_network_bytes};
+use crate::util::system::get_open_fds::{OpenFds, get_open_fds};
 
 pub struct SystemInfoState {
     pub history: RwLock<VecDeque<SystemInfo>>,
     pub max_len: usize,
     pub ram_total_size: u64,
+    /// Previous network counters, to turn the next reading into a rate.
+    last_network: Mutex<Option<(Instant, NetworkBytes)>>,
 }
 
 impl Default for SystemInfoState {
@@ -24,6 +31,7 @@ impl SystemInfoState {
             history: RwLock::new(VecDeque::with_capacity(3600)),
             max_len: 3600,
             ram_total_size: get_memory_size(),
+            last_network: Mutex::new(None),
         }
     }
 
@@ -56,29 +64,93 @@ impl SystemInfoState {
 
     pub async fn update(&self) {
         let cpu_usage = get_cpu_usage().await;
-        // get_memory_usage does blocking /proc + sysinfo syscalls; keep it off the
-        // async worker thread.
-        let memory_usage = match tokio::task::spawn_blocking(get_memory_usage).await {
-            Ok(usage) => usage,
-            Err(e) => {
-                tracing::error!(error = %e, "spawn_blocking for get_memory_usage failed");
-                0
-            }
-        };
+        // The rest are blocking /proc reads and statvfs/sysinfo syscalls; keep
+        // them off the async worker thread.
+        let (memory_usage, load_average, disks, network, open_fds) =
+            match tokio::task::spawn_blocking(|| {
+                (
+                    get_memory_usage(),
+                    get_load_average(),
+                    get_disk_usage(),
+                    get_network_bytes(),
+                    get_open_fds(),
+                )
+            })
+            .await
+            {
+                Ok(sample) => sample,
+                Err(e) => {
+                    tracing::error!(error = %e, "spawn_blocking for system sampling failed");
+                    (0, [0.0; 3], Vec::new(), None, OpenFds::default())
+                }
+            };
+        let (network_rx_bytes_per_sec, network_tx_bytes_per_sec) =
+            self.network_rates(network).await;
         let info = SystemInfo {
+            sampled_at: chrono::Utc::now(),
             cpu_usage,
             memory_usage,
+            load_average,
+            network_rx_bytes_per_sec,
+            network_tx_bytes_per_sec,
+            open_fds,
+            disks,
         };
         self.push(info).await;
     }
 
+    /// Bytes per second received and sent since the previous reading; zero
+    /// on the first one or when the counters could not be read.
+    async fn network_rates(&self, current: Option<NetworkBytes>) -> (u64, u64) {
+        let now = Instant::now();
+        let mut last = self.last_network.lock().await;
+        let previous = match current {
+            Some(current) => last.replace((now, current)),
+            None => last.take(),
+        };
+        match (previous, current) {
+            (Some((at, previous)), Some(current)) => {
+                let secs = now.duration_since(at).as_secs_f64();
+                if secs <= 0.0 {
+                    return (0, 0);
+                }
+                // Counters go backwards when an interface disappears.
+                let rate =
+                    |before: u64, after: u64| (after.saturating_sub(before) as f64 / secs) as u64;
+                (rate(previous.rx, current.rx), rate(previous.tx, current.tx))
+            }
+            _ => (0, 0),
+        }
+    }
+
+    /// The last `count` samples, oldest first.
+    pub async fn recent(&self, count: usize) -> Vec<SystemInfo> {
+        let history = self.history.read().await;
+        let skip = history.len().saturating_sub(count);
+        history.iter().skip(skip).cloned().collect()
+    }
+
+    /// The most recent sample, if any.
+    pub async fn latest(&self) -> Option<SystemInfo> {
+        let history = self.history.read().await;
+        history.back().cloned()
+    }
+
     pub async fn get_memory_usage(&self) -> u64 {
         let history = self.history.read().await;
         history.back().map(|info| info.memory_usage).unwrap_or(0)
     }
 }
 
+#[derive(Clone)]
 pub struct SystemInfo {
+    pub sampled_at: chrono::DateTime<chrono::Utc>,
     pub cpu_usage: f64,
     pub memory_usage: u64, // bytes
+    /// 1, 5 and 15 minute load averages.
+    pub load_average: [f64; 3],
+    pub network_rx_bytes_per_sec: u64,
+    pub network_tx_bytes_per_sec: u64,
+    pub open_fds: OpenFds,
+    pub disks: Vec<DiskUsage>,
 }
@@ -0,0 +1,157 @@
+//! Runtime log filters for the console and file layers, read and changed
+//! through `/api/admin/logging`.
+//!
+//! `main` puts each layer's filter behind a `tracing_subscriber::reload`
+//! layer. A filter is a default level plus per-target levels, a target
+//! matching its module path and everything under it (`rust_be_template::jobs`).
+//! Changes apply to this instance only and last until restart.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde_derive::{Deserialize, Serialize};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{filter::Targets, reload};
+use utoipa::ToSchema;
+
+/// Most per-target levels one filter takes.
+pub const MAX_TARGETS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// One layer's filter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LogFilterSpec {
+    /// Level for targets not listed in `targets`.
+    pub default: LogLevel,
+    /// Per-target levels; the longest matching target wins.
+    #[serde(default)]
+    pub targets: BTreeMap<String, LogLevel>,
+}
+
+impl LogFilterSpec {
+    pub fn level(default: LogLevel) -> Self {
+        Self {
+            default,
+            targets: BTreeMap::new(),
+        }
+    }
+
+    /// Rejects filters with too many targets or a target that is not a
+    /// module path.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.targets.len() > MAX_TARGETS {
+            return Err(format!("at most {MAX_TARGETS} targets per filter"));
+        }
+        for target in self.targets.keys() {
+            let valid = !target.is_empty()
+                && target.len() <= 200
+                && target.split("::").all(|part| {
+                    !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                });
+            if !valid {
+                return Err(format!("{target:?} is not a module path"));
+            }
+        }
+        Ok(())
+    }
+
+    fn targets(&self) -> Targets {
+        Targets::new()
+            .with_default(LevelFilter::from(self.default))
+            .with_targets(
+                self.targets
+                    .iter()
+                    .map(|(target, level)| (target.clone(), LevelFilter::from(*level))),
+            )
+    }
+}
+
+type ReloadFn = Box<dyn Fn(Targets) -> Result<(), reload::Error> + Send + Sync>;
+
+/// A layer filter that can be swapped at runtime, plus what it was at
+/// startup.
+pub struct ReloadableFilter {
+    startup: LogFilterSpec,
+    current: Mutex<LogFilterSpec>,
+    reload: ReloadFn,
+}
+
+impl ReloadableFilter {
+    /// The filter layer to attach with `with_filter`, and its handle.
+    pub fn new<S: 'static>(spec: LogFilterSpec) -> (reload::Layer<Targets, S>, Self) {
+        let (layer, handle) = reload::Layer::new(spec.targets());
+        let filter = Self {
+            startup: spec.clone(),
+            current: Mutex::new(spec),
+            reload: Box::new(move |targets| handle.reload(targets)),
+        };
+        (layer, filter)
+    }
+
+    pub fn startup(&self) -> &LogFilterSpec {
+        &self.startup
+    }
+
+    pub fn current(&self) -> LogFilterSpec {
+        self.current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Swap in `spec`; call [`LogFilterSpec::validate`] first.
+    pub fn set(&self, spec: LogFilterSpec) -> anyhow::Result<()> {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        (self.reload)(spec.targets())?;
+        *current = spec;
+        Ok(())
+    }
+}
+
+/// The console and file layer filters.
+pub struct LogLevels {
+    pub console: ReloadableFilter,
+    pub file: ReloadableFilter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_targets_as_module_paths() {
+        let mut spec = LogFilterSpec::level(LogLevel::Info);
+        spec.targets
+            .insert("rust_be_template::jobs".to_string(), LogLevel::Debug);
+        assert!(spec.validate().is_ok());
+
+        for bad in ["", "a::", "::a", "a b", "a=debug"] {
+            let mut spec = LogFilterSpec::level(LogLevel::Info);
+            spec.targets.insert(bad.to_string(), LogLevel::Debug);
+            assert!(spec.validate().is_err(), "{bad:?}");
+        }
+    }
+}
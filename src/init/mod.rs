@@ -1,7 +1,20 @@
+pub mod app_config;
+pub mod cache;
+pub mod cache_invalidation;
 pub mod compile_regex;
 pub mod config;
+pub mod db_breaker;
 pub mod db_migrations;
+pub mod db_pool;
+pub mod db_slow_query;
+pub mod error_reporting;
 pub mod load_cache;
+pub mod log_levels;
+pub mod readiness;
 pub mod search;
 pub mod server_init;
+pub mod shutdown;
 pub mod state; // Server state
+pub mod telemetry;
+pub mod tls_reload;
+pub mod unix_socket;
@@ -0,0 +1,104 @@
+//! Readiness for `/readyz`, separate from liveness (`/livez`).
+//!
+//! Startup loads the geo-IP bundles, the post cache and the other state caches
+//! before the API listens, which takes long enough for an orchestrator to
+//! give up on the instance. With `server.probe_port` (`PROBE_PORT`) set, a
+//! plain-HTTP listener serving only the two probes is bound first thing in
+//! `server_init_proc`: `/livez` answers as soon as the process runs and
+//! `/readyz` stays 503 until the TLS certificate, the connection pool and the
+//! caches are loaded. Both are also on the API router, where `/readyz` turns
+//! 503 again once shutdown begins so load balancers drain the instance.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use axum::{Router, routing::get};
+use serde_derive::Serialize;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::handlers::server::probes::{livez, readyz};
+
+#[derive(Default)]
+pub struct Readiness {
+    tls_loaded: AtomicBool,
+    pool_warm: AtomicBool,
+    caches_loaded: AtomicBool,
+    draining: AtomicBool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    /// Certificate loaded, or TLS is terminated in front of a unix socket.
+    pub tls_loaded: bool,
+    /// The primary pool opened its minimum idle connections.
+    pub pool_warm: bool,
+    /// State caches synchronized and jobs started.
+    pub caches_loaded: bool,
+    /// Shutdown has begun.
+    pub draining: bool,
+}
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mark_tls_loaded(&self) {
+        self.tls_loaded.store(true, Ordering::Release);
+    }
+
+    pub fn mark_pool_warm(&self) {
+        self.pool_warm.store(true, Ordering::Release);
+    }
+
+    pub fn mark_caches_loaded(&self) {
+        self.caches_loaded.store(true, Ordering::Release);
+    }
+
+    pub fn mark_draining(&self) {
+        self.draining.store(true, Ordering::Release);
+    }
+
+    pub fn report(&self) -> ReadinessReport {
+        let tls_loaded = self.tls_loaded.load(Ordering::Acquire);
+        let pool_warm = self.pool_warm.load(Ordering::Acquire);
+        let caches_loaded = self.caches_loaded.load(Ordering::Acquire);
+        let draining = self.draining.load(Ordering::Acquire);
+        ReadinessReport {
+            ready: tls_loaded && pool_warm && caches_loaded && !draining,
+            tls_loaded,
+            pool_warm,
+            caches_loaded,
+            draining,
+        }
+    }
+}
+
+/// `/livez` and `/readyz` over `readiness`.
+pub fn probe_router(readiness: Arc<Readiness>) -> Router {
+    Router::new()
+        .route("/livez", get(livez))
+        .route("/readyz", get(readyz))
+        .with_state(readiness)
+}
+
+/// Bind the probe listener and serve it on its own task. A bind failure is
+/// fatal, like the API listener's.
+pub async fn spawn_probe_listener(
+    addr: SocketAddr,
+    readiness: Arc<Readiness>,
+) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to bind probe listener on {addr}: {e}"))?;
+    info!(%addr, "Listening for liveness and readiness probes");
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, probe_router(readiness).into_make_service()).await {
+            error!(error = %e, "Probe listener exited with error");
+        }
+    });
+    Ok(())
+}
@@ -13,6 +13,9 @@ use tracing::{info, warn};
 use uuid::Uuid;
 
 mod query;
+mod wasm_modules;
+
+pub use wasm_modules::{WasmModuleSearchDoc, WasmModuleSearchIndex};
 
 /// Disk-persisted search index for blog posts using Tantivy.
 /// Indexes post titles and tags for fast full-text search.
@@ -322,4 +325,15 @@ impl PostSearchIndex {
     pub fn num_docs(&self) -> u64 {
         self.reader.searcher().num_docs()
     }
+
+    /// Opens every segment's doc store through the current searcher, so a
+    /// reader over missing or unreadable segment files fails here. Returns the
+    /// number of documents.
+    pub fn check_reader(&self) -> anyhow::Result<u64> {
+        let searcher = self.reader.searcher();
+        for segment_reader in searcher.segment_readers() {
+            segment_reader.get_store_reader(1)?;
+        }
+        Ok(searcher.num_docs())
+    }
 }
@@ -0,0 +1,229 @@
+use std::sync::RwLock;
+
+use tantivy::{
+    Index, IndexReader, IndexWriter, TantivyDocument, Term,
+    collector::TopDocs,
+    query::{AllQuery, BooleanQuery, BoostQuery, Occur, PhrasePrefixQuery, Query, TermQuery},
+    schema::{
+        Field, IndexRecordOption, STORED, STRING, Schema, TextFieldIndexing, TextOptions, Value,
+    },
+};
+use tracing::info;
+use uuid::Uuid;
+
+/// Titles weigh more than descriptions when ranking.
+const TITLE_BOOST: f32 = 2.0;
+
+/// One module as seen by the index.
+pub struct WasmModuleSearchDoc<'a> {
+    pub wasm_module_id: Uuid,
+    pub title: &'a str,
+    pub description: &'a str,
+    pub category: Option<&'a str>,
+    pub tags: &'a [String],
+}
+
+/// In-memory search index over WASM module titles and descriptions, with
+/// exact category and tag filters. Small enough to rebuild from the DB at
+/// startup, so it is not persisted. Visibility is not indexed: callers filter
+/// results through the module access cache.
+pub struct WasmModuleSearchIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: RwLock<IndexWriter>,
+    id_field: Field,
+    title_field: Field,
+    description_field: Field,
+    category_field: Field,
+    tags_field: Field,
+}
+
+impl WasmModuleSearchIndex {
+    pub fn new_in_memory() -> anyhow::Result<Self> {
+        let mut schema_builder = Schema::builder();
+        let id_field = schema_builder.add_text_field("wasm_module_id", STRING | STORED);
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("default")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let title_field = schema_builder.add_text_field("title", text_options.clone());
+        let description_field = schema_builder.add_text_field("description", text_options);
+        let category_field = schema_builder.add_text_field("category", STRING);
+        let tags_field = schema_builder.add_text_field("tags", STRING);
+
+        let index = Index::create_in_ram(schema_builder.build());
+        let writer = index.writer_with_num_threads(1, 15_000_000)?;
+        let reader = index.reader()?;
+
+        Ok(Self {
+            index,
+            reader,
+            writer: RwLock::new(writer),
+            id_field,
+            title_field,
+            description_field,
+            category_field,
+            tags_field,
+        })
+    }
+
+    fn writer(&self) -> anyhow::Result<std::sync::RwLockWriteGuard<'_, IndexWriter>> {
+        self.writer
+            .write()
+            .map_err(|e| anyhow::anyhow!("Writer lock poisoned: {}", e))
+    }
+
+    fn document(&self, doc: &WasmModuleSearchDoc<'_>) -> TantivyDocument {
+        let mut document = TantivyDocument::new();
+        document.add_text(self.id_field, doc.wasm_module_id.to_string());
+        document.add_text(self.title_field, doc.title);
+        document.add_text(self.description_field, doc.description);
+        if let Some(category) = doc.category {
+            document.add_text(self.category_field, category);
+        }
+        for tag in doc.tags {
+            document.add_text(self.tags_field, tag);
+        }
+        document
+    }
+
+    fn commit(&self, writer: &mut IndexWriter) -> anyhow::Result<()> {
+        writer.commit()?;
+        self.reader.reload()?;
+        Ok(())
+    }
+
+    /// Replace the whole index with `docs`.
+    pub fn rebuild<'a, I>(&self, docs: I) -> anyhow::Result<usize>
+    where
+        I: Iterator<Item = WasmModuleSearchDoc<'a>>,
+    {
+        let mut writer = self.writer()?;
+        writer.delete_all_documents()?;
+        let mut count = 0;
+        for doc in docs {
+            writer.add_document(self.document(&doc))?;
+            count += 1;
+        }
+        self.commit(&mut writer)?;
+        info!(modules_indexed = count, "WASM module search index rebuilt");
+        Ok(count)
+    }
+
+    /// Add or replace one module and commit.
+    pub fn upsert(&self, doc: &WasmModuleSearchDoc<'_>) -> anyhow::Result<()> {
+        let mut writer = self.writer()?;
+        writer.delete_term(self.id_term(doc.wasm_module_id));
+        writer.add_document(self.document(doc))?;
+        self.commit(&mut writer)
+    }
+
+    /// Remove one module and commit.
+    pub fn remove(&self, wasm_module_id: Uuid) -> anyhow::Result<()> {
+        let mut writer = self.writer()?;
+        writer.delete_term(self.id_term(wasm_module_id));
+        self.commit(&mut writer)
+    }
+
+    fn id_term(&self, wasm_module_id: Uuid) -> Term {
+        Term::from_field_text(self.id_field, &wasm_module_id.to_string())
+    }
+
+    /// Module ids matching `query_str` (every word must appear in the title or
+    /// description; the last one may be a prefix, for search-as-you-type),
+    /// best first. A blank query matches everything, so category/tag filters
+    /// can be used alone.
+    pub fn search(
+        &self,
+        query_str: &str,
+        category: Option<&str>,
+        tags: &[String],
+        limit: usize,
+    ) -> anyhow::Result<Vec<Uuid>> {
+        let words = self.tokenize(query_str)?;
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for (i, word) in words.iter().enumerate() {
+            let is_last = i + 1 == words.len();
+            let in_field = |field: Field| -> Box<dyn Query> {
+                let term = Term::from_field_text(field, word);
+                if is_last {
+                    Box::new(PhrasePrefixQuery::new(vec![term]))
+                } else {
+                    Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))
+                }
+            };
+            let either = BooleanQuery::new(vec![
+                (
+                    Occur::Should,
+                    Box::new(BoostQuery::new(in_field(self.title_field), TITLE_BOOST)),
+                ),
+                (Occur::Should, in_field(self.description_field)),
+            ]);
+            clauses.push((Occur::Must, Box::new(either)));
+        }
+        if let Some(category) = category {
+            let term = Term::from_field_text(self.category_field, category);
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+            ));
+        }
+        for tag in tags {
+            let term = Term::from_field_text(self.tags_field, tag);
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+            ));
+        }
+        if clauses.is_empty() {
+            clauses.push((Occur::Must, Box::new(AllQuery)));
+        }
+
+        let searcher = self.reader.searcher();
+        let top_docs: Vec<(f32, tantivy::DocAddress)> = searcher.search(
+            &BooleanQuery::new(clauses),
+            &TopDocs::with_limit(limit.max(1)).order_by_score(),
+        )?;
+
+        let mut ids = Vec::with_capacity(top_docs.len());
+        for (_score, address) in top_docs {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            if let Some(id) = doc
+                .get_first(self.id_field)
+                .and_then(|v| v.as_str())
+                .and_then(|s| Uuid::parse_str(s).ok())
+            {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    fn tokenize(&self, query_str: &str) -> anyhow::Result<Vec<String>> {
+        let mut analyzer = self.index.tokenizer_for_field(self.title_field)?;
+        let mut words = Vec::new();
+        let mut stream = analyzer.token_stream(query_str);
+        stream.process(&mut |token| {
+            if !token.text.is_empty() {
+                words.push(token.text.to_string());
+            }
+        });
+        Ok(words)
+    }
+
+    pub fn num_docs(&self) -> u64 {
+        self.reader.searcher().num_docs()
+    }
+
+    /// Opens every segment's doc store through the current searcher, so a
+    /// reader over missing or unreadable segment files fails here. Returns the
+    /// number of documents.
+    pub fn check_reader(&self) -> anyhow::Result<u64> {
+        let searcher = self.reader.searcher();
+        for segment_reader in searcher.segment_readers() {
+            segment_reader.get_store_reader(1)?;
+        }
+        Ok(searcher.num_docs())
+    }
+}
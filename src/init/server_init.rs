@@ -1,6 +1,6 @@
 use std::{
-    net::{IpAddr, SocketAddr},
-    path::PathBuf,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::Path,
     sync::Arc,
     time::Duration,
 };
@@ -10,61 +10,94 @@ use axum::{
     http::{StatusCode, Uri, uri::Authority},
     response::Redirect,
 };
-use axum_server::tls_rustls::RustlsConfig;
-use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use axum_server::{Handle, tls_rustls::RustlsConfig};
 use diesel_async::pooled_connection::bb8::Pool;
-use lettre::{AsyncSmtpTransport, Tokio1Executor, transport::smtp::authentication::Credentials};
 use tracing::info;
 
 use crate::{
-    init::config::EmailConfig, jobs::job_funcs::init_scheduler::task_init,
-    routers::main_router::build_router, util::extract::Host,
+    grpc::spawn_grpc_server,
+    init::{
+        app_config::{AppConfig, required},
+        cache_invalidation::spawn_cache_invalidation_listener,
+        db_pool::InstrumentedPool,
+        db_slow_query::SlowQueries,
+        log_levels::LogLevels,
+        readiness::{Readiness, spawn_probe_listener},
+        shutdown::{drain, shutdown_signal, shutdown_timeout},
+        telemetry::pg_connection_manager,
+        tls_reload::TlsReloader,
+        unix_socket::serve_unix,
+    },
+    jobs::job_funcs::init_scheduler::task_init,
+    routers::main_router::build_router,
+    util::extract::Host,
 };
 
 use super::{config::DbConfig, state::ServerState};
 
-pub async fn server_init_proc(start: tokio::time::Instant) -> anyhow::Result<()> {
+pub async fn server_init_proc(
+    start: tokio::time::Instant,
+    app_config: &'static AppConfig,
+    log_levels: Arc<LogLevels>,
+) -> anyhow::Result<()> {
     let num_cores: u32 = num_cpus::get_physical() as u32;
 
-    let host_ip: IpAddr = std::env::var("HOST_IP")
-        .map_err(|e| anyhow::anyhow!("Failed to load HOST_IP from .env: {}", e))?
-        .parse::<std::net::IpAddr>()
-        .map_err(|e| anyhow::anyhow!("Failed to parse HOST_IP as IP address: {}", e))?;
+    let readiness = Arc::new(Readiness::new());
+    if let Some(probe_port) = app_config.server.probe_port {
+        let probe_ip = app_config
+            .server
+            .host_ip
+            .filter(|_| app_config.server.socket_path.is_none())
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        spawn_probe_listener(
+            SocketAddr::new(probe_ip, probe_port),
+            Arc::clone(&readiness),
+        )
+        .await?;
+    }
 
-    let host_port: u16 = std::env::var("HOST_PORT")
-        .map_err(|e| anyhow::anyhow!("Failed to load HOST_PORT from .env: {}", e))?
-        .parse()
-        .map_err(|e| anyhow::anyhow!("Failed to parse HOST_PORT as u16: {}", e))?;
+    let (endpoint, tls) = match app_config.server.socket_path.as_deref() {
+        // TLS is terminated by the reverse proxy in front of the socket.
+        Some(socket_path) => {
+            info!(socket_path = %socket_path.display(), "Loaded host configuration.");
+            readiness.mark_tls_loaded();
+            (Endpoint::Unix(socket_path), None)
+        }
+        None => {
+            let host_ip = *required(&app_config.server.host_ip, "server.host_ip")?;
+            let host_port = *required(&app_config.server.host_port, "server.host_port")?;
 
-    let host_socket_addr: SocketAddr = SocketAddr::new(host_ip, host_port);
+            let host_socket_addr: SocketAddr = SocketAddr::new(host_ip, host_port);
 
-    info!(host_socket_addr = %host_socket_addr, "Loaded host configuration.");
+            info!(host_socket_addr = %host_socket_addr, "Loaded host configuration.");
 
-    let cert_chain_path: PathBuf = std::env::var("CERT_CHAIN_DIR")
-        .map_err(|_| anyhow::anyhow!("CERT_CHAIN_DIR environment variable is not set"))
-        .map(PathBuf::from)?;
+            let cert_chain_path =
+                required(&app_config.server.cert_chain_path, "server.cert_chain_path")?;
+            let priv_key_path = required(&app_config.server.priv_key_path, "server.priv_key_path")?;
 
-    let priv_key_path: PathBuf = std::env::var("PRIV_KEY_DIR")
-        .map_err(|_| anyhow::anyhow!("PRIV_KEY_DIR environment variable is not set"))
-        .map(PathBuf::from)?;
+            // configure certificate and private key used by https
+            let tls = TlsReloader::load(cert_chain_path, priv_key_path).await?;
 
-    // configure certificate and private key used by https
-    let config = RustlsConfig::from_pem_file(cert_chain_path, priv_key_path)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to load TLS config: {}", e))?;
+            info!(event = "tls_config_loaded", "Loaded TLS configuration");
+            readiness.mark_tls_loaded();
 
-    info!(event = "tls_config_loaded", "Loaded TLS configuration");
+            (
+                Endpoint::Https(host_socket_addr, tls.rustls_config()),
+                Some(tls),
+            )
+        }
+    };
 
-    let db_url = DbConfig::from_env()
-        .map_err(|e| anyhow::anyhow!("Failed to get DB config from environment: {}", e))?
+    let db_url = DbConfig::from_config(&app_config.database)
+        .map_err(|e| anyhow::anyhow!("Failed to get DB config: {}", e))?
         .to_url()
         .map_err(|e| anyhow::anyhow!("Failed to convert DB config to URL: {}", e))?;
 
     // Apply embedded migrations before opening the async pool or loading caches,
     // so the schema is guaranteed current. A migration failure is fatal.
-    crate::init::db_migrations::run_pending_migrations(db_url.clone())
+    crate::init::db_migrations::run_pending_migrations(db_url.clone(), &app_config.database)
         .await
-        .map_err(|e| anyhow::anyhow!("Failed to apply database migrations: {}", e))?;
+        .map_err(|e| anyhow::anyhow!("Database migrations did not complete:\n{}", e))?;
 
     info!(
         event = "database_connect_start",
@@ -76,8 +109,8 @@ pub async fn server_init_proc(start: tokio::time::Instant) -> anyhow::Result<()>
         "Loaded database configuration"
     );
 
-    let pool_config =
-        AsyncDieselConnectionManager::<diesel_async::AsyncPgConnection>::new(db_url.clone());
+    let slow_queries = SlowQueries::from_config(&app_config.database);
+    let pool_config = pg_connection_manager(db_url.clone(), Arc::clone(&slow_queries));
 
     let pool = Pool::builder()
         .min_idle(Some(num_cores))
@@ -92,26 +125,48 @@ pub async fn server_init_proc(start: tokio::time::Instant) -> anyhow::Result<()>
         max_connections = num_cores * 10u32,
         "Connection pool built"
     );
-
-    let app_name_version: String = std::env::var("APP_NAME_VERSION")
-        .map_err(|e| anyhow::anyhow!("Failed to load APP_NAME_VAR from .env: {}", e))?;
-
-    let email_config = EmailConfig::from_env()
-        .map_err(|e| anyhow::anyhow!("Failed to load email configs from .env: {}", e))?;
-    let email_creds: Credentials = email_config.to_creds();
-    let email_client: AsyncSmtpTransport<Tokio1Executor> =
-        AsyncSmtpTransport::<Tokio1Executor>::relay(&email_config.get_url())?
-            .credentials(email_creds)
-            .build();
-
-    info!(smtp_relay = %email_config.get_url(), "Email client configured");
+    readiness.mark_pool_warm();
+
+    // Replica checkouts fall back to the primary, so an unreachable replica
+    // must not block startup.
+    let read_pool = match app_config
+        .database
+        .read_url
+        .clone()
+        .filter(|v| !v.trim().is_empty())
+    {
+        Some(read_url) => {
+            let read_pool = Pool::builder()
+                .min_idle(Some(num_cores))
+                .max_size(num_cores * 10u32)
+                .connection_timeout(Duration::from_secs(2))
+                .build_unchecked(pg_connection_manager(read_url, Arc::clone(&slow_queries)));
+            info!("Read-replica connection pool built");
+            Some(InstrumentedPool::new(
+                "replica",
+                read_pool,
+                num_cores * 10u32,
+                &app_config.database,
+            ))
+        }
+        None => None,
+    };
 
     let state = Arc::new(
         ServerState::builder()
-            .app_name_version(app_name_version)
-            .pool(pool)
+            .config(app_config)
+            .pool(InstrumentedPool::new(
+                "primary",
+                pool,
+                num_cores * 10u32,
+                &app_config.database,
+            ))
+            .read_pool(read_pool)
+            .slow_queries(slow_queries)
+            .tls(tls)
+            .readiness(Arc::clone(&readiness))
+            .log_levels(log_levels)
             .server_start_time(start)
-            .email_client(email_client)
             .build()
             .await
             .map_err(|e| anyhow::anyhow!("Failed to build ServerState: {}", e))?,
@@ -124,17 +179,11 @@ pub async fn server_init_proc(start: tokio::time::Instant) -> anyhow::Result<()>
     state.sync_i18n_data().await?;
     state.sync_visitor_board_data().await?;
     state.sync_wasm_module_cache().await?;
+    state.sync_wasm_module_search_index().await?;
     state.sync_live_chat_ban_cache().await?;
     state.sync_live_chat_cache().await?;
 
-    let api_key = std::env::var("X_API_KEY")
-        .map_err(|e| anyhow::anyhow!("Failed to load X_API_KEY from .env: {}", e))?;
-
-    let api_key_uuid = uuid::Uuid::parse_str(&api_key)
-        .map_err(|e| anyhow::anyhow!("Failed to parse X_API_KEY as UUID: {}", e))?;
-
-    drop(api_key);
-
+    let api_key_uuid = *required(&app_config.server.api_key, "server.api_key")?;
     state.insert_api_key(api_key_uuid).await?;
 
     info!(
@@ -145,35 +194,96 @@ pub async fn server_init_proc(start: tokio::time::Instant) -> anyhow::Result<()>
     // initialize scheduled jobs manager
     task_init(state.clone()).await?;
 
-    tokio::spawn(async move {
-        if let Err(e) = redirect_http_to_https(
-            host_ip,
-            Ports {
-                http: 80,
-                https: host_port,
-            },
-        )
-        .await
-        {
-            tracing::error!(error = %e, "HTTP->HTTPS redirect listener exited with error");
-        }
-    });
+    // Refresh caches as soon as another instance writes, not just on schedule.
+    spawn_cache_invalidation_listener(Arc::clone(&state), db_url);
+
+    if let Endpoint::Https(host_socket_addr, _) = &endpoint {
+        let host_socket_addr = *host_socket_addr;
+        tokio::spawn(async move {
+            if let Err(e) = redirect_http_to_https(
+                host_socket_addr.ip(),
+                Ports {
+                    http: 80,
+                    https: host_socket_addr.port(),
+                },
+            )
+            .await
+            {
+                tracing::error!(error = %e, "HTTP->HTTPS redirect listener exited with error");
+            }
+        });
+
+        info!(
+            host_port = host_socket_addr.port(),
+            "Listening for HTTPS traffic"
+        );
+    }
 
-    info!(host_port = host_port, "Listening for HTTPS traffic");
+    if let Some(grpc_port) = app_config.server.grpc_port {
+        let grpc_ip = app_config
+            .server
+            .host_ip
+            .filter(|_| app_config.server.socket_path.is_none())
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        spawn_grpc_server(SocketAddr::new(grpc_ip, grpc_port), Arc::clone(&state)).await?;
+    }
+
+    let tls_poll_secs = app_config.server.tls_reload_poll_secs;
+    if tls_poll_secs > 0 && state.tls.is_some() {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Some(tls) = state.tls.as_ref() {
+                tls.watch(Duration::from_secs(tls_poll_secs)).await;
+            }
+        });
+    }
 
     info!(
         elapsed = ?start.elapsed(),
         "Initialization complete; starting server"
     );
+    readiness.mark_caches_loaded();
+
+    let handle = Handle::new();
+    let shutdown_budget = shutdown_timeout(&app_config.server);
+    {
+        let state = Arc::clone(&state);
+        let handle = handle.clone();
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            state.shutdown.begin();
+            state.readiness.mark_draining();
+            info!(timeout = ?shutdown_budget, "Draining HTTP connections");
+            handle.graceful_shutdown(Some(shutdown_budget));
+        });
+    }
 
-    axum_server::bind_rustls(host_socket_addr, config)
-        .serve(build_router(state).into_make_service_with_connect_info::<SocketAddr>())
-        .await
-        .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
+    let app = build_router(Arc::clone(&state)).into_make_service_with_connect_info::<SocketAddr>();
+    match endpoint {
+        Endpoint::Https(host_socket_addr, config) => {
+            axum_server::bind_rustls(host_socket_addr, config)
+                .handle(handle)
+                .serve(app)
+                .await
+                .map_err(|e| anyhow::anyhow!("Server error: {}", e))?
+        }
+        Endpoint::Unix(socket_path) => {
+            serve_unix(socket_path, app, &state, shutdown_budget).await?
+        }
+    }
+
+    drain(&state, shutdown_budget).await;
 
     Ok(())
 }
 
+/// Where the API listens: HTTPS on `host_ip:host_port` (with the port 80
+/// redirect), or plain HTTP on `server.socket_path`.
+enum Endpoint {
+    Https(SocketAddr, RustlsConfig),
+    Unix(&'static Path),
+}
+
 #[derive(Clone, Copy)]
 struct Ports {
     http: u16,
@@ -0,0 +1,153 @@
+//! Graceful shutdown on SIGTERM/SIGINT.
+//!
+//! On the first signal the [`ShutdownCoordinator`] on `ServerState` is
+//! cancelled: `run_job` stops starting scheduled runs and the HTTPS (or unix
+//! socket) server stops accepting connections, giving in-flight requests
+//! `SHUTDOWN_TIMEOUT_SECS` (default 30) to finish. `server_init_proc` then
+//! waits for in-flight jobs under the same budget, commits the search index
+//! and flushes the write-behind buffers before returning.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::init::app_config::ServerSection;
+use crate::init::state::ServerState;
+
+pub fn shutdown_timeout(config: &ServerSection) -> Duration {
+    Duration::from_secs(config.shutdown_timeout_secs)
+}
+
+pub struct ShutdownCoordinator {
+    token: CancellationToken,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+/// Held for the duration of one job run; dropping it lets the drain finish.
+pub struct JobGuard<'a> {
+    coordinator: &'a ShutdownCoordinator,
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            in_flight: AtomicUsize::new(0),
+            idle: Notify::new(),
+        }
+    }
+
+    pub fn begin(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown has begun.
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await;
+    }
+
+    /// `None` once shutdown has begun, so no new job starts.
+    pub fn job_guard(&self) -> Option<JobGuard<'_>> {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let guard = JobGuard { coordinator: self };
+        // Checked after the increment so `wait_for_jobs` cannot miss a job
+        // that slipped in while the token was being cancelled.
+        (!self.is_shutting_down()).then_some(guard)
+    }
+
+    /// Wait until no job holds a guard. Returns `false` on timeout.
+    pub async fn wait_for_jobs(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let idle = self.idle.notified();
+                if self.in_flight.load(Ordering::Acquire) == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for JobGuard<'_> {
+    fn drop(&mut self) {
+        if self.coordinator.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.coordinator.idle.notify_waiters();
+        }
+    }
+}
+
+/// Resolve on the first SIGTERM or SIGINT.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!(error = %e, "Failed to listen for SIGINT");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!(signal = "SIGINT", "Shutdown signal received"),
+        _ = terminate => info!(signal = "SIGTERM", "Shutdown signal received"),
+    }
+}
+
+/// Wait for in-flight jobs, then persist what only lives in memory. Runs
+/// after the HTTP server has drained.
+pub async fn drain(state: &ServerState, timeout: Duration) {
+    if !state.shutdown.wait_for_jobs(timeout).await {
+        warn!(
+            ?timeout,
+            "Scheduled jobs still running at shutdown timeout; exiting anyway"
+        );
+    }
+
+    if let Err(e) = state.search_index.commit() {
+        error!(error = ?e, "Failed to commit search index at shutdown");
+    }
+    if let Err(e) = state.flush_visitor_logs().await {
+        error!(error = ?e, "Failed to flush visitor logs at shutdown");
+    }
+    if let Err(e) = state.flush_access_log().await {
+        error!(error = ?e, "Failed to flush access log at shutdown");
+    }
+    if let Err(e) = state.flush_photograph_views().await {
+        error!(error = ?e, "Failed to flush photograph view counts at shutdown");
+    }
+    if let Err(e) = state.flush_wasm_module_loads().await {
+        error!(error = ?e, "Failed to flush WASM module loads at shutdown");
+    }
+
+    info!("Shutdown drain complete");
+}
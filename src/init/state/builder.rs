@@ -1,37 +1,73 @@
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
 
-use diesel_async::AsyncPgConnection;
-use diesel_async::pooled_connection::bb8::Pool;
-use lettre::{AsyncSmtpTransport, Tokio1Executor};
 use std::sync::Arc;
 
 use tokio::sync::RwLock;
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::domain::access_log::buffer::AccessLogBuffer;
+use crate::domain::blog::live_event::LIVE_EVENT_CHANNEL_CAPACITY;
 use crate::domain::country::{CountryAndSubdivisionsTable, IsoCurrencyTable, IsoLanguageTable};
 use crate::domain::i18n::i18n_cache::I18nCache;
+use crate::domain::job::monitor::JobMonitor;
 use crate::domain::live_chat::cache::LiveChatCache;
 use crate::domain::live_chat::rtc::{RtcConfig, RtcEngine};
+use crate::domain::notification::hub::NotificationHub;
+use crate::domain::photography::duplicates::DuplicatePolicy;
+use crate::domain::stats::dashboard::DashboardStatsCache;
+use crate::domain::wasm_module::bundle_storage::WasmBundleStorage;
+use crate::init::app_config::{AppConfig, required};
+use crate::init::cache::cache_from_config;
+use crate::init::db_pool::InstrumentedPool;
+use crate::init::db_slow_query::SlowQueries;
 use crate::init::load_cache::fastfetch_cache::FastFetchCache;
 use crate::init::load_cache::system_info::SystemInfoState;
-use crate::init::search::PostSearchIndex;
+use crate::init::log_levels::LogLevels;
+use crate::init::readiness::Readiness;
+use crate::init::search::{PostSearchIndex, WasmModuleSearchIndex};
+use crate::init::shutdown::ShutdownCoordinator;
+use crate::init::tls_reload::TlsReloader;
+use crate::jobs::auth::invalidate_sessions::SessionPurgeTracker;
+use crate::jobs::maintenance::reconcile_storage_orphans::StorageOrphanTracker;
+
+use super::cache_versions::CacheVersions;
+use super::response_cache::ResponseCacheStats;
+use super::session_stats::SessionCounters;
+use super::sites::SiteRegistry;
+use crate::jobs::queue::JobQueue;
+use crate::util::cdn::CdnConfig;
+use crate::util::email::sender::SenderIdentity;
+use crate::util::email::transport::email_transport_from_config;
+use crate::util::email::unsubscribe::UnsubscribeKey;
 use crate::util::geographic::ip_info_lookup::decompress_and_deserialize;
+use crate::util::http::endpoint_stats::EndpointStats;
+use crate::util::http::rate_limit::RateLimiter;
+use crate::util::http::response_counters::ResponseCounters;
+use crate::util::http::timeout::RequestTimeouts;
+use crate::util::image::variants::ImageVariantCache;
+use crate::util::image::watermark::WatermarkConfig;
+use crate::util::storage::storage_from_config;
+use crate::util::wasm_bundle_cache::WasmBundleCache;
 
-use super::deployment_environment::DeploymentEnvironment;
 use super::server_state::ServerState;
 
 #[derive(Default)]
 pub struct ServerStateBuilder {
-    app_name_version: Option<String>,
+    config: Option<&'static AppConfig>,
     server_start_time: Option<tokio::time::Instant>,
-    pool: Option<Pool<AsyncPgConnection>>,
-    email_client: Option<AsyncSmtpTransport<Tokio1Executor>>, // regexes: [regex::Regex; 1],
+    pool: Option<InstrumentedPool>,
+    read_pool: Option<InstrumentedPool>,
+    slow_queries: Option<Arc<SlowQueries>>,
+    tls: Option<TlsReloader>,
+    readiness: Option<Arc<Readiness>>,
+    log_levels: Option<Arc<LogLevels>>,
+    // regexes: [regex::Regex; 1],
 }
 
 impl ServerStateBuilder {
-    pub fn app_name_version(mut self, app_name_version: String) -> Self {
-        self.app_name_version = Some(app_name_version);
+    pub fn config(mut self, config: &'static AppConfig) -> Self {
+        self.config = Some(config);
         self
     }
 
@@ -40,28 +76,57 @@ impl ServerStateBuilder {
         self
     }
 
-    pub fn pool(mut self, pool: Pool<AsyncPgConnection>) -> Self {
+    pub fn pool(mut self, pool: InstrumentedPool) -> Self {
         self.pool = Some(pool);
         self
     }
 
-    pub fn email_client(mut self, email_client: AsyncSmtpTransport<Tokio1Executor>) -> Self {
-        self.email_client = Some(email_client);
+    /// Optional read-replica pool; without one, reads use the primary.
+    pub fn read_pool(mut self, read_pool: Option<InstrumentedPool>) -> Self {
+        self.read_pool = read_pool;
+        self
+    }
+
+    pub fn slow_queries(mut self, slow_queries: Arc<SlowQueries>) -> Self {
+        self.slow_queries = Some(slow_queries);
+        self
+    }
+
+    pub fn tls(mut self, tls: Option<TlsReloader>) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn readiness(mut self, readiness: Arc<Readiness>) -> Self {
+        self.readiness = Some(readiness);
+        self
+    }
+
+    pub fn log_levels(mut self, log_levels: Arc<LogLevels>) -> Self {
+        self.log_levels = Some(log_levels);
         self
     }
 
     pub async fn build(self) -> anyhow::Result<ServerState> {
+        let config = self
+            .config
+            .ok_or_else(|| anyhow::anyhow!("config is required"))?;
+        let log_levels = self
+            .log_levels
+            .ok_or_else(|| anyhow::anyhow!("log_levels is required"))?;
+
         let aws_profile_picture_config = {
             use aws_config::BehaviorVersion;
             use aws_config::meta::region::RegionProviderChain;
 
-            let aws_key = std::env::var("AWS_IMAGE_UPLOAD_KEY")
-                .map_err(|_| anyhow::anyhow!("AWS_IMAGE_UPLOAD_KEY not set"))?;
-            let aws_secret = std::env::var("AWS_IMAGE_UPLOAD_SECRET_KEY")
-                .map_err(|_| anyhow::anyhow!("AWS_IMAGE_UPLOAD_SECRET_KEY not set"))?;
+            let aws_key = required(&config.aws.image_upload_key, "aws.image_upload_key")?;
+            let aws_secret = required(
+                &config.aws.image_upload_secret_key,
+                "aws.image_upload_secret_key",
+            )?;
             let credentials = aws_sdk_s3::config::Credentials::new(
-                aws_key,
-                aws_secret,
+                aws_key.clone(),
+                aws_secret.clone(),
                 None,                     // token
                 None,                     // expiration
                 "cyhdev-profile-picture", // provider name
@@ -75,11 +140,29 @@ impl ServerStateBuilder {
                 .await
         };
 
+        let storage = storage_from_config(&config.storage, &aws_profile_picture_config)?;
+        let email_transport = email_transport_from_config(
+            &config.email,
+            config.app.deployment_environment(),
+            &aws_profile_picture_config,
+        )?;
+        let email_sender =
+            SenderIdentity::from_config(&config.email, config.app.deployment_environment())?;
+        let unsubscribe_key = UnsubscribeKey::from_config(&config.email, &config.server)?;
+        let sites = SiteRegistry::from_config(
+            &config.sites,
+            &config.storage,
+            &storage,
+            &aws_profile_picture_config,
+        )?;
+        let cache = cache_from_config(&config.cache).await?;
+        let cdn = CdnConfig::from_config(&config.cdn, &aws_profile_picture_config)?;
+
         let fastfetch_cache = FastFetchCache::init().await;
 
         // Build the SFU engine once if enabled. A bind/init failure disables RTC
         // but does not abort startup.
-        let rtc_config = RtcConfig::from_env();
+        let rtc_config = RtcConfig::from_config(&config.rtc);
         let rtc_engine = if rtc_config.enabled {
             match RtcEngine::new(rtc_config.clone()).await {
                 Ok(engine) => Some(Arc::new(engine)),
@@ -94,30 +177,36 @@ impl ServerStateBuilder {
         };
 
         Ok(ServerState {
-            app_name_version: self
-                .app_name_version
-                .ok_or_else(|| anyhow::anyhow!("app_name_version is required"))?,
+            config,
             server_start_time: self
                 .server_start_time
                 .ok_or_else(|| anyhow::anyhow!("server_start_time is required"))?,
             pool: self
                 .pool
                 .ok_or_else(|| anyhow::anyhow!("pool is required"))?,
-            responses_handled: AtomicU64::new(0u64),
-            email_client: self
-                .email_client
-                .ok_or_else(|| anyhow::anyhow!("email_client is required"))?,
+            read_pool: self.read_pool,
+            slow_queries: self
+                .slow_queries
+                .unwrap_or_else(|| SlowQueries::from_config(&config.database)),
+            tls: self.tls,
+            readiness: self.readiness.unwrap_or_default(),
+            log_levels,
+            responses: ResponseCounters::new(),
+            email_transport,
+            email_sender,
+            unsubscribe_key,
             // regexes: [get_email_regex()],
             session_map: scc::HashMap::new(),
+            session_purges: SessionPurgeTracker::new(),
+            session_stats: SessionCounters::new(),
             blog_posts_cache: scc::HashMap::new(),
             blog_post_slug_cache: scc::HashMap::new(),
             blog_post_order_cache: RwLock::new(Vec::new()),
             search_index: {
-                // Use disk-persisted index, configurable via env var
-                let index_path = std::env::var("SEARCH_INDEX_PATH")
-                    .unwrap_or_else(|_| "./data/search_index".to_string());
-                let index = PostSearchIndex::open_or_create(&index_path)?;
-                info!(path = %index_path, "Search index initialized");
+                // Disk-persisted index at `app.search_index_path`
+                let index_path = &config.app.search_index_path;
+                let index = PostSearchIndex::open_or_create(index_path)?;
+                info!(path = %index_path.display(), "Search index initialized");
                 index
             },
             geo_ip_db: {
@@ -130,36 +219,49 @@ impl ServerStateBuilder {
             languages_map: RwLock::new(IsoLanguageTable::new_empty()),
             currency_map: RwLock::new(IsoCurrencyTable::new_empty()),
             i18n_cache: RwLock::new(I18nCache::new()),
-            deployment_environment: match std::env::var("CURR_ENV").as_deref() {
-                Ok(s) => match s.to_ascii_lowercase().as_str() {
-                    // Local
-                    "local" | "localhost" => DeploymentEnvironment::Local,
-                    // Dev
-                    "dev" | "develop" | "development" => DeploymentEnvironment::Dev,
-                    // Staging
-                    "staging" | "stage" | "stg" => DeploymentEnvironment::Staging,
-                    // Prod
-                    "prd" | "prod" | "production" => DeploymentEnvironment::Prod,
-                    // Default fallback: push _ to Local
-                    _ => DeploymentEnvironment::Local,
-                },
-                Err(_) => DeploymentEnvironment::Prod,
-            },
+            deployment_environment: config.app.deployment_environment(),
             request_client: reqwest::Client::builder()
                 .user_agent("cyhdev.com")
                 .build()?,
             visitor_board_map: scc::HashMap::new(),
             visitor_log_buffer: scc::HashMap::new(),
+            access_log_buffer: AccessLogBuffer::new(config.access_log.buffer_capacity),
             system_info_state: SystemInfoState::new(),
+            host_stats_subscribers: AtomicUsize::new(0),
             aws_profile_picture_config,
             fastfetch: fastfetch_cache,
-            wasm_module_cache: scc::HashMap::new(),
+            wasm_module_cache: WasmBundleCache::from_config(&config.wasm),
+            wasm_module_access: scc::HashMap::new(),
+            wasm_module_load_buffer: scc::HashMap::new(),
             live_chat_cache: LiveChatCache::default(),
             rtc_config,
             rtc_engine,
             rtc_rooms: scc::HashMap::new(),
             photograph_batches: scc::HashMap::new(),
+            photograph_presigned_uploads: scc::HashMap::new(),
             photograph_view_buffer: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+            image_processing_queue: JobQueue::new(config.images.processing_queue_capacity),
+            watermark: WatermarkConfig::from_config(&config.watermark)?.map(Arc::new),
+            duplicate_policy: DuplicatePolicy::from_config(&config.images),
+            cdn,
+            storage,
+            sites,
+            image_variant_cache: ImageVariantCache::from_config(&config.images),
+            storage_orphans: StorageOrphanTracker::new(),
+            dashboard_stats_cache: DashboardStatsCache::default(),
+            cache_versions: CacheVersions::new(),
+            response_cache: ResponseCacheStats::new(),
+            live_events: tokio::sync::broadcast::channel(LIVE_EVENT_CHANNEL_CAPACITY).0,
+            notifications: NotificationHub::new(),
+            upload_progress: scc::HashMap::new(),
+            wasm_bundle_storage: WasmBundleStorage::from_config(&config.wasm),
+            wasm_module_search_index: WasmModuleSearchIndex::new_in_memory()?,
+            job_monitor: JobMonitor::new(),
+            shutdown: ShutdownCoordinator::new(),
+            rate_limiter: RateLimiter::from_config(&config.rate_limit, Arc::clone(&cache)),
+            request_timeouts: RequestTimeouts::new(),
+            endpoint_stats: EndpointStats::new(),
+            cache,
         })
     }
 }
@@ -0,0 +1,96 @@
+//! Version counters for the in-memory caches behind read endpoints.
+//!
+//! Every mutation of a cache bumps its counter, and `etag_middleware` derives
+//! the `ETag` of the endpoints it serves from it, so a client revalidating with
+//! `If-None-Match` gets a 304 without the handler running. The epoch (process
+//! start) keeps tags from a previous run from matching after a restart.
+//! `response_cache_middleware` keys stored responses by the same tag, so a
+//! bump also invalidates them.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachedResource {
+    /// The post metadata cache (`/api/blog/posts`).
+    Posts,
+    /// Countries, subdivisions and languages (`/api/dropdown/*`).
+    Countries,
+    /// Visit counts per location (`/api/visitor-board`).
+    VisitorBoard,
+}
+
+impl CachedResource {
+    pub const ALL: [Self; 3] = [Self::Posts, Self::Countries, Self::VisitorBoard];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Posts => "posts",
+            Self::Countries => "countries",
+            Self::VisitorBoard => "visitor-board",
+        }
+    }
+
+    /// Whether the response differs per signed-in user (votes, unpublished
+    /// posts for superusers).
+    pub fn varies_by_user(self) -> bool {
+        matches!(self, Self::Posts)
+    }
+}
+
+pub struct CacheVersions {
+    epoch: u64,
+    posts: AtomicU64,
+    countries: AtomicU64,
+    visitor_board: AtomicU64,
+}
+
+impl CacheVersions {
+    pub fn new() -> Self {
+        Self {
+            epoch: chrono::Utc::now().timestamp_millis() as u64,
+            posts: AtomicU64::new(0),
+            countries: AtomicU64::new(0),
+            visitor_board: AtomicU64::new(0),
+        }
+    }
+
+    fn counter(&self, resource: CachedResource) -> &AtomicU64 {
+        match resource {
+            CachedResource::Posts => &self.posts,
+            CachedResource::Countries => &self.countries,
+            CachedResource::VisitorBoard => &self.visitor_board,
+        }
+    }
+
+    /// Call after the cache changed, not before, so a tag is never paired
+    /// with older data.
+    pub fn bump(&self, resource: CachedResource) {
+        self.counter(resource).fetch_add(1, Ordering::Release);
+    }
+
+    pub fn version(&self, resource: CachedResource) -> u64 {
+        self.counter(resource).load(Ordering::Acquire)
+    }
+
+    /// Quoted entity tag for `resource`, per user when it varies by user.
+    pub fn etag(&self, resource: CachedResource, user_id: Option<Uuid>) -> String {
+        let version = self.version(resource);
+        match user_id.filter(|_| resource.varies_by_user()) {
+            Some(user_id) => format!(
+                "\"{}-{:x}-{version}-{}\"",
+                resource.as_str(),
+                self.epoch,
+                user_id.simple()
+            ),
+            None => format!("\"{}-{:x}-{version}\"", resource.as_str(), self.epoch),
+        }
+    }
+}
+
+impl Default for CacheVersions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
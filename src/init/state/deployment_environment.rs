@@ -6,3 +6,26 @@ pub enum DeploymentEnvironment {
     Staging,
     Prod,
 }
+
+impl DeploymentEnvironment {
+    /// Canonical name, as in `[email.environments.<name>]`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Local => "local",
+            Self::Dev => "dev",
+            Self::Staging => "staging",
+            Self::Prod => "prod",
+        }
+    }
+
+    /// Inverse of [`Self::as_str`].
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "local" => Some(Self::Local),
+            "dev" => Some(Self::Dev),
+            "staging" => Some(Self::Staging),
+            "prod" => Some(Self::Prod),
+            _ => None,
+        }
+    }
+}
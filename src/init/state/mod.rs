@@ -1,7 +1,11 @@
 pub mod builder;
+pub mod cache_versions;
 pub mod deployment_environment;
+pub mod response_cache;
 pub mod server_state;
 pub mod session;
+pub mod session_stats;
+pub mod sites;
 
 pub use builder::ServerStateBuilder;
 pub use deployment_environment::DeploymentEnvironment;
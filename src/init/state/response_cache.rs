@@ -0,0 +1,65 @@
+//! Hit counters for `response_cache_middleware`, exported on `/api/metrics`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::init::state::cache_versions::CachedResource;
+use crate::util::metrics::MetricsWriter;
+
+#[derive(Default)]
+struct ResourceCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// Requests that skipped the cache: signed in on a per-user resource,
+    /// a non-200 or uncacheable response, or a cache error.
+    bypassed: AtomicU64,
+}
+
+#[derive(Clone, Copy)]
+pub enum CacheOutcome {
+    Hit,
+    Miss,
+    Bypass,
+}
+
+#[derive(Default)]
+pub struct ResponseCacheStats {
+    counters: [ResourceCounters; CachedResource::ALL.len()],
+}
+
+impl ResponseCacheStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, resource: CachedResource, outcome: CacheOutcome) {
+        let counters = &self.counters[resource as usize];
+        let counter = match outcome {
+            CacheOutcome::Hit => &counters.hits,
+            CacheOutcome::Miss => &counters.misses,
+            CacheOutcome::Bypass => &counters.bypassed,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn write_metrics(&self, w: &mut MetricsWriter) {
+        w.header(
+            "http_response_cache_requests_total",
+            "counter",
+            "GETs seen by the response cache, by resource and result.",
+        );
+        for resource in CachedResource::ALL {
+            let counters = &self.counters[resource as usize];
+            for (result, count) in [
+                ("hit", &counters.hits),
+                ("miss", &counters.misses),
+                ("bypass", &counters.bypassed),
+            ] {
+                w.sample(
+                    "http_response_cache_requests_total",
+                    &[("resource", resource.as_str()), ("result", result)],
+                    count.load(Ordering::Relaxed) as f64,
+                );
+            }
+        }
+    }
+}
@@ -1,54 +1,139 @@
 use std::net::IpAddr;
 use std::sync::Arc;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
 
-use diesel_async::AsyncPgConnection;
-use diesel_async::pooled_connection::bb8::Pool;
-use lettre::{AsyncSmtpTransport, Tokio1Executor};
 use scc::HashSet;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
 use uuid::Uuid;
 
+use crate::domain::access_log::buffer::AccessLogBuffer;
 use crate::domain::blog::blog::CachedPostInfo;
+use crate::domain::blog::live_event::LiveEvent;
 use crate::domain::country::{CountryAndSubdivisionsTable, IsoCurrencyTable, IsoLanguageTable};
 use crate::domain::i18n::i18n_cache::I18nCache;
+use crate::domain::job::monitor::JobMonitor;
 use crate::domain::live_chat::cache::LiveChatCache;
 use crate::domain::live_chat::rtc::{RtcConfig, RtcEngine, RtcRoom};
+use crate::domain::notification::hub::NotificationHub;
 use crate::domain::photography::batch::session::BatchSession;
+use crate::domain::photography::duplicates::DuplicatePolicy;
+use crate::domain::photography::presigned_upload::PresignedUpload;
+use crate::domain::stats::dashboard::DashboardStatsCache;
+use crate::domain::upload_progress::UploadProgressEntry;
+use crate::domain::wasm_module::bundle_storage::WasmBundleStorage;
+use crate::domain::wasm_module::loads::{WasmModuleLoadBatch, WasmModuleLoadKey};
+use crate::domain::wasm_module::wasm_module::WasmModuleAccess;
+use crate::init::app_config::AppConfig;
+use crate::init::cache::Cache;
+use crate::init::db_pool::InstrumentedPool;
+use crate::init::db_slow_query::SlowQueries;
 use crate::init::load_cache::fastfetch_cache::FastFetchCache;
 use crate::init::load_cache::system_info::SystemInfoState;
-use crate::init::search::PostSearchIndex;
+use crate::init::log_levels::LogLevels;
+use crate::init::readiness::Readiness;
+use crate::init::search::{PostSearchIndex, WasmModuleSearchIndex};
+use crate::init::shutdown::ShutdownCoordinator;
+use crate::init::state::cache_versions::CacheVersions;
+use crate::init::state::response_cache::ResponseCacheStats;
+use crate::init::state::sites::SiteRegistry;
+use crate::init::tls_reload::TlsReloader;
+use crate::jobs::auth::invalidate_sessions::SessionPurgeTracker;
+use crate::jobs::maintenance::reconcile_storage_orphans::StorageOrphanTracker;
+use crate::jobs::queue::JobQueue;
+use crate::jobs::queue::image_processing::ImageProcessingJob;
+use crate::util::cdn::CdnConfig;
+use crate::util::email::sender::SenderIdentity;
+use crate::util::email::transport::EmailTransport;
+use crate::util::email::unsubscribe::UnsubscribeKey;
 use crate::util::geographic::ip_info_lookup::GeoIpDatabases;
+use crate::util::http::endpoint_stats::EndpointStats;
+use crate::util::http::rate_limit::RateLimiter;
+use crate::util::http::response_counters::ResponseCounters;
+use crate::util::http::timeout::RequestTimeouts;
+use crate::util::image::variants::ImageVariantCache;
+use crate::util::image::watermark::WatermarkConfig;
+use crate::util::storage::StorageBackend;
+use crate::util::wasm_bundle_cache::WasmBundleCache;
 
 use super::deployment_environment::DeploymentEnvironment;
 use super::session::Session;
+use super::session_stats::SessionCounters;
 
+mod access_log;
+mod admin_audit;
+mod alert_rules;
+mod cdn;
 mod core;
+mod dashboard_stats;
+mod delayed_tasks;
+mod email_outbox;
+mod email_suppressions;
 mod geo;
 mod i18n;
+mod job_runs;
 mod live_chat;
+mod live_events;
+mod notification_center;
+mod notifications;
+mod photograph_presigned_uploads;
 mod photograph_views;
 mod photography_batches;
 mod posts;
 mod rtc;
 mod sessions;
+mod soft_delete;
+mod upload_progress;
 mod visitors;
 mod wasm;
+mod wasm_files;
+mod wasm_loads;
+mod wasm_search;
+mod webhooks;
 
 pub struct ServerState {
-    pub(crate) app_name_version: String,
+    /// Validated at startup; see [`crate::init::app_config`].
+    pub(crate) config: &'static AppConfig,
     pub(crate) server_start_time: tokio::time::Instant,
-    pub(crate) pool: Pool<AsyncPgConnection>,
-    pub(crate) responses_handled: AtomicU64,
-    pub(crate) email_client: AsyncSmtpTransport<Tokio1Executor>,
+    /// Primary pool behind its circuit breaker, with checkout statistics.
+    pub(crate) pool: InstrumentedPool,
+    /// Read-replica pool (`DB_READ_URL`); `None` sends reads to `pool`.
+    pub(crate) read_pool: Option<InstrumentedPool>,
+    /// Slow statements seen by both pools' connections (`DB_SLOW_QUERY_MS`).
+    pub(crate) slow_queries: Arc<SlowQueries>,
+    /// The HTTPS listener's certificate, reloadable in place; `None` when
+    /// serving on a unix socket behind a TLS-terminating proxy.
+    pub(crate) tls: Option<TlsReloader>,
+    /// Responses by status class and method, for the state endpoint and
+    /// metrics.
+    pub(crate) responses: ResponseCounters,
+    /// Outgoing mail (`EMAIL_TRANSPORT`: SMTP relay or the SES API).
+    pub(crate) email_transport: Arc<dyn EmailTransport>,
+    /// `From`, `Reply-To` and environment tag of outgoing mail.
+    pub(crate) email_sender: SenderIdentity,
+    pub(crate) unsubscribe_key: UnsubscribeKey,
     pub(crate) session_map: scc::HashMap<uuid::Uuid, Session>,
+    /// Recent expired-session purges and their running total.
+    pub(crate) session_purges: SessionPurgeTracker,
+    /// Logins, logouts and expiries per hour, for `/api/admin/sessions/stats`.
+    pub(crate) session_stats: SessionCounters,
     pub(crate) blog_posts_cache: scc::HashMap<uuid::Uuid, CachedPostInfo>,
     pub(crate) blog_post_slug_cache: scc::HashMap<String, uuid::Uuid>,
     pub(crate) blog_post_order_cache: RwLock<Vec<uuid::Uuid>>,
+    /// Bumped on every change to the post, country and visitor board caches;
+    /// the `ETag` of the endpoints serving them.
+    pub(crate) cache_versions: CacheVersions,
+    /// Hits and misses of `response_cache_middleware`.
+    pub(crate) response_cache: ResponseCacheStats,
+    /// Posts, comments and votes pushed to `GET /api/events` subscribers.
+    pub(crate) live_events: broadcast::Sender<LiveEvent>,
+    /// Per-user channels behind `/ws/notifications`.
+    pub(crate) notifications: NotificationHub,
     pub(crate) search_index: PostSearchIndex,
     pub(crate) geo_ip_db: GeoIpDatabases,
     pub visitor_board_map: scc::HashMap<([u8; 8], [u8; 8]), u64>,
     pub(crate) visitor_log_buffer: scc::HashMap<VisitorLogKey, VisitorLogBatch>,
+    /// Sampled requests not yet flushed to `access_log`.
+    pub(crate) access_log_buffer: AccessLogBuffer,
     pub(crate) api_keys_set: HashSet<Uuid>,
     pub country_map: RwLock<CountryAndSubdivisionsTable>,
     pub languages_map: RwLock<IsoLanguageTable>,
@@ -57,9 +142,15 @@ pub struct ServerState {
     pub(crate) deployment_environment: DeploymentEnvironment,
     pub(crate) request_client: reqwest::Client,
     pub system_info_state: SystemInfoState,
+    /// Open `/ws/host-stats` sockets, capped by `host_stats.max_subscribers`.
+    pub(crate) host_stats_subscribers: AtomicUsize,
     pub aws_profile_picture_config: aws_config::SdkConfig,
     pub fastfetch: FastFetchCache,
-    pub wasm_module_cache: scc::HashMap<Uuid, (Arc<[u8]>, bool, &'static str)>,
+    pub wasm_module_cache: WasmBundleCache,
+    /// Visibility and allowed users per module, checked before serving.
+    pub(crate) wasm_module_access: scc::HashMap<Uuid, WasmModuleAccess>,
+    /// Bundle loads not yet flushed to `wasm_module_loads`.
+    pub(crate) wasm_module_load_buffer: scc::HashMap<WasmModuleLoadKey, WasmModuleLoadBatch>,
     pub live_chat_cache: LiveChatCache,
     /// SFU runtime configuration (env-derived).
     pub(crate) rtc_config: RtcConfig,
@@ -70,11 +161,58 @@ pub struct ServerState {
     /// empties (closing its `live_chat_calls` row).
     pub(crate) rtc_rooms: scc::HashMap<String, Arc<RtcRoom>>,
     pub(crate) photograph_batches: scc::HashMap<uuid::Uuid, Arc<BatchSession>>,
+    /// Outstanding presigned photograph uploads keyed by upload token. Bounded:
+    /// entries are consumed on confirm or reaped once expired.
+    pub(crate) photograph_presigned_uploads: scc::HashMap<uuid::Uuid, PresignedUpload>,
     /// Write-through buffer of unflushed photograph view increments (deltas),
     /// keyed by photograph id. Views accumulate here in RAM and a periodic job
     /// flushes them to `photographs.photograph_view_count`, so the hot path does
     /// no per-view DB write. Bounded: drained to empty on every flush.
     pub(crate) photograph_view_buffer: RwLock<std::collections::HashMap<uuid::Uuid, i64>>,
+    /// Bounded queue of accepted uploads awaiting AVIF encoding + thumbnailing.
+    pub(crate) image_processing_queue: JobQueue<ImageProcessingJob>,
+    /// Watermark stamped on published photographs when an upload opts in;
+    /// `None` when no mark is configured.
+    pub(crate) watermark: Option<Arc<WatermarkConfig>>,
+    /// Env-derived near-duplicate handling for gallery uploads.
+    pub(crate) duplicate_policy: DuplicatePolicy,
+    /// CloudFront delivery (URL rewriting/signing + invalidations); `None`
+    /// serves raw storage links.
+    pub(crate) cdn: Option<CdnConfig>,
+    /// Object storage for uploaded media (S3 or local filesystem).
+    pub(crate) storage: Arc<dyn StorageBackend>,
+    /// Host-routed sites and their upload storage.
+    pub(crate) sites: SiteRegistry,
+    /// Cache and render limiter for `/img/{id}` resize-proxy variants.
+    pub(crate) image_variant_cache: ImageVariantCache,
+    /// Last storage/DB reconciliation report and its single-flight guard.
+    pub(crate) storage_orphans: StorageOrphanTracker,
+    /// Last admin overview aggregation per range.
+    pub(crate) dashboard_stats_cache: DashboardStatsCache,
+    /// Live progress channels keyed by client-chosen upload id. Bounded: the
+    /// minute prune job drops finished and idle entries.
+    pub(crate) upload_progress: scc::HashMap<Uuid, UploadProgressEntry>,
+    /// Where new WASM bundles are written (DB column or object storage).
+    pub(crate) wasm_bundle_storage: WasmBundleStorage,
+    /// In-memory Tantivy index of module titles, descriptions, categories
+    /// and tags; rebuilt at startup.
+    pub(crate) wasm_module_search_index: WasmModuleSearchIndex,
+    /// Schedule, next mark and last execution of every scheduled job.
+    pub(crate) job_monitor: JobMonitor,
+    /// Cancelled on SIGTERM/SIGINT; tracks in-flight scheduled runs.
+    pub(crate) shutdown: ShutdownCoordinator,
+    /// What `/readyz` reports; shared with the early probe listener.
+    pub(crate) readiness: Arc<Readiness>,
+    /// Reloadable console and file log filters installed by `main`.
+    pub(crate) log_levels: Arc<LogLevels>,
+    /// Shared key-value cache (in-process or Redis).
+    pub(crate) cache: Arc<dyn Cache>,
+    /// Per-policy limits and counters; the buckets live in `cache`.
+    pub(crate) rate_limiter: RateLimiter,
+    /// Requests cut off by `timeout_middleware`, per budget.
+    pub(crate) request_timeouts: RequestTimeouts,
+    /// Per-route latency and status counters from `log_middleware`.
+    pub(crate) endpoint_stats: EndpointStats,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -0,0 +1,136 @@
+//! Persistence for the sampled request log (`access_log`).
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, QueryDsl, SelectableHelper, TextExpressionMethods,
+};
+use diesel_async::RunQueryDsl;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::access_log::access_log::{AccessLog, AccessLogFilter, AccessLogInsertable};
+use crate::schema::access_log;
+use crate::util::db::keyset::{Keyset, KeysetOrder, KeysetPaginate};
+
+/// Rows per INSERT; 7 bind parameters each stays far below Postgres' limit.
+const FLUSH_CHUNK: usize = 1_000;
+
+impl ServerState {
+    /// Whether to keep this request, per `ACCESS_LOG_SAMPLE_RATE`.
+    pub fn sample_access_log(&self) -> bool {
+        let rate = self.config.access_log.sample_rate;
+        rate > 0.0 && rand::random::<f32>() < rate
+    }
+
+    pub async fn enqueue_access_log(&self, entry: AccessLogInsertable) {
+        self.access_log_buffer.push(entry).await;
+    }
+
+    /// Write the buffered entries. On a database error they go back into the
+    /// buffer for the next flush.
+    pub async fn flush_access_log(&self) -> anyhow::Result<usize> {
+        let (entries, dropped) = self.access_log_buffer.drain().await;
+        if dropped > 0 {
+            warn!(
+                dropped,
+                "Access log buffer was full; sampled requests were dropped"
+            );
+        }
+        if entries.is_empty() {
+            return Ok(0);
+        }
+
+        let mut conn = match self.get_conn().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                self.access_log_buffer.requeue(entries).await;
+                return Err(e);
+            }
+        };
+
+        let mut inserted = 0;
+        for (i, chunk) in entries.chunks(FLUSH_CHUNK).enumerate() {
+            if let Err(e) = diesel::insert_into(access_log::table)
+                .values(chunk)
+                .execute(&mut conn)
+                .await
+            {
+                let failed = entries[i * FLUSH_CHUNK..].to_vec();
+                self.access_log_buffer.requeue(failed).await;
+                return Err(e.into());
+            }
+            inserted += chunk.len();
+        }
+
+        info!(rows_flushed = inserted, "Flushed sampled access log");
+        Ok(inserted)
+    }
+
+    pub async fn prune_access_log(&self, cutoff: DateTime<Utc>) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let deleted =
+            diesel::delete(access_log::table.filter(access_log::access_log_created_at.lt(cutoff)))
+                .execute(&mut conn)
+                .await?;
+        Ok(deleted)
+    }
+
+    /// Up to `limit` entries matching `filter`, newest first, starting after
+    /// `after`.
+    pub async fn list_access_log(
+        &self,
+        filter: AccessLogFilter,
+        after: Option<Keyset<Uuid>>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<AccessLog>> {
+        let mut conn = self.get_read_conn().await?;
+        let mut query = access_log::table
+            .select(AccessLog::as_select())
+            .into_boxed()
+            .keyset_page(
+                (access_log::access_log_created_at, access_log::access_log_id),
+                after.as_ref(),
+                KeysetOrder::NewestFirst,
+                limit,
+            );
+        if let Some(path_prefix) = filter.path_prefix {
+            let pattern = format!(
+                "{}%",
+                path_prefix
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            );
+            query = query.filter(access_log::access_log_path.like(pattern));
+        }
+        if let Some(method) = filter.method {
+            query = query.filter(access_log::access_log_method.eq(method.to_ascii_uppercase()));
+        }
+        if let Some(status) = filter.status {
+            query = query.filter(access_log::access_log_status.eq(status));
+        }
+        if let Some(class) = filter.status_class {
+            query = query.filter(
+                access_log::access_log_status
+                    .ge(class * 100)
+                    .and(access_log::access_log_status.lt((class + 1) * 100)),
+            );
+        }
+        if let Some(country_code) = filter.country_code {
+            query = query
+                .filter(access_log::access_log_country_code.eq(country_code.to_ascii_uppercase()));
+        }
+        if let Some(user_id) = filter.user_id {
+            query = query.filter(access_log::access_log_user_id.eq(user_id));
+        }
+        if let Some(since) = filter.since {
+            query = query.filter(access_log::access_log_created_at.ge(since));
+        }
+        if let Some(before) = filter.before {
+            query = query.filter(access_log::access_log_created_at.lt(before));
+        }
+        let rows = query.load(&mut conn).await?;
+        Ok(rows)
+    }
+}
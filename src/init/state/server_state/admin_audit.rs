@@ -0,0 +1,85 @@
+//! Persistence for the superuser audit trail (`admin_audit`).
+
+use diesel::{ExpressionMethods, NullableExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use tracing::{error, info};
+
+use super::ServerState;
+use crate::domain::audit::audit::{
+    AdminAction, AdminAudit, AdminAuditFilter, AdminAuditInsertable,
+};
+use crate::schema::{admin_audit, users};
+use crate::util::extract::AdminActor;
+
+impl ServerState {
+    /// Record `action` by `actor` on `target_id` with a summary of what
+    /// changed. Call after the mutation succeeded. A failed write is logged
+    /// rather than returned: the mutation itself already happened.
+    pub async fn record_admin_action(
+        &self,
+        actor: &AdminActor,
+        action: AdminAction,
+        target_id: Option<String>,
+        summary: serde_json::Value,
+    ) {
+        info!(
+            actor_id = %actor.user_id,
+            action = action.as_str(),
+            target_id = target_id.as_deref().unwrap_or("-"),
+            "Admin action"
+        );
+        let entry = AdminAuditInsertable {
+            admin_audit_actor_id: Some(actor.user_id),
+            admin_audit_action: action.as_str().to_string(),
+            admin_audit_target_type: action.target_type().to_string(),
+            admin_audit_target_id: target_id,
+            admin_audit_summary: summary,
+            admin_audit_ip: actor.ip.map(ipnet::IpNet::from),
+        };
+        if let Err(e) = self.insert_admin_audit(entry).await {
+            error!(action = action.as_str(), error = ?e, "Failed to record admin action");
+        }
+    }
+
+    async fn insert_admin_audit(&self, entry: AdminAuditInsertable) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::insert_into(admin_audit::table)
+            .values(&entry)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Entries matching `filter`, newest first, with the actor's current
+    /// user name.
+    pub async fn list_admin_audit(
+        &self,
+        filter: AdminAuditFilter,
+        limit: i64,
+    ) -> anyhow::Result<Vec<(AdminAudit, Option<String>)>> {
+        let mut conn = self.get_conn().await?;
+        let mut query = admin_audit::table
+            .left_join(users::table)
+            .order(admin_audit::admin_audit_created_at.desc())
+            .limit(limit)
+            .select((AdminAudit::as_select(), users::user_name.nullable()))
+            .into_boxed();
+        if let Some(actor_id) = filter.actor_id {
+            query = query.filter(admin_audit::admin_audit_actor_id.eq(actor_id));
+        }
+        if let Some(action) = filter.action {
+            query = query.filter(admin_audit::admin_audit_action.eq(action));
+        }
+        if let Some(target_type) = filter.target_type {
+            query = query.filter(admin_audit::admin_audit_target_type.eq(target_type));
+        }
+        if let Some(target_id) = filter.target_id {
+            query = query.filter(admin_audit::admin_audit_target_id.eq(target_id));
+        }
+        if let Some(before) = filter.before {
+            query = query.filter(admin_audit::admin_audit_created_at.lt(before));
+        }
+        let rows = query.load(&mut conn).await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,101 @@
+//! Persistence for endpoint alert rules (`alert_rules`).
+
+use chrono::{Duration, Utc};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::alert::alert_rule::{AlertRule, AlertRuleChangeset, AlertRuleInsertable};
+use crate::schema::alert_rules;
+
+impl ServerState {
+    pub async fn insert_alert_rule(&self, rule: AlertRuleInsertable) -> anyhow::Result<AlertRule> {
+        let mut conn = self.get_conn().await?;
+        let row = diesel::insert_into(alert_rules::table)
+            .values(&rule)
+            .returning(AlertRule::as_returning())
+            .get_result(&mut conn)
+            .await?;
+        Ok(row)
+    }
+
+    pub async fn list_alert_rules(&self) -> anyhow::Result<Vec<AlertRule>> {
+        let mut conn = self.get_conn().await?;
+        let rows = alert_rules::table
+            .order(alert_rules::alert_rule_created_at.asc())
+            .select(AlertRule::as_select())
+            .load(&mut conn)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn list_enabled_alert_rules(&self) -> anyhow::Result<Vec<AlertRule>> {
+        let mut conn = self.get_conn().await?;
+        let rows = alert_rules::table
+            .filter(alert_rules::alert_rule_enabled.eq(true))
+            .order(alert_rules::alert_rule_created_at.asc())
+            .select(AlertRule::as_select())
+            .load(&mut conn)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_alert_rule(&self, alert_rule_id: Uuid) -> anyhow::Result<Option<AlertRule>> {
+        let mut conn = self.get_conn().await?;
+        let row = alert_rules::table
+            .find(alert_rule_id)
+            .select(AlertRule::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Returns the updated row, or `None` when the rule does not exist.
+    pub async fn update_alert_rule(
+        &self,
+        alert_rule_id: Uuid,
+        changeset: AlertRuleChangeset,
+    ) -> anyhow::Result<Option<AlertRule>> {
+        let mut conn = self.get_conn().await?;
+        let row = diesel::update(alert_rules::table.find(alert_rule_id))
+            .set(&changeset)
+            .returning(AlertRule::as_returning())
+            .get_result(&mut conn)
+            .await
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Returns whether the rule existed.
+    pub async fn delete_alert_rule(&self, alert_rule_id: Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.get_conn().await?;
+        let deleted = diesel::delete(alert_rules::table.find(alert_rule_id))
+            .execute(&mut conn)
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    /// Stamp the rule as fired unless it fired within its cooldown. Returns
+    /// whether this caller claimed it, so of several instances seeing the
+    /// same breach only one alerts.
+    pub async fn claim_alert_rule_firing(&self, rule: &AlertRule) -> anyhow::Result<bool> {
+        let now = Utc::now();
+        let cutoff = now - Duration::minutes(rule.alert_rule_cooldown_minutes as i64);
+        let mut conn = self.get_conn().await?;
+        let claimed = diesel::update(
+            alert_rules::table.find(rule.alert_rule_id).filter(
+                alert_rules::alert_rule_last_fired_at
+                    .is_null()
+                    .or(alert_rules::alert_rule_last_fired_at.le(cutoff)),
+            ),
+        )
+        .set(alert_rules::alert_rule_last_fired_at.eq(now))
+        .execute(&mut conn)
+        .await?;
+        Ok(claimed > 0)
+    }
+}
@@ -0,0 +1,61 @@
+//! `ServerState` accessors for CDN delivery of stored image links (see
+//! `util::cdn`). Without `CLOUDFRONT_DOMAIN` every accessor is a no-op and the
+//! raw storage links are served as before.
+
+use crate::{
+    domain::photography::photographs::Photograph,
+    dto::responses::wasm_module::wasm_module_response::WasmModuleItem,
+};
+
+use super::ServerState;
+
+impl ServerState {
+    /// Client-facing URL for a stored image link.
+    /// Links outside the storage backend (and empty placeholders of pending
+    /// rows) pass through unchanged.
+    pub fn image_url(&self, link: &str) -> String {
+        match (&self.cdn, self.storage.key_from_url(link)) {
+            (Some(cdn), Some(key)) => cdn.key_url(&key),
+            _ => link.to_string(),
+        }
+    }
+
+    /// Rewrite every image link on a photograph row (main, thumbnail, RAW and
+    /// renditions) to its CDN URL, just before it is serialized.
+    pub fn deliver_photograph_links(&self, photograph: &mut Photograph) {
+        if self.cdn.is_none() {
+            return;
+        }
+        photograph.photograph_link = self.image_url(&photograph.photograph_link);
+        photograph.photograph_thumbnail_link =
+            self.image_url(&photograph.photograph_thumbnail_link);
+        if let Some(raw_link) = &photograph.photograph_raw_link {
+            photograph.photograph_raw_link = Some(self.image_url(raw_link));
+        }
+
+        let mut renditions = photograph.renditions();
+        if !renditions.is_empty() {
+            for rendition in renditions.values_mut() {
+                rendition.url = self.image_url(&rendition.url);
+            }
+            if let Ok(value) = serde_json::to_value(&renditions) {
+                photograph.photograph_renditions = value;
+            }
+        }
+    }
+
+    /// Same for a WASM module listing entry (its thumbnail lives in the image
+    /// bucket; the bundle itself is served by `serve_wasm`).
+    pub fn deliver_wasm_module_item(&self, mut item: WasmModuleItem) -> WasmModuleItem {
+        item.wasm_module_thumbnail_link = self.image_url(&item.wasm_module_thumbnail_link);
+        item
+    }
+
+    /// Drop cached CDN copies of bucket-relative `keys` that were overwritten
+    /// or deleted.
+    pub fn invalidate_cdn_keys(&self, keys: Vec<String>) {
+        if let Some(cdn) = &self.cdn {
+            cdn.invalidate(keys);
+        }
+    }
+}
@@ -1,10 +1,15 @@
-use diesel_async::AsyncPgConnection;
 use diesel_async::pooled_connection::bb8::PooledConnection;
-use lettre::{AsyncSmtpTransport, Tokio1Executor};
+use diesel_async::{AsyncConnection, AsyncPgConnection};
+use tracing::debug;
 use uuid::Uuid;
 
 use super::ServerState;
+use crate::errors::code_error::{CodeErrorResp, pool_err};
 use crate::init::state::{DeploymentEnvironment, ServerStateBuilder};
+use crate::util::db::transaction::ScopedBoxFuture;
+use crate::util::email::sender::SenderIdentity;
+use crate::util::email::transport::EmailTransport;
+use crate::util::email::unsubscribe::UnsubscribeKey;
 
 impl ServerState {
     pub fn builder() -> ServerStateBuilder {
@@ -12,26 +17,68 @@ impl ServerState {
     }
 
     pub fn get_app_name_version(&self) -> String {
-        self.app_name_version.clone()
+        self.config.app.name_version.clone()
     }
 
     pub fn get_uptime(&self) -> tokio::time::Duration {
         self.server_start_time.elapsed()
     }
 
+    /// Pool checkout, traced as `db.get_conn` so waits on a saturated pool
+    /// show up in request traces. Fails at once with
+    /// [`DbUnavailable`](crate::init::db_breaker::DbUnavailable) while
+    /// the circuit breaker is open.
+    #[tracing::instrument(name = "db.get_conn", skip_all)]
     pub async fn get_conn(&self) -> anyhow::Result<PooledConnection<'_, AsyncPgConnection>> {
-        Ok(self.pool.get().await?)
+        self.pool.get().await
     }
 
-    pub fn get_email_client(&self) -> &AsyncSmtpTransport<Tokio1Executor> {
-        &self.email_client
+    /// Checkout for reads that tolerate replication lag, from the read
+    /// replica when one is configured. Falls back to the primary when there
+    /// is no replica, its breaker is open or the checkout fails.
+    #[tracing::instrument(name = "db.get_read_conn", skip_all)]
+    pub async fn get_read_conn(&self) -> anyhow::Result<PooledConnection<'_, AsyncPgConnection>> {
+        if let Some(read_pool) = &self.read_pool {
+            match read_pool.get().await {
+                Ok(conn) => return Ok(conn),
+                Err(e) => debug!(error = %e, "Read replica checkout failed; using primary"),
+            }
+        }
+        self.get_conn().await
+    }
+
+    /// Run `f` in one transaction on a primary connection: committed when
+    /// it returns `Ok`, rolled back on `Err`, so a multi-statement write
+    /// either lands whole or not at all. Statements inside `f` map their own
+    /// errors with `code_err`; a failed `BEGIN`/`COMMIT` is `DB_QUERY_ERROR`.
+    pub async fn transaction<'env, R, F>(&self, f: F) -> Result<R, CodeErrorResp>
+    where
+        R: Send + 'env,
+        F: for<'conn> FnOnce(
+                &'conn mut AsyncPgConnection,
+            ) -> ScopedBoxFuture<'env, 'conn, Result<R, CodeErrorResp>>
+            + Send
+            + 'env,
+    {
+        let mut conn = self.get_conn().await.map_err(pool_err)?;
+        let conn: &mut AsyncPgConnection = &mut conn;
+        conn.transaction(async move |conn| f(conn).await).await
+    }
+
+    pub fn email_transport(&self) -> &dyn EmailTransport {
+        self.email_transport.as_ref()
+    }
+
+    pub fn email_sender(&self) -> &SenderIdentity {
+        &self.email_sender
+    }
+
+    pub fn unsubscribe_key(&self) -> &UnsubscribeKey {
+        &self.unsubscribe_key
     }
 
     pub fn get_responses_handled(&self) -> u64 {
-        std::sync::atomic::AtomicU64::load(
-            &self.responses_handled,
-            std::sync::atomic::Ordering::SeqCst,
-        )
+        self.responses.total()
     }
 
     pub async fn check_api_key(&self, key: &Uuid) -> bool {
@@ -45,11 +92,6 @@ impl ServerState {
         }
     }
 
-    pub fn add_responses_handled(&self) {
-        self.responses_handled
-            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-    }
-
     pub fn get_deployment_environment(&self) -> DeploymentEnvironment {
         self.deployment_environment
     }
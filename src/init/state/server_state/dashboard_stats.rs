@@ -0,0 +1,165 @@
+//! SQL aggregation behind the admin overview (`GET /api/admin/stats`).
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::{QueryableByName, sql_query, sql_types};
+use diesel_async::RunQueryDsl;
+
+use super::ServerState;
+use crate::domain::stats::dashboard::{
+    DashboardStats, DashboardTotals, RangeTotals, StatsRange, daily_series,
+};
+
+/// How long an aggregation is served before it is recomputed.
+const STATS_MAX_AGE_SECS: i64 = 60;
+
+#[derive(QueryableByName)]
+struct CountsRow {
+    #[diesel(sql_type = sql_types::BigInt)]
+    users: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    verified_users: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    posts: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    published_posts: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    comments: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    photographs: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    new_users: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    new_posts: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    new_comments: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    new_photographs: i64,
+    #[diesel(sql_type = sql_types::BigInt)]
+    visits: i64,
+}
+
+#[derive(QueryableByName)]
+struct DayRow {
+    #[diesel(sql_type = sql_types::Date)]
+    day: NaiveDate,
+    #[diesel(sql_type = sql_types::BigInt)]
+    count: i64,
+}
+
+impl ServerState {
+    /// Overview stats for `range`, from the cache unless they are older than
+    /// a minute or `refresh` is set.
+    pub async fn dashboard_stats(
+        &self,
+        range: StatsRange,
+        refresh: bool,
+    ) -> anyhow::Result<Arc<DashboardStats>> {
+        let now = Utc::now();
+        if !refresh
+            && let Some(stats) = self
+                .dashboard_stats_cache
+                .get(range, chrono::Duration::seconds(STATS_MAX_AGE_SECS), now)
+                .await
+        {
+            return Ok(stats);
+        }
+        let stats = Arc::new(self.aggregate_dashboard_stats(range, now).await?);
+        self.dashboard_stats_cache.put(stats.clone()).await;
+        Ok(stats)
+    }
+
+    async fn aggregate_dashboard_stats(
+        &self,
+        range: StatsRange,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<DashboardStats> {
+        let to = now.date_naive();
+        let from = range.first_day(to);
+        let since = from.and_time(chrono::NaiveTime::MIN).and_utc();
+        let mut conn = self.get_read_conn().await?;
+
+        let counts: CountsRow = sql_query(
+            "SELECT \
+                (SELECT COUNT(*) FROM users) AS users, \
+                (SELECT COUNT(*) FROM users WHERE user_is_email_verified) AS verified_users, \
+                (SELECT COUNT(*) FROM posts WHERE post_deleted_at IS NULL) AS posts, \
+                (SELECT COUNT(*) FROM posts \
+                    WHERE post_deleted_at IS NULL AND post_is_published) AS published_posts, \
+                (SELECT COUNT(*) FROM comments WHERE comment_deleted_at IS NULL) AS comments, \
+                (SELECT COUNT(*) FROM photographs \
+                    WHERE photograph_deleted_at IS NULL) AS photographs, \
+                (SELECT COUNT(*) FROM users WHERE user_created_at >= $1) AS new_users, \
+                (SELECT COUNT(*) FROM posts \
+                    WHERE post_deleted_at IS NULL AND post_created_at >= $1) AS new_posts, \
+                (SELECT COUNT(*) FROM comments \
+                    WHERE comment_deleted_at IS NULL AND comment_created_at >= $1) AS new_comments, \
+                (SELECT COUNT(*) FROM photographs \
+                    WHERE photograph_deleted_at IS NULL AND photograph_created_at >= $1) \
+                    AS new_photographs, \
+                (SELECT COUNT(*) FROM visitation_data WHERE visited_at >= $1) AS visits",
+        )
+        .bind::<sql_types::Timestamptz, _>(since)
+        .get_result(&mut conn)
+        .await?;
+
+        let signups: Vec<DayRow> = sql_query(
+            "SELECT (user_created_at AT TIME ZONE 'UTC')::date AS day, COUNT(*) AS count \
+             FROM users WHERE user_created_at >= $1 GROUP BY 1",
+        )
+        .bind::<sql_types::Timestamptz, _>(since)
+        .load(&mut conn)
+        .await?;
+        let visits: Vec<DayRow> = sql_query(
+            "SELECT (visited_at AT TIME ZONE 'UTC')::date AS day, COUNT(*) AS count \
+             FROM visitation_data WHERE visited_at >= $1 GROUP BY 1",
+        )
+        .bind::<sql_types::Timestamptz, _>(since)
+        .load(&mut conn)
+        .await?;
+
+        let per_day = |rows: Vec<DayRow>| -> Vec<(NaiveDate, i64)> {
+            rows.into_iter().map(|r| (r.day, r.count)).collect()
+        };
+        Ok(DashboardStats {
+            range,
+            from,
+            to,
+            totals: DashboardTotals {
+                users: counts.users,
+                verified_users: counts.verified_users,
+                posts: counts.posts,
+                published_posts: counts.published_posts,
+                comments: counts.comments,
+                photographs: counts.photographs,
+            },
+            in_range: RangeTotals {
+                new_users: counts.new_users,
+                new_posts: counts.new_posts,
+                new_comments: counts.new_comments,
+                new_photographs: counts.new_photographs,
+                visits: counts.visits,
+            },
+            daily: daily_series(from, to, &per_day(signups), &per_day(visits)),
+            computed_at: now,
+        })
+    }
+
+    /// Sessions held by this instance and the distinct users behind them.
+    pub async fn active_session_counts(&self) -> (usize, usize) {
+        let mut users = HashSet::new();
+        let mut sessions = 0;
+        self.session_map
+            .iter_async(|_, session| {
+                if session.is_unexpired() {
+                    sessions += 1;
+                    users.insert(session.user_id);
+                }
+                true
+            })
+            .await;
+        (sessions, users.len())
+    }
+}
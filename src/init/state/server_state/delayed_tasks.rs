@@ -0,0 +1,184 @@
+//! Persistence for queued and delayed tasks (`delayed_tasks`). Claims use
+//! `FOR UPDATE SKIP LOCKED`, so several instances can poll the same table
+//! without running a task twice.
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper, sql_query, sql_types::BigInt,
+};
+use diesel_async::RunQueryDsl;
+
+use super::ServerState;
+use crate::domain::job::delayed_task::{
+    ClaimedDelayedTask, DelayedTaskInsertable, DelayedTaskRow, DelayedTaskStatus,
+};
+use crate::schema::delayed_tasks;
+
+impl ServerState {
+    pub async fn insert_delayed_task(&self, task: DelayedTaskInsertable) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::insert_into(delayed_tasks::table)
+            .values(&task)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Mark up to `limit` due pending tasks as running and return them.
+    pub async fn claim_due_delayed_tasks(
+        &self,
+        limit: i64,
+    ) -> anyhow::Result<Vec<ClaimedDelayedTask>> {
+        let mut conn = self.get_conn().await?;
+        let claimed = sql_query(
+            "UPDATE delayed_tasks \
+             SET delayed_task_status = 'running', \
+                 delayed_task_attempts = delayed_task_attempts + 1, \
+                 delayed_task_claimed_at = now() \
+             WHERE delayed_task_id IN ( \
+                 SELECT delayed_task_id FROM delayed_tasks \
+                 WHERE delayed_task_status = 'pending' AND delayed_task_run_at <= now() \
+                 ORDER BY delayed_task_run_at \
+                 LIMIT $1 \
+                 FOR UPDATE SKIP LOCKED) \
+             RETURNING delayed_task_id, delayed_task_payload, delayed_task_run_at, delayed_task_attempts",
+        )
+        .bind::<BigInt, _>(limit)
+        .load::<ClaimedDelayedTask>(&mut conn)
+        .await?;
+        Ok(claimed)
+    }
+
+    /// Put tasks left running by a dead process back in the queue.
+    pub async fn requeue_stale_delayed_tasks(
+        &self,
+        claimed_before: DateTime<Utc>,
+    ) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let requeued = diesel::update(
+            delayed_tasks::table
+                .filter(delayed_tasks::delayed_task_status.eq(DelayedTaskStatus::Running.as_str()))
+                .filter(delayed_tasks::delayed_task_claimed_at.lt(claimed_before)),
+        )
+        .set(delayed_tasks::delayed_task_status.eq(DelayedTaskStatus::Pending.as_str()))
+        .execute(&mut conn)
+        .await?;
+        Ok(requeued)
+    }
+
+    pub async fn complete_delayed_task(&self, task_id: uuid::Uuid) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::update(delayed_tasks::table.find(task_id))
+            .set((
+                delayed_tasks::delayed_task_status.eq(DelayedTaskStatus::Done.as_str()),
+                delayed_tasks::delayed_task_finished_at.eq(Utc::now()),
+                delayed_tasks::delayed_task_last_error.eq(None::<String>),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt: back to pending at `retry_at`, or failed for
+    /// good when `retry_at` is `None`.
+    pub async fn fail_delayed_task(
+        &self,
+        task_id: uuid::Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        let target = delayed_tasks::table.find(task_id);
+        match retry_at {
+            Some(retry_at) => {
+                diesel::update(target)
+                    .set((
+                        delayed_tasks::delayed_task_status.eq(DelayedTaskStatus::Pending.as_str()),
+                        delayed_tasks::delayed_task_run_at.eq(retry_at),
+                        delayed_tasks::delayed_task_last_error.eq(error),
+                    ))
+                    .execute(&mut conn)
+                    .await?
+            }
+            None => {
+                diesel::update(target)
+                    .set((
+                        delayed_tasks::delayed_task_status.eq(DelayedTaskStatus::Failed.as_str()),
+                        delayed_tasks::delayed_task_finished_at.eq(Utc::now()),
+                        delayed_tasks::delayed_task_last_error.eq(error),
+                    ))
+                    .execute(&mut conn)
+                    .await?
+            }
+        };
+        Ok(())
+    }
+
+    /// Delete done and failed tasks that finished before `before`.
+    pub async fn prune_delayed_tasks(&self, before: DateTime<Utc>) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let deleted = diesel::delete(
+            delayed_tasks::table.filter(delayed_tasks::delayed_task_finished_at.lt(before)),
+        )
+        .execute(&mut conn)
+        .await?;
+        Ok(deleted)
+    }
+
+    /// Newest tasks first, optionally only those with `status`.
+    pub async fn list_delayed_tasks(
+        &self,
+        status: Option<DelayedTaskStatus>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<DelayedTaskRow>> {
+        let mut conn = self.get_conn().await?;
+        let mut query = delayed_tasks::table
+            .order(delayed_tasks::delayed_task_created_at.desc())
+            .limit(limit)
+            .select(DelayedTaskRow::as_select())
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(delayed_tasks::delayed_task_status.eq(status.as_str()));
+        }
+        Ok(query.load(&mut conn).await?)
+    }
+
+    pub async fn get_delayed_task(
+        &self,
+        task_id: uuid::Uuid,
+    ) -> anyhow::Result<Option<DelayedTaskRow>> {
+        let mut conn = self.get_conn().await?;
+        let row = delayed_tasks::table
+            .find(task_id)
+            .select(DelayedTaskRow::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Give a failed task a fresh set of attempts, due now. Returns the
+    /// updated row, or `None` when the task is missing or not failed.
+    pub async fn requeue_failed_delayed_task(
+        &self,
+        task_id: uuid::Uuid,
+    ) -> anyhow::Result<Option<DelayedTaskRow>> {
+        let mut conn = self.get_conn().await?;
+        let row = diesel::update(
+            delayed_tasks::table
+                .find(task_id)
+                .filter(delayed_tasks::delayed_task_status.eq(DelayedTaskStatus::Failed.as_str())),
+        )
+        .set((
+            delayed_tasks::delayed_task_status.eq(DelayedTaskStatus::Pending.as_str()),
+            delayed_tasks::delayed_task_attempts.eq(0),
+            delayed_tasks::delayed_task_run_at.eq(Utc::now()),
+            delayed_tasks::delayed_task_finished_at.eq(None::<DateTime<Utc>>),
+        ))
+        .returning(DelayedTaskRow::as_returning())
+        .get_result(&mut conn)
+        .await
+        .optional()?;
+        Ok(row)
+    }
+}
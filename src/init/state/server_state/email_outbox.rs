@@ -0,0 +1,188 @@
+//! Persistence for the email outbox (`email_outbox`). Claims use
+//! `FOR UPDATE SKIP LOCKED`, so several instances can send from the same
+//! table without sending an email twice.
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper, sql_query, sql_types::BigInt,
+};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::email::email_outbox::{
+    EmailOutboxInsertable, EmailOutboxRow, EmailOutboxStatus,
+};
+use crate::schema::email_outbox;
+
+impl ServerState {
+    pub async fn insert_email_outbox(&self, email: EmailOutboxInsertable) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::insert_into(email_outbox::table)
+            .values(&email)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Mark up to `limit` due pending emails as sending and return them.
+    pub async fn claim_due_emails(&self, limit: i64) -> anyhow::Result<Vec<EmailOutboxRow>> {
+        let mut conn = self.get_conn().await?;
+        let claimed = sql_query(
+            "UPDATE email_outbox \
+             SET email_outbox_status = 'sending', \
+                 email_outbox_attempts = email_outbox_attempts + 1, \
+                 email_outbox_claimed_at = now() \
+             WHERE email_outbox_id IN ( \
+                 SELECT email_outbox_id FROM email_outbox \
+                 WHERE email_outbox_status = 'pending' AND email_outbox_next_attempt_at <= now() \
+                 ORDER BY email_outbox_next_attempt_at \
+                 LIMIT $1 \
+                 FOR UPDATE SKIP LOCKED) \
+             RETURNING *",
+        )
+        .bind::<BigInt, _>(limit)
+        .load::<EmailOutboxRow>(&mut conn)
+        .await?;
+        Ok(claimed)
+    }
+
+    /// Put emails left sending by a dead process back in the queue.
+    pub async fn requeue_stale_emails(
+        &self,
+        claimed_before: DateTime<Utc>,
+    ) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let requeued = diesel::update(
+            email_outbox::table
+                .filter(email_outbox::email_outbox_status.eq(EmailOutboxStatus::Sending.as_str()))
+                .filter(email_outbox::email_outbox_claimed_at.lt(claimed_before)),
+        )
+        .set(email_outbox::email_outbox_status.eq(EmailOutboxStatus::Pending.as_str()))
+        .execute(&mut conn)
+        .await?;
+        Ok(requeued)
+    }
+
+    pub async fn mark_email_sent(
+        &self,
+        email_id: Uuid,
+        provider_message_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::update(email_outbox::table.find(email_id))
+            .set((
+                email_outbox::email_outbox_status.eq(EmailOutboxStatus::Sent.as_str()),
+                email_outbox::email_outbox_sent_at.eq(Utc::now()),
+                email_outbox::email_outbox_last_error.eq(None::<String>),
+                email_outbox::email_outbox_provider_message_id.eq(provider_message_id),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt: back to pending at `retry_at`, or dead when
+    /// `retry_at` is `None`.
+    pub async fn fail_email(
+        &self,
+        email_id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        let target = email_outbox::table.find(email_id);
+        match retry_at {
+            Some(retry_at) => {
+                diesel::update(target)
+                    .set((
+                        email_outbox::email_outbox_status.eq(EmailOutboxStatus::Pending.as_str()),
+                        email_outbox::email_outbox_next_attempt_at.eq(retry_at),
+                        email_outbox::email_outbox_last_error.eq(error),
+                    ))
+                    .execute(&mut conn)
+                    .await?
+            }
+            None => {
+                diesel::update(target)
+                    .set((
+                        email_outbox::email_outbox_status.eq(EmailOutboxStatus::Dead.as_str()),
+                        email_outbox::email_outbox_last_error.eq(error),
+                    ))
+                    .execute(&mut conn)
+                    .await?
+            }
+        };
+        Ok(())
+    }
+
+    /// Delete sent, dead and suppressed emails last attempted before `before`.
+    pub async fn prune_email_outbox(&self, before: DateTime<Utc>) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let deleted = diesel::delete(
+            email_outbox::table
+                .filter(email_outbox::email_outbox_status.eq_any([
+                    EmailOutboxStatus::Sent.as_str(),
+                    EmailOutboxStatus::Dead.as_str(),
+                    EmailOutboxStatus::Suppressed.as_str(),
+                ]))
+                .filter(email_outbox::email_outbox_claimed_at.lt(before)),
+        )
+        .execute(&mut conn)
+        .await?;
+        Ok(deleted)
+    }
+
+    /// Newest emails first, optionally only those with `status`.
+    pub async fn list_email_outbox(
+        &self,
+        status: Option<EmailOutboxStatus>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<EmailOutboxRow>> {
+        let mut conn = self.get_conn().await?;
+        let mut query = email_outbox::table
+            .order(email_outbox::email_outbox_created_at.desc())
+            .limit(limit)
+            .select(EmailOutboxRow::as_select())
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(email_outbox::email_outbox_status.eq(status.as_str()));
+        }
+        Ok(query.load(&mut conn).await?)
+    }
+
+    pub async fn get_email_outbox(&self, email_id: Uuid) -> anyhow::Result<Option<EmailOutboxRow>> {
+        let mut conn = self.get_conn().await?;
+        let row = email_outbox::table
+            .find(email_id)
+            .select(EmailOutboxRow::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Give a dead email a fresh set of attempts, due now. Returns the updated
+    /// row, or `None` when the email is missing or not dead.
+    pub async fn requeue_dead_email(
+        &self,
+        email_id: Uuid,
+    ) -> anyhow::Result<Option<EmailOutboxRow>> {
+        let mut conn = self.get_conn().await?;
+        let row = diesel::update(
+            email_outbox::table
+                .find(email_id)
+                .filter(email_outbox::email_outbox_status.eq(EmailOutboxStatus::Dead.as_str())),
+        )
+        .set((
+            email_outbox::email_outbox_status.eq(EmailOutboxStatus::Pending.as_str()),
+            email_outbox::email_outbox_attempts.eq(0),
+            email_outbox::email_outbox_next_attempt_at.eq(Utc::now()),
+        ))
+        .returning(EmailOutboxRow::as_returning())
+        .get_result(&mut conn)
+        .await
+        .optional()?;
+        Ok(row)
+    }
+}
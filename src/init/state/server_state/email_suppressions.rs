@@ -0,0 +1,188 @@
+//! Persistence for bounce and complaint feedback (`email_feedback_events`)
+//! and the suppression list it feeds (`email_suppressions`), which
+//! superusers can also add to by hand.
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper, TextExpressionMethods,
+    upsert::excluded,
+};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::email::email_outbox::EmailOutboxStatus;
+use crate::domain::email::feedback::{EmailFeedback, EmailSuppression, MANUAL_SUPPRESSION};
+use crate::schema::{email_feedback_events, email_outbox, email_suppressions};
+
+impl ServerState {
+    /// Record `feedback` from one SES notification (`payload`) and suppress
+    /// the recipients it says to. Returns how many addresses were suppressed.
+    pub async fn record_email_feedback(
+        &self,
+        feedback: &[EmailFeedback],
+        payload: &serde_json::Value,
+    ) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(async |conn| {
+            let events: Vec<_> = feedback.iter().map(|f| f.insertable(payload)).collect();
+            diesel::insert_into(email_feedback_events::table)
+                .values(&events)
+                .execute(conn)
+                .await?;
+
+            let mut suppressed = 0;
+            for item in feedback.iter().filter(|f| f.suppress) {
+                diesel::insert_into(email_suppressions::table)
+                    .values((
+                        email_suppressions::email_suppression_address.eq(&item.recipient),
+                        email_suppressions::email_suppression_reason.eq(item.kind.as_str()),
+                        email_suppressions::email_suppression_detail.eq(item.suppression_detail()),
+                        email_suppressions::email_suppression_last_event_at.eq(item.occurred_at),
+                    ))
+                    .on_conflict(email_suppressions::email_suppression_address)
+                    .do_update()
+                    .set((
+                        email_suppressions::email_suppression_reason
+                            .eq(excluded(email_suppressions::email_suppression_reason)),
+                        email_suppressions::email_suppression_detail
+                            .eq(excluded(email_suppressions::email_suppression_detail)),
+                        email_suppressions::email_suppression_event_count
+                            .eq(email_suppressions::email_suppression_event_count + 1),
+                        email_suppressions::email_suppression_last_event_at.eq(excluded(
+                            email_suppressions::email_suppression_last_event_at,
+                        )),
+                    ))
+                    .execute(conn)
+                    .await?;
+                suppressed += 1;
+            }
+            Ok(suppressed)
+        })
+        .await
+    }
+
+    /// The suppression reason for `address`, if it must not be sent to.
+    pub async fn email_suppression_reason(&self, address: &str) -> anyhow::Result<Option<String>> {
+        let mut conn = self.get_conn().await?;
+        let reason = email_suppressions::table
+            .find(address.trim().to_lowercase())
+            .select(email_suppressions::email_suppression_reason)
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(reason)
+    }
+
+    /// Close a claimed outbox email without sending it.
+    pub async fn mark_email_suppressed(&self, email_id: Uuid, reason: &str) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        let why = match reason {
+            MANUAL_SUPPRESSION => "recipient suppressed by a superuser".to_string(),
+            other => format!("recipient suppressed after a {other}"),
+        };
+        diesel::update(email_outbox::table.find(email_id))
+            .set((
+                email_outbox::email_outbox_status.eq(EmailOutboxStatus::Suppressed.as_str()),
+                email_outbox::email_outbox_last_error.eq(why),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Most recently suppressed first, optionally only those with `reason`
+    /// or whose address contains `address`.
+    pub async fn list_email_suppressions(
+        &self,
+        reason: Option<&str>,
+        address: Option<&str>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<EmailSuppression>> {
+        let mut conn = self.get_conn().await?;
+        let mut query = email_suppressions::table
+            .order(email_suppressions::email_suppression_created_at.desc())
+            .limit(limit)
+            .select(EmailSuppression::as_select())
+            .into_boxed();
+        if let Some(reason) = reason {
+            query = query.filter(email_suppressions::email_suppression_reason.eq(reason));
+        }
+        if let Some(address) = address {
+            let pattern = format!(
+                "%{}%",
+                address
+                    .trim()
+                    .to_lowercase()
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            );
+            query = query.filter(email_suppressions::email_suppression_address.like(pattern));
+        }
+        Ok(query.load(&mut conn).await?)
+    }
+
+    /// Suppress `address` by hand. An address already on the list keeps its
+    /// entry; the flag says whether a new one was made.
+    pub async fn add_email_suppression(
+        &self,
+        address: &str,
+        note: Option<&str>,
+    ) -> anyhow::Result<(EmailSuppression, bool)> {
+        let mut conn = self.get_conn().await?;
+        let address = address.trim().to_lowercase();
+        conn.transaction::<_, anyhow::Error, _>(async |conn| {
+            let inserted = diesel::insert_into(email_suppressions::table)
+                .values((
+                    email_suppressions::email_suppression_address.eq(&address),
+                    email_suppressions::email_suppression_reason.eq(MANUAL_SUPPRESSION),
+                    email_suppressions::email_suppression_detail.eq(note),
+                ))
+                .on_conflict_do_nothing()
+                .returning(EmailSuppression::as_returning())
+                .get_result(conn)
+                .await
+                .optional()?;
+            if let Some(row) = inserted {
+                return Ok((row, true));
+            }
+            let existing = email_suppressions::table
+                .find(&address)
+                .select(EmailSuppression::as_select())
+                .first(conn)
+                .await?;
+            Ok((existing, false))
+        })
+        .await
+    }
+
+    /// Lift the suppression on `address`; `None` when it was not suppressed.
+    pub async fn delete_email_suppression(
+        &self,
+        address: &str,
+    ) -> anyhow::Result<Option<EmailSuppression>> {
+        let mut conn = self.get_conn().await?;
+        let row = diesel::delete(email_suppressions::table.find(address.trim().to_lowercase()))
+            .returning(EmailSuppression::as_returning())
+            .get_result(&mut conn)
+            .await
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Delete feedback events recorded before `before`. Suppressions stay.
+    pub async fn prune_email_feedback_events(
+        &self,
+        before: DateTime<Utc>,
+    ) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let deleted = diesel::delete(
+            email_feedback_events::table
+                .filter(email_feedback_events::email_feedback_event_created_at.lt(before)),
+        )
+        .execute(&mut conn)
+        .await?;
+        Ok(deleted)
+    }
+}
@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use diesel::{DecoratableTarget, ExpressionMethods};
 use diesel_async::{AsyncConnection, RunQueryDsl};
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use super::ServerState;
@@ -10,12 +13,22 @@ use crate::domain::country::{
 };
 use crate::domain::i18n::i18n::InternationalizationString;
 use crate::domain::i18n::i18n_cache::I18nCache;
+use crate::domain::i18n::ui_text::keys::REQUIRED_UI_TEXT_KEYS;
+use crate::domain::i18n::ui_text::locale::{EN_US_COUNTRY_CODE, EN_US_LANGUAGE_CODE, UiLocale};
 use crate::domain::i18n::ui_text::source::source_bundles;
+use crate::init::state::cache_versions::CachedResource;
 use crate::schema::{
     i18n_strings, iso_country, iso_country_subdivision, iso_currency, iso_language,
 };
 use crate::util::time::now::tokio_now;
 
+/// Bounds how long another instance's bundle can outlive an i18n sync there.
+const UI_TEXT_BUNDLE_TTL: Duration = Duration::from_secs(60 * 60);
+
+fn ui_text_bundle_key(locale: UiLocale) -> String {
+    format!("i18n:ui_text:{}", locale.as_tag())
+}
+
 impl ServerState {
     pub async fn sync_country_data(&self) -> anyhow::Result<()> {
         let start = tokio::time::Instant::now();
@@ -71,6 +84,8 @@ impl ServerState {
             tracing::error!(error = ?e, "Error synchronizing languages data");
         }
 
+        self.cache_versions.bump(CachedResource::Countries);
+
         if let Ok((new_currency_map, curr_rows)) = curr_res {
             let mut lock = self.currency_map.write().await;
             *lock = new_currency_map;
@@ -92,11 +107,48 @@ impl ServerState {
         let num_rows = rows.len();
         let mut lock = self.i18n_cache.write().await;
         *lock = I18nCache::from_rows(rows);
+        drop(lock);
+
+        for locale in UiLocale::ALL {
+            if let Err(e) = self.cache.delete(&ui_text_bundle_key(locale)).await {
+                warn!(error = %e, locale = locale.as_tag(), "Could not invalidate cached UI text bundle");
+            }
+        }
 
         info!(elapsed = ?start.elapsed(), rows_synchronized = %num_rows, "Synchronized i18n data.");
         Ok(num_rows)
     }
 
+    /// UI text for `locale`, falling back to en-US per key. Built bundles
+    /// are kept in the shared cache, so instances serve the same text until
+    /// the next sync.
+    pub async fn ui_text_bundle(&self, locale: UiLocale) -> HashMap<String, String> {
+        let key = ui_text_bundle_key(locale);
+        match self.cache.get(&key).await {
+            Ok(Some(bytes)) => match serde_json::from_slice(&bytes) {
+                Ok(texts) => return texts,
+                Err(e) => warn!(error = %e, %key, "Discarding unreadable cached UI text bundle"),
+            },
+            Ok(None) => {}
+            Err(e) => warn!(error = %e, %key, "Could not read cached UI text bundle"),
+        }
+
+        let texts = self.i18n_cache.read().await.ui_text_bundle(
+            locale.country_code(),
+            locale.language_code(),
+            EN_US_COUNTRY_CODE,
+            EN_US_LANGUAGE_CODE,
+            REQUIRED_UI_TEXT_KEYS,
+        );
+        if !texts.is_empty()
+            && let Ok(bytes) = serde_json::to_vec(&texts)
+            && let Err(e) = self.cache.set(&key, bytes, UI_TEXT_BUNDLE_TTL).await
+        {
+            warn!(error = %e, %key, "Could not cache UI text bundle");
+        }
+        texts
+    }
+
     pub async fn sync_file_backed_ui_text_sources(&self) -> anyhow::Result<usize> {
         let start = tokio_now();
         let bundles = source_bundles()?;
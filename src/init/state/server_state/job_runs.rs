@@ -0,0 +1,104 @@
+//! Persistent job state: the last successful run per job (`job_runs`), used by
+//! the daily/weekly schedulers to detect runs missed while the process was
+//! down, and the execution history (`job_executions`).
+
+use chrono::{DateTime, Utc};
+use diesel::upsert::excluded;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+use super::ServerState;
+use crate::domain::job::execution::{JobExecutionInsertable, JobExecutionRow, JobOutcome};
+use crate::schema::{job_executions, job_runs};
+
+impl ServerState {
+    pub async fn last_job_success(&self, job_name: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let mut conn = self.get_conn().await?;
+        let last = job_runs::table
+            .select(job_runs::job_last_success_at)
+            .filter(job_runs::job_name.eq(job_name))
+            .first::<DateTime<Utc>>(&mut conn)
+            .await
+            .optional()?;
+        Ok(last)
+    }
+
+    pub async fn record_job_success(
+        &self,
+        job_name: &str,
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::insert_into(job_runs::table)
+            .values((
+                job_runs::job_name.eq(job_name),
+                job_runs::job_last_success_at.eq(at),
+                job_runs::job_updated_at.eq(Utc::now()),
+            ))
+            .on_conflict(job_runs::job_name)
+            .do_update()
+            .set((
+                job_runs::job_last_success_at.eq(excluded(job_runs::job_last_success_at)),
+                job_runs::job_updated_at.eq(excluded(job_runs::job_updated_at)),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn record_job_execution(
+        &self,
+        execution: JobExecutionInsertable,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::insert_into(job_executions::table)
+            .values(&execution)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Whether some instance already ran the tick scheduled for `scheduled_for`.
+    pub async fn job_mark_succeeded(
+        &self,
+        job_name: &str,
+        scheduled_for: DateTime<Utc>,
+    ) -> anyhow::Result<bool> {
+        let mut conn = self.get_conn().await?;
+        let succeeded = diesel::select(diesel::dsl::exists(
+            job_executions::table
+                .filter(job_executions::job_name.eq(job_name))
+                .filter(job_executions::job_scheduled_for.eq(scheduled_for))
+                .filter(job_executions::job_outcome.eq(JobOutcome::Success.as_str())),
+        ))
+        .get_result::<bool>(&mut conn)
+        .await?;
+        Ok(succeeded)
+    }
+
+    /// Most recent executions of one job, newest first.
+    pub async fn recent_job_executions(
+        &self,
+        job_name: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<JobExecutionRow>> {
+        let mut conn = self.get_conn().await?;
+        let rows = job_executions::table
+            .filter(job_executions::job_name.eq(job_name))
+            .order(job_executions::job_started_at.desc())
+            .limit(limit)
+            .select(JobExecutionRow::as_select())
+            .load(&mut conn)
+            .await?;
+        Ok(rows)
+    }
+
+    pub async fn prune_job_executions(&self, before: DateTime<Utc>) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let deleted =
+            diesel::delete(job_executions::table.filter(job_executions::job_started_at.lt(before)))
+                .execute(&mut conn)
+                .await?;
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,27 @@
+//! Fan-out of [`LiveEvent`]s to `GET /api/events` subscribers.
+
+use tokio::sync::broadcast;
+
+use super::ServerState;
+use crate::domain::blog::live_event::LiveEvent;
+
+impl ServerState {
+    /// Send `event` to every connected subscriber. Events about posts that are
+    /// not published (per the post cache) are dropped: the stream is public.
+    pub async fn publish_live_event(&self, event: LiveEvent) {
+        if self.live_events.receiver_count() == 0 {
+            return;
+        }
+        let is_public = self
+            .get_post_from_cache(&event.post_id())
+            .await
+            .is_some_and(|post| post.post_is_published);
+        if is_public {
+            let _ = self.live_events.send(event);
+        }
+    }
+
+    pub fn subscribe_live_events(&self) -> broadcast::Receiver<LiveEvent> {
+        self.live_events.subscribe()
+    }
+}
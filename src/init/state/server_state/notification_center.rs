@@ -0,0 +1,215 @@
+//! The stored side of notifications: listing and marking `notifications`,
+//! per-user `user_notification_preferences`, and the daily digest.
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, JoinOnDsl, NullableExpressionMethods,
+    OptionalExtension, QueryDsl, SelectableHelper, dsl::count_star,
+};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::notification::notification::NotificationRow;
+use crate::domain::notification::preferences::{DigestRecipient, NotificationPreferences};
+use crate::schema::{notifications, user_notification_preferences, users};
+use crate::util::db::keyset::{Keyset, KeysetOrder, KeysetPaginate};
+
+impl ServerState {
+    /// `user_id`'s preferences, or the defaults if they never saved any.
+    pub async fn notification_preferences(
+        &self,
+        user_id: Uuid,
+    ) -> anyhow::Result<NotificationPreferences> {
+        let mut conn = self.get_conn().await?;
+        let preferences = user_notification_preferences::table
+            .find(user_id)
+            .select(NotificationPreferences::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(preferences.unwrap_or_default())
+    }
+
+    pub async fn set_notification_preferences(
+        &self,
+        user_id: Uuid,
+        preferences: NotificationPreferences,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        let values = (
+            user_notification_preferences::notify_mentions.eq(preferences.mentions),
+            user_notification_preferences::notify_replies.eq(preferences.replies),
+            user_notification_preferences::notify_digest.eq(preferences.digest),
+            user_notification_preferences::preferences_updated_at.eq(Utc::now()),
+        );
+        diesel::insert_into(user_notification_preferences::table)
+            .values((user_notification_preferences::user_id.eq(user_id), values))
+            .on_conflict(user_notification_preferences::user_id)
+            .do_update()
+            .set(values)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Turn the digest off for `user_id`, keeping their other switches.
+    /// Returns `false` if the user no longer exists.
+    pub async fn unsubscribe_notification_digest(&self, user_id: Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.get_conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(async |conn| {
+            let exists = users::table
+                .find(user_id)
+                .select(users::user_id)
+                .first::<Uuid>(conn)
+                .await
+                .optional()?
+                .is_some();
+            if !exists {
+                return Ok(false);
+            }
+            let values = (
+                user_notification_preferences::notify_digest.eq(false),
+                user_notification_preferences::preferences_updated_at.eq(Utc::now()),
+            );
+            diesel::insert_into(user_notification_preferences::table)
+                .values((user_notification_preferences::user_id.eq(user_id), values))
+                .on_conflict(user_notification_preferences::user_id)
+                .do_update()
+                .set(values)
+                .execute(conn)
+                .await?;
+            Ok(true)
+        })
+        .await
+    }
+
+    /// `user_id`'s notifications, newest first, starting after `after`.
+    pub async fn list_notifications(
+        &self,
+        user_id: Uuid,
+        unread_only: bool,
+        after: Option<Keyset<Uuid>>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<NotificationRow>> {
+        let mut conn = self.get_read_conn().await?;
+        let mut query = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .select(NotificationRow::as_select())
+            .into_boxed()
+            .keyset_page(
+                (
+                    notifications::notification_created_at,
+                    notifications::notification_id,
+                ),
+                after.as_ref(),
+                KeysetOrder::NewestFirst,
+                limit,
+            );
+        if unread_only {
+            query = query.filter(notifications::notification_read_at.is_null());
+        }
+        Ok(query.load(&mut conn).await?)
+    }
+
+    pub async fn unread_notification_count(&self, user_id: Uuid) -> anyhow::Result<i64> {
+        let mut conn = self.get_read_conn().await?;
+        let count = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .filter(notifications::notification_read_at.is_null())
+            .count()
+            .get_result(&mut conn)
+            .await?;
+        Ok(count)
+    }
+
+    /// Mark `notification_ids` (or, with `None`, every unread notification)
+    /// of `user_id` read. Ids of other users' notifications are ignored.
+    /// Returns how many were marked.
+    pub async fn mark_notifications_read(
+        &self,
+        user_id: Uuid,
+        notification_ids: Option<&[Uuid]>,
+    ) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let unread = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .filter(notifications::notification_read_at.is_null());
+        let read_at = notifications::notification_read_at.eq(Utc::now());
+        let marked = match notification_ids {
+            Some(ids) => {
+                diesel::update(unread.filter(notifications::notification_id.eq_any(ids)))
+                    .set(read_at)
+                    .execute(&mut conn)
+                    .await?
+            }
+            None => {
+                diesel::update(unread)
+                    .set(read_at)
+                    .execute(&mut conn)
+                    .await?
+            }
+        };
+        Ok(marked)
+    }
+
+    /// Users with the digest on and unread notifications that arrived after
+    /// their last digest.
+    pub async fn notification_digest_recipients(&self) -> anyhow::Result<Vec<DigestRecipient>> {
+        let mut conn = self.get_read_conn().await?;
+        let rows: Vec<(Uuid, String, i64)> = user_notification_preferences::table
+            .inner_join(users::table)
+            .inner_join(
+                notifications::table.on(notifications::user_id
+                    .eq(user_notification_preferences::user_id)
+                    .and(notifications::notification_read_at.is_null())),
+            )
+            .filter(user_notification_preferences::notify_digest.eq(true))
+            .filter(
+                user_notification_preferences::digest_sent_at.is_null().or(
+                    notifications::notification_created_at
+                        .nullable()
+                        .gt(user_notification_preferences::digest_sent_at),
+                ),
+            )
+            // Grouped by the users key so the email column may be selected.
+            .group_by(users::user_id)
+            .select((users::user_id, users::user_email, count_star()))
+            .load(&mut conn)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, user_email, unread)| DigestRecipient {
+                user_id,
+                user_email,
+                unread,
+            })
+            .collect())
+    }
+
+    pub async fn mark_notification_digest_sent(
+        &self,
+        user_id: Uuid,
+        sent_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::update(user_notification_preferences::table.find(user_id))
+            .set(user_notification_preferences::digest_sent_at.eq(sent_at))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete read notifications older than `before`. Unread ones stay.
+    pub async fn prune_read_notifications(&self, before: DateTime<Utc>) -> anyhow::Result<usize> {
+        let mut conn = self.get_conn().await?;
+        let deleted = diesel::delete(
+            notifications::table
+                .filter(notifications::notification_read_at.is_not_null())
+                .filter(notifications::notification_created_at.lt(before)),
+        )
+        .execute(&mut conn)
+        .await?;
+        Ok(deleted)
+    }
+}
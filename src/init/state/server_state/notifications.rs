@@ -0,0 +1,216 @@
+//! Fan-out of notifications: resolving who a comment or vote concerns,
+//! storing the notification in `notifications` and pushing it to the
+//! [`NotificationHub`](crate::domain::notification::hub::NotificationHub).
+
+use std::sync::Arc;
+
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use tracing::error;
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::notification::notification::{
+    CommentLocation, Notification, NotificationEnvelope, PostedComment,
+};
+use crate::schema::{comments, notifications, photograph_comments, photographs, posts, users};
+use crate::util::string::mentions::extract_mentions;
+
+impl ServerState {
+    /// Store `notification` for `user_id` and push it to their open sockets,
+    /// unless their preferences turn it off. Failures are logged, not
+    /// returned: a lost notification never fails the request behind it.
+    pub async fn notify_user(&self, user_id: Uuid, notification: Notification) {
+        if let Err(e) = self.store_and_push(user_id, notification).await {
+            error!(%user_id, error = ?e, "Failed to send notification");
+        }
+    }
+
+    async fn store_and_push(
+        &self,
+        user_id: Uuid,
+        notification: Notification,
+    ) -> anyhow::Result<()> {
+        if !self
+            .notification_preferences(user_id)
+            .await?
+            .allows(&notification)
+        {
+            return Ok(());
+        }
+        let envelope = NotificationEnvelope::new(notification);
+        let row = envelope.insertable(user_id)?;
+        let mut conn = self.get_conn().await?;
+        diesel::insert_into(notifications::table)
+            .values(&row)
+            .execute(&mut conn)
+            .await?;
+        drop(conn);
+        self.notifications.notify(user_id, envelope).await;
+        Ok(())
+    }
+
+    /// Notify the owner of what `voter_id` upvoted: the post or photograph at
+    /// `location`, or the comment `comment_id` on it. Runs on its own task;
+    /// self-votes are ignored, and an upvote of something the owner already
+    /// has an unread upvote notification for adds nothing.
+    pub fn notify_upvote(
+        self: &Arc<Self>,
+        voter_id: Uuid,
+        location: CommentLocation,
+        comment_id: Option<Uuid>,
+    ) {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = state
+                .send_upvote_notification(voter_id, location, comment_id)
+                .await
+            {
+                error!(?location, ?comment_id, error = ?e, "Failed to send upvote notification");
+            }
+        });
+    }
+
+    async fn send_upvote_notification(
+        &self,
+        voter_id: Uuid,
+        location: CommentLocation,
+        comment_id: Option<Uuid>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        // A comment's own thread is looked up rather than trusted from the
+        // request path.
+        let target: Option<(Uuid, CommentLocation)> = match (location, comment_id) {
+            (CommentLocation::Post { post_id }, None) => posts::table
+                .filter(posts::post_id.eq(post_id))
+                .select(posts::user_id)
+                .first(&mut conn)
+                .await
+                .optional()?
+                .map(|owner_id| (owner_id, location)),
+            (CommentLocation::Photograph { photograph_id }, None) => photographs::table
+                .filter(photographs::photograph_id.eq(photograph_id))
+                .select(photographs::user_id)
+                .first(&mut conn)
+                .await
+                .optional()?
+                .map(|owner_id| (owner_id, location)),
+            (CommentLocation::Post { .. }, Some(comment_id)) => comments::table
+                .filter(comments::comment_id.eq(comment_id))
+                .select((comments::user_id, comments::post_id))
+                .first::<(Uuid, Uuid)>(&mut conn)
+                .await
+                .optional()?
+                .map(|(owner_id, post_id)| (owner_id, CommentLocation::Post { post_id })),
+            (CommentLocation::Photograph { .. }, Some(comment_id)) => photograph_comments::table
+                .filter(photograph_comments::photograph_comment_id.eq(comment_id))
+                .select((
+                    photograph_comments::user_id,
+                    photograph_comments::photograph_id,
+                ))
+                .first::<(Uuid, Uuid)>(&mut conn)
+                .await
+                .optional()?
+                .map(|(owner_id, photograph_id)| {
+                    (owner_id, CommentLocation::Photograph { photograph_id })
+                }),
+        };
+        let Some((owner_id, location)) = target.filter(|(owner_id, _)| *owner_id != voter_id)
+        else {
+            return Ok(());
+        };
+
+        let notification = Notification::Upvote {
+            location,
+            comment_id,
+        };
+        let already_unread: i64 = notifications::table
+            .filter(notifications::user_id.eq(owner_id))
+            .filter(notifications::notification_read_at.is_null())
+            .filter(notifications::notification_payload.eq(serde_json::to_value(&notification)?))
+            .count()
+            .get_result(&mut conn)
+            .await?;
+        drop(conn);
+        if already_unread == 0 {
+            self.notify_user(owner_id, notification).await;
+        }
+        Ok(())
+    }
+
+    /// Notify the author of the parent comment and everyone `@mentioned` in
+    /// `comment`, never the commenter. Runs on its own task; a user mentioned
+    /// in a reply to their own comment is sent the reply only.
+    pub fn notify_comment_audience(self: &Arc<Self>, comment: PostedComment) {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = state.send_comment_notifications(&comment).await {
+                error!(comment_id = %comment.comment_id, error = ?e, "Failed to send comment notifications");
+            }
+        });
+    }
+
+    async fn send_comment_notifications(&self, comment: &PostedComment) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+
+        let parent_author_id: Option<Uuid> = match (comment.location, comment.parent_comment_id) {
+            (_, None) => None,
+            (CommentLocation::Post { .. }, Some(parent_id)) => comments::table
+                .filter(comments::comment_id.eq(parent_id))
+                .select(comments::user_id)
+                .first(&mut conn)
+                .await
+                .optional()?,
+            (CommentLocation::Photograph { .. }, Some(parent_id)) => photograph_comments::table
+                .filter(photograph_comments::photograph_comment_id.eq(parent_id))
+                .select(photograph_comments::user_id)
+                .first(&mut conn)
+                .await
+                .optional()?,
+        };
+
+        let mentioned = extract_mentions(&comment.content);
+        let mentioned_ids: Vec<Uuid> = if mentioned.is_empty() {
+            Vec::new()
+        } else {
+            users::table
+                .filter(users::user_name.eq_any(&mentioned))
+                .select(users::user_id)
+                .load(&mut conn)
+                .await?
+        };
+        drop(conn);
+
+        if let (Some(parent_author_id), Some(parent_comment_id)) =
+            (parent_author_id, comment.parent_comment_id)
+            && parent_author_id != comment.author_id
+        {
+            self.notify_user(
+                parent_author_id,
+                Notification::CommentReply {
+                    location: comment.location,
+                    comment_id: comment.comment_id,
+                    parent_comment_id,
+                    by_user_name: comment.author_name.clone(),
+                },
+            )
+            .await;
+        }
+
+        for user_id in mentioned_ids {
+            if user_id == comment.author_id || Some(user_id) == parent_author_id {
+                continue;
+            }
+            self.notify_user(
+                user_id,
+                Notification::Mention {
+                    location: comment.location,
+                    comment_id: comment.comment_id,
+                    by_user_name: comment.author_name.clone(),
+                },
+            )
+            .await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+//! `ServerState` accessors for outstanding presigned photograph uploads.
+//!
+//! Same privacy invariant as the batch tracker: [`take_owned_presigned_upload`]
+//! returns `None` for a token that is absent, expired, *or* not owned by the
+//! requester, so the confirm handler has a single 404 path.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::photography::presigned_upload::PresignedUpload;
+
+use super::ServerState;
+
+impl ServerState {
+    /// Register a freshly-issued upload token.
+    pub async fn register_presigned_upload(&self, upload: PresignedUpload) {
+        let upload_token = upload.upload_token;
+        let _ = self
+            .photograph_presigned_uploads
+            .insert_async(upload_token, upload)
+            .await;
+    }
+
+    /// Remove and return an upload token if it exists, is unexpired, and is owned
+    /// by `requester`. Consuming the token makes confirm single-use.
+    pub async fn take_owned_presigned_upload(
+        &self,
+        upload_token: Uuid,
+        requester: Uuid,
+        now: DateTime<Utc>,
+    ) -> Option<PresignedUpload> {
+        self.photograph_presigned_uploads
+            .remove_if_async(&upload_token, |upload| {
+                upload.owner == requester && !upload.is_expired(now)
+            })
+            .await
+            .map(|(_, upload)| upload)
+    }
+
+    /// Drop expired upload tokens and return them so the caller can reap their
+    /// staged S3 objects.
+    pub async fn prune_expired_presigned_uploads(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Vec<PresignedUpload> {
+        let mut expired: Vec<PresignedUpload> = Vec::new();
+        self.photograph_presigned_uploads
+            .retain_async(|_, upload| {
+                if upload.is_expired(now) {
+                    expired.push(upload.clone());
+                    false
+                } else {
+                    true
+                }
+            })
+            .await;
+        expired
+    }
+}
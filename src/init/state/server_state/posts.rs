@@ -1,12 +1,40 @@
-use tracing::{error, info};
+use std::time::Duration;
+
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use super::ServerState;
-use crate::domain::blog::blog::CachedPostInfo;
+use crate::domain::blog::blog::{CachedPostInfo, PostInfo};
 use crate::init::load_cache::post_info::load_post_info;
+use crate::init::state::cache_versions::CachedResource;
+use crate::schema::{post_tags, posts, tags};
 use crate::util::time::now::tokio_now;
 
 impl ServerState {
+    /// Whether this read of `post_id` by `viewer` (a user or client IP key)
+    /// counts as a view: once per viewer per dedup window, across instances
+    /// when the cache is Redis. Counts when the cache is unreachable.
+    pub async fn claim_post_view(&self, post_id: Uuid, viewer: &str) -> bool {
+        // `posts.view_dedup_secs` (default 1800); 0 counts every read.
+        let window = Duration::from_secs(self.config.posts.view_dedup_secs);
+        if window.is_zero() {
+            return true;
+        }
+        match self
+            .cache
+            .set_if_absent(&format!("post_view:{post_id}:{viewer}"), Vec::new(), window)
+            .await
+        {
+            Ok(first_view) => first_view,
+            Err(e) => {
+                warn!(error = %e, %post_id, "Could not check post view dedup; counting the view");
+                true
+            }
+        }
+    }
+
     fn normalize_post_slug(slug: &str) -> Option<String> {
         let normalized = slug.trim().to_lowercase();
         if normalized.is_empty() {
@@ -74,6 +102,8 @@ impl ServerState {
                 .await;
         }
 
+        self.cache_versions.bump(CachedResource::Posts);
+
         if !sync_search_index {
             return newly_inserted;
         }
@@ -114,6 +144,8 @@ impl ServerState {
             .collect();
         let mut lock = self.blog_post_order_cache.write().await;
         *lock = ordered_post_ids;
+        drop(lock);
+        self.cache_versions.bump(CachedResource::Posts);
     }
 
     pub async fn synchronize_post_info_cache(&self) {
@@ -187,6 +219,7 @@ impl ServerState {
         );
     }
 
+    /// One page of posts, newest first, and the number of visible posts.
     pub async fn get_posts_from_cache(
         &self,
         page: usize,
@@ -229,9 +262,7 @@ impl ServerState {
             visible_posts += 1;
         }
 
-        let total_pages = visible_posts.div_ceil(page_size);
-
-        (posts, total_pages)
+        (posts, visible_posts)
     }
 
     pub async fn delete_post_from_cache(&self, post_id: Uuid) {
@@ -251,6 +282,36 @@ impl ServerState {
         }
     }
 
+    /// Reload one post and its tags into the cache, or drop it from the cache
+    /// when it is gone or soft-deleted. Used when another instance changed it.
+    pub async fn refresh_cached_post(&self, post_id: Uuid) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        let post_info: Option<PostInfo> = posts::table
+            .filter(posts::post_id.eq(post_id))
+            .filter(posts::post_deleted_at.is_null())
+            .select(PostInfo::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        let Some(post_info) = post_info else {
+            drop(conn);
+            self.delete_post_from_cache(post_id).await;
+            return Ok(());
+        };
+        let post_tags: Vec<String> = post_tags::table
+            .inner_join(tags::table)
+            .filter(post_tags::post_id.eq(post_id))
+            .select(tags::tag_name)
+            .load(&mut conn)
+            .await?;
+        drop(conn);
+        self.insert_post_to_cache(&CachedPostInfo::from_post_info_with_tags(
+            post_info, post_tags,
+        ))
+        .await;
+        Ok(())
+    }
+
     pub async fn insert_post_to_cache(&self, post: &CachedPostInfo) {
         let _ = self.upsert_post_cache_internal(post, true).await;
         self.rebuild_post_order_cache().await;
@@ -284,6 +345,7 @@ impl ServerState {
                 cached.total_downvotes = total_downvotes;
             })
             .await;
+        self.cache_versions.bump(CachedResource::Posts);
     }
 
     pub async fn get_post_from_cache(&self, post_id: &Uuid) -> Option<CachedPostInfo> {
@@ -292,6 +354,26 @@ impl ServerState {
             .await
     }
 
+    /// Every tag on a cached post with the number of posts carrying it,
+    /// most used first.
+    pub async fn post_tag_counts(&self, include_unpublished: bool) -> Vec<(String, usize)> {
+        let mut counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        self.blog_posts_cache
+            .iter_async(|_, post| {
+                if include_unpublished || post.post_is_published {
+                    for tag in &post.post_tags {
+                        *counts.entry(tag.clone()).or_default() += 1;
+                    }
+                }
+                true
+            })
+            .await;
+
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
     pub async fn get_post_id_by_slug_from_cache(&self, post_slug: &str) -> Option<Uuid> {
         let normalized_slug = Self::normalize_post_slug(post_slug)?;
         self.blog_post_slug_cache
@@ -9,6 +9,7 @@ use crate::domain::auth::{
     user_roles::UserRole,
 };
 use crate::init::state::session::{DEFAULT_SESSION_DURATION, Session};
+use crate::init::state::session_stats::{SessionBreakdown, SessionEvent};
 use crate::schema::users;
 
 impl ServerState {
@@ -51,7 +52,7 @@ impl ServerState {
             )
             .await
         {
-            Ok(_) => (),
+            Ok(_) => self.session_stats.record(SessionEvent::Created, 1),
             Err(_) => {
                 return Err(anyhow::anyhow!(
                     "Failed to insert session into scc::HashMap; key already exists!"
@@ -150,7 +151,10 @@ impl ServerState {
     pub async fn remove_session(&self, session_id: Uuid) -> anyhow::Result<(Uuid, usize)> {
         let cur_session_count = self.session_map.len();
         match self.session_map.remove_async(&session_id).await {
-            Some((session_id, _)) => Ok((session_id, cur_session_count - 1)),
+            Some((session_id, _)) => {
+                self.session_stats.record(SessionEvent::Ended, 1);
+                Ok((session_id, cur_session_count - 1))
+            }
             None => Err(anyhow::anyhow!("Session map out of sync!")),
         }
     }
@@ -170,7 +174,22 @@ impl ServerState {
                 true
             })
             .await;
+        self.session_stats
+            .record(SessionEvent::Expired, pruned as u64);
 
         (pruned, remaining)
     }
+
+    /// Counts over the sessions in memory right now.
+    pub async fn session_breakdown(&self) -> SessionBreakdown {
+        let now = chrono::Utc::now();
+        let mut breakdown = SessionBreakdown::default();
+        self.session_map
+            .iter_async(|_, session| {
+                breakdown.add(session, now);
+                true
+            })
+            .await;
+        breakdown
+    }
 }
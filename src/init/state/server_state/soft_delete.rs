@@ -0,0 +1,138 @@
+//! Restore and purge for soft-deleted content (`domain::soft_delete`).
+
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::blog::blog::{CachedPostInfo, PostInfo};
+use crate::domain::photography::photographs::PhotographObjects;
+use crate::domain::soft_delete::soft_delete::{SoftDeleteKind, SoftDeletePurge};
+use crate::schema::{comments, photographs, post_tags, posts, tags};
+
+impl ServerState {
+    /// Clear the deletion mark on one row; `false` when no deleted row has
+    /// that id. A restored post goes back into the post cache and the search
+    /// index.
+    pub async fn restore_soft_deleted(
+        &self,
+        kind: SoftDeleteKind,
+        id: Uuid,
+    ) -> anyhow::Result<bool> {
+        let mut conn = self.get_conn().await?;
+        let restored = match kind {
+            SoftDeleteKind::Post => {
+                diesel::update(
+                    posts::table
+                        .filter(posts::post_id.eq(id))
+                        .filter(posts::post_deleted_at.is_not_null()),
+                )
+                .set(posts::post_deleted_at.eq(None::<DateTime<Utc>>))
+                .execute(&mut conn)
+                .await?
+            }
+            SoftDeleteKind::Comment => {
+                diesel::update(
+                    comments::table
+                        .filter(comments::comment_id.eq(id))
+                        .filter(comments::comment_deleted_at.is_not_null()),
+                )
+                .set(comments::comment_deleted_at.eq(None::<DateTime<Utc>>))
+                .execute(&mut conn)
+                .await?
+            }
+            SoftDeleteKind::Photograph => {
+                diesel::update(
+                    photographs::table
+                        .filter(photographs::photograph_id.eq(id))
+                        .filter(photographs::photograph_deleted_at.is_not_null()),
+                )
+                .set(photographs::photograph_deleted_at.eq(None::<DateTime<Utc>>))
+                .execute(&mut conn)
+                .await?
+            }
+        };
+        if restored == 0 {
+            return Ok(false);
+        }
+
+        if kind == SoftDeleteKind::Post {
+            let post_info: PostInfo = posts::table
+                .filter(posts::post_id.eq(id))
+                .select(PostInfo::as_select())
+                .first(&mut conn)
+                .await?;
+            let post_tags: Vec<String> = post_tags::table
+                .inner_join(tags::table)
+                .filter(post_tags::post_id.eq(id))
+                .select(tags::tag_name)
+                .load(&mut conn)
+                .await?;
+            drop(conn);
+            self.insert_post_to_cache(&CachedPostInfo::from_post_info_with_tags(
+                post_info, post_tags,
+            ))
+            .await;
+        }
+        Ok(true)
+    }
+
+    /// Permanently delete rows soft-deleted before `cutoff`, then the stored
+    /// objects of the purged photographs. Votes, tags and comments of a
+    /// purged post go with it through their foreign keys.
+    pub async fn purge_soft_deleted(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> anyhow::Result<SoftDeletePurge> {
+        let mut conn = self.get_conn().await?;
+        let comments =
+            diesel::delete(comments::table.filter(comments::comment_deleted_at.lt(cutoff)))
+                .execute(&mut conn)
+                .await?;
+        let posts = diesel::delete(posts::table.filter(posts::post_deleted_at.lt(cutoff)))
+            .execute(&mut conn)
+            .await?;
+        let purged_photographs: Vec<PhotographObjects> = diesel::delete(
+            photographs::table.filter(photographs::photograph_deleted_at.lt(cutoff)),
+        )
+        .returning(PhotographObjects::as_returning())
+        .get_results(&mut conn)
+        .await?;
+        drop(conn);
+
+        let photographs = purged_photographs.len();
+        let key_from_url = |url: &str| {
+            if url.trim().is_empty() {
+                None
+            } else {
+                self.storage.key_from_url(url)
+            }
+        };
+        let mut keys: Vec<String> = purged_photographs
+            .into_iter()
+            .flat_map(|objects| objects.storage_keys(key_from_url))
+            .collect();
+        // The medium rendition shares the thumbnail key.
+        keys.sort();
+        keys.dedup();
+
+        let objects = if keys.is_empty() {
+            0
+        } else {
+            self.storage.delete_many(&keys).await
+        };
+        self.invalidate_cdn_keys(
+            keys.into_iter()
+                .filter(|key| !key.starts_with("private/"))
+                .collect(),
+        );
+
+        Ok(SoftDeletePurge {
+            posts,
+            comments,
+            photographs,
+            objects,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+//! `ServerState` accessors for live upload progress channels
+//! (see `domain::upload_progress`).
+//!
+//! Same privacy rule as the batch tracker: an id owned by another user looks
+//! absent, both to subscribers and to uploads trying to attach.
+
+use axum::http::{HeaderMap, header};
+use chrono::{DateTime, Utc};
+use scc::hash_map::Entry;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::domain::upload_progress::{
+    FINISHED_UPLOAD_PROGRESS_TTL_SECONDS, IDLE_UPLOAD_PROGRESS_TTL_SECONDS, UPLOAD_ID_HEADER,
+    UploadKind, UploadProgress, UploadProgressEntry, UploadProgressReporter, UploadStage,
+};
+
+use super::ServerState;
+
+impl ServerState {
+    /// Subscribe to `upload_id`, registering a `waiting` entry owned by
+    /// `requester` when the upload has not started yet.
+    pub async fn subscribe_upload_progress(
+        &self,
+        upload_id: Uuid,
+        requester: Uuid,
+    ) -> Option<watch::Receiver<UploadProgress>> {
+        match self.upload_progress.entry_async(upload_id).await {
+            Entry::Occupied(occ) => {
+                (occ.get().owner == requester).then(|| occ.get().sender.subscribe())
+            }
+            Entry::Vacant(vac) => {
+                let (sender, receiver) = watch::channel(UploadProgress::waiting(upload_id));
+                vac.insert_entry(UploadProgressEntry {
+                    owner: requester,
+                    sender,
+                });
+                Some(receiver)
+            }
+        }
+    }
+
+    /// Attach an upload to `upload_id` and return its reporter. `None` (the
+    /// upload simply goes untracked) when the id belongs to another user or is
+    /// already attached to an unfinished upload.
+    pub async fn start_upload_progress(
+        &self,
+        upload_id: Uuid,
+        owner: Uuid,
+        kind: UploadKind,
+        bytes_total: Option<u64>,
+    ) -> Option<UploadProgressReporter> {
+        let started = UploadProgress {
+            kind: Some(kind),
+            stage: UploadStage::Receiving,
+            bytes_total,
+            ..UploadProgress::waiting(upload_id)
+        };
+
+        match self.upload_progress.entry_async(upload_id).await {
+            Entry::Occupied(occ) => {
+                let entry = occ.get();
+                let stage = entry.sender.borrow().stage;
+                if entry.owner != owner || !(stage == UploadStage::Waiting || stage.is_terminal()) {
+                    return None;
+                }
+                entry.sender.send_replace(started);
+                Some(UploadProgressReporter::new(entry.sender.clone()))
+            }
+            Entry::Vacant(vac) => {
+                let (sender, _) = watch::channel(started);
+                let reporter = UploadProgressReporter::new(sender.clone());
+                vac.insert_entry(UploadProgressEntry { owner, sender });
+                Some(reporter)
+            }
+        }
+    }
+
+    /// [`Self::start_upload_progress`] for an upload request: `None` unless it
+    /// carries a valid `X-Upload-Id`. `Content-Length` becomes `bytes_total`.
+    pub async fn track_upload_progress(
+        &self,
+        headers: &HeaderMap,
+        owner: Uuid,
+        kind: UploadKind,
+    ) -> Option<UploadProgressReporter> {
+        let upload_id = headers
+            .get(UPLOAD_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| Uuid::parse_str(v.trim()).ok())?;
+        let bytes_total = headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        self.start_upload_progress(upload_id, owner, kind, bytes_total)
+            .await
+    }
+
+    /// Drop finished entries past their grace period and idle ones (never
+    /// attached, or an upload that stopped reporting). Returns how many were
+    /// dropped.
+    pub async fn prune_upload_progress(&self, now: DateTime<Utc>) -> usize {
+        let mut pruned = 0usize;
+        self.upload_progress
+            .retain_async(|_, entry| {
+                let progress = entry.sender.borrow();
+                let idle = (now - progress.updated_at).num_seconds();
+                let expired = if progress.stage.is_terminal() {
+                    idle > FINISHED_UPLOAD_PROGRESS_TTL_SECONDS
+                } else {
+                    idle > IDLE_UPLOAD_PROGRESS_TTL_SECONDS
+                };
+                if expired {
+                    pruned += 1;
+                }
+                !expired
+            })
+            .await;
+        pruned
+    }
+}
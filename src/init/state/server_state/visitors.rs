@@ -1,6 +1,7 @@
 use std::collections::HashMap as StdHashMap;
 use std::net::IpAddr;
 
+use chrono::{DateTime, Utc};
 use diesel::QueryDsl;
 use diesel_async::RunQueryDsl;
 use scc::hash_map::Entry;
@@ -8,8 +9,13 @@ use tracing::{info, warn};
 
 use super::{ServerState, VisitorLogBatch, VisitorLogKey};
 use crate::domain::geo::visitation_data::NewVisitationData;
+use crate::init::state::cache_versions::CachedResource;
+use crate::util::db::keyset::{Keyset, KeysetOrder, KeysetPaginate};
 use crate::util::time::now::tokio_now;
 
+/// Rows per query when rebuilding the visitor board.
+const VISITOR_SYNC_BATCH: i64 = 10_000;
+
 impl ServerState {
     pub async fn sync_visitor_board_data(&self) -> anyhow::Result<usize> {
         use crate::schema::visitation_data::dsl as vdsl;
@@ -17,25 +23,50 @@ impl ServerState {
         let start = tokio_now();
         let mut conn = self.get_conn().await?;
 
-        let visits: Vec<(f64, f64)> = vdsl::visitation_data
-            .select((vdsl::latitude, vdsl::longitude))
-            .load::<(f64, f64)>(&mut conn)
-            .await?;
-
         let mut visit_counts = std::collections::HashMap::<([u8; 8], [u8; 8]), u64>::new();
+        let mut num_rows = 0;
+
+        // Walk the table in keyset batches instead of loading it in one go.
+        let mut after: Option<Keyset<i64>> = None;
+        loop {
+            let batch: Vec<(DateTime<Utc>, i64, f64, f64)> = vdsl::visitation_data
+                .select((
+                    vdsl::visited_at,
+                    vdsl::visitation_data_id,
+                    vdsl::latitude,
+                    vdsl::longitude,
+                ))
+                .into_boxed()
+                .keyset_page(
+                    (vdsl::visited_at, vdsl::visitation_data_id),
+                    after.as_ref(),
+                    KeysetOrder::OldestFirst,
+                    VISITOR_SYNC_BATCH,
+                )
+                .load(&mut conn)
+                .await?;
+
+            for &(_, _, latitude, longitude) in &batch {
+                let lat_bytes = latitude.to_be_bytes();
+                let long_bytes = longitude.to_be_bytes();
+                let key = (lat_bytes, long_bytes);
+                *visit_counts.entry(key).or_insert(0) += 1;
+            }
+            num_rows += batch.len();
 
-        for (latitude, longitude) in visits.iter().copied() {
-            let lat_bytes = latitude.to_be_bytes();
-            let long_bytes = longitude.to_be_bytes();
-            let key = (lat_bytes, long_bytes);
-            *visit_counts.entry(key).or_insert(0) += 1;
+            match batch.last() {
+                Some(&(visited_at, id, _, _)) if batch.len() as i64 == VISITOR_SYNC_BATCH => {
+                    after = Some(Keyset::new(visited_at, id));
+                }
+                _ => break,
+            }
         }
+        drop(conn);
 
         for (key, count) in visit_counts {
             let _ = self.visitor_board_map.insert_async(key, count).await;
         }
-
-        let num_rows = visits.len();
+        self.cache_versions.bump(CachedResource::VisitorBoard);
 
         info!(elapsed = ?start.elapsed(), rows_synchronized = %num_rows, "Synchronized visitor board data.");
         Ok(num_rows)
@@ -74,6 +105,7 @@ impl ServerState {
                 vac.insert_entry(1);
             }
         }
+        self.cache_versions.bump(CachedResource::VisitorBoard);
 
         let key = VisitorLogKey {
             latitude_bytes,
@@ -1,34 +1,52 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
+use axum::body::Bytes;
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::RunQueryDsl;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use super::ServerState;
-use crate::schema::wasm_module;
+use crate::domain::wasm_module::bundle_storage::{
+    StoredWasmBundle, WASM_BUNDLE_BR_OBJECT_CONTENT_TYPE, WASM_BUNDLE_OBJECT_CONTENT_TYPE,
+    WasmBundleStorage, bundle_br_key, bundle_key, bundle_sha256,
+};
+use crate::domain::wasm_module::wasm_module::{WasmModuleAccess, WasmModuleVisibility};
+use crate::schema::{wasm_module, wasm_module_allowed_users};
 use crate::util::time::now::tokio_now;
-use crate::util::wasm_bundle::sniff_content_type_from_gzip_bytes;
+use crate::util::wasm_bundle::{
+    BrotliWasmBundle, CachedWasmBundle, bundle_etag, sniff_content_type_from_gzip_bytes,
+};
+use crate::util::wasm_bundle_cache::WasmBundleSource;
 
 impl ServerState {
     pub async fn sync_wasm_module_cache(&self) -> anyhow::Result<usize> {
         let start = tokio_now();
+        self.wasm_module_cache.reset_disk().await;
         let mut conn = self.get_conn().await?;
 
-        let rows: Vec<(Uuid, Vec<u8>)> = wasm_module::table
+        // Bundles in object storage are fetched on first serve instead.
+        type CachedRow = (Uuid, Option<Vec<u8>>, Option<Vec<u8>>, Option<String>);
+        let rows: Vec<CachedRow> = wasm_module::table
             .select((
                 wasm_module::wasm_module_id,
                 wasm_module::wasm_module_bundle_gz,
+                wasm_module::wasm_module_bundle_br,
+                wasm_module::wasm_module_bundle_integrity,
             ))
+            .filter(wasm_module::wasm_module_bundle_gz.is_not_null())
             .load(&mut conn)
             .await?;
 
         drop(conn);
 
         let mut cached = 0usize;
-        for (wasm_module_id, gz_bytes) in rows {
+        for (wasm_module_id, gz_bytes, br_bytes, integrity) in rows {
+            let Some(gz_bytes) = gz_bytes else {
+                continue;
+            };
             if self
-                .cache_wasm_module_from_gzip(wasm_module_id, gz_bytes)
+                .cache_wasm_module_from_gzip(wasm_module_id, gz_bytes, br_bytes, integrity)
                 .await
                 .is_some()
             {
@@ -36,6 +54,8 @@ impl ServerState {
             }
         }
 
+        self.sync_wasm_module_access().await?;
+
         info!(
             elapsed = ?start.elapsed(),
             rows_synchronized = %cached,
@@ -45,32 +65,153 @@ impl ServerState {
         Ok(cached)
     }
 
+    /// Reload every module's visibility and allowed users.
+    async fn sync_wasm_module_access(&self) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        let visibilities: Vec<(Uuid, WasmModuleVisibility)> = wasm_module::table
+            .select((
+                wasm_module::wasm_module_id,
+                wasm_module::wasm_module_visibility,
+            ))
+            .load(&mut conn)
+            .await?;
+        let allowed: Vec<(Uuid, Uuid)> = wasm_module_allowed_users::table
+            .select((
+                wasm_module_allowed_users::wasm_module_id,
+                wasm_module_allowed_users::user_id,
+            ))
+            .load(&mut conn)
+            .await?;
+        drop(conn);
+
+        let mut access: HashMap<Uuid, WasmModuleAccess> = visibilities
+            .into_iter()
+            .map(|(id, visibility)| {
+                (
+                    id,
+                    WasmModuleAccess {
+                        visibility,
+                        allowed_user_ids: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+        for (wasm_module_id, user_id) in allowed {
+            if let Some(entry) = access.get_mut(&wasm_module_id) {
+                entry.allowed_user_ids.push(user_id);
+            }
+        }
+
+        self.wasm_module_access
+            .retain_async(|id, _| access.contains_key(id))
+            .await;
+        for (id, entry) in access {
+            let _ = self.wasm_module_access.upsert_async(id, entry).await;
+        }
+        Ok(())
+    }
+
+    /// Access rule of a module, from the cache or the DB. `None` when the
+    /// module does not exist (or cannot be read).
+    pub async fn get_wasm_module_access(&self, wasm_module_id: Uuid) -> Option<WasmModuleAccess> {
+        if let Some(access) = self
+            .wasm_module_access
+            .read_async(&wasm_module_id, |_, v| v.clone())
+            .await
+        {
+            return Some(access);
+        }
+        match self.refresh_wasm_module_access(wasm_module_id).await {
+            Ok(access) => access,
+            Err(e) => {
+                error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to load WASM module access");
+                None
+            }
+        }
+    }
+
+    pub async fn set_wasm_module_access(&self, wasm_module_id: Uuid, access: WasmModuleAccess) {
+        let _ = self
+            .wasm_module_access
+            .upsert_async(wasm_module_id, access)
+            .await;
+    }
+
+    /// Re-read one module's access rule after a write and cache it.
+    pub async fn refresh_wasm_module_access(
+        &self,
+        wasm_module_id: Uuid,
+    ) -> anyhow::Result<Option<WasmModuleAccess>> {
+        let mut conn = self.get_conn().await?;
+        let visibility: Option<WasmModuleVisibility> = wasm_module::table
+            .select(wasm_module::wasm_module_visibility)
+            .filter(wasm_module::wasm_module_id.eq(wasm_module_id))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        let Some(visibility) = visibility else {
+            drop(conn);
+            let _ = self.wasm_module_access.remove_async(&wasm_module_id).await;
+            return Ok(None);
+        };
+        let allowed_user_ids: Vec<Uuid> = wasm_module_allowed_users::table
+            .select(wasm_module_allowed_users::user_id)
+            .filter(wasm_module_allowed_users::wasm_module_id.eq(wasm_module_id))
+            .load(&mut conn)
+            .await?;
+        drop(conn);
+
+        let access = WasmModuleAccess {
+            visibility,
+            allowed_user_ids,
+        };
+        self.set_wasm_module_access(wasm_module_id, access.clone())
+            .await;
+        Ok(Some(access))
+    }
+
     pub async fn upsert_wasm_module_cache(
         &self,
         wasm_module_id: Uuid,
         gz_bytes: Vec<u8>,
+        br_bytes: Option<Vec<u8>>,
         content_type: &'static str,
+        integrity: Option<String>,
     ) {
-        let bytes: Arc<[u8]> = Arc::from(gz_bytes.into_boxed_slice());
-        let entry = (bytes, true, content_type);
-        let _ = self
-            .wasm_module_cache
-            .insert_async(wasm_module_id, entry)
-            .await;
+        let entry = match tokio::task::spawn_blocking(move || {
+            cached_bundle(gz_bytes, br_bytes, content_type, integrity)
+        })
+        .await
+        {
+            Ok(entry) => entry,
+            Err(e) => {
+                error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to join WASM bundle hash task");
+                self.invalidate_wasm_module_bundle(wasm_module_id).await;
+                return;
+            }
+        };
+        self.wasm_module_cache.insert(wasm_module_id, entry).await;
     }
 
     async fn cache_wasm_module_from_gzip(
         &self,
         wasm_module_id: Uuid,
         gz_bytes: Vec<u8>,
-    ) -> Option<(Arc<[u8]>, bool, &'static str)> {
+        br_bytes: Option<Vec<u8>>,
+        integrity: Option<String>,
+    ) -> Option<CachedWasmBundle> {
         let sniff_result = tokio::task::spawn_blocking(move || {
             let content_type = sniff_content_type_from_gzip_bytes(&gz_bytes)?;
-            Ok::<(&'static str, Vec<u8>), anyhow::Error>((content_type, gz_bytes))
+            Ok::<CachedWasmBundle, anyhow::Error>(cached_bundle(
+                gz_bytes,
+                br_bytes,
+                content_type,
+                integrity,
+            ))
         })
         .await;
 
-        let (content_type, gz_bytes) = match sniff_result {
+        let entry = match sniff_result {
             Ok(Ok(result)) => result,
             Ok(Err(e)) => {
                 error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to sniff WASM bundle content type");
@@ -82,35 +223,27 @@ impl ServerState {
             }
         };
 
-        let bytes: Arc<[u8]> = Arc::from(gz_bytes.into_boxed_slice());
-        let entry = (bytes.clone(), true, content_type);
-
-        let _ = self
-            .wasm_module_cache
-            .insert_async(wasm_module_id, entry.clone())
+        self.wasm_module_cache
+            .insert(wasm_module_id, entry.clone())
             .await;
 
         info!(
             wasm_module_id = %wasm_module_id,
-            size_bytes = bytes.len(),
+            size_bytes = entry.bytes.len(),
             is_gzipped = true,
-            content_type = content_type,
+            has_brotli = entry.brotli.is_some(),
+            content_type = entry.content_type,
             "Loaded WASM module bundle into cache"
         );
 
         Some(entry)
     }
 
-    pub async fn get_wasm_module(
-        &self,
-        wasm_module_id: Uuid,
-    ) -> Option<(Arc<[u8]>, bool, &'static str)> {
-        if let Some(entry) = self
-            .wasm_module_cache
-            .read_async(&wasm_module_id, |_, v| v.clone())
-            .await
-        {
-            return Some(entry);
+    /// Cached bundle (memory or disk tier), else the DB row; a DB hit is
+    /// served from the bytes just read, whichever tier it lands in.
+    pub async fn get_wasm_module(&self, wasm_module_id: Uuid) -> Option<WasmBundleSource> {
+        if let Some(source) = self.wasm_module_cache.get(wasm_module_id).await {
+            return Some(source);
         }
 
         let mut conn = match self.get_conn().await {
@@ -121,8 +254,21 @@ impl ServerState {
             }
         };
 
-        let row: Option<(Uuid, Vec<u8>)> = wasm_module::table
-            .select((wasm_module::wasm_module_id, wasm_module::wasm_module_bundle_gz))
+        type BundleRow = (
+            Option<Vec<u8>>,
+            Option<String>,
+            Option<Vec<u8>>,
+            Option<String>,
+            Option<String>,
+        );
+        let row: Option<BundleRow> = wasm_module::table
+            .select((
+                wasm_module::wasm_module_bundle_gz,
+                wasm_module::wasm_module_bundle_key,
+                wasm_module::wasm_module_bundle_br,
+                wasm_module::wasm_module_bundle_br_key,
+                wasm_module::wasm_module_bundle_integrity,
+            ))
             .filter(wasm_module::wasm_module_id.eq(wasm_module_id))
             .first(&mut conn)
             .await
@@ -135,15 +281,133 @@ impl ServerState {
 
         drop(conn);
 
-        let (_, gz_bytes) = row?;
+        let (gz_bytes, bundle_key, br_bytes, br_key, integrity) = row?;
+        let gz_bytes = match (gz_bytes, bundle_key) {
+            (Some(gz_bytes), _) => gz_bytes,
+            (None, Some(key)) => match self.storage.get(&key).await {
+                Ok(Some(object)) => object.bytes,
+                Ok(None) => {
+                    error!(wasm_module_id = %wasm_module_id, key = %key, "WASM bundle object is missing");
+                    return None;
+                }
+                Err(e) => {
+                    error!(error = ?e, wasm_module_id = %wasm_module_id, key = %key, "Failed to fetch WASM bundle from storage");
+                    return None;
+                }
+            },
+            (None, None) => return None,
+        };
+        // A missing Brotli copy only costs transfer size; serve gzip.
+        let br_bytes = match (br_bytes, br_key) {
+            (Some(br_bytes), _) => Some(br_bytes),
+            (None, Some(key)) => match self.storage.get(&key).await {
+                Ok(Some(object)) => Some(object.bytes),
+                Ok(None) => {
+                    warn!(wasm_module_id = %wasm_module_id, key = %key, "Brotli WASM bundle object is missing");
+                    None
+                }
+                Err(e) => {
+                    warn!(error = ?e, wasm_module_id = %wasm_module_id, key = %key, "Failed to fetch Brotli WASM bundle from storage");
+                    None
+                }
+            },
+            (None, None) => None,
+        };
         let entry = self
-            .cache_wasm_module_from_gzip(wasm_module_id, gz_bytes)
+            .cache_wasm_module_from_gzip(wasm_module_id, gz_bytes, br_bytes, integrity)
             .await?;
 
-        Some(entry)
+        Some(WasmBundleSource::Memory(entry))
+    }
+
+    /// Hash `gz_bytes` and, in object-storage mode, upload it and its Brotli
+    /// copy. Returns the column values to write; the caller owns the row
+    /// update.
+    pub async fn persist_wasm_bundle(
+        &self,
+        wasm_module_id: Uuid,
+        gz_bytes: Vec<u8>,
+        br_bytes: Vec<u8>,
+    ) -> anyhow::Result<StoredWasmBundle> {
+        let (sha256, gz_bytes) = tokio::task::spawn_blocking(move || {
+            let sha256 = bundle_sha256(&gz_bytes);
+            (sha256, gz_bytes)
+        })
+        .await?;
+        let size_bytes = gz_bytes.len() as i64;
+
+        match self.wasm_bundle_storage {
+            WasmBundleStorage::Database => Ok(StoredWasmBundle {
+                bundle_gz: Some(gz_bytes),
+                bundle_key: None,
+                bundle_br: Some(br_bytes),
+                bundle_br_key: None,
+                sha256,
+                size_bytes,
+            }),
+            WasmBundleStorage::ObjectStorage => {
+                let key = bundle_key(wasm_module_id, &sha256);
+                self.storage
+                    .put(&key, gz_bytes, WASM_BUNDLE_OBJECT_CONTENT_TYPE)
+                    .await?;
+                let br_key = bundle_br_key(wasm_module_id, &sha256);
+                if let Err(e) = self
+                    .storage
+                    .put(&br_key, br_bytes, WASM_BUNDLE_BR_OBJECT_CONTENT_TYPE)
+                    .await
+                {
+                    self.delete_wasm_bundle_object(&key).await;
+                    return Err(e);
+                }
+                Ok(StoredWasmBundle {
+                    bundle_gz: None,
+                    bundle_key: Some(key),
+                    bundle_br: None,
+                    bundle_br_key: Some(br_key),
+                    sha256,
+                    size_bytes,
+                })
+            }
+        }
+    }
+
+    /// Best-effort removal of a bundle object no row points at any more; the
+    /// orphan reconciliation job catches whatever this misses.
+    pub async fn delete_wasm_bundle_object(&self, key: &str) {
+        if let Err(e) = self.storage.delete(key).await {
+            warn!(error = ?e, key = %key, "Failed to delete WASM bundle object");
+        }
     }
 
     pub async fn invalidate_wasm_module(&self, wasm_module_id: Uuid) {
-        let _ = self.wasm_module_cache.remove_async(&wasm_module_id).await;
+        self.invalidate_wasm_module_bundle(wasm_module_id).await;
+        let _ = self.wasm_module_access.remove_async(&wasm_module_id).await;
+    }
+
+    /// Drop only the cached bytes; the next load re-reads the row.
+    async fn invalidate_wasm_module_bundle(&self, wasm_module_id: Uuid) {
+        self.wasm_module_cache.remove(wasm_module_id).await;
+    }
+}
+
+/// Hash the bundle bytes for the ETags; run on a blocking thread for big
+/// bundles.
+fn cached_bundle(
+    gz_bytes: Vec<u8>,
+    br_bytes: Option<Vec<u8>>,
+    content_type: &'static str,
+    integrity: Option<String>,
+) -> CachedWasmBundle {
+    let etag = bundle_etag(&gz_bytes);
+    CachedWasmBundle {
+        bytes: Bytes::from(gz_bytes),
+        is_gzipped: true,
+        content_type,
+        etag: Arc::from(etag),
+        brotli: br_bytes.map(|br_bytes| BrotliWasmBundle {
+            etag: Arc::from(bundle_etag(&br_bytes)),
+            bytes: Bytes::from(br_bytes),
+        }),
+        integrity: integrity.map(Arc::from),
     }
 }
@@ -0,0 +1,133 @@
+//! `ServerState` accessors for the files of multi-file WASM modules
+//! (`domain::wasm_module::files`).
+
+use std::collections::HashSet;
+
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::wasm_module::bundle_storage::bundle_sha256;
+use crate::domain::wasm_module::files::{
+    WasmModuleFile, WasmModuleFileInsertable, wasm_module_file_key,
+};
+use crate::schema::wasm_module_files;
+use crate::util::wasm_archive::ArchiveFile;
+
+impl ServerState {
+    /// Upload every distinct file of an unpacked archive and return the rows
+    /// to insert. Objects already written are deleted again on failure.
+    pub async fn store_wasm_module_files(
+        &self,
+        wasm_module_id: Uuid,
+        files: Vec<ArchiveFile>,
+    ) -> anyhow::Result<Vec<WasmModuleFileInsertable>> {
+        let hashed: Vec<(ArchiveFile, String)> = tokio::task::spawn_blocking(move || {
+            files
+                .into_iter()
+                .map(|file| {
+                    let sha256 = bundle_sha256(&file.bytes);
+                    (file, sha256)
+                })
+                .collect()
+        })
+        .await?;
+
+        let mut rows = Vec::with_capacity(hashed.len());
+        let mut written: HashSet<String> = HashSet::new();
+        for (file, sha256) in hashed {
+            let key = wasm_module_file_key(wasm_module_id, &sha256);
+            let size_bytes = file.bytes.len() as i64;
+            if !written.contains(&key) {
+                if let Err(e) = self.storage.put(&key, file.bytes, &file.content_type).await {
+                    for key in &written {
+                        self.delete_wasm_bundle_object(key).await;
+                    }
+                    return Err(e);
+                }
+                written.insert(key.clone());
+            }
+            rows.push(WasmModuleFileInsertable {
+                wasm_module_id,
+                wasm_module_file_path: file.path,
+                wasm_module_file_key: key,
+                wasm_module_file_content_type: file.content_type,
+                wasm_module_file_size_bytes: size_bytes,
+                wasm_module_file_sha256: sha256,
+            });
+        }
+        Ok(rows)
+    }
+
+    /// Swap a module's file rows for `rows` (none turns it back into a
+    /// single-bundle module), then delete objects nothing points at any more.
+    pub async fn replace_wasm_module_files(
+        &self,
+        wasm_module_id: Uuid,
+        rows: Vec<WasmModuleFileInsertable>,
+    ) -> anyhow::Result<()> {
+        let new_keys: HashSet<String> = rows
+            .iter()
+            .map(|r| r.wasm_module_file_key.clone())
+            .collect();
+
+        let mut conn = self.get_conn().await?;
+        let old_keys: Vec<String> = conn
+            .transaction::<_, diesel::result::Error, _>(async |conn| {
+                let old_keys: Vec<String> = diesel::delete(
+                    wasm_module_files::table
+                        .filter(wasm_module_files::wasm_module_id.eq(wasm_module_id)),
+                )
+                .returning(wasm_module_files::wasm_module_file_key)
+                .get_results(&mut *conn)
+                .await?;
+                if !rows.is_empty() {
+                    diesel::insert_into(wasm_module_files::table)
+                        .values(&rows)
+                        .execute(&mut *conn)
+                        .await?;
+                }
+                Ok(old_keys)
+            })
+            .await?;
+        drop(conn);
+
+        let stale: HashSet<String> = old_keys
+            .into_iter()
+            .filter(|k| !new_keys.contains(k))
+            .collect();
+        for key in &stale {
+            self.delete_wasm_bundle_object(key).await;
+        }
+        Ok(())
+    }
+
+    /// Object keys of a module's files, e.g. to clean up after deleting it.
+    pub async fn wasm_module_file_keys(&self, wasm_module_id: Uuid) -> anyhow::Result<Vec<String>> {
+        let mut conn = self.get_conn().await?;
+        let keys: Vec<String> = wasm_module_files::table
+            .select(wasm_module_files::wasm_module_file_key)
+            .filter(wasm_module_files::wasm_module_id.eq(wasm_module_id))
+            .distinct()
+            .load(&mut conn)
+            .await?;
+        Ok(keys)
+    }
+
+    pub async fn get_wasm_module_file(
+        &self,
+        wasm_module_id: Uuid,
+        path: &str,
+    ) -> anyhow::Result<Option<WasmModuleFile>> {
+        let mut conn = self.get_conn().await?;
+        let file = wasm_module_files::table
+            .select(WasmModuleFile::as_select())
+            .filter(wasm_module_files::wasm_module_id.eq(wasm_module_id))
+            .filter(wasm_module_files::wasm_module_file_path.eq(path))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(file)
+    }
+}
@@ -0,0 +1,158 @@
+//! Buffered WASM bundle load analytics. `serve_wasm` bumps an in-memory
+//! counter per (module, day, country); `FLUSH_WASM_MODULE_LOADS` upserts the
+//! buffer into `wasm_module_loads` every minute.
+
+use std::collections::HashMap as StdHashMap;
+use std::net::IpAddr;
+
+use chrono::Utc;
+use diesel::upsert::excluded;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use scc::hash_map::Entry;
+use tracing::info;
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::wasm_module::loads::{
+    UNKNOWN_LOAD_COUNTRY, WasmModuleLoadBatch, WasmModuleLoadInsertable, WasmModuleLoadKey,
+};
+use crate::schema::{wasm_module, wasm_module_loads};
+
+impl ServerState {
+    pub async fn record_wasm_module_load(&self, wasm_module_id: Uuid, ip: Option<IpAddr>) {
+        let country_code = ip
+            .and_then(|ip| self.lookup_ip_location(ip))
+            .map(|info| info.country_code)
+            .filter(|code| code.len() == 2)
+            .map(|code| code.to_ascii_uppercase())
+            .unwrap_or_else(|| UNKNOWN_LOAD_COUNTRY.to_string());
+
+        let now = Utc::now();
+        let key = WasmModuleLoadKey {
+            wasm_module_id,
+            load_date: now.date_naive(),
+            country_code,
+        };
+        let batch = WasmModuleLoadBatch {
+            count: 1,
+            last_loaded_at: now,
+        };
+        self.merge_wasm_module_load(key, batch).await;
+    }
+
+    async fn merge_wasm_module_load(&self, key: WasmModuleLoadKey, batch: WasmModuleLoadBatch) {
+        match self.wasm_module_load_buffer.entry_async(key).await {
+            Entry::Occupied(mut occ) => {
+                let existing = occ.get_mut();
+                existing.count = existing.count.saturating_add(batch.count);
+                if batch.last_loaded_at > existing.last_loaded_at {
+                    existing.last_loaded_at = batch.last_loaded_at;
+                }
+            }
+            Entry::Vacant(vac) => {
+                vac.insert_entry(batch);
+            }
+        }
+    }
+
+    /// Upsert buffered loads; on DB failure they are merged back for the next
+    /// run. Returns the number of loads written.
+    pub async fn flush_wasm_module_loads(&self) -> anyhow::Result<u64> {
+        let mut pending: StdHashMap<WasmModuleLoadKey, WasmModuleLoadBatch> = StdHashMap::new();
+        self.wasm_module_load_buffer
+            .retain_async(|key, batch| {
+                pending.insert(key.clone(), batch.clone());
+                false
+            })
+            .await;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let rows: Vec<WasmModuleLoadInsertable> = pending
+            .iter()
+            .map(|(key, batch)| WasmModuleLoadInsertable {
+                wasm_module_id: key.wasm_module_id,
+                wasm_module_load_date: key.load_date,
+                wasm_module_load_country_code: key.country_code.clone(),
+                wasm_module_load_count: i64::try_from(batch.count).unwrap_or(i64::MAX),
+                wasm_module_last_loaded_at: batch.last_loaded_at,
+            })
+            .collect();
+        let total: u64 = pending.values().map(|b| b.count).sum();
+
+        let mut conn = match self.get_conn().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                self.requeue_wasm_module_loads(pending).await;
+                return Err(e);
+            }
+        };
+
+        // Loads of a module deleted since they were buffered would fail the
+        // FK for the whole batch; drop them instead.
+        let ids: Vec<Uuid> = rows.iter().map(|r| r.wasm_module_id).collect();
+        let existing: Vec<Uuid> = match wasm_module::table
+            .select(wasm_module::wasm_module_id)
+            .filter(wasm_module::wasm_module_id.eq_any(ids))
+            .load(&mut conn)
+            .await
+        {
+            Ok(existing) => existing,
+            Err(e) => {
+                self.requeue_wasm_module_loads(pending).await;
+                return Err(e.into());
+            }
+        };
+        let rows: Vec<WasmModuleLoadInsertable> = rows
+            .into_iter()
+            .filter(|r| existing.contains(&r.wasm_module_id))
+            .collect();
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let result = diesel::insert_into(wasm_module_loads::table)
+            .values(&rows)
+            .on_conflict((
+                wasm_module_loads::wasm_module_id,
+                wasm_module_loads::wasm_module_load_date,
+                wasm_module_loads::wasm_module_load_country_code,
+            ))
+            .do_update()
+            .set((
+                wasm_module_loads::wasm_module_load_count
+                    .eq(wasm_module_loads::wasm_module_load_count
+                        + excluded(wasm_module_loads::wasm_module_load_count)),
+                wasm_module_loads::wasm_module_last_loaded_at
+                    .eq(excluded(wasm_module_loads::wasm_module_last_loaded_at)),
+            ))
+            .execute(&mut conn)
+            .await;
+
+        match result {
+            Ok(rows_flushed) => {
+                info!(
+                    rows_flushed,
+                    load_count = total,
+                    "Flushed buffered WASM module loads"
+                );
+                Ok(total)
+            }
+            Err(e) => {
+                self.requeue_wasm_module_loads(pending).await;
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn requeue_wasm_module_loads(
+        &self,
+        pending: StdHashMap<WasmModuleLoadKey, WasmModuleLoadBatch>,
+    ) {
+        for (key, batch) in pending {
+            self.merge_wasm_module_load(key, batch).await;
+        }
+    }
+}
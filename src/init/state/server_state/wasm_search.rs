@@ -0,0 +1,83 @@
+//! `ServerState` accessors for the WASM module search index
+//! (`init::search::WasmModuleSearchIndex`). Index failures are logged and
+//! never fail the write that triggered them; the next startup rebuilds.
+
+use diesel::QueryDsl;
+use diesel_async::RunQueryDsl;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::init::search::WasmModuleSearchDoc;
+use crate::schema::wasm_module;
+use crate::util::time::now::tokio_now;
+
+type SearchRow = (Uuid, String, String, Option<String>, Vec<String>);
+
+impl ServerState {
+    pub async fn sync_wasm_module_search_index(&self) -> anyhow::Result<usize> {
+        let start = tokio_now();
+        let mut conn = self.get_conn().await?;
+        let rows: Vec<SearchRow> = wasm_module::table
+            .select((
+                wasm_module::wasm_module_id,
+                wasm_module::wasm_module_title,
+                wasm_module::wasm_module_description,
+                wasm_module::wasm_module_category,
+                wasm_module::wasm_module_tags,
+            ))
+            .load(&mut conn)
+            .await?;
+        drop(conn);
+
+        let indexed = self.wasm_module_search_index.rebuild(rows.iter().map(
+            |(id, title, description, category, tags)| WasmModuleSearchDoc {
+                wasm_module_id: *id,
+                title,
+                description,
+                category: category.as_deref(),
+                tags,
+            },
+        ))?;
+
+        info!(
+            elapsed = ?start.elapsed(),
+            modules_indexed = indexed,
+            "Synchronized WASM module search index."
+        );
+        Ok(indexed)
+    }
+
+    /// Index the current state of one module after a write.
+    pub fn index_wasm_module(&self, doc: &WasmModuleSearchDoc<'_>) {
+        if let Err(e) = self.wasm_module_search_index.upsert(doc) {
+            error!(error = ?e, wasm_module_id = %doc.wasm_module_id, "Failed to index WASM module");
+        }
+    }
+
+    /// Ranked module ids; a failed search logs and yields no matches.
+    pub fn search_wasm_modules(
+        &self,
+        query: &str,
+        category: Option<&str>,
+        tags: &[String],
+        limit: usize,
+    ) -> Vec<Uuid> {
+        match self
+            .wasm_module_search_index
+            .search(query, category, tags, limit)
+        {
+            Ok(ids) => ids,
+            Err(e) => {
+                error!(error = ?e, query = %query, "WASM module search failed");
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn unindex_wasm_module(&self, wasm_module_id: Uuid) {
+        if let Err(e) = self.wasm_module_search_index.remove(wasm_module_id) {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to remove WASM module from search index");
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Persistence for outgoing webhooks (`webhooks`) and their delivery log
+//! (`webhook_deliveries`).
+
+use chrono::Utc;
+use diesel::{
+    ExpressionMethods, OptionalExtension, PgArrayExpressionMethods, QueryDsl, SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use super::ServerState;
+use crate::domain::webhook::{
+    delivery::{WebhookDelivery, WebhookDeliveryInsertable, WebhookDeliveryStatus},
+    webhook::{Webhook, WebhookChangeset, WebhookEventType, WebhookInsertable},
+};
+use crate::schema::{webhook_deliveries, webhooks};
+
+impl ServerState {
+    pub async fn insert_webhook(&self, webhook: WebhookInsertable) -> anyhow::Result<Webhook> {
+        let mut conn = self.get_conn().await?;
+        let row = diesel::insert_into(webhooks::table)
+            .values(&webhook)
+            .returning(Webhook::as_returning())
+            .get_result(&mut conn)
+            .await?;
+        Ok(row)
+    }
+
+    pub async fn list_webhooks(&self) -> anyhow::Result<Vec<Webhook>> {
+        let mut conn = self.get_conn().await?;
+        let rows = webhooks::table
+            .order(webhooks::webhook_created_at.asc())
+            .select(Webhook::as_select())
+            .load(&mut conn)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Returns the updated row, or `None` when the webhook does not exist.
+    pub async fn update_webhook(
+        &self,
+        webhook_id: Uuid,
+        changeset: WebhookChangeset,
+    ) -> anyhow::Result<Option<Webhook>> {
+        let mut conn = self.get_conn().await?;
+        let row = diesel::update(webhooks::table.find(webhook_id))
+            .set(&changeset)
+            .returning(Webhook::as_returning())
+            .get_result(&mut conn)
+            .await
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Deletes the webhook and its delivery log. Returns whether it existed.
+    pub async fn delete_webhook(&self, webhook_id: Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.get_conn().await?;
+        let deleted = diesel::delete(webhooks::table.find(webhook_id))
+            .execute(&mut conn)
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    pub async fn webhook_exists(&self, webhook_id: Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.get_conn().await?;
+        let exists = diesel::dsl::select(diesel::dsl::exists(webhooks::table.find(webhook_id)))
+            .get_result(&mut conn)
+            .await?;
+        Ok(exists)
+    }
+
+    /// Ids of the enabled webhooks subscribed to `event_type`.
+    pub async fn subscribed_webhook_ids(
+        &self,
+        event_type: WebhookEventType,
+    ) -> anyhow::Result<Vec<Uuid>> {
+        let mut conn = self.get_conn().await?;
+        let ids = webhooks::table
+            .filter(webhooks::webhook_enabled.eq(true))
+            .filter(webhooks::webhook_event_types.contains(vec![event_type.as_str()]))
+            .select(webhooks::webhook_id)
+            .load(&mut conn)
+            .await?;
+        Ok(ids)
+    }
+
+    pub async fn insert_webhook_deliveries(
+        &self,
+        deliveries: Vec<WebhookDeliveryInsertable>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        diesel::insert_into(webhook_deliveries::table)
+            .values(&deliveries)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// A delivery with the webhook it goes to; `None` once the webhook was
+    /// deleted.
+    pub async fn get_webhook_delivery(
+        &self,
+        delivery_id: Uuid,
+    ) -> anyhow::Result<Option<(WebhookDelivery, Webhook)>> {
+        let mut conn = self.get_conn().await?;
+        let row = webhook_deliveries::table
+            .inner_join(webhooks::table)
+            .filter(webhook_deliveries::webhook_delivery_id.eq(delivery_id))
+            .select((WebhookDelivery::as_select(), Webhook::as_select()))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Record one attempt. `status` is `Pending` while the task queue will
+    /// retry, `Failed` once it gives up.
+    pub async fn record_webhook_delivery_attempt(
+        &self,
+        delivery_id: Uuid,
+        status: WebhookDeliveryStatus,
+        response_status: Option<i32>,
+        error: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        let delivered_at = (status == WebhookDeliveryStatus::Succeeded).then(Utc::now);
+        diesel::update(webhook_deliveries::table.find(delivery_id))
+            .set((
+                webhook_deliveries::webhook_delivery_status.eq(status.as_str()),
+                webhook_deliveries::webhook_delivery_attempts
+                    .eq(webhook_deliveries::webhook_delivery_attempts + 1),
+                webhook_deliveries::webhook_delivery_response_status.eq(response_status),
+                webhook_deliveries::webhook_delivery_last_error.eq(error),
+                webhook_deliveries::webhook_delivery_delivered_at.eq(delivered_at),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Newest deliveries to `webhook_id` first, optionally only those with
+    /// `status`.
+    pub async fn list_webhook_deliveries(
+        &self,
+        webhook_id: Uuid,
+        status: Option<WebhookDeliveryStatus>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let mut conn = self.get_conn().await?;
+        let mut query = webhook_deliveries::table
+            .filter(webhook_deliveries::webhook_id.eq(webhook_id))
+            .order(webhook_deliveries::webhook_delivery_created_at.desc())
+            .limit(limit)
+            .select(WebhookDelivery::as_select())
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(webhook_deliveries::webhook_delivery_status.eq(status.as_str()));
+        }
+        Ok(query.load(&mut conn).await?)
+    }
+}
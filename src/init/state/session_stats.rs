@@ -0,0 +1,216 @@
+//! Session lifecycle counters behind `GET /api/admin/sessions/stats`: logins,
+//! logouts and purged expiries per UTC hour for the last day, plus totals
+//! since startup. Per instance; reset on restart.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::dto::responses::admin::session_stats_response::{SessionEventCounts, SessionRateBucket};
+use crate::util::metrics::MetricsWriter;
+
+use super::session::Session;
+
+const SLOT_SECS: i64 = 3600;
+/// One day of hour slots.
+pub const SESSION_STATS_HOURS: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEvent {
+    Created,
+    Ended,
+    Expired,
+}
+
+impl SessionEventCounts {
+    fn add(&mut self, event: SessionEvent, count: u64) {
+        let counter = match event {
+            SessionEvent::Created => &mut self.created,
+            SessionEvent::Ended => &mut self.ended,
+            SessionEvent::Expired => &mut self.expired,
+        };
+        *counter += count;
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Slot {
+    /// Unix hour the slot holds; a stale one is reset on next use.
+    hour: i64,
+    counts: SessionEventCounts,
+}
+
+pub struct SessionCounters {
+    created_total: AtomicU64,
+    ended_total: AtomicU64,
+    expired_total: AtomicU64,
+    slots: Mutex<[Slot; SESSION_STATS_HOURS]>,
+}
+
+impl SessionCounters {
+    pub fn new() -> Self {
+        Self {
+            created_total: AtomicU64::new(0),
+            ended_total: AtomicU64::new(0),
+            expired_total: AtomicU64::new(0),
+            slots: Mutex::new(
+                [Slot {
+                    hour: i64::MIN,
+                    counts: SessionEventCounts::default(),
+                }; SESSION_STATS_HOURS],
+            ),
+        }
+    }
+
+    pub fn record(&self, event: SessionEvent, count: u64) {
+        self.record_at(event, count, Utc::now().timestamp());
+    }
+
+    fn record_at(&self, event: SessionEvent, count: u64, now_secs: i64) {
+        if count == 0 {
+            return;
+        }
+        let total = match event {
+            SessionEvent::Created => &self.created_total,
+            SessionEvent::Ended => &self.ended_total,
+            SessionEvent::Expired => &self.expired_total,
+        };
+        total.fetch_add(count, Ordering::Relaxed);
+
+        let hour = now_secs.div_euclid(SLOT_SECS);
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = &mut slots[hour.rem_euclid(SESSION_STATS_HOURS as i64) as usize];
+        if slot.hour != hour {
+            *slot = Slot {
+                hour,
+                counts: SessionEventCounts::default(),
+            };
+        }
+        slot.counts.add(event, count);
+    }
+
+    pub fn totals(&self) -> SessionEventCounts {
+        SessionEventCounts {
+            created: self.created_total.load(Ordering::Relaxed),
+            ended: self.ended_total.load(Ordering::Relaxed),
+            expired: self.expired_total.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The last `hours` (at most [`SESSION_STATS_HOURS`]) hours, oldest first,
+    /// empty ones included.
+    pub fn hourly(&self, hours: usize) -> Vec<SessionRateBucket> {
+        self.hourly_at(hours, Utc::now().timestamp())
+    }
+
+    fn hourly_at(&self, hours: usize, now_secs: i64) -> Vec<SessionRateBucket> {
+        let current = now_secs.div_euclid(SLOT_SECS);
+        let hours = hours.clamp(1, SESSION_STATS_HOURS) as i64;
+        let slots = *self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        (current - hours + 1..=current)
+            .map(|hour| SessionRateBucket {
+                hour_start: DateTime::from_timestamp(hour * SLOT_SECS, 0).unwrap_or_default(),
+                counts: slots
+                    .iter()
+                    .find(|slot| slot.hour == hour)
+                    .map(|slot| slot.counts)
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    pub fn write_metrics(&self, w: &mut MetricsWriter) {
+        let totals = self.totals();
+        w.header(
+            "session_events_total",
+            "counter",
+            "Sessions created, ended by logout and expired since startup.",
+        );
+        for (event, count) in [
+            ("created", totals.created),
+            ("ended", totals.ended),
+            ("expired", totals.expired),
+        ] {
+            w.sample("session_events_total", &[("event", event)], count as f64);
+        }
+    }
+}
+
+impl Default for SessionCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The session map at one moment, from `ServerState::session_breakdown`.
+#[derive(Debug, Default)]
+pub struct SessionBreakdown {
+    pub active: usize,
+    /// Expired but not purged yet; not counted anywhere else.
+    pub expired: usize,
+    pub users: HashSet<Uuid>,
+    pub by_country: HashMap<i32, usize>,
+    pub total_age_secs: i64,
+    pub oldest_age_secs: i64,
+}
+
+impl SessionBreakdown {
+    pub fn add(&mut self, session: &Session, now: DateTime<Utc>) {
+        if session.expires_at <= now {
+            self.expired += 1;
+            return;
+        }
+        let age_secs = (now - session.created_at).num_seconds().max(0);
+        self.active += 1;
+        self.users.insert(session.user_id);
+        *self.by_country.entry(session.user_country).or_default() += 1;
+        self.total_age_secs += age_secs;
+        self.oldest_age_secs = self.oldest_age_secs.max(age_secs);
+    }
+
+    pub fn average_age_secs(&self) -> f64 {
+        if self.active == 0 {
+            return 0.0;
+        }
+        self.total_age_secs as f64 / self.active as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_by_hour_and_forgets_after_a_day() {
+        let counters = SessionCounters::new();
+        let hour = 500_000 * SLOT_SECS;
+        counters.record_at(SessionEvent::Created, 3, hour + 10);
+        counters.record_at(SessionEvent::Ended, 1, hour + 3599);
+        counters.record_at(SessionEvent::Expired, 2, hour + SLOT_SECS);
+
+        let hourly = counters.hourly_at(3, hour + SLOT_SECS);
+        assert_eq!(hourly.len(), 3);
+        assert_eq!(hourly[0].counts, SessionEventCounts::default());
+        assert_eq!(
+            hourly[1].counts,
+            SessionEventCounts {
+                created: 3,
+                ended: 1,
+                expired: 0,
+            }
+        );
+        assert_eq!(hourly[2].counts.expired, 2);
+        assert_eq!(hourly[2].hour_start.timestamp(), hour + SLOT_SECS);
+
+        // A day later the first slot is reused and the old counts are gone.
+        let next_day = hour + SESSION_STATS_HOURS as i64 * SLOT_SECS;
+        counters.record_at(SessionEvent::Created, 1, next_day);
+        let hourly = counters.hourly_at(SESSION_STATS_HOURS, next_day);
+        let created: u64 = hourly.iter().map(|b| b.counts.created).sum();
+        assert_eq!(created, 1);
+        assert_eq!(counters.totals().created, 4);
+    }
+}
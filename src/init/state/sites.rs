@@ -0,0 +1,129 @@
+//! Host-based sites.
+//!
+//! One binary can serve several domains. Each `[[sites]]` entry maps its
+//! hosts to a frontend under `fe/sites/{frontend}/`, a display title and
+//! optionally its own upload bucket; every other host gets the default site
+//! (`fe/` and `storage.s3_bucket`). Sessions, the database and every cache
+//! stay shared, so an account signed in on one host is the same account on
+//! the others (cookies are still per host). `site_middleware` resolves the
+//! site for each request and stores it as a request extension.
+
+use std::{collections::HashMap, sync::Arc};
+
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    init::app_config::{SiteConfig, StorageSection},
+    util::storage::{StorageBackend, storage_from_config},
+};
+
+pub const DEFAULT_SITE_NAME: &str = "default";
+
+pub struct Site {
+    pub name: String,
+    pub title: Option<String>,
+    /// Directory under `fe/sites/`; `None` for the default build.
+    frontend: Option<String>,
+    /// Storage for uploads made through this site.
+    pub storage: Arc<dyn StorageBackend>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SiteInfo {
+    pub name: String,
+    pub title: Option<String>,
+}
+
+impl Site {
+    /// Path of an embedded frontend asset for this site.
+    pub fn asset_path(&self, path: &str) -> String {
+        match &self.frontend {
+            Some(frontend) => format!("sites/{frontend}/{path}"),
+            None => path.to_string(),
+        }
+    }
+
+    pub fn info(&self) -> SiteInfo {
+        SiteInfo {
+            name: self.name.clone(),
+            title: self.title.clone(),
+        }
+    }
+}
+
+pub struct SiteRegistry {
+    default: Arc<Site>,
+    by_host: HashMap<String, Arc<Site>>,
+}
+
+impl SiteRegistry {
+    /// Builds each site's storage; sites without their own bucket, and every
+    /// site on the local backend, share `storage`.
+    pub fn from_config(
+        sites: &[SiteConfig],
+        storage_config: &StorageSection,
+        storage: &Arc<dyn StorageBackend>,
+        aws_config: &aws_config::SdkConfig,
+    ) -> anyhow::Result<Self> {
+        let default = Arc::new(Site {
+            name: DEFAULT_SITE_NAME.to_string(),
+            title: None,
+            frontend: None,
+            storage: Arc::clone(storage),
+        });
+
+        let mut by_host = HashMap::new();
+        for site in sites {
+            let site_storage = match site.s3_bucket.as_deref().map(str::trim) {
+                Some(bucket) if storage.name() == "s3" => {
+                    let config = StorageSection {
+                        s3_bucket: bucket.to_string(),
+                        ..storage_config.clone()
+                    };
+                    storage_from_config(&config, aws_config)?
+                }
+                _ => Arc::clone(storage),
+            };
+            let resolved = Arc::new(Site {
+                name: site.name.trim().to_string(),
+                title: site.title.clone(),
+                frontend: site.frontend.clone(),
+                storage: site_storage,
+            });
+            for host in &site.hosts {
+                by_host.insert(host.trim().to_ascii_lowercase(), Arc::clone(&resolved));
+            }
+        }
+
+        Ok(Self { default, by_host })
+    }
+
+    /// The site for a `Host` value (port and case ignored).
+    pub fn resolve(&self, host: Option<&str>) -> Arc<Site> {
+        host.map(strip_port)
+            .and_then(|host| self.by_host.get(&host.to_ascii_lowercase()))
+            .unwrap_or(&self.default)
+            .clone()
+    }
+}
+
+fn strip_port(host: &str) -> &str {
+    // Bracketed IPv6 literals keep their colons.
+    if let Some(end) = host.find(']') {
+        return &host[..=end];
+    }
+    host.split(':').next().unwrap_or(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_ports_from_hosts() {
+        assert_eq!(strip_port("example.com"), "example.com");
+        assert_eq!(strip_port("example.com:8443"), "example.com");
+        assert_eq!(strip_port("[::1]:443"), "[::1]");
+    }
+}
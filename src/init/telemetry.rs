@@ -0,0 +1,206 @@
+//! OpenTelemetry trace export.
+//!
+//! Off unless `OTEL_EXPORTER_OTLP_ENDPOINT` (or
+//! `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`) is set, e.g. `http://tempo:4318`.
+//! Spans are batched and sent over OTLP/HTTP; the exporter, sampler
+//! (`OTEL_TRACES_SAMPLER`) and resource (`OTEL_RESOURCE_ATTRIBUTES`) read the
+//! standard `OTEL_*` variables. `OTEL_SERVICE_NAME` defaults to
+//! `APP_NAME_VERSION`.
+//!
+//! Exported spans: one per HTTP request (`routers::middleware::request_span`),
+//! one per SQL statement ([`QuerySpans`], installed on every pooled
+//! connection), one per storage call and one per scheduled job run. They are
+//! regular `tracing` spans, so the console and file logs carry the same
+//! context.
+//!
+//! The pool manager also installs the slow-query timer
+//! (`init::db_slow_query`), which works without trace export.
+
+use std::sync::Arc;
+
+use diesel::connection::{DebugQuery, Instrumentation, InstrumentationEvent};
+use diesel_async::{
+    AsyncConnection, AsyncPgConnection,
+    pooled_connection::{AsyncDieselConnectionManager, ManagerConfig},
+};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+use tracing::{Span, error, field::Empty, info, info_span};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::init::db_slow_query::{SlowQueries, SlowQueryTimer};
+
+/// Instrumentation scope reported with every span.
+const TRACER_NAME: &str = "rust-be-template";
+
+fn otlp_configured() -> bool {
+    let disabled =
+        std::env::var("OTEL_SDK_DISABLED").is_ok_and(|v| v.trim().eq_ignore_ascii_case("true"));
+    let endpoint_set = [
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+    ]
+    .iter()
+    .any(|var| std::env::var(var).is_ok_and(|v| !v.trim().is_empty()));
+    endpoint_set && !disabled
+}
+
+/// Build the OTLP tracer provider, or `None` when export is not configured.
+/// The caller must `shutdown()` it on exit to flush buffered spans.
+pub fn init_tracer_provider(app_name_version: &str) -> anyhow::Result<Option<SdkTracerProvider>> {
+    if !otlp_configured() {
+        return Ok(None);
+    }
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build OTLP span exporter: {e}"))?;
+
+    let mut resource = Resource::builder();
+    if std::env::var("OTEL_SERVICE_NAME").is_err() {
+        resource = resource.with_service_name(app_name_version.to_string());
+    }
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource.build())
+        .build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    Ok(Some(provider))
+}
+
+/// `tracing` layer forwarding spans to `provider`.
+pub fn otel_layer<S>(
+    provider: &SdkTracerProvider,
+) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(TRACER_NAME))
+}
+
+/// Flush and stop the exporter. Called once, after the server has stopped.
+pub fn shutdown_tracer_provider(provider: SdkTracerProvider) {
+    match provider.shutdown() {
+        Ok(()) => info!("Flushed OpenTelemetry spans"),
+        Err(e) => error!(error = %e, "Failed to flush OpenTelemetry spans"),
+    }
+}
+
+/// Statement text without the `-- binds: [...]` suffix diesel appends, so
+/// bound values (passwords, tokens) never reach the trace backend.
+pub(crate) fn statement_text(query: &(impl std::fmt::Display + ?Sized)) -> String {
+    let rendered = query.to_string();
+    match rendered.split_once(" -- binds:") {
+        Some((sql, _)) => sql.trim_end().to_string(),
+        None => rendered,
+    }
+}
+
+/// Opens a `db.query` span per statement, parented to whatever span the
+/// caller is in (the request or job). Pipelined statements on one connection
+/// finish in order, so a stack is enough.
+#[derive(Default)]
+pub struct QuerySpans {
+    open: Vec<Span>,
+}
+
+impl QuerySpans {
+    fn start(&mut self, query: &dyn DebugQuery) {
+        let statement = statement_text(query);
+        let span = info_span!(
+            "db.query",
+            otel.name = %statement.split_whitespace().next().unwrap_or("query"),
+            otel.kind = "client",
+            otel.status_code = Empty,
+            db.system = "postgresql",
+            db.statement = %statement,
+            error = Empty,
+        );
+        self.open.push(span);
+    }
+
+    fn finish(&mut self, error: Option<&diesel::result::Error>) {
+        if let Some(span) = self.open.pop()
+            && let Some(error) = error
+        {
+            span.record("otel.status_code", "ERROR");
+            span.record("error", tracing::field::display(error));
+        }
+    }
+}
+
+/// Everything a pooled connection reports; each part is optional.
+struct ConnectionInstrumentation {
+    spans: Option<QuerySpans>,
+    slow: Option<SlowQueryTimer>,
+}
+
+impl Instrumentation for ConnectionInstrumentation {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { query, .. } => {
+                if let Some(spans) = &mut self.spans {
+                    spans.start(query);
+                }
+                if let Some(slow) = &mut self.slow {
+                    slow.start();
+                }
+            }
+            InstrumentationEvent::FinishQuery { query, error, .. } => {
+                if let Some(slow) = &mut self.slow {
+                    slow.finish(query);
+                }
+                if let Some(spans) = &mut self.spans {
+                    spans.finish(error);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Pool manager whose connections report [`QuerySpans`] and time statements
+/// for `slow_queries`. The spans cost a formatted statement per query, so
+/// they are only installed while trace export is configured; with neither
+/// part enabled connections carry no instrumentation.
+pub fn pg_connection_manager(
+    db_url: String,
+    slow_queries: Arc<SlowQueries>,
+) -> AsyncDieselConnectionManager<AsyncPgConnection> {
+    let trace = otlp_configured();
+    if !trace && slow_queries.threshold().is_none() {
+        return AsyncDieselConnectionManager::new(db_url);
+    }
+
+    let mut config = ManagerConfig::default();
+    config.custom_setup = Box::new(move |url| {
+        let slow_queries = Arc::clone(&slow_queries);
+        Box::pin(async move {
+            let mut conn = AsyncPgConnection::establish(url).await?;
+            conn.set_instrumentation(ConnectionInstrumentation {
+                spans: trace.then(QuerySpans::default),
+                slow: SlowQueryTimer::new(slow_queries),
+            });
+            Ok(conn)
+        })
+    });
+    AsyncDieselConnectionManager::new_with_config(db_url, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statement_text_drops_binds() {
+        let query = r#"SELECT "users"."user_id" FROM "users" WHERE "users"."user_email" = $1 -- binds: ["a@b.c"]"#;
+        assert_eq!(
+            statement_text(&query),
+            r#"SELECT "users"."user_id" FROM "users" WHERE "users"."user_email" = $1"#
+        );
+        assert_eq!(statement_text(&"SELECT 1"), "SELECT 1");
+    }
+}
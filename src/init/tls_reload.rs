@@ -0,0 +1,139 @@
+//! TLS certificate hot reload.
+//!
+//! The HTTPS listener keeps one [`RustlsConfig`]; swapping its contents
+//! applies to every new handshake while open connections keep their session.
+//! [`TlsReloader::watch`] polls the certificate and key modification times
+//! every `server.tls_reload_poll_secs` and reloads when either changed, so a
+//! Let's Encrypt renewal applies without a restart. Superusers can force a
+//! reload with `POST /api/admin/tls/reload`.
+//!
+//! A failed reload (a half-written file, a key that does not match) keeps
+//! serving the previous certificate and is retried on the next change.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use axum_server::tls_rustls::RustlsConfig;
+use chrono::{DateTime, Utc};
+use tracing::{error, info};
+
+use crate::dto::responses::admin::tls_response::TlsStatus;
+
+/// Renewal tools write the chain and the key one after the other; wait for
+/// both before reloading.
+const SETTLE_DELAY: Duration = Duration::from_secs(2);
+
+pub struct TlsReloader {
+    config: RustlsConfig,
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    state: Mutex<ReloadState>,
+}
+
+struct ReloadState {
+    /// Modification times of (chain, key) at the last successful load.
+    modified: Option<(SystemTime, SystemTime)>,
+    loaded_at: DateTime<Utc>,
+    reloads: u64,
+    last_error: Option<(DateTime<Utc>, String)>,
+}
+
+impl TlsReloader {
+    pub async fn load(cert_path: &Path, key_path: &Path) -> anyhow::Result<Self> {
+        let modified = modified_times(cert_path, key_path).await.ok();
+        let config = RustlsConfig::from_pem_file(cert_path, key_path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load TLS config: {}", e))?;
+        Ok(Self {
+            config,
+            cert_path: cert_path.to_path_buf(),
+            key_path: key_path.to_path_buf(),
+            state: Mutex::new(ReloadState {
+                modified,
+                loaded_at: Utc::now(),
+                reloads: 0,
+                last_error: None,
+            }),
+        })
+    }
+
+    /// The config the listener is bound with.
+    pub fn rustls_config(&self) -> RustlsConfig {
+        self.config.clone()
+    }
+
+    /// Re-read both files now. On failure the current certificate stays.
+    pub async fn reload(&self) -> anyhow::Result<()> {
+        let modified = modified_times(&self.cert_path, &self.key_path).await.ok();
+        let result = self
+            .config
+            .reload_from_pem_file(&self.cert_path, &self.key_path)
+            .await;
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(()) => {
+                state.modified = modified;
+                state.loaded_at = Utc::now();
+                state.reloads += 1;
+                state.last_error = None;
+                info!(cert_path = %self.cert_path.display(), "TLS certificate reloaded");
+                Ok(())
+            }
+            Err(e) => {
+                // Remember the times so a broken file is not retried every poll.
+                state.modified = modified;
+                state.last_error = Some((Utc::now(), e.to_string()));
+                error!(cert_path = %self.cert_path.display(), error = %e, "TLS certificate reload failed; keeping the current one");
+                Err(anyhow::anyhow!("Failed to reload TLS config: {}", e))
+            }
+        }
+    }
+
+    /// Poll forever; started by `server_init_proc` when the interval is not 0.
+    pub async fn watch(&self, interval: Duration) {
+        info!(?interval, "Watching TLS certificate files for renewal");
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let Ok(current) = modified_times(&self.cert_path, &self.key_path).await else {
+                // Mid-rename or briefly missing; look again next tick.
+                continue;
+            };
+            let seen = self
+                .state
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .modified;
+            if seen != Some(current) {
+                tokio::time::sleep(SETTLE_DELAY).await;
+                // Errors are recorded and logged by `reload`.
+                let _ = self.reload().await;
+            }
+        }
+    }
+
+    pub fn status(&self) -> TlsStatus {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        TlsStatus {
+            cert_path: self.cert_path.display().to_string(),
+            key_path: self.key_path.display().to_string(),
+            loaded_at: state.loaded_at,
+            reloads: state.reloads,
+            last_error_at: state.last_error.as_ref().map(|(at, _)| *at),
+            last_error: state.last_error.as_ref().map(|(_, e)| e.clone()),
+        }
+    }
+}
+
+async fn modified_times(
+    cert_path: &Path,
+    key_path: &Path,
+) -> std::io::Result<(SystemTime, SystemTime)> {
+    let cert = tokio::fs::metadata(cert_path).await?.modified()?;
+    let key = tokio::fs::metadata(key_path).await?.modified()?;
+    Ok((cert, key))
+}
@@ -0,0 +1,112 @@
+//! Serving the API on a unix domain socket (`HOST_SOCKET_PATH`).
+//!
+//! Meant for deployments behind a reverse proxy on the same host: the proxy
+//! terminates TLS and forwards plain HTTP over the socket, so no certificate is
+//! loaded and the HTTP->HTTPS redirect listener is not started. Unix peers have
+//! no IP address; every connection reports [`UNIX_PEER_ADDR`] as its
+//! `ConnectInfo`, and `extract_client_ip` trusts the proxy's
+//! `X-Forwarded-For` hop instead.
+
+use std::future::IntoFuture;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    Router,
+    extract::connect_info::IntoMakeServiceWithConnectInfo,
+    serve::{Listener, ListenerExt},
+};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::{error, info, warn};
+
+use crate::init::state::ServerState;
+
+/// The `ConnectInfo` address of every unix socket connection: the local proxy.
+pub const UNIX_PEER_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+/// A [`UnixListener`] that reports peers as [`UNIX_PEER_ADDR`], so handlers and
+/// middleware keep extracting `ConnectInfo<SocketAddr>`.
+pub struct UnixSocketListener {
+    inner: UnixListener,
+}
+
+impl UnixSocketListener {
+    /// Bind `path`, replacing a socket file left behind by a previous run.
+    pub fn bind(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_socket() => {
+                std::fs::remove_file(path).map_err(|e| {
+                    anyhow::anyhow!("Failed to remove stale socket {}: {}", path.display(), e)
+                })?
+            }
+            Ok(_) => anyhow::bail!("{} exists and is not a socket", path.display()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => anyhow::bail!("Failed to inspect {}: {}", path.display(), e),
+        }
+        let inner = UnixListener::bind(path)
+            .map_err(|e| anyhow::anyhow!("Failed to bind unix socket {}: {}", path.display(), e))?;
+        Ok(Self { inner })
+    }
+}
+
+impl Listener for UnixSocketListener {
+    type Io = UnixStream;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            match self.inner.accept().await {
+                Ok((stream, _)) => return (stream, UNIX_PEER_ADDR),
+                Err(e) => {
+                    // Usually fd exhaustion; back off instead of spinning.
+                    warn!(error = %e, "Failed to accept unix socket connection");
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(UNIX_PEER_ADDR)
+    }
+}
+
+/// Serve until shutdown begins, then give open connections `budget` to finish.
+/// The socket file is removed on the way out.
+pub async fn serve_unix(
+    path: &Path,
+    app: IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+    state: &Arc<ServerState>,
+    budget: Duration,
+) -> anyhow::Result<()> {
+    // axum only derives `ConnectInfo` from a custom listener's `Addr` through
+    // `TapIo`; the no-op tap is what lets `UNIX_PEER_ADDR` reach extractors.
+    let listener = UnixSocketListener::bind(path)?.tap_io(|_: &mut UnixStream| {});
+    info!(socket_path = %path.display(), "Listening for HTTP traffic on unix socket");
+
+    let server = {
+        let state = Arc::clone(state);
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async move { state.shutdown.cancelled().await })
+            .into_future()
+    };
+    let result = tokio::select! {
+        result = server => result.map_err(|e| anyhow::anyhow!("Server error: {}", e)),
+        _ = async {
+            state.shutdown.cancelled().await;
+            tokio::time::sleep(budget).await;
+        } => {
+            warn!(timeout = ?budget, "Closing HTTP connections still open after the shutdown budget");
+            Ok(())
+        }
+    };
+
+    if let Err(e) = std::fs::remove_file(path) {
+        error!(socket_path = %path.display(), error = %e, "Failed to remove unix socket");
+    }
+    result
+}
@@ -1,13 +1,97 @@
+//! Periodic purge of expired in-memory sessions.
+//!
+//! Runs every hour at :30 by default; `SESSION_PURGE_INTERVAL_SECS` switches
+//! it to a fixed interval (see `registry::session_purge_schedule`). Each run
+//! is kept in [`SessionPurgeTracker`] for `GET /api/admin/sessions/purges`
+//! and counted in `sessions_purged_total` on `/api/metrics`.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::sync::RwLock;
 use tracing::info;
 
+use crate::dto::responses::admin::session_purge_response::SessionPurgeReport;
 use crate::init::state::ServerState;
-use std::sync::Arc;
+use crate::util::metrics::MetricsWriter;
+
+/// Runs kept for the admin endpoint: three weeks of hourly purges.
+pub const MAX_PURGE_REPORTS: usize = 1000;
+
+pub struct SessionPurgeTracker {
+    purged_total: AtomicU64,
+    /// Newest last.
+    reports: RwLock<VecDeque<SessionPurgeReport>>,
+}
+
+impl SessionPurgeTracker {
+    pub fn new() -> Self {
+        Self {
+            purged_total: AtomicU64::new(0),
+            reports: RwLock::new(VecDeque::new()),
+        }
+    }
+
+    async fn record(&self, report: SessionPurgeReport) {
+        self.purged_total
+            .fetch_add(report.purged as u64, Ordering::Relaxed);
+        let mut reports = self.reports.write().await;
+        if reports.len() == MAX_PURGE_REPORTS {
+            reports.pop_front();
+        }
+        reports.push_back(report);
+    }
+
+    pub fn purged_total(&self) -> u64 {
+        self.purged_total.load(Ordering::Relaxed)
+    }
+
+    /// Up to `limit` runs, newest first.
+    pub async fn recent(&self, limit: usize) -> Vec<SessionPurgeReport> {
+        self.reports
+            .read()
+            .await
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn write_metrics(&self, w: &mut MetricsWriter, active_sessions: usize) {
+        w.header(
+            "sessions_purged_total",
+            "counter",
+            "Expired sessions purged since startup.",
+        );
+        w.sample("sessions_purged_total", &[], self.purged_total() as f64);
+        w.header("sessions_active", "gauge", "Sessions held in memory.");
+        w.sample("sessions_active", &[], active_sessions as f64);
+    }
+}
+
+impl Default for SessionPurgeTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub async fn invalidate_sessions(state: Arc<ServerState>) {
+    let started = tokio::time::Instant::now();
     let (pruned, remaining): (usize, usize) = state.purge_expired_sessions().await;
     info!(
         pruned = pruned,
         remaining = remaining,
         "Invalidated expired sessions."
     );
+    state
+        .session_purges
+        .record(SessionPurgeReport {
+            purged_at: chrono::Utc::now(),
+            purged: pruned,
+            retained: remaining,
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+        .await;
 }
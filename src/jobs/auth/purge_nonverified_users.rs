@@ -1,8 +1,12 @@
 use std::sync::Arc;
 
-use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, QueryDsl,
+    dsl::{exists, not},
+};
 use diesel_async::RunQueryDsl;
 use tracing::{error, info};
+use uuid::Uuid;
 
 use crate::{
     init::state::ServerState,
@@ -46,3 +50,33 @@ pub async fn purge_nonverified_users(state: Arc<ServerState>) {
 
     drop(conn);
 }
+
+/// Delayed-task form of the hourly purge for one account, scheduled at signup
+/// for when its verification token expires. Keeps the account when it was
+/// verified or a resend issued a token that is still live.
+pub async fn purge_unverified_user(state: &ServerState, user_id: Uuid) -> anyhow::Result<()> {
+    let now = chrono::Utc::now();
+    let mut conn = state.get_conn().await?;
+
+    let deleted = diesel::delete(
+        users::table.filter(
+            users::user_id
+                .eq(user_id)
+                .and(users::user_is_email_verified.eq(false))
+                .and(not(exists(
+                    email_verification_tokens::table
+                        .filter(email_verification_tokens::user_id.eq(user_id))
+                        .filter(
+                            email_verification_tokens::email_verification_token_expires_at.ge(now),
+                        ),
+                ))),
+        ),
+    )
+    .execute(&mut conn)
+    .await?;
+
+    if deleted > 0 {
+        info!(%user_id, "Purged unverified user after verification window");
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Alerts for jobs that keep failing.
+//!
+//! When a job's failure streak (failed, panicked or timed-out runs since its
+//! last success, tracked by `JobMonitor`) reaches `JOB_ALERT_AFTER_FAILURES`
+//! (default 3, `0` disables), one alert with the streak's errors is sent to
+//! `JOB_ALERT_EMAIL` (comma-separated addresses) through the SMTP client and,
+//! when set, queued as a JSON webhook to `JOB_ALERT_WEBHOOK_URL` (retried by
+//! the task queue). A streak alerts once;
+//! the next alert needs a success in between.
+
+use std::sync::Arc;
+
+use chrono::SecondsFormat;
+use lettre::{Message, message::Mailbox};
+use serde_derive::Serialize;
+use tracing::{error, info, warn};
+
+use crate::{
+    domain::job::{delayed_task::DelayedTask, execution::JobExecution},
+    init::{app_config, state::ServerState},
+    jobs::job_funcs::delayed::enqueue,
+    util::email::transport::OutgoingEmail,
+};
+
+/// Longest error text quoted per failure.
+const MAX_ERROR_CHARS: usize = 500;
+
+fn alert_threshold() -> Option<u32> {
+    let threshold = app_config::config().jobs.alert_after_failures;
+    (threshold > 0).then_some(threshold)
+}
+
+/// Whether a streak of `consecutive_failures` should alert now.
+pub fn should_alert(consecutive_failures: u32) -> bool {
+    alert_threshold() == Some(consecutive_failures)
+}
+
+#[derive(Serialize)]
+struct JobFailureAlert<'a> {
+    app: String,
+    job_name: &'a str,
+    consecutive_failures: u32,
+    /// Newest failures of the streak, oldest first.
+    failures: &'a [JobExecution],
+}
+
+/// Send the alert on its own task; failures to deliver are only logged.
+pub fn spawn_failure_alert(
+    state: Arc<ServerState>,
+    job_name: String,
+    consecutive_failures: u32,
+    failures: Vec<JobExecution>,
+) {
+    tokio::spawn(async move {
+        let alert = JobFailureAlert {
+            app: state.get_app_name_version(),
+            job_name: &job_name,
+            consecutive_failures,
+            failures: &failures,
+        };
+        warn!(task_name = %job_name, consecutive_failures, "Job keeps failing; sending alert");
+
+        let jobs = &state.config.jobs;
+        for recipient in jobs
+            .alert_email
+            .iter()
+            .map(|r| r.trim())
+            .filter(|r| !r.is_empty())
+        {
+            if let Err(e) = send_email(&state, recipient, &alert).await {
+                error!(task_name = %job_name, recipient, error = ?e, "Failed to send job failure alert email");
+            }
+        }
+
+        if let Some(url) = &jobs.alert_webhook_url
+            && !url.trim().is_empty()
+            && let Err(e) = queue_webhook(&state, url.trim(), &alert).await
+        {
+            error!(task_name = %job_name, error = ?e, "Failed to queue job failure alert webhook");
+        }
+    });
+}
+
+async fn send_email(
+    state: &ServerState,
+    recipient: &str,
+    alert: &JobFailureAlert<'_>,
+) -> anyhow::Result<()> {
+    if let Some(reason) = state.email_suppression_reason(recipient).await? {
+        warn!(task_name = %alert.job_name, recipient, %reason, "Alert recipient is suppressed; not emailing");
+        return Ok(());
+    }
+    let sender = state.email_sender();
+    let to: Mailbox = recipient.parse()?;
+    let mut builder = Message::builder()
+        .from(sender.from.clone())
+        .to(to)
+        .subject(sender.subject(&format!(
+            "[{}] {} failed {} times in a row",
+            alert.app, alert.job_name, alert.consecutive_failures
+        )));
+    if let Some(reply_to) = &sender.reply_to {
+        builder = builder.reply_to(reply_to.clone());
+    }
+    let message = builder
+        .header(lettre::message::header::ContentType::TEXT_PLAIN)
+        .body(email_body(alert))?;
+    state
+        .email_transport()
+        .send(OutgoingEmail::new(message).tag("kind", "job_failure_alert"))
+        .await?;
+    info!(task_name = %alert.job_name, recipient, "Job failure alert email sent");
+    Ok(())
+}
+
+fn email_body(alert: &JobFailureAlert<'_>) -> String {
+    let mut body = format!(
+        "{} has failed {} consecutive runs on {}.\n\nRecent failures (oldest first):\n",
+        alert.job_name, alert.consecutive_failures, alert.app
+    );
+    for failure in alert.failures {
+        let error: String = failure
+            .error
+            .as_deref()
+            .unwrap_or("(no error message)")
+            .chars()
+            .take(MAX_ERROR_CHARS)
+            .collect();
+        body.push_str(&format!(
+            "\n- {} ({}, {} ms): {}\n",
+            failure
+                .scheduled_for
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            failure.outcome.as_str(),
+            failure.duration_ms,
+            error
+        ));
+    }
+    body.push_str("\nSee GET /api/admin/jobs for the full history.\n");
+    body
+}
+
+async fn queue_webhook(
+    state: &ServerState,
+    url: &str,
+    alert: &JobFailureAlert<'_>,
+) -> anyhow::Result<()> {
+    let task = DelayedTask::DeliverWebhook {
+        url: url.to_string(),
+        body: serde_json::to_value(alert)?,
+    };
+    enqueue(state, task).await?;
+    Ok(())
+}
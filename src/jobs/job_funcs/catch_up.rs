@@ -0,0 +1,94 @@
+//! Missed-run catch-up for the calendar (`Recurrence`) schedulers.
+//!
+//! Every successful run is recorded in `job_runs`. When a scheduler starts it
+//! compares that record with the most recent mark that should already have
+//! fired; if the mark was missed (the process was down) and the job's policy
+//! allows it, the job runs once right away before settling into its regular
+//! schedule. A job with no recorded run is never caught up.
+//!
+//! Policies default per job in `registry::registered_jobs`; `JOB_CATCH_UP_<JOB_NAME>=true|false`
+//! overrides them (e.g. `JOB_CATCH_UP_COMPRESS_OLD_LOGS=false`).
+
+use std::sync::Arc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use tracing::{error, info, warn};
+
+use crate::{
+    domain::job::execution::JobOutcome,
+    init::{app_config, state::ServerState},
+    jobs::job_funcs::job_runner::{JobResult, run_job},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUpPolicy {
+    /// Wait for the next regular mark.
+    Skip,
+    /// Run once at startup when the last mark was missed.
+    RunOnce,
+}
+
+impl CatchUpPolicy {
+    pub fn for_job(task_descriptor: &str, default: Self) -> Self {
+        match app_config::config()
+            .jobs
+            .job(task_descriptor)
+            .and_then(|job| job.catch_up)
+        {
+            Some(true) => Self::RunOnce,
+            Some(false) => Self::Skip,
+            None => default,
+        }
+    }
+}
+
+/// Run `task` once if the mark at `previous_mark` was missed.
+pub async fn catch_up_missed_run<F, Fut>(
+    state: &Arc<ServerState>,
+    task: &F,
+    task_descriptor: &str,
+    policy: CatchUpPolicy,
+    previous_mark: DateTime<Utc>,
+) where
+    F: Fn(Arc<ServerState>) -> Fut,
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: JobResult + Send + 'static,
+{
+    if policy == CatchUpPolicy::Skip {
+        return;
+    }
+    let last_success = match state.last_job_success(task_descriptor).await {
+        Ok(Some(last_success)) if last_success < previous_mark => last_success,
+        Ok(_) => return,
+        Err(e) => {
+            warn!(task_name = %task_descriptor, error = ?e, "Could not read last job run; skipping catch-up");
+            return;
+        }
+    };
+
+    info!(
+        task_name = %task_descriptor,
+        missed_run_time = %previous_mark.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        last_success = %last_success.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        "Running missed scheduled task"
+    );
+    if run_job(
+        state,
+        task_descriptor,
+        previous_mark,
+        task(Arc::clone(state)),
+    )
+    .await
+        == JobOutcome::Success
+    {
+        record_job_run(state, task_descriptor).await;
+    }
+}
+
+/// Record a successful run in `job_runs`. Failures are logged only; the worst
+/// case is one extra catch-up run after the next restart.
+pub async fn record_job_run(state: &ServerState, task_descriptor: &str) {
+    if let Err(e) = state.record_job_success(task_descriptor, Utc::now()).await {
+        error!(task_name = %task_descriptor, error = ?e, "Failed to record job run");
+    }
+}
@@ -0,0 +1,176 @@
+//! Postgres-backed task queue: deferred work that survives restarts.
+//!
+//! Handlers enqueue a [`DelayedTask`] with [`enqueue`] (run as soon as
+//! possible), [`schedule_once_at`] or [`schedule_after`]; the row lands in
+//! `delayed_tasks`. The dispatcher started by `task_init` polls every
+//! `DELAYED_TASK_POLL_SECS` (default 5), claims due rows and runs each on its
+//! own task. A failed or panicked attempt is retried with exponential backoff
+//! (1, 2, 4, 8 minutes) and marked `failed` after [`MAX_ATTEMPTS`]; an admin
+//! can requeue it from `POST /api/admin/tasks/{task_id}/requeue`. A claim is
+//! visible to other instances again after `DELAYED_TASK_VISIBILITY_SECS`
+//! (default 900) without a result, so a task may run again after a crash and
+//! must be idempotent.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::job::delayed_task::{ClaimedDelayedTask, DelayedTask, DelayedTaskInsertable},
+    init::{app_config, state::ServerState},
+    jobs::{
+        auth::purge_nonverified_users::purge_unverified_user,
+        tasks::{
+            email::{queue_password_reset_email, queue_validation_email},
+            webhook::{deliver_signed_webhook, deliver_webhook},
+        },
+    },
+};
+
+pub const MAX_ATTEMPTS: i32 = 5;
+const CLAIM_BATCH: i64 = 32;
+
+/// Persist `task` to run at `run_at` (immediately on the next poll if it is
+/// already past). Returns the task id.
+pub async fn schedule_once_at(
+    state: &ServerState,
+    task: DelayedTask,
+    run_at: DateTime<Utc>,
+) -> anyhow::Result<Uuid> {
+    let row = DelayedTaskInsertable::new(&task, run_at)?;
+    let task_id = row.delayed_task_id;
+    state.insert_delayed_task(row).await?;
+    info!(%task_id, kind = task.kind(), %run_at, "Delayed task scheduled");
+    Ok(task_id)
+}
+
+/// Persist `task` to run on the next poll.
+pub async fn enqueue(state: &ServerState, task: DelayedTask) -> anyhow::Result<Uuid> {
+    schedule_once_at(state, task, Utc::now()).await
+}
+
+/// Persist `task` to run `delay` from now.
+pub async fn schedule_after(
+    state: &ServerState,
+    task: DelayedTask,
+    delay: chrono::Duration,
+) -> anyhow::Result<Uuid> {
+    schedule_once_at(state, task, Utc::now() + delay).await
+}
+
+async fn execute(state: Arc<ServerState>, task: DelayedTask) -> anyhow::Result<()> {
+    match task {
+        DelayedTask::PurgeUnverifiedUser { user_id } => {
+            purge_unverified_user(&state, user_id).await
+        }
+        // Queued before the email outbox existed; hand them over to it.
+        DelayedTask::SendValidationEmail {
+            user_email,
+            token_id,
+            valid_until,
+        } => queue_validation_email(&state, &user_email, token_id, valid_until)
+            .await
+            .map(drop),
+        DelayedTask::SendPasswordResetEmail {
+            user_email,
+            token_id,
+        } => queue_password_reset_email(&state, &user_email, token_id)
+            .await
+            .map(drop),
+        DelayedTask::DeliverWebhook { url, body } => deliver_webhook(&state, &url, &body).await,
+        DelayedTask::DeliverSignedWebhook { delivery_id } => {
+            deliver_signed_webhook(&state, delivery_id).await
+        }
+    }
+}
+
+fn poll_interval() -> Duration {
+    Duration::from_secs(app_config::config().jobs.delayed_task_poll_secs)
+}
+
+/// How long a claimed task may stay `running` before it is requeued.
+fn visibility_timeout() -> chrono::Duration {
+    chrono::Duration::seconds(app_config::config().jobs.delayed_task_visibility_secs)
+}
+
+/// Poll loop; runs until the process exits. Started under `supervise`.
+pub async fn run_delayed_task_dispatcher(state: Arc<ServerState>) -> anyhow::Result<()> {
+    let interval = poll_interval();
+    info!(?interval, "Delayed task dispatcher running");
+    loop {
+        if !state.shutdown.is_shutting_down() {
+            poll_once(&state).await;
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn poll_once(state: &Arc<ServerState>) {
+    match state
+        .requeue_stale_delayed_tasks(Utc::now() - visibility_timeout())
+        .await
+    {
+        Ok(0) => {}
+        Ok(requeued) => warn!(requeued, "Requeued delayed tasks left running"),
+        Err(e) => warn!(error = ?e, "Failed to requeue stale delayed tasks"),
+    }
+
+    let claimed = match state.claim_due_delayed_tasks(CLAIM_BATCH).await {
+        Ok(claimed) => claimed,
+        Err(e) => {
+            error!(error = ?e, "Failed to claim due delayed tasks");
+            return;
+        }
+    };
+    for claimed in claimed {
+        let state = Arc::clone(state);
+        tokio::spawn(async move { run_claimed(state, claimed).await });
+    }
+}
+
+async fn run_claimed(state: Arc<ServerState>, claimed: ClaimedDelayedTask) {
+    let task_id = claimed.delayed_task_id;
+    let Some(_guard) = state.shutdown.job_guard() else {
+        // Left `running`; the stale-claim sweep requeues it after restart.
+        return;
+    };
+
+    let result = match serde_json::from_value::<DelayedTask>(claimed.delayed_task_payload) {
+        Ok(task) => match tokio::spawn(execute(Arc::clone(&state), task)).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(format!("{e:#}")),
+            Err(join_err) => Err(join_err.to_string()),
+        },
+        // An unknown kind will not parse on retry either.
+        Err(e) => {
+            error!(%task_id, error = %e, "Could not decode delayed task payload");
+            if let Err(e) = state
+                .fail_delayed_task(task_id, &format!("undecodable payload: {e}"), None)
+                .await
+            {
+                error!(%task_id, error = ?e, "Failed to record delayed task failure");
+            }
+            return;
+        }
+    };
+
+    let recorded = match result {
+        Ok(()) => {
+            info!(%task_id, late_by = %(Utc::now() - claimed.delayed_task_run_at), "Delayed task completed");
+            state.complete_delayed_task(task_id).await
+        }
+        Err(error) => {
+            let attempts = claimed.delayed_task_attempts;
+            let retry_at = (attempts < MAX_ATTEMPTS)
+                .then(|| Utc::now() + chrono::Duration::minutes(1 << (attempts - 1).clamp(0, 10)));
+            error!(%task_id, attempts, error = %error, ?retry_at, "Delayed task failed");
+            state.fail_delayed_task(task_id, &error, retry_at).await
+        }
+    };
+    if let Err(e) = recorded {
+        error!(%task_id, error = ?e, "Failed to record delayed task result");
+    }
+}
@@ -0,0 +1,131 @@
+//! Outbox sender: delivers `email_outbox` rows queued by handlers.
+//!
+//! Started by `task_init`, it polls every `EMAIL_OUTBOX_POLL_SECS` (default
+//! 5), claims due rows and sends each on its own task. A failed attempt is
+//! retried with exponential backoff (30 s doubling, capped at an hour) and
+//! the row is marked `dead` after [`MAX_ATTEMPTS`], or at once when the
+//! transport reports a permanent rejection; an admin can requeue it
+//! from `POST /api/admin/email-outbox/{email_id}/requeue`. A claim left
+//! `sending` for `DELAYED_TASK_VISIBILITY_SECS` is requeued, so an email
+//! interrupted by a crash may be sent twice. Rows addressed to a suppressed
+//! recipient (see `domain::email::feedback`) are closed as `suppressed`
+//! without sending.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{error, info, warn};
+
+use crate::{
+    domain::email::email_outbox::EmailOutboxRow,
+    init::{app_config, state::ServerState},
+    jobs::tasks::email::{OutboxDelivery, send_outbox_email},
+    util::email::transport::EmailSendError,
+};
+
+pub const MAX_ATTEMPTS: i32 = 8;
+const CLAIM_BATCH: i64 = 32;
+const FIRST_RETRY_SECS: i64 = 30;
+const MAX_RETRY_SECS: i64 = 60 * 60;
+
+fn poll_interval() -> Duration {
+    Duration::from_secs(app_config::config().jobs.email_outbox_poll_secs)
+}
+
+fn visibility_timeout() -> chrono::Duration {
+    chrono::Duration::seconds(app_config::config().jobs.delayed_task_visibility_secs)
+}
+
+/// Delay before the attempt after `attempts` failed ones.
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let secs = FIRST_RETRY_SECS.saturating_mul(1 << (attempts - 1).clamp(0, 16));
+    chrono::Duration::seconds(secs.min(MAX_RETRY_SECS))
+}
+
+/// Poll loop; runs until the process exits. Started under `supervise`.
+pub async fn run_email_outbox_sender(state: Arc<ServerState>) -> anyhow::Result<()> {
+    let interval = poll_interval();
+    info!(?interval, "Email outbox sender running");
+    loop {
+        if !state.shutdown.is_shutting_down() {
+            poll_once(&state).await;
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn poll_once(state: &Arc<ServerState>) {
+    match state
+        .requeue_stale_emails(Utc::now() - visibility_timeout())
+        .await
+    {
+        Ok(0) => {}
+        Ok(requeued) => warn!(requeued, "Requeued emails left sending"),
+        Err(e) => warn!(error = ?e, "Failed to requeue stale outbox emails"),
+    }
+
+    let claimed = match state.claim_due_emails(CLAIM_BATCH).await {
+        Ok(claimed) => claimed,
+        Err(e) => {
+            error!(error = ?e, "Failed to claim due outbox emails");
+            return;
+        }
+    };
+    for row in claimed {
+        let state = Arc::clone(state);
+        tokio::spawn(async move { send_claimed(state, row).await });
+    }
+}
+
+async fn send_claimed(state: Arc<ServerState>, row: EmailOutboxRow) {
+    let email_id = row.email_outbox_id;
+    let Some(_guard) = state.shutdown.job_guard() else {
+        // Left `sending`; the stale-claim sweep requeues it after restart.
+        return;
+    };
+
+    let recorded = match send_outbox_email(&state, &row).await {
+        Ok(OutboxDelivery::Suppressed(reason)) => {
+            info!(%email_id, template = %row.email_outbox_template, %reason, "Outbox email not sent; recipient suppressed");
+            state.mark_email_suppressed(email_id, &reason).await
+        }
+        Ok(OutboxDelivery::Sent(sent)) => {
+            info!(%email_id, template = %row.email_outbox_template, attempts = row.email_outbox_attempts, provider_message_id = ?sent.message_id, "Outbox email sent");
+            state
+                .mark_email_sent(email_id, sent.message_id.as_deref())
+                .await
+        }
+        Err(e) => {
+            let attempts = row.email_outbox_attempts;
+            let permanent = e
+                .downcast_ref::<EmailSendError>()
+                .is_some_and(|e| e.permanent);
+            let retry_at =
+                (attempts < MAX_ATTEMPTS && !permanent).then(|| Utc::now() + retry_delay(attempts));
+            let error = format!("{e:#}");
+            if retry_at.is_some() {
+                warn!(%email_id, attempts, error = %error, ?retry_at, "Outbox email failed; will retry");
+            } else {
+                error!(%email_id, attempts, permanent, error = %error, "Outbox email dead");
+            }
+            state.fail_email(email_id, &error, retry_at).await
+        }
+    };
+    if let Err(e) = recorded {
+        error!(%email_id, error = ?e, "Failed to record outbox email result");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_an_hour() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(2).num_seconds(), 60);
+        assert_eq!(retry_delay(4).num_seconds(), 240);
+        assert_eq!(retry_delay(MAX_ATTEMPTS).num_seconds(), 3600);
+    }
+}
@@ -4,7 +4,14 @@ use anyhow::{Result, anyhow};
 use chrono::{Duration, SecondsFormat, Timelike, Utc};
 use tracing::{error, info};
 
-use crate::{init::state::ServerState, util::time::duration_formatter::format_duration};
+use crate::{
+    init::state::ServerState,
+    jobs::job_funcs::{
+        jitter::next_jittered_mark,
+        job_runner::{JobResult, spawn_run},
+    },
+    util::time::duration_formatter::format_duration,
+};
 
 /// Calculate the next UTC DateTime that lands on the current/next hour,
 /// with a specific "minute + second" offset from the start of that hour.
@@ -45,15 +52,18 @@ pub fn next_scheduled_hour_mark(
 /// A helper that returns both (delay, next_mark).
 /// It calculates how long until the next scheduled mark (hourly) from now.
 pub fn next_scheduled_hourly_delay(
-    _task_descriptor: &str,
+    task_descriptor: &str,
     minute_offset: u32,
     second_offset: u32,
 ) -> Result<(tokio::time::Duration, chrono::DateTime<chrono::Utc>)> {
     let now = Utc::now();
-    let next_mark = next_scheduled_hour_mark(now, minute_offset, second_offset)?;
+    let (next_mark, run_at) =
+        next_jittered_mark(now, task_descriptor, chrono::Duration::hours(1), |after| {
+            next_scheduled_hour_mark(after, minute_offset, second_offset)
+        })?;
 
     // Convert the difference into a std::time::Duration for tokio.
-    let delay = next_mark - now;
+    let delay = run_at - now;
     let delay = delay.to_std().map_err(|e| {
         anyhow!(
             "Could not schedule job at next_scheduled_hour_mark(). Chrono->Std error: {:?}",
@@ -75,8 +85,18 @@ pub async fn schedule_task_every_hour_at<F, Fut>(
 ) -> Result<()>
 where
     F: Fn(Arc<ServerState>) -> Fut + Send + Sync + 'static,
-    Fut: std::future::Future<Output = ()> + Send + 'static,
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: JobResult + Send + 'static,
 {
+    state
+        .job_monitor
+        .register(
+            &task_descriptor,
+            format!("every hour at :{minute_offset:02}:{second_offset:02}"),
+            true,
+        )
+        .await;
+
     let mut initialized: bool = false;
     loop {
         let (delay, scheduled_run_time) =
@@ -104,11 +124,19 @@ where
             initialized = true;
         }
 
+        state
+            .job_monitor
+            .set_next_run(&task_descriptor, scheduled_run_time)
+            .await;
         tokio::time::sleep(delay).await;
 
-        let start = tokio::time::Instant::now();
-        task(Arc::clone(&state)).await;
-        let elapsed = start.elapsed();
+        spawn_run(
+            &state,
+            &task_descriptor,
+            scheduled_run_time,
+            task(Arc::clone(&state)),
+            false,
+        );
 
         // FIX: Simply add one hour to the already-calculated scheduled time.
         // This is far more efficient than calling the calculation function again.
@@ -124,9 +152,8 @@ where
         info!(
             task_name = %task_descriptor,
             next_run_time = %next_run_time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
-            duration = %format!("{:?}", elapsed),
             next_delay_human = %format_duration(next_delay),
-            "Scheduled task ran"
+            "Scheduled task dispatched"
         );
     }
 }
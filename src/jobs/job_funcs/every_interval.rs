@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use tracing::{error, info};
+
+use crate::{
+    init::state::ServerState,
+    jobs::job_funcs::{
+        jitter::next_jittered_mark,
+        job_runner::{JobResult, spawn_run},
+    },
+    util::time::duration_formatter::format_duration,
+};
+
+/// The next multiple of `interval_secs` since the Unix epoch after `now`, so
+/// every instance (and every restart) shares the same marks.
+pub fn next_interval_mark(now: DateTime<Utc>, interval_secs: u32) -> Result<DateTime<Utc>> {
+    if interval_secs == 0 {
+        return Err(anyhow!("Interval must be at least one second"));
+    }
+    let interval = i64::from(interval_secs);
+    let next = (now.timestamp().div_euclid(interval) + 1) * interval;
+    DateTime::from_timestamp(next, 0).ok_or_else(|| anyhow!("Interval mark out of range"))
+}
+
+/// Returns (delay, next_mark) for the next interval mark.
+pub fn next_interval_delay(
+    task_descriptor: &str,
+    interval_secs: u32,
+) -> Result<(tokio::time::Duration, DateTime<Utc>)> {
+    let now = Utc::now();
+    let (next_mark, run_at) = next_jittered_mark(
+        now,
+        task_descriptor,
+        Duration::seconds(i64::from(interval_secs)),
+        |after| next_interval_mark(after, interval_secs),
+    )?;
+
+    let delay = run_at - now;
+    let delay = delay.to_std().map_err(|e| {
+        anyhow!(
+            "Could not schedule job at next_interval_mark(). Chrono->Std error: {:?}",
+            e
+        )
+    })?;
+
+    Ok((delay, next_mark))
+}
+
+/// Schedules a task to run every `interval_secs` seconds, on marks aligned to
+/// the Unix epoch (e.g. 600 runs at :00, :10, :20, ... of every hour).
+pub async fn schedule_task_every_interval<F, Fut>(
+    state: Arc<ServerState>,
+    task: F,
+    task_descriptor: String,
+    interval_secs: u32,
+) -> Result<()>
+where
+    F: Fn(Arc<ServerState>) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: JobResult + Send + 'static,
+{
+    state
+        .job_monitor
+        .register(
+            &task_descriptor,
+            format!("every {interval_secs} seconds"),
+            true,
+        )
+        .await;
+
+    let mut initialized: bool = false;
+    loop {
+        let (delay, scheduled_run_time) = match next_interval_delay(&task_descriptor, interval_secs)
+        {
+            Ok((d, nm)) => (d, nm),
+            Err(e) => {
+                error!(
+                    task_name = %task_descriptor,
+                    error = ?e,
+                    "Could not calculate next scheduled time"
+                );
+                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+                continue;
+            }
+        };
+
+        if !initialized {
+            info!(
+                task_name = %task_descriptor,
+                initial_run_time = %scheduled_run_time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                ?delay,
+                delay_human = %format_duration(delay),
+                "Scheduled task initialized"
+            );
+            initialized = true;
+        }
+
+        state
+            .job_monitor
+            .set_next_run(&task_descriptor, scheduled_run_time)
+            .await;
+        tokio::time::sleep(delay).await;
+
+        spawn_run(
+            &state,
+            &task_descriptor,
+            scheduled_run_time,
+            task(Arc::clone(&state)),
+            false,
+        );
+
+        let next_run_time = scheduled_run_time + Duration::seconds(i64::from(interval_secs));
+        let next_delay = (next_run_time - Utc::now()).to_std().unwrap_or_default();
+
+        info!(
+            task_name = %task_descriptor,
+            next_run_time = %next_run_time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            next_delay_human = %format_duration(next_delay),
+            "Scheduled task dispatched"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_align_to_epoch() {
+        let now = DateTime::from_timestamp(1_800_000_123, 0).unwrap();
+        let mark = next_interval_mark(now, 600).unwrap();
+        assert_eq!(mark.timestamp(), 1_800_000_600);
+        assert_eq!(
+            next_interval_mark(mark, 600).unwrap().timestamp(),
+            1_800_001_200
+        );
+        assert!(next_interval_mark(now, 0).is_err());
+    }
+}
@@ -5,6 +5,8 @@ use chrono::{Duration, SecondsFormat, Timelike, Utc};
 use tracing::{error, info};
 
 use crate::init::state::ServerState;
+use crate::jobs::job_funcs::jitter::next_jittered_mark;
+use crate::jobs::job_funcs::job_runner::{JobResult, spawn_run};
 use crate::util::time::duration_formatter::format_duration;
 
 /// Calculate the next UTC DateTime that lands on the current/next minute,
@@ -42,15 +44,20 @@ pub fn next_scheduled_mark(
 /// A helper that returns both (delay, next_mark).
 /// It calculates how long until the next_scheduled_mark(...) from now.
 pub fn next_scheduled_delay(
-    _task_descriptor: &str,
+    task_descriptor: &str,
     second_offset: u32,
     millisecond_offset: u32,
 ) -> Result<(tokio::time::Duration, chrono::DateTime<chrono::Utc>)> {
     let now = Utc::now();
-    let next_mark = next_scheduled_mark(now, second_offset, millisecond_offset)?;
+    let (next_mark, run_at) = next_jittered_mark(
+        now,
+        task_descriptor,
+        chrono::Duration::minutes(1),
+        |after| next_scheduled_mark(after, second_offset, millisecond_offset),
+    )?;
 
     // Convert that difference into std::time::Duration for tokio.
-    let delay = next_mark - now;
+    let delay = run_at - now;
     let delay = delay.to_std().map_err(|e| {
         anyhow!(
             "Could not schedule job at next_scheduled_mark(). Chrono->Std error: {:?}",
@@ -72,8 +79,18 @@ pub async fn schedule_task_every_minute_at<F, Fut>(
 ) -> Result<()>
 where
     F: Fn(Arc<ServerState>) -> Fut + Send + Sync + 'static,
-    Fut: std::future::Future<Output = ()> + Send + 'static,
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: JobResult + Send + 'static,
 {
+    state
+        .job_monitor
+        .register(
+            &task_descriptor,
+            format!("every minute at :{second_offset:02}"),
+            true,
+        )
+        .await;
+
     let mut initialized = false;
     let mut scheduled_run_time: Option<chrono::DateTime<chrono::Utc>> = None;
 
@@ -108,11 +125,19 @@ where
             None => next_mark,
         };
 
+        state
+            .job_monitor
+            .set_next_run(&task_descriptor, this_run_time)
+            .await;
         tokio::time::sleep(delay).await;
 
-        let start = tokio::time::Instant::now();
-        task(Arc::clone(&state)).await;
-        let elapsed = start.elapsed();
+        spawn_run(
+            &state,
+            &task_descriptor,
+            this_run_time,
+            task(Arc::clone(&state)),
+            false,
+        );
 
         // Efficiently compute the next run time by simply adding one minute
         let next_run_time = this_run_time + Duration::minutes(1);
@@ -127,9 +152,8 @@ where
         info!(
             task_name = %task_descriptor,
             next_run_time = %next_run_time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
-            duration = %format!("{:?}", elapsed),
             next_delay_human = %format_duration(next_delay),
-            "Scheduled task ran"
+            "Scheduled task dispatched"
         );
 
         scheduled_run_time = Some(next_run_time);
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use tracing::{debug, error, info};
 
 use crate::init::state::ServerState;
+use crate::jobs::job_funcs::job_runner::{JobResult, spawn_run};
 use crate::util::time::duration_formatter::format_duration;
 
 /// Calculate the next UTC DateTime that lands on either the current or next second boundary,
@@ -71,8 +72,18 @@ pub async fn schedule_task_every_second_at<F, Fut>(
 ) -> Result<()>
 where
     F: Fn(Arc<ServerState>) -> Fut + Send + Sync + 'static,
-    Fut: std::future::Future<Output = ()> + Send + 'static,
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: JobResult + Send + 'static,
 {
+    state
+        .job_monitor
+        .register(
+            &task_descriptor,
+            format!("every second at +{millisecond_offset}ms {microsecond_offset}us"),
+            false,
+        )
+        .await;
+
     let mut initialized = false;
     let mut scheduled_run_time: Option<DateTime<Utc>> = None;
 
@@ -111,11 +122,19 @@ where
             None => next_mark,
         };
 
+        state
+            .job_monitor
+            .set_next_run(&task_descriptor, this_run_time)
+            .await;
         tokio::time::sleep(delay).await;
 
-        let start = tokio::time::Instant::now();
-        task(Arc::clone(&state)).await;
-        let elapsed = start.elapsed();
+        spawn_run(
+            &state,
+            &task_descriptor,
+            this_run_time,
+            task(Arc::clone(&state)),
+            false,
+        );
 
         // Efficiently compute the next run time by simply adding one second to the previously scheduled (not actual) scheduled time.
         let next_run_time = this_run_time + Duration::seconds(1);
@@ -130,9 +149,8 @@ where
         debug!(
             task_name = %task_descriptor,
             next_run_time = %next_run_time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
-            duration = %format!("{:?}", elapsed),
             next_delay_human = %format_duration(next_delay),
-            "Scheduled task ran"
+            "Scheduled task dispatched"
         );
 
         scheduled_run_time = Some(next_run_time);
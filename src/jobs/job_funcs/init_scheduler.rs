@@ -6,21 +6,13 @@ use tracing::{error, info};
 use crate::{
     init::state::ServerState,
     jobs::{
-        auth::{
-            invalidate_sessions::invalidate_sessions,
-            purge_nonverified_users::purge_nonverified_users,
-            update_system_stats::update_system_stats,
-        },
         job_funcs::{
-            every_day::schedule_task_every_day_at, every_hour::schedule_task_every_hour_at,
-            every_minute::schedule_task_every_minute_at,
-            every_second::schedule_task_every_second_at,
-        },
-        maintenance::{
-            compress_logs::compress_old_logs, flush_photograph_views::flush_photograph_views,
-            flush_visitor_logs::flush_visitor_logs, prune_live_chat::prune_live_chat_state,
-            prune_photograph_batches::prune_photograph_batches,
+            delayed::run_delayed_task_dispatcher,
+            email_outbox::run_email_outbox_sender,
+            overlap::OverlapPolicy,
+            registry::{JobDefinition, paused_at_startup, registered_jobs, run_schedule},
         },
+        queue::image_processing::{recover_unfinished_processing, run_image_processing_dispatcher},
     },
 };
 
@@ -78,131 +70,66 @@ pub async fn task_init(state: Arc<ServerState>) -> anyhow::Result<()> {
         }
     });
 
-    {
-        let state = Arc::clone(&state);
-        supervise("INVALIDATE_EXPIRED_SESSIONS", move || {
-            let state = Arc::clone(&state);
-            schedule_task_every_hour_at(
-                state,
-                move |coroutine_state: Arc<ServerState>| async move {
-                    invalidate_sessions(coroutine_state).await
-                },
-                String::from("INVALIDATE_EXPIRED_SESSIONS"),
-                30, // minutes
-                00, // seconds
-            )
-        });
-    }
-
-    {
-        let state = Arc::clone(&state);
-        supervise("PURGE_NONVERIFIED_USERS", move || {
-            let state = Arc::clone(&state);
-            schedule_task_every_hour_at(
-                state,
-                move |coroutine_state: Arc<ServerState>| async move {
-                    purge_nonverified_users(coroutine_state).await
-                },
-                String::from("PURGE_NONVERIFIED_USERS"),
-                00, // minutes
-                00, // seconds
-            )
-        });
-    }
-
-    {
-        let state = Arc::clone(&state);
-        supervise("UPDATE_SYSTEM_STATS", move || {
-            let state = Arc::clone(&state);
-            schedule_task_every_second_at(
-                state,
-                move |coroutine_state: Arc<ServerState>| async move {
-                    update_system_stats(coroutine_state).await
-                },
-                String::from("UPDATE_SYSTEM_STATS"),
-                0,
-                0,
-            )
-        });
-    }
-
-    {
-        let state = Arc::clone(&state);
-        supervise("COMPRESS_OLD_LOGS", move || {
+    // The dispatcher owns the queue receiver (taken exactly once), so it is
+    // spawned directly rather than under `supervise`; each job runs on its own
+    // task, so a panicking job cannot take the dispatcher down.
+    match state.image_processing_queue.take_receiver() {
+        Some(receiver) => {
+            tokio::spawn(run_image_processing_dispatcher(
+                Arc::clone(&state),
+                receiver,
+            ));
             let state = Arc::clone(&state);
-            schedule_task_every_day_at::<_, _>(
-                state,
-                move |coroutine_state: Arc<ServerState>| async move {
-                    compress_old_logs(coroutine_state).await
-                },
-                String::from("COMPRESS_OLD_LOGS"),
-                6,
-                30,
-                00,
-            )
-        });
+            tokio::spawn(async move {
+                if let Err(e) = recover_unfinished_processing(state).await {
+                    error!(error = ?e, "Failed to recover unfinished photograph processing");
+                }
+            });
+        }
+        None => error!("Image processing queue receiver already taken"),
     }
 
     {
         let state = Arc::clone(&state);
-        supervise("FLUSH_VISITOR_LOGS", move || {
-            let state = Arc::clone(&state);
-            schedule_task_every_minute_at(
-                state,
-                move |coroutine_state: Arc<ServerState>| async move {
-                    flush_visitor_logs(coroutine_state).await
-                },
-                String::from("FLUSH_VISITOR_LOGS"),
-                0,
-                0,
-            )
+        supervise("DELAYED_TASK_DISPATCHER", move || {
+            run_delayed_task_dispatcher(Arc::clone(&state))
         });
     }
 
     {
         let state = Arc::clone(&state);
-        supervise("PRUNE_LIVE_CHAT_STATE", move || {
-            let state = Arc::clone(&state);
-            schedule_task_every_minute_at(
-                state,
-                move |coroutine_state: Arc<ServerState>| async move {
-                    prune_live_chat_state(coroutine_state).await
-                },
-                String::from("PRUNE_LIVE_CHAT_STATE"),
-                30,
-                0,
-            )
+        supervise("EMAIL_OUTBOX_SENDER", move || {
+            run_email_outbox_sender(Arc::clone(&state))
         });
     }
 
-    {
-        let state = Arc::clone(&state);
-        supervise("FLUSH_PHOTOGRAPH_VIEWS", move || {
-            let state = Arc::clone(&state);
-            schedule_task_every_minute_at(
-                state,
-                move |coroutine_state: Arc<ServerState>| async move {
-                    flush_photograph_views(coroutine_state).await
-                },
-                String::from("FLUSH_PHOTOGRAPH_VIEWS"),
-                15,
-                0,
-            )
-        });
-    }
+    for job in registered_jobs() {
+        if job.cluster_exclusive {
+            state.job_monitor.mark_cluster_exclusive(job.name).await;
+        }
+        if job.overlap == OverlapPolicy::Queue {
+            state.job_monitor.mark_queue_overlaps(job.name).await;
+        }
+        if paused_at_startup(job.name) {
+            info!(task_name = job.name, "Job starts paused");
+            state.job_monitor.set_paused(job.name, true).await;
+        }
 
-    {
         let state = Arc::clone(&state);
-        supervise("PRUNE_PHOTOGRAPH_BATCHES", move || {
-            let state = Arc::clone(&state);
-            schedule_task_every_minute_at(
-                state,
-                move |coroutine_state: Arc<ServerState>| async move {
-                    prune_photograph_batches(coroutine_state).await
-                },
-                String::from("PRUNE_PHOTOGRAPH_BATCHES"),
-                45,
-                0,
+        let JobDefinition {
+            name,
+            schedule,
+            catch_up,
+            task,
+            ..
+        } = job;
+        supervise(name, move || {
+            run_schedule(
+                Arc::clone(&state),
+                name,
+                schedule.clone(),
+                catch_up,
+                Arc::clone(&task),
             )
         });
     }
@@ -0,0 +1,115 @@
+//! Optional random jitter for the `next_scheduled_*_delay` helpers and
+//! `next_recurrence_delay`.
+//!
+//! `JOB_JITTER_SECS` (default 0, off) sets a bound J for every job and
+//! `JOB_JITTER_SECS_<JOB_NAME>` overrides it per job. Each run then fires at a
+//! random point in `[mark - J, mark + J]`, so several deployments or heavy jobs
+//! sharing a mark do not all start in the same second. J is capped below half
+//! the schedule period. The mark itself is unchanged: it is still what
+//! `run_job` records and what the cluster lock and catch-up compare against.
+//!
+//! The offset is a keyed hash of (job, mark) with a per-process random key, so
+//! it is stable for one mark within a process and a scheduler that fired early
+//! can tell the mark is done instead of running it twice.
+
+use std::hash::{BuildHasher, RandomState};
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::init::app_config;
+
+fn jitter_key() -> &'static RandomState {
+    static KEY: OnceLock<RandomState> = OnceLock::new();
+    KEY.get_or_init(RandomState::new)
+}
+
+/// Jitter bound for one job, capped below half of `period`.
+pub fn max_jitter(task_descriptor: &str, period: Duration) -> Duration {
+    let jobs = &app_config::config().jobs;
+    let secs = jobs
+        .job(task_descriptor)
+        .and_then(|job| job.jitter_secs)
+        .unwrap_or(jobs.jitter_secs);
+    Duration::seconds(secs).min(period / 2 - Duration::seconds(1))
+}
+
+/// Offset in `[-max, max]` for this job's `mark`.
+fn offset(task_descriptor: &str, mark: DateTime<Utc>, max: Duration) -> Duration {
+    let span = max.num_milliseconds();
+    if span <= 0 {
+        return Duration::zero();
+    }
+    let hash = jitter_key().hash_one((task_descriptor, mark.timestamp_millis()));
+    Duration::milliseconds((hash % (2 * span as u64 + 1)) as i64 - span)
+}
+
+/// First mark whose jittered run time is still ahead of `now`, with that run
+/// time. `next_mark` returns the first mark strictly after its argument.
+pub fn next_jittered_mark(
+    now: DateTime<Utc>,
+    task_descriptor: &str,
+    period: Duration,
+    next_mark: impl Fn(DateTime<Utc>) -> Result<DateTime<Utc>>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let max = max_jitter(task_descriptor, period);
+    jittered_mark(now, task_descriptor, max, next_mark)
+}
+
+fn jittered_mark(
+    now: DateTime<Utc>,
+    task_descriptor: &str,
+    max: Duration,
+    next_mark: impl Fn(DateTime<Utc>) -> Result<DateTime<Utc>>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut mark = next_mark(now - max)?;
+    loop {
+        let run_at = mark + offset(task_descriptor, mark, max);
+        if run_at > now {
+            return Ok((mark, run_at));
+        }
+        mark = next_mark(mark)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_hour(after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let secs = after.timestamp();
+        let next = (secs.div_euclid(3600) + 1) * 3600;
+        Ok(DateTime::from_timestamp(next, 0).unwrap())
+    }
+
+    #[test]
+    fn offset_is_bounded_and_stable() {
+        let max = Duration::minutes(5);
+        let mark = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        for i in 0..100 {
+            let name = format!("JOB_{i}");
+            let first = offset(&name, mark, max);
+            assert!(first >= -max && first <= max);
+            assert_eq!(first, offset(&name, mark, max));
+        }
+        assert_eq!(offset("JOB", mark, Duration::zero()), Duration::zero());
+    }
+
+    #[test]
+    fn fired_mark_is_not_repeated() {
+        let max = Duration::minutes(5);
+        let mut now = DateTime::from_timestamp(1_800_000_123, 0).unwrap();
+        let mut last_mark = None;
+        for _ in 0..50 {
+            let (mark, run_at) = jittered_mark(now, "JOB", max, every_hour).unwrap();
+            assert!(run_at > now);
+            assert!((run_at - mark).abs() <= max);
+            if let Some(last_mark) = last_mark {
+                assert_eq!(mark, last_mark + Duration::hours(1));
+            }
+            last_mark = Some(mark);
+            now = run_at;
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Cluster-wide exclusivity for scheduled jobs.
+//!
+//! Every instance behind the load balancer runs the same schedulers. Jobs
+//! marked cluster-exclusive in the job registry (DB or bucket-wide work, not
+//! per-process buffers) take a session-level `pg_try_advisory_lock` keyed by
+//! the job name for the duration of one tick. The instance that gets it then
+//! checks `job_executions` for a successful run of the same mark, so an
+//! instance whose clock lags cannot repeat a tick that just finished
+//! elsewhere. `JOB_CLUSTER_LOCKS=off` disables locking for single-instance
+//! deployments.
+
+use diesel::{QueryableByName, sql_query, sql_types::Bool, sql_types::Text};
+use diesel_async::{AsyncPgConnection, RunQueryDsl, pooled_connection::bb8::PooledConnection};
+use tracing::warn;
+
+use crate::init::app_config;
+use crate::init::state::ServerState;
+
+pub fn cluster_locks_enabled() -> bool {
+    app_config::config().jobs.cluster_locks
+}
+
+#[derive(QueryableByName)]
+struct LockResult {
+    #[diesel(sql_type = Bool)]
+    locked: bool,
+}
+
+/// Holds the advisory lock on its own pooled connection. Session locks belong
+/// to the connection, so it must be unlocked before going back to the pool;
+/// call [`JobLock::release`], or the drop impl unlocks on a spawned task.
+pub struct JobLock {
+    conn: Option<PooledConnection<'static, AsyncPgConnection>>,
+    job_name: String,
+}
+
+impl JobLock {
+    /// `Ok(None)` when another instance holds the lock.
+    pub async fn try_acquire(state: &ServerState, job_name: &str) -> anyhow::Result<Option<Self>> {
+        let mut conn = state.pool.get_owned().await?;
+        let result: LockResult =
+            sql_query("SELECT pg_try_advisory_lock(hashtextextended($1, 0)) AS locked")
+                .bind::<Text, _>(job_name)
+                .get_result(&mut conn)
+                .await?;
+        Ok(result.locked.then(|| Self {
+            conn: Some(conn),
+            job_name: job_name.to_string(),
+        }))
+    }
+
+    pub async fn release(mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            unlock(&mut conn, &self.job_name).await;
+        }
+    }
+}
+
+impl Drop for JobLock {
+    fn drop(&mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            let job_name = std::mem::take(&mut self.job_name);
+            tokio::spawn(async move { unlock(&mut conn, &job_name).await });
+        }
+    }
+}
+
+async fn unlock(conn: &mut AsyncPgConnection, job_name: &str) {
+    if let Err(e) = sql_query("SELECT pg_advisory_unlock(hashtextextended($1, 0))")
+        .bind::<Text, _>(job_name)
+        .execute(conn)
+        .await
+    {
+        warn!(task_name = %job_name, error = ?e, "Failed to release job advisory lock");
+    }
+}
@@ -0,0 +1,265 @@
+//! Execution wrapper shared by every scheduler helper.
+//!
+//! `run_job` runs one tick of a job on its own task (so a panic is caught and
+//! reported instead of killing the scheduler), times it, updates the
+//! in-memory `JobMonitor` and appends a row to `job_executions`. Ticks of
+//! cluster-exclusive jobs first take the advisory lock in `job_lock`; a tick
+//! that overlaps the job's previous run is handled by `overlap`. A run is
+//! aborted when it exceeds its `timeout` budget or is cancelled from the admin
+//! API. Once shutdown has begun no new run starts.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tracing::{Instrument, Span, debug, error, field::Empty, info, warn};
+
+use crate::{
+    domain::job::execution::{JobExecution, JobExecutionInsertable, JobOutcome},
+    init::state::ServerState,
+    jobs::job_funcs::{
+        alerting::{should_alert, spawn_failure_alert},
+        catch_up::record_job_run,
+        job_lock::{JobLock, cluster_locks_enabled},
+        overlap::claim_run,
+        timeout::job_timeout,
+    },
+};
+
+/// A finished job: details to record on success, or the error.
+pub type JobOutput = Result<Option<serde_json::Value>, String>;
+
+/// What a job future resolves to. Jobs that handle their own errors return
+/// `()`; jobs returning `anyhow::Result<()>` get their error recorded, and
+/// `anyhow::Result<Option<serde_json::Value>>` also records details
+/// (`job_executions.job_details`).
+pub trait JobResult {
+    fn into_job_result(self) -> JobOutput;
+}
+
+impl JobResult for () {
+    fn into_job_result(self) -> JobOutput {
+        Ok(None)
+    }
+}
+
+impl JobResult for anyhow::Result<()> {
+    fn into_job_result(self) -> JobOutput {
+        self.map(|()| None).map_err(|e| format!("{e:#}"))
+    }
+}
+
+impl JobResult for anyhow::Result<Option<serde_json::Value>> {
+    fn into_job_result(self) -> JobOutput {
+        self.map_err(|e| format!("{e:#}"))
+    }
+}
+
+impl JobResult for JobOutput {
+    fn into_job_result(self) -> JobOutput {
+        self
+    }
+}
+
+/// Run one execution of `task_descriptor` scheduled for `scheduled_for`.
+#[tracing::instrument(
+    name = "job.run",
+    skip_all,
+    fields(
+        otel.name = %task_descriptor,
+        otel.status_code = Empty,
+        task_name = %task_descriptor,
+        scheduled_for = %scheduled_for,
+        job.outcome = Empty,
+    )
+)]
+pub async fn run_job<Fut>(
+    state: &Arc<ServerState>,
+    task_descriptor: &str,
+    scheduled_for: DateTime<Utc>,
+    job: Fut,
+) -> JobOutcome
+where
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: JobResult + Send + 'static,
+{
+    let Some(_guard) = state.shutdown.job_guard() else {
+        info!(task_name = %task_descriptor, "Shutting down; not starting scheduled task");
+        return JobOutcome::Skipped;
+    };
+
+    if state.job_monitor.is_paused(task_descriptor).await {
+        debug!(task_name = %task_descriptor, "Job paused; skipping tick");
+        return JobOutcome::Skipped;
+    }
+
+    let Some(_slot) = claim_run(state, task_descriptor).await else {
+        return JobOutcome::Skipped;
+    };
+
+    let mut lock = None;
+    let mut lock_wait_ms = None;
+    if cluster_locks_enabled()
+        && state
+            .job_monitor
+            .is_cluster_exclusive(task_descriptor)
+            .await
+    {
+        let started_at = Utc::now();
+        let lock_start = tokio::time::Instant::now();
+        match acquire_tick(state, task_descriptor, scheduled_for).await {
+            Ok(Some(acquired)) => lock = Some(acquired),
+            Ok(None) => {
+                debug!(task_name = %task_descriptor, "Tick held or already run by another instance; skipping");
+                let execution = JobExecution {
+                    scheduled_for,
+                    started_at,
+                    duration_ms: 0,
+                    outcome: JobOutcome::Skipped,
+                    error: None,
+                    lock_wait_ms: Some(lock_start.elapsed().as_millis() as i64),
+                    details: None,
+                };
+                finish(state, task_descriptor, &execution).await;
+                return JobOutcome::Skipped;
+            }
+            // Fail open: a job that cannot reach Postgres for the lock fails
+            // on its own queries anyway, and that error gets recorded.
+            Err(e) => {
+                warn!(task_name = %task_descriptor, error = ?e, "Could not take job advisory lock; running unlocked")
+            }
+        }
+        lock_wait_ms = Some(lock_start.elapsed().as_millis() as i64);
+    }
+
+    let cancel = state.job_monitor.start_run(task_descriptor).await;
+    let timeout = job_timeout(task_descriptor);
+    let started_at = Utc::now();
+    let start = tokio::time::Instant::now();
+
+    // The job's own DB and storage spans nest under this run.
+    let mut handle = tokio::spawn(job.in_current_span());
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    let (outcome, error, details) = tokio::select! {
+        joined = &mut handle => match joined {
+            Ok(output) => match output.into_job_result() {
+                Ok(details) => (JobOutcome::Success, None, details),
+                Err(e) => (JobOutcome::Failure, Some(e), None),
+            },
+            Err(join_err) => (JobOutcome::Panicked, Some(join_err.to_string()), None),
+        },
+        _ = deadline => {
+            handle.abort();
+            warn!(task_name = %task_descriptor, ?timeout, "Scheduled task exceeded its timeout; cancelled");
+            (
+                JobOutcome::TimedOut,
+                Some(format!("exceeded timeout of {}s", timeout.unwrap_or_default().as_secs())),
+                None,
+            )
+        }
+        _ = cancel.cancelled() => {
+            handle.abort();
+            warn!(task_name = %task_descriptor, "Scheduled task cancelled");
+            (JobOutcome::Cancelled, Some(String::from("cancelled by admin")), None)
+        }
+    };
+    let elapsed = start.elapsed();
+    if let Some(lock) = lock {
+        lock.release().await;
+    }
+
+    if let Some(error) = &error
+        && matches!(outcome, JobOutcome::Failure | JobOutcome::Panicked)
+    {
+        error!(task_name = %task_descriptor, outcome = outcome.as_str(), error = %error, duration = ?elapsed, "Scheduled task failed");
+    }
+
+    let execution = JobExecution {
+        scheduled_for,
+        started_at,
+        duration_ms: elapsed.as_millis() as i64,
+        outcome,
+        error,
+        lock_wait_ms,
+        details,
+    };
+    finish(state, task_descriptor, &execution).await;
+
+    let span = Span::current();
+    span.record("job.outcome", outcome.as_str());
+    if matches!(
+        outcome,
+        JobOutcome::Failure | JobOutcome::Panicked | JobOutcome::TimedOut
+    ) {
+        span.record("otel.status_code", "ERROR");
+    }
+
+    outcome
+}
+
+/// Fire one tick on its own task so the scheduler keeps its cadence while a
+/// run is in flight. `record_success` also writes `job_runs` (catch-up).
+pub fn spawn_run<Fut>(
+    state: &Arc<ServerState>,
+    task_descriptor: &str,
+    scheduled_for: DateTime<Utc>,
+    job: Fut,
+    record_success: bool,
+) where
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: JobResult + Send + 'static,
+{
+    let state = Arc::clone(state);
+    let task_descriptor = task_descriptor.to_string();
+    tokio::spawn(async move {
+        let outcome = run_job(&state, &task_descriptor, scheduled_for, job).await;
+        if record_success && outcome == JobOutcome::Success {
+            record_job_run(&state, &task_descriptor).await;
+        }
+    });
+}
+
+/// `Ok(None)` when another instance holds the lock or already ran the mark.
+async fn acquire_tick(
+    state: &ServerState,
+    task_descriptor: &str,
+    scheduled_for: DateTime<Utc>,
+) -> anyhow::Result<Option<JobLock>> {
+    let Some(lock) = JobLock::try_acquire(state, task_descriptor).await? else {
+        return Ok(None);
+    };
+    if state
+        .job_mark_succeeded(task_descriptor, scheduled_for)
+        .await?
+    {
+        lock.release().await;
+        return Ok(None);
+    }
+    Ok(Some(lock))
+}
+
+async fn finish(state: &Arc<ServerState>, task_descriptor: &str, execution: &JobExecution) {
+    let finished = state
+        .job_monitor
+        .finish_run(task_descriptor, execution)
+        .await;
+    if (execution.outcome != JobOutcome::Success || finished.persist_successes)
+        && let Err(e) = state
+            .record_job_execution(JobExecutionInsertable::new(task_descriptor, execution))
+            .await
+    {
+        warn!(task_name = %task_descriptor, error = ?e, "Failed to record job execution");
+    }
+    if execution.outcome.is_failure() && should_alert(finished.consecutive_failures) {
+        spawn_failure_alert(
+            Arc::clone(state),
+            task_descriptor.to_string(),
+            finished.consecutive_failures,
+            finished.failure_streak,
+        );
+    }
+}
@@ -1,8 +1,18 @@
-pub mod every_day;
+pub mod alerting;
+pub mod catch_up;
+pub mod delayed;
+pub mod email_outbox;
 pub mod every_hour;
+pub mod every_interval;
 pub mod every_minute;
-pub mod every_month;
 pub mod every_second;
-pub mod every_week;
-pub mod every_year;
 pub mod init_scheduler;
+pub mod jitter;
+pub mod job_lock;
+pub mod job_runner;
+pub mod overlap;
+pub mod recurrence;
+pub mod recurring;
+pub mod registry;
+pub mod timeout;
+pub mod timezone;
@@ -0,0 +1,64 @@
+//! What happens when a tick fires while the job's previous run is still in
+//! flight.
+//!
+//! The schedulers dispatch each tick on its own task (`job_runner::spawn_run`)
+//! and keep their cadence; `run_job` then claims the job's run slot in
+//! `JobMonitor`. A job never runs twice at once. With `OverlapPolicy::Skip`
+//! (the default) the overlapping tick is dropped; with `Queue` it waits for
+//! the run in flight and then runs, with at most one tick waiting.
+//! `JOB_OVERLAP_<JOB_NAME>=skip|queue` overrides the registry default.
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use tokio::sync::OwnedMutexGuard;
+use tracing::info;
+
+use crate::init::app_config;
+use crate::init::state::ServerState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Drop a tick that fires while the previous run is in flight.
+    Skip,
+    /// Run it once the previous run finishes (one tick at most waits).
+    Queue,
+}
+
+impl OverlapPolicy {
+    pub fn for_job(task_descriptor: &str, default: Self) -> Self {
+        match app_config::config()
+            .jobs
+            .job(task_descriptor)
+            .and_then(|job| job.overlap.as_deref())
+            .map(|overlap| overlap.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("skip") => Self::Skip,
+            Some("queue") => Self::Queue,
+            _ => default,
+        }
+    }
+}
+
+/// Held for one run of the job. `None` when the tick is dropped.
+pub async fn claim_run(state: &ServerState, task_descriptor: &str) -> Option<OwnedMutexGuard<()>> {
+    let slot = state.job_monitor.run_slot(task_descriptor).await;
+    if let Ok(guard) = Arc::clone(&slot.lock).try_lock_owned() {
+        return Some(guard);
+    }
+
+    if !state.job_monitor.queues_overlaps(task_descriptor).await {
+        info!(task_name = %task_descriptor, "Previous run still in flight; skipping tick");
+        return None;
+    }
+    if slot.queued.swap(true, Ordering::AcqRel) {
+        info!(task_name = %task_descriptor, "Previous run in flight and a tick already queued; skipping tick");
+        return None;
+    }
+
+    info!(task_name = %task_descriptor, "Previous run still in flight; queueing tick");
+    let guard = Arc::clone(&slot.lock).lock_owned().await;
+    slot.queued.store(false, Ordering::Release);
+    Some(guard)
+}
@@ -0,0 +1,629 @@
+//! Wall-clock recurrences for the calendar schedules: daily, weekly, monthly,
+//! yearly and cron.
+//!
+//! A [`Recurrence`] only answers "which mark comes next" (or "which came
+//! last") after an instant; the scheduler loop lives in `recurring.rs`. Marks
+//! are local times in the recurrence's timezone and go through
+//! `timezone::resolve_local`, so the DST rules documented there apply to every
+//! kind. Neither lookup panics: a rule that can never fire (e.g. a hand-built
+//! `Yearly { month: 13, .. }`) yields `None` after a bounded search.
+//!
+//! Monthly and yearly days past the end of a month are clamped to its last
+//! day (day 31 fires on Feb 28/29); cron days are not.
+
+use std::fmt;
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::jobs::job_funcs::timezone::resolve_local;
+
+/// Days searched for a matching date. Covers the eight-year gap between two
+/// Feb 29ths around 2100.
+const MAX_SEARCH_DAYS: usize = 8 * 366 + 1;
+
+/// How far a local time can sit from its instant's neighbours across a DST
+/// shift; backwards searches start this far past the given instant.
+const DST_SLACK: Duration = Duration::hours(3);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily {
+        time: NaiveTime,
+        tz: Tz,
+    },
+    Weekly {
+        weekday: Weekday,
+        time: NaiveTime,
+        tz: Tz,
+    },
+    /// `day` in 1..=31, clamped to the month's last day.
+    Monthly {
+        day: u32,
+        time: NaiveTime,
+        tz: Tz,
+    },
+    /// `month` in 1..=12, `day` in 1..=31 clamped like `Monthly`.
+    Yearly {
+        month: u32,
+        day: u32,
+        time: NaiveTime,
+        tz: Tz,
+    },
+    Cron {
+        cron: Cron,
+        tz: Tz,
+    },
+}
+
+fn clock(hour: u32, minute: u32, second: u32) -> Result<NaiveTime> {
+    NaiveTime::from_hms_opt(hour, minute, second)
+        .ok_or_else(|| anyhow!("invalid time {hour:02}:{minute:02}:{second:02}"))
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    (28..=31)
+        .rev()
+        .find(|day| NaiveDate::from_ymd_opt(year, month, *day).is_some())
+        .unwrap_or(0)
+}
+
+/// Whether `date` is the `day`th of its month, clamped to the last day.
+fn is_clamped_day(date: NaiveDate, day: u32) -> bool {
+    date.day() == day.min(days_in_month(date.year(), date.month()))
+}
+
+impl Recurrence {
+    pub fn daily(hour: u32, minute: u32, second: u32, tz: Tz) -> Result<Self> {
+        Ok(Self::Daily {
+            time: clock(hour, minute, second)?,
+            tz,
+        })
+    }
+
+    pub fn weekly(weekday: Weekday, hour: u32, minute: u32, second: u32, tz: Tz) -> Result<Self> {
+        Ok(Self::Weekly {
+            weekday,
+            time: clock(hour, minute, second)?,
+            tz,
+        })
+    }
+
+    pub fn monthly(day: u32, hour: u32, minute: u32, second: u32, tz: Tz) -> Result<Self> {
+        if !(1..=31).contains(&day) {
+            bail!("day {day} is not in 1..=31");
+        }
+        Ok(Self::Monthly {
+            day,
+            time: clock(hour, minute, second)?,
+            tz,
+        })
+    }
+
+    pub fn yearly(
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+        tz: Tz,
+    ) -> Result<Self> {
+        if !(1..=12).contains(&month) {
+            bail!("month {month} is not in 1..=12");
+        }
+        if !(1..=31).contains(&day) {
+            bail!("day {day} is not in 1..=31");
+        }
+        Ok(Self::Yearly {
+            month,
+            day,
+            time: clock(hour, minute, second)?,
+            tz,
+        })
+    }
+
+    pub fn cron(expr: &str, tz: Tz) -> Result<Self> {
+        Ok(Self::Cron {
+            cron: Cron::parse(expr)?,
+            tz,
+        })
+    }
+
+    pub fn tz(&self) -> Tz {
+        match self {
+            Self::Daily { tz, .. }
+            | Self::Weekly { tz, .. }
+            | Self::Monthly { tz, .. }
+            | Self::Yearly { tz, .. }
+            | Self::Cron { tz, .. } => *tz,
+        }
+    }
+
+    /// Nominal gap between marks, used to cap jitter. For cron it is the
+    /// smallest step of the finest field that has more than one value.
+    pub fn period(&self) -> Duration {
+        match self {
+            Self::Daily { .. } => Duration::days(1),
+            Self::Weekly { .. } => Duration::weeks(1),
+            Self::Monthly { .. } => Duration::days(28),
+            Self::Yearly { .. } => Duration::days(365),
+            Self::Cron { cron, .. } => cron.period(),
+        }
+    }
+
+    fn matches(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Daily { .. } => true,
+            Self::Weekly { weekday, .. } => date.weekday() == *weekday,
+            Self::Monthly { day, .. } => is_clamped_day(date, *day),
+            Self::Yearly { month, day, .. } => date.month() == *month && is_clamped_day(date, *day),
+            Self::Cron { cron, .. } => cron.matches(date),
+        }
+    }
+
+    /// Times of day on a matching date, ascending.
+    fn times(&self) -> Box<dyn DoubleEndedIterator<Item = NaiveTime> + '_> {
+        match self {
+            Self::Daily { time, .. }
+            | Self::Weekly { time, .. }
+            | Self::Monthly { time, .. }
+            | Self::Yearly { time, .. } => Box::new(std::iter::once(*time)),
+            Self::Cron { cron, .. } => Box::new(cron.times()),
+        }
+    }
+
+    fn resolve(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        resolve_local(self.tz(), naive).ok()
+    }
+
+    /// The first mark strictly after `after`.
+    ///
+    /// Resolving local times is monotonic, and a local time at or before
+    /// `after`'s own resolves at or before `after`, so candidates are walked
+    /// in local order from there.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let floor = after.with_timezone(&self.tz()).naive_local();
+        for date in floor.date().iter_days().take(MAX_SEARCH_DAYS) {
+            if !self.matches(date) {
+                continue;
+            }
+            for time in self.times() {
+                let naive = date.and_time(time);
+                if naive <= floor {
+                    continue;
+                }
+                if let Some(mark) = self.resolve(naive)
+                    && mark > after
+                {
+                    return Some(mark);
+                }
+            }
+        }
+        None
+    }
+
+    /// The last mark strictly before `before`.
+    pub fn previous_before(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let ceiling = (before + DST_SLACK).with_timezone(&self.tz()).naive_local();
+        let dates = std::iter::successors(Some(ceiling.date()), NaiveDate::pred_opt);
+        for date in dates.take(MAX_SEARCH_DAYS) {
+            if !self.matches(date) {
+                continue;
+            }
+            for time in self.times().rev() {
+                let naive = date.and_time(time);
+                if naive > ceiling {
+                    continue;
+                }
+                if let Some(mark) = self.resolve(naive)
+                    && mark < before
+                {
+                    return Some(mark);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily { time, tz } => write!(f, "every day at {time} {tz}"),
+            Self::Weekly { weekday, time, tz } => write!(f, "every {weekday} at {time} {tz}"),
+            Self::Monthly { day, time, tz } => {
+                write!(f, "every month on day {day} at {time} {tz}")
+            }
+            Self::Yearly {
+                month,
+                day,
+                time,
+                tz,
+            } => write!(f, "every year on {month:02}-{day:02} at {time} {tz}"),
+            Self::Cron { cron, tz } => write!(f, "cron `{}` {tz}", cron.expr),
+        }
+    }
+}
+
+/// A set of small integers (< 64).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BitSet(u64);
+
+impl BitSet {
+    fn contains(self, value: u32) -> bool {
+        value < 64 && self.0 & (1 << value) != 0
+    }
+
+    fn iter(self) -> impl DoubleEndedIterator<Item = u32> {
+        (0..64).filter(move |value| self.contains(*value))
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Smallest distance between two members, wrapping at `modulus`.
+    fn min_gap(self, modulus: u32) -> u32 {
+        let values: Vec<u32> = self.iter().collect();
+        let wrap = values
+            .first()
+            .zip(values.last())
+            .map(|(first, last)| first + modulus - last);
+        values
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .chain(wrap)
+            .min()
+            .unwrap_or(modulus)
+    }
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A cron expression: `[SEC] MIN HOUR DOM MON DOW`.
+///
+/// Fields take `*`, values, `a-b` ranges, `/step` and comma lists; months and
+/// weekdays also take three-letter names and weekday 7 is Sunday. Seconds
+/// default to 0 when only five fields are given. As in Vixie cron, when both
+/// day fields are restricted a date matching either one fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    expr: String,
+    seconds: BitSet,
+    minutes: BitSet,
+    hours: BitSet,
+    days_of_month: BitSet,
+    months: BitSet,
+    days_of_week: BitSet,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Result<u32> {
+    let lower = value.to_ascii_lowercase();
+    let parsed = match names.iter().position(|name| *name == lower) {
+        // Month names start at 1, weekday names at 0 (= `min`).
+        Some(index) => index as u32 + min,
+        None => value
+            .parse()
+            .map_err(|_| anyhow!("invalid value `{value}`"))?,
+    };
+    if !(min..=max).contains(&parsed) {
+        bail!("`{value}` is not in {min}..={max}");
+    }
+    Ok(parsed)
+}
+
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<BitSet> {
+    let mut set = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| anyhow!("invalid step `{step}`"))?;
+                if step == 0 {
+                    bail!("step must be positive in `{item}`");
+                }
+                (range, Some(step))
+            }
+            None => (item, None),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (
+                parse_value(start, min, max, names)?,
+                parse_value(end, min, max, names)?,
+            ),
+            // `a/n` runs from `a` to the field maximum.
+            None if step.is_some() => (parse_value(range, min, max, names)?, max),
+            None => {
+                let value = parse_value(range, min, max, names)?;
+                (value, value)
+            }
+        };
+        if start > end {
+            bail!("range `{range}` is backwards");
+        }
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(BitSet(set))
+}
+
+impl Cron {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let (seconds, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => bail!("expected 5 or 6 cron fields, got {n}"),
+        };
+        let [minutes, hours, days_of_month, months, days_of_week] = rest else {
+            bail!("expected 5 or 6 cron fields");
+        };
+        let mut days_of_week_set = parse_field(days_of_week, 0, 7, &WEEKDAY_NAMES)?;
+        if days_of_week_set.contains(7) {
+            days_of_week_set = BitSet((days_of_week_set.0 | 1) & !(1 << 7));
+        }
+        let cron = Self {
+            expr: fields.join(" "),
+            seconds: parse_field(seconds, 0, 59, &[])?,
+            minutes: parse_field(minutes, 0, 59, &[])?,
+            hours: parse_field(hours, 0, 23, &[])?,
+            days_of_month: parse_field(days_of_month, 1, 31, &[])?,
+            months: parse_field(months, 1, 12, &MONTH_NAMES)?,
+            days_of_week: days_of_week_set,
+            any_day_of_month: days_of_month.starts_with('*'),
+            any_day_of_week: days_of_week.starts_with('*'),
+        };
+        // Only a day-of-month-only rule can ask for a date that never exists
+        // (e.g. `0 0 30 2 *`); refuse it rather than search for years.
+        if !cron.any_day_of_month
+            && cron.any_day_of_week
+            && !cron.months.iter().any(|month| {
+                cron.days_of_month
+                    .iter()
+                    .any(|day| day <= days_in_month(2000, month))
+            })
+        {
+            bail!("`{expr}` never matches a date");
+        }
+        Ok(cron)
+    }
+
+    fn matches(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(date.month()) {
+            return false;
+        }
+        let by_month_day = self.days_of_month.contains(date.day());
+        let by_weekday = self
+            .days_of_week
+            .contains(date.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => by_weekday,
+            (false, true) => by_month_day,
+            (false, false) => by_month_day || by_weekday,
+        }
+    }
+
+    fn times(&self) -> impl DoubleEndedIterator<Item = NaiveTime> + '_ {
+        self.hours.iter().flat_map(move |hour| {
+            self.minutes.iter().flat_map(move |minute| {
+                self.seconds
+                    .iter()
+                    .filter_map(move |second| NaiveTime::from_hms_opt(hour, minute, second))
+            })
+        })
+    }
+
+    fn period(&self) -> Duration {
+        if self.seconds.len() > 1 {
+            Duration::seconds(self.seconds.min_gap(60).into())
+        } else if self.minutes.len() > 1 {
+            Duration::minutes(self.minutes.min_gap(60).into())
+        } else if self.hours.len() > 1 {
+            Duration::hours(self.hours.min_gap(24).into())
+        } else {
+            Duration::days(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn monthly_clamps_to_last_day() {
+        let r = Recurrence::monthly(31, 2, 0, 0, chrono_tz::UTC).unwrap();
+        let feb = r.next_after(utc("2026-01-31T03:00:00Z")).unwrap();
+        assert_eq!(feb, utc("2026-02-28T02:00:00Z"));
+        assert_eq!(r.next_after(feb).unwrap(), utc("2026-03-31T02:00:00Z"));
+        assert_eq!(r.previous_before(feb).unwrap(), utc("2026-01-31T02:00:00Z"));
+    }
+
+    #[test]
+    fn yearly_leap_day() {
+        let r = Recurrence::yearly(2, 29, 0, 0, 0, chrono_tz::UTC).unwrap();
+        assert_eq!(
+            r.next_after(utc("2027-01-01T00:00:00Z")).unwrap(),
+            utc("2027-02-28T00:00:00Z")
+        );
+        assert_eq!(
+            r.next_after(utc("2027-03-01T00:00:00Z")).unwrap(),
+            utc("2028-02-29T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn weekly_in_seoul() {
+        let r = Recurrence::weekly(Weekday::Mon, 3, 0, 0, chrono_tz::Asia::Seoul).unwrap();
+        // 2026-10-15 is a Thursday.
+        assert_eq!(
+            r.next_after(utc("2026-10-15T00:00:00Z")).unwrap(),
+            utc("2026-10-18T18:00:00Z")
+        );
+    }
+
+    #[test]
+    fn daily_across_spring_forward() {
+        let r = Recurrence::daily(2, 30, 0, chrono_tz::America::New_York).unwrap();
+        let gap = r.next_after(utc("2026-03-07T12:00:00Z")).unwrap();
+        assert_eq!(gap, utc("2026-03-08T07:00:00Z"));
+        assert_eq!(r.next_after(gap).unwrap(), utc("2026-03-09T06:30:00Z"));
+    }
+
+    #[test]
+    fn cron_fields() {
+        let r = Recurrence::cron("0 */15 9-17 * * mon-fri", chrono_tz::UTC).unwrap();
+        // Friday 17:45 -> Monday 09:00.
+        assert_eq!(
+            r.next_after(utc("2026-10-16T17:45:00Z")).unwrap(),
+            utc("2026-10-19T09:00:00Z")
+        );
+        assert_eq!(r.period(), Duration::minutes(15));
+
+        // Either day field matches when both are restricted.
+        let r = Recurrence::cron("0 0 13 * fri", chrono_tz::UTC).unwrap();
+        assert_eq!(
+            r.next_after(utc("2026-10-15T00:00:00Z")).unwrap(),
+            utc("2026-10-16T00:00:00Z")
+        );
+        assert_eq!(
+            r.next_after(utc("2026-11-07T00:00:00Z")).unwrap(),
+            utc("2026-11-13T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn rejects_bad_cron() {
+        for expr in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "0 0 30 2 *",
+            "0 0 31 apr,jun *",
+        ] {
+            assert!(Cron::parse(expr).is_err(), "{expr}");
+        }
+        assert!(Cron::parse("0 0 29 feb *").is_ok());
+        assert!(Cron::parse("0 0 * * 7").is_ok());
+    }
+
+    fn timezones() -> impl Strategy<Value = Tz> {
+        prop::sample::select(vec![
+            chrono_tz::UTC,
+            chrono_tz::Asia::Seoul,
+            chrono_tz::America::New_York,
+            chrono_tz::Europe::London,
+            chrono_tz::Australia::Lord_Howe,
+            chrono_tz::America::Sao_Paulo,
+        ])
+    }
+
+    fn weekdays() -> impl Strategy<Value = Weekday> {
+        (0u8..7).prop_map(|n| Weekday::try_from(n).unwrap())
+    }
+
+    fn recurrences() -> impl Strategy<Value = Recurrence> {
+        recurrences_in(timezones().boxed())
+    }
+
+    fn recurrences_in(timezones: BoxedStrategy<Tz>) -> impl Strategy<Value = Recurrence> {
+        let clock = (0u32..24, 0u32..60, 0u32..60);
+        prop_oneof![
+            (clock.clone(), timezones.clone())
+                .prop_map(|((h, m, s), tz)| Recurrence::daily(h, m, s, tz).unwrap()),
+            (weekdays(), clock.clone(), timezones.clone())
+                .prop_map(|(w, (h, m, s), tz)| Recurrence::weekly(w, h, m, s, tz).unwrap()),
+            (1u32..=31, clock.clone(), timezones.clone())
+                .prop_map(|(d, (h, m, s), tz)| Recurrence::monthly(d, h, m, s, tz).unwrap()),
+            (1u32..=12, 1u32..=31, clock, timezones.clone()).prop_map(|(mo, d, (h, m, s), tz)| {
+                Recurrence::yearly(mo, d, h, m, s, tz).unwrap()
+            }),
+            (
+                prop::sample::select(vec![
+                    "*/7 * * * * *",
+                    "0 */10 * * * *",
+                    "30 2 * * *",
+                    "0 9-17/4 * * mon-fri",
+                    "0 0 1,15 * *",
+                    "0 12 13 * fri",
+                    "0 0 29 2 *",
+                ]),
+                timezones
+            )
+                .prop_map(|(expr, tz)| Recurrence::cron(expr, tz).unwrap()),
+        ]
+    }
+
+    /// 1970..2100.
+    fn instants() -> impl Strategy<Value = DateTime<Utc>> {
+        (0i64..4_102_444_800).prop_map(|secs| DateTime::from_timestamp(secs, 0).unwrap())
+    }
+
+    proptest! {
+        #[test]
+        fn next_is_strictly_later_and_previous_strictly_earlier(
+            r in recurrences(),
+            at in instants(),
+        ) {
+            let next = r.next_after(at).unwrap();
+            let previous = r.previous_before(at).unwrap();
+            prop_assert!(previous < at && at < next);
+        }
+
+        #[test]
+        fn no_mark_is_skipped(r in recurrences(), at in instants(), fraction in 0.0f64..1.0) {
+            // Any instant between `at` and its next mark has the same next
+            // mark, and that mark's predecessor is at or before `at`.
+            let next = r.next_after(at).unwrap();
+            let span = (next - at).num_seconds() as f64;
+            let between = at + Duration::seconds((span * fraction) as i64);
+            prop_assert_eq!(r.next_after(between), Some(next));
+            prop_assert!(r.previous_before(next).unwrap() <= at);
+        }
+
+        #[test]
+        fn utc_marks_land_on_the_rule(
+            r in recurrences_in(Just(chrono_tz::UTC).boxed()),
+            at in instants(),
+        ) {
+            let next = r.next_after(at).unwrap().naive_utc();
+            prop_assert!(r.matches(next.date()));
+            prop_assert!(r.times().any(|time| time == next.time()));
+        }
+
+        #[test]
+        fn never_panics_on_arbitrary_fields(
+            month in any::<u32>(),
+            day in any::<u32>(),
+            at in instants(),
+        ) {
+            let r = Recurrence::Yearly { month, day, time: NaiveTime::default(), tz: chrono_tz::UTC };
+            let _ = r.next_after(at);
+            let _ = r.previous_before(at);
+        }
+
+        #[test]
+        fn cron_parse_never_panics(expr in "[0-9a-z*/,\\- ]{0,40}") {
+            let _ = Cron::parse(&expr);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use chrono::{SecondsFormat, Utc};
+use tracing::{error, info};
+
+use crate::{
+    init::state::ServerState,
+    jobs::job_funcs::{
+        catch_up::{CatchUpPolicy, catch_up_missed_run},
+        jitter::next_jittered_mark,
+        job_runner::{JobResult, spawn_run},
+        recurrence::Recurrence,
+    },
+    util::time::duration_formatter::format_duration,
+};
+
+/// Returns (delay, next_mark) for the next occurrence of `recurrence`.
+pub fn next_recurrence_delay(
+    task_descriptor: &str,
+    recurrence: &Recurrence,
+) -> Result<(tokio::time::Duration, chrono::DateTime<chrono::Utc>)> {
+    let now = Utc::now();
+    let (next_mark, run_at) =
+        next_jittered_mark(now, task_descriptor, recurrence.period(), |after| {
+            recurrence
+                .next_after(after)
+                .ok_or_else(|| anyhow!("{recurrence} has no mark after {after}"))
+        })?;
+
+    let delay = run_at - now;
+    let delay = delay.to_std().map_err(|e| {
+        anyhow!(
+            "Could not schedule job at next_recurrence_delay(). Chrono->Std error: {:?}",
+            e
+        )
+    })?;
+
+    Ok((delay, next_mark))
+}
+
+/// Schedules a task on a calendar recurrence (daily, weekly, monthly, yearly
+/// or cron) in the recurrence's timezone.
+pub async fn schedule_task_on_recurrence<F, Fut>(
+    state: Arc<ServerState>,
+    task: F,
+    task_descriptor: String,
+    recurrence: Recurrence,
+    catch_up: CatchUpPolicy,
+) -> Result<()>
+where
+    F: Fn(Arc<ServerState>) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: JobResult + Send + 'static,
+{
+    state
+        .job_monitor
+        .register(&task_descriptor, recurrence.to_string(), true)
+        .await;
+
+    // The last mark that should have fired is the one before the first mark.
+    if let Ok((_, first_mark)) = next_recurrence_delay(&task_descriptor, &recurrence)
+        && let Some(previous_mark) = recurrence.previous_before(first_mark)
+    {
+        catch_up_missed_run(&state, &task, &task_descriptor, catch_up, previous_mark).await;
+    }
+
+    let mut initialized: bool = false;
+    loop {
+        let (delay, next_mark) = match next_recurrence_delay(&task_descriptor, &recurrence) {
+            Ok((d, nm)) => (d, nm),
+            Err(e) => {
+                error!(
+                    task_name = %task_descriptor,
+                    error = ?e,
+                    "Could not calculate next scheduled time"
+                );
+                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+                continue;
+            }
+        };
+
+        if !initialized {
+            info!(
+                task_name = %task_descriptor,
+                initial_run_time = %next_mark.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                ?delay,
+                delay_human = %format_duration(delay),
+                "Scheduled task initialized"
+            );
+            initialized = true;
+        }
+
+        // Adding a period to the last mark would drift across DST and month
+        // lengths, so each iteration takes the helper's mark.
+        let this_run_time = next_mark;
+
+        state
+            .job_monitor
+            .set_next_run(&task_descriptor, this_run_time)
+            .await;
+        tokio::time::sleep(delay).await;
+
+        spawn_run(
+            &state,
+            &task_descriptor,
+            this_run_time,
+            task(Arc::clone(&state)),
+            true,
+        );
+
+        let Some(next_run_time) = recurrence.next_after(this_run_time) else {
+            error!(
+                task_name = %task_descriptor,
+                %recurrence,
+                "Could not calculate following scheduled time"
+            );
+            continue;
+        };
+        let next_delay = match (next_run_time - Utc::now()).to_std() {
+            Ok(next_delay) => next_delay,
+            Err(e) => {
+                error!(task_name = %task_descriptor, error = ?e, "Scheduled task next delay was negative");
+                recurrence.period().to_std().unwrap_or_default()
+            }
+        };
+
+        info!(
+            task_name = %task_descriptor,
+            next_run_time = %next_run_time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            next_delay_human = %format_duration(next_delay),
+            "Scheduled task dispatched"
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Every scheduled job `task_init` starts, keyed by name.
+//!
+//! [`registered_jobs`] is the single list of jobs with their default
+//! schedule, catch-up policy and cluster exclusivity. At startup
+//! `JOB_SCHEDULE_<JOB_NAME>` replaces a job's schedule (see
+//! [`Schedule::parse`]) and `JOB_PAUSED_<JOB_NAME>=true` starts it paused.
+//! `POST /api/admin/jobs/{job_name}/pause|resume` flips the pause flag at
+//! runtime; a paused job keeps its scheduler loop but skips its ticks.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Weekday;
+use chrono_tz::Tz;
+use tracing::{error, info};
+
+use crate::{
+    init::{app_config, state::ServerState},
+    jobs::{
+        auth::{
+            invalidate_sessions::invalidate_sessions,
+            purge_nonverified_users::purge_nonverified_users,
+            update_system_stats::update_system_stats,
+        },
+        job_funcs::{
+            catch_up::CatchUpPolicy,
+            every_hour::schedule_task_every_hour_at,
+            every_interval::schedule_task_every_interval,
+            every_minute::schedule_task_every_minute_at,
+            every_second::schedule_task_every_second_at,
+            job_runner::{JobOutput, JobResult},
+            overlap::OverlapPolicy,
+            recurrence::{Cron, Recurrence},
+            recurring::schedule_task_on_recurrence,
+        },
+        maintenance::{
+            backup_database::backup_database, compress_logs::compress_old_logs,
+            flush_access_log::flush_access_log, flush_photograph_views::flush_photograph_views,
+            flush_visitor_logs::flush_visitor_logs,
+            flush_wasm_module_loads::flush_wasm_module_loads, prune_access_log::prune_access_log,
+            prune_job_history::prune_job_history, prune_live_chat::prune_live_chat_state,
+            prune_photograph_batches::prune_photograph_batches,
+            purge_cache::purge_expired_cache_entries, purge_soft_deleted::purge_soft_deleted,
+            reconcile_storage_orphans::reconcile_storage_orphans_job,
+        },
+        monitoring::evaluate_alert_rules::evaluate_alert_rules,
+        notifications::notification_digest::send_notification_digests,
+    },
+};
+
+pub type JobFuture = Pin<Box<dyn Future<Output = JobOutput> + Send>>;
+pub type JobFn = Arc<dyn Fn(Arc<ServerState>) -> JobFuture + Send + Sync>;
+
+fn job<F, Fut>(task: F) -> JobFn
+where
+    F: Fn(Arc<ServerState>) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: JobResult,
+{
+    Arc::new(move |state| {
+        let fut = task(state);
+        Box::pin(async move { fut.await.into_job_result() })
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// Every `secs` seconds on epoch-aligned marks.
+    EveryInterval {
+        secs: u32,
+    },
+    EverySecond {
+        millisecond: u32,
+        microsecond: u32,
+    },
+    EveryMinute {
+        second: u32,
+        millisecond: u32,
+    },
+    EveryHour {
+        minute: u32,
+        second: u32,
+    },
+    EveryDay {
+        hour: u32,
+        minute: u32,
+        second: u32,
+        tz: Tz,
+    },
+    EveryWeek {
+        weekday: Weekday,
+        hour: u32,
+        minute: u32,
+        second: u32,
+        tz: Tz,
+    },
+    EveryMonth {
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+        tz: Tz,
+    },
+    EveryYear {
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+        tz: Tz,
+    },
+    Cron {
+        cron: Cron,
+        tz: Tz,
+    },
+}
+
+fn parse_u32(value: &str, max: u32, what: &str) -> Result<u32> {
+    let parsed: u32 = value
+        .parse()
+        .with_context(|| format!("invalid {what} `{value}`"))?;
+    if parsed > max {
+        bail!("{what} `{value}` is out of range");
+    }
+    Ok(parsed)
+}
+
+/// `HH:MM:SS` or `MM:SS` (`parts` fields).
+fn parse_clock(value: &str, parts: usize) -> Result<Vec<u32>> {
+    let fields: Vec<&str> = value.split(':').collect();
+    if fields.len() != parts {
+        bail!("expected {parts} `:`-separated fields in `{value}`");
+    }
+    let limits = [23, 59, 59];
+    fields
+        .iter()
+        .zip(&limits[3 - parts..])
+        .map(|(field, max)| parse_u32(field, *max, "time field"))
+        .collect()
+}
+
+fn parse_tz(value: Option<&str>) -> Result<Tz> {
+    match value {
+        None => Ok(chrono_tz::UTC),
+        Some(name) => name
+            .parse::<Tz>()
+            .map_err(|e| anyhow!("unknown timezone `{name}`: {e}")),
+    }
+}
+
+impl Schedule {
+    /// Parse a schedule override:
+    ///
+    /// - `interval SECS` (1 second to 1 day)
+    /// - `second`
+    /// - `minute SS`
+    /// - `hour MM:SS`
+    /// - `day HH:MM:SS [TZ]`
+    /// - `week WEEKDAY HH:MM:SS [TZ]` (e.g. `week mon 02:00:00 Asia/Seoul`)
+    /// - `month DAY HH:MM:SS [TZ]`
+    /// - `year MM-DD HH:MM:SS [TZ]`
+    /// - `cron [SEC] MIN HOUR DOM MON DOW [TZ]` (see [`Cron`])
+    ///
+    /// `TZ` is a `chrono-tz` name and defaults to UTC.
+    pub fn parse(value: &str) -> Result<Self> {
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        let Some((kind, args)) = fields.split_first() else {
+            bail!("empty schedule");
+        };
+        let schedule = match (kind.to_ascii_lowercase().as_str(), args) {
+            ("interval", [secs]) => {
+                let secs = parse_u32(secs, 86_400, "interval")?;
+                if secs == 0 {
+                    bail!("interval must be at least one second");
+                }
+                Self::EveryInterval { secs }
+            }
+            ("second", []) => Self::EverySecond {
+                millisecond: 0,
+                microsecond: 0,
+            },
+            ("minute", [second]) => Self::EveryMinute {
+                second: parse_u32(second, 59, "second")?,
+                millisecond: 0,
+            },
+            ("hour", [clock]) => {
+                let t = parse_clock(clock, 2)?;
+                Self::EveryHour {
+                    minute: t[0],
+                    second: t[1],
+                }
+            }
+            ("day", [clock, tz @ ..]) if tz.len() <= 1 => {
+                let t = parse_clock(clock, 3)?;
+                Self::EveryDay {
+                    hour: t[0],
+                    minute: t[1],
+                    second: t[2],
+                    tz: parse_tz(tz.first().copied())?,
+                }
+            }
+            ("week", [weekday, clock, tz @ ..]) if tz.len() <= 1 => {
+                let t = parse_clock(clock, 3)?;
+                Self::EveryWeek {
+                    weekday: weekday
+                        .parse::<Weekday>()
+                        .map_err(|_| anyhow!("invalid weekday `{weekday}`"))?,
+                    hour: t[0],
+                    minute: t[1],
+                    second: t[2],
+                    tz: parse_tz(tz.first().copied())?,
+                }
+            }
+            ("month", [day, clock, tz @ ..]) if tz.len() <= 1 => {
+                let t = parse_clock(clock, 3)?;
+                let day = parse_u32(day, 31, "day")?;
+                if day == 0 {
+                    bail!("day must be 1..=31");
+                }
+                Self::EveryMonth {
+                    day,
+                    hour: t[0],
+                    minute: t[1],
+                    second: t[2],
+                    tz: parse_tz(tz.first().copied())?,
+                }
+            }
+            ("year", [date, clock, tz @ ..]) if tz.len() <= 1 => {
+                let t = parse_clock(clock, 3)?;
+                let Some((month, day)) = date.split_once('-') else {
+                    bail!("expected MM-DD, got `{date}`");
+                };
+                let (month, day) = (parse_u32(month, 12, "month")?, parse_u32(day, 31, "day")?);
+                if month == 0 || day == 0 {
+                    bail!("month and day start at 1");
+                }
+                Self::EveryYear {
+                    month,
+                    day,
+                    hour: t[0],
+                    minute: t[1],
+                    second: t[2],
+                    tz: parse_tz(tz.first().copied())?,
+                }
+            }
+            ("cron", fields) => {
+                // A trailing field that names a timezone is the timezone;
+                // no cron field value does.
+                let (fields, tz) = match fields.split_last() {
+                    Some((last, rest)) if last.parse::<Tz>().is_ok() => (rest, Some(*last)),
+                    _ => (fields, None),
+                };
+                Self::Cron {
+                    cron: Cron::parse(&fields.join(" "))?,
+                    tz: parse_tz(tz)?,
+                }
+            }
+            _ => bail!("unrecognized schedule `{value}`"),
+        };
+        Ok(schedule)
+    }
+
+    /// The calendar recurrence of a daily or coarser schedule.
+    fn into_recurrence(self) -> Result<Recurrence> {
+        match self {
+            Self::EveryDay {
+                hour,
+                minute,
+                second,
+                tz,
+            } => Recurrence::daily(hour, minute, second, tz),
+            Self::EveryWeek {
+                weekday,
+                hour,
+                minute,
+                second,
+                tz,
+            } => Recurrence::weekly(weekday, hour, minute, second, tz),
+            Self::EveryMonth {
+                day,
+                hour,
+                minute,
+                second,
+                tz,
+            } => Recurrence::monthly(day, hour, minute, second, tz),
+            Self::EveryYear {
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                tz,
+            } => Recurrence::yearly(month, day, hour, minute, second, tz),
+            Self::Cron { cron, tz } => Ok(Recurrence::Cron { cron, tz }),
+            Self::EveryInterval { .. }
+            | Self::EverySecond { .. }
+            | Self::EveryMinute { .. }
+            | Self::EveryHour { .. } => {
+                bail!("{self:?} is not a calendar schedule")
+            }
+        }
+    }
+}
+
+pub struct JobDefinition {
+    pub name: &'static str,
+    pub schedule: Schedule,
+    /// Only used by daily and coarser schedules.
+    pub catch_up: CatchUpPolicy,
+    /// Jobs doing DB or bucket-wide work run on one instance per tick; the
+    /// rest act on per-process state (buffers, caches, local logs).
+    pub cluster_exclusive: bool,
+    /// A tick firing while the previous run is in flight.
+    pub overlap: OverlapPolicy,
+    pub task: JobFn,
+}
+
+impl JobDefinition {
+    fn new(name: &'static str, schedule: Schedule, task: JobFn) -> Self {
+        Self {
+            name,
+            schedule,
+            catch_up: CatchUpPolicy::Skip,
+            cluster_exclusive: false,
+            overlap: OverlapPolicy::for_job(name, OverlapPolicy::Skip),
+            task,
+        }
+    }
+
+    fn catch_up(mut self, default: CatchUpPolicy) -> Self {
+        self.catch_up = CatchUpPolicy::for_job(self.name, default);
+        self
+    }
+
+    fn cluster_exclusive(mut self) -> Self {
+        self.cluster_exclusive = true;
+        self
+    }
+
+    /// Apply `jobs.overrides.<JOB_NAME>.schedule` (`JOB_SCHEDULE_<JOB_NAME>`);
+    /// an unparsable override is logged and the default schedule kept.
+    fn with_config_override(mut self) -> Self {
+        if let Some(value) = app_config::config()
+            .jobs
+            .job(self.name)
+            .and_then(|job| job.schedule.as_deref())
+        {
+            match Schedule::parse(value) {
+                Ok(schedule) => {
+                    info!(task_name = self.name, schedule = %value, "Job schedule overridden");
+                    self.schedule = schedule;
+                }
+                Err(e) => {
+                    error!(task_name = self.name, schedule = %value, error = %e, "Ignoring invalid job schedule override")
+                }
+            }
+        }
+        self
+    }
+}
+
+pub fn paused_at_startup(job_name: &str) -> bool {
+    app_config::config()
+        .jobs
+        .job(job_name)
+        .and_then(|job| job.paused)
+        .unwrap_or(false)
+}
+
+/// `jobs.session_purge_interval_secs` (1..=86400) purges expired sessions on
+/// that interval; otherwise every hour at :30.
+fn session_purge_schedule() -> Schedule {
+    match app_config::config().jobs.session_purge_interval_secs {
+        Some(secs @ 1..=86_400) => Schedule::EveryInterval { secs },
+        _ => Schedule::EveryHour {
+            minute: 30,
+            second: 0,
+        },
+    }
+}
+
+/// Every scheduled job with overrides applied, in start order.
+pub fn registered_jobs() -> Vec<JobDefinition> {
+    vec![
+        JobDefinition::new(
+            "INVALIDATE_EXPIRED_SESSIONS",
+            session_purge_schedule(),
+            job(invalidate_sessions),
+        ),
+        JobDefinition::new(
+            "PURGE_NONVERIFIED_USERS",
+            Schedule::EveryHour {
+                minute: 0,
+                second: 0,
+            },
+            job(purge_nonverified_users),
+        )
+        .cluster_exclusive(),
+        JobDefinition::new(
+            "UPDATE_SYSTEM_STATS",
+            Schedule::EverySecond {
+                millisecond: 0,
+                microsecond: 0,
+            },
+            job(update_system_stats),
+        ),
+        JobDefinition::new(
+            "COMPRESS_OLD_LOGS",
+            Schedule::EveryDay {
+                hour: 6,
+                minute: 30,
+                second: 0,
+                tz: chrono_tz::UTC,
+            },
+            job(compress_old_logs),
+        )
+        .catch_up(CatchUpPolicy::RunOnce),
+        JobDefinition::new(
+            "FLUSH_VISITOR_LOGS",
+            Schedule::EveryMinute {
+                second: 0,
+                millisecond: 0,
+            },
+            job(flush_visitor_logs),
+        ),
+        JobDefinition::new(
+            "FLUSH_ACCESS_LOG",
+            Schedule::EveryMinute {
+                second: 50,
+                millisecond: 0,
+            },
+            job(flush_access_log),
+        ),
+        JobDefinition::new(
+            "PRUNE_LIVE_CHAT_STATE",
+            Schedule::EveryMinute {
+                second: 30,
+                millisecond: 0,
+            },
+            job(prune_live_chat_state),
+        ),
+        JobDefinition::new(
+            "FLUSH_PHOTOGRAPH_VIEWS",
+            Schedule::EveryMinute {
+                second: 15,
+                millisecond: 0,
+            },
+            job(flush_photograph_views),
+        ),
+        JobDefinition::new(
+            "FLUSH_WASM_MODULE_LOADS",
+            Schedule::EveryMinute {
+                second: 45,
+                millisecond: 0,
+            },
+            job(flush_wasm_module_loads),
+        ),
+        JobDefinition::new(
+            "PRUNE_PHOTOGRAPH_BATCHES",
+            Schedule::EveryMinute {
+                second: 45,
+                millisecond: 0,
+            },
+            job(prune_photograph_batches),
+        ),
+        JobDefinition::new(
+            "EVALUATE_ALERT_RULES",
+            Schedule::EveryMinute {
+                second: 20,
+                millisecond: 0,
+            },
+            job(evaluate_alert_rules),
+        ),
+        JobDefinition::new(
+            "PURGE_EXPIRED_CACHE_ENTRIES",
+            Schedule::EveryMinute {
+                second: 5,
+                millisecond: 0,
+            },
+            job(purge_expired_cache_entries),
+        ),
+        JobDefinition::new(
+            "RECONCILE_STORAGE_ORPHANS",
+            Schedule::EveryDay {
+                hour: 4,
+                minute: 15,
+                second: 0,
+                tz: chrono_tz::UTC,
+            },
+            job(reconcile_storage_orphans_job),
+        )
+        // A full bucket listing right at startup is not worth it; the next
+        // nightly pass covers the same ground.
+        .catch_up(CatchUpPolicy::Skip)
+        .cluster_exclusive(),
+        JobDefinition::new(
+            "PRUNE_JOB_HISTORY",
+            Schedule::EveryDay {
+                hour: 5,
+                minute: 0,
+                second: 0,
+                tz: chrono_tz::UTC,
+            },
+            job(prune_job_history),
+        )
+        .catch_up(CatchUpPolicy::RunOnce)
+        .cluster_exclusive(),
+        JobDefinition::new(
+            "PRUNE_ACCESS_LOG",
+            Schedule::EveryDay {
+                hour: 5,
+                minute: 10,
+                second: 0,
+                tz: chrono_tz::UTC,
+            },
+            job(prune_access_log),
+        )
+        .catch_up(CatchUpPolicy::RunOnce)
+        .cluster_exclusive(),
+        JobDefinition::new(
+            "PURGE_SOFT_DELETED",
+            Schedule::EveryDay {
+                hour: 5,
+                minute: 20,
+                second: 0,
+                tz: chrono_tz::UTC,
+            },
+            job(purge_soft_deleted),
+        )
+        .catch_up(CatchUpPolicy::RunOnce)
+        .cluster_exclusive(),
+        JobDefinition::new(
+            "SEND_NOTIFICATION_DIGESTS",
+            Schedule::EveryDay {
+                hour: 8,
+                minute: 0,
+                second: 0,
+                tz: chrono_tz::UTC,
+            },
+            job(send_notification_digests),
+        )
+        .cluster_exclusive(),
+        // A no-op unless `DB_BACKUP=on`.
+        JobDefinition::new(
+            "BACKUP_DATABASE",
+            Schedule::EveryDay {
+                hour: 3,
+                minute: 0,
+                second: 0,
+                tz: chrono_tz::UTC,
+            },
+            job(backup_database),
+        )
+        .cluster_exclusive(),
+    ]
+    .into_iter()
+    .map(JobDefinition::with_config_override)
+    .collect()
+}
+
+/// The scheduler loop for one job; `supervise` restarts it if it exits.
+pub async fn run_schedule(
+    state: Arc<ServerState>,
+    name: &'static str,
+    schedule: Schedule,
+    catch_up: CatchUpPolicy,
+    task: JobFn,
+) -> Result<()> {
+    let task = move |state: Arc<ServerState>| (task)(state);
+    let name = name.to_string();
+    match schedule {
+        Schedule::EveryInterval { secs } => {
+            schedule_task_every_interval(state, task, name, secs).await
+        }
+        Schedule::EverySecond {
+            millisecond,
+            microsecond,
+        } => schedule_task_every_second_at(state, task, name, millisecond, microsecond).await,
+        Schedule::EveryMinute {
+            second,
+            millisecond,
+        } => schedule_task_every_minute_at(state, task, name, second, millisecond).await,
+        Schedule::EveryHour { minute, second } => {
+            schedule_task_every_hour_at(state, task, name, minute, second).await
+        }
+        calendar => {
+            let recurrence = calendar.into_recurrence()?;
+            schedule_task_on_recurrence(state, task, name, recurrence, catch_up).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_overrides() {
+        assert_eq!(
+            Schedule::parse("day 03:00:00 Asia/Seoul").unwrap(),
+            Schedule::EveryDay {
+                hour: 3,
+                minute: 0,
+                second: 0,
+                tz: chrono_tz::Asia::Seoul,
+            }
+        );
+        assert_eq!(
+            Schedule::parse("interval 600").unwrap(),
+            Schedule::EveryInterval { secs: 600 }
+        );
+        assert_eq!(
+            Schedule::parse("hour 15:30").unwrap(),
+            Schedule::EveryHour {
+                minute: 15,
+                second: 30,
+            }
+        );
+        assert_eq!(
+            Schedule::parse("week mon 02:00:00").unwrap(),
+            Schedule::EveryWeek {
+                weekday: Weekday::Mon,
+                hour: 2,
+                minute: 0,
+                second: 0,
+                tz: chrono_tz::UTC,
+            }
+        );
+        assert_eq!(
+            Schedule::parse("year 12-31 23:59:59 UTC").unwrap(),
+            Schedule::EveryYear {
+                month: 12,
+                day: 31,
+                hour: 23,
+                minute: 59,
+                second: 59,
+                tz: chrono_tz::UTC,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_cron_overrides() {
+        let Schedule::Cron { cron, tz } = Schedule::parse("cron 0 */5 * * * Asia/Seoul").unwrap()
+        else {
+            panic!("not a cron schedule");
+        };
+        assert_eq!(cron, Cron::parse("0 */5 * * *").unwrap());
+        assert_eq!(tz, chrono_tz::Asia::Seoul);
+        assert!(matches!(
+            Schedule::parse("cron 30 0 */5 * * *").unwrap(),
+            Schedule::Cron {
+                tz: chrono_tz::UTC,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_overrides() {
+        for value in [
+            "",
+            "daily 03:00:00",
+            "day 24:00:00",
+            "day 03:00",
+            "day 03:00:00 Mars/Olympus",
+            "month 0 00:00:00",
+            "minute 60",
+            "interval 0",
+            "interval 86401",
+            "hour 10:00 UTC",
+            "cron 0 0 30 2 *",
+            "cron * * * * * * * UTC",
+        ] {
+            assert!(Schedule::parse(value).is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! Run-time budget for scheduled jobs.
+//!
+//! `JOB_TIMEOUT_SECS` (default 3600) bounds every run and
+//! `JOB_TIMEOUT_SECS_<JOB_NAME>` overrides it per job; `0` disables the bound.
+//! A run that exceeds it has its task aborted at the next `.await` (a stuck
+//! query or S3 call is dropped) and is recorded as `timed_out`, so the
+//! schedule moves on to the next mark.
+
+use std::time::Duration;
+
+use crate::init::app_config;
+
+/// `None` when the job may run indefinitely.
+pub fn job_timeout(task_descriptor: &str) -> Option<Duration> {
+    let jobs = &app_config::config().jobs;
+    let secs = jobs
+        .job(task_descriptor)
+        .and_then(|job| job.timeout_secs)
+        .unwrap_or(jobs.timeout_secs);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
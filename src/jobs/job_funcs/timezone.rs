@@ -0,0 +1,75 @@
+//! Wall-clock schedules in a named timezone.
+//!
+//! Every `Recurrence` (daily, weekly, monthly, yearly and cron) carries a
+//! `chrono_tz::Tz` and its times are local time there (use `chrono_tz::UTC`
+//! for wall-clock UTC). Around DST transitions:
+//! - a local time that occurs twice (clocks go back) runs once, at the first
+//!   occurrence;
+//! - a local time that does not exist (clocks go forward) runs at the first
+//!   minute after the jump, e.g. 02:30 becomes 03:00.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, LocalResult, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use tracing::error;
+
+/// Longest DST jump searched past when a local time falls into a gap.
+const MAX_GAP_MINUTES: i64 = 3 * 60;
+
+/// The instant of local time `naive` in `tz`, by the rules above.
+pub fn resolve_local(tz: Tz, naive: NaiveDateTime) -> Result<DateTime<Utc>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => Ok(dt.with_timezone(&Utc)),
+        LocalResult::None => {
+            // Inside a gap: the first whole local minute that exists.
+            let start = naive
+                .with_second(0)
+                .and_then(|n| n.with_nanosecond(0))
+                .unwrap_or(naive);
+            (1..=MAX_GAP_MINUTES)
+                .find_map(|minutes| {
+                    tz.from_local_datetime(&(start + chrono::Duration::minutes(minutes)))
+                        .earliest()
+                })
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok_or_else(|| {
+                    error!(%naive, %tz, "Local schedule time does not exist");
+                    anyhow!("Local schedule time does not exist in {tz}")
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn local_mark(tz: Tz, date: NaiveDate, h: u32, m: u32, s: u32) -> Result<DateTime<Utc>> {
+        resolve_local(tz, date.and_hms_opt(h, m, s).unwrap())
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn seoul_has_fixed_offset() {
+        let mark = local_mark(chrono_tz::Asia::Seoul, date(2026, 3, 1), 3, 0, 0).unwrap();
+        assert_eq!(mark.to_rfc3339(), "2026-02-28T18:00:00+00:00");
+    }
+
+    #[test]
+    fn spring_forward_gap_runs_after_jump() {
+        // 2026-03-08 02:00 -> 03:00 in New York.
+        let mark = local_mark(chrono_tz::America::New_York, date(2026, 3, 8), 2, 30, 0).unwrap();
+        assert_eq!(mark.to_rfc3339(), "2026-03-08T07:00:00+00:00");
+    }
+
+    #[test]
+    fn fall_back_runs_at_first_occurrence() {
+        // 2026-11-01 01:30 happens twice in New York; EDT (UTC-4) comes first.
+        let mark = local_mark(chrono_tz::America::New_York, date(2026, 11, 1), 1, 30, 0).unwrap();
+        assert_eq!(mark.to_rfc3339(), "2026-11-01T05:30:00+00:00");
+    }
+}
@@ -0,0 +1,200 @@
+//! Nightly logical backup of the database to object storage.
+//!
+//! Off unless `DB_BACKUP=on`. Each run pipes `pg_dump` (plain SQL, no owners
+//! or grants; `PG_DUMP_PATH`, default `pg_dump` on `PATH`) through zstd and
+//! uploads the result to `DB_BACKUP_PREFIX` (default `private/backups/db/`)
+//! as `<UTC timestamp>.sql.zst` on the configured storage backend. The newest
+//! `DB_BACKUP_KEEP` (default 14) backups are kept and older ones deleted.
+//! `DB_BACKUP_TABLES=users,posts,...` limits the dump to those tables.
+//!
+//! Connection settings come from the same `DB_*` variables as the pool and
+//! reach `pg_dump` through the libpq environment. The dump is buffered in
+//! memory compressed, and a run aborted by its timeout lets `pg_dump` finish
+//! in the background before discarding the output. Sizes, durations and the
+//! object key are recorded in `job_executions.job_details`.
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use anyhow::{Context, anyhow, bail};
+use chrono::Utc;
+use serde_derive::Serialize;
+use tracing::{info, warn};
+
+use crate::init::app_config::BackupSection;
+use crate::init::config::DbConfig;
+use crate::init::state::ServerState;
+
+const BACKUP_SUFFIX: &str = ".sql.zst";
+const ZSTD_LEVEL: i32 = 9;
+
+struct BackupConfig {
+    pg_dump: String,
+    prefix: String,
+    keep: usize,
+    tables: Vec<String>,
+}
+
+impl BackupConfig {
+    fn from_config(config: &BackupSection) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let mut prefix = config.prefix.trim().trim_start_matches('/').to_string();
+        if !prefix.is_empty() && !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+        Some(Self {
+            pg_dump: config.pg_dump_path.clone(),
+            prefix,
+            keep: config.keep,
+            tables: config.tables.clone(),
+        })
+    }
+}
+
+/// Recorded as the run's `job_details`.
+#[derive(Debug, Serialize)]
+struct BackupReport {
+    key: String,
+    dump_bytes: u64,
+    compressed_bytes: usize,
+    dump_ms: u64,
+    upload_ms: u64,
+    /// Older backups removed by rotation.
+    rotated: usize,
+}
+
+/// Counts bytes read through it.
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+/// Run `pg_dump` and return (uncompressed size, zstd bytes).
+fn dump_compressed(config: &BackupConfig, db: &DbConfig) -> anyhow::Result<(u64, Vec<u8>)> {
+    let mut command = Command::new(&config.pg_dump);
+    command
+        .args(["--format=plain", "--no-owner", "--no-privileges"])
+        .args(config.tables.iter().map(|t| format!("--table={t}")))
+        .envs(db.libpq_env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("could not start `{}`", config.pg_dump))?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("pg_dump stdout missing"))?;
+    // Drain stderr alongside stdout so a chatty pg_dump cannot block on it.
+    let mut stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| anyhow!("pg_dump stderr missing"))?;
+    let stderr_reader = std::thread::spawn(move || {
+        let mut message = String::new();
+        let _ = stderr.read_to_string(&mut message);
+        message
+    });
+
+    let mut reader = CountingReader {
+        inner: stdout,
+        count: 0,
+    };
+    let mut compressed = Vec::new();
+    let encoded = zstd::stream::copy_encode(&mut reader, &mut compressed, ZSTD_LEVEL);
+    let status = child.wait()?;
+    let message = stderr_reader.join().unwrap_or_default();
+    if !status.success() {
+        bail!("pg_dump exited with {status}: {}", message.trim());
+    }
+    encoded.context("compressing pg_dump output")?;
+    Ok((reader.count, compressed))
+}
+
+/// Delete all but the newest `keep` backups under the prefix. Keys are
+/// timestamps, so they sort chronologically.
+async fn rotate(state: &ServerState, config: &BackupConfig) -> anyhow::Result<usize> {
+    let mut keys: Vec<String> = state
+        .storage
+        .list(&config.prefix)
+        .await?
+        .into_iter()
+        .map(|object| object.key)
+        .filter(|key| key.ends_with(BACKUP_SUFFIX))
+        .collect();
+    if keys.len() <= config.keep {
+        return Ok(0);
+    }
+    keys.sort();
+    let stale = &keys[..keys.len() - config.keep];
+    Ok(state.storage.delete_many(stale).await)
+}
+
+pub async fn backup_database(state: Arc<ServerState>) -> anyhow::Result<Option<serde_json::Value>> {
+    let Some(config) = BackupConfig::from_config(&state.config.backup) else {
+        return Ok(None);
+    };
+    let config = Arc::new(config);
+    let db = DbConfig::from_config(&state.config.database)?;
+
+    let dump_start = tokio::time::Instant::now();
+    let (dump_bytes, compressed) = {
+        let config = Arc::clone(&config);
+        tokio::task::spawn_blocking(move || dump_compressed(&config, &db)).await??
+    };
+    let dump_ms = dump_start.elapsed().as_millis() as u64;
+
+    let key = format!(
+        "{}{}{BACKUP_SUFFIX}",
+        config.prefix,
+        Utc::now().format("%Y-%m-%dT%H%M%SZ")
+    );
+    let compressed_bytes = compressed.len();
+    let upload_start = tokio::time::Instant::now();
+    state
+        .storage
+        .put(&key, compressed, "application/zstd")
+        .await
+        .with_context(|| format!("uploading {key}"))?;
+    let upload_ms = upload_start.elapsed().as_millis() as u64;
+
+    let rotated = match rotate(&state, &config).await {
+        Ok(rotated) => rotated,
+        Err(e) => {
+            warn!(error = ?e, prefix = %config.prefix, "Could not rotate old database backups");
+            0
+        }
+    };
+
+    let report = BackupReport {
+        key,
+        dump_bytes,
+        compressed_bytes,
+        dump_ms,
+        upload_ms,
+        rotated,
+    };
+    info!(
+        key = %report.key,
+        dump_bytes,
+        compressed_bytes,
+        dump_ms,
+        upload_ms,
+        rotated,
+        "Database backup uploaded"
+    );
+    Ok(Some(serde_json::to_value(report)?))
+}
@@ -1,98 +1,177 @@
+//! Daily retention for the rolled log files under `LOGS_DIR`: a day's log is
+//! gzipped once `LOG_COMPRESS_AFTER_DAYS` old and deleted, compressed or not,
+//! once `LOG_RETENTION_DAYS` old. Ages come from the date in the file name;
+//! files without one are left alone.
+
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::path::Path;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
+use flate2::{Compression, write::GzEncoder};
+use serde_derive::Serialize;
 use tracing::{error, info};
-use zstd::stream::copy_encode;
 
 use crate::LOGS_DIR;
+use crate::init::app_config::LogFilesSection;
 use crate::init::state::ServerState;
-use crate::util::time::now::tokio_now;
-
-const EXCLUDED_EXTENSIONS: [&str; 2] = ["gz", "zst"];
+use crate::util::log_files::{LogFile, list_log_files};
+
+#[derive(Debug, Default, Serialize)]
+struct RetentionReport {
+    compressed: usize,
+    deleted: usize,
+    /// Bytes freed by compression and deletion together.
+    freed_bytes: u64,
+    failed: usize,
+}
 
-pub async fn compress_old_logs(_state: Arc<ServerState>) {
-    let now = Utc::now();
-    let now_yyyy_mm_dd = now.format("%Y-%m-%d").to_string();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Compress,
+    Delete,
+}
 
-    let logs_dir_path = Path::new(LOGS_DIR);
+fn action_for(file: &LogFile, today: NaiveDate, policy: &LogFilesSection) -> Option<Action> {
+    let age = (today - file.date?).num_days();
+    if age >= policy.retention_days {
+        Some(Action::Delete)
+    } else if age >= policy.compress_after_days && file.compression.is_none() {
+        Some(Action::Compress)
+    } else {
+        None
+    }
+}
 
-    let mut filewalker = walkdir::WalkDir::new(logs_dir_path);
-    filewalker = filewalker.max_depth(4);
+/// Writes `<path>.gz` and removes `path`; returns the compressed size.
+fn gzip(path: &Path) -> std::io::Result<u64> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+
+    let result = (|| {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut encoder = GzEncoder::new(
+            BufWriter::new(File::create(&gz_path)?),
+            Compression::default(),
+        );
+        std::io::copy(&mut reader, &mut encoder)?;
+        encoder.finish()?.flush()?;
+        std::fs::metadata(&gz_path).map(|m| m.len())
+    })();
+    match result {
+        Ok(size) => {
+            std::fs::remove_file(path)?;
+            Ok(size)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&gz_path);
+            Err(e)
+        }
+    }
+}
 
-    for entry in filewalker {
-        let entry_start = tokio_now();
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(err) => {
-                error!(logs_dir = LOGS_DIR, error = %err, "Error walking logs directory");
-                continue;
-            }
+fn apply_retention(dir: &Path, today: NaiveDate, policy: &LogFilesSection) -> RetentionReport {
+    let mut report = RetentionReport::default();
+    for file in list_log_files(dir) {
+        let Some(action) = action_for(&file, today, policy) else {
+            continue;
         };
-
-        let path = entry.path();
-        if path.is_file() {
-            let file_name = match path.file_name().and_then(|n| n.to_str()) {
-                Some(n) => n,
-                None => continue,
-            };
-
-            // Check if extension is excluded
-            if let Some(ext) = path.extension().and_then(|e| e.to_str())
-                && EXCLUDED_EXTENSIONS.contains(&ext)
-            {
-                continue;
+        let result = match action {
+            Action::Compress => gzip(&file.path).map(|compressed_bytes| {
+                report.compressed += 1;
+                file.size_bytes.saturating_sub(compressed_bytes)
+            }),
+            Action::Delete => std::fs::remove_file(&file.path).map(|()| {
+                report.deleted += 1;
+                file.size_bytes
+            }),
+        };
+        match result {
+            Ok(freed) => {
+                report.freed_bytes += freed;
+                info!(log_file = %file.name, action = ?action, freed_bytes = freed, "Log retention applied");
             }
-
-            // Exclude today's log file (by checking filename for today's date)
-            if file_name.contains(&now_yyyy_mm_dd) {
-                continue;
+            Err(e) => {
+                report.failed += 1;
+                error!(log_file = %file.name, action = ?action, error = %e, "Log retention failed");
             }
+        }
+    }
+    report
+}
 
-            let compressed_path = path.with_extension(
-                path.extension()
-                    .and_then(|e| e.to_str())
-                    .map(|e| format!("{e}.zst"))
-                    .unwrap_or_else(|| "zst".to_string()),
-            );
-
-            // spawn_blocking needs owned paths (CLAUDE.md: blocking work off the runtime threads)
-            let owned_path = path.to_path_buf();
-            let owned_compressed_path = compressed_path.clone();
-
-            let join_result = tokio::task::spawn_blocking(move || -> std::io::Result<()> {
-                let input = File::open(&owned_path)?;
-                let output = File::create(&owned_compressed_path)?;
-
-                let mut reader = BufReader::new(input);
-                let mut writer = BufWriter::new(output);
-
-                copy_encode(&mut reader, &mut writer, 11)?;
-
-                std::fs::remove_file(&owned_path)?;
-
-                Ok(())
-            })
-            .await;
-
-            match join_result {
-                Ok(Ok(())) => {
-                    let duration = entry_start.elapsed();
-                    info!(
-                        log_file_path = %entry.path().display(),
-                        duration = ?duration,
-                        "Log file compressed"
-                    );
-                }
-                Ok(Err(err)) => {
-                    error!(log_file_path = %path.display(), error = %err, "Failed to compress log file");
-                }
-                Err(join_err) => {
-                    error!(log_file_path = %path.display(), error = %join_err, "Compression task panicked or was cancelled");
-                }
-            }
+pub async fn compress_old_logs(
+    state: Arc<ServerState>,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    let policy = state.config.log_files.clone();
+    let today = Utc::now().date_naive();
+
+    let report =
+        tokio::task::spawn_blocking(move || apply_retention(Path::new(LOGS_DIR), today, &policy))
+            .await?;
+
+    if report.failed > 0 {
+        anyhow::bail!(
+            "{} of {} log files could not be compressed or deleted",
+            report.failed,
+            report.failed + report.compressed + report.deleted
+        );
+    }
+    Ok(Some(serde_json::to_value(report)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::log_files::LogCompression;
+
+    fn file(date: NaiveDate, compression: Option<LogCompression>) -> LogFile {
+        LogFile {
+            name: String::new(),
+            path: PathBuf::new(),
+            date: Some(date),
+            size_bytes: 0,
+            modified_at: None,
+            compression,
         }
     }
+
+    #[test]
+    fn compresses_then_deletes_by_age() {
+        let policy = LogFilesSection {
+            compress_after_days: 2,
+            retention_days: 10,
+        };
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let days_ago = |days| today - chrono::Duration::days(days);
+
+        assert_eq!(action_for(&file(today, None), today, &policy), None);
+        assert_eq!(action_for(&file(days_ago(1), None), today, &policy), None);
+        assert_eq!(
+            action_for(&file(days_ago(2), None), today, &policy),
+            Some(Action::Compress)
+        );
+        assert_eq!(
+            action_for(
+                &file(days_ago(5), Some(LogCompression::Zstd)),
+                today,
+                &policy
+            ),
+            None
+        );
+        assert_eq!(
+            action_for(
+                &file(days_ago(10), Some(LogCompression::Gzip)),
+                today,
+                &policy
+            ),
+            Some(Action::Delete)
+        );
+        assert_eq!(
+            action_for(&file(days_ago(30), None), today, &policy),
+            Some(Action::Delete)
+        );
+    }
 }
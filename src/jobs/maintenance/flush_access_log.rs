@@ -0,0 +1,8 @@
+use std::sync::Arc;
+
+use crate::init::state::ServerState;
+
+pub async fn flush_access_log(state: Arc<ServerState>) -> anyhow::Result<()> {
+    state.flush_access_log().await?;
+    Ok(())
+}
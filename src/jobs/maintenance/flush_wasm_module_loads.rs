@@ -0,0 +1,21 @@
+//! Periodic flush of buffered WASM bundle loads to the database.
+//!
+//! `serve_wasm` counts loads per module, UTC day and country in
+//! `ServerState::wasm_module_load_buffer` (see
+//! `init::state::server_state::wasm_loads`). This job upserts them into
+//! `wasm_module_loads` so the serve path never writes per load.
+
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::init::state::ServerState;
+
+pub async fn flush_wasm_module_loads(state: Arc<ServerState>) {
+    match state.flush_wasm_module_loads().await {
+        Ok(_) => {}
+        Err(e) => {
+            error!(error = ?e, "Failed to flush WASM module loads");
+        }
+    }
+}
@@ -1,5 +1,13 @@
+pub mod backup_database;
 pub mod compress_logs;
+pub mod flush_access_log;
 pub mod flush_photograph_views;
 pub mod flush_visitor_logs;
+pub mod flush_wasm_module_loads;
+pub mod prune_access_log;
+pub mod prune_job_history;
 pub mod prune_live_chat;
 pub mod prune_photograph_batches;
+pub mod purge_cache;
+pub mod purge_soft_deleted;
+pub mod reconcile_storage_orphans;
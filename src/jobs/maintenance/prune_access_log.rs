@@ -0,0 +1,20 @@
+//! Daily retention for `access_log`: rows older than
+//! `ACCESS_LOG_RETENTION_DAYS` (default 30) are deleted.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::info;
+
+use crate::init::state::ServerState;
+
+pub async fn prune_access_log(state: Arc<ServerState>) -> anyhow::Result<()> {
+    let retention_days = state.config.access_log.retention_days;
+    let cutoff = Utc::now() - chrono::Duration::days(retention_days);
+
+    let deleted = state.prune_access_log(cutoff).await?;
+    if deleted > 0 {
+        info!(deleted, retention_days, "Pruned old access log entries");
+    }
+    Ok(())
+}
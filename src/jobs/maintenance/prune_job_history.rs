@@ -0,0 +1,45 @@
+//! Daily retention for `job_executions`, finished `delayed_tasks`, closed
+//! `email_outbox` rows, `email_feedback_events` and read `notifications`: rows
+//! older than `JOB_HISTORY_RETENTION_DAYS` (default 30) are deleted. The
+//! suppression list and unread notifications are kept.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::info;
+
+use crate::init::state::ServerState;
+
+pub async fn prune_job_history(state: Arc<ServerState>) -> anyhow::Result<()> {
+    let retention_days = state.config.jobs.history_retention_days;
+    let cutoff = Utc::now() - chrono::Duration::days(retention_days);
+
+    let deleted = state.prune_job_executions(cutoff).await?;
+    if deleted > 0 {
+        info!(deleted, retention_days, "Pruned old job executions");
+    }
+
+    let deleted = state.prune_delayed_tasks(cutoff).await?;
+    if deleted > 0 {
+        info!(deleted, retention_days, "Pruned finished delayed tasks");
+    }
+
+    let deleted = state.prune_email_outbox(cutoff).await?;
+    if deleted > 0 {
+        info!(
+            deleted,
+            retention_days, "Pruned sent and dead outbox emails"
+        );
+    }
+
+    let deleted = state.prune_email_feedback_events(cutoff).await?;
+    if deleted > 0 {
+        info!(deleted, retention_days, "Pruned email feedback events");
+    }
+
+    let deleted = state.prune_read_notifications(cutoff).await?;
+    if deleted > 0 {
+        info!(deleted, retention_days, "Pruned read notifications");
+    }
+    Ok(())
+}
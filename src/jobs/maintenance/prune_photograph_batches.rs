@@ -1,9 +1,16 @@
-//! Periodic eviction of terminal/stuck photograph batch sessions.
+//! Periodic eviction of terminal/stuck photograph batch sessions and expired
+//! presigned uploads.
 //!
 //! `photograph_batches` would otherwise grow one `Arc<BatchSession>` per upload
 //! for the process lifetime (an unbounded runtime cache). `prune_terminal_batches`
 //! drops batches that finished long enough ago for any poller to have observed
 //! the result, plus a hard cap for wedged sessions, and removes their temp dirs.
+//!
+//! Presigned upload tokens that were never confirmed are dropped once expired,
+//! and their staged objects (if the client uploaded at all) are deleted
+//! best-effort.
+//!
+//! Upload progress channels (`X-Upload-Id`) are dropped once finished or idle.
 
 use std::sync::Arc;
 
@@ -20,4 +27,33 @@ pub async fn prune_photograph_batches(state: Arc<ServerState>) {
             "Pruned terminal/stuck photograph batch sessions"
         );
     }
+
+    let pruned_progress = state.prune_upload_progress(now).await;
+    if pruned_progress > 0 {
+        tracing::debug!(
+            pruned_progress,
+            "Pruned finished/idle upload progress entries"
+        );
+    }
+
+    let expired_uploads = state.prune_expired_presigned_uploads(now).await;
+    if expired_uploads.is_empty() {
+        return;
+    }
+
+    for upload in &expired_uploads {
+        if let Err(e) = state.storage.delete(&upload.object_key).await {
+            tracing::warn!(
+                error = ?e,
+                upload_token = %upload.upload_token,
+                key = %upload.object_key,
+                "Failed to delete staged object for expired presigned upload"
+            );
+        }
+    }
+
+    tracing::info!(
+        expired_uploads = expired_uploads.len(),
+        "Pruned expired presigned photograph uploads"
+    );
 }
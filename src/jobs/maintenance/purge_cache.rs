@@ -0,0 +1,13 @@
+//! Drops expired entries and refilled rate-limit buckets from the in-process
+//! cache. A no-op with Redis, which expires keys itself.
+
+use std::sync::Arc;
+
+use crate::init::state::ServerState;
+
+pub async fn purge_expired_cache_entries(state: Arc<ServerState>) {
+    let purged = state.cache.purge_expired().await;
+    if purged > 0 {
+        tracing::debug!(purged, "Purged expired cache entries");
+    }
+}
@@ -0,0 +1,28 @@
+//! Daily purge of soft-deleted posts, comments and photographs: rows deleted
+//! more than `SOFT_DELETE_RETENTION_DAYS` (default 30) ago are removed for
+//! good, with the photographs' stored objects.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::info;
+
+use crate::init::state::ServerState;
+
+pub async fn purge_soft_deleted(state: Arc<ServerState>) -> anyhow::Result<()> {
+    let retention_days = state.config.soft_delete.retention_days;
+    let cutoff = Utc::now() - chrono::Duration::days(retention_days);
+
+    let purged = state.purge_soft_deleted(cutoff).await?;
+    if purged.total_rows() > 0 {
+        info!(
+            posts = purged.posts,
+            comments = purged.comments,
+            photographs = purged.photographs,
+            objects = purged.objects,
+            retention_days,
+            "Purged soft-deleted content"
+        );
+    }
+    Ok(())
+}
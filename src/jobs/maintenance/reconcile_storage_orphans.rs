@@ -0,0 +1,329 @@
+//! Daily reconciliation of object storage against the database.
+//!
+//! Lists every media prefix we write to and compares the keys with what rows
+//! reference: photograph main images, thumbnails/renditions, RAW and private
+//! originals, profile pictures, WASM thumbnails, WASM bundles and module files. Resize-proxy variants count
+//! as referenced while their photograph exists. Anything else older than
+//! `STORAGE_ORPHAN_MIN_AGE_HOURS` (default 24, so in-flight uploads whose row
+//! is not committed yet are never touched) is an orphan.
+//!
+//! `STORAGE_ORPHAN_CLEANUP` = `report` (default, dry run) | `delete` | `off`
+//! controls the scheduled run; superusers can trigger a pass and read the last
+//! report through `/api/admin/storage/orphans`.
+
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use chrono::Utc;
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        photography::{
+            photographs::PhotographRenditions, presigned_upload::PRESIGNED_UPLOAD_PREFIX,
+        },
+        wasm_module::bundle_storage::WASM_BUNDLE_KEY_PREFIX,
+    },
+    dto::responses::admin::storage_orphan_report::{StorageOrphan, StorageOrphanReport},
+    init::{app_config::StorageSection, state::ServerState},
+    schema::{photographs, user_profile_pictures, wasm_module, wasm_module_files},
+    util::image::variants::VARIANT_KEY_PREFIX,
+};
+
+/// Prefixes written by this server. Anything else in the bucket is left alone.
+const SCANNED_PREFIXES: [&str; 8] = [
+    "images/",
+    "thumbnails/",
+    "originals/",
+    "private/originals/",
+    "wasm-thumbnails/",
+    WASM_BUNDLE_KEY_PREFIX,
+    VARIANT_KEY_PREFIX,
+    PRESIGNED_UPLOAD_PREFIX,
+];
+
+/// Cap on orphans listed in a report (counts and deletion cover all of them).
+pub const MAX_REPORTED_ORPHANS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanCleanupMode {
+    Off,
+    Report,
+    Delete,
+}
+
+impl OrphanCleanupMode {
+    pub fn from_config(config: &StorageSection) -> Self {
+        match config.orphan_cleanup.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" | "disabled" => Self::Off,
+            "delete" => Self::Delete,
+            _ => Self::Report,
+        }
+    }
+}
+
+/// Single-flight guard plus the most recent report.
+pub struct StorageOrphanTracker {
+    running: AtomicBool,
+    last_report: RwLock<Option<StorageOrphanReport>>,
+}
+
+impl StorageOrphanTracker {
+    pub fn new() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            last_report: RwLock::new(None),
+        }
+    }
+
+    pub async fn last_report(&self) -> Option<StorageOrphanReport> {
+        self.last_report.read().await.clone()
+    }
+}
+
+impl Default for StorageOrphanTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Holds `running` for one pass and clears it on drop, so a cancelled or
+/// panicked pass does not block every later one.
+struct PassGuard<'a>(&'a StorageOrphanTracker);
+
+impl<'a> PassGuard<'a> {
+    fn acquire(tracker: &'a StorageOrphanTracker) -> Option<Self> {
+        (!tracker.running.swap(true, Ordering::AcqRel)).then_some(Self(tracker))
+    }
+}
+
+impl Drop for PassGuard<'_> {
+    fn drop(&mut self) {
+        self.0.running.store(false, Ordering::Release);
+    }
+}
+
+/// Scheduled entry point.
+pub async fn reconcile_storage_orphans_job(state: Arc<ServerState>) {
+    let dry_run = match OrphanCleanupMode::from_config(&state.config.storage) {
+        OrphanCleanupMode::Off => return,
+        OrphanCleanupMode::Report => true,
+        OrphanCleanupMode::Delete => false,
+    };
+    match reconcile_storage_orphans(&state, dry_run).await {
+        Ok(Some(_)) => {}
+        Ok(None) => warn!("Skipping scheduled storage orphan scan; another pass is running"),
+        Err(e) => error!(error = ?e, "Storage orphan reconciliation failed"),
+    }
+}
+
+/// Run one pass; `Ok(None)` without scanning when another pass is in
+/// progress.
+pub async fn reconcile_storage_orphans(
+    state: &ServerState,
+    dry_run: bool,
+) -> anyhow::Result<Option<StorageOrphanReport>> {
+    let tracker = &state.storage_orphans;
+    let Some(_pass) = PassGuard::acquire(tracker) else {
+        return Ok(None);
+    };
+    let report = run_pass(state, dry_run).await?;
+    *tracker.last_report.write().await = Some(report.clone());
+    Ok(Some(report))
+}
+
+async fn run_pass(state: &ServerState, dry_run: bool) -> anyhow::Result<StorageOrphanReport> {
+    let started_at = Utc::now();
+    let min_age_hours = state.config.storage.orphan_min_age_hours;
+    let cutoff = started_at - chrono::Duration::hours(min_age_hours);
+
+    // List first: anything uploaded after the listing cannot be flagged, and
+    // anything referenced by a row committed before the DB read is kept.
+    let mut listed = Vec::new();
+    for prefix in SCANNED_PREFIXES {
+        listed.extend(state.storage.list(prefix).await?);
+    }
+    let (referenced, photograph_ids) = referenced_keys(state).await?;
+
+    let scanned_objects = listed.len();
+    let mut skipped_recent = 0usize;
+    let mut orphans: Vec<StorageOrphan> = Vec::new();
+    for object in listed {
+        if referenced.contains(&object.key) || is_live_variant(&object.key, &photograph_ids) {
+            continue;
+        }
+        // Unknown age counts as recent.
+        if object.last_modified.is_none_or(|t| t > cutoff) {
+            skipped_recent += 1;
+            continue;
+        }
+        orphans.push(StorageOrphan {
+            key: object.key,
+            size_bytes: object.size,
+            last_modified: object.last_modified,
+        });
+    }
+    orphans.sort_by_key(|o| o.last_modified);
+
+    let orphan_count = orphans.len();
+    let orphan_bytes = orphans.iter().map(|o| o.size_bytes).sum();
+    let deleted_count = if dry_run || orphans.is_empty() {
+        0
+    } else {
+        let keys: Vec<String> = orphans.iter().map(|o| o.key.clone()).collect();
+        let deleted = state.storage.delete_many(&keys).await;
+        state.invalidate_cdn_keys(
+            keys.into_iter()
+                .filter(|k| !k.starts_with("private/"))
+                .collect(),
+        );
+        deleted
+    };
+
+    let orphans_truncated = orphans.len() > MAX_REPORTED_ORPHANS;
+    orphans.truncate(MAX_REPORTED_ORPHANS);
+
+    let report = StorageOrphanReport {
+        started_at,
+        finished_at: Utc::now(),
+        dry_run,
+        backend: state.storage.name().to_string(),
+        scanned_objects,
+        skipped_recent,
+        orphan_count,
+        orphan_bytes,
+        deleted_count,
+        orphans,
+        orphans_truncated,
+    };
+
+    if orphan_count > 0 {
+        warn!(
+            dry_run,
+            scanned_objects,
+            orphan_count,
+            orphan_bytes,
+            deleted_count,
+            "Found orphaned storage objects"
+        );
+    } else {
+        info!(
+            scanned_objects,
+            skipped_recent, "No orphaned storage objects"
+        );
+    }
+
+    Ok(report)
+}
+
+/// Every storage key a row points at, plus the ids of existing photographs
+/// (for variant ownership).
+async fn referenced_keys(state: &ServerState) -> anyhow::Result<(HashSet<String>, HashSet<Uuid>)> {
+    let mut conn = state.get_conn().await?;
+
+    type PhotographObjects = (
+        Uuid,
+        String,
+        String,
+        serde_json::Value,
+        Option<String>,
+        Option<String>,
+    );
+    let photograph_rows: Vec<PhotographObjects> = photographs::table
+        .select((
+            photographs::photograph_id,
+            photographs::photograph_link,
+            photographs::photograph_thumbnail_link,
+            photographs::photograph_renditions,
+            photographs::photograph_raw_link,
+            photographs::photograph_private_original_key,
+        ))
+        .load(&mut conn)
+        .await?;
+
+    let profile_picture_links: Vec<Option<String>> = user_profile_pictures::table
+        .select(user_profile_pictures::user_profile_picture_link)
+        .filter(user_profile_pictures::user_profile_picture_link.is_not_null())
+        .load(&mut conn)
+        .await?;
+
+    let wasm_thumbnail_links: Vec<String> = wasm_module::table
+        .select(wasm_module::wasm_module_thumbnail_link)
+        .load(&mut conn)
+        .await?;
+
+    let wasm_bundle_keys: Vec<(Option<String>, Option<String>)> = wasm_module::table
+        .select((
+            wasm_module::wasm_module_bundle_key,
+            wasm_module::wasm_module_bundle_br_key,
+        ))
+        .filter(
+            wasm_module::wasm_module_bundle_key
+                .is_not_null()
+                .or(wasm_module::wasm_module_bundle_br_key.is_not_null()),
+        )
+        .load(&mut conn)
+        .await?;
+
+    let wasm_file_keys: Vec<String> = wasm_module_files::table
+        .select(wasm_module_files::wasm_module_file_key)
+        .distinct()
+        .load(&mut conn)
+        .await?;
+
+    drop(conn);
+
+    let storage = state.storage.as_ref();
+    let mut keys = HashSet::new();
+    let mut photograph_ids = HashSet::with_capacity(photograph_rows.len());
+    let add_url = |keys: &mut HashSet<String>, url: &str| {
+        if let Some(key) = storage.key_from_url(url) {
+            keys.insert(key);
+        }
+    };
+
+    for (id, link, thumb, renditions, raw_link, private_key) in photograph_rows {
+        photograph_ids.insert(id);
+        add_url(&mut keys, &link);
+        add_url(&mut keys, &thumb);
+        if let Some(raw_link) = raw_link {
+            add_url(&mut keys, &raw_link);
+        }
+        keys.extend(private_key);
+        let renditions: PhotographRenditions =
+            serde_json::from_value(renditions).unwrap_or_default();
+        for rendition in renditions.values() {
+            add_url(&mut keys, &rendition.url);
+        }
+    }
+    for link in profile_picture_links.into_iter().flatten() {
+        add_url(&mut keys, &link);
+    }
+    for link in wasm_thumbnail_links {
+        add_url(&mut keys, &link);
+    }
+    for (key, br_key) in wasm_bundle_keys {
+        keys.extend(key);
+        keys.extend(br_key);
+    }
+    keys.extend(wasm_file_keys);
+
+    Ok((keys, photograph_ids))
+}
+
+/// `variants/photographs/{photograph_id}/...` of a photograph that still exists.
+fn is_live_variant(key: &str, photograph_ids: &HashSet<Uuid>) -> bool {
+    key.strip_prefix(VARIANT_KEY_PREFIX)
+        .and_then(|rest| rest.split('/').next())
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .is_some_and(|id| photograph_ids.contains(&id))
+}
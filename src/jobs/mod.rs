@@ -1,3 +1,7 @@
 pub mod auth;
 pub mod job_funcs;
 pub mod maintenance;
+pub mod monitoring;
+pub mod notifications;
+pub mod queue;
+pub mod tasks;
@@ -0,0 +1,163 @@
+//! Every minute, check each enabled alert rule (`domain::alert::alert_rule`)
+//! against this instance's endpoint stats. A rule with breaching endpoints
+//! fires once its cooldown has passed: an email to each of its addresses and
+//! a JSON webhook to its URL (queued, retried by the task queue). A rule with
+//! neither only logs.
+
+use std::collections::{HashMap, hash_map::Entry};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use lettre::{Message, message::Mailbox};
+use serde_derive::Serialize;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        alert::alert_rule::{AlertBreach, AlertRule, MAX_BREACHES_PER_ALERT},
+        job::delayed_task::DelayedTask,
+    },
+    init::state::ServerState,
+    jobs::job_funcs::delayed::enqueue,
+    util::{
+        email::transport::OutgoingEmail,
+        http::endpoint_stats::{EndpointStatsWindow, EndpointSummary},
+    },
+};
+
+#[derive(Serialize)]
+struct EndpointAlert<'a> {
+    app: String,
+    alert_rule_id: Uuid,
+    rule_name: &'a str,
+    metric: &'a str,
+    threshold: f64,
+    window: &'a str,
+    fired_at: DateTime<Utc>,
+    /// Worst first, at most `MAX_BREACHES_PER_ALERT`.
+    breaches: &'a [AlertBreach],
+    /// Breaching endpoints in total.
+    breach_count: usize,
+}
+
+pub async fn evaluate_alert_rules(state: Arc<ServerState>) -> anyhow::Result<()> {
+    let rules = state.list_enabled_alert_rules().await?;
+    let mut summaries: HashMap<EndpointStatsWindow, Vec<EndpointSummary>> = HashMap::new();
+
+    for rule in rules {
+        let Some(window) = rule.window() else {
+            warn!(alert_rule_id = %rule.alert_rule_id, window = %rule.alert_rule_window, "Alert rule has an unknown window; skipped");
+            continue;
+        };
+        if let Entry::Vacant(entry) = summaries.entry(window) {
+            entry.insert(state.endpoint_stats.summary(window).await);
+        }
+        let breaches = rule.breaches(&summaries[&window]);
+        if breaches.is_empty() || !state.claim_alert_rule_firing(&rule).await? {
+            continue;
+        }
+        fire(&state, &rule, &breaches).await;
+    }
+    Ok(())
+}
+
+async fn fire(state: &ServerState, rule: &AlertRule, breaches: &[AlertBreach]) {
+    let alert = EndpointAlert {
+        app: state.get_app_name_version(),
+        alert_rule_id: rule.alert_rule_id,
+        rule_name: &rule.alert_rule_name,
+        metric: &rule.alert_rule_metric,
+        threshold: rule.alert_rule_threshold,
+        window: &rule.alert_rule_window,
+        fired_at: Utc::now(),
+        breaches: &breaches[..breaches.len().min(MAX_BREACHES_PER_ALERT)],
+        breach_count: breaches.len(),
+    };
+    warn!(
+        alert_rule_id = %rule.alert_rule_id,
+        rule_name = %rule.alert_rule_name,
+        breach_count = breaches.len(),
+        "Alert rule fired"
+    );
+
+    for recipient in rule
+        .alert_rule_emails
+        .iter()
+        .map(|r| r.trim())
+        .filter(|r| !r.is_empty())
+    {
+        if let Err(e) = send_email(state, recipient, &alert).await {
+            error!(alert_rule_id = %rule.alert_rule_id, recipient, error = ?e, "Failed to send endpoint alert email");
+        }
+    }
+
+    if let Some(url) = &rule.alert_rule_webhook_url
+        && let Err(e) = queue_webhook(state, url, &alert).await
+    {
+        error!(alert_rule_id = %rule.alert_rule_id, error = ?e, "Failed to queue endpoint alert webhook");
+    }
+}
+
+async fn send_email(
+    state: &ServerState,
+    recipient: &str,
+    alert: &EndpointAlert<'_>,
+) -> anyhow::Result<()> {
+    if let Some(reason) = state.email_suppression_reason(recipient).await? {
+        warn!(alert_rule_id = %alert.alert_rule_id, recipient, %reason, "Alert recipient is suppressed; not emailing");
+        return Ok(());
+    }
+    let sender = state.email_sender();
+    let to: Mailbox = recipient.parse()?;
+    let mut builder = Message::builder()
+        .from(sender.from.clone())
+        .to(to)
+        .subject(sender.subject(&format!(
+            "[{}] {}: {} above {} on {} endpoint(s)",
+            alert.app, alert.rule_name, alert.metric, alert.threshold, alert.breach_count
+        )));
+    if let Some(reply_to) = &sender.reply_to {
+        builder = builder.reply_to(reply_to.clone());
+    }
+    let message = builder
+        .header(lettre::message::header::ContentType::TEXT_PLAIN)
+        .body(email_body(alert))?;
+    state
+        .email_transport()
+        .send(OutgoingEmail::new(message).tag("kind", "endpoint_alert"))
+        .await?;
+    info!(alert_rule_id = %alert.alert_rule_id, recipient, "Endpoint alert email sent");
+    Ok(())
+}
+
+fn email_body(alert: &EndpointAlert<'_>) -> String {
+    let mut body = format!(
+        "Alert rule \"{}\" fired on {}: {} above {} over the last {} on {} endpoint(s).\n\nWorst first:\n",
+        alert.rule_name, alert.app, alert.metric, alert.threshold, alert.window, alert.breach_count
+    );
+    for breach in alert.breaches {
+        body.push_str(&format!(
+            "\n- {} {}: {:.3} ({} requests)\n",
+            breach.method, breach.route, breach.value, breach.requests
+        ));
+    }
+    body.push_str(&format!(
+        "\nSee GET /api/admin/endpoints/stats?window={} on that instance.\n",
+        alert.window
+    ));
+    body
+}
+
+async fn queue_webhook(
+    state: &ServerState,
+    url: &str,
+    alert: &EndpointAlert<'_>,
+) -> anyhow::Result<()> {
+    let task = DelayedTask::DeliverWebhook {
+        url: url.to_string(),
+        body: serde_json::to_value(alert)?,
+    };
+    enqueue(state, task).await?;
+    Ok(())
+}
@@ -0,0 +1 @@
+pub mod evaluate_alert_rules;
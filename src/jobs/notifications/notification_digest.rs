@@ -0,0 +1,50 @@
+//! Daily notification digest: users who turned `digest` on and have unread
+//! notifications that arrived since their last digest are queued one
+//! `notification_digest` email with the count and a signed unsubscribe
+//! link.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde_json::json;
+use tracing::{error, info};
+
+use crate::{init::state::ServerState, jobs::tasks::email::queue_notification_digest_email};
+
+pub async fn send_notification_digests(
+    state: Arc<ServerState>,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    let recipients = state.notification_digest_recipients().await?;
+    let now = Utc::now();
+    let mut queued = 0;
+    let mut failed = 0;
+    for recipient in &recipients {
+        let result = async {
+            queue_notification_digest_email(
+                &state,
+                recipient.user_id,
+                &recipient.user_email,
+                recipient.unread,
+            )
+            .await?;
+            state
+                .mark_notification_digest_sent(recipient.user_id, now)
+                .await
+        }
+        .await;
+        match result {
+            Ok(()) => queued += 1,
+            Err(e) => {
+                failed += 1;
+                error!(user_id = %recipient.user_id, error = ?e, "Failed to queue notification digest");
+            }
+        }
+    }
+    if queued > 0 {
+        info!(queued, "Queued notification digests");
+    }
+    if failed > 0 && queued == 0 {
+        anyhow::bail!("no notification digest could be queued ({failed} failed)");
+    }
+    Ok(Some(json!({ "queued": queued, "failed": failed })))
+}
@@ -0,0 +1,335 @@
+//! Asynchronous AVIF encoding + thumbnailing of accepted photograph uploads.
+//!
+//! Upload handlers stage the original to
+//! [`staged_original_path`] and insert a `pending` row (see
+//! `accept_photograph_for_processing`); this module encodes it, uploads both
+//! derivatives to S3 and flips the row to `ready` (or `failed` with an error
+//! message). Clients poll `GET /api/photographs/{photograph_id}/processing`.
+//!
+//! Concurrency is bounded by a semaphore (`num_cpus`), like the batch pipeline.
+//! The staged original is deleted once a job reaches a terminal state. On
+//! startup, [`recover_unfinished_processing`] re-enqueues rows whose staged
+//! original survived the restart and fails the rest.
+//!
+//! Jobs accepted from a tracked upload (`X-Upload-Id`) carry its
+//! [`UploadProgressReporter`] and report `processing`, then `done`/`failed`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Utc;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use tokio::sync::{Semaphore, mpsc};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::domain::photography::photographs::{PhotographContext, PhotographProcessingStatus};
+use crate::domain::upload_progress::{UploadProgressReporter, UploadStage};
+use crate::domain::webhook::webhook::WebhookEvent;
+use crate::errors::code_error::CodeError;
+use crate::init::state::ServerState;
+use crate::jobs::tasks::webhook::emit_webhook_event;
+use crate::schema::photographs;
+use crate::util::image::photograph_ingest::{delete_stored_objects, encode_and_store_photograph};
+use crate::util::image::raw_decode::RawFormat;
+
+/// (id, owner, upload content type, raw format, watermark) of an unfinished row.
+type UnfinishedRow = (Uuid, Uuid, Option<String>, Option<String>, bool);
+
+/// Default bound on queued (not yet running) jobs; override with
+/// `IMAGE_PROCESSING_QUEUE_CAPACITY`.
+pub const DEFAULT_IMAGE_PROCESSING_QUEUE_CAPACITY: usize = 256;
+
+pub struct ImageProcessingJob {
+    pub photograph_id: Uuid,
+    pub user_id: Uuid,
+    pub staged_path: PathBuf,
+    pub content_type: Option<String>,
+    pub raw_format: Option<RawFormat>,
+    pub watermark: bool,
+    pub progress: Option<UploadProgressReporter>,
+}
+
+/// Root directory under the system temp dir for staged originals awaiting
+/// processing.
+pub fn processing_root_dir() -> PathBuf {
+    std::env::temp_dir().join("cyhdev-processing")
+}
+
+pub fn staged_original_path(photograph_id: Uuid) -> PathBuf {
+    processing_root_dir().join(format!("{photograph_id}.orig"))
+}
+
+/// Drain the queue forever, running each job on its own task under a
+/// semaphore permit so a panicking job cannot take the dispatcher down.
+pub async fn run_image_processing_dispatcher(
+    state: Arc<ServerState>,
+    mut receiver: mpsc::Receiver<ImageProcessingJob>,
+) {
+    let semaphore = Arc::new(Semaphore::new(num_cpus::get().max(1)));
+
+    while let Some(job) = receiver.recv().await {
+        let permit = match Arc::clone(&semaphore).acquire_owned().await {
+            Ok(permit) => permit,
+            Err(e) => {
+                error!(error = %e, photograph_id = %job.photograph_id, "Processing semaphore closed");
+                mark_processing_failed(&state, job.photograph_id, "internal scheduling error")
+                    .await;
+                continue;
+            }
+        };
+
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let photograph_id = job.photograph_id;
+            let staged_path = job.staged_path.clone();
+            let res = tokio::spawn(process_job(Arc::clone(&state), job)).await;
+            if let Err(join_err) = res {
+                error!(photograph_id = %photograph_id, error = %join_err, "Image processing job panicked");
+                mark_processing_failed(&state, photograph_id, "processing task panicked").await;
+            }
+            let _ = tokio::fs::remove_file(&staged_path).await;
+            drop(permit);
+        });
+    }
+
+    warn!("Image processing queue closed; dispatcher exiting");
+}
+
+async fn process_job(state: Arc<ServerState>, job: ImageProcessingJob) {
+    let ImageProcessingJob {
+        photograph_id,
+        user_id,
+        staged_path,
+        content_type,
+        raw_format,
+        watermark,
+        progress,
+    } = job;
+
+    set_status(
+        &state,
+        photograph_id,
+        PhotographProcessingStatus::Processing,
+    )
+    .await;
+    if let Some(progress) = &progress {
+        progress.stage(UploadStage::Processing);
+    }
+
+    let original = match tokio::fs::read(&staged_path).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!(error = ?e, photograph_id = %photograph_id, path = %staged_path.display(), "Failed to read staged original");
+            mark_processing_failed(&state, photograph_id, "staged original unreadable").await;
+            report_failed(progress, "staged original unreadable");
+            return;
+        }
+    };
+
+    let stored = match encode_and_store_photograph(
+        &state,
+        original,
+        user_id,
+        content_type.as_deref(),
+        raw_format,
+        watermark,
+        PhotographContext::Photography,
+    )
+    .await
+    {
+        Ok(stored) => stored,
+        Err(e) => {
+            // Name the existing photograph so the uploader can find it.
+            let reason = if e.error_code == CodeError::DUPLICATE_PHOTOGRAPH.error_code {
+                &e.error_message
+            } else {
+                &e.message
+            };
+            mark_processing_failed(&state, photograph_id, reason).await;
+            report_failed(progress, reason);
+            return;
+        }
+    };
+
+    let mut conn = match state.get_conn().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!(error = ?e, photograph_id = %photograph_id, "Failed to get DB connection from pool");
+            delete_stored_objects(&state, &stored).await;
+            mark_processing_failed(&state, photograph_id, "database connection error").await;
+            report_failed(progress, "database connection error");
+            return;
+        }
+    };
+
+    if let Some(progress) = &progress {
+        progress.stage(UploadStage::Storing);
+    }
+    let update_res =
+        diesel::update(photographs::table.filter(photographs::photograph_id.eq(photograph_id)))
+            .set((
+                &stored.photograph_exif,
+                photographs::photograph_shot_at.eq(stored.photograph_shot_at),
+                photographs::photograph_image_type.eq(stored.photograph_image_type),
+                photographs::photograph_is_on_cloud.eq(true),
+                photographs::photograph_link.eq(&stored.photograph_link),
+                photographs::photograph_thumbnail_link.eq(&stored.photograph_thumbnail_link),
+                photographs::photograph_renditions.eq(&stored.photograph_renditions),
+                photographs::photograph_raw_link.eq(&stored.photograph_raw_link),
+                photographs::photograph_private_original_key.eq(&stored.private_original_key),
+                photographs::photograph_phash.eq(stored.photograph_phash),
+                photographs::photograph_duplicate_of.eq(stored.photograph_duplicate_of),
+                photographs::photograph_processing_status.eq(PhotographProcessingStatus::Ready),
+                photographs::photograph_processing_error.eq(None::<String>),
+                photographs::photograph_updated_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)
+            .await;
+    drop(conn);
+
+    match update_res {
+        Ok(1) => {
+            info!(photograph_id = %photograph_id, user_id = %user_id, "Photograph processing complete");
+            emit_webhook_event(
+                &state,
+                WebhookEvent::photograph_uploaded(
+                    photograph_id,
+                    user_id,
+                    &stored.photograph_link,
+                    &stored.photograph_thumbnail_link,
+                ),
+            );
+            if let Some(progress) = progress {
+                progress.done(photograph_id);
+            }
+        }
+        Ok(_) => {
+            // The row was deleted while processing; its objects are orphans.
+            warn!(photograph_id = %photograph_id, "Photograph row vanished during processing");
+            delete_stored_objects(&state, &stored).await;
+            report_failed(progress, "photograph deleted during processing");
+        }
+        Err(e) => {
+            error!(error = ?e, photograph_id = %photograph_id, "Failed to record processed photograph");
+            delete_stored_objects(&state, &stored).await;
+            mark_processing_failed(
+                &state,
+                photograph_id,
+                "failed to record processed photograph",
+            )
+            .await;
+            report_failed(progress, "failed to record processed photograph");
+        }
+    }
+}
+
+fn report_failed(progress: Option<UploadProgressReporter>, reason: &str) {
+    if let Some(progress) = progress {
+        progress.failed(reason);
+    }
+}
+
+async fn set_status(state: &ServerState, photograph_id: Uuid, status: PhotographProcessingStatus) {
+    let mut conn = match state.get_conn().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            warn!(error = ?e, photograph_id = %photograph_id, "Failed to get DB connection to update processing status");
+            return;
+        }
+    };
+    if let Err(e) =
+        diesel::update(photographs::table.filter(photographs::photograph_id.eq(photograph_id)))
+            .set((
+                photographs::photograph_processing_status.eq(status),
+                photographs::photograph_updated_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)
+            .await
+    {
+        warn!(error = ?e, photograph_id = %photograph_id, "Failed to update processing status");
+    }
+}
+
+/// Best-effort: flip a row to `failed` with a client-visible reason.
+pub async fn mark_processing_failed(state: &ServerState, photograph_id: Uuid, reason: &str) {
+    let mut conn = match state.get_conn().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!(error = ?e, photograph_id = %photograph_id, "Failed to get DB connection to mark processing failed");
+            return;
+        }
+    };
+    if let Err(e) =
+        diesel::update(photographs::table.filter(photographs::photograph_id.eq(photograph_id)))
+            .set((
+                photographs::photograph_processing_status.eq(PhotographProcessingStatus::Failed),
+                photographs::photograph_processing_error.eq(reason),
+                photographs::photograph_updated_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)
+            .await
+    {
+        error!(error = ?e, photograph_id = %photograph_id, "Failed to mark photograph processing failed");
+    }
+}
+
+/// Re-enqueue rows left `pending`/`processing` by a previous process whose
+/// staged original still exists; fail the rest (`IN ('pending', 'processing')`
+/// is covered by `idx_photographs_processing_unfinished`).
+pub async fn recover_unfinished_processing(state: Arc<ServerState>) -> anyhow::Result<()> {
+    let mut conn = state.get_conn().await?;
+    let unfinished: Vec<UnfinishedRow> = photographs::table
+        .filter(photographs::photograph_processing_status.eq_any(vec![
+            PhotographProcessingStatus::Pending,
+            PhotographProcessingStatus::Processing,
+        ]))
+        .order(photographs::photograph_created_at.asc())
+        .select((
+            photographs::photograph_id,
+            photographs::user_id,
+            photographs::photograph_upload_content_type,
+            photographs::photograph_raw_format,
+            photographs::photograph_watermarked,
+        ))
+        .load(&mut conn)
+        .await?;
+    drop(conn);
+
+    if unfinished.is_empty() {
+        return Ok(());
+    }
+
+    let mut requeued: usize = 0;
+    let mut failed: usize = 0;
+    for (photograph_id, user_id, content_type, raw_format, watermark) in unfinished {
+        let staged_path = staged_original_path(photograph_id);
+        if !tokio::fs::try_exists(&staged_path).await.unwrap_or(false) {
+            mark_processing_failed(&state, photograph_id, "staged original lost on restart").await;
+            failed += 1;
+            continue;
+        }
+        let job = ImageProcessingJob {
+            photograph_id,
+            user_id,
+            staged_path,
+            content_type,
+            raw_format: raw_format.as_deref().and_then(RawFormat::from_extension),
+            watermark,
+            progress: None,
+        };
+        if state.image_processing_queue.enqueue(job).await.is_err() {
+            mark_processing_failed(&state, photograph_id, "processing queue closed").await;
+            failed += 1;
+        } else {
+            requeued += 1;
+        }
+    }
+
+    info!(
+        requeued,
+        failed, "Recovered unfinished photograph processing jobs"
+    );
+    Ok(())
+}
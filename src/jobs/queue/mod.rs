@@ -0,0 +1,59 @@
+//! In-process job queues for work that must not run on the request path.
+//!
+//! A [`JobQueue`] is a bounded tokio mpsc channel held on `ServerState`:
+//! handlers enqueue without blocking (a full queue is surfaced to the caller
+//! instead of buffering unboundedly) and a single dispatcher, started from
+//! `task_init`, takes the receiver once and fans jobs out to bounded workers.
+
+pub mod image_processing;
+
+use std::sync::Mutex;
+
+use tokio::sync::mpsc;
+
+pub struct JobQueue<J> {
+    sender: mpsc::Sender<J>,
+    receiver: Mutex<Option<mpsc::Receiver<J>>>,
+    capacity: usize,
+}
+
+impl<J> JobQueue<J> {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, receiver) = mpsc::channel(capacity);
+        Self {
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+            capacity,
+        }
+    }
+
+    /// Enqueue without waiting. Hands the job back when the queue is full or
+    /// its dispatcher is gone, so the caller can clean up.
+    pub fn try_enqueue(&self, job: J) -> Result<(), J> {
+        self.sender.try_send(job).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(job) | mpsc::error::TrySendError::Closed(job) => job,
+        })
+    }
+
+    /// Enqueue, waiting for capacity. For background producers (startup
+    /// recovery) that may outrun the queue bound.
+    pub async fn enqueue(&self, job: J) -> Result<(), J> {
+        self.sender.send(job).await.map_err(|e| e.0)
+    }
+
+    /// Take the receiving half. Returns `None` after the first call, so only
+    /// one dispatcher can ever drain the queue.
+    pub fn take_receiver(&self) -> Option<mpsc::Receiver<J>> {
+        self.receiver.lock().ok()?.take()
+    }
+
+    /// Jobs currently waiting for a worker.
+    pub fn depth(&self) -> usize {
+        self.capacity - self.sender.capacity()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
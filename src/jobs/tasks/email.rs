@@ -0,0 +1,128 @@
+//! Transactional emails. Handlers queue them in the outbox (`email_outbox`)
+//! with [`queue_email`] and return; the outbox sender delivers them with
+//! [`send_outbox_email`], so neither request latency nor an SMTP outage
+//! reaches the caller.
+
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    DOMAIN_NAME,
+    domain::{
+        email::email_outbox::{EmailOutboxInsertable, EmailOutboxRow},
+        i18n::ui_text::locale::UiLocale,
+    },
+    init::state::ServerState,
+    schema::users,
+    util::email::{
+        message::EmailMessage,
+        template::EmailTemplate,
+        transport::{OutgoingEmail, SentEmail},
+        unsubscribe::EmailTopic,
+    },
+};
+
+/// Put `email` in the outbox. Returns its outbox id.
+pub async fn queue_email(state: &ServerState, email: &EmailMessage) -> anyhow::Result<Uuid> {
+    let row = EmailOutboxInsertable::new(email)?;
+    let email_id = row.email_outbox_id;
+    state.insert_email_outbox(row).await?;
+    info!(%email_id, template = email.template().as_str(), "Email queued");
+    Ok(email_id)
+}
+
+pub async fn queue_validation_email(
+    state: &ServerState,
+    user_email: &str,
+    token_id: Uuid,
+    valid_until: DateTime<Utc>,
+) -> anyhow::Result<Uuid> {
+    let email = EmailMessage::new(EmailTemplate::VerifyEmail, user_email)
+        .var(
+            "link",
+            format!(
+                "https://{DOMAIN_NAME}/api/auth/verify-user-email?email_validation_token_id={token_id}"
+            ),
+        )
+        .var("valid_until", valid_until.format("%Y-%m-%d %H:%M UTC"));
+    queue_email(state, &email).await
+}
+
+pub async fn queue_password_reset_email(
+    state: &ServerState,
+    user_email: &str,
+    token_id: Uuid,
+) -> anyhow::Result<Uuid> {
+    let email = EmailMessage::new(EmailTemplate::PasswordReset, user_email).var(
+        "link",
+        format!("https://{DOMAIN_NAME}/reset-password?token={token_id}"),
+    );
+    queue_email(state, &email).await
+}
+
+pub async fn queue_notification_digest_email(
+    state: &ServerState,
+    user_id: Uuid,
+    user_email: &str,
+    unread: i64,
+) -> anyhow::Result<Uuid> {
+    let token = state
+        .unsubscribe_key()
+        .token(user_id, EmailTopic::NotificationDigest);
+    let email = EmailMessage::new(EmailTemplate::NotificationDigest, user_email)
+        .var("count", unread)
+        .var("link", format!("https://{DOMAIN_NAME}/notifications"))
+        .unsubscribe(&token);
+    queue_email(state, &email).await
+}
+
+/// What became of a claimed outbox email.
+pub enum OutboxDelivery {
+    Sent(SentEmail),
+    /// Not sent: the recipient is suppressed for this reason (`bounce`,
+    /// `complaint` or `manual`).
+    Suppressed(String),
+}
+
+/// Render one claimed outbox row in the recipient's language and send it,
+/// tagged with its template and outbox id, unless the recipient is on the
+/// suppression list. A rejection comes back as an
+/// [`EmailSendError`](crate::util::email::transport::EmailSendError).
+pub async fn send_outbox_email(
+    state: &ServerState,
+    row: &EmailOutboxRow,
+) -> anyhow::Result<OutboxDelivery> {
+    if let Some(reason) = state
+        .email_suppression_reason(&row.email_outbox_recipient)
+        .await?
+    {
+        return Ok(OutboxDelivery::Suppressed(reason));
+    }
+    let email = row.message()?;
+    let locale = match email.locale_override() {
+        Some(locale) => locale,
+        None => recipient_locale(state, email.recipient()).await?,
+    };
+    let texts = state.ui_text_bundle(locale).await;
+    let message = email.to_message(state.email_sender(), locale, &texts)?;
+    let outgoing = OutgoingEmail::new(message)
+        .tag("template", row.email_outbox_template.as_str())
+        .tag("email_id", row.email_outbox_id.to_string());
+    let sent = state.email_transport().send(outgoing).await?;
+    Ok(OutboxDelivery::Sent(sent))
+}
+
+/// The account's `user_language`, or en-US when no account has the address.
+async fn recipient_locale(state: &ServerState, user_email: &str) -> anyhow::Result<UiLocale> {
+    let mut conn = state.get_conn().await?;
+    let language: Option<i32> = users::table
+        .filter(users::user_email.eq(user_email))
+        .select(users::user_language)
+        .first(&mut conn)
+        .await
+        .optional()?;
+    Ok(language.map_or(UiLocale::EnUs, UiLocale::from_language_code))
+}
@@ -0,0 +1,5 @@
+//! Executors for queued `DelayedTask` variants that are not tied to another
+//! job module.
+
+pub mod email;
+pub mod webhook;
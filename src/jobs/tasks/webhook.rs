@@ -0,0 +1,201 @@
+//! Outgoing webhook delivery for `DelayedTask::DeliverWebhook` and the
+//! registered, signed webhooks behind `DelayedTask::DeliverSignedWebhook`.
+//!
+//! A registered webhook receives a JSON body
+//! `{"id", "event", "created_at", "data"}` with the headers `X-Webhook-Event`,
+//! `X-Webhook-Delivery` (the delivery id, stable across retries) and
+//! `X-Webhook-Signature: t=<unix seconds>,v1=<hex HMAC-SHA256>`, where the MAC
+//! is keyed with the webhook's secret over `"{t}.{body}"`. Receivers should
+//! recompute it and reject stale timestamps.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        job::delayed_task::DelayedTask,
+        webhook::{
+            delivery::{WebhookDeliveryInsertable, WebhookDeliveryStatus},
+            webhook::WebhookEvent,
+        },
+    },
+    init::state::ServerState,
+    jobs::job_funcs::delayed::{MAX_ATTEMPTS, enqueue},
+};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub async fn deliver_webhook(
+    state: &ServerState,
+    url: &str,
+    body: &serde_json::Value,
+) -> anyhow::Result<()> {
+    state
+        .get_request_client()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .timeout(WEBHOOK_TIMEOUT)
+        .body(serde_json::to_vec(body)?)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Queue `event` for every enabled webhook subscribed to it. Runs on its own
+/// task, so the handler that raised the event never waits on the database.
+pub fn emit_webhook_event(state: &Arc<ServerState>, event: WebhookEvent) {
+    let state = Arc::clone(state);
+    tokio::spawn(async move {
+        if let Err(e) = queue_webhook_event(&state, &event).await {
+            error!(event = event.event_type.as_str(), error = ?e, "Failed to queue webhook deliveries");
+        }
+    });
+}
+
+async fn queue_webhook_event(state: &ServerState, event: &WebhookEvent) -> anyhow::Result<()> {
+    let webhook_ids = state.subscribed_webhook_ids(event.event_type).await?;
+    if webhook_ids.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::json!({
+        "id": Uuid::new_v4(),
+        "event": event.event_type.as_str(),
+        "created_at": Utc::now(),
+        "data": event.data,
+    });
+    let deliveries: Vec<WebhookDeliveryInsertable> = webhook_ids
+        .into_iter()
+        .map(|webhook_id| WebhookDeliveryInsertable {
+            webhook_delivery_id: Uuid::new_v4(),
+            webhook_id,
+            webhook_delivery_event_type: event.event_type.as_str().to_string(),
+            webhook_delivery_payload: payload.clone(),
+        })
+        .collect();
+    let delivery_ids: Vec<Uuid> = deliveries.iter().map(|d| d.webhook_delivery_id).collect();
+    state.insert_webhook_deliveries(deliveries).await?;
+
+    for delivery_id in delivery_ids {
+        enqueue(state, DelayedTask::DeliverSignedWebhook { delivery_id }).await?;
+    }
+    Ok(())
+}
+
+/// One attempt at a delivery. An error makes the task queue retry it with
+/// backoff; the delivery is marked failed along with the task's last attempt.
+pub async fn deliver_signed_webhook(state: &ServerState, delivery_id: Uuid) -> anyhow::Result<()> {
+    let Some((delivery, webhook)) = state.get_webhook_delivery(delivery_id).await? else {
+        // Deleted along with its webhook.
+        return Ok(());
+    };
+    if delivery.webhook_delivery_status == WebhookDeliveryStatus::Succeeded.as_str() {
+        return Ok(());
+    }
+    if !webhook.webhook_enabled {
+        state
+            .record_webhook_delivery_attempt(
+                delivery_id,
+                WebhookDeliveryStatus::Failed,
+                None,
+                Some("webhook disabled before delivery"),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let body = serde_json::to_vec(&delivery.webhook_delivery_payload)?;
+    let timestamp = Utc::now().timestamp();
+    let signature = sign_payload(&webhook.webhook_secret, timestamp, &body)?;
+
+    let result = state
+        .get_request_client()
+        .post(&webhook.webhook_url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("x-webhook-event", &delivery.webhook_delivery_event_type)
+        .header("x-webhook-delivery", delivery_id.to_string())
+        .header(
+            "x-webhook-signature",
+            format!("t={timestamp},v1={signature}"),
+        )
+        .timeout(WEBHOOK_TIMEOUT)
+        .body(body)
+        .send()
+        .await;
+    let (response_status, error) = match result {
+        Ok(response) if response.status().is_success() => {
+            (Some(i32::from(response.status().as_u16())), None)
+        }
+        Ok(response) => (
+            Some(i32::from(response.status().as_u16())),
+            Some(format!("endpoint answered {}", response.status())),
+        ),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    let attempts = delivery.webhook_delivery_attempts + 1;
+    let status = match &error {
+        None => WebhookDeliveryStatus::Succeeded,
+        Some(_) if attempts >= MAX_ATTEMPTS => WebhookDeliveryStatus::Failed,
+        Some(_) => WebhookDeliveryStatus::Pending,
+    };
+    state
+        .record_webhook_delivery_attempt(delivery_id, status, response_status, error.as_deref())
+        .await?;
+
+    match error {
+        None => {
+            info!(%delivery_id, webhook_id = %webhook.webhook_id, event = %delivery.webhook_delivery_event_type, "Webhook delivered");
+            Ok(())
+        }
+        Some(error) => {
+            warn!(%delivery_id, webhook_id = %webhook.webhook_id, attempts, error = %error, "Webhook delivery failed");
+            Err(anyhow::anyhow!(error))
+        }
+    }
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp}.{body}"` keyed with `secret`.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> anyhow::Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Invalid webhook secret: {}", e))?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    Ok(format!("{:x}", mac.finalize().into_bytes()))
+}
+
+/// A fresh signing secret (`whsec_` and 64 hex characters).
+pub fn generate_webhook_secret() -> String {
+    format!(
+        "whsec_{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        let signature = sign_payload(
+            "whsec_test",
+            1_700_000_000,
+            br#"{"event":"post.published"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            signature,
+            "7b75d2efbd85f56f9d78d15cce3ed1b6a66b9deace99aae9bbbef7cc25692285"
+        );
+    }
+}
@@ -1,4 +1,10 @@
+use std::sync::Arc;
+
+use init::app_config::{self, AppConfig};
+use init::error_reporting;
+use init::log_levels::{LogFilterSpec, LogLevel, LogLevels, ReloadableFilter};
 use init::server_init::server_init_proc;
+use init::telemetry::{init_tracer_provider, otel_layer, shutdown_tracer_provider};
 use mimalloc::MiMalloc;
 use tracing::{error, info, level_filters};
 use tracing_subscriber::Layer;
@@ -9,10 +15,13 @@ use tracing_subscriber::util::SubscriberInitExt;
 static GLOBAL: MiMalloc = MiMalloc;
 
 pub mod build_info;
+pub mod codegen;
 pub mod docs;
 pub mod domain;
 pub mod dto;
 pub mod errors;
+pub mod graphql;
+pub mod grpc;
 pub mod handlers;
 pub mod init;
 pub mod jobs;
@@ -26,14 +35,26 @@ pub const LOGS_DIR: &str = "./logs/";
 // main function
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
+    // `codegen [--out DIR]`: write the OpenAPI spec and clients, then exit.
+    if std::env::args().nth(1).as_deref() == Some("codegen") {
+        return codegen::run(std::env::args().skip(2));
+    }
+
     let start = tokio::time::Instant::now();
     if std::env::var("IS_AWS_ECS").is_err() {
-        dotenvy::dotenv().map_err(|e| anyhow::anyhow!("Failed to load .env: {}", e))?;
+        // Optional once a config file can carry the settings.
+        if let Err(e) = dotenvy::dotenv()
+            && !e.not_found()
+        {
+            return Err(anyhow::anyhow!("Failed to load .env: {}", e));
+        }
     }
 
+    // Before tracing, so configuration errors go to stderr as they are.
+    let config = app_config::install(AppConfig::load()?)?;
+
     // TODO: replace with a more refined build.rs constant
-    let app_name_version = std::env::var("APP_NAME_VERSION")
-        .map_err(|e| anyhow::anyhow!("Failed to get APP_NAME_VERSION: {}", e))?;
+    let app_name_version = &config.app.name_version;
 
     let filename = app_name_version.to_string();
 
@@ -41,23 +62,38 @@ async fn main() -> anyhow::Result<()> {
         tracing_appender::rolling::daily(format!("{LOGS_DIR}{app_name_version}"), filename);
     let (_non_blocking_file, _guard) = tracing_appender::non_blocking(file_appender);
 
+    // Both filters can be changed at runtime through /api/admin/logging.
+    let (console_filter, console_levels) =
+        ReloadableFilter::new(LogFilterSpec::level(LogLevel::Info));
     let console_layer = tracing_subscriber::fmt::layer()
         // .json()
         .with_ansi(true)
         .with_target(true)
         .pretty()
-        .with_filter(level_filters::LevelFilter::INFO);
+        .with_filter(console_filter);
 
+    let (file_filter, file_levels) = ReloadableFilter::new(LogFilterSpec::level(LogLevel::Debug));
     let file_layer = tracing_subscriber::fmt::layer()
         .with_target(true)
         .json()
         .with_writer(_non_blocking_file)
-        .with_filter(level_filters::LevelFilter::DEBUG);
+        .with_filter(file_filter);
+    let log_levels = Arc::new(LogLevels {
+        console: console_levels,
+        file: file_levels,
+    });
 
-    // Build a subscriber that combines both layers
+    // OTLP export when configured (see init::telemetry); INFO, like the console.
+    let tracer_provider = init_tracer_provider(app_name_version)?;
+    let otel_layer = tracer_provider
+        .as_ref()
+        .map(|provider| otel_layer(provider).with_filter(level_filters::LevelFilter::INFO));
+
+    // Build a subscriber that combines the layers
     tracing_subscriber::registry()
         .with(console_layer)
         .with(file_layer)
+        .with(otel_layer)
         .init();
 
     match rustls::crypto::aws_lc_rs::default_provider().install_default() {
@@ -68,22 +104,33 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    // Panics and ERROR-level CodeErrors go to the tracker when configured.
+    error_reporting::init(config);
+
     info!(event = "server_init_start", "Initializing server");
 
     // Apparently, when you listen in from Tokio's main thread, that slows down performance due to delegation overhead as the main thread is reserved...
-    let server_handle = tokio::spawn(async move { server_init_proc(start).await });
+    let server_handle =
+        tokio::spawn(async move { server_init_proc(start, config, log_levels).await });
 
-    match server_handle.await {
-        Ok(Ok(_)) => {}
+    let result = match server_handle.await {
+        Ok(Ok(_)) => Ok(()),
         Ok(Err(e)) => {
             error!(error = ?e, "Server initialization failed");
-            return Err(e);
+            Err(e)
         }
         Err(e) => {
             error!(error = ?e, "Server task join failed");
-            return Err(anyhow::anyhow!("Server task join error: {e}"));
+            Err(anyhow::anyhow!("Server task join error: {e}"))
         }
+    };
+
+    error_reporting::flush(std::time::Duration::from_secs(5)).await;
+
+    if let Some(provider) = tracer_provider {
+        // The batch exporter blocks while flushing.
+        tokio::task::spawn_blocking(move || shutdown_tracer_provider(provider)).await?;
     }
 
-    Ok(())
+    result
 }
@@ -3,23 +3,51 @@ use std::sync::Arc;
 use axum::{
     Router,
     extract::DefaultBodyLimit,
-    http::{HeaderValue, Method, header},
     middleware::{from_fn, from_fn_with_state},
-    routing::{delete, get, patch, post},
-};
-use tower_governor::{GovernorLayer, governor::GovernorConfigBuilder};
-use tower_http::{
-    compression::CompressionLayer,
-    cors::{AllowOrigin, CorsLayer},
+    routing::{delete, get, patch, post, put},
 };
+use tower_http::compression::CompressionLayer;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
-    DOMAIN_NAME,
     docs::ApiDoc,
+    graphql::graphql_handler,
     handlers::{
-        admin::{get_host_stats::ws_host_stats_handler, sync_i18n_cache::sync_i18n_cache},
+        admin::{
+            access_log::list_access_log,
+            admin_audit::list_admin_audit,
+            alert_rules::{
+                create_alert_rule, delete_alert_rule, list_alert_rules, update_alert_rule,
+            },
+            db_pool::get_db_pool_stats,
+            email_outbox::{list_email_outbox, requeue_email},
+            email_preview::preview_email,
+            email_suppressions::{
+                create_email_suppression, delete_email_suppression, list_email_suppressions,
+            },
+            endpoint_stats::get_endpoint_stats,
+            get_host_stats::ws_host_stats_handler,
+            jobs::{cancel_job, list_jobs, pause_job, resume_job},
+            log_files::{download_log_file, get_log_files},
+            logging::{get_logging, update_logging},
+            sent_emails::{get_sent_email, list_sent_emails},
+            session_purges::list_session_purges,
+            session_stats::get_session_stats,
+            stats::get_admin_stats,
+            storage_orphans::{get_storage_orphan_report, scan_storage_orphans},
+            sync_i18n_cache::sync_i18n_cache,
+            tasks::{list_tasks, requeue_task},
+            tls::{get_tls_status, reload_tls},
+            trash::restore_soft_deleted,
+            webhooks::{
+                create_webhook, delete_webhook, list_webhook_deliveries, list_webhooks,
+                update_webhook,
+            },
+        },
+        album::{
+            create_album, delete_album, get_album, get_albums, set_album_photographs, update_album,
+        },
         auth::{
             check_if_user_exists::check_if_user_exists_handler, is_superuser::is_superuser_handler,
             login::login, logout::logout, me::me_handler, reset_password::reset_password,
@@ -28,9 +56,9 @@ use crate::{
         },
         blog::{
             delete_comment::delete_comment, delete_post::delete_post, get_posts::get_posts,
-            read_post::read_post, rescind_comment_vote::rescind_comment_vote,
-            rescind_post_vote::rescind_post_vote, search_posts::search_posts,
-            submit_comment::submit_comment, submit_post::submit_post,
+            list_comments::list_comments, live_events::get_live_events, read_post::read_post,
+            rescind_comment_vote::rescind_comment_vote, rescind_post_vote::rescind_post_vote,
+            search_posts::search_posts, submit_comment::submit_comment, submit_post::submit_post,
             update_comment::update_comment, update_post::update_post, vote_comment::vote_comment,
             vote_post::vote_post,
         },
@@ -39,16 +67,31 @@ use crate::{
             get_languages::get_languages,
             get_subdivisions_for_country::get_subdivisions_for_country,
         },
+        email::{
+            ses_feedback::ses_feedback,
+            unsubscribe::{check_unsubscribe, unsubscribe},
+        },
         geo_ip::{lookup_ip::lookup_ip_info, lookup_my_ip::lookup_my_ip_info},
         i18n::get_ui_text_bundle::get_ui_text_bundle,
         live_chat::{get_live_chat_cache_stats, get_live_chat_messages, live_chat_ws_handler},
+        notifications::{
+            get_notification_preferences, list_notifications, mark_notification_read,
+            mark_notifications_read, update_notification_preferences, ws_notifications_handler,
+        },
         photography::{
             batch_list::batch_list, batch_status::batch_status, batch_upload::batch_upload,
+            confirm_photograph_upload::confirm_photograph_upload,
             delete_photograph_comment::delete_photograph_comment,
-            delete_photographs::delete_photographs, get_photographs::get_photographs,
-            read_photograph::read_photograph,
+            delete_photographs::delete_photographs,
+            download_photograph_original::download_photograph_original,
+            get_photograph_duplicates::get_photograph_duplicates,
+            get_photograph_processing::get_photograph_processing,
+            get_photograph_variant::get_photograph_variant, get_photographs::get_photographs,
+            get_photographs_in_bounds::get_photographs_in_bounds,
+            presign_photograph_upload::presign_photograph_upload, read_photograph::read_photograph,
             rescind_photograph_comment_vote::rescind_photograph_comment_vote,
             rescind_photograph_vote::rescind_photograph_vote,
+            set_photograph_tags::set_photograph_tags,
             submit_photograph_comment::submit_photograph_comment,
             update_photograph_comment::update_photograph_comment,
             upload_photograph::upload_photograph, vote_photograph::vote_photograph,
@@ -56,301 +99,470 @@ use crate::{
         },
         server::{
             get_host_fastfetch::get_host_fastfetch, healthcheck::healthcheck,
-            lookup_ip_loc::lookup_ip_location, root::root_handler,
+            lookup_ip_loc::lookup_ip_location, metrics::get_metrics, root::root_handler,
+            serve_storage_object::serve_storage_object, site::get_site_info,
             visitor_board::get_visitor_board_entries,
         },
+        upload::get_upload_progress::get_upload_progress,
         user::{get_user_info::get_user_info, upload_profile_picture::upload_profile_picture},
         wasm_module::{
-            delete_wasm_module, get_wasm_modules, serve_wasm, update_wasm_module,
-            update_wasm_module_assets, upload_wasm_module,
+            delete_wasm_module, get_wasm_module_stats, get_wasm_modules, search_wasm_modules,
+            serve_wasm, serve_wasm_file, update_wasm_module, update_wasm_module_assets,
+            upload_wasm_module,
         },
     },
-    init::state::{DeploymentEnvironment, ServerState},
+    init::{
+        readiness::probe_router,
+        state::{DeploymentEnvironment, ServerState, cache_versions::CachedResource},
+    },
+    util::http::{rate_limit::RateLimitPolicy, timeout::TimeoutBudget},
 };
 
 use super::middleware::{
-    auth::auth_middleware, is_logged_in::is_logged_in_middleware, logging::log_middleware,
+    api_key::api_key_check_middleware,
+    auth::auth_middleware,
+    etag::{EtagState, etag_middleware},
+    is_logged_in::is_logged_in_middleware,
+    logging::log_middleware,
+    rate_limit::{RateLimitState, rate_limit_middleware},
+    request_span::request_span_layer,
+    response_cache::{ResponseCacheState, response_cache_middleware},
     role::require_superuser_middleware,
+    site::site_middleware,
+    timeout::{TimeoutState, timeout_middleware},
 };
 
+mod cors;
+mod dev_assets;
 mod static_assets;
 
+use cors::cors_layer;
 use static_assets::static_asset_handler;
 
 const MAX_REQUEST_SIZE: usize = 1024 * 1024 * 150; // 150MB
 const BATCH_REQUEST_SIZE: usize = 1024 * 1024 * 1024; // 1GB (route-scoped to batch upload)
 
-const REPLENISHED_EVERY_MILLISECONDS: u64 = 63;
-const RATE_LIMIT_BURST_SIZE: u32 = 1024;
+/// Canonical prefix of the current API version.
+pub const API_V1_PREFIX: &str = "/api/v1";
+/// Unversioned prefix, an alias of `API_V1_PREFIX`.
+pub const API_ALIAS_PREFIX: &str = "/api";
 
 pub fn build_router(state: Arc<ServerState>) -> axum::Router {
     let auth_middleware = from_fn_with_state(state.clone(), auth_middleware);
     let require_superuser_middleware = from_fn(require_superuser_middleware);
-    // let api_key_check_middleware = from_fn_with_state(state.clone(), api_key_check_middleware);
+    let api_key_check_middleware = from_fn_with_state(state.clone(), api_key_check_middleware);
     let log_middleware = from_fn_with_state(state.clone(), log_middleware);
     let is_logged_in_middleware = from_fn_with_state(state.clone(), is_logged_in_middleware);
     let compression_middleware = CompressionLayer::new().zstd(true).gzip(true);
 
-    // Auth is cookie-based (session_id cookie with credentials), so CORS must NOT reflect an
-    // arbitrary Origin while allowing credentials. We build an explicit allow-list of trusted
-    // frontend origins. Never combine `AllowOrigin::any()` with `allow_credentials(true)`:
-    // tower-http panics on that combination at runtime.
-    let cors_layer = CorsLayer::new()
-        .allow_origin(AllowOrigin::list(build_trusted_origins(
-            state.get_deployment_environment(),
-        )))
-        .allow_credentials(true)
-        .allow_methods([
-            Method::GET,
-            Method::POST,
-            Method::PATCH,
-            Method::DELETE,
-            Method::OPTIONS,
-        ])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+    // Explicit origin allow-list with credentials unless configured otherwise; see `cors`.
+    let cors_layer = cors_layer(state.get_deployment_environment(), &state.config.cors);
 
-    let governor_conf = match GovernorConfigBuilder::default()
-        .per_millisecond(REPLENISHED_EVERY_MILLISECONDS)
-        .burst_size(RATE_LIMIT_BURST_SIZE)
-        .finish()
-    {
-        Some(conf) => Some(Arc::new(conf)),
-        None => {
-            tracing::error!(
-                replenished_every_milliseconds = REPLENISHED_EVERY_MILLISECONDS,
-                burst_size = RATE_LIMIT_BURST_SIZE,
-                "Failed to build governor config; rate limiting disabled"
-            );
-            None
-        }
+    // Per-route token buckets (see util::http::rate_limit); the global one wraps
+    // the whole router further down.
+    let rate_limit = |policy: RateLimitPolicy| {
+        from_fn_with_state(
+            RateLimitState::new(state.clone(), policy),
+            rate_limit_middleware,
+        )
+    };
+    // Deadline for producing the response head (see util::http::timeout).
+    let timeout = |budget: TimeoutBudget| {
+        from_fn_with_state(TimeoutState::new(state.clone(), budget), timeout_middleware)
+    };
+    // Conditional GETs for cache-backed reads (see `init::state::cache_versions`).
+    let etag = |resource: CachedResource| {
+        from_fn_with_state(EtagState::new(state.clone(), resource), etag_middleware)
+    };
+    // Short-TTL response cache under the ETag layer, keyed by the same versions.
+    let response_cache = |resource: CachedResource| {
+        from_fn_with_state(
+            ResponseCacheState::new(state.clone(), resource),
+            response_cache_middleware,
+        )
     };
 
     // Publicly accessible API routes
     let public_router = Router::new()
-        .route("/api/healthcheck/server", get(healthcheck))
-        .route("/api/healthcheck/state", get(root_handler))
-        .route("/api/healthcheck/fastfetch", get(get_host_fastfetch))
-        .route("/ws/host-stats", get(ws_host_stats_handler))
+        .route("/healthcheck/server", get(healthcheck))
+        .route("/healthcheck/state", get(root_handler))
+        .route("/healthcheck/fastfetch", get(get_host_fastfetch))
+        .route("/site", get(get_site_info))
+        .route(
+            "/dropdown/language",
+            get(get_languages)
+                .layer(response_cache(CachedResource::Countries))
+                .layer(etag(CachedResource::Countries)),
+        )
+        .route(
+            "/dropdown/language/{language_id}",
+            get(get_language)
+                .layer(response_cache(CachedResource::Countries))
+                .layer(etag(CachedResource::Countries)),
+        )
+        .route(
+            "/dropdown/country",
+            get(get_countries)
+                .layer(response_cache(CachedResource::Countries))
+                .layer(etag(CachedResource::Countries)),
+        )
+        .route(
+            "/dropdown/country/{country_id}",
+            get(get_country)
+                .layer(response_cache(CachedResource::Countries))
+                .layer(etag(CachedResource::Countries)),
+        )
+        .route(
+            "/dropdown/country/{country_id}/subdivision",
+            get(get_subdivisions_for_country)
+                .layer(response_cache(CachedResource::Countries))
+                .layer(etag(CachedResource::Countries)),
+        )
+        .route(
+            "/visitor-board",
+            get(get_visitor_board_entries)
+                .layer(response_cache(CachedResource::VisitorBoard))
+                .layer(etag(CachedResource::VisitorBoard)),
+        )
+        .route("/geolocate/{ip_address}", get(lookup_ip_location))
+        .route("/geo-ip-info/me", get(lookup_my_ip_info))
+        .route("/geo-ip-info/{ip_address}", get(lookup_ip_info))
+        .route("/auth/me", get(me_handler))
+        .route("/auth/is-superuser", get(is_superuser_handler))
+        .route("/users/{user_name}", get(get_user_info))
+        .route(
+            "/blog/posts",
+            get(get_posts)
+                .layer(response_cache(CachedResource::Posts))
+                .layer(etag(CachedResource::Posts)),
+        )
+        .route("/blog/posts/{post_id}", get(read_post))
+        .route("/blog/posts/{post_id}/comments", get(list_comments))
+        .route("/blog/search", get(search_posts))
+        .route("/graphql", get(graphql_handler).post(graphql_handler))
+        .route("/events", get(get_live_events))
+        .route("/live-chat/messages", get(get_live_chat_messages))
+        .route("/live-chat/cache-stats", get(get_live_chat_cache_stats))
+        .route("/i18n/ui-text", get(get_ui_text_bundle))
+        .route("/photographs/get", get(get_photographs))
+        .route("/photographs/in-bounds", get(get_photographs_in_bounds))
+        .route("/photographs/{photograph_id}", get(read_photograph))
+        .route("/albums", get(get_albums))
+        .route("/albums/{album_id}", get(get_album))
+        // WASM modules - public read endpoints
+        .route("/wasm-modules", get(get_wasm_modules))
+        .route("/wasm-modules/search", get(search_wasm_modules))
+        .route("/wasm-modules/{wasm_module_id}/wasm", get(serve_wasm))
+        .route(
+            "/wasm-modules/{wasm_module_id}/files/{*file_path}",
+            get(serve_wasm_file),
+        )
+        .layer(rate_limit(RateLimitPolicy::Read));
+
+    // Public routes outside the versioned API namespace.
+    let public_root_router = Router::new()
         .route("/ws/live-chat", get(live_chat_ws_handler))
-        .route("/api/dropdown/language", get(get_languages))
-        .route("/api/dropdown/language/{language_id}", get(get_language))
-        .route("/api/dropdown/country", get(get_countries))
-        .route("/api/dropdown/country/{country_id}", get(get_country))
-        .route(
-            "/api/dropdown/country/{country_id}/subdivision",
-            get(get_subdivisions_for_country),
-        )
-        .route("/api/visitor-board", get(get_visitor_board_entries))
-        .route("/api/geolocate/{ip_address}", get(lookup_ip_location))
-        .route("/api/geo-ip-info/me", get(lookup_my_ip_info))
-        .route("/api/geo-ip-info/{ip_address}", get(lookup_ip_info))
-        .route("/api/auth/signup", post(signup_handler))
-        .route("/api/auth/me", get(me_handler))
-        .route("/api/auth/is-superuser", get(is_superuser_handler))
-        .route(
-            "/api/auth/check-if-user-exists",
+        .route("/img/{photograph_id}", get(get_photograph_variant))
+        // Local storage backend objects (404 on S3)
+        .route("/storage/{*key}", get(serve_storage_object))
+        .layer(rate_limit(RateLimitPolicy::Read));
+
+    // Credential endpoints: public, but held to a strict per-IP budget against
+    // password guessing, account enumeration, mail flooding and unsubscribe
+    // token guessing.
+    let credentials_router = Router::new()
+        .route("/auth/signup", post(signup_handler))
+        .route(
+            "/auth/check-if-user-exists",
             post(check_if_user_exists_handler),
         )
-        .route("/api/auth/login", post(login))
+        .route("/auth/login", post(login))
         .route(
-            "/api/auth/reset-password-request",
+            "/auth/reset-password-request",
             post(reset_password_request_process),
         )
-        .route("/api/auth/reset-password", post(reset_password))
-        .route("/api/auth/verify-user-email", get(verify_user_email))
-        .route("/api/users/{user_name}", get(get_user_info))
-        .route("/api/blog/posts", get(get_posts))
-        .route("/api/blog/posts/{post_id}", get(read_post))
-        .route("/api/blog/search", get(search_posts))
-        .route("/api/live-chat/messages", get(get_live_chat_messages))
-        .route("/api/live-chat/cache-stats", get(get_live_chat_cache_stats))
-        .route("/api/i18n/ui-text", get(get_ui_text_bundle))
-        .route("/api/photographs/get", get(get_photographs))
-        .route("/api/photographs/{photograph_id}", get(read_photograph))
-        // WASM modules - public read endpoints
-        .route("/api/wasm-modules", get(get_wasm_modules))
-        .route("/api/wasm-modules/{wasm_module_id}/wasm", get(serve_wasm));
+        .route("/auth/reset-password", post(reset_password))
+        .route("/auth/verify-user-email", get(verify_user_email))
+        .route(
+            "/email/unsubscribe",
+            get(check_unsubscribe).post(unsubscribe),
+        )
+        .layer(rate_limit(RateLimitPolicy::Auth));
+
+    // Posted by SNS, which authenticates each message by its signature rather
+    // than a session; kept off the per-IP limits so a bounce burst is not lost.
+    let sns_router = Router::new().route("/email/ses-feedback", post(ses_feedback));
+
+    // Scraped by Prometheus, which cannot hold a session cookie.
+    let metrics_router = Router::new()
+        .route("/metrics", get(get_metrics))
+        .layer(api_key_check_middleware);
 
     // API routes requiring authentication
     let protected_router = Router::new()
-        .route("/api/auth/logout", post(logout))
+        .route("/auth/logout", post(logout))
+        .route("/blog/{post_id}/vote", post(vote_post))
+        .route("/blog/{post_id}/{comment_id}/vote", post(vote_comment))
+        .route("/blog/{post_id}/vote", delete(rescind_post_vote))
+        .route("/blog/{post_id}/{comment_id}", delete(delete_comment))
+        .route("/blog/{post_id}/{comment_id}", patch(update_comment))
+        .route("/blog/{post_id}", delete(delete_post))
+        .route("/blog/{post_id}/comment", post(submit_comment))
         .route(
-            "/api/user/upload-profile-picture",
-            post(upload_profile_picture),
-        )
-        .route("/api/blog/{post_id}/vote", post(vote_post))
-        .route("/api/blog/{post_id}/{comment_id}/vote", post(vote_comment))
-        .route("/api/blog/{post_id}/vote", delete(rescind_post_vote))
-        .route("/api/blog/{post_id}/{comment_id}", delete(delete_comment))
-        .route("/api/blog/{post_id}/{comment_id}", patch(update_comment))
-        .route("/api/blog/{post_id}", delete(delete_post))
-        .route("/api/blog/{post_id}/comment", post(submit_comment))
-        .route(
-            "/api/blog/{post_id}/{comment_id}/vote",
+            "/blog/{post_id}/{comment_id}/vote",
             delete(rescind_comment_vote),
         )
-        // Photograph social (votes + comments), mirroring the blog tier.
         .route(
-            "/api/photographs/{photograph_id}/vote",
-            post(vote_photograph),
+            "/photographs/{photograph_id}/original",
+            get(download_photograph_original),
         )
+        // Photograph social (votes + comments), mirroring the blog tier.
+        .route("/photographs/{photograph_id}/vote", post(vote_photograph))
         .route(
-            "/api/photographs/{photograph_id}/vote",
+            "/photographs/{photograph_id}/vote",
             delete(rescind_photograph_vote),
         )
         .route(
-            "/api/photographs/{photograph_id}/comment",
+            "/photographs/{photograph_id}/comment",
             post(submit_photograph_comment),
         )
         .route(
-            "/api/photographs/{photograph_id}/{comment_id}/vote",
+            "/photographs/{photograph_id}/{comment_id}/vote",
             post(vote_photograph_comment),
         )
         .route(
-            "/api/photographs/{photograph_id}/{comment_id}/vote",
+            "/photographs/{photograph_id}/{comment_id}/vote",
             delete(rescind_photograph_comment_vote),
         )
         .route(
-            "/api/photographs/{photograph_id}/{comment_id}",
+            "/photographs/{photograph_id}/{comment_id}",
             patch(update_photograph_comment),
         )
         .route(
-            "/api/photographs/{photograph_id}/{comment_id}",
+            "/photographs/{photograph_id}/{comment_id}",
             delete(delete_photograph_comment),
         )
+        .route("/uploads/{upload_id}/progress", get(get_upload_progress))
+        .route("/notifications", get(list_notifications))
+        .route("/notifications/read", post(mark_notifications_read))
+        .route(
+            "/notifications/{notification_id}/read",
+            post(mark_notification_read),
+        )
+        .route(
+            "/notifications/preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .layer(rate_limit(RateLimitPolicy::Write))
+        .layer(auth_middleware.clone());
+
+    // Multipart uploads get the upload budget instead of the API one.
+    let protected_upload_router = Router::new()
+        .route("/user/upload-profile-picture", post(upload_profile_picture))
+        .layer(rate_limit(RateLimitPolicy::Write))
+        .layer(auth_middleware.clone());
+
+    let protected_root_router = Router::new()
+        .route("/ws/notifications", get(ws_notifications_handler))
+        .layer(rate_limit(RateLimitPolicy::Write))
+        .layer(auth_middleware.clone());
+
+    // Host metrics are for superusers only; the socket count is capped in the
+    // handler (`host_stats.max_subscribers`).
+    let superuser_root_router = Router::new()
+        .route("/ws/host-stats", get(ws_host_stats_handler))
+        .layer(rate_limit(RateLimitPolicy::Write))
+        .layer(require_superuser_middleware.clone())
         .layer(auth_middleware.clone());
 
     // Batch upload accepts large multi-file bodies. The route-scoped
     // DefaultBodyLimit here is closest to the handler, so it overrides the global
     // 150MB limit added later on api_router, without widening it for other routes.
     let batch_upload_router = Router::new()
-        .route("/api/photographs/batch-upload", post(batch_upload))
+        .route("/photographs/batch-upload", post(batch_upload))
         .layer(DefaultBodyLimit::max(BATCH_REQUEST_SIZE));
 
     let superuser_router = Router::new()
-        .route("/api/admin/sync-i18n-cache", get(sync_i18n_cache))
-        .route("/api/blog/posts", post(submit_post))
-        .route("/api/blog/{post_id}", patch(update_post))
-        .route("/api/photographs/upload", post(upload_photograph))
-        .route("/api/photographs/delete", delete(delete_photographs))
-        .route("/api/photographs/batch/{batch_id}", get(batch_status))
-        .route("/api/photographs/batches", get(batch_list))
-        // WASM modules - protected CUD endpoints
-        .route("/api/wasm-modules", post(upload_wasm_module))
+        .route("/admin/sync-i18n-cache", get(sync_i18n_cache))
         .route(
-            "/api/wasm-modules/{wasm_module_id}",
-            patch(update_wasm_module),
+            "/admin/photographs/duplicates",
+            get(get_photograph_duplicates),
         )
+        .route("/admin/jobs", get(list_jobs))
+        .route("/admin/jobs/{job_name}/pause", post(pause_job))
+        .route("/admin/jobs/{job_name}/resume", post(resume_job))
+        .route("/admin/jobs/{job_name}/cancel", post(cancel_job))
+        .route("/admin/tasks", get(list_tasks))
+        .route("/admin/tasks/{task_id}/requeue", post(requeue_task))
+        .route("/admin/email-outbox", get(list_email_outbox))
         .route(
-            "/api/wasm-modules/{wasm_module_id}/assets",
-            post(update_wasm_module_assets),
+            "/admin/email-outbox/{email_id}/requeue",
+            post(requeue_email),
+        )
+        .route(
+            "/admin/trash/{kind}/{id}/restore",
+            post(restore_soft_deleted),
+        )
+        .route("/admin/emails/preview/{template}", get(preview_email))
+        .route("/admin/emails/sent", get(list_sent_emails))
+        .route("/admin/emails/sent/{id}", get(get_sent_email))
+        .route(
+            "/admin/email-suppressions",
+            get(list_email_suppressions).post(create_email_suppression),
+        )
+        .route(
+            "/admin/email-suppressions/{address}",
+            delete(delete_email_suppression),
+        )
+        .route("/admin/sessions/purges", get(list_session_purges))
+        .route("/admin/sessions/stats", get(get_session_stats))
+        .route("/admin/db/pool", get(get_db_pool_stats))
+        .route("/admin/stats", get(get_admin_stats))
+        .route("/admin/endpoints/stats", get(get_endpoint_stats))
+        .route(
+            "/admin/alerts/rules",
+            get(list_alert_rules).post(create_alert_rule),
+        )
+        .route(
+            "/admin/alerts/rules/{alert_rule_id}",
+            patch(update_alert_rule).delete(delete_alert_rule),
+        )
+        .route("/admin/logging", get(get_logging).put(update_logging))
+        .route("/admin/logs", get(get_log_files))
+        .route("/admin/logs/download", get(download_log_file))
+        .route("/admin/tls", get(get_tls_status))
+        .route("/admin/tls/reload", post(reload_tls))
+        .route("/admin/webhooks", get(list_webhooks).post(create_webhook))
+        .route(
+            "/admin/webhooks/{webhook_id}",
+            patch(update_webhook).delete(delete_webhook),
         )
         .route(
-            "/api/wasm-modules/{wasm_module_id}",
-            delete(delete_wasm_module),
+            "/admin/webhooks/{webhook_id}/deliveries",
+            get(list_webhook_deliveries),
+        )
+        .route("/admin/audit", get(list_admin_audit))
+        .route("/admin/access-log", get(list_access_log))
+        .route("/admin/storage/orphans", get(get_storage_orphan_report))
+        .route("/admin/storage/orphans/scan", post(scan_storage_orphans))
+        .route("/blog/posts", post(submit_post))
+        .route("/blog/{post_id}", patch(update_post))
+        .route(
+            "/photographs/{photograph_id}/processing",
+            get(get_photograph_processing),
+        )
+        .route("/photographs/delete", delete(delete_photographs))
+        .route("/photographs/presign", post(presign_photograph_upload))
+        .route(
+            "/photographs/presign/{upload_token}/confirm",
+            post(confirm_photograph_upload),
+        )
+        .route("/photographs/batch/{batch_id}", get(batch_status))
+        .route("/photographs/batches", get(batch_list))
+        .route(
+            "/photographs/{photograph_id}/tags",
+            put(set_photograph_tags),
+        )
+        .route("/albums", post(create_album))
+        .route("/albums/{album_id}", patch(update_album))
+        .route("/albums/{album_id}", delete(delete_album))
+        .route("/albums/{album_id}/photographs", put(set_album_photographs))
+        // WASM modules - protected CUD endpoints
+        .route("/wasm-modules/{wasm_module_id}", patch(update_wasm_module))
+        .route("/wasm-modules/{wasm_module_id}", delete(delete_wasm_module))
+        .route(
+            "/wasm-modules/{wasm_module_id}/stats",
+            get(get_wasm_module_stats),
+        )
+        .layer(require_superuser_middleware.clone())
+        .layer(auth_middleware.clone());
+
+    let superuser_upload_router = Router::new()
+        .route("/photographs/upload", post(upload_photograph))
+        .route("/wasm-modules", post(upload_wasm_module))
+        .route(
+            "/wasm-modules/{wasm_module_id}/assets",
+            post(update_wasm_module_assets),
         )
         .merge(batch_upload_router)
         .layer(require_superuser_middleware.clone())
         .layer(auth_middleware.clone());
 
-    // Combine all API routes and apply shared middleware. Rate limiting is intentionally NOT
-    // applied here; it is applied to the outer router below so that the static fallback and
-    // Swagger UI assets are throttled too (otherwise those surfaces are unbounded). CORS stays
-    // scoped to the API router only.
-    let api_router = public_router
+    // Version 1 of the API, with paths relative to its prefix. `layer` only wraps routes already
+    // added, so the upload routers are merged after the API timeout and keep their own budget.
+    let api_v1 = public_router
+        .merge(credentials_router)
         .merge(protected_router)
         .merge(superuser_router)
+        .merge(metrics_router)
+        .merge(sns_router)
+        .layer(timeout(TimeoutBudget::Api))
+        .merge(
+            protected_upload_router
+                .merge(superuser_upload_router)
+                .layer(timeout(TimeoutBudget::Upload)),
+        );
+
+    // Combine all API routes and apply shared middleware. The global rate limit is intentionally
+    // NOT applied here; it is applied to the outer router below so that the static fallback and
+    // Swagger UI assets are throttled too (otherwise those surfaces are unbounded). CORS stays
+    // scoped to the API router only.
+    //
+    // `/api/v1` is canonical and bare `/api` is an alias of it. A breaking version gets its own
+    // router nested at `/api/v2`, built from the routers above that it keeps unchanged plus its
+    // replacements; `/api` keeps pointing at v1 until the clients have moved.
+    let api_router = Router::new()
+        .nest(API_V1_PREFIX, api_v1.clone())
+        .nest(API_ALIAS_PREFIX, api_v1)
+        .merge(public_root_router)
+        .merge(protected_root_router)
+        .merge(superuser_root_router)
         .layer(is_logged_in_middleware)
-        // .layer(api_key_check_middleware)
         .layer(log_middleware)
         .layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE))
         .layer(cors_layer)
         .with_state(state.clone());
 
     // Final router: merge API routes and set the static asset handler as the fallback
-    let router = Router::new().merge(api_router);
+    let mut router = Router::new()
+        .merge(api_router)
+        .merge(probe_router(Arc::clone(&state.readiness)));
+
+    // Local only: the frontend comes from disk, with the `/__dev/reload` stream.
+    if let Some(dev_router) = dev_assets::dev_router(&state) {
+        router = router.merge(dev_router);
+    }
 
-    // Swagger UI is always available, but in prod it is gated behind auth + superuser.
+    // Swagger UI and the raw spec, outside prod unless `API_DOCS` says otherwise. When enabled in
+    // prod they are gated behind auth + superuser.
     //
     // NOTE: `SwaggerUi` itself doesn't expose `.layer(...)`, so we nest it under a router where we
     // can apply middleware layers.
-    let swagger_ui = SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi());
-
-    let mut swagger_router = Router::new().merge(swagger_ui);
-
-    if matches!(
+    let is_prod = matches!(
         state.get_deployment_environment(),
         DeploymentEnvironment::Prod
-    ) {
-        swagger_router = swagger_router
-            .layer(require_superuser_middleware)
-            .layer(auth_middleware.clone());
+    );
+    let mut swagger_router = Router::new();
+    if state.config.server.api_docs.unwrap_or(!is_prod) {
+        let swagger_ui = SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi());
+        swagger_router = swagger_router.merge(swagger_ui);
+        if is_prod {
+            swagger_router = swagger_router
+                .layer(require_superuser_middleware)
+                .layer(auth_middleware.clone());
+        }
     }
 
     // Set the static asset fallback first, then wrap the entire router (API + swagger + static
-    // fallback) in the rate limiter so every request surface is throttled, then apply compression
-    // as the outermost layer. `governor_conf` is consumed exactly once here.
-    let mut router = router
+    // fallback) in the global rate limit so every request surface is throttled, then apply
+    // compression as the outermost layer.
+    router
         .merge(swagger_router)
-        .fallback_service(get(static_asset_handler));
-
-    if let Some(governor_conf) = governor_conf {
-        router = router.layer(GovernorLayer::new(governor_conf));
-    }
-
-    router.layer(compression_middleware)
-}
-
-/// Builds the explicit list of trusted CORS origins for credentialed requests.
-///
-/// The session cookie is scoped to [`DOMAIN_NAME`], so the production frontend origins are
-/// `https://{DOMAIN_NAME}` and `https://www.{DOMAIN_NAME}`. Non-production environments also
-/// accept the usual local dev origins so the frontend dev server can talk to the API.
-///
-/// Additional origins may be supplied via the comma-separated `CORS_ALLOWED_ORIGINS` env var
-/// (e.g. a separately-hosted frontend) without recompiling.
-///
-/// Origins that fail to parse into a [`HeaderValue`] are logged and skipped rather than panicking,
-/// keeping startup infallible.
-fn build_trusted_origins(env: DeploymentEnvironment) -> Vec<HeaderValue> {
-    let mut origins: Vec<String> = vec![
-        format!("https://{DOMAIN_NAME}"),
-        format!("https://www.{DOMAIN_NAME}"),
-    ];
-
-    // In non-prod environments, allow the local frontend dev server origins.
-    if !matches!(env, DeploymentEnvironment::Prod) {
-        origins.extend(
-            [
-                "http://localhost:3000",
-                "http://127.0.0.1:3000",
-                "http://localhost:5173",
-                "http://127.0.0.1:5173",
-            ]
-            .into_iter()
-            .map(str::to_owned),
-        );
-    }
-
-    // Operator-supplied extra origins (comma-separated), for split-origin deployments.
-    if let Ok(extra) = std::env::var("CORS_ALLOWED_ORIGINS") {
-        origins.extend(
-            extra
-                .split(',')
-                .map(str::trim)
-                .filter(|origin| !origin.is_empty())
-                .map(str::to_owned),
-        );
-    }
-
-    origins
-        .into_iter()
-        .filter_map(|origin| match HeaderValue::from_str(&origin) {
-            Ok(value) => Some(value),
-            Err(e) => {
-                tracing::error!(origin = %origin, error = %e, "Skipping invalid CORS origin");
-                None
-            }
-        })
-        .collect()
+        .fallback_service(get(static_asset_handler))
+        // Host-based site selection for the static fallback and handlers.
+        .layer(from_fn_with_state(state.clone(), site_middleware))
+        // Per-route, so the span can name the matched route.
+        .layer(request_span_layer())
+        .layer(rate_limit(RateLimitPolicy::Global))
+        .layer(compression_middleware)
 }
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+
+use crate::{DOMAIN_NAME, init::app_config::CorsSection, init::state::DeploymentEnvironment};
+
+/// Builds the API's CORS layer from `AppConfig::cors`.
+///
+/// Auth is cookie-based (session_id cookie with credentials), so by default CORS must NOT
+/// reflect an arbitrary Origin: the allow-list is `https://{DOMAIN_NAME}`,
+/// `https://www.{DOMAIN_NAME}`, `cors.allowed_origins` and the list for `env` (the local
+/// frontend dev servers outside prod). `*` is only honored with credentials off;
+/// `AppConfig::validate` rejects the combination, which tower-http panics on at runtime.
+pub fn cors_layer(env: DeploymentEnvironment, config: &CorsSection) -> CorsLayer {
+    let is_wildcard = |item: &String| item.trim() == "*";
+
+    let allow_origin = if !config.allow_credentials && config.origins(env).any(|o| o == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(build_trusted_origins(config.origins(env)))
+    };
+
+    let allow_methods =
+        if !config.allow_credentials && config.allowed_methods.iter().any(is_wildcard) {
+            AllowMethods::any()
+        } else {
+            AllowMethods::list(config.allowed_methods.iter().filter_map(|method| {
+            match Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes()) {
+                Ok(method) => Some(method),
+                Err(e) => {
+                    tracing::error!(method = %method, error = %e, "Skipping invalid CORS method");
+                    None
+                }
+            }
+        }))
+        };
+
+    let allow_headers =
+        if !config.allow_credentials && config.allowed_headers.iter().any(is_wildcard) {
+            AllowHeaders::any()
+        } else {
+            AllowHeaders::list(config.allowed_headers.iter().filter_map(|name| {
+                match HeaderName::from_bytes(name.trim().as_bytes()) {
+                    Ok(name) => Some(name),
+                    Err(e) => {
+                        tracing::error!(header = %name, error = %e, "Skipping invalid CORS header");
+                        None
+                    }
+                }
+            }))
+        };
+
+    let mut layer = CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_credentials(config.allow_credentials)
+        .allow_methods(allow_methods)
+        .allow_headers(allow_headers);
+    if config.max_age_secs > 0 {
+        layer = layer.max_age(Duration::from_secs(config.max_age_secs));
+    }
+    layer
+}
+
+/// Builds the explicit list of trusted CORS origins for credentialed requests.
+///
+/// The session cookie is scoped to [`DOMAIN_NAME`], so the production frontend origins are
+/// `https://{DOMAIN_NAME}` and `https://www.{DOMAIN_NAME}`; configured origins are added on top,
+/// e.g. a separately-hosted frontend, without recompiling.
+///
+/// Origins that fail to parse into a [`HeaderValue`] are logged and skipped rather than panicking,
+/// keeping startup infallible.
+fn build_trusted_origins<'a>(configured: impl Iterator<Item = &'a str>) -> Vec<HeaderValue> {
+    let mut origins: Vec<String> = vec![
+        format!("https://{DOMAIN_NAME}"),
+        format!("https://www.{DOMAIN_NAME}"),
+    ];
+    origins.extend(
+        configured
+            .filter(|origin| *origin != "*")
+            .map(str::to_owned),
+    );
+    origins.sort();
+    origins.dedup();
+
+    origins
+        .into_iter()
+        .filter_map(|origin| match HeaderValue::from_str(&origin) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                tracing::error!(origin = %origin, error = %e, "Skipping invalid CORS origin");
+                None
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,177 @@
+//! Local-environment frontend serving from `app.frontend_dir` (`FRONTEND_DIR`).
+//!
+//! The embedded `fe/` copy only changes with a rebuild. In
+//! `DeploymentEnvironment::Local`, `static_asset_handler` reads files from
+//! disk instead, and a watcher polls the directory and announces changes on
+//! `GET /__dev/reload` (Server-Sent Events, `event: reload`). Served
+//! `index.html` pages get a small script that reloads on that event, so a
+//! frontend rebuild shows up without touching the server.
+
+use std::{
+    convert::Infallible,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use axum::{
+    Router,
+    extract::State,
+    http::{StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use futures_util::stream;
+use mime_guess::from_path;
+use tokio::sync::broadcast;
+use tracing::{debug, info};
+
+use crate::init::{
+    app_config::config,
+    state::{DeploymentEnvironment, ServerState, sites::Site},
+};
+
+const RELOAD_PATH: &str = "/__dev/reload";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const RELOAD_SCRIPT: &str = "<script>new EventSource(\"/__dev/reload\").addEventListener(\"reload\", () => location.reload());</script>";
+
+/// The frontend directory to serve from, in the local environment only.
+pub(super) fn disk_root() -> Option<&'static Path> {
+    let config = config();
+    matches!(
+        config.app.deployment_environment(),
+        DeploymentEnvironment::Local
+    )
+    .then_some(config.app.frontend_dir.as_path())
+}
+
+#[derive(Clone)]
+struct DevReload {
+    reload: broadcast::Sender<()>,
+    state: Arc<ServerState>,
+}
+
+/// `/__dev/reload` plus its watcher task; `None` outside the local
+/// environment.
+pub(super) fn dev_router(state: &Arc<ServerState>) -> Option<Router> {
+    let root = disk_root()?.to_path_buf();
+    let (reload, _) = broadcast::channel(16);
+    info!(dir = %root.display(), "Serving the frontend from disk with live reload");
+    tokio::spawn(watch(root, reload.clone(), Arc::clone(state)));
+    Some(
+        Router::new()
+            .route(RELOAD_PATH, get(reload_events))
+            .with_state(DevReload {
+                reload,
+                state: Arc::clone(state),
+            }),
+    )
+}
+
+async fn reload_events(State(dev): State<DevReload>) -> impl IntoResponse {
+    let receiver = dev.reload.subscribe();
+    let events = stream::unfold((dev.state, receiver), |(state, mut receiver)| async move {
+        tokio::select! {
+            // Do not hold graceful shutdown open.
+            _ = state.shutdown.cancelled() => None,
+            received = receiver.recv() => match received {
+                Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => Some((
+                    Ok::<_, Infallible>(Event::default().event("reload").data("")),
+                    (state, receiver),
+                )),
+                Err(broadcast::error::RecvError::Closed) => None,
+            },
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+}
+
+/// Polls `root` and sends on `reload` whenever a file is added, removed or
+/// modified, until shutdown.
+async fn watch(root: PathBuf, reload: broadcast::Sender<()>, state: Arc<ServerState>) {
+    let mut last = snapshot(root.clone()).await;
+    loop {
+        tokio::select! {
+            _ = state.shutdown.cancelled() => return,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+        let current = snapshot(root.clone()).await;
+        if current != last {
+            debug!(dir = %root.display(), "Frontend changed on disk; reloading clients");
+            last = current;
+            // No subscribers is not an error.
+            let _ = reload.send(());
+        }
+    }
+}
+
+/// File count, total size and newest modification time under `root`.
+async fn snapshot(root: PathBuf) -> (usize, u64, Option<SystemTime>) {
+    tokio::task::spawn_blocking(move || {
+        let mut summary = (0, 0, None);
+        let mut dirs = vec![root];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    dirs.push(entry.path());
+                    continue;
+                }
+                summary.0 += 1;
+                summary.1 += metadata.len();
+                summary.2 = summary.2.max(metadata.modified().ok());
+            }
+        }
+        summary
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Disk counterpart of the embedded lookup: the file itself, else the site's
+/// `index.html` for SPA routes. Nothing is cached by the browser.
+pub(super) async fn serve_from_disk(root: &Path, site: &Site, path: &str) -> Response {
+    let index = site.asset_path("index.html");
+    for candidate in [site.asset_path(path), index.clone()] {
+        // Request paths are relative and may not climb out of the root.
+        if candidate.split('/').any(|segment| segment == "..") {
+            continue;
+        }
+        let Ok(bytes) = tokio::fs::read(root.join(&candidate)).await else {
+            continue;
+        };
+        let body = if candidate == index {
+            inject_reload_script(bytes)
+        } else {
+            bytes
+        };
+        let mime = from_path(&candidate).first_or_octet_stream();
+        return (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, mime.as_ref()),
+                (header::CACHE_CONTROL, "no-store"),
+            ],
+            body,
+        )
+            .into_response();
+    }
+    (StatusCode::NOT_FOUND, "Not Found").into_response()
+}
+
+fn inject_reload_script(html: Vec<u8>) -> Vec<u8> {
+    let mut html = String::from_utf8_lossy(&html).into_owned();
+    match html.rfind("</body>") {
+        Some(at) => html.insert_str(at, RELOAD_SCRIPT),
+        None => html.push_str(RELOAD_SCRIPT),
+    }
+    html.into_bytes()
+}
@@ -1,14 +1,71 @@
+use std::sync::Arc;
+
 use axum::{
-    http::{HeaderMap, StatusCode, Uri, header},
+    Extension, Json,
+    http::{HeaderMap, HeaderValue, StatusCode, Uri, header},
     response::{IntoResponse, Response},
 };
 use mime_guess::from_path;
 use rust_embed::Embed;
 
+use super::dev_assets;
+use crate::init::state::sites::Site;
+
 #[derive(Embed)]
 #[folder = "fe/"]
 struct EmbeddedAssets;
 
+/// Fingerprinted names for the embedded files, generated by `build.rs`.
+mod manifest {
+    include!(concat!(env!("OUT_DIR"), "/asset_manifest.rs"));
+}
+
+/// Served at `{site}/asset-manifest.json` unless the frontend ships its own.
+const MANIFEST_PATH: &str = "asset-manifest.json";
+
+/// Fingerprinted names change with their contents, so they never need
+/// revalidating.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// Entry points (and the manifest) must be revalidated so a deploy is picked
+/// up on the next load.
+const NO_CACHE: &str = "no-cache";
+
+fn lookup(table: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    table
+        .binary_search_by(|(entry, _)| (*entry).cmp(key))
+        .ok()
+        .map(|i| table[i].1)
+}
+
+/// The embedded file behind a fingerprinted path.
+fn logical_path(fingerprinted: &str) -> Option<&'static str> {
+    lookup(manifest::BY_FINGERPRINT, fingerprinted)
+}
+
+/// `{logical: fingerprinted}` for the files under `prefix` (a site's frontend
+/// directory, or empty), with the prefix stripped.
+fn site_manifest(prefix: &str) -> serde_json::Map<String, serde_json::Value> {
+    manifest::BY_LOGICAL
+        .iter()
+        .filter_map(|(logical, fingerprinted)| {
+            let logical = logical.strip_prefix(prefix)?;
+            let fingerprinted = fingerprinted.strip_prefix(prefix)?;
+            // The default site's table leaves out the other sites' trees.
+            if prefix.is_empty() && logical.starts_with("sites/") {
+                return None;
+            }
+            Some((logical.to_string(), fingerprinted.to_string().into()))
+        })
+        .collect()
+}
+
+fn with_cache_control(mut response: Response, value: &'static str) -> Response {
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static(value));
+    response
+}
+
 /// Serves static files embedded in the binary, prioritizing pre-compressed .zst files.
 #[derive(Clone, Copy)]
 enum ContentCodingPreference {
@@ -171,34 +228,64 @@ fn serve_uncompressed_asset(path: &str) -> Option<Response> {
 }
 
 /// Serves static files embedded in the binary and negotiates zstd/gzip via Accept-Encoding.
-pub(super) async fn static_asset_handler(uri: Uri, headers: HeaderMap) -> impl IntoResponse {
+/// Each site reads from its own frontend directory (`Site::asset_path`).
+///
+/// A fingerprinted name from the build-time manifest serves the underlying
+/// file with a year-long immutable lifetime; `index.html`, including the SPA
+/// fallback, is sent `no-cache`. Other paths keep the default heuristics.
+/// The local environment reads from disk instead (`dev_assets`).
+pub(super) async fn static_asset_handler(
+    Extension(site): Extension<Arc<Site>>,
+    uri: Uri,
+    headers: HeaderMap,
+) -> impl IntoResponse {
     let mut path = uri.path().trim_start_matches('/').to_string();
     if path.is_empty() {
         path = "index.html".to_string();
     }
+    if let Some(root) = dev_assets::disk_root() {
+        return dev_assets::serve_from_disk(root, &site, &path).await;
+    }
+    let path = site.asset_path(&path);
+    let index = site.asset_path("index.html");
 
     let selected_encoding = select_static_encoding(&headers);
 
-    // 1. Try an encoded version matching client support.
+    // 1. Fingerprinted name: serve the file it stands for.
+    if let Some(logical) = logical_path(&path) {
+        let response = serve_compressed_asset(logical, selected_encoding)
+            .or_else(|| serve_uncompressed_asset(logical));
+        if let Some(response) = response {
+            return with_cache_control(response, IMMUTABLE);
+        }
+    }
+
+    // 2. Try an encoded version matching client support.
     if let Some(response) = serve_compressed_asset(&path, selected_encoding) {
-        return response;
+        return entry_point_cache_control(response, &path, &index);
     }
 
-    // 2. Fallback to the uncompressed direct path.
+    // 3. Fallback to the uncompressed direct path.
     if let Some(response) = serve_uncompressed_asset(&path) {
-        return response;
+        return entry_point_cache_control(response, &path, &index);
+    }
+
+    // 4. The fingerprint manifest, when the frontend does not ship one.
+    if path == site.asset_path(MANIFEST_PATH) {
+        let prefix = site.asset_path("");
+        return with_cache_control(Json(site_manifest(&prefix)).into_response(), NO_CACHE);
     }
 
-    // 3. SPA fallback: serve encoded index.html first, then plain index.html.
-    if let Some(response) = serve_compressed_asset("index.html", selected_encoding) {
-        return response;
+    // 5. SPA fallback: serve encoded index.html first, then plain index.html.
+    if let Some(response) = serve_compressed_asset(&index, selected_encoding) {
+        return with_cache_control(response, NO_CACHE);
     }
 
-    if let Some(response) = serve_uncompressed_asset("index.html") {
-        return response;
+    if let Some(response) = serve_uncompressed_asset(&index) {
+        return with_cache_control(response, NO_CACHE);
     }
 
-    // 4. If nothing is found, return an error.
+    // 6. If nothing is found, return an error.
     (
         StatusCode::NOT_FOUND,
         [(header::VARY, "Accept-Encoding")],
@@ -206,3 +293,11 @@ pub(super) async fn static_asset_handler(uri: Uri, headers: HeaderMap) -> impl I
     )
         .into_response()
 }
+
+fn entry_point_cache_control(response: Response, path: &str, index: &str) -> Response {
+    if path == index {
+        with_cache_control(response, NO_CACHE)
+    } else {
+        response
+    }
+}
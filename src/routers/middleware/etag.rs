@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    init::state::{ServerState, cache_versions::CachedResource},
+    routers::middleware::is_logged_in::AuthSession,
+    util::http::conditional::if_none_match_matches,
+};
+
+/// Middleware state: the cache whose version tags the wrapped routes.
+#[derive(Clone)]
+pub struct EtagState {
+    state: Arc<ServerState>,
+    resource: CachedResource,
+}
+
+impl EtagState {
+    pub fn new(state: Arc<ServerState>, resource: CachedResource) -> Self {
+        Self { state, resource }
+    }
+}
+
+/// Response extension for a handler whose body is not a pure function of the
+/// cache version (e.g. the degraded posts list), so it must not be tagged.
+#[derive(Clone, Copy)]
+pub struct SkipEtag;
+
+/// Tags GET responses with a weak `ETag` derived from the cache version and
+/// answers 304 when `If-None-Match` already carries it. Tags are weak because
+/// the JSON envelope's timing meta differs between otherwise equal responses.
+///
+/// The version is read before the handler runs, so a change racing the
+/// request pairs newer data with the older tag and the next revalidation
+/// simply misses. Runs inside `is_logged_in_middleware` for per-user tags.
+pub async fn etag_middleware(
+    State(etag): State<EtagState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return next.run(request).await;
+    }
+
+    let user_id = request
+        .extensions()
+        .get::<Option<AuthSession>>()
+        .and_then(|session| session.as_ref().map(|s| s.user_id));
+    let tag = etag.state.cache_versions.etag(etag.resource, user_id);
+    let Ok(header_value) = HeaderValue::from_str(&format!("W/{tag}")) else {
+        return next.run(request).await;
+    };
+
+    let mut response = if if_none_match_matches(request.headers(), &tag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let response = next.run(request).await;
+        if response.status() != StatusCode::OK || response.extensions().get::<SkipEtag>().is_some()
+        {
+            return response;
+        }
+        response
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(header::ETAG, header_value);
+    if etag.resource.varies_by_user() {
+        headers.append(header::VARY, HeaderValue::from_static("cookie"));
+    }
+    response
+}
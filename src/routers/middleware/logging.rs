@@ -5,7 +5,7 @@ use std::{
 
 use axum::{
     body::Body,
-    extract::{ConnectInfo, State},
+    extract::{ConnectInfo, MatchedPath, State},
     http::{HeaderMap, HeaderValue, Request, Response, StatusCode},
     middleware::Next,
 };
@@ -16,10 +16,20 @@ use uuid::Uuid;
 
 use crate::{
     build_info::{BUILD_TIME_UTC, LIB_VERSION_MAP, RUSTC_VERSION},
-    errors::code_error::CodeErrorLogContext,
-    init::state::{DeploymentEnvironment, ServerState},
-    routers::middleware::is_logged_in::AuthSession,
-    util::extract::client_ip::extract_client_ip,
+    domain::access_log::access_log::AccessLogInsertable,
+    errors::{
+        code_error::CodeErrorLogContext,
+        problem::{PROBLEM_CONTEXT, ProblemContext, prefers_problem_json},
+    },
+    init::{
+        error_reporting::{self, CURRENT_REQUEST, ReportedRequest},
+        state::{DeploymentEnvironment, ServerState},
+    },
+    routers::{
+        main_router::{API_ALIAS_PREFIX, API_V1_PREFIX},
+        middleware::is_logged_in::AuthSession,
+    },
+    util::{extract::client_ip::extract_client_ip, http::endpoint_stats::UNMATCHED_ROUTE},
 };
 
 #[derive(Debug, Clone)]
@@ -78,13 +88,13 @@ pub async fn log_middleware(
     let start = Instant::now();
     let now = Utc::now(); // earliest possible timestamp of server-received request
 
-    state.add_responses_handled();
-
     let method = request.method().clone();
     let path = request.uri().path().to_owned();
+    let route = route_label(request.extensions().get::<MatchedPath>());
 
     let client_ip = extract_client_ip(request.headers(), info);
     let request_id = request_id_from_headers(request.headers());
+    let wants_problem_json = prefers_problem_json(request.headers());
 
     match state.get_deployment_environment() {
         DeploymentEnvironment::Local
@@ -102,16 +112,65 @@ pub async fn log_middleware(
         client_ip,
     });
 
-    let mut response = next.run(request).await;
+    let reported = ReportedRequest {
+        request_id: request_id.clone(),
+        method: method.to_string(),
+        path: path.clone(),
+        route: route.clone(),
+        client_ip,
+    };
+    let served = async {
+        if wants_problem_json {
+            let context = ProblemContext {
+                request_id: request_id.clone(),
+            };
+            PROBLEM_CONTEXT.scope(context, next.run(request)).await
+        } else {
+            next.run(request).await
+        }
+    };
+    let mut response = CURRENT_REQUEST.scope(reported.clone(), served).await;
     add_server_headers(&mut response, &request_id);
 
     let duration = start.elapsed();
     let status = response.status();
+    state.responses.record(&method, status);
+    state
+        .endpoint_stats
+        .record(&method, &route, status, duration)
+        .await;
     let error_context = response.extensions().get::<CodeErrorLogContext>().cloned();
     let actor = response
         .extensions()
         .get::<AuthSession>()
         .map(RequestActor::from);
+    if let Some(context) = &error_context
+        && context.log_level == Level::ERROR
+    {
+        error_reporting::capture_code_error(
+            &reported,
+            actor.as_ref().map(|actor| actor.user_id),
+            status.as_u16(),
+            context,
+        );
+    }
+    if state.sample_access_log() {
+        let country_code = client_ip
+            .and_then(|ip| state.lookup_ip_location(ip))
+            .map(|info| info.country_code)
+            .filter(|code| !code.is_empty() && code != "-");
+        state
+            .enqueue_access_log(AccessLogInsertable {
+                access_log_method: method.to_string(),
+                access_log_path: path.clone(),
+                access_log_status: status.as_u16() as i16,
+                access_log_latency_ms: duration.as_secs_f64() * 1000.0,
+                access_log_country_code: country_code,
+                access_log_user_id: actor.as_ref().map(|actor| actor.user_id),
+                access_log_created_at: Utc::now(),
+            })
+            .await;
+    }
     log_completed_request(CompletedRequestLog {
         request_id: &request_id,
         method: &method,
@@ -126,6 +185,22 @@ pub async fn log_middleware(
     response
 }
 
+/// Route template for the endpoint stats, with `/api/v1` folded into its
+/// `/api` alias so both count as one endpoint.
+fn route_label(matched: Option<&MatchedPath>) -> String {
+    let Some(matched) = matched else {
+        return UNMATCHED_ROUTE.to_owned();
+    };
+    match matched
+        .as_str()
+        .strip_prefix(API_V1_PREFIX)
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+    {
+        Some(rest) => format!("{API_ALIAS_PREFIX}{rest}"),
+        None => matched.as_str().to_owned(),
+    }
+}
+
 fn request_id_from_headers(headers: &HeaderMap) -> String {
     match headers.get("x-request-id") {
         Some(value) => match value.to_str() {
@@ -1,5 +1,11 @@
 pub mod api_key;
 pub mod auth;
+pub mod etag;
 pub mod is_logged_in;
 pub mod logging;
+pub mod rate_limit;
+pub mod request_span;
+pub mod response_cache;
 pub mod role;
+pub mod site;
+pub mod timeout;
@@ -0,0 +1,68 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    errors::code_error::{CodeError, code_err},
+    init::state::ServerState,
+    routers::middleware::is_logged_in::AuthSession,
+    util::{extract::client_ip::extract_client_ip, http::rate_limit::RateLimitPolicy},
+};
+
+/// Middleware state: the policy the wrapped routes are charged against.
+#[derive(Clone)]
+pub struct RateLimitState {
+    state: Arc<ServerState>,
+    policy: RateLimitPolicy,
+}
+
+impl RateLimitState {
+    pub fn new(state: Arc<ServerState>, policy: RateLimitPolicy) -> Self {
+        Self { state, policy }
+    }
+}
+
+/// Charges one token per request and answers 429 with `Retry-After` once the
+/// bucket is empty. Requests are keyed by user only where this runs inside
+/// `is_logged_in_middleware`, which attaches the session.
+pub async fn rate_limit_middleware(
+    State(limit): State<RateLimitState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(ip) = extract_client_ip(request.headers(), addr) else {
+        return next.run(request).await;
+    };
+    let user_id = request
+        .extensions()
+        .get::<Option<AuthSession>>()
+        .and_then(|session| session.as_ref().map(|s| s.user_id));
+
+    let limiter = &limit.state.rate_limiter;
+    let subject = limiter.subject(limit.policy, ip, user_id);
+    match limiter.check(limit.policy, subject).await {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            let mut response = code_err(
+                CodeError::RATE_LIMITED,
+                format!(
+                    "{} limit exceeded; retry after {retry_after}s",
+                    limit.policy.as_str()
+                ),
+            )
+            .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            response
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! One `http.request` span per request, the root of its trace (exported when
+//! `init::telemetry` is configured). Handler, DB and storage spans nest
+//! under it.
+
+use std::time::Duration;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    response::Response,
+};
+use tower_http::{
+    classify::{ServerErrorsAsFailures, SharedClassifier},
+    trace::TraceLayer,
+};
+use tracing::{Span, field::Empty, info_span};
+
+fn make_request_span(request: &Request) -> Span {
+    let method = request.method();
+    // Route template, so traces group by endpoint rather than by id.
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str);
+    info_span!(
+        "http.request",
+        otel.name = %format!("{method} {}", route.unwrap_or("fallback")),
+        otel.kind = "server",
+        otel.status_code = Empty,
+        http.request.method = %method,
+        http.route = route,
+        url.path = %request.uri().path(),
+        http.response.status_code = Empty,
+    )
+}
+
+fn record_response(response: &Response, _latency: Duration, span: &Span) {
+    let status = response.status();
+    span.record("http.response.status_code", status.as_u16());
+    if status.is_server_error() {
+        span.record("otel.status_code", "ERROR");
+    }
+}
+
+pub type RequestSpanLayer = TraceLayer<
+    SharedClassifier<ServerErrorsAsFailures>,
+    fn(&Request) -> Span,
+    (),
+    fn(&Response, Duration, &Span),
+    (),
+    (),
+    (),
+>;
+
+/// Request/response logging stays in `logging::log_middleware`; this layer
+/// only opens and annotates the span.
+pub fn request_span_layer() -> RequestSpanLayer {
+    TraceLayer::new_for_http()
+        .make_span_with(make_request_span as fn(&Request) -> Span)
+        .on_request(())
+        .on_response(record_response as fn(&Response, Duration, &Span))
+        .on_body_chunk(())
+        .on_eos(())
+        .on_failure(())
+}
@@ -0,0 +1,142 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::debug;
+
+use crate::{
+    init::state::{ServerState, cache_versions::CachedResource, response_cache::CacheOutcome},
+    routers::middleware::{etag::SkipEtag, is_logged_in::AuthSession},
+};
+
+/// Bodies above this are passed through instead of being stored.
+const MAX_CACHED_BODY: u64 = 4 * 1024 * 1024;
+
+/// Middleware state: the cache whose version keys the wrapped routes.
+#[derive(Clone)]
+pub struct ResponseCacheState {
+    state: Arc<ServerState>,
+    resource: CachedResource,
+}
+
+impl ResponseCacheState {
+    pub fn new(state: Arc<ServerState>, resource: CachedResource) -> Self {
+        Self { state, resource }
+    }
+}
+
+/// Serves repeated GETs of a cache-backed endpoint from `state.cache` for
+/// `RESPONSE_CACHE_TTL_SECS`, keyed by path, query and the resource's cache
+/// version. The write handlers' `cache_versions.bump` is the invalidation:
+/// a bump moves every later request to a new key, and the old entries expire.
+/// Responses the handler marks [`SkipEtag`] are not stored, for the same
+/// reason they are not tagged.
+///
+/// Requests to a per-user resource (see `CachedResource::varies_by_user`)
+/// bypass the cache when signed in. Runs inside `etag_middleware`, so a
+/// matching `If-None-Match` is answered before the cache is consulted.
+/// Responses carry `x-cache: hit|miss`.
+pub async fn response_cache_middleware(
+    State(cache): State<ResponseCacheState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let state = &cache.state;
+    let ttl = state.config.cache.response_ttl_secs;
+    if ttl == 0 || request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    let signed_in = request
+        .extensions()
+        .get::<Option<AuthSession>>()
+        .is_some_and(Option::is_some);
+    if signed_in && cache.resource.varies_by_user() {
+        state
+            .response_cache
+            .record(cache.resource, CacheOutcome::Bypass);
+        return next.run(request).await;
+    }
+
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map_or_else(|| request.uri().path(), |pq| pq.as_str());
+    let tag = state.cache_versions.etag(cache.resource, None);
+    let key = format!("response:{}:{path_and_query}", tag.trim_matches('"'));
+
+    match state.cache.get(&key).await {
+        Ok(Some(body)) => {
+            state
+                .response_cache
+                .record(cache.resource, CacheOutcome::Hit);
+            return cached_response(body);
+        }
+        Ok(None) => {}
+        Err(e) => debug!(error = %e, key = %key, "Response cache read failed"),
+    }
+
+    let response = next.run(request).await;
+    let cacheable = response.status() == StatusCode::OK
+        && response.extensions().get::<SkipEtag>().is_none()
+        && response
+            .body()
+            .size_hint()
+            .exact()
+            .is_some_and(|len| len <= MAX_CACHED_BODY);
+    if !cacheable {
+        state
+            .response_cache
+            .record(cache.resource, CacheOutcome::Bypass);
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_CACHED_BODY as usize).await {
+        Ok(body) => body,
+        Err(e) => {
+            debug!(error = %e, "Failed to buffer response for the response cache");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if let Err(e) = state
+        .cache
+        .set(&key, body.to_vec(), Duration::from_secs(ttl))
+        .await
+    {
+        debug!(error = %e, key = %key, "Response cache write failed");
+    }
+    state
+        .response_cache
+        .record(cache.resource, CacheOutcome::Miss);
+
+    let mut response = Response::from_parts(parts, Body::from(body));
+    response
+        .headers_mut()
+        .insert("x-cache", HeaderValue::from_static("miss"));
+    response
+}
+
+/// Cached bodies are the handlers' JSON envelopes.
+fn cached_response(body: Vec<u8>) -> Response {
+    (
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (
+                header::HeaderName::from_static("x-cache"),
+                HeaderValue::from_static("hit"),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
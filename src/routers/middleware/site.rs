@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::init::state::ServerState;
+
+/// Resolves the request's site (`init::state::sites`) from `Host`, or the
+/// URI authority on HTTP/2, and inserts it as an `Arc<Site>` extension for
+/// the static fallback and site-aware handlers.
+pub async fn site_middleware(
+    State(state): State<Arc<ServerState>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| request.uri().host());
+    let site = state.sites.resolve(host);
+    request.extensions_mut().insert(site);
+    next.run(request).await
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    errors::code_error::{CodeError, code_err},
+    init::state::ServerState,
+    util::http::timeout::TimeoutBudget,
+};
+
+/// Middleware state: the budget the wrapped routes run under.
+#[derive(Clone)]
+pub struct TimeoutState {
+    state: Arc<ServerState>,
+    budget: TimeoutBudget,
+}
+
+impl TimeoutState {
+    pub fn new(state: Arc<ServerState>, budget: TimeoutBudget) -> Self {
+        Self { state, budget }
+    }
+}
+
+/// Answers `REQUEST_TIMEOUT` once the rest of the request has run past its
+/// budget, dropping the handler future so a stuck query or storage call
+/// stops holding the connection. Only producing the response head is
+/// timed; streamed bodies and upgraded WebSockets are not cut off.
+pub async fn timeout_middleware(
+    State(timeout): State<TimeoutState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(limit) = timeout.budget.duration(&timeout.state.config.server) else {
+        return next.run(request).await;
+    };
+    match tokio::time::timeout(limit, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            timeout.state.request_timeouts.record(timeout.budget);
+            code_err(
+                CodeError::REQUEST_TIMEOUT,
+                format!(
+                    "{} budget of {}s exceeded",
+                    timeout.budget.as_str(),
+                    limit.as_secs()
+                ),
+            )
+            .into_response()
+        }
+    }
+}
@@ -4,6 +4,81 @@ pub mod sql_types {
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "photograph_context"))]
     pub struct PhotographContext;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "photograph_processing_status"))]
+    pub struct PhotographProcessingStatus;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "wasm_module_visibility"))]
+    pub struct WasmModuleVisibility;
+}
+
+diesel::table! {
+    access_log (access_log_id) {
+        access_log_id -> Uuid,
+        #[max_length = 16]
+        access_log_method -> Varchar,
+        access_log_path -> Text,
+        access_log_status -> Int2,
+        access_log_latency_ms -> Float8,
+        #[max_length = 8]
+        access_log_country_code -> Nullable<Varchar>,
+        access_log_user_id -> Nullable<Uuid>,
+        access_log_created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    admin_audit (admin_audit_id) {
+        admin_audit_id -> Uuid,
+        admin_audit_actor_id -> Nullable<Uuid>,
+        #[max_length = 64]
+        admin_audit_action -> Varchar,
+        #[max_length = 32]
+        admin_audit_target_type -> Varchar,
+        admin_audit_target_id -> Nullable<Text>,
+        admin_audit_summary -> Jsonb,
+        admin_audit_ip -> Nullable<Inet>,
+        admin_audit_created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    alert_rules (alert_rule_id) {
+        alert_rule_id -> Uuid,
+        #[max_length = 100]
+        alert_rule_name -> Varchar,
+        #[max_length = 32]
+        alert_rule_metric -> Varchar,
+        alert_rule_threshold -> Float8,
+        #[max_length = 8]
+        alert_rule_window -> Varchar,
+        #[max_length = 16]
+        alert_rule_method -> Nullable<Varchar>,
+        alert_rule_route -> Nullable<Text>,
+        alert_rule_min_requests -> Int4,
+        alert_rule_cooldown_minutes -> Int4,
+        alert_rule_emails -> Array<Text>,
+        alert_rule_webhook_url -> Nullable<Text>,
+        alert_rule_enabled -> Bool,
+        alert_rule_last_fired_at -> Nullable<Timestamptz>,
+        alert_rule_created_at -> Timestamptz,
+        alert_rule_updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    albums (album_id) {
+        album_id -> Uuid,
+        user_id -> Uuid,
+        album_title -> Text,
+        album_description -> Text,
+        album_cover_photograph_id -> Nullable<Uuid>,
+        album_sort_order -> Int4,
+        album_created_at -> Timestamptz,
+        album_updated_at -> Timestamptz,
+    }
 }
 
 diesel::table! {
@@ -27,6 +102,81 @@ diesel::table! {
         parent_comment_id -> Nullable<Uuid>,
         total_upvotes -> Int8,
         total_downvotes -> Int8,
+        comment_deleted_at -> Nullable<Timestamptz>,
+        comment_version -> Int4,
+    }
+}
+
+diesel::table! {
+    delayed_tasks (delayed_task_id) {
+        delayed_task_id -> Uuid,
+        #[max_length = 64]
+        delayed_task_kind -> Varchar,
+        delayed_task_payload -> Jsonb,
+        delayed_task_run_at -> Timestamptz,
+        #[max_length = 16]
+        delayed_task_status -> Varchar,
+        delayed_task_attempts -> Int4,
+        delayed_task_last_error -> Nullable<Text>,
+        delayed_task_created_at -> Timestamptz,
+        delayed_task_claimed_at -> Nullable<Timestamptz>,
+        delayed_task_finished_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    email_feedback_events (email_feedback_event_id) {
+        email_feedback_event_id -> Uuid,
+        #[max_length = 16]
+        email_feedback_event_kind -> Varchar,
+        #[max_length = 32]
+        email_feedback_event_type -> Nullable<Varchar>,
+        #[max_length = 64]
+        email_feedback_event_subtype -> Nullable<Varchar>,
+        #[max_length = 320]
+        email_feedback_event_recipient -> Varchar,
+        #[max_length = 255]
+        email_feedback_event_provider_message_id -> Nullable<Varchar>,
+        email_feedback_event_diagnostic -> Nullable<Text>,
+        email_feedback_event_payload -> Jsonb,
+        email_feedback_event_occurred_at -> Timestamptz,
+        email_feedback_event_created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    email_outbox (email_outbox_id) {
+        email_outbox_id -> Uuid,
+        #[max_length = 64]
+        email_outbox_template -> Varchar,
+        #[max_length = 320]
+        email_outbox_recipient -> Varchar,
+        #[max_length = 16]
+        email_outbox_locale -> Nullable<Varchar>,
+        email_outbox_vars -> Jsonb,
+        #[max_length = 16]
+        email_outbox_status -> Varchar,
+        email_outbox_attempts -> Int4,
+        email_outbox_next_attempt_at -> Timestamptz,
+        email_outbox_last_error -> Nullable<Text>,
+        email_outbox_created_at -> Timestamptz,
+        email_outbox_claimed_at -> Nullable<Timestamptz>,
+        email_outbox_sent_at -> Nullable<Timestamptz>,
+        #[max_length = 255]
+        email_outbox_provider_message_id -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    email_suppressions (email_suppression_address) {
+        #[max_length = 320]
+        email_suppression_address -> Varchar,
+        #[max_length = 16]
+        email_suppression_reason -> Varchar,
+        email_suppression_detail -> Nullable<Text>,
+        email_suppression_event_count -> Int4,
+        email_suppression_created_at -> Timestamptz,
+        email_suppression_last_event_at -> Timestamptz,
     }
 }
 
@@ -110,6 +260,31 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    job_executions (job_execution_id) {
+        job_execution_id -> Int8,
+        #[max_length = 64]
+        job_name -> Varchar,
+        job_scheduled_for -> Timestamptz,
+        job_started_at -> Timestamptz,
+        job_duration_ms -> Int8,
+        #[max_length = 16]
+        job_outcome -> Varchar,
+        job_error -> Nullable<Text>,
+        job_lock_wait_ms -> Nullable<Int8>,
+        job_details -> Nullable<Jsonb>,
+    }
+}
+
+diesel::table! {
+    job_runs (job_name) {
+        #[max_length = 64]
+        job_name -> Varchar,
+        job_last_success_at -> Timestamptz,
+        job_updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     live_chat_bans (live_chat_ban_id) {
         live_chat_ban_id -> Uuid,
@@ -161,6 +336,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    notifications (notification_id) {
+        notification_id -> Uuid,
+        user_id -> Uuid,
+        notification_type -> Varchar,
+        notification_payload -> Jsonb,
+        notification_created_at -> Timestamptz,
+        notification_read_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     password_reset_tokens (password_reset_token_id) {
         password_reset_token_id -> Uuid,
@@ -183,6 +369,7 @@ diesel::table! {
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::PhotographContext;
+    use super::sql_types::PhotographProcessingStatus;
 
     photographs (photograph_id) {
         photograph_id -> Uuid,
@@ -201,6 +388,26 @@ diesel::table! {
         photograph_view_count -> Int8,
         photograph_total_upvotes -> Int8,
         photograph_total_downvotes -> Int8,
+        album_id -> Nullable<Uuid>,
+        photograph_album_position -> Int4,
+        photograph_camera_make -> Nullable<Text>,
+        photograph_camera_model -> Nullable<Text>,
+        photograph_lens_model -> Nullable<Text>,
+        photograph_focal_length_mm -> Nullable<Float8>,
+        photograph_aperture_f_number -> Nullable<Float8>,
+        photograph_exposure_time_seconds -> Nullable<Float8>,
+        photograph_iso -> Nullable<Int4>,
+        photograph_processing_status -> PhotographProcessingStatus,
+        photograph_processing_error -> Nullable<Text>,
+        photograph_renditions -> Jsonb,
+        photograph_raw_format -> Nullable<Text>,
+        photograph_raw_link -> Nullable<Text>,
+        photograph_watermarked -> Bool,
+        photograph_private_original_key -> Nullable<Text>,
+        photograph_phash -> Nullable<Int8>,
+        photograph_duplicate_of -> Nullable<Uuid>,
+        photograph_deleted_at -> Nullable<Timestamptz>,
+        photograph_upload_content_type -> Nullable<Text>,
     }
 }
 
@@ -238,6 +445,26 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    photograph_download_events (photograph_download_event_id) {
+        photograph_download_event_id -> Uuid,
+        photograph_id -> Uuid,
+        user_id -> Uuid,
+        photograph_download_object_key -> Text,
+        #[max_length = 16]
+        photograph_download_method -> Varchar,
+        photograph_download_ip -> Nullable<Inet>,
+        photograph_downloaded_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    photograph_tags (photograph_id, tag_id) {
+        photograph_id -> Uuid,
+        tag_id -> Int2,
+    }
+}
+
 diesel::table! {
     post_tags (post_id, tag_id) {
         post_id -> Uuid,
@@ -272,6 +499,8 @@ diesel::table! {
         post_metadata -> Jsonb,
         total_upvotes -> Int8,
         total_downvotes -> Int8,
+        post_deleted_at -> Nullable<Timestamptz>,
+        post_version -> Int4,
     }
 }
 
@@ -317,6 +546,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    user_notification_preferences (user_id) {
+        user_id -> Uuid,
+        notify_mentions -> Bool,
+        notify_replies -> Bool,
+        notify_digest -> Bool,
+        digest_sent_at -> Nullable<Timestamptz>,
+        preferences_updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     user_roles (user_role_id) {
         user_role_id -> Uuid,
@@ -353,6 +593,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::WasmModuleVisibility;
+
     wasm_module (wasm_module_id) {
         wasm_module_id -> Uuid,
         user_id -> Uuid,
@@ -362,10 +605,88 @@ diesel::table! {
         wasm_module_updated_at -> Timestamptz,
         wasm_module_thumbnail_link -> Text,
         wasm_module_title -> Text,
-        wasm_module_bundle_gz -> Bytea,
+        wasm_module_bundle_gz -> Nullable<Bytea>,
+        wasm_module_visibility -> WasmModuleVisibility,
+        wasm_module_bundle_key -> Nullable<Varchar>,
+        #[max_length = 64]
+        wasm_module_bundle_sha256 -> Varchar,
+        wasm_module_bundle_size_bytes -> Int8,
+        #[max_length = 64]
+        wasm_module_category -> Nullable<Varchar>,
+        wasm_module_tags -> Array<Text>,
+        wasm_module_bundle_br -> Nullable<Bytea>,
+        wasm_module_bundle_br_key -> Nullable<Varchar>,
+        #[max_length = 96]
+        wasm_module_bundle_integrity -> Nullable<Varchar>,
+        wasm_module_version -> Int4,
+    }
+}
+
+diesel::table! {
+    wasm_module_allowed_users (wasm_module_id, user_id) {
+        wasm_module_id -> Uuid,
+        user_id -> Uuid,
+        wasm_module_allowed_user_created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    wasm_module_files (wasm_module_id, wasm_module_file_path) {
+        wasm_module_id -> Uuid,
+        #[max_length = 512]
+        wasm_module_file_path -> Varchar,
+        wasm_module_file_key -> Varchar,
+        #[max_length = 255]
+        wasm_module_file_content_type -> Varchar,
+        wasm_module_file_size_bytes -> Int8,
+        #[max_length = 64]
+        wasm_module_file_sha256 -> Varchar,
+    }
+}
+
+diesel::table! {
+    wasm_module_loads (wasm_module_id, wasm_module_load_date, wasm_module_load_country_code) {
+        wasm_module_id -> Uuid,
+        wasm_module_load_date -> Date,
+        #[max_length = 2]
+        wasm_module_load_country_code -> Varchar,
+        wasm_module_load_count -> Int8,
+        wasm_module_last_loaded_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    webhook_deliveries (webhook_delivery_id) {
+        webhook_delivery_id -> Uuid,
+        webhook_id -> Uuid,
+        #[max_length = 64]
+        webhook_delivery_event_type -> Varchar,
+        webhook_delivery_payload -> Jsonb,
+        #[max_length = 16]
+        webhook_delivery_status -> Varchar,
+        webhook_delivery_attempts -> Int4,
+        webhook_delivery_response_status -> Nullable<Int4>,
+        webhook_delivery_last_error -> Nullable<Text>,
+        webhook_delivery_created_at -> Timestamptz,
+        webhook_delivery_delivered_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    webhooks (webhook_id) {
+        webhook_id -> Uuid,
+        webhook_url -> Text,
+        webhook_secret -> Text,
+        webhook_event_types -> Array<Text>,
+        webhook_enabled -> Bool,
+        webhook_created_at -> Timestamptz,
+        webhook_updated_at -> Timestamptz,
     }
 }
 
+diesel::joinable!(access_log -> users (access_log_user_id));
+diesel::joinable!(admin_audit -> users (admin_audit_actor_id));
+diesel::joinable!(albums -> users (user_id));
 diesel::joinable!(comment_votes -> comments (comment_id));
 diesel::joinable!(comment_votes -> users (user_id));
 diesel::joinable!(comments -> posts (post_id));
@@ -378,6 +699,7 @@ diesel::joinable!(iso_country -> iso_language (country_primary_language));
 diesel::joinable!(iso_country_subdivision -> iso_country (country_code));
 diesel::joinable!(live_chat_bans -> users (user_id));
 diesel::joinable!(live_chat_messages -> users (user_id));
+diesel::joinable!(notifications -> users (user_id));
 diesel::joinable!(password_reset_tokens -> users (user_id));
 diesel::joinable!(photographs -> user_profile_picture_image_types (photograph_image_type));
 diesel::joinable!(photographs -> users (user_id));
@@ -387,6 +709,9 @@ diesel::joinable!(photograph_comments -> photographs (photograph_id));
 diesel::joinable!(photograph_comments -> users (user_id));
 diesel::joinable!(photograph_comment_votes -> photograph_comments (photograph_comment_id));
 diesel::joinable!(photograph_comment_votes -> users (user_id));
+diesel::joinable!(photograph_download_events -> users (user_id));
+diesel::joinable!(photograph_tags -> photographs (photograph_id));
+diesel::joinable!(photograph_tags -> tags (tag_id));
 diesel::joinable!(live_chat_call_participants -> live_chat_calls (live_chat_call_id));
 diesel::joinable!(live_chat_call_participants -> users (user_id));
 diesel::joinable!(post_tags -> posts (post_id));
@@ -398,30 +723,49 @@ diesel::joinable!(role_permissions -> permissions (permission_id));
 diesel::joinable!(role_permissions -> roles (role_id));
 diesel::joinable!(user_profile_pictures -> user_profile_picture_image_types (user_profile_picture_image_type));
 diesel::joinable!(user_profile_pictures -> users (user_id));
+diesel::joinable!(user_notification_preferences -> users (user_id));
 diesel::joinable!(user_roles -> roles (role_id));
 diesel::joinable!(user_roles -> users (user_id));
 diesel::joinable!(users -> iso_country (user_country));
 diesel::joinable!(users -> iso_country_subdivision (user_subdivision));
 diesel::joinable!(users -> iso_language (user_language));
 diesel::joinable!(wasm_module -> users (user_id));
+diesel::joinable!(wasm_module_allowed_users -> users (user_id));
+diesel::joinable!(wasm_module_allowed_users -> wasm_module (wasm_module_id));
+diesel::joinable!(wasm_module_files -> wasm_module (wasm_module_id));
+diesel::joinable!(wasm_module_loads -> wasm_module (wasm_module_id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    access_log,
+    admin_audit,
+    alert_rules,
+    albums,
     comment_votes,
     comments,
+    delayed_tasks,
+    email_feedback_events,
+    email_outbox,
+    email_suppressions,
     email_verification_tokens,
     i18n_strings,
     iso_country,
     iso_country_subdivision,
     iso_currency,
     iso_language,
+    job_executions,
+    job_runs,
     live_chat_bans,
     live_chat_call_participants,
     live_chat_calls,
     live_chat_messages,
+    notifications,
     password_reset_tokens,
     permissions,
     photograph_comment_votes,
     photograph_comments,
+    photograph_download_events,
+    photograph_tags,
     photograph_votes,
     photographs,
     post_tags,
@@ -432,8 +776,14 @@ diesel::allow_tables_to_appear_in_same_query!(
     tags,
     user_profile_picture_image_types,
     user_profile_pictures,
+    user_notification_preferences,
     user_roles,
     users,
     visitation_data,
     wasm_module,
+    wasm_module_allowed_users,
+    wasm_module_files,
+    wasm_module_loads,
+    webhook_deliveries,
+    webhooks,
 );
@@ -0,0 +1,217 @@
+//! CloudFront delivery for images stored in the S3 bucket.
+//!
+//! Rows keep raw storage links (the source of truth); response DTOs rewrite
+//! them to the CDN domain and, when a key pair is configured, append a canned-policy
+//! signature (`Expires`/`Signature`/`Key-Pair-Id`). Expiry is rounded up to a
+//! fraction of the TTL so repeated requests get byte-identical URLs and browser
+//! caches keep hitting.
+//!
+//! Objects overwritten in place or deleted are invalidated through the
+//! CloudFront API, fire-and-forget.
+//!
+//! Config (`[cdn]`; environment overrides):
+//! - `CLOUDFRONT_DOMAIN` (required to enable; e.g. `img.cyhdev.com`)
+//! - `CLOUDFRONT_KEY_PAIR_ID` + `CLOUDFRONT_PRIVATE_KEY_PATH` (PEM, PKCS#1 or
+//!   PKCS#8) to sign URLs
+//! - `CLOUDFRONT_URL_TTL_SECS` (default 3600)
+//! - `CLOUDFRONT_DISTRIBUTION_ID` to issue invalidations
+
+use anyhow::anyhow;
+use aws_sdk_cloudfront::types::{InvalidationBatch, Paths};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::Utc;
+use rsa::{
+    RsaPrivateKey,
+    pkcs1::DecodeRsaPrivateKey,
+    pkcs1v15::SigningKey,
+    pkcs8::DecodePrivateKey,
+    signature::{SignatureEncoding, Signer},
+};
+use sha1::Sha1;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::init::app_config::CdnSection;
+
+/// Signed URLs are shared for this fraction of the TTL.
+const EXPIRY_BUCKETS_PER_TTL: i64 = 4;
+
+struct UrlSigner {
+    key_pair_id: String,
+    signing_key: SigningKey<Sha1>,
+}
+
+pub struct CdnConfig {
+    pub domain: String,
+    pub url_ttl_secs: i64,
+    signer: Option<UrlSigner>,
+    distribution_id: Option<String>,
+    client: aws_sdk_cloudfront::Client,
+}
+
+impl CdnConfig {
+    /// `Ok(None)` when `cdn.domain` is unset (links stay raw S3 URLs).
+    pub fn from_config(
+        config: &CdnSection,
+        aws_config: &aws_config::SdkConfig,
+    ) -> anyhow::Result<Option<Self>> {
+        let domain = match config.domain.as_deref().map(str::trim) {
+            Some(d) if !d.is_empty() => d
+                .trim_start_matches("https://")
+                .trim_end_matches('/')
+                .to_string(),
+            _ => return Ok(None),
+        };
+
+        let signer = match (&config.key_pair_id, &config.private_key_path) {
+            (Some(key_pair_id), Some(path)) => {
+                let pem = std::fs::read_to_string(path).map_err(|e| {
+                    anyhow!(
+                        "Failed to read CLOUDFRONT_PRIVATE_KEY_PATH {}: {e}",
+                        path.display()
+                    )
+                })?;
+                let key = RsaPrivateKey::from_pkcs1_pem(&pem)
+                    .or_else(|_| RsaPrivateKey::from_pkcs8_pem(&pem))
+                    .map_err(|e| {
+                        anyhow!("Invalid CloudFront private key {}: {e}", path.display())
+                    })?;
+                Some(UrlSigner {
+                    key_pair_id: key_pair_id.clone(),
+                    signing_key: SigningKey::<Sha1>::new(key),
+                })
+            }
+            (None, None) => None,
+            _ => {
+                return Err(anyhow!(
+                    "CLOUDFRONT_KEY_PAIR_ID and CLOUDFRONT_PRIVATE_KEY_PATH must be set together"
+                ));
+            }
+        };
+
+        let url_ttl_secs = config.url_ttl_secs;
+        let distribution_id = config
+            .distribution_id
+            .clone()
+            .filter(|s| !s.trim().is_empty());
+
+        info!(
+            domain = %domain,
+            signed = signer.is_some(),
+            url_ttl_secs,
+            invalidations = distribution_id.is_some(),
+            "CloudFront image delivery configured"
+        );
+
+        Ok(Some(Self {
+            domain,
+            url_ttl_secs,
+            signer,
+            distribution_id,
+            client: aws_sdk_cloudfront::Client::new(aws_config),
+        }))
+    }
+
+    /// Client-facing URL for a bucket-relative key (see
+    /// `StorageBackend::key_from_url`).
+    pub fn key_url(&self, key: &str) -> String {
+        let url = format!("https://{}/{}", self.domain, key.trim_start_matches('/'));
+        match &self.signer {
+            Some(signer) => self.sign(signer, &url),
+            None => url,
+        }
+    }
+
+    fn sign(&self, signer: &UrlSigner, url: &str) -> String {
+        let bucket = (self.url_ttl_secs / EXPIRY_BUCKETS_PER_TTL).max(60);
+        let expires = ((Utc::now().timestamp() + self.url_ttl_secs) / bucket + 1) * bucket;
+
+        let policy = format!(
+            r#"{{"Statement":[{{"Resource":"{url}","Condition":{{"DateLessThan":{{"AWS:EpochTime":{expires}}}}}}}]}}"#
+        );
+        let signature = signer.signing_key.sign(policy.as_bytes()).to_vec();
+
+        let separator = if url.contains('?') { '&' } else { '?' };
+        format!(
+            "{url}{separator}Expires={expires}&Signature={}&Key-Pair-Id={}",
+            cloudfront_base64(&signature),
+            signer.key_pair_id
+        )
+    }
+
+    /// Invalidate cached copies of `keys` (bucket-relative). Runs in the
+    /// background; failures are logged, since the objects are already gone or
+    /// replaced at the origin and only expire more slowly.
+    pub fn invalidate(&self, keys: Vec<String>) {
+        let Some(distribution_id) = self.distribution_id.clone() else {
+            return;
+        };
+        if keys.is_empty() {
+            return;
+        }
+        let client = self.client.clone();
+
+        tokio::spawn(async move {
+            let paths: Vec<String> = keys
+                .iter()
+                .map(|key| format!("/{}", key.trim_start_matches('/')))
+                .collect();
+            let count = paths.len();
+
+            let batch = Paths::builder()
+                .quantity(count as i32)
+                .set_items(Some(paths))
+                .build()
+                .map_err(anyhow::Error::from)
+                .and_then(|paths| {
+                    InvalidationBatch::builder()
+                        .paths(paths)
+                        .caller_reference(Uuid::new_v4().to_string())
+                        .build()
+                        .map_err(anyhow::Error::from)
+                });
+
+            let batch = match batch {
+                Ok(batch) => batch,
+                Err(e) => {
+                    error!(error = %e, "Failed to build CloudFront invalidation batch");
+                    return;
+                }
+            };
+
+            match client
+                .create_invalidation()
+                .distribution_id(&distribution_id)
+                .invalidation_batch(batch)
+                .send()
+                .await
+            {
+                Ok(_) => info!(
+                    distribution_id = %distribution_id,
+                    paths = count,
+                    "Issued CloudFront invalidation"
+                ),
+                Err(e) => error!(
+                    error = ?e,
+                    distribution_id = %distribution_id,
+                    paths = count,
+                    "Failed to issue CloudFront invalidation"
+                ),
+            }
+        });
+    }
+}
+
+/// CloudFront's URL-safe base64 variant: `+` -> `-`, `=` -> `_`, `/` -> `~`.
+fn cloudfront_base64(bytes: &[u8]) -> String {
+    STANDARD
+        .encode(bytes)
+        .chars()
+        .map(|c| match c {
+            '+' => '-',
+            '=' => '_',
+            '/' => '~',
+            other => other,
+        })
+        .collect()
+}
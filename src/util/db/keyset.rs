@@ -0,0 +1,200 @@
+//! Keyset pagination over `(created_at, id)`.
+//!
+//! `OFFSET n` makes Postgres walk and discard `n` rows, so deep pages of a
+//! large table get slower the further back they are. A keyset page instead
+//! starts right after the last row of the previous one with a row-value
+//! comparison, `(created_at, id) < ($1, $2)`, which a `(created_at, id)`
+//! index answers directly at any depth. The id breaks ties between rows
+//! written in the same microsecond.
+//!
+//! ```ignore
+//! let rows: Vec<AccessLog> = access_log::table
+//!     .select(AccessLog::as_select())
+//!     .into_boxed()
+//!     .keyset_page(
+//!         (access_log::access_log_created_at, access_log::access_log_id),
+//!         after.as_ref(),
+//!         KeysetOrder::NewestFirst,
+//!         page_size + 1,
+//!     )
+//!     .load(&mut conn)
+//!     .await?;
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    Column,
+    dsl::{AsExprOf, sql},
+    expression::{SqlLiteral, UncheckedBind},
+    query_dsl::methods::{FilterDsl, LimitDsl, OrderDsl},
+    sql_types::{Bool, Text, Timestamptz},
+};
+use uuid::Uuid;
+
+use crate::dto::responses::pagination::{decode_cursor, encode_cursor};
+
+/// Id column types a keyset can bind.
+pub trait KeysetId: Copy + fmt::Display + FromStr {
+    /// Postgres type the bound id is cast to.
+    const PG_TYPE: &'static str;
+}
+
+impl KeysetId for Uuid {
+    const PG_TYPE: &'static str = "uuid";
+}
+
+impl KeysetId for i64 {
+    const PG_TYPE: &'static str = "int8";
+}
+
+/// Direction of a keyset walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeysetOrder {
+    NewestFirst,
+    OldestFirst,
+}
+
+impl KeysetOrder {
+    fn sql(self) -> (&'static str, &'static str) {
+        match self {
+            // (direction, comparison that means "after" in that direction)
+            Self::NewestFirst => ("DESC", "<"),
+            Self::OldestFirst => ("ASC", ">"),
+        }
+    }
+}
+
+/// Sort position of one row: the last row of a page, from which the next
+/// page starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keyset<Id> {
+    pub at: DateTime<Utc>,
+    pub id: Id,
+}
+
+impl<Id: KeysetId> Keyset<Id> {
+    pub fn new(at: DateTime<Utc>, id: Id) -> Self {
+        Self { at, id }
+    }
+
+    /// Opaque cursor for API responses; see `CursorPage::next_cursor`.
+    pub fn to_cursor(&self) -> String {
+        encode_cursor(&self.to_string())
+    }
+
+    /// Inverse of `to_cursor`; `None` for anything it did not produce.
+    pub fn from_cursor(cursor: &str) -> Option<Self> {
+        decode_cursor(cursor)?.parse().ok()
+    }
+}
+
+/// `<unix micros>_<id>`, the key `CursorPage::from_overfetched` encodes.
+impl<Id: KeysetId> fmt::Display for Keyset<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.at.timestamp_micros(), self.id)
+    }
+}
+
+impl<Id: KeysetId> FromStr for Keyset<Id> {
+    type Err = ();
+
+    fn from_str(key: &str) -> Result<Self, ()> {
+        let (micros, id) = key.split_once('_').ok_or(())?;
+        let at =
+            DateTime::<Utc>::from_timestamp_micros(micros.parse().map_err(|_| ())?).ok_or(())?;
+        Ok(Self {
+            at,
+            id: id.parse().map_err(|_| ())?,
+        })
+    }
+}
+
+/// The `(created_at, id) < ($1, $2)` row comparison built by
+/// [`KeysetPaginate::keyset_page`].
+type KeysetPredicate = SqlLiteral<
+    Bool,
+    UncheckedBind<
+        SqlLiteral<Bool, UncheckedBind<SqlLiteral<Bool>, AsExprOf<DateTime<Utc>, Timestamptz>>>,
+        AsExprOf<String, Text>,
+    >,
+>;
+
+/// Keyset paging for boxed select queries.
+pub trait KeysetPaginate<Id: KeysetId>: Sized {
+    /// Orders by `columns` (timestamp, id) in `order`, keeps only rows past
+    /// `after`, and takes up to `limit` rows. Column names are emitted
+    /// unqualified, so they must be unambiguous in the query; this schema's
+    /// table-prefixed names are.
+    fn keyset_page<C, I>(
+        self,
+        columns: (C, I),
+        after: Option<&Keyset<Id>>,
+        order: KeysetOrder,
+        limit: i64,
+    ) -> Self
+    where
+        C: Column,
+        I: Column;
+}
+
+impl<Q, Id> KeysetPaginate<Id> for Q
+where
+    Q: OrderDsl<SqlLiteral<Timestamptz>, Output = Q>
+        + FilterDsl<KeysetPredicate, Output = Q>
+        + LimitDsl<Output = Q>,
+    Id: KeysetId,
+{
+    fn keyset_page<C, I>(
+        self,
+        _columns: (C, I),
+        after: Option<&Keyset<Id>>,
+        order: KeysetOrder,
+        limit: i64,
+    ) -> Self
+    where
+        C: Column,
+        I: Column,
+    {
+        let (direction, past) = order.sql();
+        let mut query = self.order(sql::<Timestamptz>(&format!(
+            "\"{}\" {direction}, \"{}\" {direction}",
+            C::NAME,
+            I::NAME
+        )));
+        if let Some(after) = after {
+            query = query.filter(
+                sql::<Bool>(&format!("(\"{}\", \"{}\") {past} (", C::NAME, I::NAME))
+                    .bind::<Timestamptz, _>(after.at)
+                    .sql(", CAST(")
+                    .bind::<Text, _>(after.id.to_string())
+                    .sql(&format!(" AS {}))", Id::PG_TYPE)),
+            );
+        }
+        query.limit(limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyset_cursor_round_trip() {
+        let at = DateTime::<Utc>::from_timestamp_micros(1_760_000_000_123_456).unwrap();
+        let id = Uuid::nil();
+        let keyset = Keyset::new(at, id);
+        assert_eq!(
+            Keyset::<Uuid>::from_cursor(&keyset.to_cursor()),
+            Some(keyset)
+        );
+
+        let numeric = Keyset::new(at, 42_i64);
+        assert_eq!(numeric.to_string(), "1760000000123456_42");
+        assert_eq!("1760000000123456_42".parse(), Ok(numeric));
+
+        assert_eq!(Keyset::<Uuid>::from_cursor("not a cursor"), None);
+        assert!("1760000000123456_42".parse::<Keyset<Uuid>>().is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod keyset;
+pub mod transaction;
@@ -0,0 +1,50 @@
+//! The callback type of `ServerState::transaction`.
+//!
+//! A transaction body borrows the connection for some `'conn` chosen by the
+//! caller of the callback, and often captures borrows from the handler too
+//! (`&[String]`, the request body). A plain `for<'conn> FnOnce(&'conn mut
+//! AsyncPgConnection) -> BoxFuture<'conn, _>` would demand that those
+//! captures outlive every `'conn`, which they cannot. [`ScopedBoxFuture`]
+//! carries an implied `'env: 'conn` bound so the body only has to be valid
+//! for connection borrows shorter than what it captures.
+//!
+//! ```ignore
+//! state
+//!     .transaction(|conn| {
+//!         async move {
+//!             diesel::insert_into(posts::table).values(&row).execute(conn).await?;
+//!             Ok(())
+//!         }
+//!         .scope_boxed()
+//!     })
+//!     .await
+//! ```
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+/// Well-formed only when `'env: 'conn`; as the default parameter of
+/// [`ScopedFuture`] it makes every use imply that bound.
+pub type ImpliedLifetimeBound<'env, 'conn> = PhantomData<&'conn &'env ()>;
+
+/// A future living for `'conn` that may borrow anything outliving `'env`.
+pub trait ScopedFuture<'env, 'conn, Bound = ImpliedLifetimeBound<'env, 'conn>>: Future {}
+
+impl<'env: 'conn, 'conn, Fut: Future + 'conn> ScopedFuture<'env, 'conn> for Fut {}
+
+pub type ScopedBoxFuture<'env, 'conn, T> =
+    Pin<Box<dyn ScopedFuture<'env, 'conn, Output = T> + Send + 'conn>>;
+
+pub trait ScopedFutureExt: Future + Send + Sized {
+    /// Box the future as a transaction body.
+    fn scope_boxed<'env, 'conn>(self) -> ScopedBoxFuture<'env, 'conn, Self::Output>
+    where
+        Self: 'conn,
+        'env: 'conn,
+    {
+        Box::pin(self)
+    }
+}
+
+impl<Fut: Future + Send> ScopedFutureExt for Fut {}
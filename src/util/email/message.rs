@@ -0,0 +1,140 @@
+//! Typed builder for transactional emails.
+
+use std::collections::{BTreeMap, HashMap};
+
+use lettre::message::{
+    Mailbox, MultiPart,
+    header::{HeaderName, HeaderValue},
+};
+use tracing::error;
+
+use super::sender::SenderIdentity;
+use super::template::{EmailTemplate, render};
+use super::unsubscribe::{one_click_unsubscribe_link, unsubscribe_page_link};
+use crate::domain::i18n::ui_text::locale::UiLocale;
+
+/// Var holding the one-click unsubscribe URL; its presence adds the
+/// `List-Unsubscribe` headers.
+const ONE_CLICK_VAR: &str = "one_click_unsubscribe";
+
+/// A transactional email before rendering: which template, to whom, and the
+/// values its texts and links refer to. The language is the recipient's
+/// unless [`Self::locale`] pins one.
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    template: EmailTemplate,
+    to: String,
+    locale: Option<UiLocale>,
+    vars: BTreeMap<String, String>,
+}
+
+impl EmailMessage {
+    pub fn new(template: EmailTemplate, to: impl Into<String>) -> Self {
+        Self {
+            template,
+            to: to.into(),
+            locale: None,
+            vars: BTreeMap::new(),
+        }
+    }
+
+    /// Rebuild a message stored in the outbox.
+    pub fn from_parts(
+        template: EmailTemplate,
+        to: String,
+        locale: Option<UiLocale>,
+        vars: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            template,
+            to,
+            locale,
+            vars,
+        }
+    }
+
+    pub fn locale(mut self, locale: UiLocale) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// `{name}` in the email's texts and `vars.name` in its template.
+    pub fn var(mut self, name: &str, value: impl ToString) -> Self {
+        self.vars.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Offer an opt-out with the signed `token`: `vars.unsubscribe_link` for
+    /// the footer, and the RFC 8058 `List-Unsubscribe` headers.
+    pub fn unsubscribe(self, token: &str) -> Self {
+        self.var("unsubscribe_link", unsubscribe_page_link(token))
+            .var(ONE_CLICK_VAR, one_click_unsubscribe_link(token))
+    }
+
+    pub fn template(&self) -> EmailTemplate {
+        self.template
+    }
+
+    pub fn recipient(&self) -> &str {
+        &self.to
+    }
+
+    /// The pinned language, if any.
+    pub fn locale_override(&self) -> Option<UiLocale> {
+        self.locale
+    }
+
+    pub fn vars(&self) -> &BTreeMap<String, String> {
+        &self.vars
+    }
+
+    /// Render in `locale` against `texts`, the UI text bundle for it, as a
+    /// plain-text and HTML alternative from `sender`.
+    pub fn to_message(
+        self,
+        sender: &SenderIdentity,
+        locale: UiLocale,
+        texts: &HashMap<String, String>,
+    ) -> anyhow::Result<lettre::Message> {
+        let rendered = render(self.template, locale.as_tag(), texts, &self.vars)?;
+        let to = parse_mailbox(&self.to, "to")?;
+        let mut builder = lettre::Message::builder()
+            .from(sender.from.clone())
+            .to(to)
+            .subject(sender.subject(&rendered.subject));
+        if let Some(reply_to) = &sender.reply_to {
+            builder = builder.reply_to(reply_to.clone());
+        }
+        if let Some(link) = self.vars.get(ONE_CLICK_VAR) {
+            builder = builder
+                .raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str("List-Unsubscribe"),
+                    format!("<{link}>"),
+                ))
+                .raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str("List-Unsubscribe-Post"),
+                    "List-Unsubscribe=One-Click".to_string(),
+                ));
+        }
+        match builder.multipart(MultiPart::alternative_plain_html(
+            rendered.text,
+            rendered.html,
+        )) {
+            Ok(message) => Ok(message),
+            Err(e) => {
+                error!(template = self.template.as_str(), error = %e, "Failed to build email");
+                Err(e.into())
+            }
+        }
+    }
+}
+
+fn parse_mailbox(raw: &str, field: &'static str) -> anyhow::Result<Mailbox> {
+    match raw.parse::<Mailbox>() {
+        Ok(mailbox) => Ok(mailbox),
+        Err(e) => {
+            error!(field, error = %e, "Failed to parse email mailbox");
+            Err(e.into())
+        }
+    }
+}
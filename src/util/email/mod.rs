@@ -1 +1,6 @@
-pub mod emails;
+pub mod message;
+pub mod sender;
+pub mod sns;
+pub mod template;
+pub mod transport;
+pub mod unsubscribe;
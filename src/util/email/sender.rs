@@ -0,0 +1,112 @@
+//! Who outgoing email is from: `From`, `Reply-To` and the environment tag,
+//! resolved once from `[email]` and the deployment environment.
+
+use anyhow::Context;
+use lettre::message::Mailbox;
+
+use crate::init::app_config::EmailSection;
+use crate::init::state::DeploymentEnvironment;
+
+#[derive(Debug, Clone)]
+pub struct SenderIdentity {
+    pub from: Mailbox,
+    pub reply_to: Option<Mailbox>,
+    /// e.g. `[staging]`; `None` in prod unless configured.
+    pub tag: Option<String>,
+}
+
+impl SenderIdentity {
+    /// `[email]` with `[email.environments.<env>]` laid over it. Fails on an
+    /// address that does not parse.
+    pub fn from_config(email: &EmailSection, env: DeploymentEnvironment) -> anyhow::Result<Self> {
+        let over = email.environments.get(env.as_str());
+        let pick = |base: &Option<String>, over: Option<&Option<String>>| {
+            over.and_then(|value| value.clone())
+                .or_else(|| base.clone())
+        };
+
+        let address = over
+            .and_then(|o| o.from_address.clone())
+            .unwrap_or_else(|| email.from_address.clone());
+        let name = over
+            .and_then(|o| o.from_name.clone())
+            .unwrap_or_else(|| email.from_name.clone());
+        let reply_to = pick(&email.reply_to, over.map(|o| &o.reply_to));
+        let tag = match pick(&email.environment_tag, over.map(|o| &o.environment_tag)) {
+            Some(tag) => Some(tag.trim().to_string()).filter(|tag| !tag.is_empty()),
+            None => match env {
+                DeploymentEnvironment::Prod => None,
+                other => Some(format!("[{}]", other.as_str())),
+            },
+        };
+
+        let address = address
+            .trim()
+            .parse()
+            .with_context(|| format!("from_address {address:?} is not an email address"))?;
+        let name = name.trim();
+        let display = match (&tag, name.is_empty()) {
+            (Some(tag), false) => Some(format!("{tag} {name}")),
+            (Some(tag), true) => Some(tag.clone()),
+            (None, false) => Some(name.to_string()),
+            (None, true) => None,
+        };
+        let reply_to = reply_to
+            .map(|raw| raw.trim().to_string())
+            .filter(|raw| !raw.is_empty())
+            .map(|raw| {
+                raw.parse::<Mailbox>()
+                    .with_context(|| format!("reply_to {raw:?} is not a mailbox"))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            from: Mailbox::new(display, address),
+            reply_to,
+            tag,
+        })
+    }
+
+    /// `subject` with the environment tag in front, if there is one.
+    pub fn subject(&self, subject: &str) -> String {
+        match &self.tag {
+            Some(tag) => format!("{tag} {subject}"),
+            None => subject.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init::app_config::EmailSenderOverride;
+
+    #[test]
+    fn tags_non_prod_and_applies_environment_overrides() {
+        let mut email = EmailSection::default();
+        let prod = SenderIdentity::from_config(&email, DeploymentEnvironment::Prod).unwrap();
+        assert_eq!(prod.tag, None);
+        assert_eq!(prod.subject("Hi"), "Hi");
+
+        let staging = SenderIdentity::from_config(&email, DeploymentEnvironment::Staging).unwrap();
+        assert_eq!(staging.subject("Hi"), "[staging] Hi");
+        assert_eq!(staging.from.name.as_deref(), Some("[staging] cyhdev.com"));
+
+        email.environments.insert(
+            "staging".to_string(),
+            EmailSenderOverride {
+                from_address: Some("staging@example.com".to_string()),
+                environment_tag: Some(String::new()),
+                reply_to: Some("Team <team@example.com>".to_string()),
+                ..Default::default()
+            },
+        );
+        let staging = SenderIdentity::from_config(&email, DeploymentEnvironment::Staging).unwrap();
+        assert_eq!(staging.tag, None);
+        assert_eq!(staging.from.email.to_string(), "staging@example.com");
+        assert!(staging.reply_to.is_some());
+
+        email.from_address = "not an address".to_string();
+        assert!(SenderIdentity::from_config(&email, DeploymentEnvironment::Prod).is_err());
+    }
+}
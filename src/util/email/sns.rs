@@ -0,0 +1,219 @@
+//! Amazon SNS HTTP(S) messages: parsing and signature verification.
+//!
+//! SNS signs every message with a key whose certificate it publishes at
+//! `SigningCertURL`. The URL must be HTTPS on an `sns.<region>.amazonaws.com`
+//! host; certificates are fetched once per URL and kept for the life of the
+//! process. `SignatureVersion` 1 is SHA1withRSA, 2 is SHA256withRSA.
+
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use rsa::{
+    RsaPublicKey,
+    pkcs1v15::{Signature, VerifyingKey},
+    pkcs8::DecodePublicKey,
+    signature::Verifier,
+};
+use serde_derive::Deserialize;
+use sha1::Sha1;
+use sha2::Sha256;
+use x509_cert::{
+    Certificate,
+    der::{DecodePem, Encode},
+};
+
+/// One SNS message as POSTed to a subscribed endpoint.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SnsMessage {
+    /// `SubscriptionConfirmation`, `Notification` or
+    /// `UnsubscribeConfirmation`.
+    #[serde(rename = "Type")]
+    pub kind: String,
+    pub message_id: String,
+    pub topic_arn: String,
+    pub subject: Option<String>,
+    pub message: String,
+    pub timestamp: String,
+    pub token: Option<String>,
+    #[serde(rename = "SubscribeURL")]
+    pub subscribe_url: Option<String>,
+    pub signature_version: String,
+    pub signature: String,
+    #[serde(rename = "SigningCertURL")]
+    pub signing_cert_url: String,
+}
+
+/// Why [`SnsMessage::verify`] did not accept a message.
+#[derive(Debug)]
+pub enum SnsVerifyError {
+    /// The message is malformed, unsigned or signed by someone else.
+    Invalid(String),
+    /// The signing certificate could not be fetched; worth retrying.
+    CertUnavailable(anyhow::Error),
+}
+
+impl SnsMessage {
+    pub fn is_notification(&self) -> bool {
+        self.kind == "Notification"
+    }
+
+    pub fn is_subscription_confirmation(&self) -> bool {
+        self.kind == "SubscriptionConfirmation"
+    }
+
+    /// The canonical string SNS signed: selected fields as `Name\nvalue\n`
+    /// in a fixed order that depends on the message type.
+    fn string_to_sign(&self) -> Result<String, SnsVerifyError> {
+        let missing = |field: &str| SnsVerifyError::Invalid(format!("{field} missing"));
+        let mut fields: Vec<(&str, &str)> = vec![("Message", &self.message)];
+        fields.push(("MessageId", &self.message_id));
+        if self.is_notification() {
+            if let Some(subject) = &self.subject {
+                fields.push(("Subject", subject));
+            }
+        } else {
+            let url = self
+                .subscribe_url
+                .as_deref()
+                .ok_or_else(|| missing("SubscribeURL"))?;
+            fields.push(("SubscribeURL", url));
+        }
+        fields.push(("Timestamp", &self.timestamp));
+        if !self.is_notification() {
+            let token = self.token.as_deref().ok_or_else(|| missing("Token"))?;
+            fields.push(("Token", token));
+        }
+        fields.push(("TopicArn", &self.topic_arn));
+        fields.push(("Type", &self.kind));
+
+        Ok(fields
+            .into_iter()
+            .map(|(name, value)| format!("{name}\n{value}\n"))
+            .collect())
+    }
+
+    /// Check the signature against the certificate at `SigningCertURL`.
+    pub async fn verify(&self, client: &reqwest::Client) -> Result<(), SnsVerifyError> {
+        if !is_sns_url(&self.signing_cert_url) {
+            return Err(SnsVerifyError::Invalid(format!(
+                "SigningCertURL {} is not an SNS URL",
+                self.signing_cert_url
+            )));
+        }
+        let signed = self.string_to_sign()?;
+        let signature = STANDARD
+            .decode(self.signature.as_bytes())
+            .ok()
+            .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+            .ok_or_else(|| SnsVerifyError::Invalid("Signature is not valid base64".into()))?;
+        let key = signing_key(client, &self.signing_cert_url)
+            .await
+            .map_err(SnsVerifyError::CertUnavailable)?;
+
+        let verified = match self.signature_version.as_str() {
+            "1" => VerifyingKey::<Sha1>::new(key).verify(signed.as_bytes(), &signature),
+            "2" => VerifyingKey::<Sha256>::new(key).verify(signed.as_bytes(), &signature),
+            other => {
+                return Err(SnsVerifyError::Invalid(format!(
+                    "unknown SignatureVersion {other}"
+                )));
+            }
+        };
+        verified.map_err(|_| SnsVerifyError::Invalid("signature does not match".into()))
+    }
+}
+
+/// Whether `url` is HTTPS on an SNS endpoint (`sns.<region>.amazonaws.com`,
+/// or `.amazonaws.com.cn`), as signing certificates and subscribe links are.
+pub fn is_sns_url(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let Some(region) = host.strip_prefix("sns.").and_then(|rest| {
+        rest.strip_suffix(".amazonaws.com")
+            .or_else(|| rest.strip_suffix(".amazonaws.com.cn"))
+    }) else {
+        return false;
+    };
+    url.scheme() == "https"
+        && url.port().is_none()
+        && !region.is_empty()
+        && region
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn key_cache() -> &'static scc::HashMap<String, RsaPublicKey> {
+    static KEYS: OnceLock<scc::HashMap<String, RsaPublicKey>> = OnceLock::new();
+    KEYS.get_or_init(scc::HashMap::new)
+}
+
+async fn signing_key(client: &reqwest::Client, url: &str) -> anyhow::Result<RsaPublicKey> {
+    if let Some(key) = key_cache().read_async(url, |_, key| key.clone()).await {
+        return Ok(key);
+    }
+    let pem = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    let cert = Certificate::from_pem(&pem).map_err(|e| anyhow!("bad SNS certificate: {e}"))?;
+    let spki = cert
+        .tbs_certificate
+        .subject_public_key_info
+        .to_der()
+        .map_err(|e| anyhow!("bad SNS certificate key: {e}"))?;
+    let key = RsaPublicKey::from_public_key_der(&spki)
+        .map_err(|e| anyhow!("SNS certificate key is not RSA: {e}"))?;
+    let _ = key_cache().upsert_async(url.to_string(), key.clone()).await;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_https_sns_hosts() {
+        assert!(is_sns_url(
+            "https://sns.us-east-1.amazonaws.com/SimpleNotificationService-abc.pem"
+        ));
+        assert!(is_sns_url("https://sns.cn-north-1.amazonaws.com.cn/x.pem"));
+        assert!(!is_sns_url("http://sns.us-east-1.amazonaws.com/x.pem"));
+        assert!(!is_sns_url(
+            "https://sns.us-east-1.amazonaws.com.evil.com/x.pem"
+        ));
+        assert!(!is_sns_url(
+            "https://evil.com/sns.us-east-1.amazonaws.com/x.pem"
+        ));
+        assert!(!is_sns_url("https://sns..amazonaws.com/x.pem"));
+    }
+
+    #[test]
+    fn notification_string_to_sign_skips_subscription_fields() {
+        let message = SnsMessage {
+            kind: "Notification".into(),
+            message_id: "id".into(),
+            topic_arn: "arn".into(),
+            subject: None,
+            message: "{}".into(),
+            timestamp: "t".into(),
+            token: None,
+            subscribe_url: None,
+            signature_version: "1".into(),
+            signature: String::new(),
+            signing_cert_url: String::new(),
+        };
+        assert_eq!(
+            message.string_to_sign().unwrap(),
+            "Message\n{}\nMessageId\nid\nTimestamp\nt\nTopicArn\narn\nType\nNotification\n"
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Email templates (`templates/`), compiled into the binary and rendered with
+//! minijinja: an HTML part (`<name>.html`, escaped) and a plain-text part
+//! (`<name>.txt`, not escaped) per email. Templates hold only markup; every
+//! sentence comes from the `email.*` UI text keys, so each language is a row
+//! in `i18n_strings` rather than another template file.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use minijinja::{Environment, context};
+
+use crate::DOMAIN_NAME;
+
+/// One kind of transactional email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTemplate {
+    VerifyEmail,
+    PasswordReset,
+    NotificationDigest,
+}
+
+impl EmailTemplate {
+    pub const ALL: [EmailTemplate; 3] = [
+        Self::VerifyEmail,
+        Self::PasswordReset,
+        Self::NotificationDigest,
+    ];
+
+    /// Stored name, as in `email_outbox.email_outbox_template`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::VerifyEmail => "verify_email",
+            Self::PasswordReset => "password_reset",
+            Self::NotificationDigest => "notification_digest",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "verify_email" => Some(Self::VerifyEmail),
+            "password_reset" => Some(Self::PasswordReset),
+            "notification_digest" => Some(Self::NotificationDigest),
+            _ => None,
+        }
+    }
+
+    fn file(self) -> &'static str {
+        match self {
+            Self::VerifyEmail => "verify_email.html",
+            Self::PasswordReset => "password_reset.html",
+            Self::NotificationDigest => "notification_digest.html",
+        }
+    }
+
+    fn text_file(self) -> &'static str {
+        match self {
+            Self::VerifyEmail => "verify_email.txt",
+            Self::PasswordReset => "password_reset.txt",
+            Self::NotificationDigest => "notification_digest.txt",
+        }
+    }
+
+    /// Placeholder values for every var the template uses, for previews.
+    pub fn sample_vars(self) -> BTreeMap<String, String> {
+        let token = "00000000-0000-0000-0000-000000000000";
+        let vars = match self {
+            Self::VerifyEmail => vec![
+                (
+                    "link",
+                    format!(
+                        "https://{DOMAIN_NAME}/api/auth/verify-user-email?email_validation_token_id={token}"
+                    ),
+                ),
+                ("valid_until", "2026-01-01 00:00 UTC".to_string()),
+            ],
+            Self::PasswordReset => vec![(
+                "link",
+                format!("https://{DOMAIN_NAME}/reset-password?token={token}"),
+            )],
+            Self::NotificationDigest => vec![
+                ("count", "3".to_string()),
+                ("link", format!("https://{DOMAIN_NAME}/notifications")),
+                (
+                    "unsubscribe_link",
+                    format!("https://{DOMAIN_NAME}/unsubscribe?token=sample"),
+                ),
+            ],
+        };
+        vars.into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    /// UI text key prefix for this email's own strings; the template sees
+    /// them as `t.<rest of key>`.
+    fn text_prefix(self) -> &'static str {
+        match self {
+            Self::VerifyEmail => "email.verify_email.",
+            Self::PasswordReset => "email.password_reset.",
+            Self::NotificationDigest => "email.notification_digest.",
+        }
+    }
+}
+
+/// Strings shared by every email, seen as `common.<rest of key>`.
+const COMMON_TEXT_PREFIX: &str = "email.common.";
+
+fn environment() -> &'static Environment<'static> {
+    static ENV: OnceLock<Environment<'static>> = OnceLock::new();
+    ENV.get_or_init(|| {
+        let mut env = Environment::new();
+        for (name, source) in [
+            ("layout.html", include_str!("./templates/layout.html")),
+            (
+                "verify_email.html",
+                include_str!("./templates/verify_email.html"),
+            ),
+            (
+                "password_reset.html",
+                include_str!("./templates/password_reset.html"),
+            ),
+            (
+                "notification_digest.html",
+                include_str!("./templates/notification_digest.html"),
+            ),
+            ("layout.txt", include_str!("./templates/layout.txt")),
+            (
+                "verify_email.txt",
+                include_str!("./templates/verify_email.txt"),
+            ),
+            (
+                "password_reset.txt",
+                include_str!("./templates/password_reset.txt"),
+            ),
+            (
+                "notification_digest.txt",
+                include_str!("./templates/notification_digest.txt"),
+            ),
+        ] {
+            env.add_template(name, source)
+                .unwrap_or_else(|e| panic!("email template {name} does not compile: {e}"));
+        }
+        env
+    })
+}
+
+/// A rendered email: the localized subject, the HTML body and its plain-text
+/// alternative.
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+/// Render `template` with `texts` (a UI text bundle, as from
+/// `ServerState::ui_text_bundle`) for the language tagged `lang`. `{name}`
+/// placeholders in the texts are filled from `vars`, which the template also
+/// sees as `vars.<name>`. Everything is HTML-escaped in the HTML part.
+pub fn render(
+    template: EmailTemplate,
+    lang: &str,
+    texts: &HashMap<String, String>,
+    vars: &BTreeMap<String, String>,
+) -> anyhow::Result<RenderedEmail> {
+    let own = texts_with_prefix(texts, template.text_prefix(), vars);
+    let common = texts_with_prefix(texts, COMMON_TEXT_PREFIX, vars);
+    let subject = own
+        .get("subject")
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("No subject text for {}", template.file()))?;
+
+    let ctx = context! {
+        lang => lang,
+        subject => &subject,
+        t => own,
+        common => common,
+        vars => vars,
+    };
+    let html = environment().get_template(template.file())?.render(&ctx)?;
+    let text = environment()
+        .get_template(template.text_file())?
+        .render(&ctx)?;
+    Ok(RenderedEmail {
+        subject,
+        html,
+        text,
+    })
+}
+
+fn texts_with_prefix(
+    texts: &HashMap<String, String>,
+    prefix: &str,
+    vars: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    texts
+        .iter()
+        .filter_map(|(key, text)| {
+            let name = key.strip_prefix(prefix)?;
+            let filled = vars.iter().fold(text.clone(), |text, (var, value)| {
+                text.replace(&format!("{{{var}}}"), value)
+            });
+            Some((name.to_string(), filled))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_texts_and_escapes_vars() {
+        let texts: HashMap<String, String> = [
+            ("email.common.footer", "Footer"),
+            ("email.common.ignore", "Ignore it."),
+            ("email.verify_email.subject", "Verify"),
+            ("email.verify_email.heading", "Heading"),
+            ("email.verify_email.body", "Body"),
+            ("email.verify_email.button", "Go"),
+            ("email.verify_email.expires", "Valid until {valid_until}."),
+            ("email.password_reset.subject", "Other"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let vars = BTreeMap::from([
+            (
+                "link".to_string(),
+                "https://example.com/?a=1&b=2".to_string(),
+            ),
+            ("valid_until".to_string(), "tomorrow".to_string()),
+        ]);
+
+        let email = render(EmailTemplate::VerifyEmail, "en-US", &texts, &vars).unwrap();
+        assert_eq!(email.subject, "Verify");
+        assert!(email.html.contains("<h1>Heading</h1>"));
+        assert!(email.html.contains("Valid until tomorrow. Ignore it."));
+        assert!(email.html.contains("a=1&amp;b=2"));
+        assert!(email.html.contains("<html lang=\"en-US\">"));
+        assert!(email.text.contains("Go: https://example.com/?a=1&b=2"));
+    }
+}
@@ -0,0 +1,228 @@
+//! Local-development implementation of [`EmailTransport`]: every message is
+//! written to `<sink_dir>/<id>.eml` instead of being delivered, and can be
+//! read back through `GET /api/admin/emails/sent`.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::Utc;
+use serde_derive::Serialize;
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::init::app_config::EmailSection;
+
+use super::{EmailSendError, EmailTransport, OutgoingEmail, SentEmail};
+
+pub struct FileTransport {
+    dir: PathBuf,
+}
+
+/// Headers of one captured message.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SinkEmail {
+    /// File name without `.eml`; newer ids sort after older ones.
+    pub id: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub reply_to: Option<String>,
+    pub subject: Option<String>,
+    pub date: Option<String>,
+    pub size_bytes: u64,
+}
+
+impl FileTransport {
+    pub fn from_config(config: &EmailSection) -> Self {
+        info!(dir = %config.sink_dir.display(), "Email transport: local file sink");
+        Self {
+            dir: config.sink_dir.clone(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The newest `limit` captured messages, newest first.
+    pub async fn list(&self, limit: usize) -> anyhow::Result<Vec<SinkEmail>> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut ids = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            if let Some(id) = name.to_str().and_then(|n| n.strip_suffix(".eml")) {
+                ids.push(id.to_string());
+            }
+        }
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        ids.truncate(limit);
+
+        let mut emails = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some((email, _)) = self.read(&id).await? {
+                emails.push(email);
+            }
+        }
+        Ok(emails)
+    }
+
+    /// Headers and raw source of message `id`, or `None` if there is no such
+    /// file (or `id` is not one this sink could have written).
+    pub async fn read(&self, id: &str) -> anyhow::Result<Option<(SinkEmail, String)>> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Ok(None);
+        }
+        let raw = match tokio::fs::read(self.dir.join(format!("{id}.eml"))).await {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let size_bytes = raw.len() as u64;
+        let raw = String::from_utf8_lossy(&raw).into_owned();
+        let header = |name: &str| header_value(&raw, name);
+        let email = SinkEmail {
+            id: id.to_string(),
+            from: header("From"),
+            to: header("To"),
+            reply_to: header("Reply-To"),
+            subject: header("Subject"),
+            date: header("Date"),
+            size_bytes,
+        };
+        Ok(Some((email, raw)))
+    }
+}
+
+#[async_trait]
+impl EmailTransport for FileTransport {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    async fn send(&self, email: OutgoingEmail) -> Result<SentEmail, EmailSendError> {
+        let id = format!(
+            "{}-{}",
+            Utc::now().format("%Y%m%dT%H%M%S%6fZ"),
+            Uuid::new_v4().simple()
+        );
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(EmailSendError::transient)?;
+        tokio::fs::write(
+            self.dir.join(format!("{id}.eml")),
+            email.message.formatted(),
+        )
+        .await
+        .map_err(EmailSendError::transient)?;
+        info!(id = %id, to = ?email.message.envelope().to(), "Email written to local sink");
+        Ok(SentEmail {
+            message_id: Some(id),
+        })
+    }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        Ok(())
+    }
+
+    fn as_file_sink(&self) -> Option<&FileTransport> {
+        Some(self)
+    }
+}
+
+/// First `name` header of `raw`, unfolded, with RFC 2047 encoded words
+/// decoded.
+fn header_value(raw: &str, name: &str) -> Option<String> {
+    let head = raw.split("\r\n\r\n").next().unwrap_or(raw);
+    let mut lines = head.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !key.eq_ignore_ascii_case(name) {
+            continue;
+        }
+        let mut value = value.trim().to_string();
+        while let Some(next) = lines.next_if(|l| l.starts_with([' ', '\t'])) {
+            value.push(' ');
+            value.push_str(next.trim());
+        }
+        return Some(decode_words(&value));
+    }
+    None
+}
+
+/// Decode `=?utf-8?b?...?=` and `=?utf-8?q?...?=` words; anything else is
+/// left as written. Whitespace between two encoded words is dropped.
+fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut previous_encoded = false;
+    for (i, word) in value.split(' ').enumerate() {
+        match decode_word(word) {
+            Some(decoded) => {
+                if i > 0 && !previous_encoded {
+                    out.push(' ');
+                }
+                out.push_str(&decoded);
+                previous_encoded = true;
+            }
+            None => {
+                if i > 0 {
+                    out.push(' ');
+                }
+                out.push_str(word);
+                previous_encoded = false;
+            }
+        }
+    }
+    out
+}
+
+fn decode_word(word: &str) -> Option<String> {
+    let inner = word.strip_prefix("=?")?.strip_suffix("?=")?;
+    let mut parts = inner.splitn(3, '?');
+    let charset = parts.next()?;
+    let encoding = parts.next()?;
+    let text = parts.next()?;
+    if !charset.eq_ignore_ascii_case("utf-8") {
+        return None;
+    }
+    let bytes = match encoding {
+        "b" | "B" => STANDARD.decode(text).ok()?,
+        "q" | "Q" => {
+            let mut bytes = Vec::with_capacity(text.len());
+            let mut chars = text.bytes();
+            while let Some(b) = chars.next() {
+                match b {
+                    b'_' => bytes.push(b' '),
+                    b'=' => {
+                        let hex = [chars.next()?, chars.next()?];
+                        bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+                    }
+                    other => bytes.push(other),
+                }
+            }
+            bytes
+        }
+        _ => return None,
+    };
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::header_value;
+
+    #[test]
+    fn reads_folded_and_encoded_headers() {
+        let raw = "From: a@example.com\r\nSubject: =?utf-8?b?7ZWc6rWt7Ja0?=\r\n =?utf-8?q?_test?=\r\nTo: b@example.com\r\n\r\nSubject: body";
+        assert_eq!(header_value(raw, "from").as_deref(), Some("a@example.com"));
+        assert_eq!(header_value(raw, "Subject").as_deref(), Some("한국어 test"));
+        assert_eq!(header_value(raw, "Reply-To"), None);
+    }
+}
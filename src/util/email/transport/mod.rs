@@ -0,0 +1,123 @@
+//! Delivery of built emails.
+//!
+//! Senders hand a [`lettre::Message`] to the [`EmailTransport`] on
+//! `ServerState::email_transport` instead of talking SMTP themselves.
+//! `EMAIL_TRANSPORT` selects the implementation:
+//! - `smtp` (default): [`smtp::SmtpTransport`], the SES SMTP relay at
+//!   `AWS_SES_SMTP_URL`.
+//! - `ses`: [`ses::SesTransport`], the SES v2 `SendEmail` API with the shared
+//!   AWS credentials. Supports a configuration set (`AWS_SES_CONFIGURATION_SET`)
+//!   and message tags, and reports rejections by error code.
+//! - `file`: [`file::FileTransport`], `.eml` files under `EMAIL_SINK_DIR`
+//!   for local development. The default when `EMAIL_TRANSPORT` is unset in
+//!   the local environment.
+
+pub mod file;
+pub mod ses;
+pub mod smtp;
+
+use std::{fmt, sync::Arc};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::init::app_config::EmailSection;
+use crate::init::state::DeploymentEnvironment;
+
+/// A built message plus delivery metadata.
+pub struct OutgoingEmail {
+    pub message: lettre::Message,
+    /// Name/value pairs attached to the send (SES message tags); transports
+    /// without tagging ignore them.
+    pub tags: Vec<(String, String)>,
+}
+
+impl OutgoingEmail {
+    pub fn new(message: lettre::Message) -> Self {
+        Self {
+            message,
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn tag(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// What the provider said about an accepted message.
+#[derive(Debug, Default)]
+pub struct SentEmail {
+    /// Provider message id (SES `MessageId`); `None` over SMTP.
+    pub message_id: Option<String>,
+}
+
+/// A failed send. `permanent` means retrying the same message cannot succeed
+/// (rejected address, unverified sender, malformed message).
+#[derive(Debug)]
+pub struct EmailSendError {
+    pub permanent: bool,
+    /// Provider error code: the SMTP status (`550`) or SES error name
+    /// (`MessageRejected`).
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl EmailSendError {
+    pub fn transient(message: impl ToString) -> Self {
+        Self {
+            permanent: false,
+            code: None,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for EmailSendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.permanent {
+            "permanent"
+        } else {
+            "transient"
+        };
+        match &self.code {
+            Some(code) => write!(f, "{kind} email failure ({code}): {}", self.message),
+            None => write!(f, "{kind} email failure: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for EmailSendError {}
+
+#[async_trait]
+pub trait EmailTransport: Send + Sync {
+    /// Short transport name for logs (`smtp`, `ses`).
+    fn name(&self) -> &'static str;
+
+    async fn send(&self, email: OutgoingEmail) -> Result<SentEmail, EmailSendError>;
+
+    /// Cheap reachability check for `/healthcheck`.
+    async fn health_check(&self) -> anyhow::Result<()>;
+
+    /// The local sink, when this transport is one.
+    fn as_file_sink(&self) -> Option<&file::FileTransport> {
+        None
+    }
+}
+
+/// Build the transport `config.transport_for(env)` names.
+pub fn email_transport_from_config(
+    config: &EmailSection,
+    env: DeploymentEnvironment,
+    aws_config: &aws_config::SdkConfig,
+) -> anyhow::Result<Arc<dyn EmailTransport>> {
+    match config.transport_for(env).as_str() {
+        "smtp" => Ok(Arc::new(smtp::SmtpTransport::from_config(config)?)),
+        "ses" => Ok(Arc::new(ses::SesTransport::from_config(config, aws_config))),
+        "file" => Ok(Arc::new(file::FileTransport::from_config(config))),
+        other => Err(anyhow!(
+            "Unknown EMAIL_TRANSPORT: {other} (expected smtp, ses or file)"
+        )),
+    }
+}
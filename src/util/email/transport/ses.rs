@@ -0,0 +1,154 @@
+//! SES v2 API implementation of [`EmailTransport`].
+
+use async_trait::async_trait;
+use aws_sdk_sesv2::{
+    error::{DisplayErrorContext, ProvideErrorMetadata, SdkError},
+    primitives::Blob,
+    types::{EmailContent, MessageTag, RawMessage},
+};
+use tracing::info;
+
+use crate::init::app_config::EmailSection;
+
+use super::{EmailSendError, EmailTransport, OutgoingEmail, SentEmail};
+
+/// SES error codes that will fail the same way on every retry.
+const PERMANENT_ERRORS: &[&str] = &[
+    "MessageRejected",
+    "BadRequestException",
+    "MailFromDomainNotVerifiedException",
+    "AccountSuspendedException",
+];
+
+pub struct SesTransport {
+    client: aws_sdk_sesv2::Client,
+    configuration_set: Option<String>,
+}
+
+impl SesTransport {
+    /// Credentials from the shared AWS config; region from
+    /// `email.ses_region` when set.
+    pub fn from_config(config: &EmailSection, aws_config: &aws_config::SdkConfig) -> Self {
+        let mut builder = aws_sdk_sesv2::config::Builder::from(aws_config);
+        if let Some(region) = config
+            .ses_region
+            .as_deref()
+            .filter(|r| !r.trim().is_empty())
+        {
+            builder = builder.region(aws_config::Region::new(region.trim().to_string()));
+        }
+        let client = aws_sdk_sesv2::Client::from_conf(builder.build());
+        let configuration_set = config
+            .ses_configuration_set
+            .clone()
+            .filter(|set| !set.trim().is_empty());
+        info!(
+            configuration_set = configuration_set.as_deref().unwrap_or("(none)"),
+            "Email transport: SES API"
+        );
+        Self {
+            client,
+            configuration_set,
+        }
+    }
+}
+
+#[async_trait]
+impl EmailTransport for SesTransport {
+    fn name(&self) -> &'static str {
+        "ses"
+    }
+
+    async fn send(&self, email: OutgoingEmail) -> Result<SentEmail, EmailSendError> {
+        let raw = RawMessage::builder()
+            .data(Blob::new(email.message.formatted()))
+            .build()
+            .map_err(|e| EmailSendError {
+                permanent: true,
+                code: None,
+                message: e.to_string(),
+            })?;
+        let tags = email
+            .tags
+            .into_iter()
+            .map(|(name, value)| {
+                MessageTag::builder()
+                    .name(ses_tag_value(&name))
+                    .value(ses_tag_value(&value))
+                    .build()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(EmailSendError::transient)?;
+
+        let output = self
+            .client
+            .send_email()
+            .content(EmailContent::builder().raw(raw).build())
+            .set_configuration_set_name(self.configuration_set.clone())
+            .set_email_tags((!tags.is_empty()).then_some(tags))
+            .send()
+            .await
+            .map_err(send_error)?;
+        Ok(SentEmail {
+            message_id: output.message_id,
+        })
+    }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        let account = self.client.get_account().send().await?;
+        if account.sending_enabled {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("SES sending is disabled for this account"))
+        }
+    }
+}
+
+/// SES tag names and values allow only ASCII letters, digits, `_`, `-`, `.`
+/// and `@`, up to 256 characters.
+fn ses_tag_value(raw: &str) -> String {
+    raw.chars()
+        .take(256)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '@') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn send_error<E, R>(e: SdkError<E, R>) -> EmailSendError
+where
+    E: ProvideErrorMetadata + std::error::Error + 'static,
+    R: std::fmt::Debug,
+{
+    let message = match e.as_service_error() {
+        Some(service) => service.message().unwrap_or("no message").to_string(),
+        None => DisplayErrorContext(&e).to_string(),
+    };
+    let code = e
+        .as_service_error()
+        .and_then(|s| s.code())
+        .map(str::to_string);
+    EmailSendError {
+        permanent: code
+            .as_deref()
+            .is_some_and(|code| PERMANENT_ERRORS.contains(&code)),
+        code,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ses_tag_value;
+
+    #[test]
+    fn tag_values_are_restricted_to_ses_charset() {
+        assert_eq!(ses_tag_value("verify_email"), "verify_email");
+        assert_eq!(ses_tag_value("a b/c"), "a_b_c");
+        assert_eq!(ses_tag_value(&"x".repeat(300)).len(), 256);
+    }
+}
@@ -0,0 +1,52 @@
+//! SMTP relay implementation of [`EmailTransport`].
+
+use async_trait::async_trait;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use tracing::info;
+
+use crate::init::app_config::EmailSection;
+use crate::init::config::EmailConfig;
+
+use super::{EmailSendError, EmailTransport, OutgoingEmail, SentEmail};
+
+pub struct SmtpTransport {
+    client: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpTransport {
+    pub fn from_config(config: &EmailSection) -> anyhow::Result<Self> {
+        let email_config = EmailConfig::from_config(config)
+            .map_err(|e| anyhow::anyhow!("Failed to load email config: {}", e))?;
+        let client = AsyncSmtpTransport::<Tokio1Executor>::relay(&email_config.get_url())?
+            .credentials(email_config.to_creds())
+            .build();
+        info!(smtp_relay = %email_config.get_url(), "Email transport: SMTP relay");
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for SmtpTransport {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    async fn send(&self, email: OutgoingEmail) -> Result<SentEmail, EmailSendError> {
+        match self.client.send(email.message).await {
+            Ok(_) => Ok(SentEmail::default()),
+            Err(e) => Err(EmailSendError {
+                permanent: e.is_permanent(),
+                code: e.status().map(|code| code.to_string()),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        if self.client.test_connection().await? {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("SMTP server did not accept NOOP"))
+        }
+    }
+}
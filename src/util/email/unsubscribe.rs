@@ -0,0 +1,133 @@
+//! Signed unsubscribe tokens, put in the links of optional emails so a
+//! recipient can opt out without logging in.
+//!
+//! A token is `<user id>.<topic>.<signature>`, the signature being the
+//! URL-safe base64 HMAC-SHA256 of `<user id>.<topic>`. Tokens do not expire,
+//! so the link in an old email keeps working; changing the key
+//! (`EMAIL_UNSUBSCRIBE_SECRET`) invalidates every link already sent.
+
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::DOMAIN_NAME;
+use crate::init::app_config::{EmailSection, ServerSection};
+
+/// What an unsubscribe token opts out of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTopic {
+    /// The daily notification digest (`notify_digest`).
+    NotificationDigest,
+}
+
+impl EmailTopic {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NotificationDigest => "notification_digest",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "notification_digest" => Some(Self::NotificationDigest),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct UnsubscribeKey {
+    key: Vec<u8>,
+}
+
+impl UnsubscribeKey {
+    /// `email.unsubscribe_secret`, or when unset a key derived from the API
+    /// key so every instance signs alike without another secret.
+    pub fn from_config(email: &EmailSection, server: &ServerSection) -> anyhow::Result<Self> {
+        if let Some(secret) = email
+            .unsubscribe_secret
+            .as_deref()
+            .filter(|s| !s.trim().is_empty())
+        {
+            return Ok(Self {
+                key: secret.as_bytes().to_vec(),
+            });
+        }
+        let api_key = server.api_key.ok_or_else(|| {
+            anyhow!("unsubscribe links need EMAIL_UNSUBSCRIBE_SECRET or X_API_KEY")
+        })?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(api_key.as_bytes())
+            .expect("HMAC takes keys of any length");
+        mac.update(b"email-unsubscribe");
+        Ok(Self {
+            key: mac.finalize().into_bytes().to_vec(),
+        })
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC takes keys of any length")
+    }
+
+    pub fn token(&self, user_id: Uuid, topic: EmailTopic) -> String {
+        let payload = format!("{}.{}", user_id.simple(), topic.as_str());
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+        format!("{payload}.{signature}")
+    }
+
+    /// The user and topic `token` was signed for, or `None` if it is
+    /// malformed or was not signed with this key.
+    pub fn verify(&self, token: &str) -> Option<(Uuid, EmailTopic)> {
+        let (payload, signature) = token.trim().rsplit_once('.')?;
+        let (user_id, topic) = payload.split_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature).ok()?;
+        Some((Uuid::parse_str(user_id).ok()?, EmailTopic::parse(topic)?))
+    }
+}
+
+/// The page that confirms an unsubscribe (shown in the email body).
+pub fn unsubscribe_page_link(token: &str) -> String {
+    format!("https://{DOMAIN_NAME}/unsubscribe?token={token}")
+}
+
+/// The one-click endpoint for the `List-Unsubscribe` header (RFC 8058).
+pub fn one_click_unsubscribe_link(token: &str) -> String {
+    format!("https://{DOMAIN_NAME}/api/email/unsubscribe?token={token}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_verify_only_with_their_key_and_payload() {
+        let key = UnsubscribeKey {
+            key: b"secret".to_vec(),
+        };
+        let user_id = Uuid::new_v4();
+        let token = key.token(user_id, EmailTopic::NotificationDigest);
+        assert_eq!(
+            key.verify(&token),
+            Some((user_id, EmailTopic::NotificationDigest))
+        );
+
+        let other = UnsubscribeKey {
+            key: b"other".to_vec(),
+        };
+        assert_eq!(other.verify(&token), None);
+
+        let forged = token.replacen(
+            &user_id.simple().to_string(),
+            &Uuid::nil().simple().to_string(),
+            1,
+        );
+        assert_eq!(key.verify(&forged), None);
+        assert_eq!(key.verify("garbage"), None);
+    }
+}
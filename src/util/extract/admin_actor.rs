@@ -0,0 +1,44 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use uuid::Uuid;
+
+use crate::errors::code_error::{CodeError, CodeErrorResp, code_err};
+use crate::util::extract::client_ip::extract_client_ip;
+
+/// Who is making an audited request: the user id `auth_middleware` inserted
+/// and the client IP, for `ServerState::record_admin_action`.
+#[derive(Clone, Copy, Debug)]
+pub struct AdminActor {
+    pub user_id: Uuid,
+    pub ip: Option<IpAddr>,
+}
+
+impl<S> FromRequestParts<S> for AdminActor
+where
+    S: Send + Sync,
+{
+    type Rejection = CodeErrorResp;
+
+    fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> impl std::future::Future<Output = Result<Self, Self::Rejection>> + Send {
+        let result = match parts.extensions.get::<Uuid>().copied() {
+            Some(user_id) => Ok(AdminActor {
+                user_id,
+                ip: parts
+                    .extensions
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .and_then(|ConnectInfo(addr)| extract_client_ip(&parts.headers, *addr)),
+            }),
+            None => Err(code_err(
+                CodeError::UNAUTHORIZED_ACCESS,
+                "Missing user id in request",
+            )),
+        };
+
+        std::future::ready(result)
+    }
+}
@@ -2,14 +2,21 @@ use std::{net::IpAddr, net::SocketAddr};
 
 use axum::http::HeaderMap;
 
-/// Number of reverse proxies we control, read once from env (e.g. "1" for a single
-/// nginx in front). When unset or 0 we trust NO forwarded headers and use the socket
-/// peer, which is the safe default for ban enforcement and logging.
+use crate::init::app_config;
+
+/// Number of reverse proxies we control, from `server.trusted_proxy_hops` (e.g. 1
+/// for a single nginx in front). When 0 we trust NO forwarded headers and use the
+/// socket peer, which is the safe default for ban enforcement and logging.
+///
+/// On a unix socket (`server.socket_path`) the peer is always the local proxy,
+/// so its hop is trusted on top of the configured count.
 fn trusted_proxy_hops() -> usize {
-    std::env::var("TRUSTED_PROXY_HOPS")
-        .ok()
-        .and_then(|v| v.trim().parse::<usize>().ok())
-        .unwrap_or(0)
+    let server = &app_config::config().server;
+    if server.socket_path.is_some() {
+        server.trusted_proxy_hops + 1
+    } else {
+        server.trusted_proxy_hops
+    }
 }
 
 /// Resolve the real client IP, establishing a trusted-proxy boundary instead of
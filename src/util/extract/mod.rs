@@ -1,4 +1,8 @@
+pub mod admin_actor;
 pub mod client_ip;
 pub mod host;
+pub mod validated;
 
+pub use admin_actor::AdminActor;
 pub use host::Host;
+pub use validated::{ValidatedJson, ValidatedQuery};
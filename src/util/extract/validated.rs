@@ -0,0 +1,132 @@
+//! `Json`/`Query` extractors that also run the DTO's `validator` rules.
+//!
+//! A body or query string that does not deserialize is `INVALID_REQUEST`
+//! (400). One that deserializes but breaks a `#[validate(...)]` rule is
+//! `VALIDATION_FAILED` (422), with every failed rule listed in
+//! `field_errors`, so clients can mark each field instead of parsing one
+//! message.
+
+use axum::{
+    Json,
+    extract::{FromRequest, FromRequestParts, Query, Request},
+    http::request::Parts,
+};
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::errors::code_error::{CodeError, CodeErrorResp, FieldError, code_err};
+
+/// `Json<T>` that only yields values passing `T::validate`.
+pub struct ValidatedJson<T>(pub T);
+
+/// `Query<T>` that only yields values passing `T::validate`.
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = CodeErrorResp;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(|rejection| code_err(CodeError::INVALID_REQUEST, rejection.body_text()))?;
+        validate(&value)?;
+        Ok(Self(value))
+    }
+}
+
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = CodeErrorResp;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| code_err(CodeError::INVALID_REQUEST, rejection.body_text()))?;
+        validate(&value)?;
+        Ok(Self(value))
+    }
+}
+
+fn validate(value: &impl Validate) -> Result<(), CodeErrorResp> {
+    value.validate().map_err(|errors| {
+        let field_errors = field_errors(&errors);
+        let summary = field_errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field.as_deref().unwrap_or("request"), e.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        code_err(CodeError::VALIDATION_FAILED, summary).with_field_errors(field_errors)
+    })
+}
+
+/// Flattens `errors` into one entry per failed rule, sorted by field.
+/// Struct-level (`schema`) rules are reported without a field.
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut fields: Vec<_> = errors.field_errors().into_iter().collect();
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    fields
+        .into_iter()
+        .flat_map(|(field, errors)| {
+            let field = (field != "__all__").then(|| field.to_string());
+            errors.iter().map(move |error| FieldError {
+                field: field.clone(),
+                code: error.code.to_string(),
+                message: message(error),
+            })
+        })
+        .collect()
+}
+
+/// The rule's own message, else one built from its parameters.
+fn message(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    let param = |name: &str| error.params.get(name).map(|v| v.to_string());
+    match (error.code.as_ref(), param("min"), param("max")) {
+        ("length", Some(min), Some(max)) => format!("must be {min} to {max} characters"),
+        ("length", Some(min), None) => format!("must be at least {min} characters"),
+        ("length", None, Some(max)) => format!("must be at most {max} characters"),
+        ("range", Some(min), Some(max)) => format!("must be between {min} and {max}"),
+        ("range", Some(min), None) => format!("must be at least {min}"),
+        ("range", None, Some(max)) => format!("must be at most {max}"),
+        ("email", _, _) => "must be a valid email address".to_string(),
+        _ => "is invalid".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Validate)]
+    struct Sample {
+        #[validate(length(min = 1, max = 3))]
+        name: String,
+        #[validate(range(min = 1))]
+        page: usize,
+    }
+
+    #[test]
+    fn reports_each_failed_field() {
+        let errors = Sample {
+            name: "long".to_string(),
+            page: 0,
+        }
+        .validate()
+        .unwrap_err();
+        let fields = field_errors(&errors);
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].field.as_deref(), Some("name"));
+        assert_eq!(fields[0].message, "must be 1 to 3 characters");
+        assert_eq!(fields[1].field.as_deref(), Some("page"));
+        assert_eq!(fields[1].code, "range");
+    }
+}
@@ -0,0 +1,168 @@
+//! Conditional (`If-None-Match`) and single-range (`Range`/`If-Range`)
+//! request handling for handlers that serve whole in-memory bodies.
+//!
+//! `If-None-Match` compares weakly and `If-Range` strongly, as RFC 9110
+//! requires. Only one `bytes=` range is honored; multi-range requests get the
+//! full body, which the RFC allows.
+
+use std::ops::Range;
+
+use axum::http::{HeaderMap, header};
+
+/// Whether `If-None-Match` matches `etag` (or is `*`), i.e. the client's copy
+/// is current and a 304 should be sent.
+pub fn if_none_match_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// Outcome of a `Range` header against a body of known length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// No (usable) range: send the whole body with 200.
+    Full,
+    /// Send `range` with 206.
+    Partial(Range<usize>),
+    /// Send 416 with `Content-Range: bytes */len`.
+    Unsatisfiable,
+}
+
+/// Resolve `Range` for a body of `len` bytes whose current validator is
+/// `etag`. A stale `If-Range` downgrades to [`RangeRequest::Full`].
+pub fn resolve_range(headers: &HeaderMap, len: usize, etag: &str) -> RangeRequest {
+    let Some(range) = headers.get(header::RANGE).and_then(|v| v.to_str().ok()) else {
+        return RangeRequest::Full;
+    };
+    if let Some(if_range) = headers.get(header::IF_RANGE).and_then(|v| v.to_str().ok())
+        && if_range.trim() != etag
+    {
+        return RangeRequest::Full;
+    }
+    parse_byte_range(range, len)
+}
+
+/// Parse a single `bytes=` range (`a-b`, `a-`, `-n`). Anything else,
+/// including multiple ranges, yields [`RangeRequest::Full`].
+pub fn parse_byte_range(value: &str, len: usize) -> RangeRequest {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RangeRequest::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // Suffix: the last `n` bytes.
+        let Ok(suffix) = end.parse::<usize>() else {
+            return RangeRequest::Full;
+        };
+        if suffix == 0 || len == 0 {
+            return RangeRequest::Unsatisfiable;
+        }
+        len.saturating_sub(suffix)..len
+    } else {
+        let Ok(first) = start.parse::<usize>() else {
+            return RangeRequest::Full;
+        };
+        let last = if end.is_empty() {
+            len.saturating_sub(1)
+        } else {
+            match end.parse::<usize>() {
+                Ok(last) if last >= first => last.min(len.saturating_sub(1)),
+                _ => return RangeRequest::Full,
+            }
+        };
+        if first >= len {
+            return RangeRequest::Unsatisfiable;
+        }
+        first..last + 1
+    };
+    RangeRequest::Partial(range)
+}
+
+/// `Content-Range` value for a partial response.
+pub fn content_range(range: &Range<usize>, len: usize) -> String {
+    format!("bytes {}-{}/{len}", range.start, range.end - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn parses_single_ranges() {
+        assert_eq!(
+            parse_byte_range("bytes=0-99", 1000),
+            RangeRequest::Partial(0..100)
+        );
+        assert_eq!(
+            parse_byte_range("bytes=900-", 1000),
+            RangeRequest::Partial(900..1000)
+        );
+        assert_eq!(
+            parse_byte_range("bytes=-100", 1000),
+            RangeRequest::Partial(900..1000)
+        );
+        assert_eq!(
+            parse_byte_range("bytes=-5000", 1000),
+            RangeRequest::Partial(0..1000)
+        );
+        assert_eq!(
+            parse_byte_range("bytes=990-2000", 1000),
+            RangeRequest::Partial(990..1000)
+        );
+    }
+
+    #[test]
+    fn rejects_or_ignores_bad_ranges() {
+        assert_eq!(
+            parse_byte_range("bytes=1000-", 1000),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(
+            parse_byte_range("bytes=-0", 1000),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(parse_byte_range("bytes=0-1,5-9", 1000), RangeRequest::Full);
+        assert_eq!(parse_byte_range("bytes=9-1", 1000), RangeRequest::Full);
+        assert_eq!(parse_byte_range("items=0-1", 1000), RangeRequest::Full);
+        assert_eq!(parse_byte_range("bytes=abc", 1000), RangeRequest::Full);
+    }
+
+    #[test]
+    fn stale_if_range_serves_full_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, HeaderValue::from_static("bytes=0-9"));
+        headers.insert(header::IF_RANGE, HeaderValue::from_static("\"old\""));
+        assert_eq!(resolve_range(&headers, 100, "\"new\""), RangeRequest::Full);
+        headers.insert(header::IF_RANGE, HeaderValue::from_static("\"new\""));
+        assert_eq!(
+            resolve_range(&headers, 100, "\"new\""),
+            RangeRequest::Partial(0..10)
+        );
+    }
+
+    #[test]
+    fn matches_if_none_match_lists() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_static("\"a\", W/\"b\""),
+        );
+        assert!(if_none_match_matches(&headers, "\"b\""));
+        assert!(!if_none_match_matches(&headers, "\"c\""));
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(if_none_match_matches(&headers, "\"c\""));
+    }
+}
@@ -0,0 +1,410 @@
+//! Per-route latency histograms and status counters, fed by `log_middleware`
+//! and read by `GET /api/admin/endpoints/stats`.
+//!
+//! Each route keeps the last hour as one-minute slots so a window can be
+//! summed from the slots it covers; percentiles are estimated from fixed
+//! latency buckets. Counts are per instance and reset on restart.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use axum::http::{Method, StatusCode};
+use scc::hash_map::Entry;
+use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+const SLOT_SECS: i64 = 60;
+/// One hour of minute slots, the longest window.
+const SLOTS: usize = 60;
+
+/// Upper bounds of the latency buckets, in milliseconds. Slower requests go
+/// into a final overflow bucket.
+const LATENCY_BOUNDS_MS: [f64; 24] = [
+    1.0, 2.0, 3.0, 5.0, 7.5, 10.0, 15.0, 20.0, 30.0, 50.0, 75.0, 100.0, 150.0, 200.0, 300.0, 500.0,
+    750.0, 1000.0, 1500.0, 2000.0, 3000.0, 5000.0, 10000.0, 30000.0,
+];
+
+/// Route label for requests that matched no route.
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// How far back a summary reaches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ToSchema)]
+pub enum EndpointStatsWindow {
+    #[serde(rename = "1m")]
+    Minute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[default]
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "1h")]
+    Hour,
+}
+
+impl EndpointStatsWindow {
+    pub fn minutes(self) -> i64 {
+        match self {
+            Self::Minute => 1,
+            Self::FiveMinutes => 5,
+            Self::FifteenMinutes => 15,
+            Self::Hour => 60,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Minute => "1m",
+            Self::FiveMinutes => "5m",
+            Self::FifteenMinutes => "15m",
+            Self::Hour => "1h",
+        }
+    }
+
+    /// Inverse of [`Self::as_str`].
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "1m" => Some(Self::Minute),
+            "5m" => Some(Self::FiveMinutes),
+            "15m" => Some(Self::FifteenMinutes),
+            "1h" => Some(Self::Hour),
+            _ => None,
+        }
+    }
+}
+
+/// Responses by status class.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct StatusClassCounts {
+    #[serde(rename = "1xx")]
+    pub informational: u64,
+    #[serde(rename = "2xx")]
+    pub success: u64,
+    #[serde(rename = "3xx")]
+    pub redirection: u64,
+    #[serde(rename = "4xx")]
+    pub client_error: u64,
+    #[serde(rename = "5xx")]
+    pub server_error: u64,
+}
+
+impl StatusClassCounts {
+    fn add(&mut self, status: StatusCode, count: u64) {
+        let class = match status.as_u16() / 100 {
+            1 => &mut self.informational,
+            2 => &mut self.success,
+            3 => &mut self.redirection,
+            4 => &mut self.client_error,
+            _ => &mut self.server_error,
+        };
+        *class += count;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.informational += other.informational;
+        self.success += other.success;
+        self.redirection += other.redirection;
+        self.client_error += other.client_error;
+        self.server_error += other.server_error;
+    }
+}
+
+/// One route over a window.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EndpointSummary {
+    pub method: String,
+    /// Route template, e.g. `/api/blog/{post_id}`; `/api/v1` paths are
+    /// counted under their `/api` alias.
+    pub route: String,
+    pub requests: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    pub status_classes: StatusClassCounts,
+    /// Share of requests answered 4xx, 0 to 1.
+    pub client_error_rate: f64,
+    /// Share of requests answered 5xx, 0 to 1.
+    pub server_error_rate: f64,
+    /// Responses per status code since startup, not just the window.
+    pub status_codes: BTreeMap<u16, u64>,
+}
+
+#[derive(Clone, Copy)]
+struct Slot {
+    /// Unix minute the slot holds; a stale one is reset on next use.
+    minute: i64,
+    latency: [u32; LATENCY_BOUNDS_MS.len() + 1],
+    sum_ms: f64,
+    max_ms: f64,
+    statuses: StatusClassCounts,
+}
+
+impl Slot {
+    const EMPTY: Slot = Slot {
+        minute: i64::MIN,
+        latency: [0; LATENCY_BOUNDS_MS.len() + 1],
+        sum_ms: 0.0,
+        max_ms: 0.0,
+        statuses: StatusClassCounts {
+            informational: 0,
+            success: 0,
+            redirection: 0,
+            client_error: 0,
+            server_error: 0,
+        },
+    };
+}
+
+struct RouteStats {
+    slots: Box<[Slot; SLOTS]>,
+    status_codes: BTreeMap<u16, u64>,
+}
+
+impl RouteStats {
+    fn new() -> Self {
+        Self {
+            slots: Box::new([Slot::EMPTY; SLOTS]),
+            status_codes: BTreeMap::new(),
+        }
+    }
+
+    fn record(&mut self, minute: i64, status: StatusCode, latency_ms: f64) {
+        *self.status_codes.entry(status.as_u16()).or_default() += 1;
+        let slot = &mut self.slots[minute.rem_euclid(SLOTS as i64) as usize];
+        if slot.minute > minute {
+            // Recorded late, after the slot moved on to a newer minute.
+            return;
+        }
+        if slot.minute != minute {
+            *slot = Slot {
+                minute,
+                ..Slot::EMPTY
+            };
+        }
+        let bucket = LATENCY_BOUNDS_MS
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(LATENCY_BOUNDS_MS.len());
+        slot.latency[bucket] = slot.latency[bucket].saturating_add(1);
+        slot.sum_ms += latency_ms;
+        slot.max_ms = slot.max_ms.max(latency_ms);
+        slot.statuses.add(status, 1);
+    }
+
+    /// `None` when nothing was recorded in the window ending at `minute`.
+    fn summarize(
+        &self,
+        method: &str,
+        route: &str,
+        minute: i64,
+        minutes: i64,
+    ) -> Option<EndpointSummary> {
+        let mut latency = [0u64; LATENCY_BOUNDS_MS.len() + 1];
+        let mut sum_ms = 0.0;
+        let mut max_ms: f64 = 0.0;
+        let mut statuses = StatusClassCounts::default();
+        for slot in self
+            .slots
+            .iter()
+            .filter(|s| s.minute > minute - minutes && s.minute <= minute)
+        {
+            for (total, count) in latency.iter_mut().zip(slot.latency) {
+                *total += count as u64;
+            }
+            sum_ms += slot.sum_ms;
+            max_ms = max_ms.max(slot.max_ms);
+            statuses.merge(&slot.statuses);
+        }
+        let requests: u64 = latency.iter().sum();
+        if requests == 0 {
+            return None;
+        }
+        let rate = |count: u64| count as f64 / requests as f64;
+        Some(EndpointSummary {
+            method: method.to_string(),
+            route: route.to_string(),
+            requests,
+            p50_ms: percentile(&latency, 0.50, max_ms),
+            p95_ms: percentile(&latency, 0.95, max_ms),
+            p99_ms: percentile(&latency, 0.99, max_ms),
+            mean_ms: sum_ms / requests as f64,
+            max_ms,
+            status_classes: statuses,
+            client_error_rate: rate(statuses.client_error),
+            server_error_rate: rate(statuses.server_error),
+            status_codes: self.status_codes.clone(),
+        })
+    }
+}
+
+/// The `q` quantile of `buckets`, interpolated linearly within the bucket it
+/// falls in and capped at the largest value seen.
+fn percentile(buckets: &[u64], q: f64, max_ms: f64) -> f64 {
+    let total: u64 = buckets.iter().sum();
+    let rank = q * total as f64;
+    let mut seen = 0u64;
+    for (i, count) in buckets.iter().copied().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        if (seen + count) as f64 >= rank {
+            let lower = if i == 0 {
+                0.0
+            } else {
+                LATENCY_BOUNDS_MS[i - 1]
+            };
+            let upper = LATENCY_BOUNDS_MS
+                .get(i)
+                .copied()
+                .unwrap_or(max_ms)
+                .min(max_ms);
+            let within = ((rank - seen as f64) / count as f64).clamp(0.0, 1.0);
+            return lower + (upper - lower) * within;
+        }
+        seen += count;
+    }
+    max_ms
+}
+
+/// Latency and status counters per `(method, route)`.
+#[derive(Default)]
+pub struct EndpointStats {
+    routes: scc::HashMap<(String, String), RouteStats>,
+}
+
+impl EndpointStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one response. `route` is the matched route template, or
+    /// [`UNMATCHED_ROUTE`].
+    pub async fn record(
+        &self,
+        method: &Method,
+        route: &str,
+        status: StatusCode,
+        latency: Duration,
+    ) {
+        self.record_at(
+            method,
+            route,
+            status,
+            latency,
+            chrono::Utc::now().timestamp(),
+        )
+        .await;
+    }
+
+    async fn record_at(
+        &self,
+        method: &Method,
+        route: &str,
+        status: StatusCode,
+        latency: Duration,
+        now_secs: i64,
+    ) {
+        let minute = now_secs.div_euclid(SLOT_SECS);
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        let key = (method_label(method).to_string(), route.to_string());
+        match self.routes.entry_async(key).await {
+            Entry::Occupied(mut occ) => occ.get_mut().record(minute, status, latency_ms),
+            Entry::Vacant(vac) => {
+                let mut stats = RouteStats::new();
+                stats.record(minute, status, latency_ms);
+                vac.insert_entry(stats);
+            }
+        }
+    }
+
+    /// Routes with traffic in `window`, busiest first.
+    pub async fn summary(&self, window: EndpointStatsWindow) -> Vec<EndpointSummary> {
+        self.summary_at(window, chrono::Utc::now().timestamp())
+            .await
+    }
+
+    async fn summary_at(&self, window: EndpointStatsWindow, now_secs: i64) -> Vec<EndpointSummary> {
+        let minute = now_secs.div_euclid(SLOT_SECS);
+        let mut summaries = Vec::new();
+        self.routes
+            .iter_async(|(method, route), stats| {
+                summaries.extend(stats.summarize(method, route, minute, window.minutes()));
+                true
+            })
+            .await;
+        summaries.sort_unstable_by(|a, b| {
+            b.requests
+                .cmp(&a.requests)
+                .then_with(|| a.route.cmp(&b.route))
+                .then_with(|| a.method.cmp(&b.method))
+        });
+        summaries
+    }
+}
+
+/// Standard methods by name; anything else shares one label so odd methods
+/// cannot grow the map.
+pub(crate) fn method_label(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::PATCH => "PATCH",
+        Method::DELETE => "DELETE",
+        Method::HEAD => "HEAD",
+        Method::OPTIONS => "OPTIONS",
+        _ => "OTHER",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn summarizes_window_percentiles_and_error_rates() {
+        let stats = EndpointStats::new();
+        let now = 1_800_000_000;
+        let route = "/api/blog/{post_id}";
+        // Two hours old and in the slot the rest reuse: outside every window,
+        // but still in status_codes.
+        stats
+            .record_at(
+                &Method::GET,
+                route,
+                StatusCode::NOT_FOUND,
+                Duration::from_millis(5),
+                now - 7200,
+            )
+            .await;
+        for ms in 1..=100u64 {
+            let status = if ms % 10 == 0 {
+                StatusCode::INTERNAL_SERVER_ERROR
+            } else {
+                StatusCode::OK
+            };
+            stats
+                .record_at(&Method::GET, route, status, Duration::from_millis(ms), now)
+                .await;
+        }
+
+        let summary = stats.summary_at(EndpointStatsWindow::Minute, now).await;
+        assert_eq!(summary.len(), 1);
+        let get = &summary[0];
+        assert_eq!(get.requests, 100);
+        assert_eq!(get.status_classes.server_error, 10);
+        assert!((get.server_error_rate - 0.1).abs() < 1e-9);
+        assert_eq!(get.client_error_rate, 0.0);
+        assert_eq!(get.p50_ms, 50.0);
+        assert!((get.p95_ms - 95.0).abs() < 1e-9, "p95 {}", get.p95_ms);
+        assert!(get.p99_ms <= get.max_ms);
+        assert_eq!(get.max_ms, 100.0);
+        assert_eq!(get.status_codes.get(&404), Some(&1));
+
+        let later = stats
+            .summary_at(EndpointStatsWindow::Hour, now + 3600)
+            .await;
+        assert!(later.is_empty());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod conditional;
+pub mod endpoint_stats;
+pub mod rate_limit;
+pub mod response_counters;
+pub mod timeout;
@@ -0,0 +1,244 @@
+//! Token-bucket rate limiting.
+//!
+//! Every request is charged one token from a bucket per (policy, subject).
+//! The subject is the signed-in user when there is one and the client IP
+//! otherwise, so users behind one NAT do not share a budget. A bucket holds up
+//! to `burst` tokens and refills continuously at `burst / period`. Buckets
+//! live in the shared [`Cache`], so with Redis every instance draws from the
+//! same budget; a request is let through when the cache cannot be reached.
+//!
+//! Policies (defaults; `[rate_limit]` `<policy> = "BURST/PERIOD_SECS"` or
+//! `RATE_LIMIT_<POLICY>` overrides, `RATE_LIMIT=off` disables limiting):
+//!
+//! - `global`: every request including static assets, per IP; 1024 burst,
+//!   one token per 63 ms.
+//! - `read`: public API routes; 300 burst, 10/s.
+//! - `write`: signed-in API routes; 60 burst, 1/s.
+//! - `auth`: login, signup, password reset and email checks, per IP; 10 per
+//!   minute.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::init::app_config::RateLimitSection;
+use crate::init::cache::Cache;
+use crate::util::metrics::MetricsWriter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitPolicy {
+    Global,
+    Read,
+    Write,
+    Auth,
+}
+
+impl RateLimitPolicy {
+    pub const ALL: [RateLimitPolicy; 4] = [Self::Global, Self::Read, Self::Write, Self::Auth];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Auth => "auth",
+        }
+    }
+
+    fn default_limit(self) -> Limit {
+        match self {
+            Self::Global => Limit {
+                burst: 1024.0,
+                per_second: 1000.0 / 63.0,
+            },
+            Self::Read => Limit {
+                burst: 300.0,
+                per_second: 10.0,
+            },
+            Self::Write => Limit {
+                burst: 60.0,
+                per_second: 1.0,
+            },
+            Self::Auth => Limit {
+                burst: 10.0,
+                per_second: 10.0 / 60.0,
+            },
+        }
+    }
+
+    /// Whether a signed-in user is limited by user id rather than by IP.
+    /// Global and auth limits guard the server and credential endpoints, so
+    /// they always count per IP.
+    fn keys_by_user(self) -> bool {
+        matches!(self, Self::Read | Self::Write)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Limit {
+    burst: f64,
+    per_second: f64,
+}
+
+impl Limit {
+    /// `BURST/PERIOD_SECS`, e.g. `10/60`.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let (burst, period) = value.trim().split_once('/')?;
+        let burst = burst.trim().parse::<u32>().ok().filter(|b| *b > 0)?;
+        let period = period.trim().parse::<f64>().ok().filter(|p| *p > 0.0)?;
+        Some(Self {
+            burst: f64::from(burst),
+            per_second: f64::from(burst) / period,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitSubject {
+    Ip(IpAddr),
+    User(Uuid),
+}
+
+impl std::fmt::Display for RateLimitSubject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ip(ip) => write!(f, "ip:{ip}"),
+            Self::User(user_id) => write!(f, "user:{user_id}"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct PolicyCounters {
+    allowed: AtomicU64,
+    limited: AtomicU64,
+    /// Cache failures, each of which let the request through.
+    errors: AtomicU64,
+}
+
+pub struct RateLimiter {
+    enabled: bool,
+    limits: [Limit; 4],
+    cache: Arc<dyn Cache>,
+    counters: [PolicyCounters; 4],
+}
+
+impl RateLimiter {
+    /// Limits were checked by `AppConfig::validate`.
+    pub fn from_config(config: &RateLimitSection, cache: Arc<dyn Cache>) -> Self {
+        let enabled = config.enabled;
+        let limits = RateLimitPolicy::ALL.map(|policy| {
+            let configured = match policy {
+                RateLimitPolicy::Global => &config.global,
+                RateLimitPolicy::Read => &config.read,
+                RateLimitPolicy::Write => &config.write,
+                RateLimitPolicy::Auth => &config.auth,
+            };
+            configured
+                .as_deref()
+                .and_then(Limit::parse)
+                .unwrap_or_else(|| policy.default_limit())
+        });
+        if !enabled {
+            info!("Rate limiting disabled (RATE_LIMIT=off)");
+        }
+        Self {
+            enabled,
+            limits,
+            cache,
+            counters: Default::default(),
+        }
+    }
+
+    /// The subject `policy` counts a request against.
+    pub fn subject(
+        &self,
+        policy: RateLimitPolicy,
+        ip: IpAddr,
+        user_id: Option<Uuid>,
+    ) -> RateLimitSubject {
+        match user_id {
+            Some(user_id) if policy.keys_by_user() => RateLimitSubject::User(user_id),
+            _ => RateLimitSubject::Ip(ip),
+        }
+    }
+
+    /// Charge one request. `Err` holds the wait before the next one is
+    /// allowed, for `Retry-After`.
+    pub async fn check(
+        &self,
+        policy: RateLimitPolicy,
+        subject: RateLimitSubject,
+    ) -> Result<(), Duration> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let limit = self.limits[policy as usize];
+        let key = format!("ratelimit:{}:{subject}", policy.as_str());
+        let counters = &self.counters[policy as usize];
+        match self
+            .cache
+            .take_token(&key, limit.burst, limit.per_second)
+            .await
+        {
+            Ok(None) => {
+                counters.allowed.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Ok(Some(wait)) => {
+                counters.limited.fetch_add(1, Ordering::Relaxed);
+                Err(wait)
+            }
+            Err(e) => {
+                counters.errors.fetch_add(1, Ordering::Relaxed);
+                debug!(error = %e, policy = policy.as_str(), "Rate limit check failed; allowing request");
+                Ok(())
+            }
+        }
+    }
+
+    pub fn write_metrics(&self, w: &mut MetricsWriter) {
+        w.header(
+            "rate_limit_requests_total",
+            "counter",
+            "Requests checked by the rate limiter, by policy and result.",
+        );
+        for policy in RateLimitPolicy::ALL {
+            let counters = &self.counters[policy as usize];
+            for (result, count) in [
+                ("allowed", &counters.allowed),
+                ("limited", &counters.limited),
+                ("error", &counters.errors),
+            ] {
+                w.sample(
+                    "rate_limit_requests_total",
+                    &[("policy", policy.as_str()), ("result", result)],
+                    count.load(Ordering::Relaxed) as f64,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_burst_over_period() {
+        assert_eq!(
+            Limit::parse("10/60"),
+            Some(Limit {
+                burst: 10.0,
+                per_second: 10.0 / 60.0
+            })
+        );
+        assert_eq!(Limit::parse("0/60"), None);
+        assert_eq!(Limit::parse("10"), None);
+        assert_eq!(Limit::parse("10/0"), None);
+    }
+}
@@ -0,0 +1,142 @@
+//! Responses since startup, in total, by status class and by method, fed by
+//! `log_middleware` and read by `GET /api/healthcheck/state` and
+//! `/api/metrics`. Per instance; reset on restart.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::http::{Method, StatusCode};
+
+use super::endpoint_stats::{StatusClassCounts, method_label};
+use crate::util::metrics::MetricsWriter;
+
+/// Every label `method_label` gives, in the order they are counted.
+const METHODS: [&str; 8] = [
+    "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "OTHER",
+];
+
+#[derive(Default)]
+pub struct ResponseCounters {
+    total: AtomicU64,
+    /// 1xx to 5xx; anything else counts as 5xx.
+    classes: [AtomicU64; 5],
+    methods: [AtomicU64; METHODS.len()],
+}
+
+impl ResponseCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, method: &Method, status: StatusCode) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+        let class = (status.as_u16() / 100).clamp(1, 5) as usize - 1;
+        self.classes[class].fetch_add(1, Ordering::Relaxed);
+        let label = method_label(method);
+        if let Some(i) = METHODS.iter().position(|m| *m == label) {
+            self.methods[i].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    pub fn by_status_class(&self) -> StatusClassCounts {
+        let [
+            informational,
+            success,
+            redirection,
+            client_error,
+            server_error,
+        ] = self.classes.each_ref().map(|c| c.load(Ordering::Relaxed));
+        StatusClassCounts {
+            informational,
+            success,
+            redirection,
+            client_error,
+            server_error,
+        }
+    }
+
+    /// Methods seen at least once.
+    pub fn by_method(&self) -> BTreeMap<String, u64> {
+        METHODS
+            .iter()
+            .zip(&self.methods)
+            .map(|(method, count)| (method.to_string(), count.load(Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    pub fn write_metrics(&self, w: &mut MetricsWriter) {
+        w.header(
+            "http_responses_total",
+            "counter",
+            "Responses handled since startup.",
+        );
+        w.sample("http_responses_total", &[], self.total() as f64);
+
+        let classes = self.by_status_class();
+        w.header(
+            "http_responses_status_class_total",
+            "counter",
+            "Responses handled since startup, by status class.",
+        );
+        for (class, count) in [
+            ("1xx", classes.informational),
+            ("2xx", classes.success),
+            ("3xx", classes.redirection),
+            ("4xx", classes.client_error),
+            ("5xx", classes.server_error),
+        ] {
+            w.sample(
+                "http_responses_status_class_total",
+                &[("class", class)],
+                count as f64,
+            );
+        }
+
+        w.header(
+            "http_responses_method_total",
+            "counter",
+            "Responses handled since startup, by request method.",
+        );
+        for (method, count) in METHODS.iter().zip(&self.methods) {
+            w.sample(
+                "http_responses_method_total",
+                &[("method", method)],
+                count.load(Ordering::Relaxed) as f64,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_by_class_and_method() {
+        let counters = ResponseCounters::new();
+        counters.record(&Method::GET, StatusCode::OK);
+        counters.record(&Method::GET, StatusCode::NOT_FOUND);
+        counters.record(&Method::POST, StatusCode::INTERNAL_SERVER_ERROR);
+        counters.record(&Method::from_bytes(b"PROPFIND").unwrap(), StatusCode::OK);
+
+        assert_eq!(counters.total(), 4);
+        let classes = counters.by_status_class();
+        assert_eq!(
+            (classes.success, classes.client_error, classes.server_error),
+            (2, 1, 1)
+        );
+        assert_eq!(
+            counters.by_method().into_iter().collect::<Vec<_>>(),
+            [
+                ("GET".to_string(), 2),
+                ("OTHER".to_string(), 1),
+                ("POST".to_string(), 1)
+            ]
+        );
+    }
+}
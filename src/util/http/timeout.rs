@@ -0,0 +1,68 @@
+//! Request time budgets for `timeout_middleware` and their expiry counters.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::init::app_config::ServerSection;
+use crate::util::metrics::MetricsWriter;
+
+/// Which budget a route runs under.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeoutBudget {
+    /// JSON API calls: `REQUEST_TIMEOUT_SECS`, default 5.
+    Api = 0,
+    /// Multipart uploads that process the body inline:
+    /// `UPLOAD_TIMEOUT_SECS`, default 120.
+    Upload = 1,
+}
+
+impl TimeoutBudget {
+    pub const ALL: [TimeoutBudget; 2] = [TimeoutBudget::Api, TimeoutBudget::Upload];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimeoutBudget::Api => "api",
+            TimeoutBudget::Upload => "upload",
+        }
+    }
+
+    /// `None` when the budget is configured as 0 (no timeout).
+    pub fn duration(self, server: &ServerSection) -> Option<Duration> {
+        let secs = match self {
+            TimeoutBudget::Api => server.request_timeout_secs,
+            TimeoutBudget::Upload => server.upload_timeout_secs,
+        };
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+}
+
+/// Requests cut off per budget since startup.
+#[derive(Default)]
+pub struct RequestTimeouts {
+    expired: [AtomicU64; 2],
+}
+
+impl RequestTimeouts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, budget: TimeoutBudget) {
+        self.expired[budget as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn write_metrics(&self, w: &mut MetricsWriter) {
+        w.header(
+            "http_request_timeouts_total",
+            "counter",
+            "Requests answered with REQUEST_TIMEOUT, by budget.",
+        );
+        for budget in TimeoutBudget::ALL {
+            w.sample(
+                "http_request_timeouts_total",
+                &[("budget", budget.as_str())],
+                self.expired[budget as usize].load(Ordering::Relaxed) as f64,
+            );
+        }
+    }
+}
@@ -1,8 +1,9 @@
 //! Background pipeline that drives a batch-upload session to completion.
 //!
 //! Files are staged to disk by the batch-upload handler; this module reads each
-//! one back under a bounded semaphore permit, encodes the main image + thumbnail
-//! (reusing [`process_uploaded_image`]), uploads both to S3 with the same
+//! one back under a bounded semaphore permit, encodes the main image + responsive
+//! renditions (reusing [`process_photograph_derivatives`], optionally
+//! watermarked), uploads them to object storage with the same
 //! orphan-cleanup contract as the single-file `upload_photograph` handler,
 //! inserts the row, and records per-item status on the shared [`BatchSession`].
 //!
@@ -14,7 +15,6 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use aws_sdk_s3::primitives::ByteStream;
 use chrono::Utc;
 use diesel_async::RunQueryDsl;
 use tokio::io::AsyncWriteExt;
@@ -25,19 +25,21 @@ use uuid::Uuid;
 
 use crate::domain::photography::batch::session::BatchSession;
 use crate::domain::photography::batch::status::ProcessingStatus;
+use crate::domain::photography::duplicates::phash_to_db;
 use crate::domain::photography::photographs::{
-    Photograph, PhotographContext, PhotographInsertable,
+    Photograph, PhotographContext, PhotographExif, PhotographInsertable, PhotographProcessingStatus,
 };
 use crate::init::state::ServerState;
 use crate::schema::photographs;
-use crate::util::image::exif_utils::extract_exif_shot_at;
+use crate::util::image::exif_utils::{extract_exif_metadata, extract_exif_shot_at};
 use crate::util::image::map_image_format_to_db_enum::map_image_format_to_str;
+use crate::util::image::photograph_ingest::{
+    check_near_duplicate, private_original_key, upload_original, upload_renditions,
+};
 use crate::util::image::process_uploaded_images::{
-    CyhdevImageType, IMAGE_ENCODING_FORMAT, process_uploaded_image,
+    IMAGE_ENCODING_FORMAT, PhotographDerivatives, process_photograph_derivatives,
 };
 
-use crate::util::s3::AWS_S3_BUCKET_NAME;
-
 /// Root directory under the system temp dir for all batch staging.
 pub fn batch_root_dir() -> PathBuf {
     std::env::temp_dir().join("cyhdev-batch")
@@ -76,22 +78,17 @@ pub struct BatchPipelineItem {
 /// Spawn the background supervisor for a registered batch session.
 ///
 /// Fire-and-forget: status is observed via the in-memory [`BatchSession`].
+/// `watermark` must already be resolved against the server configuration.
 pub fn spawn_batch(
     state: Arc<ServerState>,
     batch: Arc<BatchSession>,
     items: Vec<BatchPipelineItem>,
     user_id: Uuid,
     context: PhotographContext,
+    watermark: bool,
 ) {
     tokio::spawn(async move {
         let batch_id = batch.batch_id;
-        let s3_client = aws_sdk_s3::Client::new(&state.aws_profile_picture_config);
-        let region = state
-            .aws_profile_picture_config
-            .region()
-            .map(|r| r.to_string())
-            .unwrap_or_else(|| "us-west-1".to_string());
-
         let permits = num_cpus::get().max(1);
         let semaphore = Arc::new(Semaphore::new(permits));
         let mut join_set: JoinSet<()> = JoinSet::new();
@@ -119,14 +116,9 @@ pub fn spawn_batch(
 
             let state = Arc::clone(&state);
             let batch = Arc::clone(&batch);
-            let s3_client = s3_client.clone();
-            let region = region.clone();
 
             join_set.spawn(async move {
-                process_batch_item(
-                    state, s3_client, region, batch, batch_id, item, user_id, context,
-                )
-                .await;
+                process_batch_item(state, batch, batch_id, item, user_id, context, watermark).await;
                 drop(permit);
             });
         }
@@ -190,16 +182,14 @@ impl Drop for StagedFileGuard {
 }
 
 /// Process a single staged file end-to-end, recording status transitions.
-#[allow(clippy::too_many_arguments)]
 async fn process_batch_item(
     state: Arc<ServerState>,
-    s3_client: aws_sdk_s3::Client,
-    region: String,
     batch: Arc<BatchSession>,
     batch_id: Uuid,
     item: BatchPipelineItem,
     user_id: Uuid,
     context: PhotographContext,
+    watermark: bool,
 ) {
     let item_id = item.item_id;
     let path = batch_item_path(batch_id, item_id);
@@ -229,30 +219,39 @@ async fn process_batch_item(
 
     // EXIF parse on the blocking pool; non-fatal (mirrors upload_photograph).
     let exif_bytes = bits.clone();
-    let photograph_shot_at = match tokio::task::spawn_blocking(move || {
-        extract_exif_shot_at(&exif_bytes)
+    let (photograph_shot_at, photograph_exif) = match tokio::task::spawn_blocking(move || {
+        (
+            extract_exif_shot_at(&exif_bytes),
+            extract_exif_metadata(&exif_bytes),
+        )
     })
     .await
     {
-        Ok(Ok(dt_opt)) => dt_opt,
-        Ok(Err(e)) => {
+        Ok((Ok(dt_opt), exif)) => (dt_opt, exif),
+        Ok((Err(e), exif)) => {
             warn!(batch_id = %batch_id, item_id = %item_id, error = ?e, "Failed to parse EXIF; continuing");
-            None
+            (None, exif)
         }
         Err(e) => {
             warn!(batch_id = %batch_id, item_id = %item_id, error = ?e, "EXIF blocking task panicked; continuing");
-            None
+            (None, PhotographExif::default())
         }
     };
 
-    let bits_clone = bits.clone();
-    let (main_res, thumb_res) = tokio::join!(
-        process_uploaded_image(bits, None, CyhdevImageType::Photograph),
-        process_uploaded_image(bits_clone, None, CyhdevImageType::Thumbnail),
-    );
-
-    let processed_image = match main_res {
-        Ok(bytes) => bytes,
+    let watermark_config = if watermark {
+        state.watermark.clone()
+    } else {
+        None
+    };
+    // Watermarked items keep the untouched original for the private prefix.
+    let untouched_original = watermark_config.as_ref().map(|_| bits.clone());
+
+    let PhotographDerivatives {
+        main: processed_image,
+        renditions,
+        phash,
+    } = match process_photograph_derivatives(bits, None, watermark_config).await {
+        Ok(derivatives) => derivatives,
         Err(e) => {
             error!(batch_id = %batch_id, item_id = %item_id, error = ?e, "Failed to encode photograph");
             batch
@@ -261,24 +260,22 @@ async fn process_batch_item(
             return;
         }
     };
-    let processed_thumbnail = match thumb_res {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            error!(batch_id = %batch_id, item_id = %item_id, error = ?e, "Failed to encode thumbnail");
-            batch
-                .fail_item(
-                    item_id,
-                    format!("failed to encode thumbnail: {e}"),
-                    Utc::now(),
-                )
-                .await;
-            return;
+
+    let photograph_duplicate_of = match context {
+        PhotographContext::Photography => {
+            match check_near_duplicate(&state, phash, user_id).await {
+                Ok(duplicate_of) => duplicate_of,
+                Err(e) => {
+                    batch.fail_item(item_id, e.error_message, Utc::now()).await;
+                    return;
+                }
+            }
         }
+        PhotographContext::Post => None,
     };
 
     let (extension, image_type_db_id) = map_image_format_to_str(IMAGE_ENCODING_FORMAT);
     let image_path = format!("images/{item_id}.{extension}");
-    let thumbnail_path = format!("thumbnails/{item_id}.{extension}");
     let content_type = item
         .content_type
         .clone()
@@ -289,60 +286,66 @@ async fn process_batch_item(
         .await;
 
     // Upload main image.
-    if let Err(e) = s3_client
-        .put_object()
-        .bucket(AWS_S3_BUCKET_NAME)
-        .key(&image_path)
-        .content_type(&content_type)
-        .body(ByteStream::from(processed_image))
-        .send()
+    if let Err(e) = state
+        .storage
+        .put(&image_path, processed_image, &content_type)
         .await
     {
-        error!(batch_id = %batch_id, item_id = %item_id, key = %image_path, error = ?e, "Failed to upload main image to S3");
+        error!(batch_id = %batch_id, item_id = %item_id, key = %image_path, error = ?e, "Failed to upload main image");
         batch
             .fail_item(item_id, format!("failed to upload image: {e}"), Utc::now())
             .await;
         return;
     }
 
-    // Upload thumbnail; on failure delete the orphaned main object.
-    if let Err(e) = s3_client
-        .put_object()
-        .bucket(AWS_S3_BUCKET_NAME)
-        .key(&thumbnail_path)
-        .content_type(&content_type)
-        .body(ByteStream::from(processed_thumbnail))
-        .send()
-        .await
-    {
-        error!(batch_id = %batch_id, item_id = %item_id, key = %thumbnail_path, error = ?e, "Failed to upload thumbnail to S3");
-        if let Err(cleanup_err) = s3_client
-            .delete_object()
-            .bucket(AWS_S3_BUCKET_NAME)
-            .key(&image_path)
-            .send()
-            .await
-        {
-            error!(batch_id = %batch_id, item_id = %item_id, key = %image_path, error = ?cleanup_err, "Failed to clean up orphaned main image after thumbnail failure");
+    // Upload renditions; on failure delete the orphaned main object (the
+    // helper already removed any renditions it uploaded).
+    let uploaded = match upload_renditions(&state, item_id, renditions, &content_type).await {
+        Ok(uploaded) => uploaded,
+        Err(e) => {
+            state
+                .storage
+                .delete_many(std::slice::from_ref(&image_path))
+                .await;
+            batch
+                .fail_item(
+                    item_id,
+                    format!("failed to upload thumbnail: {e}"),
+                    Utc::now(),
+                )
+                .await;
+            return;
         }
-        batch
-            .fail_item(
-                item_id,
-                format!("failed to upload thumbnail: {e}"),
-                Utc::now(),
-            )
-            .await;
-        return;
-    }
+    };
 
-    let object_url = format!(
-        "https://{}.s3.{}.amazonaws.com/{}",
-        AWS_S3_BUCKET_NAME, region, image_path
-    );
-    let thumbnail_url = format!(
-        "https://{}.s3.{}.amazonaws.com/{}",
-        AWS_S3_BUCKET_NAME, region, thumbnail_path
-    );
+    let mut object_keys = Vec::with_capacity(uploaded.keys.len() + 2);
+    object_keys.push(image_path.clone());
+    object_keys.extend(uploaded.keys.iter().cloned());
+
+    let private_key = match untouched_original {
+        Some(bytes) => {
+            let key = private_original_key(item_id, item.content_type.as_deref(), None);
+            if let Err(e) = upload_original(&state, &key, bytes, &content_type).await {
+                state.storage.delete_many(&object_keys).await;
+                batch
+                    .fail_item(
+                        item_id,
+                        format!("failed to upload original: {e}"),
+                        Utc::now(),
+                    )
+                    .await;
+                return;
+            }
+            object_keys.push(key.clone());
+            Some(key)
+        }
+        None => None,
+    };
+
+    let object_url = state.storage.public_url(&image_path);
+    let thumbnail_url = uploaded.thumbnail_link.clone();
+    let photograph_renditions = serde_json::to_value(&uploaded.renditions)
+        .unwrap_or_else(|_| serde_json::Value::Object(Default::default()));
 
     batch
         .set_status(item_id, ProcessingStatus::Persisting, Utc::now())
@@ -352,7 +355,7 @@ async fn process_batch_item(
         Ok(conn) => conn,
         Err(e) => {
             error!(batch_id = %batch_id, item_id = %item_id, error = ?e, "Failed to get DB connection for batch item");
-            delete_both_objects(&s3_client, &image_path, &thumbnail_path).await;
+            state.storage.delete_many(&object_keys).await;
             batch
                 .fail_item(
                     item_id,
@@ -377,6 +380,16 @@ async fn process_batch_item(
                 photograph_lat: item.lat,
                 photograph_lon: item.lon,
                 photograph_thumbnail_link: thumbnail_url.clone(),
+                photograph_exif,
+                photograph_processing_status: PhotographProcessingStatus::Ready,
+                photograph_renditions,
+                photograph_raw_format: None,
+                photograph_raw_link: None,
+                photograph_watermarked: private_key.is_some(),
+                photograph_private_original_key: private_key,
+                photograph_phash: Some(phash_to_db(phash)),
+                photograph_duplicate_of,
+                photograph_upload_content_type: None,
             })
             .get_result(&mut conn)
             .await;
@@ -386,7 +399,7 @@ async fn process_batch_item(
         Ok(photograph) => photograph,
         Err(e) => {
             error!(batch_id = %batch_id, item_id = %item_id, error = ?e, "Failed to insert photograph row");
-            delete_both_objects(&s3_client, &image_path, &thumbnail_path).await;
+            state.storage.delete_many(&object_keys).await;
             batch
                 .fail_item(
                     item_id,
@@ -409,22 +422,6 @@ async fn process_batch_item(
         .await;
 }
 
-/// Delete both S3 objects, logging individual failures. Used when a DB insert
-/// fails after both uploads succeeded (orphan cleanup).
-async fn delete_both_objects(client: &aws_sdk_s3::Client, image_path: &str, thumbnail_path: &str) {
-    for key in [image_path, thumbnail_path] {
-        if let Err(e) = client
-            .delete_object()
-            .bucket(AWS_S3_BUCKET_NAME)
-            .key(key)
-            .send()
-            .await
-        {
-            error!(bucket = AWS_S3_BUCKET_NAME, key = %key, error = ?e, "Failed to delete orphaned S3 object");
-        }
-    }
-}
-
 /// Convenience used by the handler while streaming: append a chunk to a file.
 pub async fn append_chunk(file: &mut tokio::fs::File, chunk: &[u8]) -> std::io::Result<()> {
     file.write_all(chunk).await
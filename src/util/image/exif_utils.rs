@@ -1,9 +1,11 @@
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
-use exif::{In, Tag};
+use exif::{Exif, In, Tag, Value};
 use std::io::Cursor;
 use tracing::{debug, warn};
 
+use crate::domain::photography::photographs::PhotographExif;
+
 pub fn extract_exif_shot_at(image_bytes: &[u8]) -> Result<Option<DateTime<Utc>>> {
     let mut cursor = Cursor::new(image_bytes);
 
@@ -84,3 +86,112 @@ pub fn extract_exif_shot_at(image_bytes: &[u8]) -> Result<Option<DateTime<Utc>>>
 
     Ok(Some(dt_utc))
 }
+
+/// Extract camera/lens/exposure EXIF fields. Never fails: an unreadable
+/// container or missing tag simply leaves the corresponding field `None`.
+pub fn extract_exif_metadata(image_bytes: &[u8]) -> PhotographExif {
+    let mut cursor = Cursor::new(image_bytes);
+
+    let exif_reader = match exif::Reader::new().read_from_container(&mut cursor) {
+        Ok(r) => r,
+        Err(e) => {
+            debug!(error = %e, "Could not read standard EXIF container");
+            return PhotographExif::default();
+        }
+    };
+
+    PhotographExif {
+        photograph_camera_make: ascii_field(&exif_reader, Tag::Make),
+        photograph_camera_model: ascii_field(&exif_reader, Tag::Model),
+        photograph_lens_model: ascii_field(&exif_reader, Tag::LensModel),
+        photograph_focal_length_mm: rational_field(&exif_reader, Tag::FocalLength),
+        photograph_aperture_f_number: rational_field(&exif_reader, Tag::FNumber),
+        photograph_exposure_time_seconds: rational_field(&exif_reader, Tag::ExposureTime),
+        photograph_iso: exif_reader
+            .get_field(Tag::PhotographicSensitivity, In::PRIMARY)
+            .and_then(|f| f.value.get_uint(0))
+            .and_then(|v| i32::try_from(v).ok()),
+    }
+}
+
+/// Decimal `(lat, lon)` from the EXIF GPS IFD. `None` when either axis or its
+/// hemisphere reference is missing, the receiver flagged the fix as void
+/// (`GPSStatus = V`), or the values are out of range.
+pub fn extract_exif_gps(image_bytes: &[u8]) -> Option<(f64, f64)> {
+    let mut cursor = Cursor::new(image_bytes);
+
+    let exif_reader = match exif::Reader::new().read_from_container(&mut cursor) {
+        Ok(r) => r,
+        Err(e) => {
+            debug!(error = %e, "Could not read standard EXIF container");
+            return None;
+        }
+    };
+
+    if ascii_field(&exif_reader, Tag::GPSStatus).as_deref() == Some("V") {
+        debug!("EXIF GPS fix flagged as void");
+        return None;
+    }
+
+    let lat = gps_coordinate(&exif_reader, Tag::GPSLatitude, Tag::GPSLatitudeRef, 'S')?;
+    let lon = gps_coordinate(&exif_reader, Tag::GPSLongitude, Tag::GPSLongitudeRef, 'W')?;
+
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        warn!(lat, lon, "EXIF GPS coordinates out of range");
+        return None;
+    }
+
+    Some((lat, lon))
+}
+
+/// Degrees/minutes/seconds rationals plus hemisphere ref to signed degrees.
+fn gps_coordinate(exif_reader: &Exif, tag: Tag, ref_tag: Tag, negative_ref: char) -> Option<f64> {
+    let field = exif_reader.get_field(tag, In::PRIMARY)?;
+    let Value::Rational(parts) = &field.value else {
+        return None;
+    };
+    let mut degrees = 0.0;
+    for (part, scale) in parts.iter().zip([1.0, 60.0, 3600.0]) {
+        if part.denom == 0 {
+            return None;
+        }
+        degrees += part.to_f64() / scale;
+    }
+    if parts.is_empty() || !degrees.is_finite() {
+        return None;
+    }
+
+    let hemisphere = ascii_field(exif_reader, ref_tag)?;
+    if hemisphere.starts_with(negative_ref) {
+        degrees = -degrees;
+    }
+    Some(degrees)
+}
+
+fn ascii_field(exif_reader: &Exif, tag: Tag) -> Option<String> {
+    let field = exif_reader.get_field(tag, In::PRIMARY)?;
+    match &field.value {
+        Value::Ascii(parts) => parts
+            .first()
+            .map(|bytes| {
+                String::from_utf8_lossy(bytes)
+                    .trim_end_matches('\0')
+                    .trim()
+                    .to_string()
+            })
+            .filter(|s| !s.is_empty()),
+        _ => None,
+    }
+}
+
+fn rational_field(exif_reader: &Exif, tag: Tag) -> Option<f64> {
+    let field = exif_reader.get_field(tag, In::PRIMARY)?;
+    match &field.value {
+        Value::Rational(values) => values
+            .first()
+            .filter(|r| r.denom != 0)
+            .map(|r| r.to_f64())
+            .filter(|v| v.is_finite() && *v > 0.0),
+        _ => None,
+    }
+}
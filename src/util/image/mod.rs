@@ -1,4 +1,9 @@
 pub mod batch_pipeline;
 pub mod exif_utils;
 pub mod map_image_format_to_db_enum;
+pub mod phash;
+pub mod photograph_ingest;
 pub mod process_uploaded_images;
+pub mod raw_decode;
+pub mod variants;
+pub mod watermark;
@@ -0,0 +1,101 @@
+//! 64-bit DCT perceptual hash (pHash) for near-duplicate photograph detection.
+//!
+//! The image is reduced to a 32x32 grayscale thumbnail, the low-frequency 8x8
+//! corner of its 2D DCT-II is taken, and each coefficient contributes one bit:
+//! set when above the median (the DC term is excluded from the median so
+//! overall brightness does not skew it). Re-encodes, resizes and mild edits
+//! keep the Hamming distance between hashes small.
+
+use image::{DynamicImage, imageops::FilterType};
+
+const SAMPLE_SIZE: usize = 32;
+const HASH_SIZE: usize = 8;
+
+/// Perceptual hash of a decoded image.
+pub fn perceptual_hash(img: &DynamicImage) -> u64 {
+    let gray = img
+        .resize_exact(SAMPLE_SIZE as u32, SAMPLE_SIZE as u32, FilterType::Triangle)
+        .to_luma8();
+    let pixels: Vec<f64> = gray.pixels().map(|p| f64::from(p[0])).collect();
+
+    // cos((2x + 1) * u * pi / 2N) for the HASH_SIZE lowest frequencies.
+    let mut cosines = [[0.0f64; SAMPLE_SIZE]; HASH_SIZE];
+    for (u, row) in cosines.iter_mut().enumerate() {
+        for (x, value) in row.iter_mut().enumerate() {
+            *value =
+                (((2 * x + 1) * u) as f64 * std::f64::consts::PI / (2 * SAMPLE_SIZE) as f64).cos();
+        }
+    }
+
+    // Row pass: SAMPLE_SIZE rows x HASH_SIZE frequencies.
+    let mut rows = [[0.0f64; HASH_SIZE]; SAMPLE_SIZE];
+    for (y, out) in rows.iter_mut().enumerate() {
+        let line = &pixels[y * SAMPLE_SIZE..(y + 1) * SAMPLE_SIZE];
+        for (u, coefficient) in out.iter_mut().enumerate() {
+            *coefficient = line.iter().zip(cosines[u]).map(|(p, c)| p * c).sum();
+        }
+    }
+
+    // Column pass over the row coefficients.
+    let mut dct = [0.0f64; HASH_SIZE * HASH_SIZE];
+    for v in 0..HASH_SIZE {
+        for u in 0..HASH_SIZE {
+            dct[v * HASH_SIZE + u] = (0..SAMPLE_SIZE).map(|y| rows[y][u] * cosines[v][y]).sum();
+        }
+    }
+
+    let mut ac: Vec<f64> = dct[1..].to_vec();
+    ac.sort_by(|a, b| a.total_cmp(b));
+    let median = ac[ac.len() / 2];
+
+    dct.iter()
+        .enumerate()
+        .filter(|(_, coefficient)| **coefficient > median)
+        .fold(0u64, |hash, (bit, _)| hash | (1u64 << bit))
+}
+
+/// Number of differing bits between two hashes.
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    /// Smooth synthetic scene; `shift` brightens every pixel.
+    fn scene(width: u32, height: u32, shift: f64) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+            let (u, v) = (x as f64 / width as f64, y as f64 / height as f64);
+            let value = 110.0 + 60.0 * (u * 5.0).sin() + 45.0 * (v * 3.0 + u).cos() + shift;
+            Luma([value.clamp(0.0, 255.0) as u8])
+        }))
+    }
+
+    #[test]
+    fn identical_images_hash_equal() {
+        let img = scene(256, 192, 0.0);
+        assert_eq!(perceptual_hash(&img), perceptual_hash(&img.clone()));
+    }
+
+    #[test]
+    fn near_identical_images_hash_close() {
+        let original = perceptual_hash(&scene(256, 192, 0.0));
+        let brighter = perceptual_hash(&scene(256, 192, 8.0));
+        let resized =
+            perceptual_hash(&scene(256, 192, 0.0).resize_exact(160, 120, FilterType::Lanczos3));
+        assert!(hamming_distance(original, brighter) <= 6);
+        assert!(hamming_distance(original, resized) <= 6);
+
+        let unrelated = perceptual_hash(&scene(256, 192, 0.0).rotate90());
+        assert!(hamming_distance(original, unrelated) > 6);
+    }
+
+    #[test]
+    fn hamming_distance_counts_differing_bits() {
+        assert_eq!(hamming_distance(0, 0), 0);
+        assert_eq!(hamming_distance(0b1011, 0b0001), 2);
+        assert_eq!(hamming_distance(0, u64::MAX), 64);
+    }
+}
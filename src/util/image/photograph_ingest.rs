@@ -0,0 +1,748 @@
+//! Shared "original bytes -> object storage + `photographs` row" path for single photograph
+//! uploads.
+//!
+//! Used by the multipart `upload_photograph` handler and by the presigned
+//! upload `confirm` handler, which differ only in how the original bytes reach
+//! the server. The batch pipeline keeps its own copy because it reports
+//! per-stage status on a [`BatchSession`](crate::domain::photography::batch::BatchSession)
+//! instead of failing a request.
+//!
+//! Cleanup contract (same as the batch pipeline): a rendition upload failure
+//! deletes the already-uploaded objects, and a DB insert failure deletes all
+//! of them, so the bucket never accumulates untracked files.
+//!
+//! Watermarked uploads publish stamped derivatives only; the untouched
+//! original goes to the private `private/originals/` prefix (the bucket policy
+//! must not grant public reads there) and is tracked by key, not link.
+//!
+//! [`accept_photograph_for_processing`] is the asynchronous variant: it only
+//! stages the original and inserts a `pending` row; the image processing queue
+//! (`jobs::queue::image_processing`) runs [`encode_and_store_photograph`] later.
+
+use std::{path::Path, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use diesel_async::RunQueryDsl;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::photography::{
+        duplicates::{DuplicateAction, find_near_duplicate, phash_to_db},
+        photographs::{
+            Photograph, PhotographContext, PhotographExif, PhotographInsertable,
+            PhotographProcessingStatus, PhotographRendition, PhotographRenditions,
+        },
+        tags::PhotographTag,
+    },
+    domain::upload_progress::{UploadProgressReporter, UploadStage},
+    errors::code_error::{CodeError, CodeErrorResp, code_err},
+    init::state::ServerState,
+    jobs::queue::image_processing::{
+        ImageProcessingJob, mark_processing_failed, staged_original_path,
+    },
+    schema::photographs,
+    util::db::transaction::ScopedFutureExt,
+    util::image::{
+        exif_utils::{extract_exif_gps, extract_exif_metadata, extract_exif_shot_at},
+        map_image_format_to_db_enum::map_image_format_to_str,
+        process_uploaded_images::{
+            EncodedRendition, IMAGE_ENCODING_FORMAT, PhotographDerivatives, RenditionSize,
+            format_size, process_photograph_derivatives,
+        },
+        raw_decode::{RawFormat, develop_raw},
+    },
+};
+
+/// Caller-resolved metadata for a single photograph ingest.
+pub struct PhotographIngestInput {
+    pub user_id: Uuid,
+    pub content_type: Option<String>,
+    pub comments: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub context: PhotographContext,
+    /// Normalized tag names (see [`normalize_tags`](crate::domain::photography::tags::normalize_tags)).
+    pub tags: Vec<String>,
+    /// Set when the original is a camera RAW that must be developed first.
+    pub raw_format: Option<RawFormat>,
+    /// Stamp the configured watermark (see [`resolve_watermark`]).
+    pub watermark: bool,
+}
+
+/// Apply the per-context metadata rules shared by every single-upload entry
+/// point: photography uploads require comments + coordinates (taken from the
+/// original's EXIF GPS when both `lat` and `lon` are omitted), post images fall
+/// back to the file name and `(0, 0)`.
+pub async fn resolve_photograph_metadata(
+    user_id: Uuid,
+    context: PhotographContext,
+    comments: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    file_name: Option<&str>,
+    original: &[u8],
+) -> Result<(String, f64, f64), CodeErrorResp> {
+    match context {
+        PhotographContext::Photography => {
+            let comments = match comments {
+                Some(c) if !c.is_empty() => c,
+                _ => {
+                    warn!(user_id = %user_id, "Missing required comments field");
+                    return Err(code_err(
+                        CodeError::FILE_UPLOAD_ERROR,
+                        "Missing required comments field",
+                    ));
+                }
+            };
+
+            let (lat, lon) = match (lat, lon) {
+                (Some(lat), Some(lon)) => (lat, lon),
+                (None, None) => match exif_location(original).await {
+                    Some((lat, lon)) => {
+                        info!(user_id = %user_id, lat, lon, "Using EXIF GPS coordinates");
+                        (lat, lon)
+                    }
+                    None => {
+                        warn!(user_id = %user_id, "Missing lat/lon fields and no EXIF GPS");
+                        return Err(code_err(
+                            CodeError::FILE_UPLOAD_ERROR,
+                            "Missing latitude/longitude fields and the photograph has no EXIF GPS location",
+                        ));
+                    }
+                },
+                (None, Some(_)) => {
+                    warn!(user_id = %user_id, "Missing required lat field");
+                    return Err(code_err(
+                        CodeError::FILE_UPLOAD_ERROR,
+                        "Missing required latitude field",
+                    ));
+                }
+                (Some(_), None) => {
+                    warn!(user_id = %user_id, "Missing required lon field");
+                    return Err(code_err(
+                        CodeError::FILE_UPLOAD_ERROR,
+                        "Missing required longitude field",
+                    ));
+                }
+            };
+
+            Ok((comments, lat, lon))
+        }
+        PhotographContext::Post => {
+            let fallback_comment = file_name
+                .map(str::to_owned)
+                .unwrap_or_else(|| "post image".to_string());
+            let comments = comments.unwrap_or(fallback_comment);
+            Ok((comments, lat.unwrap_or(0.0), lon.unwrap_or(0.0)))
+        }
+    }
+}
+
+/// EXIF GPS of an upload, parsed on a blocking thread like the other EXIF
+/// extraction.
+async fn exif_location(original: &[u8]) -> Option<(f64, f64)> {
+    let bytes = original.to_vec();
+    tokio::task::spawn_blocking(move || extract_exif_gps(&bytes))
+        .await
+        .unwrap_or_else(|e| {
+            error!(error = ?e, "EXIF GPS extraction blocking task panicked");
+            None
+        })
+}
+
+/// Resolve an upload's `watermark` toggle against the server configuration:
+/// unspecified falls back to `WATERMARK_DEFAULT`, and asking for a watermark
+/// that is not configured is rejected rather than silently ignored.
+pub fn resolve_watermark(
+    state: &ServerState,
+    requested: Option<bool>,
+) -> Result<bool, CodeErrorResp> {
+    match (requested, state.watermark.as_deref()) {
+        (Some(true), None) => Err(code_err(
+            CodeError::INVALID_REQUEST,
+            "Watermarking is not configured on this server",
+        )),
+        (Some(requested), _) => Ok(requested),
+        (None, config) => Ok(config.is_some_and(|c| c.default_enabled)),
+    }
+}
+
+/// Parse a multipart/form boolean (`true`/`false`, `1`/`0`, `on`/`off`).
+pub fn parse_form_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Storage key for the unwatermarked original of a watermarked photograph.
+pub fn private_original_key(
+    image_id: Uuid,
+    content_type: Option<&str>,
+    raw_format: Option<RawFormat>,
+) -> String {
+    let extension = match raw_format {
+        Some(format) => format.extension(),
+        None => match content_type.unwrap_or_default() {
+            "image/jpeg" | "image/jpg" => "jpg",
+            "image/png" => "png",
+            "image/webp" => "webp",
+            "image/tiff" => "tif",
+            "image/avif" => "avif",
+            "image/gif" => "gif",
+            "image/heic" => "heic",
+            _ => "orig",
+        },
+    };
+    format!("private/originals/{image_id}.{extension}")
+}
+
+/// Upload an untouched original (RAW or pre-watermark) under `key`.
+pub async fn upload_original(
+    state: &ServerState,
+    key: &str,
+    bytes: Vec<u8>,
+    content_type: &str,
+) -> anyhow::Result<()> {
+    let size_bytes = bytes.len();
+    state
+        .storage
+        .put(key, bytes, content_type)
+        .await
+        .inspect_err(|e| {
+            error!(
+                error = ?e,
+                backend = state.storage.name(),
+                key = %key,
+                "Failed to upload photograph original"
+            );
+        })?;
+
+    info!(
+        backend = state.storage.name(),
+        key = %key,
+        size_bytes,
+        size_human = %format_size(size_bytes),
+        "Uploaded photograph original"
+    );
+    Ok(())
+}
+
+/// Apply the near-duplicate policy to a freshly computed perceptual hash.
+///
+/// Returns the closest existing gallery photograph under `warn`, and a
+/// `DUPLICATE_PHOTOGRAPH` (409) error naming it under `reject`. A failed
+/// lookup is logged and treated as "no duplicate" rather than failing the
+/// upload.
+pub async fn check_near_duplicate(
+    state: &ServerState,
+    phash: u64,
+    user_id: Uuid,
+) -> Result<Option<Uuid>, CodeErrorResp> {
+    let policy = state.duplicate_policy;
+    if policy.action == DuplicateAction::Off {
+        return Ok(None);
+    }
+
+    let nearest = match state.get_conn().await {
+        Ok(mut conn) => find_near_duplicate(&mut conn, phash, policy.threshold).await,
+        Err(e) => {
+            warn!(error = ?e, user_id = %user_id, "Skipping duplicate check: no DB connection");
+            return Ok(None);
+        }
+    };
+
+    match nearest {
+        Ok(None) => Ok(None),
+        Ok(Some((duplicate_of, distance))) => {
+            if policy.action == DuplicateAction::Reject {
+                return Err(code_err(
+                    CodeError::DUPLICATE_PHOTOGRAPH,
+                    format!("near-duplicate of photograph {duplicate_of} (distance {distance})"),
+                ));
+            }
+            warn!(
+                user_id = %user_id,
+                duplicate_of = %duplicate_of,
+                distance,
+                "Uploaded photograph is a near-duplicate of an existing one"
+            );
+            Ok(Some(duplicate_of))
+        }
+        Err(e) => {
+            warn!(error = ?e, user_id = %user_id, "Duplicate check query failed; continuing");
+            Ok(None)
+        }
+    }
+}
+
+/// Encoded derivatives of one photograph, already uploaded to storage.
+pub struct StoredPhotograph {
+    pub image_path: String,
+    /// Storage keys of every uploaded rendition (the medium one doubles as the
+    /// legacy thumbnail).
+    pub rendition_keys: Vec<String>,
+    pub photograph_link: String,
+    pub photograph_thumbnail_link: String,
+    pub photograph_renditions: serde_json::Value,
+    /// Storage key + link of the untouched RAW original, for unwatermarked RAW
+    /// uploads.
+    pub raw_path: Option<String>,
+    pub photograph_raw_link: Option<String>,
+    /// Private key of the unwatermarked original, for watermarked uploads.
+    pub private_original_key: Option<String>,
+    pub photograph_phash: i64,
+    pub photograph_duplicate_of: Option<Uuid>,
+    pub photograph_image_type: i32,
+    pub photograph_shot_at: Option<DateTime<Utc>>,
+    pub photograph_exif: PhotographExif,
+}
+
+/// Storage key for one rendition. `Medium` keeps the pre-rendition thumbnail key,
+/// so `photograph_thumbnail_link` and the medium rendition are one object.
+pub fn rendition_key(image_id: Uuid, size: RenditionSize, extension: &str) -> String {
+    match size {
+        RenditionSize::Medium => format!("thumbnails/{image_id}.{extension}"),
+        other => format!("thumbnails/{image_id}_{}.{extension}", other.as_str()),
+    }
+}
+
+/// Renditions uploaded to storage for one photograph.
+pub struct UploadedRenditions {
+    pub keys: Vec<String>,
+    pub renditions: PhotographRenditions,
+    /// Link of the medium rendition, stored as `photograph_thumbnail_link`.
+    pub thumbnail_link: String,
+}
+
+/// Upload every rendition. On failure the renditions already uploaded are
+/// deleted before the error is returned; the caller still owns the main object.
+pub async fn upload_renditions(
+    state: &ServerState,
+    image_id: Uuid,
+    renditions: Vec<EncodedRendition>,
+    content_type: &str,
+) -> anyhow::Result<UploadedRenditions> {
+    let (extension, _) = map_image_format_to_str(IMAGE_ENCODING_FORMAT);
+    let mut uploaded = UploadedRenditions {
+        keys: Vec::with_capacity(renditions.len()),
+        renditions: PhotographRenditions::new(),
+        thumbnail_link: String::new(),
+    };
+
+    for rendition in renditions {
+        let key = rendition_key(image_id, rendition.size, extension);
+        let size_bytes = rendition.bytes.len();
+        if let Err(e) = state.storage.put(&key, rendition.bytes, content_type).await {
+            error!(
+                error = ?e,
+                backend = state.storage.name(),
+                key = %key,
+                "Failed to upload photograph rendition"
+            );
+            state.storage.delete_many(&uploaded.keys).await;
+            return Err(anyhow::anyhow!(
+                "failed to upload {} rendition: {e}",
+                rendition.size.as_str()
+            ));
+        }
+
+        info!(
+            backend = state.storage.name(),
+            key = %key,
+            rendition = rendition.size.as_str(),
+            size_bytes,
+            size_human = %format_size(size_bytes),
+            "Uploaded photograph rendition"
+        );
+
+        let url = state.storage.public_url(&key);
+        if rendition.size == RenditionSize::Medium {
+            uploaded.thumbnail_link = url.clone();
+        }
+        uploaded.renditions.insert(
+            rendition.size.as_str().to_string(),
+            PhotographRendition {
+                url,
+                width: rendition.width,
+                height: rendition.height,
+            },
+        );
+        uploaded.keys.push(key);
+    }
+
+    Ok(uploaded)
+}
+
+/// Extract EXIF, encode the main image + renditions, and upload them to
+/// storage. RAW originals (`raw_format`) are developed first and also kept.
+/// With `watermark`, the derivatives are stamped and the untouched original
+/// (RAW or not) is kept under the private prefix instead. Gallery uploads are
+/// checked for near-duplicates before anything is uploaded.
+///
+/// On any upload failure the already-uploaded objects are deleted;
+/// on success the caller owns every object and must call
+/// [`delete_stored_objects`] if it cannot persist them.
+pub async fn encode_and_store_photograph(
+    state: &ServerState,
+    original: Vec<u8>,
+    user_id: Uuid,
+    content_type: Option<&str>,
+    raw_format: Option<RawFormat>,
+    watermark: bool,
+    context: PhotographContext,
+) -> Result<StoredPhotograph, CodeErrorResp> {
+    let watermark_config = match (watermark, &state.watermark) {
+        (false, _) => None,
+        (true, Some(config)) => Some(Arc::clone(config)),
+        (true, None) => {
+            return Err(code_err(
+                CodeError::COULD_NOT_PROCESS_IMAGE,
+                "Watermark requested but no watermark is configured",
+            ));
+        }
+    };
+
+    // Extract EXIF (shot date + camera/exposure fields) from the original
+    // bytes on a blocking thread so the synchronous EXIF container parse does
+    // not stall a Tokio worker (mirrors the spawn_blocking offload used for
+    // image processing).
+    let exif_bytes = original.clone();
+    let (photograph_shot_at, photograph_exif) = match tokio::task::spawn_blocking(move || {
+        (
+            extract_exif_shot_at(&exif_bytes),
+            extract_exif_metadata(&exif_bytes),
+        )
+    })
+    .await
+    {
+        Ok((Ok(dt_opt), exif)) => (dt_opt, exif),
+        Ok((Err(e), exif)) => {
+            error!(
+                error = ?e,
+                user_id = %user_id,
+                "Failed to parse EXIF shot-at datetime from uploaded photograph"
+            );
+            (None, exif)
+        }
+        Err(e) => {
+            error!(
+                error = ?e,
+                user_id = %user_id,
+                "EXIF extraction blocking task panicked"
+            );
+            (None, PhotographExif::default())
+        }
+    };
+
+    // RAW: develop to TIFF for encoding and keep the RAW bytes for storage.
+    // Watermarked: keep the untouched bytes for the private prefix.
+    let (decodable, untouched_original) = match raw_format {
+        Some(format) => {
+            let developed = develop_raw(original.clone(), format).await.map_err(|e| {
+                error!(error = ?e, user_id = %user_id, "Failed to develop RAW photograph");
+                code_err(CodeError::COULD_NOT_PROCESS_IMAGE, e)
+            })?;
+            (developed, Some(original))
+        }
+        None if watermark_config.is_some() => (original.clone(), Some(original)),
+        None => (original, None),
+    };
+
+    let PhotographDerivatives {
+        main: processed_image,
+        renditions,
+        phash,
+    } = process_photograph_derivatives(decodable, None, watermark_config.clone())
+        .await
+        .map_err(|e| {
+            error!(error = ?e, user_id = %user_id, "Failed to process uploaded photograph");
+            code_err(CodeError::COULD_NOT_PROCESS_IMAGE, e)
+        })?;
+
+    let photograph_duplicate_of = match context {
+        PhotographContext::Photography => check_near_duplicate(state, phash, user_id).await?,
+        PhotographContext::Post => None,
+    };
+
+    let main_size_bytes: usize = processed_image.len();
+
+    let image_id: Uuid = uuid::Uuid::new_v4();
+    let (extension, image_type_db_id) = map_image_format_to_str(IMAGE_ENCODING_FORMAT);
+
+    let image_path = format!("images/{image_id}.{extension}");
+    let original_content_type = match raw_format {
+        Some(format) => format.mime_type(),
+        None => content_type.unwrap_or("application/octet-stream"),
+    };
+    // A RAW MIME type would be wrong for the AVIF derivatives.
+    let content_type = match raw_format {
+        Some(_) => "image/avif",
+        None => content_type.unwrap_or("application/octet-stream"),
+    };
+
+    state
+        .storage
+        .put(&image_path, processed_image, content_type)
+        .await
+        .map_err(|e| {
+            error!(
+                error = ?e,
+                user_id = %user_id,
+                backend = state.storage.name(),
+                key = %image_path,
+                "Failed to upload photograph"
+            );
+            code_err(CodeError::FILE_UPLOAD_ERROR, e)
+        })?;
+
+    info!(
+        user_id = %user_id,
+        backend = state.storage.name(),
+        key = %image_path,
+        main_size_bytes,
+        main_size_human = %format_size(main_size_bytes),
+        "Uploaded main photograph"
+    );
+
+    let uploaded = match upload_renditions(state, image_id, renditions, content_type).await {
+        Ok(uploaded) => uploaded,
+        Err(e) => {
+            // Clean up the orphaned main object that was already uploaded.
+            state
+                .storage
+                .delete_many(std::slice::from_ref(&image_path))
+                .await;
+            return Err(code_err(CodeError::FILE_UPLOAD_ERROR, e));
+        }
+    };
+
+    // Unwatermarked RAW originals are published next to the derivatives;
+    // anything kept alongside a watermarked photograph stays private.
+    let original_key: Option<String> =
+        untouched_original
+            .as_ref()
+            .map(|_| match (watermark_config.is_some(), raw_format) {
+                (false, Some(format)) => format!("originals/{image_id}.{}", format.extension()),
+                _ => private_original_key(image_id, Some(original_content_type), raw_format),
+            });
+
+    if let (Some(key), Some(bytes)) = (&original_key, untouched_original)
+        && let Err(e) = upload_original(state, key, bytes, original_content_type).await
+    {
+        let mut keys = uploaded.keys;
+        keys.push(image_path);
+        state.storage.delete_many(&keys).await;
+        return Err(code_err(CodeError::FILE_UPLOAD_ERROR, e));
+    }
+
+    let (raw_path, private_original_key) = if watermark_config.is_some() {
+        (None, original_key)
+    } else {
+        (original_key, None)
+    };
+
+    let photograph_renditions = serde_json::to_value(&uploaded.renditions)
+        .unwrap_or_else(|_| serde_json::Value::Object(Default::default()));
+
+    Ok(StoredPhotograph {
+        photograph_link: state.storage.public_url(&image_path),
+        photograph_thumbnail_link: uploaded.thumbnail_link,
+        photograph_renditions,
+        photograph_raw_link: raw_path.as_deref().map(|key| state.storage.public_url(key)),
+        raw_path,
+        private_original_key,
+        photograph_phash: phash_to_db(phash),
+        photograph_duplicate_of,
+        image_path,
+        rendition_keys: uploaded.keys,
+        photograph_image_type: image_type_db_id,
+        photograph_shot_at,
+        photograph_exif,
+    })
+}
+
+/// Delete every stored object of a [`StoredPhotograph`] that could not be
+/// persisted, so the bucket does not accumulate untracked files.
+pub async fn delete_stored_objects(state: &ServerState, stored: &StoredPhotograph) {
+    let mut keys = Vec::with_capacity(stored.rendition_keys.len() + 1);
+    keys.push(stored.image_path.clone());
+    keys.extend(stored.rendition_keys.iter().cloned());
+    keys.extend(stored.raw_path.iter().cloned());
+    keys.extend(stored.private_original_key.iter().cloned());
+    state.storage.delete_many(&keys).await;
+}
+
+/// Insert a photograph row (plus its tag links) in one transaction, so a tag
+/// failure never leaves an untagged photograph behind.
+async fn insert_photograph_with_tags(
+    state: &ServerState,
+    insertable: PhotographInsertable,
+    tags: &[String],
+) -> Result<Photograph, CodeErrorResp> {
+    state
+        .transaction(|conn| {
+            async move {
+                let photograph: Photograph = diesel::insert_into(photographs::table)
+                    .values(insertable)
+                    .get_result(&mut *conn)
+                    .await
+                    .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+
+                PhotographTag::replace_for_photograph(&mut *conn, photograph.photograph_id, tags)
+                    .await
+                    .map_err(|e| code_err(CodeError::DB_INSERTION_ERROR, e))?;
+
+                Ok(photograph)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+/// Encode, upload, and persist one photograph from its original bytes.
+pub async fn ingest_photograph(
+    state: Arc<ServerState>,
+    original: Vec<u8>,
+    input: PhotographIngestInput,
+) -> Result<Photograph, CodeErrorResp> {
+    let user_id = input.user_id;
+
+    let stored = encode_and_store_photograph(
+        &state,
+        original,
+        user_id,
+        input.content_type.as_deref(),
+        input.raw_format,
+        input.watermark,
+        input.context,
+    )
+    .await?;
+
+    let insertable = PhotographInsertable {
+        user_id,
+        photograph_shot_at: stored.photograph_shot_at,
+        photograph_image_type: stored.photograph_image_type,
+        photograph_context: input.context,
+        photograph_is_on_cloud: true,
+        photograph_link: stored.photograph_link.clone(),
+        photograph_comments: input.comments,
+        photograph_lat: input.lat,
+        photograph_lon: input.lon,
+        photograph_thumbnail_link: stored.photograph_thumbnail_link.clone(),
+        photograph_exif: stored.photograph_exif.clone(),
+        photograph_processing_status: PhotographProcessingStatus::Ready,
+        photograph_renditions: stored.photograph_renditions.clone(),
+        photograph_raw_format: input.raw_format.map(|f| f.extension().to_string()),
+        photograph_raw_link: stored.photograph_raw_link.clone(),
+        photograph_watermarked: input.watermark,
+        photograph_private_original_key: stored.private_original_key.clone(),
+        photograph_phash: Some(stored.photograph_phash),
+        photograph_duplicate_of: stored.photograph_duplicate_of,
+        photograph_upload_content_type: None,
+    };
+
+    match insert_photograph_with_tags(&state, insertable, &input.tags).await {
+        Ok(photograph) => Ok(photograph),
+        Err(e) => {
+            error!(
+                error = ?e,
+                user_id = %user_id,
+                key = %stored.image_path,
+                "Failed to insert photograph row into DB"
+            );
+            // DB insertion failed after every upload succeeded.
+            delete_stored_objects(&state, &stored).await;
+            Err(e)
+        }
+    }
+}
+
+/// Accept one photograph for asynchronous processing.
+///
+/// Stages the original bytes to disk, inserts a `pending` row (empty links,
+/// tags attached) and enqueues an [`ImageProcessingJob`]; the queue worker
+/// fills in the links and flips the row to `ready`. Returns the pending row.
+/// A full queue marks the row `failed` and surfaces
+/// `PROCESSING_QUEUE_FULL` (503) so the client can retry. `progress`, when the
+/// upload is tracked, is handed to the job once queued.
+pub async fn accept_photograph_for_processing(
+    state: Arc<ServerState>,
+    original: Vec<u8>,
+    input: PhotographIngestInput,
+    progress: Option<UploadProgressReporter>,
+) -> Result<Photograph, CodeErrorResp> {
+    let user_id = input.user_id;
+    let (_, image_type_db_id) = map_image_format_to_str(IMAGE_ENCODING_FORMAT);
+
+    let insertable = PhotographInsertable {
+        user_id,
+        photograph_shot_at: None,
+        photograph_image_type: image_type_db_id,
+        photograph_context: input.context,
+        photograph_is_on_cloud: false,
+        photograph_link: String::new(),
+        photograph_comments: input.comments,
+        photograph_lat: input.lat,
+        photograph_lon: input.lon,
+        photograph_thumbnail_link: String::new(),
+        photograph_exif: PhotographExif::default(),
+        photograph_processing_status: PhotographProcessingStatus::Pending,
+        photograph_renditions: serde_json::Value::Object(Default::default()),
+        photograph_raw_format: input.raw_format.map(|f| f.extension().to_string()),
+        photograph_raw_link: None,
+        photograph_watermarked: input.watermark,
+        photograph_private_original_key: None,
+        photograph_phash: None,
+        photograph_duplicate_of: None,
+        photograph_upload_content_type: input.content_type.clone(),
+    };
+
+    let photograph = insert_photograph_with_tags(&state, insertable, &input.tags).await?;
+    let photograph_id = photograph.photograph_id;
+
+    let staged_path = staged_original_path(photograph_id);
+    if let Err(e) = stage_original(&staged_path, &original).await {
+        error!(error = ?e, photograph_id = %photograph_id, "Failed to stage original for processing");
+        mark_processing_failed(&state, photograph_id, "failed to stage original").await;
+        return Err(code_err(CodeError::FILE_UPLOAD_ERROR, e));
+    }
+    drop(original);
+
+    if let Some(progress) = &progress {
+        progress.resource(photograph_id);
+        progress.stage(UploadStage::Queued);
+    }
+    let job = ImageProcessingJob {
+        photograph_id,
+        user_id,
+        staged_path,
+        content_type: input.content_type,
+        raw_format: input.raw_format,
+        watermark: input.watermark,
+        progress,
+    };
+
+    if let Err(job) = state.image_processing_queue.try_enqueue(job) {
+        warn!(photograph_id = %photograph_id, "Image processing queue full; rejecting upload");
+        let _ = tokio::fs::remove_file(&job.staged_path).await;
+        mark_processing_failed(&state, photograph_id, "processing queue full").await;
+        if let Some(progress) = job.progress {
+            progress.failed("processing queue full");
+        }
+        return Err(CodeError::PROCESSING_QUEUE_FULL.into());
+    }
+
+    info!(user_id = %user_id, photograph_id = %photograph_id, "Queued photograph for processing");
+
+    Ok(photograph)
+}
+
+async fn stage_original(path: &Path, original: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(path, original).await
+}
@@ -3,9 +3,12 @@ use fast_image_resize::{PixelType, ResizeOptions, Resizer, images::Image as Fast
 use image::{
     DynamicImage, GenericImageView, ImageFormat, load_from_memory, load_from_memory_with_format,
 };
-use std::{io::Cursor, time::Instant};
+use std::{borrow::Cow, io::Cursor, sync::Arc, time::Instant};
 use tracing::info;
 
+use super::phash::perceptual_hash;
+use super::watermark::{WatermarkConfig, apply_watermark};
+
 pub const IMAGE_ENCODING_FORMAT: ImageFormat = ImageFormat::Avif;
 
 #[repr(u8)]
@@ -53,6 +56,130 @@ pub fn format_size(bytes: usize) -> String {
     }
 }
 
+/// Responsive renditions generated for every photograph, served to the
+/// frontend as a `srcset`. `Medium` matches the legacy single thumbnail
+/// (`CyhdevImageType::Thumbnail`) and keeps its S3 key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenditionSize {
+    Small,
+    Medium,
+    Large,
+}
+
+pub const RENDITION_SIZES: [RenditionSize; 3] = [
+    RenditionSize::Small,
+    RenditionSize::Medium,
+    RenditionSize::Large,
+];
+
+impl RenditionSize {
+    pub fn max_long_width(&self) -> u32 {
+        match self {
+            RenditionSize::Small => 400,
+            RenditionSize::Medium => CyhdevImageType::Thumbnail.max_long_width(),
+            RenditionSize::Large => 1600,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RenditionSize::Small => "small",
+            RenditionSize::Medium => "medium",
+            RenditionSize::Large => "large",
+        }
+    }
+}
+
+/// One encoded rendition with its final pixel dimensions (for `srcset`
+/// width descriptors).
+pub struct EncodedRendition {
+    pub size: RenditionSize,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: Vec<u8>,
+}
+
+fn decode_image(bits: &[u8], format: Option<ImageFormat>) -> anyhow::Result<DynamicImage> {
+    // Attempt to decode the image from memory, using the provided format if auto-detection fails.
+    match load_from_memory(bits) {
+        Ok(img) => Ok(img),
+        Err(e) => {
+            if let Some(fmt) = format {
+                load_from_memory_with_format(bits, fmt).map_err(|e2| {
+                    anyhow!("Failed to decode image with the provided format: {:?}", e2)
+                })
+            } else {
+                Err(anyhow!("Failed to decode image: {:?}", e))
+            }
+        }
+    }
+}
+
+/// Downscale so the long edge is at most `max_long_width`. Returns `None` when
+/// the image already fits (never upscales), so callers can reuse it as-is.
+fn resize_to_long_edge(
+    img: &DynamicImage,
+    max_long_width: u32,
+) -> anyhow::Result<Option<DynamicImage>> {
+    resize_to_fit(img, Some(max_long_width), Some(max_long_width))
+}
+
+/// Downscale to fit inside `max_width` x `max_height` (either may be
+/// unbounded), keeping the aspect ratio. `None` when it already fits.
+fn resize_to_fit(
+    img: &DynamicImage,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+) -> anyhow::Result<Option<DynamicImage>> {
+    let (width, height) = img.dimensions();
+    let scale_w = max_width.map_or(1.0, |w| w as f64 / width as f64);
+    let scale_h = max_height.map_or(1.0, |h| h as f64 / height as f64);
+    let scale = scale_w.min(scale_h);
+    if scale >= 1.0 {
+        return Ok(None);
+    }
+
+    let new_width = (width as f64 * scale).round().max(1.0) as u32;
+    let new_height = (height as f64 * scale).round().max(1.0) as u32;
+
+    let src_data = img.to_rgba8().into_raw();
+    let src_image = FastImage::from_vec_u8(width, height, src_data, PixelType::U8x4)
+        .map_err(|_| anyhow!("Failed to create fast image from buffer"))?;
+
+    let mut dst_image = FastImage::new(new_width, new_height, src_image.pixel_type());
+
+    let mut resizer = Resizer::new();
+    resizer
+        .resize(&src_image, &mut dst_image, &ResizeOptions::default())
+        .map_err(|_| anyhow!("Failed to resize image"))?;
+
+    let dst_data = dst_image.into_vec();
+    let dst_buffer =
+        image::ImageBuffer::<image::Rgba<u8>, _>::from_raw(new_width, new_height, dst_data)
+            .ok_or(anyhow!("Failed to create image buffer"))?;
+
+    Ok(Some(DynamicImage::ImageRgba8(dst_buffer)))
+}
+
+/// Encode as `format`; JPEG has no alpha channel, so it gets an RGB copy.
+fn encode_image_as(img: &DynamicImage, format: ImageFormat) -> anyhow::Result<Vec<u8>> {
+    let rgb;
+    let img = if format == ImageFormat::Jpeg && img.color().has_alpha() {
+        rgb = DynamicImage::ImageRgb8(img.to_rgb8());
+        &rgb
+    } else {
+        img
+    };
+    let mut output_buffer = Vec::new();
+    img.write_to(&mut Cursor::new(&mut output_buffer), format)
+        .map_err(|e| anyhow!("Failed to encode image as {:?}: {:?}", format, e))?;
+    Ok(output_buffer)
+}
+
+fn encode_image(img: &DynamicImage) -> anyhow::Result<Vec<u8>> {
+    encode_image_as(img, IMAGE_ENCODING_FORMAT)
+}
+
 pub async fn process_uploaded_image(
     bits: Vec<u8>,
     format: Option<image::ImageFormat>,
@@ -63,57 +190,9 @@ pub async fn process_uploaded_image(
     let start = Instant::now();
 
     let result = tokio::task::spawn_blocking(move || {
-        // Attempt to decode the image from memory, using the provided format if auto-detection fails.
-        let img = match load_from_memory(&bits) {
-            Ok(img) => img,
-            Err(e) => {
-                if let Some(fmt) = format {
-                    load_from_memory_with_format(&bits, fmt).map_err(|e2| {
-                        anyhow!("Failed to decode image with the provided format: {:?}", e2)
-                    })?
-                } else {
-                    return Err(anyhow!("Failed to decode image: {:?}", e));
-                }
-            }
-        };
-
-        // Determine dimensions and resize if necessary.
-        let (width, height) = img.dimensions();
-        let max_edge = width.max(height);
-        let resized_img = if max_edge > image_type.max_long_width() {
-            let scale = image_type.max_long_width() as f64 / max_edge as f64;
-            let new_width = (width as f64 * scale).round().max(1.0) as u32;
-            let new_height = (height as f64 * scale).round().max(1.0) as u32;
-
-            let src_data = img.to_rgba8().into_raw();
-            let src_image = FastImage::from_vec_u8(width, height, src_data, PixelType::U8x4)
-                .map_err(|_| anyhow!("Failed to create fast image from buffer"))?;
-
-            let mut dst_image = FastImage::new(new_width, new_height, src_image.pixel_type());
-
-            let mut resizer = Resizer::new();
-            resizer
-                .resize(&src_image, &mut dst_image, &ResizeOptions::default())
-                .map_err(|_| anyhow!("Failed to resize image"))?;
-
-            let dst_data = dst_image.into_vec();
-            let dst_buffer =
-                image::ImageBuffer::<image::Rgba<u8>, _>::from_raw(new_width, new_height, dst_data)
-                    .ok_or(anyhow!("Failed to create image buffer"))?;
-
-            DynamicImage::ImageRgba8(dst_buffer)
-        } else {
-            img
-        };
-
-        let mut output_buffer = Vec::new();
-        {
-            let mut cursor = Cursor::new(&mut output_buffer);
-            resized_img
-                .write_to(&mut cursor, IMAGE_ENCODING_FORMAT)
-                .map_err(|e| anyhow!("Failed to encode image as AVIF: {:?}", e))?;
-        }
-        Ok(output_buffer)
+        let img = decode_image(&bits, format)?;
+        let resized_img = resize_to_long_edge(&img, image_type.max_long_width())?;
+        encode_image(resized_img.as_ref().unwrap_or(&img))
     })
     .await
     .map_err(|e| anyhow!("Blocking image processing task panicked: {:?}", e))?;
@@ -134,3 +213,146 @@ pub async fn process_uploaded_image(
 
     result
 }
+
+/// Main image plus every rendition of one photograph.
+pub struct PhotographDerivatives {
+    pub main: Vec<u8>,
+    pub renditions: Vec<EncodedRendition>,
+    /// Perceptual hash of the unwatermarked original, for duplicate detection.
+    pub phash: u64,
+}
+
+/// Downscale (never upscale) to `max_long_width`, stamp the watermark if one
+/// is given, and encode.
+fn render_derivative(
+    source: &DynamicImage,
+    max_long_width: u32,
+    watermark: Option<&WatermarkConfig>,
+) -> anyhow::Result<(u32, u32, Vec<u8>)> {
+    let mut output = match resize_to_long_edge(source, max_long_width)? {
+        Some(resized) => Cow::Owned(resized),
+        None => Cow::Borrowed(source),
+    };
+    if let Some(config) = watermark {
+        apply_watermark(output.to_mut(), config);
+    }
+    let (width, height) = output.dimensions();
+    Ok((width, height, encode_image(&output)?))
+}
+
+/// Encode the main photograph and every [`RENDITION_SIZES`] rendition from a
+/// single decode of the original. Renditions larger than the original are
+/// emitted at the original size rather than upscaled. With `watermark` set,
+/// each output is stamped after resizing so the mark stays proportionate.
+pub async fn process_photograph_derivatives(
+    bits: Vec<u8>,
+    format: Option<image::ImageFormat>,
+    watermark: Option<Arc<WatermarkConfig>>,
+) -> anyhow::Result<PhotographDerivatives> {
+    let original_size = bits.len();
+    let start = Instant::now();
+
+    let watermark_applied = watermark.is_some();
+
+    let result = tokio::task::spawn_blocking(move || {
+        let img = decode_image(&bits, format)?;
+        drop(bits);
+        let watermark = watermark.as_deref();
+        let phash = perceptual_hash(&img);
+
+        // The full-size encode dominates; run it alongside the renditions.
+        std::thread::scope(|scope| {
+            let main = scope.spawn(|| {
+                render_derivative(
+                    &img,
+                    CyhdevImageType::Photograph.max_long_width(),
+                    watermark,
+                )
+            });
+
+            let renditions = RENDITION_SIZES
+                .iter()
+                .map(|size| {
+                    let (width, height, bytes) =
+                        render_derivative(&img, size.max_long_width(), watermark)?;
+                    Ok(EncodedRendition {
+                        size: *size,
+                        width,
+                        height,
+                        bytes,
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            let (_, _, main) = main
+                .join()
+                .map_err(|_| anyhow!("Main photograph encode thread panicked"))??;
+
+            Ok(PhotographDerivatives {
+                main,
+                renditions,
+                phash,
+            })
+        })
+    })
+    .await
+    .map_err(|e| anyhow!("Blocking photograph processing task panicked: {:?}", e))?;
+
+    if let Ok(ref derivatives) = result {
+        let processed_size: usize = derivatives.main.len()
+            + derivatives
+                .renditions
+                .iter()
+                .map(|r| r.bytes.len())
+                .sum::<usize>();
+        info!(
+            original_size_bytes = original_size,
+            original_size_human = %format_size(original_size),
+            processed_size_bytes = processed_size,
+            processed_size_human = %format_size(processed_size),
+            renditions = derivatives.renditions.len(),
+            watermarked = watermark_applied,
+            elapsed_ms = %start.elapsed().as_millis(),
+            "Completed photograph processing and AVIF encoding"
+        );
+    }
+
+    result
+}
+
+/// Decode a stored image, downscale it to fit `max_width` x `max_height`
+/// (never upscaling) and encode it as `format`. Backs the `/img/{id}` resize
+/// proxy.
+pub async fn render_image_variant(
+    bits: Vec<u8>,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    format: ImageFormat,
+) -> anyhow::Result<Vec<u8>> {
+    let original_size = bits.len();
+    let start = Instant::now();
+
+    let result = tokio::task::spawn_blocking(move || {
+        let img = decode_image(&bits, None)?;
+        drop(bits);
+        let resized = resize_to_fit(&img, max_width, max_height)?;
+        encode_image_as(resized.as_ref().unwrap_or(&img), format)
+    })
+    .await
+    .map_err(|e| anyhow!("Blocking image variant task panicked: {:?}", e))?;
+
+    if let Ok(ref rendered) = result {
+        info!(
+            source_size_bytes = original_size,
+            variant_size_bytes = rendered.len(),
+            variant_size_human = %format_size(rendered.len()),
+            max_width = ?max_width,
+            max_height = ?max_height,
+            format = ?format,
+            elapsed_ms = %start.elapsed().as_millis(),
+            "Rendered image variant"
+        );
+    }
+
+    result
+}
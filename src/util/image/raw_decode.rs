@@ -0,0 +1,145 @@
+//! Camera RAW (CR2/NEF/ARW) support for photograph ingestion.
+//!
+//! The `image` crate cannot demosaic RAW sensor data, so RAW originals are
+//! first developed to a 16-bit TIFF by an external dcraw-compatible converter
+//! (`RAW_CONVERTER_BIN`, default `dcraw`) under a timeout. The TIFF then
+//! enters the normal [`process_uploaded_image`](super::process_uploaded_images::process_uploaded_image)
+//! pipeline; the untouched RAW is kept in S3 next to the AVIF derivatives.
+//!
+//! EXIF is still read from the RAW bytes: all three formats are TIFF
+//! containers that `kamadak-exif` parses directly.
+
+use std::io::Write;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use serde_derive::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+use tokio::process::Command;
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::init::app_config;
+
+/// Arguments for a dcraw-compatible converter: write to stdout (`-c`), use
+/// the camera white balance (`-w`), emit TIFF (`-T`) at 16 bits (`-6`).
+const DEFAULT_RAW_CONVERTER_ARGS: [&str; 4] = ["-c", "-w", "-T", "-6"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RawFormat {
+    Cr2,
+    Nef,
+    Arw,
+}
+
+impl RawFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RawFormat::Cr2 => "cr2",
+            RawFormat::Nef => "nef",
+            RawFormat::Arw => "arw",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            RawFormat::Cr2 => "image/x-canon-cr2",
+            RawFormat::Nef => "image/x-nikon-nef",
+            RawFormat::Arw => "image/x-sony-arw",
+        }
+    }
+
+    pub fn from_extension(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "cr2" => Some(RawFormat::Cr2),
+            "nef" => Some(RawFormat::Nef),
+            "arw" => Some(RawFormat::Arw),
+            _ => None,
+        }
+    }
+
+    pub fn from_mime(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "image/x-canon-cr2" => Some(RawFormat::Cr2),
+            "image/x-nikon-nef" => Some(RawFormat::Nef),
+            "image/x-sony-arw" => Some(RawFormat::Arw),
+            _ => None,
+        }
+    }
+
+    /// Identify a RAW upload from its declared MIME type, falling back to the
+    /// file extension: browsers usually send RAW files as
+    /// `application/octet-stream`.
+    pub fn detect(content_type: Option<&str>, file_name: Option<&str>) -> Option<Self> {
+        content_type.and_then(Self::from_mime).or_else(|| {
+            file_name
+                .and_then(|name| name.rsplit_once('.'))
+                .and_then(|(_, ext)| Self::from_extension(ext))
+        })
+    }
+}
+
+/// Develop a RAW original into TIFF bytes the `image` crate can decode.
+///
+/// The converter is killed if it runs past `RAW_CONVERTER_TIMEOUT_SECS`; the
+/// staged RAW is removed when its guard drops, on every path.
+pub async fn develop_raw(raw: Vec<u8>, format: RawFormat) -> anyhow::Result<Vec<u8>> {
+    let start = Instant::now();
+    let raw_size = raw.len();
+
+    // dcraw reads from a path, not stdin; the extension selects its parser.
+    let suffix = format!(".{}", format.extension());
+    let staged = tokio::task::spawn_blocking(move || -> std::io::Result<NamedTempFile> {
+        let mut staged = tempfile::Builder::new()
+            .prefix("cyhdev-raw-")
+            .suffix(&suffix)
+            .tempfile()?;
+        staged.write_all(&raw)?;
+        Ok(staged)
+    })
+    .await
+    .map_err(|e| anyhow!("Blocking RAW staging task panicked: {:?}", e))?
+    .map_err(|e| anyhow!("Failed to stage RAW file: {e}"))?;
+
+    let images = &app_config::config().images;
+    let program = &images.raw_converter_bin;
+    let limit = Duration::from_secs(images.raw_converter_timeout_secs);
+    let child = Command::new(program)
+        .args(DEFAULT_RAW_CONVERTER_ARGS)
+        .arg(staged.path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow!("Failed to run RAW converter `{program}`: {e}"))?;
+
+    // On expiry the `wait_with_output` future drops, taking the child with it,
+    // and `kill_on_drop` ends the process.
+    let output = tokio::time::timeout(limit, child.wait_with_output())
+        .await
+        .map_err(|_| anyhow!("RAW converter `{program}` timed out after {limit:?}"))?
+        .map_err(|e| anyhow!("Failed to run RAW converter `{program}`: {e}"))?;
+    drop(staged);
+
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(anyhow!(
+            "RAW converter `{program}` failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let tiff = output.stdout;
+
+    info!(
+        raw_format = format.extension(),
+        raw_size_bytes = raw_size,
+        developed_size_bytes = tiff.len(),
+        elapsed_ms = %start.elapsed().as_millis(),
+        "Developed RAW photograph"
+    );
+
+    Ok(tiff)
+}
@@ -0,0 +1,299 @@
+//! On-the-fly photograph variants for `GET /img/{photograph_id}`.
+//!
+//! A variant is a stored image (main image or the smallest rendition that
+//! covers the request) downscaled to fit `w` x `h` and re-encoded as `fmt`.
+//! Requested dimensions are rounded up to [`VARIANT_DIMENSION_STEP`] so the
+//! number of cacheable variants per photograph stays bounded.
+//!
+//! Rendered variants are cached under
+//! `variants/photographs/{photograph_id}/{source}-{w}x{h}.{ext}`, where
+//! `source` is the file stem of the source object, so replacing a photograph's
+//! derivatives never serves a stale variant. `IMAGE_VARIANT_CACHE` selects
+//! where:
+//! - `storage` (default): the configured [`StorageBackend`].
+//! - `disk`: `IMAGE_VARIANT_CACHE_DIR` (default `./cache/image-variants`).
+//! - `off`: render every request.
+
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+
+use image::ImageFormat;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::photography::photographs::Photograph,
+    init::app_config::ImagesSection,
+    util::{image::process_uploaded_images::CyhdevImageType, storage::StorageBackend},
+};
+
+/// Requested dimensions are rounded up to a multiple of this.
+pub const VARIANT_DIMENSION_STEP: u32 = 16;
+/// Largest width or height a variant may be requested at.
+pub const MAX_VARIANT_DIMENSION: u32 = 2400;
+
+/// Storage prefix of cached variants.
+pub const VARIANT_KEY_PREFIX: &str = "variants/photographs/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantFormat {
+    Avif,
+    Webp,
+    Jpeg,
+    Png,
+}
+
+impl VariantFormat {
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "avif" => Some(Self::Avif),
+            "webp" => Some(Self::Webp),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Avif => "avif",
+            Self::Webp => "webp",
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Avif => "image/avif",
+            Self::Webp => "image/webp",
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+        }
+    }
+
+    pub fn image_format(&self) -> ImageFormat {
+        match self {
+            Self::Avif => ImageFormat::Avif,
+            Self::Webp => ImageFormat::WebP,
+            Self::Jpeg => ImageFormat::Jpeg,
+            Self::Png => ImageFormat::Png,
+        }
+    }
+}
+
+/// Validated `w`/`h`/`fmt`; `None` dimensions are unbounded.
+#[derive(Debug, Clone, Copy)]
+pub struct VariantSpec {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub format: VariantFormat,
+}
+
+impl VariantSpec {
+    /// Validate query parameters. Zero or oversized dimensions and unknown
+    /// formats are rejected; the format defaults to AVIF.
+    pub fn from_params(
+        width: Option<u32>,
+        height: Option<u32>,
+        format: Option<&str>,
+    ) -> Result<Self, &'static str> {
+        let snap = |value: Option<u32>| -> Result<Option<u32>, &'static str> {
+            match value {
+                None => Ok(None),
+                Some(0) => Err("w and h must be positive"),
+                Some(v) if v > MAX_VARIANT_DIMENSION => Err("w and h must be at most 2400"),
+                Some(v) => Ok(Some(
+                    v.div_ceil(VARIANT_DIMENSION_STEP) * VARIANT_DIMENSION_STEP,
+                )),
+            }
+        };
+        let format = match format {
+            None => VariantFormat::Avif,
+            Some(f) => VariantFormat::from_param(f).ok_or("fmt must be avif, webp, jpeg or png")?,
+        };
+        Ok(Self {
+            width: snap(width)?,
+            height: snap(height)?,
+            format,
+        })
+    }
+
+    /// Bounds actually applied when rendering: unbounded requests still stop
+    /// at the photograph size limit.
+    pub fn render_bounds(&self) -> (Option<u32>, Option<u32>) {
+        let cap = CyhdevImageType::Photograph.max_long_width();
+        (
+            Some(self.width.unwrap_or(cap)),
+            Some(self.height.unwrap_or(cap)),
+        )
+    }
+
+    pub fn cache_key(&self, photograph_id: Uuid, source_key: &str) -> String {
+        let source = Path::new(source_key)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("source");
+        format!(
+            "{VARIANT_KEY_PREFIX}{photograph_id}/{source}-{}x{}.{}",
+            self.width.unwrap_or(0),
+            self.height.unwrap_or(0),
+            self.format.extension()
+        )
+    }
+}
+
+/// Storage key of the smallest stored image that still covers `spec`: a
+/// rendition when one is large enough, else the main image.
+pub fn variant_source_key(
+    photograph: &Photograph,
+    spec: &VariantSpec,
+    storage: &dyn StorageBackend,
+) -> Option<String> {
+    let main = || storage.key_from_url(&photograph.photograph_link);
+    if spec.width.is_none() && spec.height.is_none() {
+        return main();
+    }
+    let mut renditions: Vec<_> = photograph.renditions().into_values().collect();
+    renditions.sort_by_key(|r| r.width);
+    renditions
+        .iter()
+        .find(|r| {
+            spec.width.is_none_or(|w| r.width >= w) && spec.height.is_none_or(|h| r.height >= h)
+        })
+        .and_then(|r| storage.key_from_url(&r.url))
+        .or_else(main)
+}
+
+#[derive(Debug, Clone)]
+pub enum VariantCacheLocation {
+    Off,
+    Disk(PathBuf),
+    Storage,
+}
+
+/// Variant cache plus a cap on concurrent renders (each is a full decode and
+/// encode).
+pub struct ImageVariantCache {
+    location: VariantCacheLocation,
+    render_permits: Semaphore,
+}
+
+impl ImageVariantCache {
+    /// `images.variant_cache` = `storage` (default) | `disk` | `off`,
+    /// `images.variant_cache_dir` for `disk`, `images.variant_render_concurrency`
+    /// (default: half the CPUs, at least 1).
+    pub fn from_config(config: &ImagesSection) -> Self {
+        let location = match config.variant_cache.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" | "disabled" => VariantCacheLocation::Off,
+            "disk" | "fs" => VariantCacheLocation::Disk(config.variant_cache_dir.clone()),
+            _ => VariantCacheLocation::Storage,
+        };
+        let concurrency = config
+            .variant_render_concurrency
+            .filter(|n| *n > 0)
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| (n.get() / 2).max(1))
+                    .unwrap_or(1)
+            });
+
+        info!(location = ?location, concurrency, "Image variant cache configured");
+
+        Self {
+            location,
+            render_permits: Semaphore::new(concurrency),
+        }
+    }
+
+    /// Wait for a render slot.
+    pub async fn render_permit(&self) -> SemaphorePermit<'_> {
+        self.render_permits
+            .acquire()
+            .await
+            .expect("image variant semaphore is never closed")
+    }
+
+    /// Cached variant bytes; misses and read failures both yield `None`.
+    pub async fn get(&self, storage: &dyn StorageBackend, key: &str) -> Option<Vec<u8>> {
+        let result = match &self.location {
+            VariantCacheLocation::Off => return None,
+            VariantCacheLocation::Storage => {
+                storage.get(key).await.map(|object| object.map(|o| o.bytes))
+            }
+            VariantCacheLocation::Disk(root) => match disk_path(root, key) {
+                Some(path) => match tokio::fs::read(path).await {
+                    Ok(bytes) => Ok(Some(bytes)),
+                    Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                    Err(e) => Err(e.into()),
+                },
+                None => Ok(None),
+            },
+        };
+        result.unwrap_or_else(|e| {
+            warn!(error = ?e, key = %key, "Failed to read cached image variant");
+            None
+        })
+    }
+
+    /// Best-effort store; failures are logged and the variant is re-rendered
+    /// next time.
+    pub async fn put(
+        &self,
+        storage: &dyn StorageBackend,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: &str,
+    ) {
+        let result = match &self.location {
+            VariantCacheLocation::Off => return,
+            VariantCacheLocation::Storage => storage.put(key, bytes, content_type).await,
+            VariantCacheLocation::Disk(root) => match disk_path(root, key) {
+                Some(path) => write_disk(&path, bytes).await,
+                None => return,
+            },
+        };
+        if let Err(e) = result {
+            error!(error = ?e, key = %key, "Failed to cache image variant");
+        }
+    }
+
+    /// Drop the disk-cached variants of deleted photographs. Storage-cached
+    /// variants are reclaimed by the orphaned-object cleanup instead (backends
+    /// cannot delete by prefix).
+    pub async fn purge_photographs(&self, photograph_ids: &[Uuid]) {
+        let VariantCacheLocation::Disk(root) = &self.location else {
+            return;
+        };
+        for id in photograph_ids {
+            let dir = root.join(format!("{VARIANT_KEY_PREFIX}{id}"));
+            match tokio::fs::remove_dir_all(&dir).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => warn!(error = ?e, dir = %dir.display(), "Failed to purge image variants"),
+            }
+        }
+    }
+}
+
+fn disk_path(root: &Path, key: &str) -> Option<PathBuf> {
+    let relative = Path::new(key);
+    relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then(|| root.join(relative))
+}
+
+async fn write_disk(path: &Path, bytes: Vec<u8>) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let mut tmp = path.to_path_buf().into_os_string();
+    tmp.push(".part");
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
@@ -0,0 +1,209 @@
+//! Optional watermark overlay for published photographs.
+//!
+//! The mark is either a logo image (`WATERMARK_LOGO_PATH`) or a line of text
+//! (`WATERMARK_TEXT` rendered with `WATERMARK_FONT_PATH`). It is rasterized
+//! once at startup into an RGBA stamp with the configured opacity already
+//! folded into its alpha channel, so applying it per image is just a resize and
+//! an alpha blend onto the chosen corner.
+//!
+//! Watermarking is toggled per upload; `WATERMARK_DEFAULT` decides what an
+//! upload that does not say gets. When a photograph is watermarked the
+//! unwatermarked original is kept under the private `private/originals/` S3
+//! prefix (see `photograph_ingest`).
+
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont, point};
+use anyhow::anyhow;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage, imageops::FilterType};
+use tracing::info;
+
+use crate::init::app_config::WatermarkSection;
+
+/// Pixel size text marks are rasterized at before being scaled per image.
+const TEXT_RENDER_PX: f32 = 128.0;
+/// Gap between the stamp and the image edges, as a fraction of the short edge.
+const MARGIN_FRACTION: f32 = 0.02;
+/// Below this size the stamp would be illegible; skip it.
+const MIN_STAMP_EDGE: u32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl WatermarkCorner {
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "top_left" | "tl" => Some(Self::TopLeft),
+            "top_right" | "tr" => Some(Self::TopRight),
+            "bottom_left" | "bl" => Some(Self::BottomLeft),
+            "bottom_right" | "br" => Some(Self::BottomRight),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TopLeft => "top_left",
+            Self::TopRight => "top_right",
+            Self::BottomLeft => "bottom_left",
+            Self::BottomRight => "bottom_right",
+        }
+    }
+}
+
+/// Configured watermark settings plus the pre-rendered stamp.
+pub struct WatermarkConfig {
+    stamp: RgbaImage,
+    pub corner: WatermarkCorner,
+    /// Stamp width as a fraction of the image width.
+    pub scale: f32,
+    /// Whether uploads that do not specify `watermark` get one.
+    pub default_enabled: bool,
+}
+
+impl WatermarkConfig {
+    /// Load the watermark from the `[watermark]` section. `Ok(None)` when
+    /// neither a logo nor a text mark is configured; a configured but
+    /// unreadable mark is an error so a typo does not silently publish
+    /// unwatermarked photographs.
+    pub fn from_config(config: &WatermarkSection) -> anyhow::Result<Option<Self>> {
+        let logo_path = config
+            .logo_path
+            .as_ref()
+            .filter(|p| !p.as_os_str().is_empty());
+        let text = config.text.as_ref().filter(|s| !s.trim().is_empty());
+
+        let stamp = match (logo_path, text) {
+            (Some(path), _) => image::open(path)
+                .map_err(|e| anyhow!("Failed to load WATERMARK_LOGO_PATH {}: {e}", path.display()))?
+                .to_rgba8(),
+            (None, Some(text)) => {
+                let font_path = config
+                    .font_path
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("WATERMARK_TEXT requires WATERMARK_FONT_PATH"))?;
+                let font_bytes = std::fs::read(font_path).map_err(|e| {
+                    anyhow!(
+                        "Failed to read WATERMARK_FONT_PATH {}: {e}",
+                        font_path.display()
+                    )
+                })?;
+                let font = FontArc::try_from_vec(font_bytes)
+                    .map_err(|e| anyhow!("Invalid watermark font {}: {e}", font_path.display()))?;
+                render_text_stamp(&font, text)?
+            }
+            (None, None) => return Ok(None),
+        };
+
+        let corner = match &config.position {
+            Some(raw) => WatermarkCorner::from_param(raw)
+                .ok_or_else(|| anyhow!("Invalid WATERMARK_POSITION: {raw}"))?,
+            None => WatermarkCorner::BottomRight,
+        };
+        let opacity = config.opacity;
+        let scale = config.scale;
+        let default_enabled = config.default_enabled;
+
+        let mut stamp = stamp;
+        for pixel in stamp.pixels_mut() {
+            pixel[3] = (f32::from(pixel[3]) * opacity).round() as u8;
+        }
+
+        info!(
+            corner = corner.as_str(),
+            opacity,
+            scale,
+            default_enabled,
+            stamp_width = stamp.width(),
+            stamp_height = stamp.height(),
+            "Photograph watermark configured"
+        );
+
+        Ok(Some(Self {
+            stamp,
+            corner,
+            scale,
+            default_enabled,
+        }))
+    }
+}
+
+/// Rasterize `text` as white glyphs on a transparent background.
+fn render_text_stamp(font: &FontArc, text: &str) -> anyhow::Result<RgbaImage> {
+    let scale = PxScale::from(TEXT_RENDER_PX);
+    let scaled = font.as_scaled(scale);
+
+    let mut glyphs = Vec::with_capacity(text.len());
+    let mut caret = 0.0f32;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(prev) = previous {
+            caret += scaled.kern(prev, id);
+        }
+        glyphs.push(id.with_scale_and_position(scale, point(caret, scaled.ascent())));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+    }
+
+    let width = caret.ceil() as u32;
+    let height = (scaled.ascent() - scaled.descent()).ceil() as u32;
+    if width == 0 || height == 0 {
+        return Err(anyhow!("WATERMARK_TEXT renders to an empty image"));
+    }
+
+    let mut stamp = RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 0]));
+    for glyph in glyphs {
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let x = bounds.min.x as i64 + i64::from(gx);
+                let y = bounds.min.y as i64 + i64::from(gy);
+                if x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+                    let pixel = stamp.get_pixel_mut(x as u32, y as u32);
+                    let alpha = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+                    pixel[3] = pixel[3].max(alpha);
+                }
+            });
+        }
+    }
+
+    Ok(stamp)
+}
+
+/// Blend the stamp onto `img` in the configured corner, sized relative to the
+/// image so every rendition carries a proportionate mark.
+pub fn apply_watermark(img: &mut DynamicImage, config: &WatermarkConfig) {
+    let (width, height) = img.dimensions();
+    let (stamp_w, stamp_h) = config.stamp.dimensions();
+
+    let margin = (width.min(height) as f32 * MARGIN_FRACTION).round() as u32;
+    let max_w = (width as f32 * config.scale).round();
+    let max_h = height.saturating_sub(margin * 2) as f32;
+    let factor = (max_w / stamp_w as f32).min(max_h / stamp_h as f32);
+    let target_w = (stamp_w as f32 * factor).round() as u32;
+    let target_h = (stamp_h as f32 * factor).round() as u32;
+    if target_w < MIN_STAMP_EDGE || target_h < MIN_STAMP_EDGE {
+        return;
+    }
+
+    let stamp = image::imageops::resize(&config.stamp, target_w, target_h, FilterType::Lanczos3);
+
+    let x = match config.corner {
+        WatermarkCorner::TopLeft | WatermarkCorner::BottomLeft => margin,
+        WatermarkCorner::TopRight | WatermarkCorner::BottomRight => {
+            width.saturating_sub(target_w + margin)
+        }
+    };
+    let y = match config.corner {
+        WatermarkCorner::TopLeft | WatermarkCorner::TopRight => margin,
+        WatermarkCorner::BottomLeft | WatermarkCorner::BottomRight => {
+            height.saturating_sub(target_h + margin)
+        }
+    };
+
+    image::imageops::overlay(img, &stamp, i64::from(x), i64::from(y));
+}
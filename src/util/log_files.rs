@@ -0,0 +1,139 @@
+//! Files the daily rolling appender leaves under `LOGS_DIR`
+//! (`<APP_NAME_VERSION>/<APP_NAME_VERSION>.YYYY-MM-DD`) and their compressed
+//! archives, for the COMPRESS_OLD_LOGS job and `/api/admin/logs`.
+
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+/// How deep below `LOGS_DIR` log files are looked for.
+const MAX_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LogCompression {
+    Gzip,
+    /// Archives written before the job switched to gzip.
+    Zstd,
+}
+
+impl LogCompression {
+    fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "gz" => Some(Self::Gzip),
+            "zst" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Gzip => "application/gzip",
+            Self::Zstd => "application/zstd",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogFile {
+    /// Path below `LOGS_DIR`, `/`-separated.
+    pub name: String,
+    pub path: PathBuf,
+    /// The day the appender wrote it, from the file name.
+    pub date: Option<NaiveDate>,
+    pub size_bytes: u64,
+    pub modified_at: Option<DateTime<Utc>>,
+    pub compression: Option<LogCompression>,
+}
+
+impl LogFile {
+    /// Rolled over: written on a day before `today`.
+    pub fn is_archived(&self, today: NaiveDate) -> bool {
+        self.date.is_some_and(|date| date < today)
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self.compression {
+            Some(compression) => compression.content_type(),
+            // The file layer writes JSON lines.
+            None => "application/x-ndjson",
+        }
+    }
+}
+
+/// The day in a `<prefix>.YYYY-MM-DD` name, with or without a `.gz` or
+/// `.zst` suffix.
+pub fn log_file_date(file_name: &str) -> Option<NaiveDate> {
+    let stem = file_name
+        .strip_suffix(".gz")
+        .or_else(|| file_name.strip_suffix(".zst"))
+        .unwrap_or(file_name);
+    let (_, date) = stem.rsplit_once('.')?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// Every file below `dir`, sorted by name. Blocking; call from
+/// `spawn_blocking`.
+pub fn list_log_files(dir: &Path) -> Vec<LogFile> {
+    let mut files: Vec<LogFile> = walkdir::WalkDir::new(dir)
+        .max_depth(MAX_DEPTH)
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::error!(logs_dir = %dir.display(), error = %e, "Error walking logs directory");
+                None
+            }
+        })
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let name = relative_name(dir, entry.path())?;
+            let file_name = entry.file_name().to_str()?;
+            let metadata = entry.metadata().ok()?;
+            Some(LogFile {
+                date: log_file_date(file_name),
+                compression: entry
+                    .path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .and_then(LogCompression::from_extension),
+                size_bytes: metadata.len(),
+                modified_at: metadata.modified().ok().map(DateTime::<Utc>::from),
+                path: entry.into_path(),
+                name,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    files
+}
+
+fn relative_name(dir: &Path, path: &Path) -> Option<String> {
+    let parts = path
+        .strip_prefix(dir)
+        .ok()?
+        .components()
+        .map(|part| match part {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_day_from_rolled_names() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 1);
+        assert_eq!(log_file_date("app-0.1.0.2026-10-01"), day);
+        assert_eq!(log_file_date("app-0.1.0.2026-10-01.gz"), day);
+        assert_eq!(log_file_date("app-0.1.0.2026-10-01.zst"), day);
+        assert_eq!(log_file_date("app-0.1.0"), None);
+        assert_eq!(log_file_date("app.2026-13-01"), None);
+    }
+}
@@ -0,0 +1,133 @@
+//! Minimal Prometheus text exposition (format 0.0.4) for `GET /api/metrics`.
+//!
+//! Counters and gauges are written straight from the atomics that already
+//! exist in `ServerState`; [`Histogram`] covers the distributions.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Cumulative histogram over fixed upper bounds, in seconds.
+pub struct Histogram {
+    bounds: &'static [f64],
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, seconds: f64) {
+        let seconds = seconds.max(0.0);
+        if let Some(i) = self.bounds.iter().position(|bound| seconds <= *bound) {
+            self.buckets[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add((seconds * 1_000_000.0) as u64, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default)]
+pub struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `# HELP` and `# TYPE` lines; call once per metric name.
+    pub fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+        let _ = writeln!(self.out, "# TYPE {name} {kind}");
+    }
+
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        write_labels(&mut self.out, labels, None);
+        let _ = writeln!(self.out, " {value}");
+    }
+
+    pub fn histogram(&mut self, name: &str, labels: &[(&str, &str)], histogram: &Histogram) {
+        let mut cumulative = 0;
+        for (bound, bucket) in histogram.bounds.iter().zip(&histogram.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = write!(self.out, "{name}_bucket");
+            write_labels(&mut self.out, labels, Some(&bound.to_string()));
+            let _ = writeln!(self.out, " {cumulative}");
+        }
+        let count = histogram.count.load(Ordering::Relaxed);
+        let _ = write!(self.out, "{name}_bucket");
+        write_labels(&mut self.out, labels, Some("+Inf"));
+        let _ = writeln!(self.out, " {count}");
+
+        let sum = histogram.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        self.sample(&format!("{name}_sum"), labels, sum);
+        self.sample(&format!("{name}_count"), labels, count as f64);
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn write_labels(out: &mut String, labels: &[(&str, &str)], le: Option<&str>) {
+    if labels.is_empty() && le.is_none() {
+        return;
+    }
+    out.push('{');
+    let mut first = true;
+    for (key, value) in labels.iter().copied().chain(le.map(|le| ("le", le))) {
+        if !first {
+            out.push(',');
+        }
+        first = false;
+        let _ = write!(out, "{key}=\"");
+        for c in value.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '"' => out.push_str("\\\""),
+                '\n' => out.push_str("\\n"),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+    out.push('}');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_cumulative_histogram() {
+        static BOUNDS: [f64; 2] = [0.5, 1.0];
+        let histogram = Histogram::new(&BOUNDS);
+        histogram.observe(0.25);
+        histogram.observe(0.75);
+        histogram.observe(3.0);
+
+        let mut w = MetricsWriter::new();
+        w.histogram("job_seconds", &[("job", "A\"B")], &histogram);
+        assert_eq!(
+            w.finish(),
+            "job_seconds_bucket{job=\"A\\\"B\",le=\"0.5\"} 1\n\
+             job_seconds_bucket{job=\"A\\\"B\",le=\"1\"} 2\n\
+             job_seconds_bucket{job=\"A\\\"B\",le=\"+Inf\"} 3\n\
+             job_seconds_sum{job=\"A\\\"B\"} 4\n\
+             job_seconds_count{job=\"A\\\"B\"} 3\n"
+        );
+    }
+}
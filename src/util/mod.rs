@@ -1,12 +1,20 @@
 pub mod auth;
+pub mod cdn;
 pub mod crypto;
+pub mod db;
 pub mod email;
 pub mod extract;
 pub mod geographic;
+pub mod http;
 pub mod image;
 pub mod init_logger;
+pub mod log_files;
+pub mod metrics;
 pub mod s3;
+pub mod storage;
 pub mod string;
 pub mod system;
 pub mod time;
+pub mod wasm_archive;
 pub mod wasm_bundle;
+pub mod wasm_bundle_cache;
@@ -0,0 +1,196 @@
+//! Local-filesystem implementation of [`StorageBackend`], for development
+//! without AWS credentials.
+//!
+//! Objects live under `LOCAL_STORAGE_ROOT` (default `./storage`) at their key
+//! path and are served back by `GET /storage/{*key}` (private prefixes
+//! excluded). `LOCAL_STORAGE_PUBLIC_URL` (default `/storage`) is the URL prefix
+//! written into rows; set it to an absolute origin when the frontend is served
+//! elsewhere. Presigned URLs are S3-only.
+
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::init::app_config::StorageSection;
+
+use super::{ListedObject, StorageBackend, StoredObject};
+
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalStorage {
+    pub fn from_config(config: &StorageSection) -> anyhow::Result<Self> {
+        let root = config.local_root.clone();
+        std::fs::create_dir_all(&root).map_err(|e| {
+            anyhow!(
+                "Failed to create LOCAL_STORAGE_ROOT {}: {e}",
+                root.display()
+            )
+        })?;
+        let public_url = config
+            .local_public_url
+            .trim()
+            .trim_end_matches('/')
+            .to_string();
+
+        info!(
+            root = %root.display(),
+            public_url = %public_url,
+            "Using local filesystem object storage"
+        );
+
+        Ok(Self { root, public_url })
+    }
+
+    /// Resolve `key` under the root, refusing anything that could escape it.
+    fn path_for(&self, key: &str) -> anyhow::Result<PathBuf> {
+        let relative = Path::new(key.trim_start_matches('/'));
+        let is_plain = relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if key.is_empty() || !is_plain {
+            return Err(anyhow!("Invalid storage key: {key}"));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key.trim_start_matches('/'))
+    }
+
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        let key = url.strip_prefix(&self.public_url)?.strip_prefix('/')?;
+        (!key.is_empty()).then(|| key.to_string())
+    }
+
+    fn serves_objects(&self) -> bool {
+        true
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> anyhow::Result<()> {
+        let path = self.path_for(key)?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        // Write-then-rename so readers never see a partial object.
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".part");
+        tokio::fs::write(&tmp, bytes).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<StoredObject>> {
+        let path = self.path_for(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(StoredObject {
+                bytes,
+                content_type: mime_guess::from_path(&path)
+                    .first()
+                    .map(|m| m.essence_str().to_string()),
+            })),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn content_length(&self, key: &str) -> anyhow::Result<Option<u64>> {
+        match tokio::fs::metadata(self.path_for(key)?).await {
+            Ok(meta) => Ok(Some(meta.len())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        let meta = tokio::fs::metadata(&self.root).await?;
+        if !meta.is_dir() {
+            return Err(anyhow!(
+                "Storage root {} is not a directory",
+                self.root.display()
+            ));
+        }
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> anyhow::Result<Vec<ListedObject>> {
+        let root = self.root.clone();
+        let prefix = prefix.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut objects = Vec::new();
+            for entry in walkdir::WalkDir::new(&root) {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) if e.io_error().map(|io| io.kind()) == Some(ErrorKind::NotFound) => {
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let Ok(relative) = entry.path().strip_prefix(&root) else {
+                    continue;
+                };
+                let key = relative
+                    .components()
+                    .filter_map(|c| c.as_os_str().to_str())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                // In-flight writes (see `put`).
+                if !key.starts_with(&prefix) || key.ends_with(".part") {
+                    continue;
+                }
+                let metadata = entry.metadata()?;
+                objects.push(ListedObject {
+                    key,
+                    size: metadata.len(),
+                    last_modified: metadata.modified().ok().map(chrono::DateTime::from),
+                });
+            }
+            Ok(objects)
+        })
+        .await
+        .map_err(|e| anyhow!("Local storage list task panicked: {e:?}"))?
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.path_for(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn presign_put(
+        &self,
+        _key: &str,
+        _content_type: &str,
+        _expires_in: Duration,
+    ) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "presigned uploads are not supported by the local storage backend"
+        ))
+    }
+
+    async fn presign_get(&self, _key: &str, _expires_in: Duration) -> anyhow::Result<String> {
+        Err(anyhow!(
+            "presigned downloads are not supported by the local storage backend"
+        ))
+    }
+}
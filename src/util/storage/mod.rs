@@ -0,0 +1,158 @@
+//! Object storage for uploaded media (photographs, renditions, originals,
+//! profile pictures, WASM thumbnails).
+//!
+//! Handlers talk to a [`StorageBackend`] on `ServerState::storage` instead of
+//! building S3 clients themselves. Keys are bucket-relative paths
+//! (`images/{id}.avif`, `thumbnails/...`, `private/originals/...`); rows store
+//! the public URL returned by [`StorageBackend::public_url`] and recover the
+//! key with [`StorageBackend::key_from_url`].
+//!
+//! `STORAGE_BACKEND` selects the implementation:
+//! - `s3` (default): [`s3::S3Storage`], bucket `S3_BUCKET` (default
+//!   `cyhdev-img`).
+//! - `local`: [`local::LocalStorage`], for development without AWS.
+
+pub mod local;
+pub mod s3;
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tracing::error;
+
+use crate::init::app_config::StorageSection;
+
+/// Object bytes plus the content type recorded at `put` time.
+pub struct StoredObject {
+    pub bytes: Vec<u8>,
+    pub content_type: Option<String>,
+}
+
+/// One entry of [`StorageBackend::list`].
+pub struct ListedObject {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Short backend name for logs (`s3`, `local`).
+    fn name(&self) -> &'static str;
+
+    /// Public URL clients use to fetch `key`.
+    fn public_url(&self, key: &str) -> String;
+
+    /// Inverse of [`public_url`](Self::public_url); `None` for links this
+    /// backend did not produce.
+    fn key_from_url(&self, url: &str) -> Option<String>;
+
+    /// Whether this server must serve public objects itself (`/storage/...`).
+    fn serves_objects(&self) -> bool {
+        false
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> anyhow::Result<()>;
+
+    /// `Ok(None)` when the object does not exist.
+    async fn get(&self, key: &str) -> anyhow::Result<Option<StoredObject>>;
+
+    /// Size in bytes without fetching the body; `Ok(None)` when missing.
+    async fn content_length(&self, key: &str) -> anyhow::Result<Option<u64>>;
+
+    /// Every object whose key starts with `prefix`.
+    async fn list(&self, prefix: &str) -> anyhow::Result<Vec<ListedObject>>;
+
+    /// Deleting a missing object is not an error.
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+
+    /// Cheap reachability probe for the deep healthcheck (S3 `HeadBucket`,
+    /// or the local root being a readable directory).
+    async fn health_check(&self) -> anyhow::Result<()>;
+
+    /// Best-effort delete of many objects; returns how many were deleted and
+    /// logs individual failures.
+    async fn delete_many(&self, keys: &[String]) -> usize {
+        let mut deleted = 0;
+        for key in keys {
+            match self.delete(key).await {
+                Ok(()) => deleted += 1,
+                Err(e) => error!(
+                    error = ?e,
+                    backend = self.name(),
+                    key = %key,
+                    "Failed to delete stored object"
+                ),
+            }
+        }
+        deleted
+    }
+
+    /// URL a client can `PUT` `key` to directly, valid for `expires_in`.
+    async fn presign_put(
+        &self,
+        key: &str,
+        content_type: &str,
+        expires_in: Duration,
+    ) -> anyhow::Result<String>;
+
+    /// Time-limited URL to `GET` `key` (including private keys).
+    async fn presign_get(&self, key: &str, expires_in: Duration) -> anyhow::Result<String>;
+}
+
+/// Build the backend selected by `storage.backend` (`STORAGE_BACKEND`).
+pub fn storage_from_config(
+    config: &StorageSection,
+    aws_config: &aws_config::SdkConfig,
+) -> anyhow::Result<Arc<dyn StorageBackend>> {
+    match config.backend.trim().to_ascii_lowercase().as_str() {
+        "" | "s3" => Ok(Arc::new(s3::S3Storage::from_config(config, aws_config))),
+        "local" | "fs" | "filesystem" => Ok(Arc::new(local::LocalStorage::from_config(config)?)),
+        other => Err(anyhow!(
+            "Unknown STORAGE_BACKEND: {other} (expected s3 or local)"
+        )),
+    }
+}
+
+/// Canonical form of a key taken from a request path: empty segments (from a
+/// leading, trailing or doubled `/`) are dropped. `None` for a key with no
+/// segments or with a `.` or `..` segment. Prefix checks must run on this
+/// form, since backends resolve `/private/x` and `private//x` to the same
+/// object as `private/x`.
+pub fn normalize_key(key: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    for segment in key.split('/') {
+        match segment {
+            "" => {}
+            "." | ".." => return None,
+            segment => segments.push(segment),
+        }
+    }
+    if segments.is_empty() {
+        return None;
+    }
+    Some(segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_request_keys() {
+        assert_eq!(
+            normalize_key("images/a.avif").as_deref(),
+            Some("images/a.avif")
+        );
+        // `%2Fprivate/x` reaches the handler already decoded.
+        assert_eq!(normalize_key("/private/x").as_deref(), Some("private/x"));
+        assert_eq!(normalize_key("//private/x").as_deref(), Some("private/x"));
+        assert_eq!(normalize_key("private//x/").as_deref(), Some("private/x"));
+        assert_eq!(normalize_key("images/../private/x"), None);
+        assert_eq!(normalize_key("./private/x"), None);
+        assert_eq!(normalize_key(""), None);
+        assert_eq!(normalize_key("//"), None);
+    }
+}
@@ -0,0 +1,272 @@
+//! S3 implementation of [`StorageBackend`].
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use aws_sdk_s3::{
+    error::SdkError,
+    presigning::PresigningConfig,
+    primitives::ByteStream,
+    types::{Delete, ObjectIdentifier},
+};
+use chrono::{DateTime, Utc};
+use tracing::{error, info};
+
+use crate::init::app_config::StorageSection;
+use crate::util::s3::AWS_S3_BUCKET_NAME;
+
+use super::{ListedObject, StorageBackend, StoredObject};
+
+/// `DeleteObjects` accepts at most this many keys per request.
+const DELETE_BATCH_SIZE: usize = 1000;
+
+pub struct S3Storage {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+    region: String,
+}
+
+impl S3Storage {
+    /// Bucket from `storage.s3_bucket` (default `cyhdev-img`), region from
+    /// the shared AWS config.
+    pub fn from_config(config: &StorageSection, aws_config: &aws_config::SdkConfig) -> Self {
+        let bucket = match config.s3_bucket.trim() {
+            "" => AWS_S3_BUCKET_NAME.to_string(),
+            bucket => bucket.to_string(),
+        };
+        let region = aws_config
+            .region()
+            .map(|r| r.to_string())
+            .unwrap_or_else(|| "us-west-1".to_string());
+
+        info!(bucket = %bucket, region = %region, "Using S3 object storage");
+
+        Self {
+            client: aws_sdk_s3::Client::new(aws_config),
+            bucket,
+            region,
+        }
+    }
+
+    fn host(&self) -> String {
+        format!("{}.s3.{}.amazonaws.com", self.bucket, self.region)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("https://{}/{key}", self.host())
+    }
+
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        let url = reqwest::Url::parse(url).ok()?;
+        if url.host_str()? != self.host() {
+            return None;
+        }
+        let key = url.path().trim_start_matches('/');
+        (!key.is_empty()).then(|| key.to_string())
+    }
+
+    #[tracing::instrument(name = "s3.put", skip_all, fields(otel.kind = "client", key = %key))]
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> anyhow::Result<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(content_type)
+            .body(ByteStream::from(bytes))
+            .send()
+            .await
+            .map_err(|e| anyhow!("S3 put {key} failed: {e}"))?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "s3.get", skip_all, fields(otel.kind = "client", key = %key))]
+    async fn get(&self, key: &str) -> anyhow::Result<Option<StoredObject>> {
+        let object = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(object) => object,
+            Err(SdkError::ServiceError(e)) if e.err().is_no_such_key() => return Ok(None),
+            Err(e) => return Err(anyhow!("S3 get {key} failed: {e}")),
+        };
+
+        let content_type = object.content_type().map(str::to_owned);
+        let bytes = object
+            .body
+            .collect()
+            .await
+            .map_err(|e| anyhow!("S3 get {key} body read failed: {e}"))?
+            .into_bytes()
+            .to_vec();
+
+        Ok(Some(StoredObject {
+            bytes,
+            content_type,
+        }))
+    }
+
+    #[tracing::instrument(name = "s3.head", skip_all, fields(otel.kind = "client", key = %key))]
+    async fn content_length(&self, key: &str) -> anyhow::Result<Option<u64>> {
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(head) => Ok(Some(head.content_length().unwrap_or(0).max(0) as u64)),
+            Err(SdkError::ServiceError(e)) if e.err().is_not_found() => Ok(None),
+            Err(e) => Err(anyhow!("S3 head {key} failed: {e}")),
+        }
+    }
+
+    #[tracing::instrument(name = "s3.head_bucket", skip_all, fields(otel.kind = "client"))]
+    async fn health_check(&self) -> anyhow::Result<()> {
+        self.client
+            .head_bucket()
+            .bucket(&self.bucket)
+            .send()
+            .await
+            .map_err(|e| anyhow!("S3 head bucket {} failed: {e}", self.bucket))?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "s3.list", skip_all, fields(otel.kind = "client", prefix = %prefix))]
+    async fn list(&self, prefix: &str) -> anyhow::Result<Vec<ListedObject>> {
+        let mut objects = Vec::new();
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(prefix)
+            .into_paginator()
+            .send();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| anyhow!("S3 list {prefix} failed: {e}"))?;
+            objects.extend(page.contents().iter().filter_map(|object| {
+                Some(ListedObject {
+                    key: object.key()?.to_string(),
+                    size: object.size().unwrap_or(0).max(0) as u64,
+                    last_modified: object
+                        .last_modified()
+                        .and_then(|t| DateTime::<Utc>::from_timestamp(t.secs(), t.subsec_nanos())),
+                })
+            }));
+        }
+        Ok(objects)
+    }
+
+    #[tracing::instrument(name = "s3.delete", skip_all, fields(otel.kind = "client", key = %key))]
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| anyhow!("S3 delete {key} failed: {e}"))?;
+        Ok(())
+    }
+
+    /// Batched `DeleteObjects` instead of one request per key.
+    #[tracing::instrument(name = "s3.delete_many", skip_all, fields(otel.kind = "client", keys = keys.len()))]
+    async fn delete_many(&self, keys: &[String]) -> usize {
+        let mut total_deleted = 0usize;
+
+        for chunk in keys.chunks(DELETE_BATCH_SIZE) {
+            let mut identifiers: Vec<ObjectIdentifier> = Vec::with_capacity(chunk.len());
+            for k in chunk {
+                match ObjectIdentifier::builder().key(k).build() {
+                    Ok(obj_id) => identifiers.push(obj_id),
+                    Err(e) => {
+                        error!(
+                            key = %k,
+                            error = %e,
+                            "Failed to build S3 ObjectIdentifier; skipping key"
+                        );
+                    }
+                }
+            }
+            if identifiers.is_empty() {
+                continue;
+            }
+
+            let delete = match Delete::builder().set_objects(Some(identifiers)).build() {
+                Ok(d) => d,
+                Err(e) => {
+                    error!(error = %e, "Failed to build S3 Delete request; skipping batch");
+                    continue;
+                }
+            };
+
+            match self
+                .client
+                .delete_objects()
+                .bucket(&self.bucket)
+                .set_delete(Some(delete))
+                .send()
+                .await
+            {
+                Ok(output) => {
+                    total_deleted += output.deleted().len();
+                    for err in output.errors() {
+                        error!(
+                            key = ?err.key(),
+                            code = ?err.code(),
+                            message = ?err.message(),
+                            "Failed to delete S3 object"
+                        );
+                    }
+                }
+                Err(e) => {
+                    error!(error = %e, bucket = %self.bucket, "S3 batch deletion failed");
+                }
+            }
+        }
+
+        total_deleted
+    }
+
+    async fn presign_put(
+        &self,
+        key: &str,
+        content_type: &str,
+        expires_in: Duration,
+    ) -> anyhow::Result<String> {
+        let presigned = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(content_type)
+            .presigned(PresigningConfig::expires_in(expires_in)?)
+            .await
+            .map_err(|e| anyhow!("S3 presign put {key} failed: {e}"))?;
+        Ok(presigned.uri().to_string())
+    }
+
+    async fn presign_get(&self, key: &str, expires_in: Duration) -> anyhow::Result<String> {
+        let presigned = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .presigned(PresigningConfig::expires_in(expires_in)?)
+            .await
+            .map_err(|e| anyhow!("S3 presign get {key} failed: {e}"))?;
+        Ok(presigned.uri().to_string())
+    }
+}
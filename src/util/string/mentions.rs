@@ -0,0 +1,51 @@
+/// Most mentions honored in one comment, so a comment cannot ping everyone.
+pub const MAX_MENTIONS: usize = 10;
+
+/// Distinct `@user_name` mentions in `text`, in order of appearance. A user
+/// name is 1-20 alphanumeric characters (see `validate_username`); an `@`
+/// preceded by an alphanumeric character (an email address) is not a mention.
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let starts_mention = c == '@' && !previous.is_some_and(char::is_alphanumeric);
+        previous = Some(c);
+        if !starts_mention {
+            continue;
+        }
+
+        let mut name = String::new();
+        while let Some(&next) = chars.peek() {
+            if !next.is_alphanumeric() {
+                break;
+            }
+            name.push(next);
+            previous = Some(next);
+            chars.next();
+        }
+        if (1..=20).contains(&name.chars().count()) && !mentions.contains(&name) {
+            mentions.push(name);
+            if mentions.len() == MAX_MENTIONS {
+                break;
+            }
+        }
+    }
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_distinct_mentions() {
+        assert_eq!(
+            extract_mentions("@alice thanks, cc @bob and @alice. mail me@example.com"),
+            vec!["alice".to_string(), "bob".to_string()]
+        );
+        assert_eq!(extract_mentions("@ nobody @"), Vec::<String>::new());
+        assert_eq!(extract_mentions("(@홍길동)"), vec!["홍길동".to_string()]);
+    }
+}
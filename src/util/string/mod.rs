@@ -1,2 +1,3 @@
 pub mod generate_slug;
+pub mod mentions;
 pub mod validations;
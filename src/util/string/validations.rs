@@ -32,3 +32,39 @@ pub fn validate_password_form(password: &str) -> bool {
 
     has_lowercase && has_uppercase && has_ascii_digit
 }
+
+/// `validator` rules over the checks above, for `#[validate(custom(...))]`.
+pub mod rules {
+    use validator::ValidationError;
+
+    fn invalid(code: &'static str, message: &'static str) -> ValidationError {
+        ValidationError::new(code).with_message(message.into())
+    }
+
+    pub fn user_name(value: &str) -> Result<(), ValidationError> {
+        if super::validate_username(value) {
+            Ok(())
+        } else {
+            Err(invalid("user_name", "must be 1 to 20 letters or digits"))
+        }
+    }
+
+    pub fn password(value: &str) -> Result<(), ValidationError> {
+        if super::validate_password_form(value) {
+            Ok(())
+        } else {
+            Err(invalid(
+                "password",
+                "must be at least 8 characters with a lowercase letter, an uppercase letter and a digit",
+            ))
+        }
+    }
+
+    pub fn email(value: &str) -> Result<(), ValidationError> {
+        if email_address::EmailAddress::is_valid(value) {
+            Ok(())
+        } else {
+            Err(invalid("email", "must be a valid email address"))
+        }
+    }
+}
@@ -0,0 +1,153 @@
+/// Space on one mounted filesystem.
+#[derive(Debug, Clone)]
+pub struct DiskUsage {
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    /// What an unprivileged process can still write.
+    pub available_bytes: u64,
+}
+
+/// Filesystems that hold no disk space of their own.
+#[cfg(target_os = "linux")]
+const VIRTUAL_FS_TYPES: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "securityfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
+
+/// Usage of every real filesystem this process can see, one entry per mount
+/// point. Blocking; call from `spawn_blocking`.
+pub fn get_disk_usage() -> Vec<DiskUsage> {
+    platform_disk_usage()
+}
+
+#[cfg(target_os = "linux")]
+// The statvfs field widths vary by target; the casts are no-ops only on 64-bit.
+#[allow(clippy::unnecessary_cast)]
+fn platform_disk_usage() -> Vec<DiskUsage> {
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+
+    let mounts = match std::fs::read_to_string("/proc/self/mounts") {
+        Ok(mounts) => mounts,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to read /proc/self/mounts");
+            return Vec::new();
+        }
+    };
+
+    let mut disks: Vec<DiskUsage> = Vec::new();
+    for (mount_point, fs_type) in parse_mounts(&mounts) {
+        if VIRTUAL_FS_TYPES.contains(&fs_type) || disks.iter().any(|d| d.mount_point == mount_point)
+        {
+            continue;
+        }
+        let Ok(path) = CString::new(mount_point.as_str()) else {
+            continue;
+        };
+        let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+        if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+            continue;
+        }
+        let stat = unsafe { stat.assume_init() };
+        let block = stat.f_frsize as u64;
+        let total_bytes = stat.f_blocks as u64 * block;
+        if total_bytes == 0 {
+            continue;
+        }
+        disks.push(DiskUsage {
+            mount_point,
+            total_bytes,
+            used_bytes: total_bytes.saturating_sub(stat.f_bfree as u64 * block),
+            available_bytes: stat.f_bavail as u64 * block,
+        });
+    }
+    disks
+}
+
+#[cfg(not(target_os = "linux"))]
+fn platform_disk_usage() -> Vec<DiskUsage> {
+    Vec::new()
+}
+
+/// `(mount point, filesystem type)` per line of a `/proc/mounts` table, with
+/// the octal escapes (`\040` for a space) in mount points undone.
+#[cfg(target_os = "linux")]
+fn parse_mounts(table: &str) -> Vec<(String, &str)> {
+    table
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?;
+            let fs_type = fields.next()?;
+            Some((unescape_mount_point(mount_point), fs_type))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn unescape_mount_point(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && let Some(code) = raw
+                .get(i + 1..i + 4)
+                .and_then(|octal| u8::from_str_radix(octal, 8).ok())
+        {
+            out.push(code);
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_unescapes_mount_table() {
+        let table = "/dev/sda1 / ext4 rw,relatime 0 0\n\
+                     proc /proc proc rw 0 0\n\
+                     /dev/sdb1 /mnt/my\\040disk xfs rw 0 0\n";
+        assert_eq!(
+            parse_mounts(table),
+            vec![
+                ("/".to_string(), "ext4"),
+                ("/proc".to_string(), "proc"),
+                ("/mnt/my disk".to_string(), "xfs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_disk_usage() {
+        for disk in get_disk_usage() {
+            assert!(disk.used_bytes <= disk.total_bytes, "{disk:?}");
+        }
+    }
+}
@@ -0,0 +1,18 @@
+/// 1, 5 and 15 minute load averages, or zeros where the platform has none.
+pub fn get_load_average() -> [f64; 3] {
+    #[cfg(target_os = "linux")]
+    {
+        let mut loads = [0.0f64; 3];
+        if unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) } == 3 {
+            loads
+        } else {
+            tracing::error!("Failed to query load average with getloadavg");
+            [0.0; 3]
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        [0.0; 3]
+    }
+}
@@ -0,0 +1,66 @@
+/// Bytes received and sent since boot, summed over every interface but
+/// loopback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetworkBytes {
+    pub rx: u64,
+    pub tx: u64,
+}
+
+/// Blocking; call from `spawn_blocking`. `None` where the counters cannot be
+/// read.
+pub fn get_network_bytes() -> Option<NetworkBytes> {
+    #[cfg(target_os = "linux")]
+    {
+        match std::fs::read_to_string("/proc/net/dev") {
+            Ok(table) => Some(parse_net_dev(&table)),
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to read /proc/net/dev");
+                None
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Sum `/proc/net/dev`: two header lines, then
+/// `iface: rx_bytes rx_packets ... (8 rx fields) tx_bytes ...`.
+#[cfg(target_os = "linux")]
+fn parse_net_dev(table: &str) -> NetworkBytes {
+    let mut total = NetworkBytes::default();
+    for line in table.lines().skip(2) {
+        let Some((iface, counters)) = line.split_once(':') else {
+            continue;
+        };
+        if iface.trim() == "lo" {
+            continue;
+        }
+        let counters: Vec<u64> = counters
+            .split_whitespace()
+            .filter_map(|field| field.parse().ok())
+            .collect();
+        if let (Some(rx), Some(tx)) = (counters.first(), counters.get(8)) {
+            total.rx = total.rx.saturating_add(*rx);
+            total.tx = total.tx.saturating_add(*tx);
+        }
+    }
+    total
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_interfaces_except_loopback() {
+        let table = "Inter-|   Receive                                                |  Transmit\n \
+             face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n    \
+             lo: 5000 10 0 0 0 0 0 0 5000 10 0 0 0 0 0 0\n  \
+             eth0: 1200 3 0 0 0 0 0 0 800 2 0 0 0 0 0 0\n  \
+             eth1: 300 1 0 0 0 0 0 0 200 1 0 0 0 0 0 0\n";
+        assert_eq!(parse_net_dev(table), NetworkBytes { rx: 1500, tx: 1000 });
+    }
+}
@@ -0,0 +1,49 @@
+/// File descriptors this process has open and its soft `RLIMIT_NOFILE`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenFds {
+    pub open: u64,
+    pub limit: u64,
+}
+
+/// Blocking; call from `spawn_blocking`. Zeros where the platform has no
+/// `/proc/self/fd`.
+pub fn get_open_fds() -> OpenFds {
+    #[cfg(target_os = "linux")]
+    {
+        let open = match std::fs::read_dir("/proc/self/fd") {
+            // Includes the descriptor read_dir itself holds.
+            Ok(entries) => entries.count().saturating_sub(1) as u64,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to read /proc/self/fd");
+                0
+            }
+        };
+        let mut rlimit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        let limit = if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut rlimit) } == 0 {
+            rlimit.rlim_cur
+        } else {
+            0
+        };
+        OpenFds { open, limit }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        OpenFds::default()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::get_open_fds;
+
+    #[test]
+    fn test_get_open_fds() {
+        let fds = get_open_fds();
+        assert!(fds.open >= 3, "stdin/stdout/stderr at least: {fds:?}");
+        assert!(fds.open <= fds.limit);
+    }
+}
@@ -1,4 +1,8 @@
 pub mod get_cpu_usage;
+pub mod get_disk_usage;
+pub mod get_load_average;
 pub mod get_memory_size;
 pub mod get_memory_usage;
+pub mod get_network_bytes;
+pub mod get_open_fds;
 pub mod get_process_memory_usage;
@@ -0,0 +1,226 @@
+//! Zip uploads of multi-file WASM modules (wasm + JS glue + assets).
+//!
+//! The archive is unpacked in memory with limits on entry count and total
+//! unpacked size. Paths are normalized to relative, `/`-separated form; a
+//! single top-level directory shared by every entry (as produced by zipping a
+//! build folder) is stripped. The entry page is `index.html`, or the only
+//! root-level `.html` file.
+
+use std::io::{Cursor, Read};
+
+use anyhow::anyhow;
+
+use crate::util::wasm_bundle::{NormalizedBundle, normalize_bundle_bytes};
+
+/// Upper bound on the unpacked size of all files together.
+pub const MAX_UNPACKED_ARCHIVE_BYTES: usize = 200 * 1024 * 1024;
+pub const MAX_ARCHIVE_FILES: usize = 2000;
+/// Matches `wasm_module_files.wasm_module_file_path`.
+pub const MAX_ARCHIVE_PATH_LEN: usize = 512;
+
+pub struct ArchiveFile {
+    pub path: String,
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+pub struct UnpackedArchive {
+    pub files: Vec<ArchiveFile>,
+    /// Path of the entry page within `files`.
+    pub entry: String,
+}
+
+impl UnpackedArchive {
+    pub fn entry_file(&self) -> Option<&ArchiveFile> {
+        self.files.iter().find(|f| f.path == self.entry)
+    }
+}
+
+pub fn is_zip_archive(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04")
+}
+
+/// Relative, `/`-separated form of an archive or request path; `None` for
+/// anything that could escape the module (`..`, absolute paths, drive
+/// letters) or is not a plain file name.
+pub fn normalize_archive_path(raw: &str) -> Option<String> {
+    let raw = raw.replace('\\', "/");
+    if raw.starts_with('/') {
+        return None;
+    }
+    let mut parts = Vec::new();
+    for part in raw.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return None,
+            part if part.contains(':') || part.chars().any(char::is_control) => return None,
+            part => parts.push(part),
+        }
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    let path = parts.join("/");
+    (path.len() <= MAX_ARCHIVE_PATH_LEN).then_some(path)
+}
+
+/// Archive metadata that is never served.
+fn is_junk(path: &str) -> bool {
+    path.starts_with("__MACOSX/")
+        || path
+            .rsplit('/')
+            .next()
+            .is_some_and(|name| name == ".DS_Store" || name == "Thumbs.db")
+}
+
+pub fn content_type_for_path(path: &str) -> String {
+    if path.ends_with(".html") || path.ends_with(".htm") {
+        return "text/html; charset=utf-8".to_string();
+    }
+    mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .essence_str()
+        .to_string()
+}
+
+pub fn unpack_bundle_archive(data: &[u8]) -> anyhow::Result<UnpackedArchive> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))?;
+    if archive.len() > MAX_ARCHIVE_FILES {
+        return Err(anyhow!("Archive has more than {MAX_ARCHIVE_FILES} entries"));
+    }
+
+    let mut files: Vec<ArchiveFile> = Vec::new();
+    let mut total = 0usize;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let path = normalize_archive_path(entry.name())
+            .ok_or_else(|| anyhow!("Invalid path in archive: {}", entry.name()))?;
+        if is_junk(&path) {
+            continue;
+        }
+
+        // Declared sizes can lie, so the read itself is bounded.
+        let remaining = MAX_UNPACKED_ARCHIVE_BYTES - total;
+        let mut bytes = Vec::new();
+        (&mut entry)
+            .take(remaining as u64 + 1)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() > remaining {
+            return Err(anyhow!(
+                "Archive unpacks to more than {MAX_UNPACKED_ARCHIVE_BYTES} bytes"
+            ));
+        }
+        total += bytes.len();
+
+        if files.iter().any(|f| f.path == path) {
+            return Err(anyhow!("Duplicate path in archive: {path}"));
+        }
+        files.push(ArchiveFile {
+            content_type: content_type_for_path(&path),
+            path,
+            bytes,
+        });
+    }
+
+    strip_common_root(&mut files);
+
+    let entry = if files.iter().any(|f| f.path == "index.html") {
+        "index.html".to_string()
+    } else {
+        let mut root_pages = files
+            .iter()
+            .filter(|f| !f.path.contains('/') && f.content_type.starts_with("text/html"));
+        match (root_pages.next(), root_pages.next()) {
+            (Some(page), None) => page.path.clone(),
+            _ => return Err(anyhow!("Archive needs an index.html entry page")),
+        }
+    };
+
+    Ok(UnpackedArchive { files, entry })
+}
+
+/// Normalize an uploaded bundle. A zip is unpacked and its entry page becomes
+/// the module's regular bundle; anything else goes through
+/// [`normalize_bundle_bytes`].
+pub fn prepare_uploaded_bundle(
+    data: &[u8],
+    is_zip: bool,
+    is_gzipped: bool,
+    is_html: bool,
+    max_decompressed_size: usize,
+) -> anyhow::Result<(NormalizedBundle, Option<UnpackedArchive>)> {
+    if !is_zip {
+        let bundle = normalize_bundle_bytes(data, is_gzipped, is_html, max_decompressed_size)?;
+        return Ok((bundle, None));
+    }
+    let archive = unpack_bundle_archive(data)?;
+    let entry = archive
+        .entry_file()
+        .ok_or_else(|| anyhow!("Archive entry page is missing"))?;
+    let bundle = normalize_bundle_bytes(&entry.bytes, false, true, max_decompressed_size)?;
+    Ok((bundle, Some(archive)))
+}
+
+/// Drop a leading directory every file shares (`dist/index.html` ->
+/// `index.html`).
+fn strip_common_root(files: &mut [ArchiveFile]) {
+    let Some(root) = files
+        .first()
+        .and_then(|f| f.path.split_once('/'))
+        .map(|(root, _)| format!("{root}/"))
+    else {
+        return;
+    };
+    if files.iter().all(|f| f.path.starts_with(&root)) {
+        for file in files {
+            file.path = file.path[root.len()..].to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_relative_paths() {
+        assert_eq!(
+            normalize_archive_path("dist/./pkg//app.wasm").as_deref(),
+            Some("dist/pkg/app.wasm")
+        );
+        assert_eq!(
+            normalize_archive_path("assets\\logo.png").as_deref(),
+            Some("assets/logo.png")
+        );
+    }
+
+    #[test]
+    fn rejects_escaping_paths() {
+        assert_eq!(normalize_archive_path("../etc/passwd"), None);
+        assert_eq!(normalize_archive_path("a/../../b"), None);
+        assert_eq!(normalize_archive_path("/abs/path"), None);
+        assert_eq!(normalize_archive_path("C:/windows"), None);
+        assert_eq!(normalize_archive_path(""), None);
+        assert_eq!(normalize_archive_path("./"), None);
+    }
+
+    #[test]
+    fn strips_shared_root_directory() {
+        let file = |path: &str| ArchiveFile {
+            path: path.to_string(),
+            bytes: Vec::new(),
+            content_type: content_type_for_path(path),
+        };
+        let mut files = vec![file("dist/index.html"), file("dist/pkg/app.wasm")];
+        strip_common_root(&mut files);
+        assert_eq!(files[0].path, "index.html");
+        assert_eq!(files[1].path, "pkg/app.wasm");
+
+        let mut files = vec![file("dist/index.html"), file("other.js")];
+        strip_common_root(&mut files);
+        assert_eq!(files[0].path, "dist/index.html");
+    }
+}
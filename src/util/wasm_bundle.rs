@@ -1,14 +1,77 @@
 use std::io::{Read, Write};
+use std::sync::Arc;
 
 use anyhow::anyhow;
+use axum::body::Bytes;
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
 use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use sha1::{Digest, Sha1};
+use sha2::Sha384;
 
 pub const HTML_CONTENT_TYPE: &str = "text/html; charset=utf-8";
 pub const WASM_CONTENT_TYPE: &str = "application/wasm";
 
+/// Brotli window for stored bundles: 4 MiB, which every mainstream browser
+/// decodes.
+const BROTLI_LGWIN: u32 = 22;
+const BROTLI_QUALITY: u32 = 11;
+
 pub struct NormalizedBundle {
     pub gz_bytes: Vec<u8>,
+    pub br_bytes: Vec<u8>,
     pub content_type: &'static str,
+    /// See [`bundle_integrity`].
+    pub integrity: String,
+}
+
+/// A bundle held in memory (see `util::wasm_bundle_cache`). Cloning shares
+/// the bytes.
+#[derive(Clone)]
+pub struct CachedWasmBundle {
+    pub bytes: Bytes,
+    pub is_gzipped: bool,
+    pub content_type: &'static str,
+    /// Strong ETag of the gzip representation (see [`bundle_etag`]).
+    pub etag: Arc<str>,
+    /// Brotli copy, for bundles uploaded since it was introduced.
+    pub brotli: Option<BrotliWasmBundle>,
+    /// SRI value recorded at upload, if any.
+    pub integrity: Option<Arc<str>>,
+}
+
+impl CachedWasmBundle {
+    /// Bytes held across both encodings.
+    pub fn memory_len(&self) -> usize {
+        self.bytes.len() + self.brotli.as_ref().map_or(0, |br| br.bytes.len())
+    }
+}
+
+#[derive(Clone)]
+pub struct BrotliWasmBundle {
+    pub bytes: Bytes,
+    /// Strong ETag of the Brotli bytes; differs from the gzip one.
+    pub etag: Arc<str>,
+}
+
+/// Quoted strong ETag derived from the stored (gzipped) bundle bytes. The
+/// identity representation appends `-identity` inside the quotes, since the
+/// two encodings are different bytes.
+pub fn bundle_etag(gz_bytes: &[u8]) -> String {
+    format!("\"{}\"", URL_SAFE_NO_PAD.encode(Sha1::digest(gz_bytes)))
+}
+
+/// Subresource Integrity value (`sha384-<base64>`) of the decoded bundle,
+/// which is what a browser checks whatever the transfer encoding.
+pub fn bundle_integrity(raw_bytes: &[u8]) -> String {
+    format!("sha384-{}", STANDARD.encode(Sha384::digest(raw_bytes)))
+}
+
+/// ETag of the decompressed representation of a bundle tagged `gzip_etag`.
+pub fn identity_etag(gzip_etag: &str) -> String {
+    format!("{}-identity\"", gzip_etag.trim_end_matches('"'))
 }
 
 pub fn looks_like_html(data: &[u8]) -> bool {
@@ -42,6 +105,13 @@ pub fn gzip_compress_max(data: &[u8]) -> anyhow::Result<Vec<u8>> {
     Ok(encoder.finish()?)
 }
 
+pub fn brotli_compress_max(data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut encoder =
+        brotli::CompressorWriter::new(Vec::new(), 64 * 1024, BROTLI_QUALITY, BROTLI_LGWIN);
+    encoder.write_all(data)?;
+    Ok(encoder.into_inner())
+}
+
 pub fn gzip_decompress_limited(data: &[u8], max_size: usize) -> anyhow::Result<Vec<u8>> {
     let mut decoder = GzDecoder::new(data);
     let mut out = Vec::new();
@@ -87,6 +157,8 @@ pub fn normalize_bundle_bytes(
     }
 
     let gz_bytes = gzip_compress_max(&raw_bytes)?;
+    let br_bytes = brotli_compress_max(&raw_bytes)?;
+    let integrity = bundle_integrity(&raw_bytes);
     let content_type = if is_html {
         HTML_CONTENT_TYPE
     } else {
@@ -95,7 +167,9 @@ pub fn normalize_bundle_bytes(
 
     Ok(NormalizedBundle {
         gz_bytes,
+        br_bytes,
         content_type,
+        integrity,
     })
 }
 
@@ -0,0 +1,328 @@
+//! Size-bounded cache of WASM bundles for `serve_wasm`.
+//!
+//! Hot bundles stay in memory as [`Bytes`], so serving one shares the buffer
+//! instead of copying it. Once the in-memory total passes
+//! `WASM_MODULE_CACHE_MAX_BYTES` (default 256 MiB), the least recently served
+//! bundles are evicted. With the disk tier on (`WASM_MODULE_DISK_CACHE` =
+//! `on` (default) | `off`, directory `WASM_MODULE_CACHE_DIR`, default
+//! `./cache/wasm-bundles`), evicted and oversized bundles are spilled there and
+//! streamed from the file; otherwise the next request re-reads the DB row.
+//!
+//! A bundle's Brotli copy, when it has one, is cached, counted, evicted and
+//! spilled together with the gzip bytes.
+//!
+//! The disk tier only lives as long as the process: it is wiped when the cache
+//! is rebuilt at startup.
+
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+use axum::body::Bytes;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::init::app_config::WasmSection;
+use crate::util::wasm_bundle::CachedWasmBundle;
+
+/// A bundle evicted to the disk tier.
+#[derive(Clone)]
+pub struct SpilledWasmBundle {
+    pub path: PathBuf,
+    pub len: u64,
+    pub is_gzipped: bool,
+    pub content_type: &'static str,
+    pub etag: Arc<str>,
+    pub brotli: Option<SpilledBrotliBundle>,
+    pub integrity: Option<Arc<str>>,
+}
+
+/// The spilled Brotli copy of a bundle.
+#[derive(Clone)]
+pub struct SpilledBrotliBundle {
+    pub path: PathBuf,
+    pub len: u64,
+    pub etag: Arc<str>,
+}
+
+/// Where a cached bundle is served from.
+pub enum WasmBundleSource {
+    Memory(CachedWasmBundle),
+    Disk(SpilledWasmBundle),
+}
+
+impl WasmBundleSource {
+    pub fn is_gzipped(&self) -> bool {
+        match self {
+            Self::Memory(b) => b.is_gzipped,
+            Self::Disk(b) => b.is_gzipped,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Memory(b) => b.content_type,
+            Self::Disk(b) => b.content_type,
+        }
+    }
+
+    pub fn etag(&self) -> &str {
+        match self {
+            Self::Memory(b) => &b.etag,
+            Self::Disk(b) => &b.etag,
+        }
+    }
+
+    pub fn brotli_etag(&self) -> Option<&str> {
+        match self {
+            Self::Memory(b) => b.brotli.as_ref().map(|br| &*br.etag),
+            Self::Disk(b) => b.brotli.as_ref().map(|br| &*br.etag),
+        }
+    }
+
+    pub fn integrity(&self) -> Option<&str> {
+        match self {
+            Self::Memory(b) => b.integrity.as_deref(),
+            Self::Disk(b) => b.integrity.as_deref(),
+        }
+    }
+
+    /// Stored size in bytes.
+    pub fn stored_len(&self) -> u64 {
+        match self {
+            Self::Memory(b) => b.bytes.len() as u64,
+            Self::Disk(b) => b.len,
+        }
+    }
+
+    /// The whole stored body (reads the file for spilled bundles).
+    pub async fn read_all(&self) -> std::io::Result<Bytes> {
+        match self {
+            Self::Memory(b) => Ok(b.bytes.clone()),
+            Self::Disk(b) => tokio::fs::read(&b.path).await.map(Bytes::from),
+        }
+    }
+}
+
+struct MemoryEntry {
+    bundle: CachedWasmBundle,
+    last_used: AtomicU64,
+}
+
+pub struct WasmBundleCache {
+    memory: scc::HashMap<Uuid, MemoryEntry>,
+    disk: scc::HashMap<Uuid, SpilledWasmBundle>,
+    memory_bytes: AtomicUsize,
+    /// Logical clock for LRU ordering.
+    clock: AtomicU64,
+    max_memory_bytes: usize,
+    disk_dir: Option<PathBuf>,
+}
+
+impl WasmBundleCache {
+    /// `cache_max_bytes`, `disk_cache` and `cache_dir` of the `[wasm]`
+    /// section (see the module docs).
+    pub fn from_config(config: &WasmSection) -> Self {
+        let max_memory_bytes = config.cache_max_bytes;
+        let disk_dir = config.disk_cache.then(|| config.cache_dir.clone());
+
+        info!(max_memory_bytes, disk_dir = ?disk_dir, "WASM bundle cache configured");
+
+        Self {
+            memory: scc::HashMap::new(),
+            disk: scc::HashMap::new(),
+            memory_bytes: AtomicUsize::new(0),
+            clock: AtomicU64::new(0),
+            max_memory_bytes,
+            disk_dir,
+        }
+    }
+
+    /// Bytes currently held in memory.
+    pub fn memory_bytes(&self) -> usize {
+        self.memory_bytes.load(Ordering::Relaxed)
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub async fn get(&self, wasm_module_id: Uuid) -> Option<WasmBundleSource> {
+        let now = self.tick();
+        if let Some(bundle) = self
+            .memory
+            .read_async(&wasm_module_id, |_, entry| {
+                entry.last_used.store(now, Ordering::Relaxed);
+                entry.bundle.clone()
+            })
+            .await
+        {
+            return Some(WasmBundleSource::Memory(bundle));
+        }
+
+        let spilled = self
+            .disk
+            .read_async(&wasm_module_id, |_, v| v.clone())
+            .await?;
+        // Someone may have cleared the directory under us.
+        let brotli_present = match &spilled.brotli {
+            Some(br) => tokio::fs::metadata(&br.path).await.is_ok(),
+            None => true,
+        };
+        if brotli_present && tokio::fs::metadata(&spilled.path).await.is_ok() {
+            Some(WasmBundleSource::Disk(spilled))
+        } else {
+            self.remove_spilled(wasm_module_id).await;
+            None
+        }
+    }
+
+    /// Cache `bundle`, evicting least recently served bundles to stay under
+    /// the memory bound. A bundle larger than the whole bound goes straight to
+    /// the disk tier.
+    pub async fn insert(&self, wasm_module_id: Uuid, bundle: CachedWasmBundle) {
+        self.remove_spilled(wasm_module_id).await;
+
+        let len = bundle.memory_len();
+        if len > self.max_memory_bytes {
+            self.remove_memory(wasm_module_id).await;
+            self.spill(wasm_module_id, bundle).await;
+            return;
+        }
+
+        let entry = MemoryEntry {
+            bundle,
+            last_used: AtomicU64::new(self.tick()),
+        };
+        self.memory_bytes.fetch_add(len, Ordering::Relaxed);
+        if let Some(old) = self.memory.upsert_async(wasm_module_id, entry).await {
+            self.memory_bytes
+                .fetch_sub(old.bundle.memory_len(), Ordering::Relaxed);
+        }
+        self.evict_to_capacity().await;
+    }
+
+    /// Drop a module from both tiers.
+    pub async fn remove(&self, wasm_module_id: Uuid) {
+        self.remove_memory(wasm_module_id).await;
+        self.remove_spilled(wasm_module_id).await;
+    }
+
+    /// Empty the disk tier, including files left by a previous process.
+    pub async fn reset_disk(&self) {
+        self.disk.retain_async(|_, _| false).await;
+        let Some(dir) = &self.disk_dir else {
+            return;
+        };
+        match tokio::fs::remove_dir_all(dir).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!(error = ?e, dir = %dir.display(), "Failed to clear WASM bundle disk cache")
+            }
+        }
+    }
+
+    async fn evict_to_capacity(&self) {
+        while self.memory_bytes() > self.max_memory_bytes {
+            let mut oldest: Option<(Uuid, u64)> = None;
+            self.memory
+                .iter_async(|id, entry| {
+                    let used = entry.last_used.load(Ordering::Relaxed);
+                    if oldest.is_none_or(|(_, t)| used < t) {
+                        oldest = Some((*id, used));
+                    }
+                    true
+                })
+                .await;
+            let Some((id, _)) = oldest else {
+                break;
+            };
+            if let Some((_, entry)) = self.memory.remove_async(&id).await {
+                self.memory_bytes
+                    .fetch_sub(entry.bundle.memory_len(), Ordering::Relaxed);
+                info!(
+                    wasm_module_id = %id,
+                    size_bytes = entry.bundle.memory_len(),
+                    "Evicted WASM bundle from memory cache"
+                );
+                self.spill(id, entry.bundle).await;
+            }
+        }
+    }
+
+    async fn remove_memory(&self, wasm_module_id: Uuid) {
+        if let Some((_, entry)) = self.memory.remove_async(&wasm_module_id).await {
+            self.memory_bytes
+                .fetch_sub(entry.bundle.memory_len(), Ordering::Relaxed);
+        }
+    }
+
+    async fn remove_spilled(&self, wasm_module_id: Uuid) {
+        if let Some((_, spilled)) = self.disk.remove_async(&wasm_module_id).await {
+            let brotli_path = spilled.brotli.map(|br| br.path);
+            for path in std::iter::once(spilled.path).chain(brotli_path) {
+                match tokio::fs::remove_file(&path).await {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == ErrorKind::NotFound => {}
+                    Err(e) => {
+                        warn!(error = ?e, path = %path.display(), "Failed to delete spilled WASM bundle")
+                    }
+                }
+            }
+        }
+    }
+
+    /// Best-effort write to the disk tier; without one (or on failure) the
+    /// bundle is simply dropped and re-read from the DB when next requested.
+    async fn spill(&self, wasm_module_id: Uuid, bundle: CachedWasmBundle) {
+        let Some(dir) = &self.disk_dir else {
+            return;
+        };
+        let path = dir.join(format!("{wasm_module_id}.bundle"));
+        let brotli_path = dir.join(format!("{wasm_module_id}.bundle.br"));
+
+        let result = async {
+            tokio::fs::create_dir_all(dir).await?;
+            write_atomically(&path, &bundle.bytes).await?;
+            if let Some(br) = &bundle.brotli {
+                write_atomically(&brotli_path, &br.bytes).await?;
+            }
+            Ok::<(), std::io::Error>(())
+        }
+        .await;
+        if let Err(e) = result {
+            error!(error = ?e, wasm_module_id = %wasm_module_id, "Failed to spill WASM bundle to disk");
+            let _ = tokio::fs::remove_file(&path).await;
+            return;
+        }
+
+        let spilled = SpilledWasmBundle {
+            path,
+            len: bundle.bytes.len() as u64,
+            is_gzipped: bundle.is_gzipped,
+            content_type: bundle.content_type,
+            etag: bundle.etag,
+            brotli: bundle.brotli.map(|br| SpilledBrotliBundle {
+                path: brotli_path,
+                len: br.bytes.len() as u64,
+                etag: br.etag,
+            }),
+            integrity: bundle.integrity,
+        };
+        let _ = self.disk.upsert_async(wasm_module_id, spilled).await;
+    }
+}
+
+/// Write through a `.part` file so readers never see a partial bundle.
+async fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.to_path_buf().into_os_string();
+    tmp.push(".part");
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, path).await
+}